@@ -0,0 +1,73 @@
+//! Removes the ANSI escape sequences of a text.
+//! Kafka records sometimes contain colored log lines, they must not leak into the clipboard,
+//! the reports or the terminal when colors are disabled.
+
+use std::borrow::Cow;
+
+const ESC: char = '\x1b';
+const BEL: char = '\x07';
+
+/// Returns the text without its ANSI escape sequences: CSI sequences such as colors or cursor moves,
+/// OSC sequences such as hyperlinks or window titles, and the other two-character escape sequences.
+pub fn strip_ansi(text: &str) -> Cow<'_, str> {
+    if !text.contains(ESC) {
+        return Cow::Borrowed(text);
+    }
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != ESC {
+            stripped.push(c);
+            continue;
+        }
+        match chars.next() {
+            // CSI, the sequence ends with a character between '@' and '~'
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC, the sequence ends with BEL or `ESC \`
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == BEL {
+                        break;
+                    }
+                    if c == ESC && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // `ESC ( B` and the other sequences with intermediate characters
+            Some(c) if (' '..='/').contains(&c) => {
+                for c in chars.by_ref() {
+                    if !(' '..='/').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+    Cow::Owned(stripped)
+}
+
+#[test]
+fn test_strip_ansi() {
+    assert!(matches!(
+        strip_ansi("no colors"),
+        Cow::Borrowed("no colors")
+    ));
+    assert_eq!(
+        strip_ansi("\x1b[1;31mERROR\x1b[0m payment failed"),
+        "ERROR payment failed"
+    );
+    assert_eq!(
+        strip_ansi("\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x07 \x1b(Bdone"),
+        "link done"
+    );
+    assert_eq!(strip_ansi("truncated \x1b[3"), "truncated ");
+}
@@ -1,24 +1,76 @@
 //! This app is both a kafka consumer and a kafka admin client.
+use chrono::{DateTime, Utc};
 use lib::{
-    ConsumerGroupDetail, Error, ExportedKafkaRecord, KafkaRecord, TopicDetail,
-    kafka::SchemaRegistryClient, search::offset::FromOffset,
+    ConsumerGroupDetail, ConsumerGroupMember, ConsumerGroupOverview, CountMethod, Error,
+    ExportedKafkaRecord, KafkaRecord, MemberAssignment, PartitionOffset, PayloadFormat,
+    TopicDetail, TopicOffsets, TopicPreview,
+    kafka::{
+        ExportedSchema, SchemaId, SchemaRegistryClient, SchemaRegistryKind, SchemaResponse,
+        assign_partitions,
+    },
+    search::{ScanLimit, offset::FromOffset},
 };
 use rdkafka::{
-    Offset, TopicPartitionList,
-    consumer::{BaseConsumer, Consumer, StreamConsumer},
+    Message, Offset, TopicPartitionList,
+    admin::{AdminClient, AdminOptions, ResourceSpecifier},
+    client::DefaultClientContext,
+    consumer::{BaseConsumer, Consumer, ConsumerContext, DefaultConsumerContext, StreamConsumer},
+    error::KafkaError,
+    groups::GroupInfo,
+    message::{Header, Headers, OwnedHeaders, OwnedMessage},
+    producer::{DeliveryFuture, FutureProducer, FutureRecord, Producer},
 };
+use serde_json::Value;
 use thousands::Separable;
-use tracing::{info, warn};
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::{error, info, warn};
 
-use std::{collections::HashSet, fs, time::Duration};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque, btree_map::Entry},
+    fs,
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
 
 use itertools::Itertools;
 
 use crate::{
-    configuration::{Configuration, ConsumerConfig, InternalConfig, YozefuConfig},
+    APPLICATION_NAME, AuditAction, AuditEvent, AuditLog, Checkpoint, Checkpointer, DeadLetter,
+    HeaderEdit, Notes, ProduceOptions, RecordToProduce, RecordsExporter, ValueSchema,
+    broker_configs::BrokerConfigEntry,
+    client_quotas::{ClientQuota, describe_client_quotas},
+    configuration::{
+        CommitMode, Configuration, ConsumerConfig, InternalConfig, YozefuConfig, set_record_count,
+    },
+    consumer_group_description::{
+        ConsumerGroupDescription, committed_offsets, describe_consumer_groups,
+    },
+    latency_probe::{LatencySample, PROBE_HEADER, PROBE_KEY},
+    projection::{Projection, is_csv},
+    query_plan::{Filtering, PartitionScan, QueryPlan},
+    reassignment::{BrokerRack, PartitionAssignment},
+    record_count::sampled_count,
+    records_exporter::{CsvFile, lock_export_writes},
+    reporting_context::{ReportingContext, STATISTICS_INTERVAL_MS},
+    retention::RetentionWarning,
+    schema_usage::SchemaUsageReport,
     search::{Search, ValidSearchQuery},
+    sqlite_exporter::{SqliteDatabase, is_sqlite},
 };
 
+/// Number of records produced before waiting for their delivery
+const PRODUCE_WINDOW: usize = 1_000;
+
+/// Canary records of the latency probe not consumed after this duration are considered lost
+const CANARY_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Timeout of the operations of a transactional producer
+const TRANSACTION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Ids of the schemas written to each schemas file, so the file is not read again for every exported record.
+static EXPORTED_SCHEMA_IDS: Mutex<BTreeMap<PathBuf, HashSet<u32>>> = Mutex::new(BTreeMap::new());
+
 /// Struct exposing different functions for consuming kafka records.
 #[derive(Debug, Clone)]
 pub struct App {
@@ -43,54 +95,273 @@ impl App {
         }
     }
 
+    /// Returns the schema registry client of the cluster, routing the topics to the registries configured for their prefix.
     pub fn schema_registry(&self) -> Option<SchemaRegistryClient> {
-        match self.config.schema_registry_config_of(&self.cluster) {
-            Some(config) => Some(SchemaRegistryClient::new(config.url, &config.headers)),
-            None => None,
+        let config = self.config.schema_registry_config_of(&self.cluster)?;
+        let client = config.client().and_then(|client| {
+            self.config
+                .schema_registries()
+                .iter()
+                .try_fold(client, |client, (prefix, config)| {
+                    Ok(client.with_route(prefix, config.client()?))
+                })
+        });
+        match client {
+            Ok(client) => Some(client),
+            Err(e) => {
+                error!(
+                    "Cannot create the schema registry client of cluster '{}': {e}",
+                    self.cluster
+                );
+                None
+            }
         }
     }
 
+    /// Parses the kafka message, decoding its key and its value with the decoders configured for the topic.
+    pub async fn parse_record(
+        &self,
+        message: OwnedMessage,
+        schema_registry: &mut Option<SchemaRegistryClient>,
+    ) -> KafkaRecord {
+        let key = self
+            .config
+            .key_decoder_of(message.topic())
+            .decode(message.key().unwrap_or_default());
+        let value = self
+            .config
+            .value_decoder_of(message.topic())
+            .decode(message.payload().unwrap_or_default());
+        let mut record = KafkaRecord::parse(message, schema_registry).await;
+        if let Some(key) = key {
+            record.key_as_string = key.to_string();
+            record.key = key;
+            record.key_schema = None;
+        }
+        if let Some((value, encoding)) = value {
+            record.value_as_string = value.to_string();
+            record.value = value;
+            record.value_schema = None;
+            record.value_format = Some(PayloadFormat::new(encoding));
+            record.raw_value = None;
+            record.readable_value = None;
+        }
+        let mut masked = false;
+        for rule in self.config.masking_rules_of(&record.topic) {
+            rule.apply(&mut record);
+            masked = true;
+        }
+        if masked {
+            record.key_as_string = record.key.to_string();
+            record.value_as_string = record.value.to_string();
+            // The undecoded bytes and the readable avro value would reveal what the rules mask
+            record.raw_value = None;
+            record.readable_value = None;
+        }
+        record
+    }
+
     /// Create a kafka consumer
     pub fn create_consumer(&self, topics: &Vec<String>) -> Result<StreamConsumer, Error> {
-        let offset = self.search_query.offset().unwrap_or(FromOffset::End);
-        match offset {
-            FromOffset::Beginning => self.assign_partitions(topics, Offset::Beginning),
-            FromOffset::End => self.assign_partitions(topics, Offset::End),
-            FromOffset::Offset(o) => self.assign_partitions(topics, Offset::Offset(o)),
-            FromOffset::OffsetTail(o) => self.assign_partitions(topics, Offset::OffsetTail(o)),
-            FromOffset::Timestamp(timestamp) => {
-                let consumer: StreamConsumer = self.config.create_kafka_consumer()?;
-                let mut tp = TopicPartitionList::new();
-                for t in topics {
-                    let metadata = consumer.fetch_metadata(Some(t), Duration::from_secs(10))?;
-                    for m in metadata.topics() {
-                        for p in m.partitions() {
-                            tp.add_partition(m.name(), p.id());
-                        }
-                    }
-                }
-                tp.set_all_offsets(Offset::Offset(timestamp))?;
-                let tt = consumer.offsets_for_times(tp, Duration::from_secs(60))?;
-                consumer.assign(&tt)?;
-                Ok(consumer)
+        self.create_consumer_with_context(topics, DefaultConsumerContext, &[])
+    }
+
+    /// Create a kafka consumer reporting its errors and the state of the brokers to the context.
+    /// Partitions listed in `positions`, with the offset of the last consumed record, resume right after this record.
+    pub fn create_reporting_consumer(
+        &self,
+        topics: &Vec<String>,
+        context: ReportingContext,
+        positions: &HashMap<(String, i32), i64>,
+    ) -> Result<StreamConsumer<ReportingContext>, Error> {
+        let consumer = self.create_consumer_with_context(
+            topics,
+            context,
+            &[("statistics.interval.ms", STATISTICS_INTERVAL_MS)],
+        )?;
+        Self::resume_consumer(consumer, positions)
+    }
+
+    /// Create a kafka consumer resuming the partitions listed in `positions` right after the last consumed record.
+    pub fn create_resumed_consumer(
+        &self,
+        topics: &Vec<String>,
+        positions: &HashMap<(String, i32), i64>,
+    ) -> Result<StreamConsumer, Error> {
+        let consumer = self.create_consumer_with_context(topics, DefaultConsumerContext, &[])?;
+        Self::resume_consumer(consumer, positions)
+    }
+
+    fn resume_consumer<C: ConsumerContext + 'static>(
+        consumer: StreamConsumer<C>,
+        positions: &HashMap<(String, i32), i64>,
+    ) -> Result<StreamConsumer<C>, Error> {
+        if positions.is_empty() {
+            return Ok(consumer);
+        }
+        let mut assignment = consumer.assignment()?;
+        for ((topic, partition), offset) in positions {
+            if assignment.find_partition(topic, *partition).is_some() {
+                assignment.set_partition_offset(topic, *partition, Offset::Offset(offset + 1))?;
             }
         }
+        consumer.assign(&assignment)?;
+        info!("Consumer resumed at {positions:?}");
+        Ok(consumer)
+    }
+
+    /// Returns the checkpointer of the search on the given topics, see [`Checkpoint`].
+    /// Only searches started with `resume` are checkpointed, searches only reading new records never are.
+    /// The checkpointer starts from the checkpoint of the previous run of the same search.
+    pub fn checkpointer(
+        &self,
+        topics: &[String],
+        resume: bool,
+    ) -> Result<Option<Checkpointer>, Error> {
+        if !resume || matches!(self.search_query.offset(), None | Some(FromOffset::End)) {
+            return Ok(None);
+        }
+        let query = self.search_query.query().to_string();
+        let file = self
+            .config
+            .workspace()
+            .checkpoints_dir()
+            .join(Checkpoint::file_name(&self.cluster, topics, &query));
+        let checkpoint = Checkpoint::load(&file)?;
+        if checkpoint.is_none() {
+            warn!("No checkpoint found for this search, it starts from scratch");
+        }
+        let checkpoint =
+            checkpoint.unwrap_or_else(|| Checkpoint::new(&self.cluster, topics, &query));
+        Ok(Some(Checkpointer::new(checkpoint, &file)))
+    }
+
+    /// Create a kafka consumer reading the topics from the beginning, to materialize the latest value of each key.
+    /// librdkafka reports a [`KafkaError::PartitionEOF`](rdkafka::error::KafkaError::PartitionEOF) once a partition has been read up to its end.
+    pub fn create_compacted_consumer(&self, topics: &Vec<String>) -> Result<StreamConsumer, Error> {
+        let consumer = self.create_assigned_consumer_with_context(
+            DefaultConsumerContext,
+            &[("enable.partition.eof", "true")],
+            &[],
+        )?;
+        self.assign_partitions(consumer, topics, &FromOffset::Beginning)
+    }
+
+    fn create_consumer_with_context<C: ConsumerContext + 'static>(
+        &self,
+        topics: &Vec<String>,
+        context: C,
+        defaults: &[(&str, &str)],
+    ) -> Result<StreamConsumer<C>, Error> {
+        let offset = self.search_query.offset().unwrap_or(FromOffset::End);
+        let consumer = self.create_assigned_consumer_with_context(
+            context,
+            defaults,
+            &self.commit_properties(),
+        )?;
+        self.assign_partitions(consumer, topics, &offset)
+    }
+
+    /// Appends the action to the audit log of the workspace.
+    pub fn audit(
+        &self,
+        action: AuditAction,
+        topics: &[String],
+        details: &str,
+    ) -> Result<(), Error> {
+        AuditLog::new(&self.config.workspace().audit_file()).append(&AuditEvent::new(
+            &self.cluster,
+            action,
+            topics,
+            details,
+        ))
+    }
+
+    /// Returns the notes attached to the records of the cluster.
+    pub fn notes(&self) -> Result<Notes, Error> {
+        Notes::load(&self.config.workspace().notes_file(), &self.cluster)
     }
 
     pub fn consumer_config(&self) -> ConsumerConfig {
         self.config.consumer_config(&self.cluster)
     }
 
-    /// Exports a given kafka record to a file.
-    /// The Name of the file is automatically generated at the runtime
-    pub fn export_record(&self, record: &KafkaRecord) -> Result<(), Error> {
-        let output_file = self.config.output_file();
+    /// Returns the volume of records after which the search stops, the clause of the search query wins over the configuration.
+    pub fn scan_limit(&self) -> Option<ScanLimit> {
+        self.search_query
+            .query()
+            .scan_limit
+            .or(self.consumer_config().scan_limit)
+    }
+
+    /// Exports a given kafka record to a file and returns the path of this file.
+    /// The name of the file is generated from the export template of the configuration,
+    /// the record is inserted into a SQLite database when the file ends with `.db`, `.sqlite` or `.sqlite3`
+    /// and written as a row when the file ends with `.csv`.
+    pub fn export_record(&self, record: &KafkaRecord) -> Result<PathBuf, Error> {
+        let output_file = self.config.export_file_of(&record.topic)?;
+        let anonymized = match self.config.anonymization_profile()? {
+            Some(profile) => profile.apply(record),
+            None => record.clone(),
+        };
+        let export_config = &self.config.workspace().config().export;
+        let lock = lock_export_writes();
+        let output_file = if is_sqlite(&output_file) {
+            let projection = Projection::of(self.search_query.query(), export_config)?;
+            let fields = projection.as_ref().map_or(&[][..], Projection::fields);
+            SqliteDatabase::open(&output_file, fields)?
+                .insert(&anonymized, &self.search_query.query().to_string())?;
+            output_file
+        } else if is_csv(&output_file) {
+            let projection = Projection::of_csv(self.search_query.query(), export_config)?;
+            let output_file = export_config.rotate_csv(&output_file, &projection.header())?;
+            CsvFile::open(&output_file, projection)?.write(&anonymized)?;
+            output_file
+        } else {
+            match Projection::of(self.search_query.query(), export_config)? {
+                Some(projection) => {
+                    Self::export_projection_to_json(&projection, &anonymized, &output_file)?;
+                }
+                None => self.export_record_to_json(&anonymized, &output_file)?,
+            }
+            output_file
+        };
+        drop(lock);
+        info!(
+            "A record has been exported into file '{}'",
+            output_file.display()
+        );
+        // The record is exported, a failing audit log must not report the opposite
+        if let Err(e) = self.audit(
+            AuditAction::Export,
+            std::slice::from_ref(&record.topic),
+            &format!(
+                "Record {}-{} offset {} exported into '{}'",
+                record.topic,
+                record.partition,
+                record.offset,
+                output_file.display()
+            ),
+        ) {
+            warn!("Cannot write the export to the audit log: {e}");
+        }
+        Ok(output_file)
+    }
+
+    fn export_record_to_json(&self, record: &KafkaRecord, output_file: &Path) -> Result<(), Error> {
         fs::create_dir_all(output_file.parent().unwrap())?;
         let content = fs::read_to_string(output_file).unwrap_or("[]".to_string());
         let mut exported_records: Vec<ExportedKafkaRecord> = serde_json::from_str(&content)?;
 
         let mut exported_record_kafka: ExportedKafkaRecord = record.into();
         exported_record_kafka.set_search_query(self.search_query.query());
+        exported_record_kafka.note = match self.notes() {
+            Ok(notes) => notes.text_of(record),
+            Err(e) => {
+                warn!("The record is exported without its note: {e}");
+                None
+            }
+        };
         exported_records.push(exported_record_kafka);
         exported_records.sort_by(|a, b| {
             a.record
@@ -106,17 +377,434 @@ impl App {
             current.compute_deltas_ms(first_ts, previous_ts);
         }
 
+        let json = serde_json::to_string_pretty(&exported_records)?;
+        fs::write(output_file, &json)?;
+        set_record_count(output_file, json.len() as u64, exported_records.len());
+        Ok(())
+    }
+
+    /// Appends the selected fields of the record to the JSON array of the file.
+    fn export_projection_to_json(
+        projection: &Projection,
+        record: &KafkaRecord,
+        output_file: &Path,
+    ) -> Result<(), Error> {
+        fs::create_dir_all(output_file.parent().unwrap())?;
+        let content = fs::read_to_string(output_file).unwrap_or("[]".to_string());
+        let mut exported_records: Vec<Value> = serde_json::from_str(&content)?;
+        exported_records.push(projection.project(record));
+        let json = serde_json::to_string_pretty(&exported_records)?;
+        fs::write(output_file, &json)?;
+        set_record_count(output_file, json.len() as u64, exported_records.len());
+        Ok(())
+    }
+
+    /// Returns the schema values are serialized with: the schema with this id, or the latest version of the subject.
+    pub async fn value_schema(
+        &self,
+        topic: &str,
+        schema: &ValueSchema,
+    ) -> Result<(u32, SchemaResponse), Error> {
+        let mut schema_registry = self.schema_registry().ok_or(Error::Error(format!(
+            "No schema registry is configured for cluster '{}'",
+            self.cluster
+        )))?;
+        let schema_registry = schema_registry.for_topic(topic);
+        if schema_registry.kind() != SchemaRegistryKind::Confluent {
+            return Err(Error::Error(format!(
+                "Records of topic '{topic}' can only be serialized with the schemas of a Confluent schema registry"
+            )));
+        }
+        let not_found = || Error::SchemaRegistry(format!("Schema {schema:?} not found"));
+        match schema {
+            ValueSchema::Id(id) => Ok((
+                *id,
+                schema_registry.schema(*id).await?.ok_or_else(not_found)?,
+            )),
+            ValueSchema::Subject(subject) => {
+                let version = schema_registry
+                    .versions(subject)
+                    .await?
+                    .into_iter()
+                    .max()
+                    .ok_or_else(not_found)?;
+                let response = schema_registry
+                    .schema_version(subject, version)
+                    .await?
+                    .ok_or_else(not_found)?;
+                Ok((response.id, response.schema))
+            }
+        }
+    }
+
+    /// Produces the records to the topic and returns the number of records produced.
+    /// Values are serialized with the schema when one is given, as JSON otherwise, strings are produced as they are.
+    /// A transactional produce is aborted when one of the records cannot be produced, none of them is then visible to `read_committed` consumers.
+    pub async fn produce(
+        &self,
+        topic: &str,
+        records: &[RecordToProduce],
+        options: &ProduceOptions,
+    ) -> Result<usize, Error> {
+        self.config
+            .check_destructive("produce records", &options.confirmation)?;
+        if options.transactional
+            && self
+                .config
+                .specific
+                .producer_config()
+                .transactional_id
+                .is_none()
+        {
+            return Err(Error::Error(format!(
+                "Cannot produce records inside a transaction: no 'transactional_id' is configured for cluster '{}'",
+                self.cluster
+            )));
+        }
+        let producer: FutureProducer = InternalConfig::kafka_client_config_from_properties(
+            self.config.producer_config_map(options.transactional),
+        )
+        .create()?;
+
+        if !options.transactional {
+            Self::send_records(&producer, topic, records, options).await?;
+        } else {
+            let transaction = producer.clone();
+            blocking(move || {
+                transaction.init_transactions(TRANSACTION_TIMEOUT)?;
+                Ok(transaction.begin_transaction()?)
+            })
+            .await?;
+            let sent = Self::send_records(&producer, topic, records, options).await;
+            let transaction = producer.clone();
+            match sent {
+                Ok(()) => {
+                    blocking(move || Ok(transaction.commit_transaction(TRANSACTION_TIMEOUT)?))
+                        .await?;
+                }
+                Err(e) => {
+                    warn!("Aborting the transaction producing records to '{topic}': {e}");
+                    blocking(move || Ok(transaction.abort_transaction(TRANSACTION_TIMEOUT)?))
+                        .await?;
+                    return Err(e);
+                }
+            }
+        }
+        // The records are produced, a failing audit log must not report the opposite
+        if let Err(e) = self.audit(
+            AuditAction::Produce,
+            &[topic.to_string()],
+            &format!(
+                "{} records produced{}",
+                records.len(),
+                match options.transactional {
+                    true => " in a transaction",
+                    false => "",
+                }
+            ),
+        ) {
+            warn!("Cannot write the production to the audit log: {e}");
+        }
+        Ok(records.len())
+    }
+
+    /// Sends a record of a dead letter queue back to its original topic, once its headers are edited.
+    /// On protected clusters, `confirmation` must be the name of the cluster.
+    /// The key keeps its bytes, values serialized with a schema are serialized again with the same schema.
+    /// Returns the topic the record has been republished to.
+    pub async fn republish(
+        &self,
+        record: &KafkaRecord,
+        header_edits: &[HeaderEdit],
+        confirmation: &str,
+    ) -> Result<String, Error> {
+        self.config
+            .check_destructive("republish records", confirmation)?;
+        let dead_letter = DeadLetter::of(record).ok_or(Error::Error(format!(
+            "The headers of record {}-{}@{} do not tell its original topic",
+            record.topic, record.partition, record.offset
+        )))?;
+        if self.config.masking_rules_of(&record.topic).next().is_some() {
+            return Err(Error::Error(format!(
+                "Records of topic '{}' are masked, they cannot be republished",
+                record.topic
+            )));
+        }
+        // The record is read again for the bytes of its key, the parsed key may be the json of an avro key
+        let message = self
+            .poll_record(&record.topic, record.partition, record.offset)
+            .await?;
+        if message.offset() != record.offset {
+            return Err(Error::Error(format!(
+                "Record {}-{}@{} does not exist anymore",
+                record.topic, record.partition, record.offset
+            )));
+        }
+        let raw_key = message.key().map(<[u8]>::to_vec);
+        let record = match record.truncated {
+            true => {
+                self.parse_record(message, &mut self.schema_registry())
+                    .await
+            }
+            false => record.clone(),
+        };
+        if record.raw_value.is_some() {
+            return Err(Error::Error(
+                "The value of the record could not be decoded, it cannot be republished"
+                    .to_string(),
+            ));
+        }
+        let value_schema = match &record.value_schema {
+            Some(schema) => Some(
+                self.value_schema(&dead_letter.topic, &ValueSchema::Id(schema.id.0))
+                    .await?,
+            ),
+            None => None,
+        };
+        let mut republished = dead_letter.republished(&record, raw_key);
+        republished.edit_headers(header_edits);
+        let options = ProduceOptions {
+            value_schema,
+            confirmation: confirmation.to_string(),
+            ..Default::default()
+        };
+        self.produce(&dead_letter.topic, &[republished], &options)
+            .await?;
+        Ok(dead_letter.topic)
+    }
+
+    /// Sends the records and waits for their delivery.
+    async fn send_records(
+        producer: &FutureProducer,
+        topic: &str,
+        records: &[RecordToProduce],
+        options: &ProduceOptions,
+    ) -> Result<(), Error> {
+        let mut deliveries = VecDeque::new();
+        // Above a billion records per second, the period would be zero and `interval` panics
+        let mut interval = options.rate.map(|rate| {
+            tokio::time::interval(
+                (Duration::from_secs(1) / rate.get()).max(Duration::from_nanos(1)),
+            )
+        });
+        for record in records {
+            if let Some(interval) = interval.as_mut() {
+                interval.tick().await;
+            }
+            let payload = match (&record.value, &options.value_schema) {
+                (Value::Null, _) => None,
+                (value, Some((id, schema))) => Some(schema.encode(*id, value)?),
+                (Value::String(s), None) => Some(s.as_bytes().to_vec()),
+                (value, None) => Some(serde_json::to_vec(value)?),
+            };
+            let mut headers = OwnedHeaders::new_with_capacity(record.headers.len());
+            for (key, value) in &record.headers {
+                headers = headers.insert(Header {
+                    key,
+                    value: Some(value),
+                });
+            }
+            let mut message = FutureRecord::<[u8], Vec<u8>>::to(topic).headers(headers);
+            let key = match &record.raw_key {
+                Some(key) => Some(key.as_slice()),
+                None => record.key.as_deref().map(str::as_bytes),
+            };
+            if let Some(key) = key {
+                message = message.key(key);
+            }
+            if let Some(payload) = &payload {
+                message = message.payload(payload);
+            }
+            deliveries.push_back(producer.send_result(message).map_err(|(e, _)| e)?);
+            // Waits for the oldest deliveries so the queue of the producer never fills up
+            if deliveries.len() >= PRODUCE_WINDOW
+                && let Some(delivery) = deliveries.pop_front()
+            {
+                delivered(delivery).await?;
+            }
+        }
+        while let Some(delivery) = deliveries.pop_front() {
+            delivered(delivery).await?;
+        }
+        Ok(())
+    }
+
+    /// Produces a canary record to the topic at every `interval` and sends the time it took to consume it back.
+    /// Canaries are produced from the end of the topic, those not consumed within a minute are considered lost.
+    /// The probe runs until the receiver of the samples is dropped.
+    /// `confirmation` is the text typed by the user, the name of the cluster on protected clusters.
+    pub async fn probe_latency(
+        &self,
+        topic: &str,
+        interval: Duration,
+        confirmation: &str,
+        samples: UnboundedSender<LatencySample>,
+    ) -> Result<(), Error> {
+        self.config
+            .check_destructive("produce canary records", confirmation)?;
+        let consumer = self.create_assigned_consumer()?;
+        assign_partitions(&consumer, &[topic.to_string()], &FromOffset::End)?;
+        let producer: FutureProducer = InternalConfig::kafka_client_config_from_properties(
+            self.config.producer_config_map(false),
+        )
+        .create()?;
+        if let Err(e) = self.audit(
+            AuditAction::Produce,
+            &[topic.to_string()],
+            "latency probe started",
+        ) {
+            warn!("Cannot write the latency probe to the audit log: {e}");
+        }
+
+        let probe = format!("{}-{}", APPLICATION_NAME, rand::random::<u32>());
+        let mut sent: HashMap<u64, Instant> = HashMap::new();
+        let mut sequence = 0;
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    if samples.is_closed() {
+                        return Ok(());
+                    }
+                    let payload = serde_json::json!({
+                        "probe": probe,
+                        "sequence": sequence,
+                        "sent_at": Utc::now().timestamp_millis()
+                    })
+                    .to_string();
+                    let headers = OwnedHeaders::new().insert(Header {
+                        key: PROBE_HEADER,
+                        value: Some(&probe),
+                    });
+                    let message = FutureRecord::to(topic)
+                        .key(PROBE_KEY)
+                        .payload(&payload)
+                        .headers(headers);
+                    // The delivery is not awaited, a canary that is not produced is never consumed
+                    drop(producer.send_result(message).map_err(|(e, _)| e)?);
+                    sent.insert(sequence, Instant::now());
+                    sent.retain(|_, at| at.elapsed() < CANARY_TIMEOUT);
+                    sequence += 1;
+                }
+                message = consumer.recv() => {
+                    let message = message?;
+                    let is_canary = message.headers().is_some_and(|headers| {
+                        headers
+                            .iter()
+                            .any(|h| h.key == PROBE_HEADER && h.value == Some(probe.as_bytes()))
+                    });
+                    let canary = message
+                        .payload()
+                        .filter(|_| is_canary)
+                        .and_then(|payload| serde_json::from_slice::<Value>(payload).ok())
+                        .and_then(|payload| payload["sequence"].as_u64());
+                    if let Some(sequence) = canary
+                        && let Some(at) = sent.remove(&sequence)
+                    {
+                        let sample = LatencySample {
+                            sequence,
+                            timestamp: Utc::now().timestamp_millis(),
+                            latency: at.elapsed(),
+                        };
+                        if samples.send(sample).is_err() {
+                            return Ok(());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns an exporter streaming kafka records into the export files, see [`RecordsExporter`].
+    pub fn records_exporter(&self) -> Result<RecordsExporter, Error> {
+        RecordsExporter::new(self.config.clone(), self.search_query.query().clone())
+    }
+
+    /// Writes the schemas referenced by the record into the schemas file of `export_file`, if enabled.
+    /// Schemas already present in the file are not fetched again.
+    pub async fn export_schemas(
+        &self,
+        record: &KafkaRecord,
+        export_file: &Path,
+    ) -> Result<(), Error> {
+        let Some(schemas_file) = self.config.schemas_file_of(export_file) else {
+            return Ok(());
+        };
+        let Some(mut schema_registry) = self.schema_registry() else {
+            return Ok(());
+        };
+        let schema_registry = schema_registry.for_topic(&record.topic);
+        let ids = {
+            let _lock = lock_export_writes();
+            let mut exported_ids = EXPORTED_SCHEMA_IDS
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            let exported_ids = match exported_ids.entry(schemas_file.clone()) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(
+                    Self::read_exported_schemas(&schemas_file)?
+                        .iter()
+                        .map(|e| e.id)
+                        .collect(),
+                ),
+            };
+            [&record.key_schema, &record.value_schema]
+                .into_iter()
+                .flatten()
+                .map(|s| s.id.0)
+                .filter(|id| !exported_ids.contains(id))
+                .unique()
+                .collect_vec()
+        };
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        let mut schemas = vec![];
+        for id in ids {
+            let Some(response) = schema_registry.schema(id).await? else {
+                warn!("Schema {id} not found in the schema registry, it is not exported");
+                continue;
+            };
+            let subjects = schema_registry.subjects_of(id).await?;
+            schemas.push(ExportedSchema::new(id, subjects, response));
+        }
+
+        // The records exporter may have written the file while the schemas were fetched
+        let _lock = lock_export_writes();
+        let mut exported_schemas = Self::read_exported_schemas(&schemas_file)?;
+        for schema in schemas {
+            if !exported_schemas.iter().any(|e| e.id == schema.id) {
+                exported_schemas.push(schema);
+            }
+        }
+        exported_schemas.sort_by_key(|e| e.id);
+
+        fs::create_dir_all(schemas_file.parent().unwrap())?;
         fs::write(
-            output_file,
-            serde_json::to_string_pretty(&exported_records)?,
+            &schemas_file,
+            serde_json::to_string_pretty(&exported_schemas)?,
         )?;
+        EXPORTED_SCHEMA_IDS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(
+                schemas_file.clone(),
+                exported_schemas.iter().map(|e| e.id).collect(),
+            );
         info!(
-            "A record has been exported into file '{}'",
-            output_file.display()
+            "Schemas have been exported into file '{}'",
+            schemas_file.display()
         );
         Ok(())
     }
 
+    /// Reads the schemas already exported into the schemas file, none when it does not exist.
+    fn read_exported_schemas(schemas_file: &Path) -> Result<Vec<ExportedSchema>, Error> {
+        let content = fs::read_to_string(schemas_file).unwrap_or("[]".to_string());
+        Ok(serde_json::from_str(&content)?)
+    }
+
     /// Calculates an estimate of the number of records that are going to be read.
     /// This function is used to render a progress bar.
     pub fn estimate_number_of_records_to_read(
@@ -162,54 +850,174 @@ impl App {
         Ok(count)
     }
 
+    /// Returns how the search query would be executed on the topics, without consuming any record:
+    /// the assigned partitions, the offsets they are read from and whether the records are filtered before or after decoding.
+    pub fn query_plan(&self, topics: &Vec<String>) -> Result<QueryPlan, Error> {
+        let consumer = self.create_consumer(topics)?;
+        let mut partitions = vec![];
+        for t in consumer.assignment()?.elements() {
+            let watermarks =
+                consumer.fetch_watermarks(t.topic(), t.partition(), Duration::from_secs(10))?;
+            partitions.push(PartitionScan::new(
+                t.topic(),
+                t.partition(),
+                t.offset(),
+                watermarks,
+            ));
+        }
+        partitions.sort_by(|a, b| (&a.topic, a.partition).cmp(&(&b.topic, b.partition)));
+        let query = self.search_query.query();
+        Ok(QueryPlan {
+            query: query.to_string(),
+            from: match &query.from {
+                Some(FromOffset::Timestamp(timestamp)) => {
+                    DateTime::from_timestamp_millis(*timestamp)
+                        .map(|d| d.to_rfc3339())
+                        .unwrap_or_else(|| timestamp.to_string())
+                }
+                Some(from) => from.to_string(),
+                None => FromOffset::End.to_string(),
+            },
+            partitions,
+            limit: query.limit,
+            scan_limit: self.scan_limit(),
+            filtering: Filtering::of(query),
+            consumer_group: match self.commit_properties().is_empty() {
+                true => None,
+                false => self.search_properties().remove("group.id"),
+            },
+        })
+    }
+
+    /// Creates a kafka consumer, the `with (...)` clause of the search query overrides the kafka properties.
     fn create_assigned_consumer(&self) -> Result<StreamConsumer, Error> {
-        self.config.create_kafka_consumer()
+        self.create_assigned_consumer_with_context(DefaultConsumerContext, &[], &[])
+    }
+
+    /// Kafka properties of the consumers of a search, they commit the offsets of the records read
+    /// when the consumer group of the cluster is in `commit` mode. Read-only clusters never commit.
+    fn commit_properties(&self) -> Vec<(&'static str, &'static str)> {
+        match self.config.consumer_group().mode {
+            CommitMode::Commit if !self.config.read_only() => vec![
+                ("enable.auto.commit", "true"),
+                ("enable.auto.offset.store", "true"),
+            ],
+            _ => vec![],
+        }
+    }
+
+    /// Kafka properties of the cluster, overridden by the `with (...)` clause of the search query.
+    fn search_properties(&self) -> HashMap<String, String> {
+        let mut properties = self
+            .config
+            .kafka_config_map_for(Some(&self.search_query.query().to_string()));
+        properties.extend(
+            self.search_query
+                .query()
+                .consumer_properties
+                .iter()
+                .cloned(),
+        );
+        properties
+    }
+
+    /// `defaults` are kafka properties applied unless the user has set them, `overrides` win over the user's properties.
+    fn create_assigned_consumer_with_context<C: ConsumerContext + 'static>(
+        &self,
+        context: C,
+        defaults: &[(&str, &str)],
+        overrides: &[(&str, &str)],
+    ) -> Result<StreamConsumer<C>, Error> {
+        let mut properties = self.search_properties();
+        for (key, value) in defaults {
+            properties
+                .entry((*key).to_string())
+                .or_insert_with(|| (*value).to_string());
+        }
+        for (key, value) in overrides {
+            properties.insert((*key).to_string(), (*value).to_string());
+        }
+        InternalConfig::kafka_client_config_from_properties(properties)
+            .create_with_context(context)
+            .map_err(std::convert::Into::into)
     }
 
     /// Assigns topics to a consumer
-    fn assign_partitions(
+    fn assign_partitions<C: ConsumerContext + 'static>(
         &self,
+        consumer: StreamConsumer<C>,
         topics: &Vec<String>,
-        offset: Offset,
-    ) -> Result<StreamConsumer, Error> {
-        let consumer = self.create_assigned_consumer()?;
-        let mut assignments = TopicPartitionList::new();
-        for topic in topics {
-            let metadata = consumer.fetch_metadata(Some(topic), Duration::from_secs(10))?;
-            for t in metadata.topics() {
-                for p in t.partitions() {
-                    assignments.add_partition_offset(topic, p.id(), offset)?;
-                }
-            }
-        }
-        consumer.assign(&assignments)?;
+        offset: &FromOffset,
+    ) -> Result<StreamConsumer<C>, Error> {
+        assign_partitions(&consumer, topics, offset)?;
         info!("New Consumer created, about to consume {topics:?}");
         Ok(consumer)
     }
 
-    /// Returns the topics details for a given list topics
+    /// Returns the topics details for a given list topics, the records are counted with the given method.
     /// This function is not ready yet
-    pub fn topic_details(&self, topics: HashSet<String>) -> Result<Vec<TopicDetail>, Error> {
+    pub fn topic_details(
+        &self,
+        topics: HashSet<String>,
+        count_method: CountMethod,
+    ) -> Result<Vec<TopicDetail>, Error> {
         let mut results = vec![];
+        let consumer: BaseConsumer = self.config.create_kafka_consumer()?;
+        let admin: AdminClient<DefaultClientContext> =
+            InternalConfig::kafka_client_config_from_properties(self.config.kafka_config_map())
+                .create()?;
+        let group_list = consumer.fetch_group_list(None, Duration::from_secs(10))?;
+        // The offsets of each group are listed once, whatever the number of topics
+        let mut committed = HashMap::new();
+        for g in group_list.groups() {
+            let offsets = committed_offsets(&admin, g.name(), Duration::from_secs(10))
+                .unwrap_or_else(|e| {
+                    warn!("Cannot compute the lag of group '{}': {e}", g.name());
+                    vec![]
+                });
+            committed.insert(g.name().to_string(), offsets);
+        }
         for topic in topics {
-            let consumer: BaseConsumer = self.config.create_kafka_consumer()?;
             let metadata = consumer.fetch_metadata(Some(&topic), Duration::from_secs(10))?;
             let metadata = metadata.topics().first().unwrap();
-            let mut detail = TopicDetail {
-                name: topic.clone(),
-                replicas: metadata.partitions().first().unwrap().replicas().len(),
-                partitions: metadata.partitions().len(),
-                consumer_groups: vec![],
-                count: self.count_records_in_topic(&topic)?,
-            };
+            let mut watermarks = HashMap::new();
+            for partition in metadata.partitions() {
+                watermarks.insert(
+                    partition.id(),
+                    consumer.fetch_watermarks(&topic, partition.id(), Duration::from_secs(10))?,
+                );
+            }
+            let mut detail = TopicDetail::default();
+            detail.name.clone_from(&topic);
+            detail.replicas = metadata.partitions().first().unwrap().replicas().len();
+            detail.partitions = metadata.partitions().len();
+            detail.count = self.count_records(&topic, &watermarks, count_method)?;
+            detail.count_method = count_method;
+            let high_watermarks = watermarks
+                .iter()
+                .map(|(partition, (_, high))| (*partition, *high))
+                .collect();
             let mut consumer_groups = vec![];
-            let metadata = consumer.fetch_group_list(None, Duration::from_secs(10))?;
-            for g in metadata.groups() {
-                consumer_groups.push(ConsumerGroupDetail {
-                    name: g.name().to_string(),
-                    members: vec![], //Self::parse_members(g, g.members())?,
-                    state: g.state().parse()?,
-                });
+            for g in group_list.groups() {
+                let committed_lag = committed_lag(
+                    committed.get(g.name()).map_or(&[], Vec::as_slice),
+                    &topic,
+                    &high_watermarks,
+                );
+                let members = Self::parse_members(g)
+                    .into_iter()
+                    .filter_map(|mut member| {
+                        member.assignments.retain(|a| a.topic == topic);
+                        (!member.assignments.is_empty()).then_some(member)
+                    })
+                    .collect();
+                let mut group = ConsumerGroupDetail::default();
+                group.name = g.name().to_string();
+                group.members = members;
+                group.state = g.state().parse()?;
+                group.protocol = g.protocol().to_string();
+                group.committed_lag = committed_lag;
+                consumer_groups.push(group);
             }
 
             detail.consumer_groups = consumer_groups;
@@ -219,6 +1027,652 @@ impl App {
         Ok(results)
     }
 
+    /// Lists the consumer groups of the cluster with their members, the topics they consume and their lag, sorted by name.
+    /// The offsets committed on internal topics are ignored.
+    /// The rebalance protocol and the static members are only known when the groups can be described with the `DescribeConsumerGroups` API.
+    pub fn consumer_groups(&self) -> Result<Vec<ConsumerGroupOverview>, Error> {
+        let consumer: BaseConsumer = self.config.create_kafka_consumer()?;
+        let group_list = consumer.fetch_group_list(None, Duration::from_secs(10))?;
+        let admin: AdminClient<DefaultClientContext> =
+            InternalConfig::kafka_client_config_from_properties(self.config.kafka_config_map())
+                .create()?;
+        let names = group_list
+            .groups()
+            .iter()
+            .map(|g| g.name().to_string())
+            .collect_vec();
+        let mut descriptions: HashMap<String, ConsumerGroupDescription> =
+            describe_consumer_groups(&admin, &names, Duration::from_secs(10))
+                .unwrap_or_else(|e| {
+                    warn!("{e}");
+                    vec![]
+                })
+                .into_iter()
+                .map(|d| (d.name.clone(), d))
+                .collect();
+
+        let mut high_watermarks: HashMap<(String, i32), i64> = HashMap::new();
+        let mut groups = vec![];
+        for group in group_list.groups() {
+            let mut overview = ConsumerGroupOverview::default();
+            overview.name = group.name().to_string();
+            overview.state = group.state().parse().unwrap_or_default();
+            overview.protocol = group.protocol().to_string();
+            overview.members = Self::parse_members(group);
+            if let Some(description) = descriptions.remove(group.name()) {
+                overview.state = description.state;
+                overview.group_type = description.group_type;
+                overview.members = description.members;
+                if !description.assignor.is_empty() {
+                    overview.protocol = description.assignor;
+                }
+            }
+
+            let committed = committed_offsets(&admin, group.name(), Duration::from_secs(10))
+                .unwrap_or_else(|e| {
+                    warn!(
+                        "Cannot fetch the committed offsets of group '{}': {e}",
+                        group.name()
+                    );
+                    vec![]
+                })
+                .into_iter()
+                .filter(|(topic, _, _)| !topic.starts_with("__"))
+                .collect_vec();
+
+            for (topic, partition, offset) in &committed {
+                let key = (topic.clone(), *partition);
+                let high = match high_watermarks.get(&key) {
+                    Some(high) => *high,
+                    None => {
+                        let (_, high) = consumer.fetch_watermarks(
+                            topic,
+                            *partition,
+                            Duration::from_secs(10),
+                        )?;
+                        high_watermarks.insert(key, high);
+                        high
+                    }
+                };
+                overview.lag = Some(overview.lag.unwrap_or(0) + (high - offset).max(0) as usize);
+            }
+
+            overview.topics = committed
+                .into_iter()
+                .map(|(topic, _, _)| topic)
+                .chain(
+                    overview
+                        .members
+                        .iter()
+                        .flat_map(|m| m.assignments.iter().map(|a| a.topic.clone())),
+                )
+                .sorted()
+                .dedup()
+                .collect_vec();
+            groups.push(overview);
+        }
+        groups.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(groups)
+    }
+
+    /// Counts the records of the partitions from their watermarks.
+    fn count_records(
+        &self,
+        topic: &str,
+        watermarks: &HashMap<i32, (i64, i64)>,
+        method: CountMethod,
+    ) -> Result<i64, Error> {
+        match method {
+            CountMethod::Offsets => Ok(watermarks.values().map(|(low, high)| high - low).sum()),
+            CountMethod::Sampling => {
+                let mut properties = self.config.kafka_config_map();
+                properties.insert("enable.partition.eof".to_string(), "true".to_string());
+                let consumer: BaseConsumer =
+                    InternalConfig::kafka_client_config_from_properties(properties).create()?;
+                let mut count = 0;
+                for (partition, (low, high)) in watermarks {
+                    count += sampled_count(&consumer, topic, *partition, *low, *high)?;
+                }
+                Ok(count)
+            }
+        }
+    }
+
+    /// Returns, for each partition of the topic, the offset of the first record produced at or after `timestamp`, in milliseconds.
+    /// Partitions without such record are at their end.
+    pub fn offsets_for_timestamp(
+        &self,
+        topic: &str,
+        timestamp: i64,
+    ) -> Result<TopicOffsets, Error> {
+        let consumer: BaseConsumer = self.config.create_kafka_consumer()?;
+        let metadata = consumer.fetch_metadata(Some(topic), Duration::from_secs(10))?;
+        let Some(metadata_topic) = metadata.topics().first() else {
+            return Err(Error::Error(format!("Topic '{topic}' does not exist")));
+        };
+        let mut assignments = TopicPartitionList::new();
+        for partition in metadata_topic.partitions() {
+            assignments.add_partition_offset(topic, partition.id(), Offset::Offset(timestamp))?;
+        }
+        let offsets = consumer.offsets_for_times(assignments, Duration::from_secs(60))?;
+
+        let mut partitions = vec![];
+        for element in offsets.elements() {
+            let offset = match element.offset() {
+                Offset::Offset(offset) => offset,
+                _ => {
+                    consumer
+                        .fetch_watermarks(topic, element.partition(), Duration::from_secs(10))?
+                        .1
+                }
+            };
+            let mut partition = PartitionOffset::default();
+            partition.partition = element.partition();
+            partition.offset = offset;
+            partitions.push(partition);
+        }
+        partitions.sort_by_key(|p| p.partition);
+        let mut offsets = TopicOffsets::default();
+        offsets.topic = topic.to_string();
+        offsets.timestamp = timestamp;
+        offsets.partitions = partitions;
+        Ok(offsets)
+    }
+
+    /// Returns the client quotas of the cluster, sorted by entity and name, see [`ClientQuota`].
+    pub async fn client_quotas(&self) -> Result<Vec<ClientQuota>, Error> {
+        let consumer: BaseConsumer = self.config.create_kafka_consumer()?;
+        let brokers = blocking(move || {
+            let metadata = consumer.fetch_metadata(None, Duration::from_secs(10))?;
+            Ok(metadata
+                .brokers()
+                .iter()
+                .map(|b| {
+                    (
+                        b.host().to_string(),
+                        u16::try_from(b.port()).unwrap_or_default(),
+                    )
+                })
+                .collect_vec())
+        })
+        .await?;
+        describe_client_quotas(&self.config.kafka_config_map(), &brokers).await
+    }
+
+    /// Returns the configuration entries of every broker of the cluster, sorted by broker and name.
+    pub async fn broker_configs(&self) -> Result<Vec<BrokerConfigEntry>, Error> {
+        let consumer: BaseConsumer = self.config.create_kafka_consumer()?;
+        let brokers = blocking(move || {
+            let metadata = consumer.fetch_metadata(None, Duration::from_secs(10))?;
+            Ok(metadata.brokers().iter().map(|b| b.id()).collect_vec())
+        })
+        .await?;
+        let admin: AdminClient<DefaultClientContext> =
+            InternalConfig::kafka_client_config_from_properties(self.config.kafka_config_map())
+                .create()?;
+        let specifiers = brokers
+            .iter()
+            .map(|id| ResourceSpecifier::Broker(*id))
+            .collect_vec();
+        let resources = admin
+            .describe_configs(
+                &specifiers,
+                &AdminOptions::new().request_timeout(Some(Duration::from_secs(10))),
+            )
+            .await?;
+
+        let mut entries = vec![];
+        for (broker, resource) in brokers.into_iter().zip(resources) {
+            match resource {
+                Ok(resource) => entries.extend(
+                    resource
+                        .entries
+                        .iter()
+                        .map(|e| BrokerConfigEntry::new(broker, e)),
+                ),
+                Err(e) => warn!("Cannot describe the configuration of broker {broker}: {e}"),
+            }
+        }
+        entries.sort_by(|a, b| (a.broker, &a.name).cmp(&(b.broker, &b.name)));
+        Ok(entries)
+    }
+
+    /// Returns the brokers of the cluster with their rack, and the current replicas of the partitions of the topics.
+    /// The rack of a broker is unknown when its configuration cannot be described.
+    pub async fn partition_assignments(
+        &self,
+        topics: &[String],
+    ) -> Result<(Vec<BrokerRack>, Vec<PartitionAssignment>), Error> {
+        let consumer: BaseConsumer = self.config.create_kafka_consumer()?;
+        let names = topics.to_vec();
+        let (brokers, mut assignments) = blocking(move || {
+            let metadata = consumer.fetch_metadata(None, Duration::from_secs(10))?;
+            let brokers = metadata.brokers().iter().map(|b| b.id()).collect_vec();
+            let mut assignments = vec![];
+            for topic in metadata
+                .topics()
+                .iter()
+                .filter(|t| names.contains(&t.name().to_string()))
+            {
+                for partition in topic.partitions() {
+                    assignments.push(PartitionAssignment {
+                        topic: topic.name().to_string(),
+                        partition: partition.id(),
+                        leader: partition.leader(),
+                        replicas: partition.replicas().to_vec(),
+                        in_sync_replicas: partition.isr().to_vec(),
+                    });
+                }
+            }
+            Ok((brokers, assignments))
+        })
+        .await?;
+
+        let configs = self.broker_configs().await.unwrap_or_else(|e| {
+            warn!("Cannot describe the configuration of the brokers: {e}");
+            vec![]
+        });
+        let mut brokers = brokers
+            .into_iter()
+            .map(|id| BrokerRack {
+                id,
+                rack: configs
+                    .iter()
+                    .find(|c| c.broker == id && c.name == "broker.rack")
+                    .and_then(|c| c.value.clone())
+                    .filter(|rack| !rack.is_empty()),
+            })
+            .collect_vec();
+        brokers.sort_by_key(|b| b.id);
+
+        assignments.sort_by(|a, b| (&a.topic, a.partition).cmp(&(&b.topic, b.partition)));
+        if let Some(topic) = topics
+            .iter()
+            .find(|t| !assignments.iter().any(|a| &a.topic == *t))
+        {
+            return Err(Error::Error(format!("Topic '{topic}' does not exist")));
+        }
+        Ok((brokers, assignments))
+    }
+
+    /// Returns an overview of a topic: the number of partitions, an estimate of the number of records and its most recent record.
+    /// The most recent record is looked up among the last record of each partition.
+    pub async fn topic_preview(&self, topic: &str) -> Result<TopicPreview, Error> {
+        let consumer: BaseConsumer = self.config.create_kafka_consumer()?;
+        let mut preview = TopicPreview::default();
+        preview.name = topic.to_string();
+        let name = topic.to_string();
+        let (count, partitions, latest) = blocking(move || {
+            let topic = name.as_str();
+            let metadata = consumer.fetch_metadata(Some(topic), Duration::from_secs(10))?;
+            let Some(metadata_topic) = metadata.topics().first() else {
+                return Ok((0, 0, None));
+            };
+
+            let mut count = 0;
+            let mut assignments = TopicPartitionList::new();
+            for partition in metadata_topic.partitions() {
+                let (low, high) =
+                    consumer.fetch_watermarks(topic, partition.id(), Duration::from_secs(10))?;
+                count += high - low;
+                if high > low {
+                    assignments.add_partition_offset(
+                        topic,
+                        partition.id(),
+                        Offset::Offset(high - 1),
+                    )?;
+                }
+            }
+            let partitions = metadata_topic.partitions().len();
+            if assignments.count() == 0 {
+                return Ok((count, partitions, None));
+            }
+
+            consumer.assign(&assignments)?;
+            let deadline = Instant::now() + Duration::from_secs(3);
+            let mut latest: Option<OwnedMessage> = None;
+            for _ in 0..assignments.count() {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                match consumer.poll(remaining) {
+                    Some(Ok(message)) => {
+                        let is_more_recent = latest.as_ref().is_none_or(|l| {
+                            l.timestamp().to_millis() < message.timestamp().to_millis()
+                        });
+                        if is_more_recent {
+                            latest = Some(message.detach());
+                        }
+                    }
+                    Some(Err(e)) => {
+                        warn!("Cannot read the latest record of topic '{topic}': {e}");
+                    }
+                    None => break,
+                }
+            }
+            Ok((count, partitions, latest))
+        })
+        .await?;
+        preview.count = count;
+        preview.partitions = partitions;
+
+        if let Some(message) = latest {
+            preview.latest_timestamp = message.timestamp().to_millis();
+            preview.sample = Some(
+                self.parse_record(message, &mut self.schema_registry())
+                    .await,
+            );
+        }
+        Ok(preview)
+    }
+
+    /// Reads about `sample` of the latest records of the topic, spread over its partitions, and counts the schemas their values are serialized with.
+    /// A schema version is deprecated when a newer version of its subject has been registered.
+    pub async fn schema_usage(
+        &self,
+        topic: &str,
+        sample: usize,
+    ) -> Result<SchemaUsageReport, Error> {
+        let mut properties = self.config.kafka_config_map();
+        properties.insert("enable.partition.eof".to_string(), "true".to_string());
+        let name = topic.to_string();
+        let messages = blocking(move || {
+            let topic = name.as_str();
+            let consumer: BaseConsumer =
+                InternalConfig::kafka_client_config_from_properties(properties).create()?;
+            let metadata = consumer.fetch_metadata(Some(topic), Duration::from_secs(10))?;
+            let Some(metadata_topic) = metadata.topics().first() else {
+                return Err(Error::Error(format!("Topic '{topic}' does not exist")));
+            };
+            let per_partition = sample.div_ceil(metadata_topic.partitions().len().max(1)) as i64;
+            let mut assignments = TopicPartitionList::new();
+            // High watermark of the partitions not read up to it yet
+            let mut pending = HashMap::new();
+            for partition in metadata_topic.partitions() {
+                let (low, high) =
+                    consumer.fetch_watermarks(topic, partition.id(), Duration::from_secs(10))?;
+                let start = (high - per_partition).max(low);
+                if high > start {
+                    pending.insert(partition.id(), high);
+                    assignments.add_partition_offset(
+                        topic,
+                        partition.id(),
+                        Offset::Offset(start),
+                    )?;
+                }
+            }
+            if pending.is_empty() {
+                return Ok(vec![]);
+            }
+            consumer.assign(&assignments)?;
+            // Transaction markers and compacted records take offsets too, counting the records would never reach the high watermark
+            let mut messages = vec![];
+            let deadline = Instant::now() + Duration::from_secs(30);
+            while !pending.is_empty() {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                match consumer.poll(remaining) {
+                    Some(Ok(message)) => {
+                        if pending
+                            .get(&message.partition())
+                            .is_some_and(|high| message.offset() < *high)
+                        {
+                            messages.push(message.detach());
+                        } else {
+                            pending.remove(&message.partition());
+                        }
+                    }
+                    Some(Err(KafkaError::PartitionEOF(partition))) => {
+                        pending.remove(&partition);
+                    }
+                    Some(Err(e)) => warn!("Cannot read a record of topic '{topic}': {e}"),
+                    None => break,
+                }
+            }
+            Ok(messages)
+        })
+        .await?;
+
+        let mut schema_registry = self.schema_registry();
+        let ids: Vec<_> = messages
+            .iter()
+            .map(|message| {
+                let id = match schema_registry.as_mut() {
+                    Some(registry) => registry.for_topic(topic).schema_id(
+                        message.payload(),
+                        message.headers(),
+                        false,
+                    ),
+                    None => SchemaId::parse(message.payload()).map(|id| (id, 5)),
+                };
+                id.map(|(id, _)| id.0)
+            })
+            .collect();
+
+        let mut report = SchemaUsageReport::new(topic, ids);
+        if let Some(schema_registry) = schema_registry.as_mut() {
+            let schema_registry = schema_registry.for_topic(topic);
+            let mut latest_versions = HashMap::new();
+            for usage in &mut report.schemas {
+                usage.subjects = schema_registry.subjects_of(usage.id).await?;
+                for subject in &usage.subjects {
+                    if !latest_versions.contains_key(&subject.subject) {
+                        let versions = schema_registry.versions(&subject.subject).await?;
+                        latest_versions.insert(
+                            subject.subject.clone(),
+                            versions.into_iter().max().unwrap_or_default(),
+                        );
+                    }
+                }
+            }
+            report.flag_deprecated(&latest_versions);
+        }
+        Ok(report)
+    }
+
+    /// Returns a warning for each topic where the records the search should start from have been deleted by the retention policy.
+    /// Without this warning, the search silently returns fewer records than expected.
+    /// A search resumed from its checkpoint starts after the checkpointed `positions` of the partitions instead.
+    pub fn retention_warnings(
+        &self,
+        topics: &[String],
+        positions: &HashMap<(String, i32), i64>,
+    ) -> Result<Vec<RetentionWarning>, Error> {
+        let from = self.search_query.offset().unwrap_or(FromOffset::End);
+        if positions.is_empty() && matches!(from, FromOffset::Beginning | FromOffset::End) {
+            return Ok(vec![]);
+        }
+        let consumer: BaseConsumer = self.config.create_kafka_consumer()?;
+        let mut warnings = vec![];
+        for topic in topics {
+            let metadata = consumer.fetch_metadata(Some(topic), Duration::from_secs(10))?;
+            let Some(metadata_topic) = metadata.topics().first() else {
+                continue;
+            };
+            // Partitions whose oldest records have been deleted, with their first retained offset
+            let mut partitions = vec![];
+            for partition in metadata_topic.partitions() {
+                let (low, high) =
+                    consumer.fetch_watermarks(topic, partition.id(), Duration::from_secs(10))?;
+                let deleted = match (positions.get(&(topic.clone(), partition.id())), &from) {
+                    (Some(position), _) => position + 1 < low,
+                    (None, FromOffset::Beginning | FromOffset::End) => false,
+                    (None, FromOffset::Offset(offset)) => *offset < low,
+                    (None, FromOffset::OffsetTail(count)) => high - count < low && low > 0,
+                    (None, FromOffset::Timestamp(_)) => low > 0 && high > low,
+                };
+                if deleted {
+                    partitions.push((partition.id(), low));
+                }
+            }
+            if partitions.is_empty() {
+                continue;
+            }
+            let earliest_timestamp = match from {
+                FromOffset::Timestamp(timestamp) if positions.is_empty() => {
+                    match self.earliest_timestamp(topic, &partitions)? {
+                        Some(earliest) if earliest > timestamp => Some(earliest),
+                        _ => continue,
+                    }
+                }
+                _ => None,
+            };
+            warnings.push(RetentionWarning {
+                topic: topic.clone(),
+                earliest_timestamp,
+                partitions,
+            });
+        }
+        Ok(warnings)
+    }
+
+    /// Returns the timestamp of the oldest record among the first record of the given partitions.
+    fn earliest_timestamp(
+        &self,
+        topic: &str,
+        partitions: &[(i32, i64)],
+    ) -> Result<Option<i64>, Error> {
+        let consumer: BaseConsumer = self.config.create_kafka_consumer()?;
+        let mut assignments = TopicPartitionList::new();
+        for (partition, offset) in partitions {
+            assignments.add_partition_offset(topic, *partition, Offset::Offset(*offset))?;
+        }
+        consumer.assign(&assignments)?;
+        let deadline = Instant::now() + Duration::from_secs(3);
+        let mut earliest: Option<i64> = None;
+        for _ in 0..partitions.len() {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match consumer.poll(remaining) {
+                Some(Ok(message)) => {
+                    if let Some(timestamp) = message.timestamp().to_millis() {
+                        earliest = Some(earliest.map_or(timestamp, |e| e.min(timestamp)));
+                    }
+                }
+                Some(Err(e)) => warn!("Cannot read the oldest record of topic '{topic}': {e}"),
+                None => break,
+            }
+        }
+        Ok(earliest)
+    }
+
+    /// Reads again the record at the given offset, to get its full value once it has been truncated.
+    pub async fn fetch_record(
+        &self,
+        topic: &str,
+        partition: i32,
+        offset: i64,
+    ) -> Result<KafkaRecord, Error> {
+        let message = self.poll_record(topic, partition, offset).await?;
+        // The consumer returns the next record when the offset has no record, after a compaction for instance
+        if message.offset() != offset {
+            return Err(Error::Error(format!(
+                "There is no record at offset {offset} of partition {partition} of topic '{topic}', the next one is at offset {}",
+                message.offset()
+            )));
+        }
+        Ok(self
+            .parse_record(message, &mut self.schema_registry())
+            .await)
+    }
+
+    /// Reads the first record of a partition from the given offset, off the async threads.
+    async fn poll_record(
+        &self,
+        topic: &str,
+        partition: i32,
+        offset: i64,
+    ) -> Result<OwnedMessage, Error> {
+        let consumer: BaseConsumer = self.config.create_kafka_consumer()?;
+        let topic = topic.to_string();
+        blocking(move || {
+            let mut assignments = TopicPartitionList::new();
+            assignments.add_partition_offset(&topic, partition, Offset::Offset(offset))?;
+            consumer.assign(&assignments)?;
+            match consumer.poll(Duration::from_secs(10)) {
+                Some(Ok(message)) => Ok(message.detach()),
+                Some(Err(e)) => Err(e.into()),
+                None => Err(Error::Error(format!(
+                    "Cannot read the record at offset {offset} of partition {partition} of topic '{topic}'"
+                ))),
+            }
+        })
+        .await
+    }
+
+    /// Reads the record right after or right before the given offset of a partition, whether it matches the search query or not.
+    /// Offsets without record, removed by the compaction or used by transaction markers, are skipped.
+    pub async fn fetch_adjacent_record(
+        &self,
+        topic: &str,
+        partition: i32,
+        offset: i64,
+        next: bool,
+    ) -> Result<KafkaRecord, Error> {
+        // Transaction markers use offsets without record, the end of the partition stops the polling instead of the timeout
+        let mut properties = self.config.kafka_config_map();
+        properties.insert("enable.partition.eof".to_string(), "true".to_string());
+        let consumer: BaseConsumer =
+            InternalConfig::kafka_client_config_from_properties(properties).create()?;
+        let topic = topic.to_string();
+        let message = blocking(move || {
+            let (low, high) =
+                consumer.fetch_watermarks(&topic, partition, Duration::from_secs(10))?;
+            let no_record = |position: &str| {
+                Error::Error(format!(
+                    "The record at offset {offset} is the {position} record of partition {partition} of topic '{topic}'"
+                ))
+            };
+            if next {
+                if offset + 1 >= high {
+                    return Err(no_record("last"));
+                }
+                let mut assignments = TopicPartitionList::new();
+                assignments.add_partition_offset(&topic, partition, Offset::Offset(offset + 1))?;
+                consumer.assign(&assignments)?;
+                return match consumer.poll(Duration::from_secs(10)) {
+                    Some(Ok(message)) => Ok(message.detach()),
+                    Some(Err(KafkaError::PartitionEOF(_))) => Err(no_record("last")),
+                    Some(Err(e)) => Err(e.into()),
+                    None => Err(Error::Error(format!(
+                        "Cannot read the record after offset {offset} of partition {partition} of topic '{topic}'"
+                    ))),
+                };
+            }
+
+            // The previous offset may have no record, the records before are read by larger and larger steps
+            let mut step = 1;
+            loop {
+                let start = (offset - step).max(low);
+                if start >= offset {
+                    return Err(no_record("first"));
+                }
+                let mut assignments = TopicPartitionList::new();
+                assignments.add_partition_offset(&topic, partition, Offset::Offset(start))?;
+                consumer.assign(&assignments)?;
+                let mut previous = None;
+                while let Some(message) = consumer.poll(Duration::from_secs(10)) {
+                    let message = match message {
+                        Ok(message) => message,
+                        Err(KafkaError::PartitionEOF(_)) => break,
+                        Err(e) => return Err(e.into()),
+                    };
+                    if message.offset() >= offset {
+                        break;
+                    }
+                    previous = Some(message.detach());
+                }
+                if let Some(message) = previous {
+                    return Ok(message);
+                }
+                if start == low {
+                    return Err(no_record("first"));
+                }
+                step *= 10;
+            }
+        })
+        .await?;
+        Ok(self
+            .parse_record(message, &mut self.schema_registry())
+            .await)
+    }
+
     pub fn count_records_in_topic(&self, topic: &str) -> Result<i64, Error> {
         let mut count = 0;
         let consumer: BaseConsumer = self.config.create_kafka_consumer()?;
@@ -250,54 +1704,32 @@ impl App {
         Ok(topics)
     }
 
-    // TODO https://github.com/fede1024/rust-rdkafka/pull/680
-    //    pub fn parse_members(
-    //        group: &GroupInfo,
-    //        members: &[GroupMemberInfo],
-    //    ) -> Result<Vec<ConsumerGroupMember>, anyhow::Error> {
-    //        return Ok(vec![]);
-    //        let members = members
-    //            .iter()
-    //            .map(|member| {
-    //                let mut assigns = Vec::new();
-    //                if group.protocol_type() == "consumer" {
-    //                    if let Some(assignment) = member.assignment() {
-    //                        let mut payload_rdr = Cursor::new(assignment);
-    //                        assigns = Self::parse_member_assignment(&mut payload_rdr)
-    //                            .expect("Parse member assignment failed");
-    //                    }
-    //                }
-    //                ConsumerGroupMember {
-    //                    member: member.id().to_owned(),
-    //                    start_offset: 0,
-    //                    end_offset: 0,
-    //                    assignments: assigns,
-    //                }
-    //            })
-    //            .collect::<Vec<_>>();
-    //
-    //        Ok(members)
-    //    }
-    //
-    //    fn parse_member_assignment(
-    //        payload_rdr: &mut Cursor<&[u8]>,
-    //    ) -> Result<Vec<MemberAssignment>, anyhow::Error> {
-    //        return Ok(vec![]);
-    //        let _version = payload_rdr.read_i16::<BigEndian>()?;
-    //        let assign_len = payload_rdr.read_i32::<BigEndian>()?;
-    //        let mut assigns = Vec::with_capacity(assign_len as usize);
-    //        for _ in 0..assign_len {
-    //            let topic = read_str(payload_rdr)?.to_owned();
-    //            let partition_len = payload_rdr.read_i32::<BigEndian>()?;
-    //            let mut partitions = Vec::with_capacity(partition_len as usize);
-    //            for _ in 0..partition_len {
-    //                let partition = payload_rdr.read_i32::<BigEndian>()?;
-    //                partitions.push(partition);
-    //            }
-    //            assigns.push(MemberAssignment { topic, partitions })
-    //        }
-    //        Ok(assigns)
-    //    }
+    /// Returns the members of the group, their assignments are only known for groups using the `consumer` protocol type.
+    fn parse_members(group: &GroupInfo) -> Vec<ConsumerGroupMember> {
+        group
+            .members()
+            .iter()
+            .map(|member| {
+                let mut parsed = ConsumerGroupMember::default();
+                parsed.member = member.id().to_string();
+                parsed.client_id = member.client_id().to_string();
+                parsed.host = member.client_host().to_string();
+                parsed.assignments = match (group.protocol_type(), member.assignment()) {
+                    ("consumer", Some(assignment)) => MemberAssignment::parse(assignment)
+                        .unwrap_or_else(|e| {
+                            warn!(
+                                "Cannot parse the assignment of member '{}' of group '{}': {e}",
+                                member.id(),
+                                group.name()
+                            );
+                            vec![]
+                        }),
+                    _ => vec![],
+                };
+                parsed
+            })
+            .collect()
+    }
 
     /// Lists available topics on the cluster with a custom kafka client.
     pub fn list_topics_from_client(yozefu_config: &YozefuConfig) -> Result<Vec<String>, Error> {
@@ -312,11 +1744,41 @@ impl App {
     }
 }
 
-//fn read_str<'a>(rdr: &'a mut Cursor<&[u8]>) -> Result<&'a str, Error> {
-//    let len = (rdr.read_i16::<BigEndian>())? as usize;
-//    let pos = rdr.position() as usize;
-//    let slice = str::from_utf8(&rdr.get_ref()[pos..(pos + len)])?;
-//    rdr.consume(len);
-//    Ok(slice)
-//}
-//
+/// Waits for the delivery of a produced record.
+async fn delivered(delivery: DeliveryFuture) -> Result<(), Error> {
+    match delivery.await {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err((e, _))) => Err(e.into()),
+        Err(_) => Err(Error::Error(
+            "The delivery of a record has been canceled".to_string(),
+        )),
+    }
+}
+
+/// Returns the number of records between the committed offsets of a group and the high watermark of each partition of the topic,
+/// `None` when the group has not committed any offset on the topic.
+fn committed_lag(
+    committed: &[(String, i32, i64)],
+    topic: &str,
+    high_watermarks: &HashMap<i32, i64>,
+) -> Option<usize> {
+    let lags = committed
+        .iter()
+        .filter(|(t, _, _)| t == topic)
+        .filter_map(|(_, partition, offset)| {
+            high_watermarks
+                .get(partition)
+                .map(|high| (high - offset).max(0) as usize)
+        })
+        .collect_vec();
+    (!lags.is_empty()).then(|| lags.iter().sum())
+}
+
+/// Runs a blocking call of the kafka clients on the threads dedicated to blocking operations, so it does not stall the async tasks.
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, Error> + Send + 'static,
+) -> Result<T, Error> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| Error::Error(format!("A blocking kafka operation failed: {e}")))?
+}
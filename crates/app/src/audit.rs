@@ -0,0 +1,132 @@
+//! Append-only audit log of the actions run against the clusters.
+//! Each line of the audit file of the workspace is a JSON object describing an action:
+//! when it happened, who ran it, the target cluster and what it did.
+//! Lines are never modified nor removed by yozefu.
+
+use std::{
+    env,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use lib::Error;
+use serde::{Deserialize, Serialize};
+
+/// An action affecting a cluster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    /// Records of topics have been read with a search query
+    Search,
+    /// Records have been written to export files
+    Export,
+    /// Records have been produced to topics
+    Produce,
+}
+
+impl std::fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Search => write!(f, "search"),
+            Self::Export => write!(f, "export"),
+            Self::Produce => write!(f, "produce"),
+        }
+    }
+}
+
+/// An entry of the audit log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEvent {
+    pub timestamp: DateTime<Utc>,
+    /// Name of the user of the operating system
+    pub user: String,
+    pub cluster: String,
+    pub action: AuditAction,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub topics: Vec<String>,
+    /// Search query, exported records...
+    pub details: String,
+}
+
+impl AuditEvent {
+    pub fn new(cluster: &str, action: AuditAction, topics: &[String], details: &str) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            user: env::var("USER")
+                .or_else(|_| env::var("USERNAME"))
+                .unwrap_or_default(),
+            cluster: cluster.to_string(),
+            action,
+            topics: topics.to_vec(),
+            details: details.to_string(),
+        }
+    }
+}
+
+/// The audit file of a workspace.
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    file: PathBuf,
+}
+
+impl AuditLog {
+    pub fn new(file: &Path) -> Self {
+        Self {
+            file: file.to_path_buf(),
+        }
+    }
+
+    /// Appends the event at the end of the audit file.
+    pub fn append(&self, event: &AuditEvent) -> Result<(), Error> {
+        if let Some(parent) = self.file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.file)?;
+        writeln!(file, "{}", serde_json::to_string(event)?)?;
+        Ok(())
+    }
+
+    /// Reads the events of the audit file, the oldest first.
+    pub fn read(&self) -> Result<Vec<AuditEvent>, Error> {
+        if !self.file.exists() {
+            return Ok(vec![]);
+        }
+        fs::read_to_string(&self.file)?
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str(line).map_err(|e| {
+                    Error::Error(format!(
+                        "Invalid event at line {} of '{}': {e}",
+                        index + 1,
+                        self.file.display()
+                    ))
+                })
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_audit_log() {
+    let directory = tempfile::tempdir().unwrap();
+    let log = AuditLog::new(&directory.path().join("audit.jsonl"));
+    assert_eq!(log.read().unwrap(), vec![]);
+
+    let search = AuditEvent::new(
+        "production",
+        AuditAction::Search,
+        &["payments".to_string()],
+        "from begin value.status == \"error\"",
+    );
+    let export = AuditEvent::new("production", AuditAction::Export, &[], "42 records");
+    log.append(&search).unwrap();
+    log.append(&export).unwrap();
+    assert_eq!(log.read().unwrap(), vec![search, export]);
+}
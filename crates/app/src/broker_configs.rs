@@ -0,0 +1,67 @@
+//! Configuration entries of the brokers, as described by the `DescribeConfigs` admin API.
+
+use rdkafka::admin::{ConfigEntry, ConfigSource};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct BrokerConfigEntry {
+    pub broker: i32,
+    pub name: String,
+    /// `None` when the value is sensitive or not set
+    pub value: Option<String>,
+    /// Where the value comes from: `default`, `static`, `dynamic broker`, `dynamic default broker`...
+    pub source: String,
+    pub read_only: bool,
+    pub sensitive: bool,
+}
+
+impl BrokerConfigEntry {
+    pub fn new(broker: i32, entry: &ConfigEntry) -> Self {
+        Self {
+            broker,
+            name: entry.name.clone(),
+            value: entry.value.clone(),
+            source: match entry.source {
+                ConfigSource::Unknown => "unknown",
+                ConfigSource::Default => "default",
+                ConfigSource::DynamicTopic => "dynamic topic",
+                ConfigSource::DynamicBroker => "dynamic broker",
+                ConfigSource::DynamicDefaultBroker => "dynamic default broker",
+                ConfigSource::StaticBroker => "static",
+            }
+            .to_string(),
+            read_only: entry.is_read_only,
+            sensitive: entry.is_sensitive,
+        }
+    }
+
+    /// Returns `true` when the name, the value or the source contains the filter, ignoring the case.
+    pub fn matches(&self, filter: &str) -> bool {
+        let filter = filter.trim().to_lowercase();
+        [
+            Some(self.name.as_str()),
+            self.value.as_deref(),
+            Some(self.source.as_str()),
+        ]
+        .into_iter()
+        .flatten()
+        .any(|s| s.to_lowercase().contains(&filter))
+    }
+}
+
+#[test]
+fn test_matches() {
+    let entry = BrokerConfigEntry {
+        broker: 1,
+        name: "log.retention.hours".to_string(),
+        value: Some("168".to_string()),
+        source: "static".to_string(),
+        read_only: true,
+        sensitive: false,
+    };
+    assert!(entry.matches("Retention"));
+    assert!(entry.matches(" 168"));
+    assert!(entry.matches("static"));
+    assert!(entry.matches(""));
+    assert!(!entry.matches("compression"));
+}
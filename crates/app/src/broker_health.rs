@@ -0,0 +1,106 @@
+//! State of the connection to each broker, computed from the statistics of librdkafka.
+
+use rdkafka::statistics::{Broker, Statistics};
+
+/// Brokers answering slower than this, in microseconds, are considered degraded
+const DEGRADED_RTT: i64 = 500_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConnectionState {
+    Connected,
+    /// The broker is slow to answer or librdkafka is connecting to it
+    Degraded,
+    Disconnected,
+}
+
+impl ConnectionState {
+    /// Overall state of the connection, `None` when no broker has been contacted yet.
+    pub fn of(brokers: &[BrokerHealth]) -> Option<Self> {
+        let states = brokers.iter().map(|b| b.state);
+        match (states.clone().min(), states.max()) {
+            (Some(best), Some(worst)) if best == worst => Some(best),
+            (Some(_), Some(_)) => Some(Self::Degraded),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ConnectionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Connected => write!(f, "connected"),
+            Self::Degraded => write!(f, "degraded"),
+            Self::Disconnected => write!(f, "disconnected"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrokerHealth {
+    /// Name of the broker, `HOSTNAME:PORT/ID`
+    pub name: String,
+    pub state: ConnectionState,
+}
+
+impl BrokerHealth {
+    /// Lists the brokers of the statistics, sorted by name.
+    /// Brokers librdkafka never connected to, as well as its internal brokers, are ignored.
+    pub fn from_statistics(statistics: &Statistics) -> Vec<Self> {
+        let mut brokers: Vec<Self> = statistics
+            .brokers
+            .values()
+            .filter(|b| b.source == "learned" || b.source == "configured")
+            .filter_map(|b| {
+                Self::state_of(b).map(|state| Self {
+                    name: b.name.clone(),
+                    state,
+                })
+            })
+            .collect();
+        brokers.sort_by(|a, b| a.name.cmp(&b.name));
+        brokers
+    }
+
+    fn state_of(broker: &Broker) -> Option<ConnectionState> {
+        match broker.state.as_str() {
+            "INIT" => None,
+            "DOWN" => Some(ConnectionState::Disconnected),
+            "UP" if broker
+                .rtt
+                .as_ref()
+                .is_some_and(|rtt| rtt.avg > DEGRADED_RTT) =>
+            {
+                Some(ConnectionState::Degraded)
+            }
+            "UP" => Some(ConnectionState::Connected),
+            _ => Some(ConnectionState::Degraded),
+        }
+    }
+}
+
+#[test]
+fn test_connection_state() {
+    let broker = |state| BrokerHealth {
+        name: "kafka:9092/1".to_string(),
+        state,
+    };
+    assert_eq!(ConnectionState::of(&[]), None);
+    assert_eq!(
+        ConnectionState::of(&[
+            broker(ConnectionState::Connected),
+            broker(ConnectionState::Connected)
+        ]),
+        Some(ConnectionState::Connected)
+    );
+    assert_eq!(
+        ConnectionState::of(&[
+            broker(ConnectionState::Connected),
+            broker(ConnectionState::Disconnected)
+        ]),
+        Some(ConnectionState::Degraded)
+    );
+    assert_eq!(
+        ConnectionState::of(&[broker(ConnectionState::Disconnected)]),
+        Some(ConnectionState::Disconnected)
+    );
+}
@@ -0,0 +1,199 @@
+//! Progress of a search reading the history of topics.
+//! A checkpoint stores, per partition, the offset of the last processed record.
+//! When the search is interrupted, after a crash or a network failure,
+//! `--resume` restarts it from its checkpoint instead of reading the topics from the beginning again.
+//! Only searches started with `--resume` write a checkpoint.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+use lib::{Error, KafkaRecord};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Minimum duration between two writes of the checkpoint file
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Offsets of the last records processed by a search.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub cluster: String,
+    pub topics: Vec<String>,
+    pub query: String,
+    /// Offset of the last processed record, by partition, by topic
+    pub positions: BTreeMap<String, BTreeMap<i32, i64>>,
+    /// Number of records matching the query so far, the `limit` of the query keeps counting after a resume
+    #[serde(default)]
+    pub matched: usize,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Checkpoint {
+    pub fn new(cluster: &str, topics: &[String], query: &str) -> Self {
+        Self {
+            cluster: cluster.to_string(),
+            topics: topics.iter().sorted().cloned().collect(),
+            query: query.to_string(),
+            positions: BTreeMap::new(),
+            matched: 0,
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Returns the name of the checkpoint file of a search.
+    /// The same search on the same cluster and topics always uses the same file.
+    pub fn file_name(cluster: &str, topics: &[String], query: &str) -> String {
+        let input = format!(
+            "{cluster}\n{}\n{}",
+            topics.iter().sorted().join(","),
+            query.trim()
+        );
+        format!("{:x}.json", Sha256::digest(input.as_bytes()))
+    }
+
+    /// Records the offset of the last processed record of a partition.
+    pub fn record(&mut self, topic: &str, partition: i32, offset: i64) {
+        let position = self
+            .positions
+            .entry(topic.to_string())
+            .or_default()
+            .entry(partition)
+            .or_insert(offset);
+        *position = offset.max(*position);
+    }
+
+    /// Returns the offset of the last processed record of each partition.
+    pub fn positions(&self) -> HashMap<(String, i32), i64> {
+        self.positions
+            .iter()
+            .flat_map(|(topic, partitions)| {
+                partitions
+                    .iter()
+                    .map(|(partition, offset)| ((topic.clone(), *partition), *offset))
+            })
+            .collect()
+    }
+
+    /// Reads the checkpoint file, `None` when the search has never been checkpointed.
+    pub fn load(file: &Path) -> Result<Option<Self>, Error> {
+        if !file.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(file)?;
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| Error::Error(format!("Invalid checkpoint '{}': {e}", file.display())))
+    }
+
+    /// Writes the checkpoint file.
+    /// The content goes to a temporary file first so a crash never leaves a truncated checkpoint.
+    pub async fn save(&mut self, file: &Path) -> Result<(), Error> {
+        if let Some(parent) = file.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        self.updated_at = Utc::now();
+        let temporary = file.with_extension("json.tmp");
+        tokio::fs::write(&temporary, serde_json::to_string_pretty(self)?).await?;
+        tokio::fs::rename(&temporary, file).await?;
+        Ok(())
+    }
+}
+
+/// Keeps the checkpoint of a search up to date while records are processed.
+#[derive(Debug)]
+pub struct Checkpointer {
+    checkpoint: Checkpoint,
+    file: PathBuf,
+    saved_at: Instant,
+}
+
+impl Checkpointer {
+    pub fn new(checkpoint: Checkpoint, file: &Path) -> Self {
+        Self {
+            checkpoint,
+            file: file.to_path_buf(),
+            saved_at: Instant::now(),
+        }
+    }
+
+    /// Returns the positions the search resumes from, empty when the search starts from scratch.
+    pub fn positions(&self) -> HashMap<(String, i32), i64> {
+        self.checkpoint.positions()
+    }
+
+    /// Returns the number of records that matched the query before the search was resumed.
+    pub fn matched(&self) -> usize {
+        self.checkpoint.matched
+    }
+
+    /// Marks the record as processed, the checkpoint file is written every few seconds.
+    /// Call it once the record has been output, a record checkpointed before is lost after a crash.
+    pub async fn processed(&mut self, record: &KafkaRecord, matched: bool) -> Result<(), Error> {
+        self.checkpoint
+            .record(&record.topic, record.partition, record.offset);
+        if matched {
+            self.checkpoint.matched += 1;
+        }
+        if self.saved_at.elapsed() < CHECKPOINT_INTERVAL {
+            return Ok(());
+        }
+        self.save().await
+    }
+
+    /// Writes the checkpoint file now.
+    pub async fn save(&mut self) -> Result<(), Error> {
+        self.saved_at = Instant::now();
+        self.checkpoint.save(&self.file).await
+    }
+
+    /// Deletes the checkpoint file once the search has read everything it had to.
+    pub async fn complete(&self) -> Result<(), Error> {
+        match tokio::fs::try_exists(&self.file).await? {
+            true => Ok(tokio::fs::remove_file(&self.file).await?),
+            false => Ok(()),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_checkpoint() {
+    let directory = tempfile::tempdir().unwrap();
+    let topics = vec!["payments".to_string(), "orders".to_string()];
+    let file = directory.path().join(Checkpoint::file_name(
+        "production",
+        &topics,
+        "from begin value.status == \"error\"",
+    ));
+    assert_eq!(Checkpoint::load(&file).unwrap(), None);
+
+    let mut checkpoint = Checkpoint::new("production", &topics, "from begin");
+    checkpoint.record("payments", 0, 41);
+    checkpoint.record("payments", 0, 12);
+    checkpoint.record("orders", 2, 7);
+    checkpoint.matched = 3;
+    checkpoint.save(&file).await.unwrap();
+
+    let loaded = Checkpoint::load(&file).unwrap().unwrap();
+    assert_eq!(loaded, checkpoint);
+    assert_eq!(
+        loaded.positions(),
+        HashMap::from([
+            (("payments".to_string(), 0), 41),
+            (("orders".to_string(), 2), 7)
+        ])
+    );
+    assert_eq!(
+        Checkpoint::file_name("production", &topics, "from begin"),
+        Checkpoint::file_name(
+            "production",
+            &["orders".to_string(), "payments".to_string()],
+            "from begin "
+        )
+    );
+}
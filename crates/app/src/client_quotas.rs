@@ -0,0 +1,340 @@
+//! Client quotas of the cluster, as described by the `DescribeClientQuotas` API of the brokers.
+//! librdkafka does not implement this API, the request is sent to a broker over a connection of its own,
+//! opened with the kafka properties of the cluster: `PLAINTEXT`, `SSL` and the `PLAIN` SASL mechanism are supported.
+
+use std::{collections::HashMap, fs, path::Path, time::Duration};
+
+use itertools::Itertools;
+use lib::Error;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::TcpStream,
+};
+use tokio_native_tls::native_tls::{Certificate, Identity, TlsConnector};
+
+const SASL_HANDSHAKE: i16 = 17;
+const SASL_AUTHENTICATE: i16 = 36;
+const DESCRIBE_CLIENT_QUOTAS: i16 = 48;
+
+/// Requests of a connection are sent one by one, they share the same correlation id
+const CORRELATION_ID: i32 = 1;
+
+/// Maximum time to connect to a broker and describe the quotas
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ClientQuota {
+    /// Entity the quota applies to, such as `user=alice, client-id=payments`, `<default>` is the default entity
+    pub entity: String,
+    /// `producer_byte_rate`, `consumer_byte_rate`, `request_percentage`, `controller_mutation_rate`...
+    pub name: String,
+    pub value: f64,
+}
+
+impl ClientQuota {
+    /// Returns `true` when the entity or the name contains the filter, ignoring the case.
+    pub fn matches(&self, filter: &str) -> bool {
+        let filter = filter.trim().to_lowercase();
+        [&self.entity, &self.name]
+            .into_iter()
+            .any(|s| s.to_lowercase().contains(&filter))
+    }
+}
+
+trait Connection: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Connection for T {}
+
+/// Describes the client quotas with the first broker answering, sorted by entity and name.
+pub(crate) async fn describe_client_quotas(
+    properties: &HashMap<String, String>,
+    brokers: &[(String, u16)],
+) -> Result<Vec<ClientQuota>, Error> {
+    let mut error = Error::Error("The cluster has no broker".to_string());
+    for (host, port) in brokers {
+        let quotas = tokio::time::timeout(TIMEOUT, describe(properties, host, *port))
+            .await
+            .unwrap_or(Err(Error::Error(format!(
+                "Broker {host}:{port} did not describe the client quotas in time"
+            ))));
+        match quotas {
+            Ok(quotas) => return Ok(quotas),
+            Err(e) => error = e,
+        }
+    }
+    Err(error)
+}
+
+async fn describe(
+    properties: &HashMap<String, String>,
+    host: &str,
+    port: u16,
+) -> Result<Vec<ClientQuota>, Error> {
+    let client_id = properties
+        .get("client.id")
+        .map_or(crate::APPLICATION_NAME, String::as_str);
+    let mut connection = connect(properties, host, port).await?;
+    if properties
+        .get("security.protocol")
+        .is_some_and(|p| p.to_lowercase().starts_with("sasl"))
+    {
+        authenticate(&mut connection, properties, client_id).await?;
+    }
+
+    // No component and not strict: every quota of the cluster
+    let mut body = vec![];
+    body.extend(0_i32.to_be_bytes());
+    body.push(0);
+    let response = exchange(&mut connection, client_id, DESCRIBE_CLIENT_QUOTAS, 0, &body).await?;
+    let mut response = Response(&response);
+    let _throttle_time_ms = response.i32()?;
+    check_error(response.i16()?, response.nullable_string()?)?;
+
+    let mut quotas = vec![];
+    for _ in 0..response.i32()?.max(0) {
+        let mut entity = vec![];
+        for _ in 0..response.i32()?.max(0) {
+            let entity_type = response.string()?;
+            let name = response
+                .nullable_string()?
+                .unwrap_or("<default>".to_string());
+            entity.push(format!("{entity_type}={name}"));
+        }
+        let entity = entity.join(", ");
+        for _ in 0..response.i32()?.max(0) {
+            quotas.push(ClientQuota {
+                entity: entity.clone(),
+                name: response.string()?,
+                value: response.f64()?,
+            });
+        }
+    }
+    Ok(quotas
+        .into_iter()
+        .sorted_by(|a, b| a.entity.cmp(&b.entity).then_with(|| a.name.cmp(&b.name)))
+        .collect())
+}
+
+async fn connect(
+    properties: &HashMap<String, String>,
+    host: &str,
+    port: u16,
+) -> Result<Box<dyn Connection>, Error> {
+    let protocol = properties
+        .get("security.protocol")
+        .map_or("plaintext".to_string(), |p| p.to_lowercase());
+    let stream = TcpStream::connect((host, port)).await?;
+    match protocol.as_str() {
+        "plaintext" | "sasl_plaintext" => Ok(Box::new(stream)),
+        "ssl" | "sasl_ssl" => Ok(Box::new(
+            tls_connector(properties)?
+                .connect(host, stream)
+                .await
+                .map_err(tls_error)?,
+        )),
+        protocol => Err(Error::Error(format!(
+            "Unknown security protocol '{protocol}'"
+        ))),
+    }
+}
+
+/// TLS settings of the connection, from the `ssl.*` properties of librdkafka.
+fn tls_connector(
+    properties: &HashMap<String, String>,
+) -> Result<tokio_native_tls::TlsConnector, Error> {
+    let mut builder = TlsConnector::builder();
+    // librdkafka also accepts a directory or `probe`, the certificates of the system are used then
+    if let Some(ca) = properties
+        .get("ssl.ca.location")
+        .filter(|ca| Path::new(ca).is_file())
+    {
+        builder.add_root_certificate(Certificate::from_pem(&fs::read(ca)?).map_err(tls_error)?);
+    }
+    if let (Some(certificate), Some(key)) = (
+        properties.get("ssl.certificate.location"),
+        properties.get("ssl.key.location"),
+    ) {
+        builder.identity(
+            Identity::from_pkcs8(&fs::read(certificate)?, &fs::read(key)?).map_err(tls_error)?,
+        );
+    }
+    if properties
+        .get("enable.ssl.certificate.verification")
+        .is_some_and(|v| v == "false")
+    {
+        builder.danger_accept_invalid_certs(true);
+    }
+    if properties
+        .get("ssl.endpoint.identification.algorithm")
+        .is_some_and(|v| v == "none")
+    {
+        builder.danger_accept_invalid_hostnames(true);
+    }
+    Ok(builder.build().map_err(tls_error)?.into())
+}
+
+/// Authenticates with the `PLAIN` SASL mechanism, the only one supported.
+async fn authenticate(
+    connection: &mut Box<dyn Connection>,
+    properties: &HashMap<String, String>,
+    client_id: &str,
+) -> Result<(), Error> {
+    let mechanism = properties
+        .get("sasl.mechanisms")
+        .or(properties.get("sasl.mechanism"))
+        .map_or("GSSAPI", String::as_str);
+    if !mechanism.eq_ignore_ascii_case("PLAIN") {
+        return Err(Error::Error(format!(
+            "Client quotas cannot be described with the SASL mechanism '{mechanism}', only PLAIN is supported"
+        )));
+    }
+    let mut body = vec![];
+    put_string(&mut body, "PLAIN");
+    let response = exchange(connection, client_id, SASL_HANDSHAKE, 1, &body).await?;
+    check_error(Response(&response).i16()?, None)?;
+
+    let username = properties.get("sasl.username").map_or("", String::as_str);
+    let password = properties.get("sasl.password").map_or("", String::as_str);
+    let token = format!("\0{username}\0{password}");
+    let mut body = vec![];
+    body.extend((token.len() as i32).to_be_bytes());
+    body.extend(token.as_bytes());
+    let response = exchange(connection, client_id, SASL_AUTHENTICATE, 0, &body).await?;
+    let mut response = Response(&response);
+    check_error(response.i16()?, response.nullable_string()?)
+}
+
+/// Sends the request and returns the body of the response.
+async fn exchange(
+    connection: &mut Box<dyn Connection>,
+    client_id: &str,
+    api_key: i16,
+    api_version: i16,
+    body: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let mut request = vec![];
+    request.extend(api_key.to_be_bytes());
+    request.extend(api_version.to_be_bytes());
+    request.extend(CORRELATION_ID.to_be_bytes());
+    put_string(&mut request, client_id);
+    request.extend(body);
+    connection
+        .write_all(&(request.len() as i32).to_be_bytes())
+        .await?;
+    connection.write_all(&request).await?;
+    connection.flush().await?;
+
+    let size = connection.read_i32().await?;
+    let mut response = vec![0; usize::try_from(size).map_err(|_| malformed())?];
+    connection.read_exact(&mut response).await?;
+    // The header of the response is the correlation id
+    match Response(&response).i32()? {
+        CORRELATION_ID => Ok(response.split_off(4)),
+        _ => Err(malformed()),
+    }
+}
+
+fn put_string(buffer: &mut Vec<u8>, s: &str) {
+    buffer.extend((s.len() as i16).to_be_bytes());
+    buffer.extend(s.as_bytes());
+}
+
+fn check_error(code: i16, message: Option<String>) -> Result<(), Error> {
+    match code {
+        0 => Ok(()),
+        code => Err(Error::Error(format!(
+            "The broker answered with the error code {code}{}",
+            message.map(|m| format!(": {m}")).unwrap_or_default()
+        ))),
+    }
+}
+
+fn tls_error(e: tokio_native_tls::native_tls::Error) -> Error {
+    Error::Error(format!("TLS error: {e}"))
+}
+
+fn malformed() -> Error {
+    Error::Error("The response of the broker is malformed".to_string())
+}
+
+/// Reads the fields of a response of the kafka protocol.
+struct Response<'a>(&'a [u8]);
+
+impl Response<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let (bytes, rest) = self.0.split_first_chunk::<N>().ok_or(malformed())?;
+        self.0 = rest;
+        Ok(*bytes)
+    }
+
+    fn i16(&mut self) -> Result<i16, Error> {
+        self.take().map(i16::from_be_bytes)
+    }
+
+    fn i32(&mut self) -> Result<i32, Error> {
+        self.take().map(i32::from_be_bytes)
+    }
+
+    fn f64(&mut self) -> Result<f64, Error> {
+        self.take().map(f64::from_be_bytes)
+    }
+
+    fn nullable_string(&mut self) -> Result<Option<String>, Error> {
+        let Ok(length) = usize::try_from(self.i16()?) else {
+            return Ok(None);
+        };
+        let (s, rest) = self.0.split_at_checked(length).ok_or(malformed())?;
+        self.0 = rest;
+        Ok(Some(String::from_utf8_lossy(s).into_owned()))
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        self.nullable_string()?.ok_or(malformed())
+    }
+}
+
+#[test]
+fn test_parse_response() {
+    let mut response = vec![];
+    response.extend(0_i32.to_be_bytes());
+    response.extend(0_i16.to_be_bytes());
+    response.extend((-1_i16).to_be_bytes());
+    response.extend(1_i32.to_be_bytes());
+    response.extend(2_i32.to_be_bytes());
+    put_string(&mut response, "user");
+    put_string(&mut response, "alice");
+    put_string(&mut response, "client-id");
+    response.extend((-1_i16).to_be_bytes());
+    response.extend(1_i32.to_be_bytes());
+    put_string(&mut response, "producer_byte_rate");
+    response.extend(1_048_576_f64.to_be_bytes());
+
+    let mut response = Response(&response);
+    assert_eq!(response.i32().unwrap(), 0);
+    assert_eq!(response.i16().unwrap(), 0);
+    assert_eq!(response.nullable_string().unwrap(), None);
+    assert_eq!(response.i32().unwrap(), 1);
+    assert_eq!(response.i32().unwrap(), 2);
+    assert_eq!(response.string().unwrap(), "user");
+    assert_eq!(response.string().unwrap(), "alice");
+    assert_eq!(response.string().unwrap(), "client-id");
+    assert_eq!(response.nullable_string().unwrap(), None);
+    assert_eq!(response.i32().unwrap(), 1);
+    assert_eq!(response.string().unwrap(), "producer_byte_rate");
+    assert_eq!(response.f64().unwrap(), 1_048_576.);
+    assert!(response.i16().is_err());
+}
+
+#[test]
+fn test_matches() {
+    let quota = ClientQuota {
+        entity: "user=alice, client-id=<default>".to_string(),
+        name: "producer_byte_rate".to_string(),
+        value: 1_048_576.,
+    };
+    assert!(quota.matches("Alice"));
+    assert!(quota.matches("byte_rate"));
+    assert!(quota.matches(""));
+    assert!(!quota.matches("consumer"));
+}
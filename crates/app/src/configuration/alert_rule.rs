@@ -0,0 +1,62 @@
+//! Rules of the watch mode, see [`crate::Watch`].
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// An alert triggered when too many records match a search query within a minute.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+pub struct AlertRule {
+    /// Name of the alert
+    pub name: String,
+    /// Search query the records must match, the `from`, `limit` and `order by` clauses are ignored
+    pub query: String,
+    /// The alert is triggered when more records than this threshold match within a minute
+    pub threshold: usize,
+    /// What happens when the alert is triggered
+    #[serde(default = "default_alert_actions")]
+    pub actions: Vec<AlertAction>,
+}
+
+/// What happens when an alert is triggered.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum AlertAction {
+    /// Rings the terminal bell
+    Bell,
+    /// Shows a desktop notification, with `notify-send` on Linux and `osascript` on macOS
+    Notification,
+    /// Runs a shell command. The alert is passed with the `YOZEFU_ALERT_NAME`, `YOZEFU_ALERT_COUNT` and `YOZEFU_ALERT_THRESHOLD` environment variables
+    Command(String),
+    /// Sends the alert as JSON with a POST request to the URL
+    Webhook(Url),
+    /// Sends the alert to the notifier of the `notifiers` property with this name
+    Notify(String),
+}
+
+fn default_alert_actions() -> Vec<AlertAction> {
+    vec![AlertAction::Bell]
+}
+
+#[test]
+fn test_deserialize_alert_rule() {
+    let rule: AlertRule = serde_json::from_str(
+        r#"{
+            "name": "payment errors",
+            "query": "topic == \"payments\" and value.status == \"error\"",
+            "threshold": 100,
+            "actions": ["bell", "notification", {"command": "say alert"}, {"webhook": "http://localhost:8080/alerts"}]
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(rule.actions.len(), 4);
+    assert_eq!(
+        rule.actions[2],
+        AlertAction::Command("say alert".to_string())
+    );
+
+    let rule: AlertRule =
+        serde_json::from_str(r#"{"name": "any", "query": "", "threshold": 0}"#).unwrap();
+    assert_eq!(rule.actions, vec![AlertAction::Bell]);
+}
@@ -0,0 +1,215 @@
+//! Anonymization profiles scrub kafka records before they are exported,
+//! so production samples can be shared without leaking sensitive data.
+
+use lib::{DataType, KafkaRecord};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+/// A named set of rules applied to exported kafka records.
+/// Fields are referenced like in the search query: `key`, `value.user.email`, `value.items[0].id` or `headers.authorization`.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Default)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+pub struct AnonymizationProfile {
+    /// Fields whose value is replaced by its SHA-256 hash
+    #[serde(default)]
+    pub hash: Vec<String>,
+    /// Fields removed from the record
+    #[serde(default)]
+    pub drop: Vec<String>,
+    /// Truncates the timestamp of the record to the given precision
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generalize_timestamp: Option<TimestampPrecision>,
+}
+
+/// Precision used to generalize the timestamp of a record
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum TimestampPrecision {
+    Second,
+    Minute,
+    Hour,
+    Day,
+}
+
+impl TimestampPrecision {
+    fn truncate(self, timestamp: i64) -> i64 {
+        let unit = match self {
+            TimestampPrecision::Second => 1_000,
+            TimestampPrecision::Minute => 60_000,
+            TimestampPrecision::Hour => 3_600_000,
+            TimestampPrecision::Day => 86_400_000,
+        };
+        timestamp - timestamp.rem_euclid(unit)
+    }
+}
+
+impl AnonymizationProfile {
+    /// Returns an anonymized copy of the given kafka record.
+    pub fn apply(&self, record: &KafkaRecord) -> KafkaRecord {
+        let mut record = record.clone();
+        for field in &self.drop {
+            Self::edit(&mut record, field, |_| None);
+        }
+        for field in &self.hash {
+            Self::edit(&mut record, field, |value| Some(Value::String(hash(value))));
+        }
+        if let Some(precision) = self.generalize_timestamp {
+            record.timestamp = record.timestamp.map(|t| precision.truncate(t));
+        }
+        record.key_as_string = record.key.to_string();
+        record.value_as_string = record.value.to_string();
+        record
+    }
+
+    /// Replaces the field of the record with the result of `f`, the field is removed when `f` returns `None`.
+    pub(super) fn edit(record: &mut KafkaRecord, field: &str, f: impl Fn(&Value) -> Option<Value>) {
+        let (root, path) = field.split_once('.').unwrap_or((field, ""));
+        match root {
+            "key" => Self::edit_data_type(&mut record.key, path, f),
+            "value" => Self::edit_data_type(&mut record.value, path, f),
+            "headers" => {
+                let names = match path.is_empty() {
+                    true => record.headers.keys().cloned().collect(),
+                    false => vec![path.to_string()],
+                };
+                for name in names {
+                    let Some(header) = record.headers.get(&name) else {
+                        continue;
+                    };
+                    match f(&Value::String(header.clone())) {
+                        Some(value) => {
+                            let value = DataType::Json(value).raw();
+                            record.headers.insert(name, value);
+                        }
+                        None => {
+                            record.headers.remove(&name);
+                        }
+                    }
+                }
+            }
+            _ => warn!("Cannot anonymize unknown field '{field}'"),
+        }
+    }
+
+    fn edit_data_type(data: &mut DataType, path: &str, f: impl Fn(&Value) -> Option<Value>) {
+        if path.is_empty() {
+            *data = match f(&data.clone().into()) {
+                Some(Value::String(s)) => DataType::String(s),
+                Some(value) => DataType::Json(value),
+                None => DataType::default(),
+            };
+            return;
+        }
+
+        let DataType::Json(json) = data else {
+            return;
+        };
+        let pointer = format!("/{}", path.replace(['.', '['], "/").replace(']', ""));
+        let Some(current) = json.pointer(&pointer) else {
+            return;
+        };
+        match f(current) {
+            Some(value) => {
+                if let Some(current) = json.pointer_mut(&pointer) {
+                    *current = value;
+                }
+            }
+            None => {
+                let (parent, last) = pointer.rsplit_once('/').unwrap_or_default();
+                match json.pointer_mut(parent) {
+                    Some(Value::Object(map)) => {
+                        map.shift_remove(last);
+                    }
+                    Some(Value::Array(array)) => {
+                        if let Ok(index) = last.parse::<usize>() {
+                            if index < array.len() {
+                                array.remove(index);
+                            }
+                        }
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+/// Hashes a json value, strings are hashed without their quotes.
+fn hash(value: &Value) -> String {
+    let input = match value {
+        Value::String(s) => s.clone(),
+        v => v.to_string(),
+    };
+    format!("{:x}", Sha256::digest(input.as_bytes()))
+}
+
+#[test]
+fn test_drop_and_hash_fields() {
+    use serde_json::json;
+    let mut record = KafkaRecord::default();
+    record.key = DataType::String("my-key".to_string());
+    record.value =
+        DataType::Json(json!({"user": {"email": "jon@example.com", "age": 32}, "items": [1, 2]}));
+    record
+        .headers
+        .insert("authorization".to_string(), "Bearer secret".to_string());
+
+    let profile = AnonymizationProfile {
+        hash: vec!["key".to_string(), "value.user.email".to_string()],
+        drop: vec![
+            "value.user.age".to_string(),
+            "value.items[0]".to_string(),
+            "headers.authorization".to_string(),
+        ],
+        generalize_timestamp: None,
+    };
+    let anonymized = profile.apply(&record);
+
+    assert_eq!(
+        anonymized.key,
+        DataType::String(hash(&Value::String("my-key".to_string())))
+    );
+    assert_eq!(
+        anonymized.value,
+        DataType::Json(json!({"user": {"email": hash(&json!("jon@example.com"))}, "items": [2]}))
+    );
+    assert!(anonymized.headers.is_empty());
+    assert_eq!(anonymized.value_as_string, anonymized.value.to_string());
+}
+
+#[test]
+fn test_generalize_timestamp() {
+    let mut record = KafkaRecord::default();
+    record.timestamp = Some(1_700_000_123_456);
+    let profile = AnonymizationProfile {
+        generalize_timestamp: Some(TimestampPrecision::Hour),
+        ..Default::default()
+    };
+    assert_eq!(profile.apply(&record).timestamp, Some(1_699_999_200_000));
+}
+
+#[test]
+fn test_hash_all_headers() {
+    let mut record = KafkaRecord::default();
+    record
+        .headers
+        .insert("authorization".to_string(), "Bearer secret".to_string());
+    record
+        .headers
+        .insert("trace-id".to_string(), "abc".to_string());
+
+    let profile = AnonymizationProfile {
+        hash: vec!["headers".to_string()],
+        ..Default::default()
+    };
+    let anonymized = profile.apply(&record);
+
+    assert_eq!(anonymized.headers.len(), 2);
+    assert_eq!(
+        anonymized.headers["trace-id"],
+        hash(&Value::String("abc".to_string()))
+    );
+}
@@ -0,0 +1,38 @@
+//! How the TUI copies content to the clipboard.
+//! The system clipboard is not reachable from a remote host,
+//! the OSC 52 escape sequence asks the terminal emulator to fill its clipboard instead.
+
+use std::env;
+
+use serde::{Deserialize, Serialize};
+
+/// Clipboard used by the TUI.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum ClipboardBackend {
+    /// The clipboard of the operating system
+    System,
+    /// The OSC 52 escape sequence, works through SSH and tmux if the terminal supports it
+    Osc52,
+}
+
+impl ClipboardBackend {
+    /// Uses OSC 52 over SSH, the system clipboard otherwise.
+    pub fn detect() -> Self {
+        Self::from_env(env::var_os("SSH_TTY").is_some() || env::var_os("SSH_CONNECTION").is_some())
+    }
+
+    fn from_env(ssh: bool) -> Self {
+        match ssh {
+            true => Self::Osc52,
+            false => Self::System,
+        }
+    }
+}
+
+#[test]
+fn test_detect_clipboard_backend() {
+    assert_eq!(ClipboardBackend::from_env(true), ClipboardBackend::Osc52);
+    assert_eq!(ClipboardBackend::from_env(false), ClipboardBackend::System);
+}
@@ -1,12 +1,22 @@
 //! module defining the configuration structure of the application
 
 use indexmap::IndexMap;
+use lib::{
+    Error,
+    kafka::{
+        ApicurioOptions, SchemaRegistryAuth, SchemaRegistryClient, SchemaRegistryKind,
+        SchemaRegistryOptions,
+    },
+};
 use resolve_path::PathResolveExt;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, path::PathBuf};
+use std::{collections::HashMap, fs, path::PathBuf};
 use url::Url;
 
-use crate::configuration::{ConsumerConfig, YozefuConfig};
+use crate::configuration::{
+    ConsumerConfig, ConsumerGroupConfig, KeyDecoder, MaskingRule, ProducerConfig, Secret,
+    TraceConfig, ValueDecoder, YozefuConfig,
+};
 
 use super::Configuration;
 
@@ -29,8 +39,18 @@ impl Default for ClusterConfig {
         Self {
             url_template: None,
             schema_registry: None,
+            schema_registries: IndexMap::new(),
             kafka: IndexMap::new(),
             consumer: None,
+            consumer_group: None,
+            producer: None,
+            secrets: IndexMap::new(),
+            key_decoders: IndexMap::new(),
+            value_decoders: IndexMap::new(),
+            masking_rules: IndexMap::new(),
+            traces: IndexMap::new(),
+            read_only: false,
+            protected: false,
         }
     }
 }
@@ -44,10 +64,40 @@ pub struct ClusterConfig {
     /// Schema registry configuration
     #[serde(skip_serializing_if = "Option::is_none")]
     pub schema_registry: Option<SchemaRegistryConfig>,
+    /// Schema registries of the topics starting with a given prefix, the longest prefix wins. Other topics use `schema_registry`
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub schema_registries: IndexMap<String, SchemaRegistryConfig>,
     /// Kafka consumer properties for this cluster, see <https://github.com/confluentinc/librdkafka/blob/master/CONFIGURATION.md> for more details
     pub kafka: IndexMap<String, String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub consumer: Option<ConsumerConfig>,
+    /// Whether the consumers of the searches commit their offsets with a stable group id, or only assign the partitions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub consumer_group: Option<ConsumerGroupConfig>,
+    /// Kafka producer settings, used when producing records to this cluster
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub producer: Option<ProducerConfig>,
+    /// Kafka properties resolved at startup from a command or the OS keyring, instead of being stored in plaintext
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub secrets: IndexMap<String, Secret>,
+    /// How the keys are decoded, by topic. Keys of other topics are decoded with the schema registry, as JSON or as strings
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub key_decoders: IndexMap<String, KeyDecoder>,
+    /// How the values are decoded, by topic. The format of values of other topics is detected from their content
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub value_decoders: IndexMap<String, ValueDecoder>,
+    /// Personal data redacted in the records as soon as they are read, by topic. The rules of `*` apply to every topic
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub masking_rules: IndexMap<String, Vec<MaskingRule>>,
+    /// Where the correlation id of the records is and the related topics to trace it in, by topic. The trace of `*` applies to the other topics
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub traces: IndexMap<String, TraceConfig>,
+    /// Disables the operations modifying the cluster, such as committing offsets or creating topics
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub read_only: bool,
+    /// Destructive operations, such as deleting a topic or resetting offsets, require typing the name of the cluster to confirm
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub protected: bool,
 }
 
 impl ClusterConfig {
@@ -68,6 +118,30 @@ impl ClusterConfig {
         cloned
     }
 
+    /// Resolves the secrets and injects them into the kafka properties and the schema registry headers.
+    pub fn resolve_secrets(mut self) -> Result<Self, Error> {
+        for (key, secret) in &self.secrets {
+            self.kafka.insert(key.clone(), secret.resolve()?);
+        }
+        for schema_registry in self
+            .schema_registry
+            .iter_mut()
+            .chain(self.schema_registries.values_mut())
+        {
+            for (key, secret) in &schema_registry.secrets {
+                schema_registry
+                    .headers
+                    .insert(key.clone(), secret.resolve()?);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Returns `true` if the value of the kafka property must not be displayed.
+    pub fn is_sensitive(&self, key: &str) -> bool {
+        SENSITIVE_KAFKA_PROPERTIES.contains(&key) || self.secrets.contains_key(key)
+    }
+
     //    // cluster is something that can be converted to &str, must be a generic though
     //    pub fn create<T>(self, cluster: T) -> ClusterConfig
     //    where
@@ -86,9 +160,168 @@ impl ClusterConfig {
 pub struct SchemaRegistryConfig {
     /// Url of the schema registry
     pub url: Url,
+    /// Implementation of the schema registry, `confluent` by default
+    #[serde(default, skip_serializing_if = "SchemaRegistryType::is_confluent")]
+    pub kind: SchemaRegistryType,
+    /// How the Apicurio serializers reference the schemas, when `kind` is `apicurio`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub apicurio: Option<ApicurioConfig>,
     /// HTTP headers to be used when communicating with the schema registry
     #[serde(default)]
     pub headers: HashMap<String, String>,
+    /// HTTP headers resolved at startup from a command or the OS keyring
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub secrets: HashMap<String, Secret>,
+    /// Basic authentication or bearer token sent with every request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authentication: Option<SchemaRegistryAuthentication>,
+    /// Certificates of the HTTPS connection, for registries signed by a private authority or requiring mutual TLS
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<SchemaRegistryTls>,
+}
+
+/// Implementations of the schema registry supported by yozefu
+#[derive(Debug, Deserialize, PartialEq, Eq, Serialize, Clone, Copy, Default)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaRegistryType {
+    /// Confluent schema registry and the registries compatible with its API, such as Redpanda or Karapace
+    #[default]
+    Confluent,
+    /// Apicurio registry, the URL is the one of the REST API v2: `https://<host>/apis/registry/v2`
+    Apicurio,
+}
+
+impl SchemaRegistryType {
+    fn is_confluent(&self) -> bool {
+        self == &Self::Confluent
+    }
+}
+
+/// Settings of the Apicurio serializers producing the records, see <https://www.apicur.io/registry/docs/apicurio-registry/2.6.x/getting-started/assembly-configuring-kafka-client-serdes.html>
+#[derive(Debug, Deserialize, PartialEq, Eq, Serialize, Clone, Default)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+pub struct ApicurioConfig {
+    /// Id referenced by the records, like `apicurio.registry.use-id`
+    #[serde(default)]
+    pub use_id: ApicurioId,
+    /// Length of the id following the magic byte when the id is not in the headers, like `apicurio.registry.id-handler`
+    #[serde(default)]
+    pub id_handler: ApicurioIdHandler,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq, Serialize, Clone, Copy, Default)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ApicurioId {
+    #[default]
+    GlobalId,
+    ContentId,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq, Serialize, Clone, Copy, Default)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ApicurioIdHandler {
+    /// The id is written on 8 bytes
+    #[default]
+    Default,
+    /// The id is written on 4 bytes, like the Confluent wire format
+    Legacy,
+}
+
+/// How yozefu authenticates to the schema registry
+#[derive(Debug, Deserialize, PartialEq, Eq, Serialize, Clone)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaRegistryAuthentication {
+    /// HTTP basic authentication, such as the API key and secret of Confluent Cloud
+    Basic {
+        username: String,
+        password: Option<String>,
+    },
+    /// Static token sent in the `Authorization: Bearer <token>` header
+    Bearer(String),
+}
+
+/// PEM files of the HTTPS connection to the schema registry
+#[derive(Debug, Deserialize, PartialEq, Eq, Serialize, Clone, Default)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+pub struct SchemaRegistryTls {
+    /// Certificate authority trusted in addition to the system ones
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_location: Option<PathBuf>,
+    /// Client certificate presented to the schema registry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub certificate_location: Option<PathBuf>,
+    /// Private key of the client certificate, in the PKCS #8 format
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_location: Option<PathBuf>,
+}
+
+impl SchemaRegistryConfig {
+    pub fn new(url: Url) -> Self {
+        Self {
+            url,
+            kind: SchemaRegistryType::default(),
+            apicurio: None,
+            headers: HashMap::default(),
+            secrets: HashMap::default(),
+            authentication: None,
+            tls: None,
+        }
+    }
+
+    /// Options of the HTTP client, the certificates are read from their files.
+    pub fn options(&self) -> Result<SchemaRegistryOptions, Error> {
+        let read = |path: &PathBuf| {
+            fs::read(path.resolve()).map_err(|e| {
+                Error::Error(format!(
+                    "Cannot read the schema registry certificate '{}': {e}",
+                    path.display()
+                ))
+            })
+        };
+        let tls = self.tls.clone().unwrap_or_default();
+        let client_certificate = match (&tls.certificate_location, &tls.key_location) {
+            (Some(certificate), Some(key)) => Some((read(certificate)?, read(key)?)),
+            (None, None) => None,
+            _ => {
+                return Err(Error::Error(
+                    "Both 'certificate_location' and 'key_location' are required for mutual TLS with the schema registry".to_string(),
+                ));
+            }
+        };
+        let apicurio = self.apicurio.clone().unwrap_or_default();
+        let mut options = SchemaRegistryOptions::default();
+        if self.kind == SchemaRegistryType::Apicurio {
+            let mut apicurio_options = ApicurioOptions::default();
+            apicurio_options.use_content_id = apicurio.use_id == ApicurioId::ContentId;
+            apicurio_options.legacy_id = apicurio.id_handler == ApicurioIdHandler::Legacy;
+            options.kind = SchemaRegistryKind::Apicurio(apicurio_options);
+        }
+        options.headers = self.headers.clone();
+        options.auth = match &self.authentication {
+            None => SchemaRegistryAuth::None,
+            Some(SchemaRegistryAuthentication::Basic { username, password }) => {
+                SchemaRegistryAuth::Basic {
+                    username: username.clone(),
+                    password: password.clone(),
+                }
+            }
+            Some(SchemaRegistryAuthentication::Bearer(token)) => {
+                SchemaRegistryAuth::Bearer(token.clone())
+            }
+        };
+        options.ca_certificate = tls.ca_location.as_ref().map(read).transpose()?;
+        options.client_certificate = client_certificate;
+        Ok(options)
+    }
+
+    /// Creates a client of the schema registry.
+    pub fn client(&self) -> Result<SchemaRegistryClient, Error> {
+        SchemaRegistryClient::new(self.url.clone(), &self.options()?)
+    }
 }
 
 impl Configuration for ClusterConfig {
@@ -104,8 +337,18 @@ impl ClusterConfig {
         Self {
             url_template: None,
             schema_registry: None,
+            schema_registries: self.schema_registries,
             kafka: indexmap::IndexMap::from_iter(kafka_properties),
             consumer: self.consumer,
+            consumer_group: self.consumer_group,
+            producer: self.producer,
+            secrets: self.secrets,
+            key_decoders: self.key_decoders,
+            value_decoders: self.value_decoders,
+            masking_rules: self.masking_rules,
+            traces: self.traces,
+            read_only: self.read_only,
+            protected: self.protected,
         }
     }
 
@@ -118,3 +361,42 @@ impl ClusterConfig {
     }
 }
 //
+
+#[test]
+fn test_schema_registry_options() {
+    let config: SchemaRegistryConfig = serde_json::from_str(
+        r#"{"url": "https://registry.acme.io", "authentication": {"basic": {"username": "api-key", "password": "api-secret"}}}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        config.options().unwrap().auth,
+        SchemaRegistryAuth::Basic {
+            username: "api-key".to_string(),
+            password: Some("api-secret".to_string())
+        }
+    );
+
+    let config: SchemaRegistryConfig = serde_json::from_str(
+        r#"{"url": "https://registry.acme.io", "authentication": {"bearer": "token"}, "tls": {"certificate_location": "client.pem"}}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        config.authentication,
+        Some(SchemaRegistryAuthentication::Bearer("token".to_string()))
+    );
+    assert!(config.options().is_err());
+
+    let config: SchemaRegistryConfig = serde_json::from_str(
+        r#"{"url": "https://apicurio.acme.io/apis/registry/v2", "kind": "apicurio", "apicurio": {"id_handler": "legacy"}}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        config.options().unwrap().kind,
+        SchemaRegistryKind::Apicurio({
+            let mut apicurio = ApicurioOptions::default();
+            apicurio.use_content_id = false;
+            apicurio.legacy_id = true;
+            apicurio
+        })
+    );
+}
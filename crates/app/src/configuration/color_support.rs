@@ -0,0 +1,97 @@
+//! Colors a terminal is able to display.
+//! Themes defined with RGB colors are degraded accordingly.
+//! The [`NO_COLOR`](https://no-color.org/) environment variable disables colors altogether.
+
+use std::env;
+
+use serde::{Deserialize, Serialize};
+
+/// Number of colors supported by the terminal.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+pub enum ColorSupport {
+    /// 24-bit RGB colors
+    #[serde(rename = "truecolor")]
+    TrueColor,
+    /// The 256 colors palette
+    #[serde(rename = "256")]
+    Ansi256,
+    /// The 16 ANSI colors
+    #[serde(rename = "16")]
+    Ansi16,
+    /// No colors at all, the output does not contain any ANSI color escape sequence
+    #[serde(rename = "none")]
+    Monochrome,
+}
+
+impl ColorSupport {
+    /// Detects the colors supported by the terminal with the `NO_COLOR`, `COLORTERM` and `TERM` environment variables.
+    pub fn detect() -> Self {
+        Self::from_env(
+            env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()),
+            env::var("COLORTERM").ok().as_deref(),
+            env::var("TERM").ok().as_deref(),
+            env::var_os("WT_SESSION").is_some(),
+        )
+    }
+
+    fn from_env(
+        no_color: bool,
+        colorterm: Option<&str>,
+        term: Option<&str>,
+        windows_terminal: bool,
+    ) -> Self {
+        if no_color || term == Some("dumb") {
+            return Self::Monochrome;
+        }
+        if matches!(colorterm, Some("truecolor" | "24bit")) || windows_terminal {
+            return Self::TrueColor;
+        }
+        match term {
+            Some(term) if term.ends_with("-direct") || term.contains("truecolor") => {
+                Self::TrueColor
+            }
+            Some(term) if term.contains("256") => Self::Ansi256,
+            // The Windows console does not define `TERM` and supports RGB colors since Windows 10
+            None if cfg!(target_os = "windows") => Self::TrueColor,
+            _ => Self::Ansi16,
+        }
+    }
+
+    /// Returns `true` when colors can be written to the terminal.
+    pub fn is_colored(&self) -> bool {
+        *self != Self::Monochrome
+    }
+}
+
+#[test]
+fn test_detect_color_support() {
+    assert_eq!(
+        ColorSupport::from_env(false, Some("truecolor"), Some("xterm-256color"), false),
+        ColorSupport::TrueColor
+    );
+    assert_eq!(
+        ColorSupport::from_env(false, None, Some("xterm-256color"), false),
+        ColorSupport::Ansi256
+    );
+    assert_eq!(
+        ColorSupport::from_env(false, None, Some("xterm-direct"), false),
+        ColorSupport::TrueColor
+    );
+    assert_eq!(
+        ColorSupport::from_env(false, None, Some("linux"), false),
+        ColorSupport::Ansi16
+    );
+    assert_eq!(
+        ColorSupport::from_env(false, None, Some("xterm"), true),
+        ColorSupport::TrueColor
+    );
+    assert_eq!(
+        ColorSupport::from_env(true, Some("truecolor"), Some("xterm-256color"), false),
+        ColorSupport::Monochrome
+    );
+    assert_eq!(
+        ColorSupport::from_env(false, None, Some("dumb"), false),
+        ColorSupport::Monochrome
+    );
+}
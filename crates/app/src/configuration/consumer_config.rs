@@ -1,11 +1,21 @@
+use lib::search::ScanLimit;
 use serde::{Deserialize, Serialize};
 
+use crate::APPLICATION_NAME;
+
 /// Configuration for the kafka consumer
 #[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
 #[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct ConsumerConfig {
     pub buffer_capacity: usize,
     pub timeout_in_ms: u64,
+    /// Values bigger than this number of bytes are truncated in the list of records, the full value is read again when the record is opened. 0 disables the truncation
+    #[serde(default = "default_value_preview_size")]
+    pub value_preview_size: usize,
+    /// Volume of records read after which a search stops, when the search query has no 'scan limit' clause: '10GB', '500MiB' or '1000000 records'
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, schemars(with = "Option<String>"))]
+    pub scan_limit: Option<ScanLimit>,
 }
 
 impl Default for ConsumerConfig {
@@ -13,6 +23,42 @@ impl Default for ConsumerConfig {
         Self {
             buffer_capacity: 1000,
             timeout_in_ms: 10,
+            value_preview_size: default_value_preview_size(),
+            scan_limit: None,
         }
     }
 }
+
+fn default_value_preview_size() -> usize {
+    64 * 1024
+}
+
+/// How the consumers of a cluster show up to the brokers
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Default)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+pub struct ConsumerGroupConfig {
+    #[serde(default)]
+    pub mode: CommitMode,
+    /// Group id of the consumers, `yozefu` by default. It replaces the `group.id` kafka property
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+}
+
+impl ConsumerGroupConfig {
+    /// Returns the group id of the consumers.
+    pub fn group_id(&self) -> String {
+        self.id.clone().unwrap_or(APPLICATION_NAME.to_string())
+    }
+}
+
+/// Whether the consumers of a search commit the offsets of the records they read
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum CommitMode {
+    /// Partitions are assigned to the consumers, no consumer group is joined and no offset is committed
+    #[default]
+    Assign,
+    /// Offsets of the records read are committed with the group id, lag monitoring tools see yozefu like any other consumer
+    Commit,
+}
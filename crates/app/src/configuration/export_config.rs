@@ -0,0 +1,303 @@
+//! Where kafka records are exported and when a new export file is started.
+//! The files are rotated by size or number of records, the number of records of a file is remembered
+//! so a file is only read again when someone else wrote it.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+};
+
+use lib::Error;
+use serde::{Deserialize, Serialize, de::IgnoredAny};
+
+/// What is known of an export file, valid as long as the file keeps this size.
+struct Counted {
+    size: u64,
+    records: usize,
+    /// Header of a CSV file, read along with its rows
+    header: Option<Vec<String>>,
+}
+
+/// Number of records of the export files, with the size of the file when they were counted.
+/// Files are only read again to count their records when someone else wrote them.
+static RECORD_COUNTS: Mutex<BTreeMap<PathBuf, Counted>> = Mutex::new(BTreeMap::new());
+
+/// Returns the number of records of the file of the given size, `count` reads them when the file has changed since it was counted.
+pub(crate) fn record_count(
+    file: &Path,
+    size: u64,
+    count: impl FnOnce() -> Result<usize, Error>,
+) -> Result<usize, Error> {
+    let cached = RECORD_COUNTS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(file)
+        .filter(|c| c.size == size)
+        .map(|c| c.records);
+    match cached {
+        Some(records) => Ok(records),
+        None => {
+            let records = count()?;
+            set_record_count(file, size, records);
+            Ok(records)
+        }
+    }
+}
+
+/// Records the number of records of the file of the given size, right after writing it.
+/// The header of a CSV file is kept, rows are appended after it.
+pub(crate) fn set_record_count(file: &Path, size: u64, records: usize) {
+    RECORD_COUNTS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .entry(file.to_path_buf())
+        .and_modify(|c| {
+            c.size = size;
+            c.records = records;
+        })
+        .or_insert(Counted {
+            size,
+            records,
+            header: None,
+        });
+}
+
+/// Returns the header and the number of rows of the CSV file of the given size, the file is read once.
+fn csv_header_and_count(file: &Path, size: u64) -> Result<(Vec<String>, usize), Error> {
+    let mut counts = RECORD_COUNTS.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(Counted {
+        records,
+        header: Some(header),
+        ..
+    }) = counts.get(file).filter(|c| c.size == size)
+    {
+        return Ok((header.clone(), *records));
+    }
+    let csv_error = |e: csv::Error| Error::Error(format!("Cannot read '{}': {e}", file.display()));
+    let mut reader = csv::Reader::from_path(file).map_err(csv_error)?;
+    let header = reader
+        .headers()
+        .map_err(csv_error)?
+        .iter()
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    let records = reader.records().count();
+    counts.insert(
+        file.to_path_buf(),
+        Counted {
+            size,
+            records,
+            header: Some(header.clone()),
+        },
+    );
+    Ok((header, records))
+}
+
+/// Configuration of the files kafka records are exported to
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+pub struct ExportConfig {
+    /// Template of the export file, relative to the export directory.
+    /// Supported placeholders are `{cluster}`, `{topic}` and `{date}`
+    #[serde(default = "default_file")]
+    pub file: String,
+    /// A new file is started when the current one is larger than this size, in bytes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_size: Option<u64>,
+    /// A new file is started when the current one contains this number of records
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_records: Option<usize>,
+    /// Fields written to the export files, referenced like in the search query: `offset`, `value.amount` or `headers.tenant`.
+    /// The `select` clause of the search query wins over them. The whole records are exported when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<String>,
+}
+
+fn default_file() -> String {
+    "export-{date}.json".to_string()
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            file: default_file(),
+            max_size: None,
+            max_records: None,
+            fields: vec![],
+        }
+    }
+}
+
+impl ExportConfig {
+    /// Returns the file the next record of the topic should be written to.
+    /// When the file is full, records are written to `<name>-1.json`, `<name>-2.json`...
+    pub fn rotate(&self, file: &Path) -> Result<PathBuf, Error> {
+        if self.max_size.is_none() && self.max_records.is_none() {
+            return Ok(file.to_path_buf());
+        }
+        let mut index = 0;
+        loop {
+            let candidate = Self::rotated(file, index);
+            if !self.is_full(&candidate)? {
+                return Ok(candidate);
+            }
+            index += 1;
+        }
+    }
+
+    /// Returns the CSV file the next row of the topic should be written to.
+    /// A new file is started when the current one is full or when its header is not `header`,
+    /// the selected fields have changed since it was written.
+    pub fn rotate_csv(&self, file: &Path, header: &[String]) -> Result<PathBuf, Error> {
+        let mut index = 0;
+        loop {
+            let candidate = Self::rotated(file, index);
+            if self.accepts_csv_rows(&candidate, header)? {
+                return Ok(candidate);
+            }
+            index += 1;
+        }
+    }
+
+    fn rotated(file: &Path, index: usize) -> PathBuf {
+        if index == 0 {
+            return file.to_path_buf();
+        }
+        let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+        let name = match file.extension() {
+            Some(extension) => format!("{stem}-{index}.{}", extension.to_string_lossy()),
+            None => format!("{stem}-{index}"),
+        };
+        file.with_file_name(name)
+    }
+
+    fn is_full(&self, file: &Path) -> Result<bool, Error> {
+        let Ok(metadata) = fs::metadata(file) else {
+            return Ok(false);
+        };
+        if self.max_size.is_some_and(|max| metadata.len() >= max) {
+            return Ok(true);
+        }
+        if let Some(max) = self.max_records {
+            let records = record_count(file, metadata.len(), || {
+                let records: Vec<IgnoredAny> = serde_json::from_reader(fs::File::open(file)?)?;
+                Ok(records.len())
+            })?;
+            return Ok(records >= max);
+        }
+        Ok(false)
+    }
+
+    fn accepts_csv_rows(&self, file: &Path, header: &[String]) -> Result<bool, Error> {
+        let Ok(metadata) = fs::metadata(file) else {
+            return Ok(true);
+        };
+        if metadata.len() == 0 {
+            return Ok(true);
+        }
+        if self.max_size.is_some_and(|max| metadata.len() >= max) {
+            return Ok(false);
+        }
+        let (file_header, records) = csv_header_and_count(file, metadata.len())?;
+        if file_header != header {
+            return Ok(false);
+        }
+        Ok(self.max_records.is_none_or(|max| records < max))
+    }
+}
+
+#[test]
+fn test_rotate_export_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("export.json");
+    let config = ExportConfig {
+        max_records: Some(2),
+        ..Default::default()
+    };
+
+    assert_eq!(config.rotate(&file).unwrap(), file);
+    fs::write(&file, "[1]").unwrap();
+    assert_eq!(config.rotate(&file).unwrap(), file);
+    fs::write(&file, "[1, 2]").unwrap();
+    assert_eq!(
+        config.rotate(&file).unwrap(),
+        dir.path().join("export-1.json")
+    );
+
+    let config = ExportConfig {
+        max_size: Some(4),
+        ..Default::default()
+    };
+    fs::write(dir.path().join("export-1.json"), "[1, 2]").unwrap();
+    assert_eq!(
+        config.rotate(&file).unwrap(),
+        dir.path().join("export-2.json")
+    );
+}
+
+#[test]
+fn test_rotate_csv_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("export.csv");
+    let header = vec!["offset".to_string(), "key".to_string()];
+    let config = ExportConfig {
+        max_records: Some(2),
+        ..Default::default()
+    };
+
+    assert_eq!(config.rotate_csv(&file, &header).unwrap(), file);
+    fs::write(
+        &file,
+        "offset,key
+1,a
+",
+    )
+    .unwrap();
+    assert_eq!(config.rotate_csv(&file, &header).unwrap(), file);
+    fs::write(
+        &file,
+        "offset,key
+1,a
+2,b
+",
+    )
+    .unwrap();
+    assert_eq!(
+        config.rotate_csv(&file, &header).unwrap(),
+        dir.path().join("export-1.csv")
+    );
+
+    let config = ExportConfig::default();
+    fs::write(
+        &file,
+        "offset,value
+1,a
+",
+    )
+    .unwrap();
+    assert_eq!(
+        config.rotate_csv(&file, &header).unwrap(),
+        dir.path().join("export-1.csv")
+    );
+}
+
+#[test]
+fn test_rotate_with_record_count() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("export.json");
+    let config = ExportConfig {
+        max_records: Some(2),
+        ..Default::default()
+    };
+
+    fs::write(&file, "[1, 2]").unwrap();
+    set_record_count(&file, 6, 1);
+    assert_eq!(config.rotate(&file).unwrap(), file);
+    fs::write(&file, "[1, 2, 3]").unwrap();
+    assert_eq!(
+        config.rotate(&file).unwrap(),
+        dir.path().join("export-1.json")
+    );
+}
@@ -1,7 +1,7 @@
 //! module defining the configuration structure of the application
 
 use std::{
-    fs,
+    env, fs,
     path::{Path, PathBuf},
 };
 
@@ -9,10 +9,16 @@ use indexmap::IndexMap;
 use itertools::Itertools;
 use lib::Error;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
 
 use crate::{
     APPLICATION_NAME,
-    configuration::{ClusterConfig, ConsumerConfig},
+    configuration::{
+        AlertRule, AnonymizationProfile, ClipboardBackend, ClusterConfig, ColorSupport,
+        ConsumerConfig, ExportConfig, HighlighterConfig, LayoutPreset, Notifier, RefreshRates,
+        Units,
+    },
 };
 
 use super::cluster_config::SchemaRegistryConfig;
@@ -38,8 +44,26 @@ pub struct GlobalConfig {
     /// The theme to use in the TUI
     #[serde(default = "default_theme")]
     pub theme: String,
+    /// Layout of the TUI: `default`, `compact`, `wide` or `zen`. The last used layout is saved
+    #[serde(default)]
+    pub layout: LayoutPreset,
     /// The theme to use for syntax highlighting
     pub highlighter_theme: Option<String>,
+    /// How the values are highlighted, read at startup
+    #[serde(default)]
+    pub highlighter: HighlighterConfig,
+    /// Colors supported by the terminal: `truecolor`, `256`, `16` or `none`. Detected from the environment when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color_support: Option<ColorSupport>,
+    /// Clipboard used to copy records: `system` or `osc52`. OSC 52 is used over SSH when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clipboard: Option<ClipboardBackend>,
+    /// Units of the sizes and durations displayed in the TUI
+    #[serde(default)]
+    pub units: Units,
+    /// How often the TUI draws the screen, when it is active and when it is idle
+    #[serde(default)]
+    pub refresh_rates: RefreshRates,
     /// The kafka properties for each cluster
     pub clusters: IndexMap<String, ClusterConfig>,
     #[serde(default)]
@@ -47,15 +71,39 @@ pub struct GlobalConfig {
     pub consumer: ConsumerConfig,
     /// The default kafka properties inherited for every cluster
     pub default_kafka_config: IndexMap<String, String>,
+    /// Template of the `client.id` of the kafka clients, so the request logs of the brokers attribute the traffic to yozefu. Placeholders: '{user}', '{host}', '{cluster}' and '{query}', a hash of the search query. A `client.id` set in the kafka properties takes precedence
+    #[serde(default = "default_client_id")]
+    pub client_id: String,
     /// History of past search queries
     pub history: Vec<String>,
     /// Show shortcuts
     #[serde(default = "default_show_shortcuts")]
     pub show_shortcuts: bool,
+    /// Screen-reader friendly mode: no spinners, textual labels instead of symbols and a status line announcing the selected item
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub accessible: bool,
     #[serde(default = "default_export_directory")]
     pub export_directory: PathBuf,
+    /// Name and rotation of the export files
+    #[serde(default)]
+    pub export: ExportConfig,
     /// The file to write logs to
     pub log_file: Option<PathBuf>,
+    /// Named anonymization profiles that can be applied to exported records
+    #[serde(default)]
+    pub anonymization_profiles: IndexMap<String, AnonymizationProfile>,
+    /// Rules of the watch mode, evaluated against every record read by the consumer
+    #[serde(default)]
+    pub alerts: Vec<AlertRule>,
+    /// Named HTTP endpoints the alerts can be sent to
+    #[serde(default)]
+    pub notifiers: IndexMap<String, Notifier>,
+    /// Named sequences of key strokes run from the command palette, such as `["ctrl+e", "enter"]`
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub macros: IndexMap<String, Vec<String>>,
+    /// Named search queries run from the command palette, their `:name` placeholders are prompted when they are run
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub queries: IndexMap<String, String>,
 }
 
 fn default_url_template() -> String {
@@ -78,6 +126,10 @@ fn default_show_shortcuts() -> bool {
     true
 }
 
+fn default_client_id() -> String {
+    format!("{APPLICATION_NAME}-{{query}}")
+}
+
 impl GlobalConfig {
     pub fn new(path: &Path) -> Self {
         Self {
@@ -90,15 +142,39 @@ impl GlobalConfig {
             initial_query: "from end - 10".to_string(),
             clusters: IndexMap::default(),
             default_kafka_config: IndexMap::default(),
+            client_id: default_client_id(),
             theme: default_theme(),
             highlighter_theme: None,
+            highlighter: HighlighterConfig::default(),
+            layout: LayoutPreset::default(),
+            color_support: None,
+            clipboard: None,
+            units: Units::default(),
+            refresh_rates: RefreshRates::default(),
             show_shortcuts: true,
+            accessible: false,
             export_directory: default_export_directory(),
+            export: ExportConfig::default(),
             consumer: ConsumerConfig::default(),
             log_file: None,
+            anonymization_profiles: IndexMap::default(),
+            alerts: vec![],
+            notifiers: IndexMap::default(),
+            macros: IndexMap::default(),
+            queries: IndexMap::default(),
         }
     }
 
+    /// Returns the colors supported by the terminal, detected from the environment if not configured.
+    pub fn color_support(&self) -> ColorSupport {
+        self.color_support.unwrap_or_else(ColorSupport::detect)
+    }
+
+    /// Returns the clipboard backend, detected from the environment if not configured.
+    pub fn clipboard(&self) -> ClipboardBackend {
+        self.clipboard.unwrap_or_else(ClipboardBackend::detect)
+    }
+
     /// Reads a configuration file.
     pub fn read(file: &Path) -> Result<Self, Error> {
         let content = fs::read_to_string(file);
@@ -122,6 +198,68 @@ impl GlobalConfig {
         Ok(config)
     }
 
+    /// Overrides a property of the configuration.
+    /// The property is a JavaScript Object Notation Pointer (RFC 6901), the leading '/' is optional.
+    /// The value is parsed as JSON. If it fails, the value is considered as a string.
+    pub fn with_override(self, property: &str, value: &str) -> Result<Self, Error> {
+        let pointer = match property.starts_with('/') {
+            true => property.to_string(),
+            false => format!("/{property}"),
+        };
+        let path = self.path.clone();
+        let mut config = serde_json::to_value(&self)?;
+
+        let mut current = &mut config;
+        for segment in pointer.split('/').skip(1) {
+            let segment = segment.replace("~1", "/").replace("~0", "~");
+            current = match current {
+                Value::Object(map) => map
+                    .entry(segment)
+                    .or_insert(Value::Object(Default::default())),
+                Value::Array(array) => {
+                    match segment.parse::<usize>().ok().and_then(|i| array.get_mut(i)) {
+                        Some(value) => value,
+                        None => {
+                            return Err(Error::Error(format!(
+                                "Cannot override '{property}': '{segment}' is not a valid index"
+                            )));
+                        }
+                    }
+                }
+                _ => {
+                    return Err(Error::Error(format!(
+                        "Cannot override '{property}': '{segment}' is not an object"
+                    )));
+                }
+            };
+        }
+
+        let new_value = serde_json::from_str(value).unwrap_or(Value::String(value.to_string()));
+        let is_string = new_value.is_string();
+        *current = new_value;
+        let mut overridden = match serde_json::from_value::<Self>(config.clone()) {
+            Ok(c) => Ok(c),
+            // '10000' is parsed as a number but kafka properties are strings
+            Err(_) if !is_string => {
+                *config.pointer_mut(&pointer).unwrap() = Value::String(value.to_string());
+                serde_json::from_value::<Self>(config)
+            }
+            Err(e) => Err(e),
+        }
+        .map_err(|e| Error::Error(format!("Cannot override '{property}' with '{value}': {e}")))?;
+
+        if serde_json::to_value(&overridden)?
+            .pointer(&pointer)
+            .is_none()
+        {
+            return Err(Error::Error(format!(
+                "Cannot override '{property}': there is no such property in the configuration"
+            )));
+        }
+        overridden.path = path;
+        Ok(overridden)
+    }
+
     /// Returns the name of the logs file
     pub fn log_file(&self) -> Option<PathBuf> {
         self.log_file.clone()
@@ -136,13 +274,61 @@ impl GlobalConfig {
     }
 
     /// Consumer config of a given cluster
-    pub(crate) fn consumer_config_of(&self, cluster: &str) -> ConsumerConfig {
+    pub fn consumer_config_of(&self, cluster: &str) -> ConsumerConfig {
         self.clusters
             .get(cluster)
             .and_then(|e| e.consumer.clone())
             .unwrap_or(self.consumer.clone())
     }
 
+    /// Renders the `client.id` template for the given cluster.
+    /// `{query}` is a hash of the search query of the consumers reading records, `none` for the other clients.
+    /// The values of the placeholders only keep letters, digits, '.', '_' and '-'.
+    pub fn client_id(&self, cluster: &str, query: Option<&str>) -> String {
+        let sanitize = |value: &str| {
+            value
+                .chars()
+                .map(
+                    |c| match c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+                        true => c,
+                        false => '_',
+                    },
+                )
+                .collect::<String>()
+        };
+        let user = env::var("USER")
+            .or_else(|_| env::var("USERNAME"))
+            .unwrap_or_default();
+        let query = match query {
+            Some(query) => {
+                format!("{:x}", Sha256::digest(query.trim().as_bytes()))[..8].to_string()
+            }
+            None => "none".to_string(),
+        };
+        self.client_id
+            .replace("{user}", &sanitize(&user))
+            .replace(
+                "{host}",
+                &sanitize(&gethostname::gethostname().to_string_lossy()),
+            )
+            .replace("{cluster}", &sanitize(cluster))
+            .replace("{query}", &query)
+    }
+
+    /// Returns the anonymization profile with the given name.
+    pub fn anonymization_profile(&self, name: &str) -> Result<AnonymizationProfile, Error> {
+        self.anonymization_profiles
+            .get(name)
+            .cloned()
+            .ok_or_else(|| {
+                Error::Error(format!(
+                    "Unknown anonymization profile '{}'. Possible profiles: [{}].",
+                    name,
+                    self.anonymization_profiles.keys().join(", ")
+                ))
+            })
+    }
+
     /// Returns the schema registry configuration for the given cluster.
     pub fn schema_registry_config_of(&self, cluster: &str) -> Option<SchemaRegistryConfig> {
         self.clusters
@@ -151,6 +337,43 @@ impl GlobalConfig {
     }
 }
 
+#[test]
+fn test_with_override() {
+    let config = GlobalConfig::new(Path::new("config.json"))
+        .with_override("/theme", "dark")
+        .unwrap()
+        .with_override("consumer/buffer_capacity", "42")
+        .unwrap()
+        .with_override("/default_kafka_config/fetch.min.bytes", "10000")
+        .unwrap();
+    assert_eq!(config.theme, "dark");
+    assert_eq!(config.consumer.buffer_capacity, 42);
+    assert_eq!(
+        config.default_kafka_config.get("fetch.min.bytes"),
+        Some(&"10000".to_string())
+    );
+    assert_eq!(config.path, PathBuf::from("config.json"));
+
+    let config = GlobalConfig::new(Path::new("config.json"));
+    assert!(config.clone().with_override("/unknown", "value").is_err());
+    assert!(config.with_override("/show_shortcuts", "maybe").is_err());
+}
+
+#[test]
+fn test_client_id() {
+    let mut config = GlobalConfig::new(Path::new("config.json"));
+    config.client_id = "{cluster}-{query}".to_string();
+    assert_eq!(
+        config.client_id("prod eu", Some("from begin")),
+        format!(
+            "prod_eu-{}",
+            &format!("{:x}", Sha256::digest("from begin"))[..8]
+        )
+    );
+    config.client_id = "yozefu-{cluster}-{query}".to_string();
+    assert_eq!(config.client_id("prod", None), "yozefu-prod-none");
+}
+
 #[test]
 fn generate_json_schema_for_global_config() {
     use schemars::schema_for;
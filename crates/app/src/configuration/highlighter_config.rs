@@ -0,0 +1,58 @@
+//! How the values of the records are highlighted in the TUI.
+//! Syntaxes (`.sublime-syntax`) and themes (`.tmTheme`) of the `themes` directory of the workspace
+//! are loaded on top of the bundled ones, to highlight niche formats.
+
+use serde::{Deserialize, Serialize};
+
+/// Engine highlighting the values.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum HighlighterBackend {
+    /// Sublime Text syntaxes and TextMate themes, with syntect
+    #[default]
+    Syntect,
+    /// Values are not highlighted
+    None,
+}
+
+/// Syntaxes bundled with yozefu.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum BundledSyntaxes {
+    /// The syntaxes of Sublime Text: JSON, YAML, XML...
+    #[default]
+    Full,
+    /// Plain text only, the syntaxes of the workspace are the only ones available
+    Minimal,
+}
+
+/// Highlighter of the values of the records.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+pub struct HighlighterConfig {
+    /// `syntect` or `none`
+    #[serde(default)]
+    pub backend: HighlighterBackend,
+    /// Bundled syntaxes: `full` or `minimal`
+    #[serde(default)]
+    pub bundled_syntaxes: BundledSyntaxes,
+    /// Name or file extension of the syntax the values are highlighted with, such as `json` or `yaml`
+    #[serde(default = "default_syntax")]
+    pub syntax: String,
+}
+
+fn default_syntax() -> String {
+    "json".to_string()
+}
+
+impl Default for HighlighterConfig {
+    fn default() -> Self {
+        Self {
+            backend: HighlighterBackend::default(),
+            bundled_syntaxes: BundledSyntaxes::default(),
+            syntax: default_syntax(),
+        }
+    }
+}
@@ -1,11 +1,20 @@
 //! module defining the configuration of the yozefu application
 
-use std::{collections::HashMap, fs, path::PathBuf};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
 
 use chrono::Local;
+use indexmap::IndexMap;
 use lib::Error;
 
-use crate::configuration::{ConsumerConfig, SchemaRegistryConfig, Workspace};
+use crate::configuration::{
+    AnonymizationProfile, ClipboardBackend, ColorSupport, ConsumerConfig, ConsumerGroupConfig,
+    GlobalConfig, KeyDecoder, LayoutPreset, MaskingRule, SchemaRegistryConfig, TraceConfig,
+    ValueDecoder, Workspace,
+};
 
 use super::{Configuration, yozefu_config::YozefuConfig};
 
@@ -18,15 +27,7 @@ pub struct InternalConfig {
 
 impl Configuration for InternalConfig {
     fn kafka_config_map(&self) -> HashMap<String, String> {
-        let mut config_map: HashMap<String, String> = self
-            .workspace
-            .config()
-            .default_kafka_config
-            .clone()
-            .into_iter()
-            .collect();
-        config_map.extend(self.specific.kafka_config_map());
-        config_map
+        self.kafka_config_map_for(None)
     }
 }
 
@@ -38,13 +39,20 @@ impl InternalConfig {
         }
         .clone();
 
-        let output_file = directory.join(format!(
-            "export-{}.json",
-            // Windows does not support ':' in filenames
-            Local::now()
-                .to_rfc3339_opts(chrono::SecondsFormat::Secs, false)
-                .replace(':', "-"),
-        ));
+        let template = match &specific.export_file {
+            Some(file) => file.clone(),
+            None => directory.join(&workspace.config().export.file),
+        };
+        // Windows does not support ':' in filenames
+        let date = Local::now()
+            .to_rfc3339_opts(chrono::SecondsFormat::Secs, false)
+            .replace(':', "-");
+        let output_file = PathBuf::from(
+            template
+                .to_string_lossy()
+                .replace("{cluster}", specific.cluster())
+                .replace("{date}", &date),
+        );
 
         let output_file = std::path::absolute(output_file)
             .expect("Failed to get absolute path for the export file");
@@ -68,11 +76,105 @@ impl InternalConfig {
         self.specific.cluster()
     }
 
+    /// Returns `true` when the operations modifying the cluster are disabled.
+    pub fn read_only(&self) -> bool {
+        self.specific.read_only()
+    }
+
+    /// Returns the kafka properties of a client reading the records of the given search query.
+    /// The `client.id` is rendered from the template unless the user sets it in the kafka properties.
+    pub fn kafka_config_map_for(&self, query: Option<&str>) -> HashMap<String, String> {
+        let mut config_map: HashMap<String, String> = self
+            .workspace
+            .config()
+            .default_kafka_config
+            .clone()
+            .into_iter()
+            .collect();
+        config_map.extend(self.specific.kafka_config_map());
+        config_map
+            .entry("client.id".to_string())
+            .or_insert_with(|| self.client_id(query));
+        config_map
+    }
+
+    /// Returns the `client.id` of the kafka clients, see [`GlobalConfig::client_id`].
+    pub fn client_id(&self, query: Option<&str>) -> String {
+        self.workspace.config().client_id(self.cluster(), query)
+    }
+
+    /// Returns the consumer group settings of the cluster, see [`ConsumerGroupConfig`].
+    pub fn consumer_group(&self) -> ConsumerGroupConfig {
+        self.specific.consumer_group()
+    }
+
+    /// Returns an error when the cluster is read-only, see [`YozefuConfig::check_writable`].
+    pub fn check_writable(&self, operation: &str) -> Result<(), Error> {
+        self.specific.check_writable(operation)
+    }
+
+    /// Returns `true` when destructive operations require typing the name of the cluster.
+    pub fn protected(&self) -> bool {
+        self.specific.protected()
+    }
+
+    /// Returns an error when a destructive operation is not confirmed, see [`YozefuConfig::check_destructive`].
+    pub fn check_destructive(&self, operation: &str, confirmation: &str) -> Result<(), Error> {
+        self.specific.check_destructive(operation, confirmation)
+    }
+
+    /// Kafka properties of the producer: the properties of the cluster overridden by its producer configuration.
+    /// `transactional.id` is only set when the records are produced inside a transaction.
+    pub fn producer_config_map(&self, transactional: bool) -> HashMap<String, String> {
+        let mut config_map = self.kafka_config_map();
+        config_map.extend(
+            self.specific
+                .producer_config()
+                .kafka_properties(transactional),
+        );
+        config_map
+    }
+
     /// Consumer configuration for the given cluster.
     pub fn consumer_config(&self, cluster: &str) -> ConsumerConfig {
         self.workspace.config().consumer_config_of(cluster)
     }
 
+    /// Returns how the keys of the given topic are decoded.
+    pub fn key_decoder_of(&self, topic: &str) -> KeyDecoder {
+        self.specific
+            .config()
+            .key_decoders
+            .get(topic)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns the masking rules of the given topic, the rules of `*` apply to every topic.
+    pub fn masking_rules_of(&self, topic: &str) -> impl Iterator<Item = &MaskingRule> + use<'_> {
+        let rules = &self.specific.config().masking_rules;
+        [rules.get("*"), rules.get(topic)]
+            .into_iter()
+            .flatten()
+            .flatten()
+    }
+
+    /// Returns the trace of the given topic, the trace of `*` applies to the other topics.
+    pub fn trace_of(&self, topic: &str) -> Option<&TraceConfig> {
+        let traces = &self.specific.config().traces;
+        traces.get(topic).or_else(|| traces.get("*"))
+    }
+
+    /// Returns how the values of the given topic are decoded.
+    pub fn value_decoder_of(&self, topic: &str) -> ValueDecoder {
+        self.specific
+            .config()
+            .value_decoders
+            .get(topic)
+            .copied()
+            .unwrap_or_default()
+    }
+
     /// Returns the schema registry configuration for the given cluster.
     pub fn schema_registry_config_of(&self, cluster: &str) -> Option<SchemaRegistryConfig> {
         match &self.specific.schema_registry() {
@@ -81,11 +183,43 @@ impl InternalConfig {
         }
     }
 
+    /// Returns the schema registries of the topics starting with a given prefix.
+    pub fn schema_registries(&self) -> &IndexMap<String, SchemaRegistryConfig> {
+        &self.specific.config().schema_registries
+    }
+
+    /// Returns the anonymization profile applied to exported kafka records, if any.
+    pub fn anonymization_profile(&self) -> Result<Option<AnonymizationProfile>, Error> {
+        self.specific
+            .anonymization_profile
+            .as_ref()
+            .map(|name| self.workspace.config().anonymization_profile(name))
+            .transpose()
+    }
+
     /// Returns the output file path for exported kafka records.
+    /// It may contain the `{topic}` placeholder, see [`InternalConfig::export_file_of`].
     pub fn output_file(&self) -> &PathBuf {
         &self.output_file
     }
 
+    /// Returns the file the next kafka record of the given topic is exported to.
+    pub fn export_file_of(&self, topic: &str) -> Result<PathBuf, Error> {
+        let file = PathBuf::from(self.output_file.to_string_lossy().replace("{topic}", topic));
+        // SQLite databases are not rotated, CSV files are rotated once their header is known, see [`ExportConfig::rotate_csv`]
+        if crate::is_sqlite(&file) || crate::is_csv(&file) {
+            return Ok(file);
+        }
+        self.workspace.config().export.rotate(&file)
+    }
+
+    /// Returns the file where the schemas of the records exported to `export_file` are written, if enabled.
+    pub fn schemas_file_of(&self, export_file: &Path) -> Option<PathBuf> {
+        self.specific
+            .export_schemas
+            .then(|| export_file.with_extension("schemas.json"))
+    }
+
     pub fn workspace(&self) -> &Workspace {
         &self.workspace
     }
@@ -107,15 +241,133 @@ impl InternalConfig {
         &self.workspace.config.theme
     }
 
+    pub fn color_support(&self) -> ColorSupport {
+        self.workspace.config.color_support()
+    }
+
+    pub fn clipboard(&self) -> ClipboardBackend {
+        self.workspace.config.clipboard()
+    }
+
+    pub fn layout(&self) -> LayoutPreset {
+        self.workspace.config.layout
+    }
+
+    /// Changes the layout, it is saved with [`InternalConfig::save_config`].
+    pub fn set_layout(&mut self, layout: LayoutPreset) {
+        self.workspace.config.layout = layout;
+    }
+
+    /// Applies the settings that changed between 2 versions of the configuration file
+    /// and that can be changed while the TUI is running.
+    /// Returns the names of the reloaded settings.
+    pub fn reload(&mut self, previous: &GlobalConfig, config: &GlobalConfig) -> Vec<&'static str> {
+        let mut reloaded = vec![];
+        let cluster = self.specific.cluster().to_string();
+        let current = &mut self.workspace.config;
+        if previous.theme != config.theme {
+            current.theme.clone_from(&config.theme);
+            reloaded.push("theme");
+        }
+        if previous.highlighter_theme != config.highlighter_theme {
+            current
+                .highlighter_theme
+                .clone_from(&config.highlighter_theme);
+            reloaded.push("highlighter theme");
+        }
+        if previous.color_support != config.color_support {
+            current.color_support = config.color_support;
+            reloaded.push("color support");
+        }
+        if previous.consumer_config_of(&cluster) != config.consumer_config_of(&cluster) {
+            current.consumer = config.consumer.clone();
+            if let (Some(current), Some(new)) = (
+                current.clusters.get_mut(&cluster),
+                config.clusters.get(&cluster),
+            ) {
+                current.consumer.clone_from(&new.consumer);
+            }
+            reloaded.push("consumer");
+        }
+        reloaded
+    }
+
     pub fn save_config(&mut self) -> Result<(), Error> {
         let history = &self.workspace.config.history;
         if history.len() > 1000 {
             self.workspace.config.history = history.iter().skip(500).cloned().collect();
         }
+        // Only the history and the layout are saved, overridden properties must not end up in the configuration file
+        let config = match GlobalConfig::read(&self.workspace.config.path) {
+            Ok(mut config) => {
+                config.history.clone_from(&self.workspace.config.history);
+                config.layout = self.workspace.config.layout;
+                config
+            }
+            Err(_) => self.workspace.config.clone(),
+        };
         fs::write(
             &self.workspace.config.path,
-            serde_json::to_string_pretty(&self.workspace.config)?,
+            serde_json::to_string_pretty(&config)?,
         )?;
         Ok(())
     }
 }
+
+#[test]
+fn test_export_files() {
+    use crate::configuration::ClusterConfig;
+
+    let mut global_config = GlobalConfig::new(Path::new("/tmp/yozefu/config.json"));
+    global_config.export.file = "{cluster}/{topic}-{date}.json".to_string();
+    let workspace = Workspace::new(
+        Path::new("/tmp/yozefu"),
+        global_config,
+        PathBuf::from("/tmp/yozefu/logs"),
+    );
+    let yozefu_config = ClusterConfig::default().create("localhost");
+    let config = InternalConfig::new(yozefu_config.clone(), workspace.clone());
+    let export_file = config.export_file_of("payments").unwrap();
+    assert!(export_file.parent().unwrap().ends_with("localhost"));
+    assert!(
+        export_file
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("payments-")
+    );
+    assert_eq!(config.schemas_file_of(&export_file), None);
+
+    let config = InternalConfig::new(yozefu_config.with_schemas_export(), workspace);
+    let export_file = config.export_file_of("payments").unwrap();
+    assert_eq!(
+        config.schemas_file_of(&export_file),
+        Some(export_file.with_extension("schemas.json"))
+    );
+}
+
+#[test]
+fn test_client_id_set_by_the_user() {
+    use crate::configuration::ClusterConfig;
+
+    let mut global_config = GlobalConfig::new(Path::new("/tmp/yozefu/config.json"));
+    global_config.client_id = "yozefu-{cluster}-{query}".to_string();
+    let workspace = Workspace::new(
+        Path::new("/tmp/yozefu"),
+        global_config,
+        PathBuf::from("/tmp/yozefu/logs"),
+    );
+    let mut yozefu_config = ClusterConfig::default().create("localhost");
+    let config = InternalConfig::new(yozefu_config.clone(), workspace.clone());
+    assert_eq!(
+        config.kafka_config_map()["client.id"],
+        "yozefu-localhost-none"
+    );
+
+    yozefu_config.set_kafka_property("client.id", "billing-team");
+    let config = InternalConfig::new(yozefu_config, workspace);
+    assert_eq!(
+        config.kafka_config_map_for(Some("from begin"))["client.id"],
+        "billing-team"
+    );
+}
@@ -0,0 +1,89 @@
+//! Decoders of the keys of kafka records, configured per topic.
+
+use std::fmt::Write;
+
+use lib::DataType;
+use serde::{Deserialize, Serialize};
+
+/// How the bytes of the keys of a topic are decoded.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum KeyDecoder {
+    /// With the schema registry (Avro, JSON schema or protobuf) when the key starts with a schema id, JSON or string otherwise
+    #[default]
+    Auto,
+    /// UTF-8 string, even when it looks like JSON
+    String,
+    /// Big-endian 32-bit signed integer
+    Int,
+    /// Big-endian 64-bit signed integer
+    Long,
+    /// 16 bytes formatted as `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx`
+    Uuid,
+    /// Hexadecimal representation of the bytes
+    Hex,
+}
+
+impl KeyDecoder {
+    /// Decodes the key, `None` when the key should be decoded as usual or doesn't have the expected size.
+    pub fn decode(&self, key: &[u8]) -> Option<DataType> {
+        match self {
+            Self::Auto => None,
+            Self::String => Some(DataType::String(String::from_utf8_lossy(key).to_string())),
+            Self::Int => Some(DataType::Json(
+                i32::from_be_bytes(key.try_into().ok()?).into(),
+            )),
+            Self::Long => Some(DataType::Json(
+                i64::from_be_bytes(key.try_into().ok()?).into(),
+            )),
+            Self::Uuid => {
+                let bytes: [u8; 16] = key.try_into().ok()?;
+                let hex = hex(&bytes);
+                Some(DataType::String(format!(
+                    "{}-{}-{}-{}-{}",
+                    &hex[0..8],
+                    &hex[8..12],
+                    &hex[12..16],
+                    &hex[16..20],
+                    &hex[20..32]
+                )))
+            }
+            Self::Hex => Some(DataType::String(hex(key))),
+        }
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut acc, b| {
+        let _ = write!(acc, "{b:02x}");
+        acc
+    })
+}
+
+#[test]
+fn test_decode() {
+    assert_eq!(KeyDecoder::Auto.decode(b"key"), None);
+    assert_eq!(
+        KeyDecoder::Long.decode(&42_i64.to_be_bytes()),
+        Some(DataType::Json(42.into()))
+    );
+    assert_eq!(
+        KeyDecoder::Int.decode(&(-7_i32).to_be_bytes()),
+        Some(DataType::Json((-7).into()))
+    );
+    assert_eq!(KeyDecoder::Int.decode(b"toolong"), None);
+    assert_eq!(
+        KeyDecoder::Uuid.decode(&[
+            0x55, 0x0e, 0x84, 0x00, 0xe2, 0x9b, 0x41, 0xd4, 0xa7, 0x16, 0x44, 0x66, 0x55, 0x44,
+            0x00, 0x00
+        ]),
+        Some(DataType::String(
+            "550e8400-e29b-41d4-a716-446655440000".to_string()
+        ))
+    );
+    assert_eq!(
+        KeyDecoder::Hex.decode(&[0xca, 0xfe]),
+        Some(DataType::String("cafe".to_string()))
+    );
+}
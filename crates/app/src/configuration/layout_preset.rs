@@ -0,0 +1,51 @@
+//! Arrangements of the panels of the TUI.
+
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+/// Layout of the TUI, switched with `CTRL + L`.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum LayoutPreset {
+    /// Topics on the left, records on the right
+    #[default]
+    Default,
+    /// For small terminals: no topics panel and shorter columns
+    Compact,
+    /// The records list and the record details side by side
+    Wide,
+    /// The record details only
+    Zen,
+}
+
+impl LayoutPreset {
+    /// Returns the layout that comes after this one.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Default => Self::Compact,
+            Self::Compact => Self::Wide,
+            Self::Wide => Self::Zen,
+            Self::Zen => Self::Default,
+        }
+    }
+}
+
+impl Display for LayoutPreset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Default => write!(f, "default"),
+            Self::Compact => write!(f, "compact"),
+            Self::Wide => write!(f, "wide"),
+            Self::Zen => write!(f, "zen"),
+        }
+    }
+}
+
+#[test]
+fn test_next_layout() {
+    assert_eq!(LayoutPreset::Default.next(), LayoutPreset::Compact);
+    assert_eq!(LayoutPreset::Zen.next(), LayoutPreset::Default);
+    assert_eq!(LayoutPreset::Wide.to_string(), "wide");
+}
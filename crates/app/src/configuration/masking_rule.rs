@@ -0,0 +1,156 @@
+//! Masking rules redact personal data as soon as the records are read,
+//! so the list of records, the details view, the clipboard and the export files never show it.
+
+use itertools::Itertools;
+use lib::KafkaRecord;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use super::AnonymizationProfile;
+
+/// Redacts a field of the records, or the parts of the records matching a regular expression.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+pub struct MaskingRule {
+    /// Field to mask, referenced like in the search query: `key`, `value.card.number` or `headers.authorization`. The key, the value and the headers when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    /// Regular expression, only the matching parts of the field are masked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, schemars(with = "Option<String>"))]
+    pub pattern: Option<Pattern>,
+    /// Number of trailing characters of each masked value left visible, `4` shows `****1234` for a card number
+    #[serde(default)]
+    pub keep_last: usize,
+}
+
+/// A regular expression, compiled when the configuration is read.
+#[derive(Debug, Clone)]
+pub struct Pattern(Regex);
+
+impl Pattern {
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        Regex::new(pattern).map(Self)
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl Eq for Pattern {}
+
+impl Serialize for Pattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        Self::new(&pattern).map_err(serde::de::Error::custom)
+    }
+}
+
+impl MaskingRule {
+    /// Masks the record in place.
+    pub fn apply(&self, record: &mut KafkaRecord) {
+        let mask = |value: &Value| Some(self.mask(value));
+        match &self.field {
+            Some(field) => AnonymizationProfile::edit(record, field, mask),
+            None => {
+                AnonymizationProfile::edit(record, "key", mask);
+                AnonymizationProfile::edit(record, "value", mask);
+                for header in record.headers.keys().cloned().collect_vec() {
+                    AnonymizationProfile::edit(record, &format!("headers.{header}"), mask);
+                }
+            }
+        }
+    }
+
+    /// Masks a JSON value: the parts of its strings matching the pattern, or the whole value when there is no pattern.
+    fn mask(&self, value: &Value) -> Value {
+        match (&self.pattern, value) {
+            (_, Value::Null) => Value::Null,
+            (None, Value::String(s)) => Value::String(mask(s, self.keep_last)),
+            (None, value) => Value::String(mask(&value.to_string(), self.keep_last)),
+            (Some(pattern), Value::String(s)) => Value::String(
+                pattern
+                    .0
+                    .replace_all(s, |c: &Captures| mask(&c[0], self.keep_last))
+                    .to_string(),
+            ),
+            (Some(pattern), Value::Number(n)) if pattern.0.is_match(&n.to_string()) => {
+                Value::String(mask(&n.to_string(), self.keep_last))
+            }
+            (Some(_), Value::Array(values)) => {
+                Value::Array(values.iter().map(|v| self.mask(v)).collect())
+            }
+            (Some(_), Value::Object(map)) => Value::Object(
+                map.iter()
+                    .map(|(key, value)| (key.clone(), self.mask(value)))
+                    .collect(),
+            ),
+            (Some(_), value) => value.clone(),
+        }
+    }
+}
+
+/// Replaces the characters of the string with `*`, except the last `keep_last` ones.
+/// Strings not longer than `keep_last` are entirely masked.
+fn mask(s: &str, keep_last: usize) -> String {
+    let count = s.chars().count();
+    let masked = match keep_last < count {
+        true => count - keep_last,
+        false => count,
+    };
+    s.chars()
+        .enumerate()
+        .map(|(index, c)| if index < masked { '*' } else { c })
+        .collect()
+}
+
+#[test]
+fn test_masking_rule() {
+    use lib::DataType;
+    use serde_json::json;
+
+    let mut record = KafkaRecord::default();
+    record.key = DataType::String("jon@example.com".to_string());
+    record.value = DataType::Json(json!({
+        "card": {"number": "4970100000001234", "expiry": 1228},
+        "comment": "contact jon@example.com or jane@example.com"
+    }));
+    record
+        .headers
+        .insert("reply-to".to_string(), "jane@example.com".to_string());
+
+    let rules: Vec<MaskingRule> = serde_json::from_str(
+        r#"[
+            {"field": "value.card.number", "keep_last": 4},
+            {"field": "value.card.expiry"},
+            {"pattern": "[a-z]+@example\\.com"}
+        ]"#,
+    )
+    .unwrap();
+    for rule in &rules {
+        rule.apply(&mut record);
+    }
+
+    assert_eq!(record.key, DataType::String("***************".to_string()));
+    assert_eq!(
+        record.value,
+        DataType::Json(json!({
+            "card": {"number": "************1234", "expiry": "****"},
+            "comment": "contact *************** or ****************"
+        }))
+    );
+    assert_eq!(record.headers["reply-to"], "****************");
+    assert_eq!(mask("12", 4), "**");
+
+    assert!(serde_json::from_str::<MaskingRule>(r#"{"pattern": "[a-z"}"#).is_err());
+}
@@ -3,23 +3,64 @@ use std::collections::HashMap;
 use lib::Error;
 use rdkafka::{ClientConfig, config::FromClientConfig};
 
+mod alert_rule;
+mod anonymization_profile;
+mod clipboard_backend;
 mod cluster_config;
+mod color_support;
 mod consumer_config;
+mod export_config;
 mod global_config;
+mod highlighter_config;
 mod internal_config;
+mod key_decoder;
+mod layout_preset;
+mod masking_rule;
+mod notifier;
+mod producer_config;
+mod refresh_rates;
+mod secret;
+mod trace_config;
+mod units;
+mod value_decoder;
 mod workspace;
 mod yozefu_config;
 
+pub use alert_rule::AlertAction;
+pub use alert_rule::AlertRule;
+pub use anonymization_profile::AnonymizationProfile;
+pub use anonymization_profile::TimestampPrecision;
+pub use clipboard_backend::ClipboardBackend;
 pub use cluster_config::ClusterConfig;
 pub use cluster_config::KAFKA_PROPERTIES_WITH_LOCATIONS;
 pub use cluster_config::SENSITIVE_KAFKA_PROPERTIES;
+pub use cluster_config::SchemaRegistryAuthentication;
 pub use cluster_config::SchemaRegistryConfig;
-pub use consumer_config::ConsumerConfig;
+pub use cluster_config::SchemaRegistryTls;
+pub use cluster_config::SchemaRegistryType;
+pub use cluster_config::{ApicurioConfig, ApicurioId, ApicurioIdHandler};
+pub use color_support::ColorSupport;
+pub use consumer_config::{CommitMode, ConsumerConfig, ConsumerGroupConfig};
+pub use export_config::ExportConfig;
+pub(crate) use export_config::{record_count, set_record_count};
 pub use global_config::GlobalConfig;
+pub use highlighter_config::{BundledSyntaxes, HighlighterBackend, HighlighterConfig};
 pub use internal_config::InternalConfig;
+pub use key_decoder::KeyDecoder;
+pub use layout_preset::LayoutPreset;
+pub use masking_rule::{MaskingRule, Pattern};
+pub use notifier::DEFAULT_NOTIFIER_TEMPLATE;
+pub use notifier::Notifier;
+pub use producer_config::Acks;
+pub use producer_config::ProducerConfig;
+pub use refresh_rates::RefreshRates;
+pub use secret::Secret;
+pub use trace_config::TraceConfig;
 use tracing::debug;
 use tracing::enabled;
-pub use workspace::Workspace;
+pub use units::{SizeUnit, Units};
+pub use value_decoder::ValueDecoder;
+pub use workspace::{Workspace, read_themes};
 pub use yozefu_config::YozefuConfig;
 
 pub trait Configuration {
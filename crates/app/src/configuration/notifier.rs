@@ -0,0 +1,67 @@
+//! Named HTTP notifiers the alert rules of the watch mode send their alerts to.
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Default message of a notifier, see [`Notifier::template`].
+pub const DEFAULT_NOTIFIER_TEMPLATE: &str =
+    "Alert '{name}': {count} records matching `{query}` at {minute}. Latest record: {record}";
+
+/// An HTTP endpoint receiving the alerts.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Notifier {
+    /// The alert is sent as JSON with a POST request, the message is the `message` property
+    Webhook {
+        url: Url,
+        /// HTTP headers of the request
+        #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+        headers: IndexMap<String, String>,
+        /// Message of the notification
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        template: Option<String>,
+    },
+    /// The message is sent to a Slack incoming webhook, or any service accepting Slack payloads
+    Slack {
+        url: Url,
+        /// Message of the notification, it can use Slack markdown
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        template: Option<String>,
+    },
+}
+
+impl Notifier {
+    /// Returns the template of the message.
+    /// The placeholders `{name}`, `{query}`, `{count}`, `{threshold}`, `{minute}` and `{record}` are replaced by the details of the alert.
+    pub fn template(&self) -> &str {
+        match self {
+            Self::Webhook { template, .. } | Self::Slack { template, .. } => {
+                template.as_deref().unwrap_or(DEFAULT_NOTIFIER_TEMPLATE)
+            }
+        }
+    }
+
+    pub fn url(&self) -> &Url {
+        match self {
+            Self::Webhook { url, .. } | Self::Slack { url, .. } => url,
+        }
+    }
+}
+
+#[test]
+fn test_deserialize_notifier() {
+    let notifier: Notifier = serde_json::from_str(
+        r#"{"type": "slack", "url": "https://hooks.slack.com/services/T000/B000/XXXX"}"#,
+    )
+    .unwrap();
+    assert_eq!(notifier.template(), DEFAULT_NOTIFIER_TEMPLATE);
+
+    let notifier: Notifier = serde_json::from_str(
+        r#"{"type": "webhook", "url": "http://localhost:8080", "headers": {"authorization": "Bearer token"}, "template": "{count} errors"}"#,
+    )
+    .unwrap();
+    assert_eq!(notifier.template(), "{count} errors");
+    assert_eq!(notifier.url().as_str(), "http://localhost:8080/");
+}
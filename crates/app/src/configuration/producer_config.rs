@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+
+/// Number of acknowledgments the leader must receive before a record is considered produced.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+pub enum Acks {
+    /// All the in-sync replicas acknowledge the record
+    #[default]
+    #[serde(rename = "all")]
+    All,
+    /// Only the leader acknowledges the record
+    #[serde(rename = "1")]
+    Leader,
+    /// Records are not acknowledged
+    #[serde(rename = "0")]
+    NoAck,
+}
+
+impl Acks {
+    fn as_property(self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::Leader => "1",
+            Self::NoAck => "0",
+        }
+    }
+}
+
+/// Configuration of the kafka producer, used when producing records to the cluster.
+/// Settings that are not set leave the kafka properties of the cluster, or the defaults of librdkafka, as they are.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Default)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+pub struct ProducerConfig {
+    /// Acknowledgments: `all`, `1` or `0`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acks: Option<Acks>,
+    /// Records are written exactly once and in order, even when the producer retries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotence: Option<bool>,
+    /// Identifier of the transactional producer, required to produce records inside a transaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transactional_id: Option<String>,
+}
+
+impl ProducerConfig {
+    /// Kafka properties of the settings that are set, they override the kafka properties of the cluster.
+    /// A transactional producer is always idempotent, librdkafka enables idempotence along with `transactional.id`.
+    pub fn kafka_properties(&self, transactional: bool) -> Vec<(String, String)> {
+        let mut properties = vec![];
+        if let Some(acks) = self.acks {
+            properties.push(("acks".to_string(), acks.as_property().to_string()));
+        }
+        match (transactional, &self.transactional_id) {
+            (true, Some(id)) => properties.push(("transactional.id".to_string(), id.clone())),
+            _ => {
+                if let Some(idempotence) = self.idempotence {
+                    properties.push(("enable.idempotence".to_string(), idempotence.to_string()));
+                }
+            }
+        }
+        properties
+    }
+}
+
+#[test]
+fn test_kafka_properties() {
+    assert!(ProducerConfig::default().kafka_properties(false).is_empty());
+    let config: ProducerConfig = serde_json::from_str(
+        r#"{"acks": "1", "idempotence": false, "transactional_id": "yozefu-seeder"}"#,
+    )
+    .unwrap();
+    assert_eq!(config.acks, Some(Acks::Leader));
+    assert_eq!(
+        config.kafka_properties(false),
+        vec![
+            ("acks".to_string(), "1".to_string()),
+            ("enable.idempotence".to_string(), "false".to_string())
+        ]
+    );
+    assert_eq!(
+        config.kafka_properties(true),
+        vec![
+            ("acks".to_string(), "1".to_string()),
+            ("transactional.id".to_string(), "yozefu-seeder".to_string())
+        ]
+    );
+}
@@ -0,0 +1,99 @@
+//! How often the TUI draws the screen and ticks.
+//! When the user does not type anything and no record is read for a while, the TUI is idle:
+//! it draws and ticks less often to save the CPU and the battery.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Frame and tick rates of the TUI, when it is active and when it is idle.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+pub struct RefreshRates {
+    /// Number of frames drawn per second
+    #[serde(default = "default_frame_rate")]
+    pub frame_rate: u32,
+    /// Number of ticks per second, the ticks animate the spinners and hide the notifications
+    #[serde(default = "default_tick_rate")]
+    pub tick_rate: u32,
+    /// Number of frames drawn per second when the TUI is idle
+    #[serde(default = "default_idle_frame_rate")]
+    pub idle_frame_rate: u32,
+    /// Number of ticks per second when the TUI is idle
+    #[serde(default = "default_idle_tick_rate")]
+    pub idle_tick_rate: u32,
+    /// The TUI is idle after this delay without key stroke nor record read, in milliseconds
+    #[serde(default = "default_idle_after_ms")]
+    pub idle_after_ms: u64,
+}
+
+fn default_frame_rate() -> u32 {
+    30
+}
+
+fn default_tick_rate() -> u32 {
+    4
+}
+
+fn default_idle_frame_rate() -> u32 {
+    2
+}
+
+fn default_idle_tick_rate() -> u32 {
+    1
+}
+
+fn default_idle_after_ms() -> u64 {
+    5_000
+}
+
+impl Default for RefreshRates {
+    fn default() -> Self {
+        Self {
+            frame_rate: default_frame_rate(),
+            tick_rate: default_tick_rate(),
+            idle_frame_rate: default_idle_frame_rate(),
+            idle_tick_rate: default_idle_tick_rate(),
+            idle_after_ms: default_idle_after_ms(),
+        }
+    }
+}
+
+impl RefreshRates {
+    /// Time between 2 frames.
+    pub fn frame_delay(&self, idle: bool) -> Duration {
+        match idle {
+            true => Self::delay_of(self.idle_frame_rate),
+            false => Self::delay_of(self.frame_rate),
+        }
+    }
+
+    /// Time between 2 ticks.
+    pub fn tick_delay(&self, idle: bool) -> Duration {
+        match idle {
+            true => Self::delay_of(self.idle_tick_rate),
+            false => Self::delay_of(self.tick_rate),
+        }
+    }
+
+    pub fn idle_after(&self) -> Duration {
+        Duration::from_millis(self.idle_after_ms)
+    }
+
+    /// A rate of 0 is raised to 1 per second so the TUI keeps drawing.
+    fn delay_of(rate: u32) -> Duration {
+        Duration::from_secs(1) / rate.max(1)
+    }
+}
+
+#[test]
+fn test_refresh_rates() {
+    let rates = RefreshRates::default();
+    assert_eq!(rates.tick_delay(false), Duration::from_millis(250));
+    assert_eq!(rates.frame_delay(true), Duration::from_millis(500));
+    let rates = RefreshRates {
+        idle_frame_rate: 0,
+        ..rates
+    };
+    assert_eq!(rates.frame_delay(true), Duration::from_secs(1));
+}
@@ -0,0 +1,64 @@
+//! Sensitive properties can be resolved at startup
+//! instead of being stored in plaintext in the configuration file.
+
+use std::process::Command;
+
+use lib::Error;
+use serde::{Deserialize, Serialize};
+
+/// Source of a sensitive property.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Secret {
+    /// The secret is the standard output of a shell command, for instance `pass show kafka/prod`
+    Command(String),
+    /// The secret is stored in the OS keyring
+    Keyring { service: String, user: String },
+}
+
+impl Secret {
+    /// Returns the value of the secret.
+    pub fn resolve(&self) -> Result<String, Error> {
+        match self {
+            Secret::Command(command) => Self::run(command),
+            Secret::Keyring { service, user } => keyring::Entry::new(service, user)
+                .and_then(|entry| entry.get_password())
+                .map_err(|e| {
+                    Error::Error(format!(
+                        "Cannot read the secret of user '{user}' for service '{service}' from the keyring: {e}"
+                    ))
+                }),
+        }
+    }
+
+    fn run(command: &str) -> Result<String, Error> {
+        let output = match cfg!(target_os = "windows") {
+            true => Command::new("cmd").args(["/C", command]).output(),
+            false => Command::new("sh").args(["-c", command]).output(),
+        }
+        .map_err(|e| Error::Error(format!("Cannot execute secret command '{command}': {e}")))?;
+
+        if !output.status.success() {
+            return Err(Error::Error(format!(
+                "Secret command '{}' failed ({}): {}",
+                command,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .trim_end_matches(['\r', '\n'])
+            .to_string())
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_resolve_secret_from_command() {
+    let secret = Secret::Command("echo my-password".to_string());
+    assert_eq!(secret.resolve().unwrap(), "my-password");
+
+    let secret = Secret::Command("exit 3".to_string());
+    assert!(secret.resolve().is_err());
+}
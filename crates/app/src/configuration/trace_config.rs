@@ -0,0 +1,94 @@
+//! A trace follows a record across topics: the records of the related topics sharing its correlation id,
+//! such as the order, the payment and the shipment of a customer checkout.
+
+use itertools::Itertools;
+use lib::{
+    Error, KafkaRecord,
+    search::{string::quote, symbol::Symbol},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Where the correlation id of the records is, and the topics to look for it in.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+pub struct TraceConfig {
+    /// Field holding the correlation id, referenced like in the search query: `headers.correlation-id` or `value.order.id`
+    pub field: String,
+    /// Topics searched for records with the same correlation id, in addition to the topic of the record
+    #[serde(default)]
+    pub topics: Vec<String>,
+}
+
+impl TraceConfig {
+    /// Returns the topics to search and the search query listing the records sharing the correlation id of the record, oldest first.
+    pub fn search(&self, record: &KafkaRecord) -> Result<(Vec<String>, String), Error> {
+        let field = Symbol::parse_field(&self.field)
+            .map_err(|e| Error::Error(format!("Invalid trace field '{}': {e}", self.field)))?;
+        let id = match field.value_of(record) {
+            Value::String(id) if !id.is_empty() => quote(&id).ok_or(Error::Error(format!(
+                "The correlation id '{id}' contains both single and double quotes"
+            )))?,
+            Value::Number(id) => id.to_string(),
+            _ => {
+                return Err(Error::Error(format!(
+                    "Record {}-{}@{} has no correlation id in '{}'",
+                    record.topic,
+                    record.partition,
+                    record.offset,
+                    field.field()
+                )));
+            }
+        };
+        let topics = [record.topic.clone()]
+            .into_iter()
+            .chain(self.topics.iter().cloned())
+            .unique()
+            .collect();
+        let query = format!(
+            "from begin {} == {id} order by timestamp asc",
+            field.field()
+        );
+        Ok((topics, query))
+    }
+}
+
+#[test]
+fn test_trace_search() {
+    use lib::DataType;
+    use serde_json::json;
+
+    let mut record = KafkaRecord::default();
+    record.topic = "orders".to_string();
+    record.value = DataType::Json(json!({"order": {"id": 42}}));
+    record
+        .headers
+        .insert("correlation-id".to_string(), "checkout-7".to_string());
+    let trace = TraceConfig {
+        field: "headers.correlation-id".to_string(),
+        topics: vec!["payments".to_string(), "orders".to_string()],
+    };
+    assert_eq!(
+        trace.search(&record).unwrap(),
+        (
+            vec!["orders".to_string(), "payments".to_string()],
+            r#"from begin headers.correlation-id == "checkout-7" order by timestamp asc"#
+                .to_string()
+        )
+    );
+
+    let trace = TraceConfig {
+        field: "value.order.id".to_string(),
+        ..trace
+    };
+    assert_eq!(
+        trace.search(&record).unwrap().1,
+        "from begin value.order.id == 42 order by timestamp asc"
+    );
+
+    let trace = TraceConfig {
+        field: "value.customer.id".to_string(),
+        ..trace
+    };
+    assert!(trace.search(&record).is_err());
+}
@@ -0,0 +1,132 @@
+//! Units of the sizes and durations displayed by the TUI:
+//! sizes in powers of 1024 (`KiB`, `MiB`) or in powers of 1000 (`kB`, `MB`),
+//! durations with more or less units (`3 m` or `3 m 12 s`).
+
+use std::time::Duration;
+
+use bytesize::ByteSize;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+/// Units of the durations, the largest first.
+const DURATION_UNITS: [(&str, u128); 5] = [
+    ("d", 86_400_000),
+    ("h", 3_600_000),
+    ("m", 60_000),
+    ("s", 1_000),
+    ("ms", 1),
+];
+
+/// Prefixes of the sizes.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+pub enum SizeUnit {
+    /// Binary prefixes, powers of 1024: `KiB`, `MiB`, `GiB`...
+    #[default]
+    #[serde(rename = "iec")]
+    Iec,
+    /// Decimal prefixes, powers of 1000: `kB`, `MB`, `GB`...
+    #[serde(rename = "si")]
+    Si,
+}
+
+/// How sizes and durations are formatted.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+pub struct Units {
+    /// Prefixes of the sizes: `iec` (`KiB`) or `si` (`kB`)
+    #[serde(default)]
+    pub size: SizeUnit,
+    /// Maximum number of units of the durations, `2` formats 192 seconds as `3 m 12 s` and `1` as `3 m`
+    #[serde(default = "default_duration_precision")]
+    pub duration_precision: usize,
+}
+
+fn default_duration_precision() -> usize {
+    2
+}
+
+impl Default for Units {
+    fn default() -> Self {
+        Self {
+            size: SizeUnit::default(),
+            duration_precision: default_duration_precision(),
+        }
+    }
+}
+
+impl Units {
+    /// Formats a number of bytes: `2.0 KiB` or `2.0 kB`.
+    pub fn format_size(&self, bytes: u64) -> String {
+        let display = ByteSize(bytes).display();
+        match self.size {
+            SizeUnit::Iec => display.iec(),
+            SizeUnit::Si => display.si(),
+        }
+        .to_string()
+    }
+
+    /// Formats a duration with the largest units first, down to the millisecond: `850 ms`, `1 s 250 ms`, `2 h 5 m`.
+    /// Units equal to zero are omitted.
+    pub fn format_duration(&self, duration: Duration) -> String {
+        let millis = duration.as_millis();
+        let Some(largest) = DURATION_UNITS.iter().position(|(_, unit)| millis >= *unit) else {
+            return "0 ms".to_string();
+        };
+        DURATION_UNITS
+            .iter()
+            .skip(largest)
+            .take(self.duration_precision.max(1))
+            .filter_map(|(name, unit)| {
+                let value = millis / unit % Self::next_unit(*unit);
+                (value > 0).then(|| format!("{value} {name}"))
+            })
+            .join(" ")
+    }
+
+    /// Number of times a unit fits in the next larger unit.
+    fn next_unit(unit: u128) -> u128 {
+        DURATION_UNITS
+            .iter()
+            .rev()
+            .map(|(_, u)| *u)
+            .find(|u| *u > unit)
+            .map_or(u128::MAX, |u| u / unit)
+    }
+}
+
+#[test]
+fn test_format_size() {
+    let units = Units::default();
+    assert_eq!(units.format_size(2_048), "2.0 KiB");
+    let units = Units {
+        size: SizeUnit::Si,
+        ..units
+    };
+    assert_eq!(units.format_size(2_048), "2.0 kB");
+}
+
+#[test]
+fn test_format_duration() {
+    let units = Units::default();
+    let format = |millis| units.format_duration(Duration::from_millis(millis));
+    assert_eq!(format(0), "0 ms");
+    assert_eq!(format(850), "850 ms");
+    assert_eq!(format(1_250), "1 s 250 ms");
+    assert_eq!(format(192_000), "3 m 12 s");
+    assert_eq!(format(3_600_000), "1 h");
+    assert_eq!(format(7_500_000), "2 h 5 m");
+    let units = Units {
+        duration_precision: 1,
+        ..units
+    };
+    assert_eq!(units.format_duration(Duration::from_millis(192_000)), "3 m");
+    let units = Units {
+        duration_precision: 4,
+        ..units
+    };
+    assert_eq!(
+        units.format_duration(Duration::from_millis(90_061_001)),
+        "1 d 1 h 1 m 1 s"
+    );
+}
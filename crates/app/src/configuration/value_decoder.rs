@@ -0,0 +1,48 @@
+//! Decoders of the values of kafka records, configured per topic.
+
+use lib::{
+    DataType,
+    kafka::{Encoding, from_cbor, from_message_pack},
+};
+use serde::{Deserialize, Serialize};
+
+/// How the bytes of the values of a topic are decoded.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum ValueDecoder {
+    /// With the schema registry when the value starts with a schema id, otherwise the format is detected from the content
+    #[default]
+    Auto,
+    /// MessagePack, converted to JSON
+    MessagePack,
+    /// CBOR, converted to JSON
+    Cbor,
+}
+
+impl ValueDecoder {
+    /// Decodes the value and returns its encoding, `None` when the value should be decoded as usual or is not valid.
+    pub fn decode(&self, value: &[u8]) -> Option<(DataType, Encoding)> {
+        match self {
+            Self::Auto => None,
+            Self::MessagePack => {
+                from_message_pack(value).map(|v| (DataType::Json(v), Encoding::MessagePack))
+            }
+            Self::Cbor => from_cbor(value).map(|v| (DataType::Json(v), Encoding::Cbor)),
+        }
+    }
+}
+
+#[test]
+fn test_decode() {
+    assert_eq!(ValueDecoder::Auto.decode(b"{}"), None);
+    assert_eq!(
+        ValueDecoder::MessagePack.decode(&[0x2a]),
+        Some((DataType::Json(42.into()), Encoding::MessagePack))
+    );
+    assert_eq!(
+        ValueDecoder::Cbor.decode(&[0xa1, 0x61, b'a', 0x01]),
+        Some((DataType::Json(serde_json::json!({"a": 1})), Encoding::Cbor))
+    );
+    assert_eq!(ValueDecoder::Cbor.decode(&[0xff, 0xff]), None);
+}
@@ -1,22 +1,40 @@
 use std::{
-    collections::HashMap,
     fs,
     path::{Path, PathBuf},
 };
 
 use directories::ProjectDirs;
+use indexmap::IndexMap;
 use itertools::Itertools;
 use lib::Error;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 
 use crate::{APPLICATION_NAME, configuration::GlobalConfig};
 
+/// Reads all the themes of the themes file, in the order they are defined.
+/// The themes are generic since they are defined by the TUI.
+pub fn read_themes<T: DeserializeOwned>(file: &Path) -> Result<IndexMap<String, T>, Error> {
+    let content = fs::read_to_string(file)?;
+    serde_json::from_str(&content).map_err(|e| {
+        Error::Error(format!(
+            "Failed to parse the themes file '{}': {}",
+            file.display(),
+            e
+        ))
+    })
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 #[cfg_attr(test, derive(schemars::JsonSchema))]
 /// The workspace is the directory containing yozefu configuration, logs, themes, filters...
+/// Besides the default workspace, named workspaces isolate them per project
+/// in the `workspaces` directory of the default workspace.
 pub struct Workspace {
     /// Config directory of Yozefu, the `path` is a directory
     pub path: PathBuf,
+    /// Directory of the default workspace, containing the named workspaces
+    root: PathBuf,
     /// Specific config file of Yozefu
     pub(super) config: GlobalConfig,
     /// Specific log file of Yozefu
@@ -27,6 +45,7 @@ impl Default for Workspace {
     fn default() -> Self {
         Self {
             path: Self::yozefu_directory().unwrap(),
+            root: Self::yozefu_directory().unwrap(),
             config: GlobalConfig::new(
                 &Workspace::yozefu_directory()
                     .unwrap()
@@ -41,16 +60,96 @@ impl Workspace {
     pub const CONFIG_FILENAME: &str = "config.json";
     pub const LOGS_FILENAME: &str = "application.log";
     pub const THEMES_FILENAME: &str = "themes.json";
+    pub const THEMES_DIRECTORY: &str = "themes";
     pub const FILTERS_DIRECTORY: &str = "filters";
+    pub const AUDIT_FILENAME: &str = "audit.jsonl";
+    pub const NOTES_FILENAME: &str = "notes.json";
+    pub const WORKSPACES_DIRECTORY: &str = "workspaces";
+    pub const EXPORTS_DIRECTORY: &str = "exports";
+    pub const CHECKPOINTS_DIRECTORY: &str = "checkpoints";
+    /// Name of the workspace that is not stored in the `workspaces` directory
+    pub const DEFAULT_WORKSPACE: &str = "default";
 
     pub fn new(directory: &Path, config: GlobalConfig, log_file: PathBuf) -> Self {
         Self {
             path: directory.to_path_buf(),
+            root: directory.to_path_buf(),
             config,
             log_file,
         }
     }
 
+    /// Sets the directory of the default workspace, when this workspace is a named one.
+    pub fn with_root(mut self, root: &Path) -> Self {
+        self.root = root.to_path_buf();
+        self
+    }
+
+    /// Returns the directory of the workspace with the given name.
+    pub fn directory_of(root: &Path, name: &str) -> PathBuf {
+        match name == Self::DEFAULT_WORKSPACE {
+            true => root.to_path_buf(),
+            false => root.join(Self::WORKSPACES_DIRECTORY).join(name),
+        }
+    }
+
+    /// Checks the name of a workspace can be used as a directory name.
+    pub fn validate_name(name: &str) -> Result<(), Error> {
+        match !name.is_empty()
+            && name != ".."
+            && name
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        {
+            true => Ok(()),
+            false => Err(Error::Error(format!(
+                "'{name}' is not a valid workspace name, only letters, digits, '-', '_' and '.' are allowed"
+            ))),
+        }
+    }
+
+    /// Directory of the default workspace, containing the named workspaces.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Returns the name of this workspace.
+    pub fn name(&self) -> String {
+        match self.path == self.root {
+            true => Self::DEFAULT_WORKSPACE.to_string(),
+            false => self
+                .path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or(Self::DEFAULT_WORKSPACE.to_string()),
+        }
+    }
+
+    /// Returns the names of the available workspaces, the default one first.
+    pub fn names(&self) -> Vec<String> {
+        let mut names = fs::read_dir(self.root.join(Self::WORKSPACES_DIRECTORY))
+            .map(|entries| {
+                entries
+                    .filter_map(Result::ok)
+                    .filter(|entry| entry.path().join(Self::CONFIG_FILENAME).is_file())
+                    .map(|entry| entry.file_name().to_string_lossy().to_string())
+                    .filter(|name| name != Self::DEFAULT_WORKSPACE)
+                    .sorted()
+                    .collect_vec()
+            })
+            .unwrap_or_default();
+        names.insert(0, Self::DEFAULT_WORKSPACE.to_string());
+        names
+    }
+
+    /// Returns the workspace following this one in [`Workspace::names`].
+    pub fn next_name(&self) -> String {
+        let names = self.names();
+        let current = self.name();
+        let index = names.iter().position(|n| n == &current).unwrap_or(0);
+        names[(index + 1) % names.len()].clone()
+    }
+
     /// The default yozefu directory containing themes, filters, config...
     fn yozefu_directory() -> Result<PathBuf, Error> {
         ProjectDirs::from("io", "maif", APPLICATION_NAME)
@@ -79,21 +178,78 @@ impl Workspace {
         self.path.join(Self::THEMES_FILENAME)
     }
 
+    /// Returns the directory of the syntaxes and themes of the highlighter, see [`crate::configuration::HighlighterConfig`]
+    pub fn themes_dir(&self) -> PathBuf {
+        self.path.join(Self::THEMES_DIRECTORY)
+    }
+
     /// Returns the list of available theme names.
     pub fn themes(&self) -> Vec<String> {
-        let file = self.themes_file();
-        let content = fs::read_to_string(file).unwrap_or("{}".to_string());
-        let themes: HashMap<String, Value> = serde_json::from_str(&content).unwrap_or_default();
-        themes
-            .keys()
-            .map(std::string::ToString::to_string)
+        read_themes::<Value>(&self.themes_file())
+            .unwrap_or_default()
+            .into_keys()
             .collect_vec()
     }
 
+    /// Returns the append-only file recording the actions run against the clusters, see [`crate::AuditLog`]
+    pub fn audit_file(&self) -> PathBuf {
+        self.path.join(Self::AUDIT_FILENAME)
+    }
+
+    /// Returns the file containing the notes attached to records, see [`crate::Notes`]
+    pub fn notes_file(&self) -> PathBuf {
+        self.path.join(Self::NOTES_FILENAME)
+    }
+
     /// Returns the name of the directory containing wasm filters
     pub fn filters_dir(&self) -> PathBuf {
         let dir = self.path.join(Self::FILTERS_DIRECTORY);
         let _ = fs::create_dir_all(&dir);
         dir
     }
+
+    /// Returns the name of the directory containing the checkpoints of the searches, see [`crate::Checkpoint`]
+    pub fn checkpoints_dir(&self) -> PathBuf {
+        let dir = self.path.join(Self::CHECKPOINTS_DIRECTORY);
+        let _ = fs::create_dir_all(&dir);
+        dir
+    }
+}
+
+#[test]
+fn test_named_workspaces() {
+    let root = tempfile::tempdir().unwrap();
+    let root = root.path();
+    let workspace = Workspace::new(
+        root,
+        GlobalConfig::new(&root.join(Workspace::CONFIG_FILENAME)),
+        root.join(Workspace::LOGS_FILENAME),
+    );
+    assert_eq!(workspace.name(), "default");
+    assert_eq!(workspace.names(), vec!["default"]);
+    assert_eq!(workspace.next_name(), "default");
+
+    for name in ["client-b", "client-a"] {
+        let directory = Workspace::directory_of(root, name);
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join(Workspace::CONFIG_FILENAME), "{}").unwrap();
+    }
+    assert_eq!(workspace.names(), vec!["default", "client-a", "client-b"]);
+    assert_eq!(workspace.next_name(), "client-a");
+
+    let directory = Workspace::directory_of(root, "client-b");
+    let named = Workspace::new(
+        &directory,
+        GlobalConfig::new(&directory.join(Workspace::CONFIG_FILENAME)),
+        directory.join(Workspace::LOGS_FILENAME),
+    )
+    .with_root(root);
+    assert_eq!(named.name(), "client-b");
+    assert_eq!(named.next_name(), "default");
+    assert_eq!(Workspace::directory_of(root, "default"), root);
+
+    assert!(Workspace::validate_name("client-a").is_ok());
+    assert!(Workspace::validate_name("../etc").is_err());
+    assert!(Workspace::validate_name("..").is_err());
+    assert!(Workspace::validate_name("").is_err());
 }
@@ -1,9 +1,21 @@
 //! module defining the configuration of the yozefu application
 
 use super::{Configuration, SchemaRegistryConfig};
-use crate::{APPLICATION_NAME, configuration::ClusterConfig};
+use crate::{
+    APPLICATION_NAME,
+    configuration::{ClusterConfig, ConsumerGroupConfig, ProducerConfig},
+};
+use lib::Error;
 use std::{collections::HashMap, path::PathBuf};
 
+/// Kafka properties enforced in read-only mode, whatever the configuration says.
+/// A consumer subscribing to a topic that does not exist could create it.
+const READ_ONLY_KAFKA_PROPERTIES: [(&str, &str); 3] = [
+    ("enable.auto.commit", "false"),
+    ("enable.auto.offset.store", "false"),
+    ("allow.auto.create.topics", "false"),
+];
+
 /// composed of kafka properties and
 /// an optional user-specific configuration.
 #[derive(Debug, Clone)]
@@ -12,6 +24,11 @@ pub struct YozefuConfig {
     cluster_config: ClusterConfig,
     pub log_file: Option<PathBuf>,
     pub export_directory: Option<PathBuf>,
+    /// Template of the export file, overrides the one of the configuration file
+    pub export_file: Option<PathBuf>,
+    pub anonymization_profile: Option<String>,
+    /// Write the schemas of exported kafka records next to the export file
+    pub export_schemas: bool,
 }
 
 impl YozefuConfig {
@@ -21,6 +38,9 @@ impl YozefuConfig {
             cluster_config,
             log_file: None,
             export_directory: None,
+            export_file: None,
+            anonymization_profile: None,
+            export_schemas: false,
         }
     }
 
@@ -46,6 +66,9 @@ impl YozefuConfig {
             cluster_config: self.cluster_config,
             log_file: self.log_file,
             export_directory: Some(exported_directory),
+            export_file: self.export_file,
+            anonymization_profile: self.anonymization_profile,
+            export_schemas: self.export_schemas,
         }
     }
 
@@ -55,6 +78,87 @@ impl YozefuConfig {
             cluster_config: self.cluster_config,
             log_file: Some(logs_file),
             export_directory: self.export_directory,
+            export_file: self.export_file,
+            anonymization_profile: self.anonymization_profile,
+            export_schemas: self.export_schemas,
+        }
+    }
+
+    pub fn with_anonymization_profile(self, anonymization_profile: &str) -> Self {
+        Self {
+            cluster: self.cluster,
+            cluster_config: self.cluster_config,
+            log_file: self.log_file,
+            export_directory: self.export_directory,
+            export_file: self.export_file,
+            anonymization_profile: Some(anonymization_profile.to_string()),
+            export_schemas: self.export_schemas,
+        }
+    }
+
+    pub fn with_export_file(self, export_file: PathBuf) -> Self {
+        Self {
+            export_file: Some(export_file),
+            ..self
+        }
+    }
+
+    pub fn with_schemas_export(self) -> Self {
+        Self {
+            export_schemas: true,
+            ..self
+        }
+    }
+
+    /// Disables the operations modifying the cluster, see [`YozefuConfig::check_writable`].
+    pub fn with_read_only(mut self) -> Self {
+        self.cluster_config.read_only = true;
+        self
+    }
+
+    /// Consumer group settings of the cluster, the partitions are only assigned when it is not set.
+    pub fn consumer_group(&self) -> ConsumerGroupConfig {
+        self.cluster_config
+            .consumer_group
+            .clone()
+            .unwrap_or_default()
+    }
+
+    /// Producer configuration of the cluster, the default one when it is not set.
+    pub fn producer_config(&self) -> ProducerConfig {
+        self.cluster_config.producer.clone().unwrap_or_default()
+    }
+
+    pub fn read_only(&self) -> bool {
+        self.cluster_config.read_only
+    }
+
+    /// Returns an error when the cluster is read-only.
+    /// Every operation modifying the cluster must call it first.
+    pub fn check_writable(&self, operation: &str) -> Result<(), Error> {
+        match self.read_only() {
+            true => Err(Error::Error(format!(
+                "Cannot {operation}: cluster '{}' is read-only",
+                self.cluster
+            ))),
+            false => Ok(()),
+        }
+    }
+
+    pub fn protected(&self) -> bool {
+        self.cluster_config.protected
+    }
+
+    /// Returns an error when a destructive operation is not allowed:
+    /// the cluster is read-only, or it is protected and the confirmation is not the name of the cluster.
+    pub fn check_destructive(&self, operation: &str, confirmation: &str) -> Result<(), Error> {
+        self.check_writable(operation)?;
+        match self.protected() && confirmation != self.cluster {
+            true => Err(Error::Error(format!(
+                "Cannot {operation}: cluster '{}' is protected, type its name to confirm",
+                self.cluster
+            ))),
+            false => Ok(()),
         }
     }
 
@@ -69,6 +173,9 @@ impl YozefuConfig {
             cluster_config: self.cluster_config.with_kafka_properties(kafka_properties),
             log_file: self.log_file,
             export_directory: self.export_directory,
+            export_file: self.export_file,
+            anonymization_profile: self.anonymization_profile,
+            export_schemas: self.export_schemas,
         }
     }
 }
@@ -77,6 +184,9 @@ impl Configuration for YozefuConfig {
     /// Returns the kafka properties
     fn kafka_config_map(&self) -> HashMap<String, String> {
         let mut config_map = self.cluster_config.kafka_config_map();
+        if let Some(id) = &self.consumer_group().id {
+            config_map.insert("group.id".into(), id.clone());
+        }
 
         // Default properties
         for (key, value) in [
@@ -87,6 +197,54 @@ impl Configuration for YozefuConfig {
                 config_map.insert(key.into(), value.into());
             }
         }
+        if self.read_only() {
+            for (key, value) in READ_ONLY_KAFKA_PROPERTIES {
+                config_map.insert(key.into(), value.into());
+            }
+        }
         config_map
     }
 }
+
+#[test]
+fn test_read_only() {
+    let mut config = ClusterConfig::default().create("production");
+    config.set_kafka_property("enable.auto.commit", "true");
+    assert!(config.check_writable("reset offsets").is_ok());
+    assert_eq!(config.kafka_config_map()["enable.auto.commit"], "true");
+
+    let config = config.with_read_only();
+    assert_eq!(
+        config
+            .check_writable("reset offsets")
+            .unwrap_err()
+            .to_string(),
+        "Cannot reset offsets: cluster 'production' is read-only"
+    );
+    assert_eq!(config.kafka_config_map()["enable.auto.commit"], "false");
+    assert_eq!(
+        config.kafka_config_map()["allow.auto.create.topics"],
+        "false"
+    );
+}
+
+#[test]
+fn test_consumer_group() {
+    let mut cluster_config = ClusterConfig::default();
+    cluster_config.set_kafka_property("group.id", "team-payments");
+    assert_eq!(
+        cluster_config
+            .clone()
+            .create("production")
+            .kafka_config_map()["group.id"],
+        "team-payments"
+    );
+
+    cluster_config.consumer_group = Some(ConsumerGroupConfig {
+        mode: super::CommitMode::Commit,
+        id: Some("yozefu-alice".to_string()),
+    });
+    let config = cluster_config.create("production");
+    assert_eq!(config.kafka_config_map()["group.id"], "yozefu-alice");
+    assert_eq!(config.consumer_group().group_id(), "yozefu-alice");
+}
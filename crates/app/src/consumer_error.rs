@@ -0,0 +1,100 @@
+//! Errors reported by librdkafka while consuming, like authentication failures or unknown topics.
+//! librdkafka keeps retrying in the background and only reports these errors to the context of the consumer,
+//! so they are turned into a [`ConsumerError`] the user can act on.
+
+use rdkafka::error::{KafkaError, RDKafkaErrorCode};
+
+/// An error of the kafka consumer, with the broker that reported it and suggestions to fix it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsumerError {
+    /// The broker the error comes from, when librdkafka mentions it
+    pub broker: Option<String>,
+    /// Name of the librdkafka error code, `SaslAuthenticationFailed` for instance
+    pub code: String,
+    pub message: String,
+    pub suggestions: Vec<&'static str>,
+}
+
+impl ConsumerError {
+    pub fn new(error: &KafkaError, reason: &str) -> Self {
+        let code = error.rdkafka_error_code();
+        let (broker, message) = match reason.split_once(": ") {
+            Some((broker, message)) if is_broker(broker) => (Some(broker.to_string()), message),
+            _ => (None, reason),
+        };
+        Self {
+            broker,
+            code: code.map_or_else(|| "Unknown".to_string(), |c| format!("{c:?}")),
+            message: message.to_string(),
+            suggestions: code.map(suggestions_for).unwrap_or_default(),
+        }
+    }
+}
+
+impl From<&KafkaError> for ConsumerError {
+    fn from(error: &KafkaError) -> Self {
+        Self::new(error, &error.to_string())
+    }
+}
+
+/// librdkafka prefixes its messages with the name of the broker, `sasl_ssl://kafka:9092/1` or `localhost:9092/bootstrap`.
+fn is_broker(name: &str) -> bool {
+    name.contains(':') && name.contains('/') && !name.contains(' ')
+}
+
+fn suggestions_for(code: RDKafkaErrorCode) -> Vec<&'static str> {
+    match code {
+        RDKafkaErrorCode::Authentication | RDKafkaErrorCode::SaslAuthenticationFailed => vec![
+            "Check the 'sasl.mechanism', 'sasl.username' and 'sasl.password' properties of the cluster",
+            "Check the 'security.protocol' property, 'SASL_SSL' or 'SASL_PLAINTEXT'",
+        ],
+        RDKafkaErrorCode::SSL => vec![
+            "Check the 'security.protocol' property, 'SSL' or 'SASL_SSL'",
+            "The certificate of the broker might not be trusted, set 'ssl.ca.location'",
+            "Check that the broker listens for TLS connections on this port",
+        ],
+        RDKafkaErrorCode::UnknownTopic | RDKafkaErrorCode::UnknownTopicOrPartition => vec![
+            "Check that the topic exists on this cluster and that its name is spelled correctly",
+            "You might not be allowed to describe this topic",
+        ],
+        RDKafkaErrorCode::TopicAuthorizationFailed
+        | RDKafkaErrorCode::GroupAuthorizationFailed
+        | RDKafkaErrorCode::ClusterAuthorizationFailed => vec![
+            "Check the ACLs of your user on the cluster",
+            "Check the 'group.id' property if the ACLs restrict consumer groups",
+        ],
+        RDKafkaErrorCode::AllBrokersDown
+        | RDKafkaErrorCode::BrokerTransportFailure
+        | RDKafkaErrorCode::Resolve => vec![
+            "Check the 'bootstrap.servers' property of the cluster",
+            "Check that the brokers are reachable from this machine, a VPN might be required",
+            "The brokers might advertise listeners that are not reachable from this machine",
+        ],
+        RDKafkaErrorCode::OperationTimedOut | RDKafkaErrorCode::RequestTimedOut => {
+            vec!["Check that the brokers are reachable from this machine, a VPN might be required"]
+        }
+        _ => vec![],
+    }
+}
+
+#[test]
+fn test_consumer_error() {
+    let error = ConsumerError::new(
+        &KafkaError::Global(RDKafkaErrorCode::Authentication),
+        "sasl_ssl://kafka:9092/1: SASL authentication error: Authentication failed",
+    );
+    assert_eq!(error.broker, Some("sasl_ssl://kafka:9092/1".to_string()));
+    assert_eq!(error.code, "Authentication");
+    assert_eq!(
+        error.message,
+        "SASL authentication error: Authentication failed"
+    );
+    assert!(!error.suggestions.is_empty());
+
+    let error = ConsumerError::new(
+        &KafkaError::Global(RDKafkaErrorCode::AllBrokersDown),
+        "1/1 brokers are down",
+    );
+    assert_eq!(error.broker, None);
+    assert_eq!(error.message, "1/1 brokers are down");
+}
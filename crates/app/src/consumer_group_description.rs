@@ -0,0 +1,247 @@
+//! Descriptions of consumer groups, as returned by the `DescribeConsumerGroups` admin API of librdkafka.
+//! Unlike the group list of the classic `DescribeGroups` API, they tell the rebalance protocol of the group
+//! and the `group.instance.id` of static members, and they include the groups using the consumer protocol of KIP-848.
+//! The committed offsets of the groups are listed with the `ListConsumerGroupOffsets` admin API, through the same admin client.
+//! `rdkafka` does not wrap these APIs yet, they are called through the bindings of `rdkafka-sys`.
+
+use std::{
+    collections::BTreeMap,
+    ffi::{CStr, CString, c_char},
+    ptr,
+    time::Duration,
+};
+
+use lib::{ConsumerGroupMember, ConsumerGroupState, ConsumerGroupType, Error, MemberAssignment};
+use rdkafka::{admin::AdminClient, bindings as rdsys, client::DefaultClientContext};
+use tracing::warn;
+
+/// A consumer group described by the group coordinator
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ConsumerGroupDescription {
+    pub name: String,
+    pub group_type: ConsumerGroupType,
+    pub state: ConsumerGroupState,
+    /// Partition assignor of the group, such as `range` or `uniform`
+    pub assignor: String,
+    pub members: Vec<ConsumerGroupMember>,
+}
+
+/// Describes the consumer groups, the groups that cannot be described are logged and skipped.
+pub(crate) fn describe_consumer_groups(
+    admin: &AdminClient<DefaultClientContext>,
+    groups: &[String],
+    timeout: Duration,
+) -> Result<Vec<ConsumerGroupDescription>, Error> {
+    if groups.is_empty() {
+        return Ok(vec![]);
+    }
+    let names = groups
+        .iter()
+        .map(|g| CString::new(g.as_str()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| Error::Error(format!("Invalid consumer group name: {e}")))?;
+    let mut pointers = names.iter().map(|n| n.as_ptr()).collect::<Vec<_>>();
+    let rk = admin.inner().native_ptr();
+    // SAFETY: the names outlive the call, librdkafka copies them.
+    // The event is destroyed once the descriptions are copied.
+    unsafe {
+        let queue = rdsys::rd_kafka_queue_new(rk);
+        rdsys::rd_kafka_DescribeConsumerGroups(
+            rk,
+            pointers.as_mut_ptr(),
+            pointers.len(),
+            ptr::null(),
+            queue,
+        );
+        let event = result_event(queue, timeout, "describing the consumer groups")?;
+        let descriptions = descriptions(event);
+        rdsys::rd_kafka_event_destroy(event);
+        Ok(descriptions)
+    }
+}
+
+/// Lists the offsets committed by the consumer group, as `(topic, partition, offset)`.
+/// Unlike [`rdkafka::consumer::Consumer::committed_offsets`], the partitions do not need to be known and no consumer is created for the group.
+pub(crate) fn committed_offsets(
+    admin: &AdminClient<DefaultClientContext>,
+    group: &str,
+    timeout: Duration,
+) -> Result<Vec<(String, i32, i64)>, Error> {
+    let name = CString::new(group)
+        .map_err(|e| Error::Error(format!("Invalid consumer group name: {e}")))?;
+    let rk = admin.inner().native_ptr();
+    // SAFETY: the request is copied by librdkafka, it is destroyed right after the call.
+    // The event is destroyed once the offsets are copied.
+    unsafe {
+        let mut request = rdsys::rd_kafka_ListConsumerGroupOffsets_new(name.as_ptr(), ptr::null());
+        let queue = rdsys::rd_kafka_queue_new(rk);
+        rdsys::rd_kafka_ListConsumerGroupOffsets(rk, &raw mut request, 1, ptr::null(), queue);
+        rdsys::rd_kafka_ListConsumerGroupOffsets_destroy(request);
+        let event = result_event(
+            queue,
+            timeout,
+            &format!("listing the committed offsets of group '{group}'"),
+        )?;
+        let offsets = offsets(event);
+        rdsys::rd_kafka_event_destroy(event);
+        offsets
+    }
+}
+
+/// Waits for the result event of an admin request and destroys the queue, `operation` tells what failed in the errors.
+unsafe fn result_event(
+    queue: *mut rdsys::rd_kafka_queue_t,
+    timeout: Duration,
+    operation: &str,
+) -> Result<*mut rdsys::rd_kafka_event_t, Error> {
+    unsafe {
+        let event = rdsys::rd_kafka_queue_poll(
+            queue,
+            i32::try_from(timeout.as_millis()).unwrap_or(i32::MAX),
+        );
+        rdsys::rd_kafka_queue_destroy(queue);
+        if event.is_null() {
+            return Err(Error::Error(format!("Timeout while {operation}")));
+        }
+        match rdsys::rd_kafka_event_error(event) {
+            rdsys::rd_kafka_resp_err_t::RD_KAFKA_RESP_ERR_NO_ERROR => Ok(event),
+            _ => {
+                let error = Error::Error(format!(
+                    "Error while {operation}: {}",
+                    string(rdsys::rd_kafka_event_error_string(event))
+                ));
+                rdsys::rd_kafka_event_destroy(event);
+                Err(error)
+            }
+        }
+    }
+}
+
+/// Copies the committed offsets of the result event, partitions without committed offset are skipped.
+unsafe fn offsets(event: *mut rdsys::rd_kafka_event_t) -> Result<Vec<(String, i32, i64)>, Error> {
+    let mut offsets = vec![];
+    unsafe {
+        let result = rdsys::rd_kafka_event_ListConsumerGroupOffsets_result(event);
+        let mut count = 0;
+        let groups = rdsys::rd_kafka_ListConsumerGroupOffsets_result_groups(result, &raw mut count);
+        for i in 0..count {
+            let group = *groups.add(i);
+            let error = rdsys::rd_kafka_group_result_error(group);
+            if !error.is_null() {
+                return Err(Error::Error(format!(
+                    "Cannot list the committed offsets of group '{}': {}",
+                    string(rdsys::rd_kafka_group_result_name(group)),
+                    string(rdsys::rd_kafka_error_string(error))
+                )));
+            }
+            let partitions = rdsys::rd_kafka_group_result_partitions(group);
+            if partitions.is_null() {
+                continue;
+            }
+            let partitions = &*partitions;
+            for p in 0..usize::try_from(partitions.cnt).unwrap_or_default() {
+                let partition = &*partitions.elems.add(p);
+                if partition.offset >= 0 {
+                    offsets.push((
+                        string(partition.topic),
+                        partition.partition,
+                        partition.offset,
+                    ));
+                }
+            }
+        }
+    }
+    Ok(offsets)
+}
+
+/// Copies the descriptions of the result event.
+unsafe fn descriptions(event: *mut rdsys::rd_kafka_event_t) -> Vec<ConsumerGroupDescription> {
+    let mut descriptions = vec![];
+    unsafe {
+        let result = rdsys::rd_kafka_event_DescribeConsumerGroups_result(event);
+        let mut count = 0;
+        let groups = rdsys::rd_kafka_DescribeConsumerGroups_result_groups(result, &raw mut count);
+        for i in 0..count {
+            let group = *groups.add(i);
+            let name = string(rdsys::rd_kafka_ConsumerGroupDescription_group_id(group));
+            let error = rdsys::rd_kafka_ConsumerGroupDescription_error(group);
+            if !error.is_null() {
+                warn!(
+                    "Cannot describe consumer group '{name}': {}",
+                    string(rdsys::rd_kafka_error_string(error))
+                );
+                continue;
+            }
+            let members = (0..rdsys::rd_kafka_ConsumerGroupDescription_member_count(group))
+                .map(|m| member(rdsys::rd_kafka_ConsumerGroupDescription_member(group, m)))
+                .collect();
+            descriptions.push(ConsumerGroupDescription {
+                name,
+                group_type: match rdsys::rd_kafka_ConsumerGroupDescription_type(group) {
+                    rdsys::rd_kafka_consumer_group_type_t::RD_KAFKA_CONSUMER_GROUP_TYPE_CLASSIC => {
+                        ConsumerGroupType::Classic
+                    }
+                    rdsys::rd_kafka_consumer_group_type_t::RD_KAFKA_CONSUMER_GROUP_TYPE_CONSUMER => {
+                        ConsumerGroupType::Consumer
+                    }
+                    _ => ConsumerGroupType::Unknown,
+                },
+                state: string(rdsys::rd_kafka_consumer_group_state_name(
+                    rdsys::rd_kafka_ConsumerGroupDescription_state(group),
+                ))
+                .parse()
+                .unwrap_or_default(),
+                assignor: string(rdsys::rd_kafka_ConsumerGroupDescription_partition_assignor(
+                    group,
+                )),
+                members,
+            });
+        }
+    }
+    descriptions
+}
+
+unsafe fn member(member: *const rdsys::rd_kafka_MemberDescription_t) -> ConsumerGroupMember {
+    unsafe {
+        let instance_id = rdsys::rd_kafka_MemberDescription_group_instance_id(member);
+        let mut assignments: BTreeMap<String, Vec<i32>> = BTreeMap::new();
+        let assignment = rdsys::rd_kafka_MemberDescription_assignment(member);
+        let partitions = match assignment.is_null() {
+            true => ptr::null(),
+            false => rdsys::rd_kafka_MemberAssignment_partitions(assignment),
+        };
+        if !partitions.is_null() {
+            let partitions = &*partitions;
+            for i in 0..usize::try_from(partitions.cnt).unwrap_or_default() {
+                let partition = &*partitions.elems.add(i);
+                assignments
+                    .entry(string(partition.topic))
+                    .or_default()
+                    .push(partition.partition);
+            }
+        }
+        let mut description = ConsumerGroupMember::default();
+        description.member = string(rdsys::rd_kafka_MemberDescription_consumer_id(member));
+        description.client_id = string(rdsys::rd_kafka_MemberDescription_client_id(member));
+        description.host = string(rdsys::rd_kafka_MemberDescription_host(member));
+        description.instance_id = (!instance_id.is_null()).then(|| string(instance_id));
+        description.assignments = assignments
+            .into_iter()
+            .map(|(topic, partitions)| {
+                let mut assignment = MemberAssignment::default();
+                assignment.topic = topic;
+                assignment.partitions = partitions;
+                assignment
+            })
+            .collect();
+        description
+    }
+}
+
+/// Copies a string owned by librdkafka, `NULL` is an empty string.
+unsafe fn string(s: *const c_char) -> String {
+    match s.is_null() {
+        true => String::new(),
+        false => unsafe { CStr::from_ptr(s) }.to_string_lossy().to_string(),
+    }
+}
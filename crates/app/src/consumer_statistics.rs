@@ -0,0 +1,57 @@
+//! Metrics of the kafka consumer, extracted from the statistics librdkafka emits every `statistics.interval.ms`.
+
+use rdkafka::statistics::Statistics;
+
+use crate::BrokerHealth;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ConsumerStatistics {
+    /// Wall clock time of the statistics, in seconds since the epoch
+    pub time: i64,
+    pub brokers: Vec<BrokerHealth>,
+    /// Average round-trip time to the brokers, in milliseconds
+    pub latency: u64,
+    /// Number of requests sent to the brokers since the consumer started
+    pub requests: u64,
+    /// Number of records fetched from the brokers and waiting to be consumed
+    pub fetch_queue: u64,
+    /// Number of records between the consumer position and the end of the partitions
+    pub lag: u64,
+}
+
+impl From<&Statistics> for ConsumerStatistics {
+    fn from(statistics: &Statistics) -> Self {
+        let brokers = BrokerHealth::from_statistics(statistics);
+        let latencies = statistics
+            .brokers
+            .values()
+            .filter_map(|b| b.rtt.as_ref())
+            .filter(|rtt| rtt.cnt > 0)
+            .map(|rtt| rtt.avg)
+            .collect::<Vec<_>>();
+        let latency = match latencies.len() {
+            0 => 0,
+            n => latencies.iter().sum::<i64>() / n as i64 / 1000,
+        };
+        // Partition -1 is the internal partition of librdkafka
+        let partitions = statistics
+            .topics
+            .values()
+            .flat_map(|t| t.partitions.values())
+            .filter(|p| p.partition >= 0);
+        let (fetch_queue, lag) = partitions.fold((0, 0), |(queue, lag), p| {
+            (
+                queue + p.fetchq_cnt.max(0) as u64,
+                lag + p.consumer_lag.max(0) as u64,
+            )
+        });
+        Self {
+            time: statistics.time,
+            brokers,
+            latency: latency.max(0) as u64,
+            requests: statistics.tx.max(0) as u64,
+            fetch_queue,
+            lag,
+        }
+    }
+}
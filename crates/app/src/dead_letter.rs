@@ -0,0 +1,212 @@
+//! Records of a dead letter queue (DLQ) are records a consumer failed to process.
+//! The producer of the DLQ describes the failure in the headers of the record:
+//! the topic, the partition and the offset of the original record and the exception that was thrown.
+//! Kafka Connect and Spring Kafka conventions are supported.
+
+use std::collections::BTreeMap;
+
+use itertools::Itertools;
+use lib::KafkaRecord;
+
+use crate::RecordToProduce;
+
+/// Headers set by Kafka Connect when `errors.deadletterqueue.context.headers.enable` is `true`
+const CONNECT: Convention = Convention {
+    prefix: "__connect.errors.",
+    topic: "__connect.errors.topic",
+    partition: "__connect.errors.partition",
+    offset: "__connect.errors.offset",
+    exception: "__connect.errors.exception.class.name",
+    message: "__connect.errors.exception.message",
+};
+
+/// Headers set by the `DeadLetterPublishingRecoverer` of Spring Kafka
+const SPRING: Convention = Convention {
+    prefix: "kafka_dlt-",
+    topic: "kafka_dlt-original-topic",
+    partition: "kafka_dlt-original-partition",
+    offset: "kafka_dlt-original-offset",
+    exception: "kafka_dlt-exception-fqcn",
+    message: "kafka_dlt-exception-message",
+};
+
+/// Names of the headers describing the failure.
+struct Convention {
+    /// Prefix of all the headers of the convention, they are removed when the record is republished
+    prefix: &'static str,
+    topic: &'static str,
+    partition: &'static str,
+    offset: &'static str,
+    exception: &'static str,
+    message: &'static str,
+}
+
+/// The failure of a record sent to a dead letter queue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeadLetter {
+    /// Topic of the original record, the record is republished to it
+    pub topic: String,
+    pub partition: Option<i32>,
+    pub offset: Option<i64>,
+    /// Fully qualified name of the exception, `java.lang.NullPointerException` for instance
+    pub exception: Option<String>,
+    pub message: Option<String>,
+    prefix: &'static str,
+}
+
+impl DeadLetter {
+    /// Reads the failure from the headers of the record, `None` when the record has no original topic.
+    pub fn of(record: &KafkaRecord) -> Option<Self> {
+        [CONNECT, SPRING].into_iter().find_map(|convention| {
+            let header = |name: &str| {
+                record
+                    .headers
+                    .get(name)
+                    .map(|v| v.trim().to_string())
+                    .filter(|v| !v.is_empty())
+            };
+            Some(Self {
+                topic: header(convention.topic)?,
+                partition: header(convention.partition)
+                    .and_then(|p| parse_number(&p))
+                    .and_then(|p| i32::try_from(p).ok()),
+                offset: header(convention.offset).and_then(|o| parse_number(&o)),
+                exception: header(convention.exception),
+                message: header(convention.message),
+                prefix: convention.prefix,
+            })
+        })
+    }
+
+    /// Short name of the exception, records are grouped by it.
+    pub fn error_type(&self) -> String {
+        match &self.exception {
+            Some(exception) => exception
+                .rsplit('.')
+                .next()
+                .unwrap_or(exception)
+                .to_string(),
+            None => "Unknown error".to_string(),
+        }
+    }
+
+    /// Where the original record was: `orders-2@1042`.
+    pub fn origin(&self) -> String {
+        let mut origin = self.topic.clone();
+        if let Some(partition) = self.partition {
+            origin.push_str(&format!("-{partition}"));
+        }
+        if let Some(offset) = self.offset {
+            origin.push_str(&format!("@{offset}"));
+        }
+        origin
+    }
+
+    /// The record to send back to the original topic: the same key and value, without the headers describing the failure.
+    /// The key is sent with the bytes it was read with, `raw_key`, so it lands in the same partition.
+    pub fn republished(&self, record: &KafkaRecord, raw_key: Option<Vec<u8>>) -> RecordToProduce {
+        RecordToProduce {
+            key: None,
+            raw_key,
+            value: record.value.clone().into(),
+            headers: record
+                .headers
+                .iter()
+                .filter(|(name, _)| !name.starts_with(self.prefix))
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect(),
+        }
+    }
+
+    /// Groups the records of a dead letter queue by error type, the most frequent errors first.
+    /// Records without a failure in their headers are ignored.
+    pub fn group<'a>(
+        records: impl Iterator<Item = &'a KafkaRecord>,
+    ) -> Vec<(String, Vec<(&'a KafkaRecord, DeadLetter)>)> {
+        let mut groups: BTreeMap<String, Vec<(&KafkaRecord, DeadLetter)>> = BTreeMap::new();
+        for record in records {
+            if let Some(dead_letter) = Self::of(record) {
+                groups
+                    .entry(dead_letter.error_type())
+                    .or_default()
+                    .push((record, dead_letter));
+            }
+        }
+        groups
+            .into_iter()
+            .sorted_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(&b.0)))
+            .collect()
+    }
+}
+
+/// Kafka Connect writes numbers as text, Spring Kafka as big-endian integers.
+/// Headers are decoded as UTF-8, so the bytes of small integers are still there.
+fn parse_number(value: &str) -> Option<i64> {
+    if let Ok(number) = value.parse::<i64>() {
+        return Some(number);
+    }
+    match value.as_bytes() {
+        bytes @ [_, _, _, _] => Some(i64::from(i32::from_be_bytes(bytes.try_into().ok()?))),
+        bytes @ [_, _, _, _, _, _, _, _] => Some(i64::from_be_bytes(bytes.try_into().ok()?)),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_dead_letter() {
+    use lib::DataType;
+    use serde_json::json;
+
+    let record = |headers: &[(&str, &str)]| {
+        let mut record = KafkaRecord::default();
+        record.key_as_string = "order-42".to_string();
+        record.value = DataType::Json(json!({"amount": 12}));
+        record.headers = headers
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        record
+    };
+    let connect = record(&[
+        ("__connect.errors.topic", "orders"),
+        ("__connect.errors.partition", "2"),
+        ("__connect.errors.offset", "1042"),
+        (
+            "__connect.errors.exception.class.name",
+            "org.apache.kafka.connect.errors.DataException",
+        ),
+        ("__connect.errors.exception.message", "Unknown field"),
+        ("tenant", "acme"),
+    ]);
+    let dead_letter = DeadLetter::of(&connect).unwrap();
+    assert_eq!(dead_letter.origin(), "orders-2@1042");
+    assert_eq!(dead_letter.error_type(), "DataException");
+    assert_eq!(
+        dead_letter.republished(&connect, Some(b"order-42".to_vec())),
+        RecordToProduce {
+            key: None,
+            raw_key: Some(b"order-42".to_vec()),
+            value: json!({"amount": 12}),
+            headers: vec![("tenant".to_string(), "acme".to_string())],
+        }
+    );
+
+    let spring = record(&[
+        ("kafka_dlt-original-topic", "payments"),
+        ("kafka_dlt-original-partition", "\0\0\0\u{3}"),
+        ("kafka_dlt-exception-fqcn", "java.lang.NullPointerException"),
+    ]);
+    let dead_letter = DeadLetter::of(&spring).unwrap();
+    assert_eq!(dead_letter.origin(), "payments-3");
+    assert_eq!(DeadLetter::of(&record(&[("tenant", "acme")])), None);
+
+    let records = [connect, spring.clone(), spring];
+    let groups = DeadLetter::group(records.iter());
+    assert_eq!(
+        groups
+            .iter()
+            .map(|(error, records)| (error.as_str(), records.len()))
+            .collect::<Vec<_>>(),
+        vec![("NullPointerException", 2), ("DataException", 1)]
+    );
+}
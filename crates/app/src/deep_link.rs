@@ -0,0 +1,201 @@
+//! Links reproducing a search on another machine, to share it in a chat message or an incident report:
+//! ```text
+//! yozefu://cluster/prod?topics=orders,payments&query=key+%3D%3D+%22ord-42%22&from=2024-11-23T12%3A00%3A00.000Z
+//! ```
+//! The link holds the cluster, the topics, the search query and optionally the time bounds of the records.
+
+use std::{fmt::Display, str::FromStr};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use itertools::Itertools;
+use lib::{Error, error::SearchError, search::append_predicate};
+use percent_encoding::percent_decode_str;
+use url::Url;
+
+/// Scheme of the links
+pub const DEEP_LINK_SCHEME: &str = "yozefu";
+
+/// A search shared as a `yozefu://` link.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeepLink {
+    pub cluster: String,
+    pub topics: Vec<String>,
+    pub query: String,
+    /// Records older than this date are not read
+    pub from: Option<DateTime<Utc>>,
+    /// Records more recent than this date are ignored
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl DeepLink {
+    pub fn new(cluster: &str, topics: &[String], query: &str) -> Self {
+        Self {
+            cluster: cluster.to_string(),
+            topics: topics.to_vec(),
+            query: query.trim().to_string(),
+            from: None,
+            to: None,
+        }
+    }
+
+    /// Pins the link to the records produced between the two dates.
+    pub fn with_time_bounds(
+        mut self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Self {
+        self.from = from;
+        self.to = to;
+        self
+    }
+
+    /// Returns the search query restricted to the time bounds of the link.
+    /// The consumer starts at the lower bound, whatever the `from` clause of the query.
+    pub fn search_query(&self) -> Result<String, SearchError> {
+        let predicate = match (self.from, self.to) {
+            (Some(from), Some(to)) => Some(format!(
+                r#"timestamp between "{}" and "{}""#,
+                rfc3339(from),
+                rfc3339(to)
+            )),
+            (Some(from), None) => Some(format!(r#"timestamp >= "{}""#, rfc3339(from))),
+            (None, Some(to)) => Some(format!(r#"timestamp <= "{}""#, rfc3339(to))),
+            (None, None) => None,
+        };
+        let query = match predicate {
+            Some(predicate) => append_predicate(&self.query, &predicate)?,
+            None => self.query.clone(),
+        };
+        Ok(match self.from {
+            Some(from) => format!(r#"{query} from "{}""#, rfc3339(from))
+                .trim()
+                .to_string(),
+            None => query,
+        })
+    }
+}
+
+fn rfc3339(date: DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+impl Display for DeepLink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut url = Url::parse(&format!("{DEEP_LINK_SCHEME}://cluster/")).unwrap();
+        url.path_segments_mut().unwrap().pop().push(&self.cluster);
+        {
+            let mut pairs = url.query_pairs_mut();
+            if !self.topics.is_empty() {
+                pairs.append_pair("topics", &self.topics.join(","));
+            }
+            pairs.append_pair("query", &self.query);
+            if let Some(from) = self.from {
+                pairs.append_pair("from", &rfc3339(from));
+            }
+            if let Some(to) = self.to {
+                pairs.append_pair("to", &rfc3339(to));
+            }
+        }
+        write!(f, "{url}")
+    }
+}
+
+impl FromStr for DeepLink {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| Error::Error(format!("Invalid link '{s}': {reason}"));
+        let url = Url::parse(s.trim()).map_err(|e| invalid(&e.to_string()))?;
+        if url.scheme() != DEEP_LINK_SCHEME {
+            return Err(invalid(&format!(
+                "the scheme should be '{DEEP_LINK_SCHEME}://'"
+            )));
+        }
+        if url.host_str() != Some("cluster") {
+            return Err(invalid(&format!(
+                "it should start with '{DEEP_LINK_SCHEME}://cluster/'"
+            )));
+        }
+        let cluster = url
+            .path_segments()
+            .and_then(|mut segments| segments.find(|s| !s.is_empty()))
+            .map(|s| percent_decode_str(s).decode_utf8_lossy().to_string())
+            .ok_or_else(|| invalid("the cluster is missing"))?;
+
+        let mut link = DeepLink::new(&cluster, &[], "");
+        for (name, value) in url.query_pairs() {
+            match name.as_ref() {
+                "topics" => {
+                    link.topics = value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|t| !t.is_empty())
+                        .map(str::to_string)
+                        .collect_vec();
+                }
+                "query" => link.query = value.trim().to_string(),
+                "from" | "to" => {
+                    let date = DateTime::parse_from_rfc3339(&value)
+                        .map_err(|e| invalid(&format!("'{name}' is not a RFC 3339 date: {e}")))?
+                        .with_timezone(&Utc);
+                    match name.as_ref() {
+                        "from" => link.from = Some(date),
+                        _ => link.to = Some(date),
+                    }
+                }
+                _ => (),
+            }
+        }
+        Ok(link)
+    }
+}
+
+#[test]
+fn test_deep_link_round_trip() {
+    let link = DeepLink::new(
+        "prod eu",
+        &["orders".to_string(), "payments".to_string()],
+        r#"from begin key == "ord-42" and value.total > 10"#,
+    )
+    .with_time_bounds(
+        DateTime::from_timestamp_millis(1_732_359_600_000),
+        DateTime::from_timestamp_millis(1_732_370_400_000),
+    );
+    let uri = link.to_string();
+    assert!(uri.starts_with("yozefu://cluster/prod%20eu?topics=orders%2Cpayments&query="));
+    assert_eq!(uri.parse::<DeepLink>().unwrap(), link);
+}
+
+#[test]
+fn test_deep_link_search_query() {
+    let link: DeepLink =
+        "yozefu://cluster/prod?query=from%20end%20key%20%3D%3D%20%22a%22&from=2024-11-23T11:00:00.000Z"
+            .parse()
+            .unwrap();
+    assert_eq!(link.cluster, "prod");
+    assert!(link.topics.is_empty());
+    assert_eq!(
+        link.search_query().unwrap(),
+        r#"from end key == "a" and timestamp >= "2024-11-23T11:00:00.000Z" from "2024-11-23T11:00:00.000Z""#
+    );
+    assert!("https://cluster/prod".parse::<DeepLink>().is_err());
+    assert!("yozefu://cluster/".parse::<DeepLink>().is_err());
+}
+
+#[test]
+fn test_deep_link_of_the_typed_query() {
+    use crate::search::ValidSearchQuery;
+    use lib::parse_search_query;
+
+    let input = r#"from begin (key == "ord-42" or key == "ord-43") and offset > 10"#;
+    let search_query =
+        ValidSearchQuery::from(input, std::path::Path::new("tests/filters")).unwrap();
+    let link = DeepLink::new("prod", &["orders".to_string()], search_query.input())
+        .with_time_bounds(DateTime::from_timestamp_millis(1_732_359_600_000), None);
+
+    let link = link.to_string().parse::<DeepLink>().unwrap();
+    assert_eq!(link.query, input);
+    let (_, query) = parse_search_query(&link.query).unwrap();
+    assert_eq!(&query, search_query.query());
+    assert!(parse_search_query(&link.search_query().unwrap()).is_ok());
+}
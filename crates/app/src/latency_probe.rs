@@ -0,0 +1,90 @@
+//! End-to-end latency probe: canary records are produced to a topic at a regular interval and consumed back,
+//! the time between the production and the consumption of each canary is a latency sample.
+//! It tells whether the cluster is healthy without a separate monitoring tool.
+
+use std::{collections::VecDeque, time::Duration};
+
+/// Key of the canary records
+pub const PROBE_KEY: &str = "yozefu-latency-probe";
+
+/// Header identifying the canary records of a probe, so concurrent probes ignore the canaries of each other
+pub const PROBE_HEADER: &str = "yozefu-probe";
+
+/// Time between the production and the consumption of a canary record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencySample {
+    /// Sequence number of the canary, starting from 0
+    pub sequence: u64,
+    /// When the canary was consumed, in milliseconds since the epoch
+    pub timestamp: i64,
+    pub latency: Duration,
+}
+
+/// The latest latency samples of a probe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatencyStatistics {
+    samples: VecDeque<LatencySample>,
+    capacity: usize,
+}
+
+impl LatencyStatistics {
+    /// Keeps the `capacity` latest samples.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, sample: LatencySample) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn samples(&self) -> impl Iterator<Item = &LatencySample> {
+        self.samples.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// The latency below which `percentile` percent of the samples are, using the nearest-rank method.
+    pub fn percentile(&self, percentile: f64) -> Option<Duration> {
+        let mut latencies: Vec<Duration> = self.samples.iter().map(|s| s.latency).collect();
+        latencies.sort();
+        let rank = (percentile / 100. * latencies.len() as f64).ceil() as usize;
+        latencies
+            .get(rank.clamp(1, latencies.len().max(1)) - 1)
+            .copied()
+    }
+
+    pub fn max(&self) -> Option<Duration> {
+        self.samples.iter().map(|s| s.latency).max()
+    }
+}
+
+#[test]
+fn test_latency_percentiles() {
+    let mut statistics = LatencyStatistics::new(100);
+    assert_eq!(statistics.percentile(50.), None);
+    for sequence in 0..120 {
+        statistics.push(LatencySample {
+            sequence,
+            timestamp: 0,
+            latency: Duration::from_millis(sequence),
+        });
+    }
+    assert_eq!(statistics.len(), 100);
+    assert_eq!(statistics.samples().next().unwrap().sequence, 20);
+    assert_eq!(statistics.percentile(50.), Some(Duration::from_millis(69)));
+    assert_eq!(statistics.percentile(99.), Some(Duration::from_millis(118)));
+    assert_eq!(statistics.percentile(0.), Some(Duration::from_millis(20)));
+    assert_eq!(statistics.max(), Some(Duration::from_millis(119)));
+}
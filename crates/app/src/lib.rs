@@ -2,11 +2,64 @@
 //! - List topics, and consume records,
 //! - Fetch information about a given topic,
 //! - Consume records.
+mod ansi;
 mod app;
+mod audit;
+mod broker_configs;
+mod broker_health;
+mod checkpoint;
+mod client_quotas;
 pub mod configuration;
+mod consumer_error;
+mod consumer_group_description;
+mod consumer_statistics;
+mod dead_letter;
+mod deep_link;
+mod latency_probe;
+mod notes;
+mod projection;
+mod query_plan;
+mod reassignment;
+mod record_count;
+mod record_template;
+mod records_exporter;
+mod records_producer;
+mod reporting_context;
+mod retention;
+mod schema_usage;
 pub mod search;
+mod shutdown;
+mod sqlite_exporter;
+mod watch;
 
+pub use ansi::strip_ansi;
 pub use app::App;
+pub use audit::{AuditAction, AuditEvent, AuditLog};
+pub use broker_configs::BrokerConfigEntry;
+pub use broker_health::{BrokerHealth, ConnectionState};
+pub use checkpoint::{Checkpoint, Checkpointer};
+pub use client_quotas::ClientQuota;
+pub use consumer_error::ConsumerError;
+pub use consumer_statistics::ConsumerStatistics;
+pub use dead_letter::DeadLetter;
+pub use deep_link::{DEEP_LINK_SCHEME, DeepLink};
+pub use latency_probe::{LatencySample, LatencyStatistics};
+pub use notes::{Note, Notes};
+pub use projection::{Projection, is_csv};
+pub use query_plan::{Filtering, PartitionScan, QueryPlan};
+pub use reassignment::{BrokerRack, PartitionAssignment, PartitionReplicas, ReassignmentPlan};
+pub use record_template::{RecordTemplate, is_template};
+pub use records_exporter::RecordsExporter;
+pub use records_producer::{
+    HeaderEdit, InputFormat, ProduceOptions, RecordMapping, RecordToProduce, ValueSchema,
+    read_records,
+};
+pub use reporting_context::ReportingContext;
+pub use retention::RetentionWarning;
+pub use schema_usage::{SchemaUsage, SchemaUsageReport};
+pub use shutdown::ShutdownSignals;
+pub use sqlite_exporter::{RECORDS_TABLE, is_sqlite};
+pub use watch::{Alert, Watch};
 /// Name of the application
 pub const APPLICATION_NAME: &str = "yozefu";
 
@@ -0,0 +1,120 @@
+//! Free-text notes attached to records during long investigations, so they can be handed over to someone else.
+//! Notes are stored in the `notes.json` file of the workspace, by cluster and by topic, partition and offset.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use lib::{Error, KafkaRecord};
+use serde::{Deserialize, Serialize};
+
+/// A note attached to a record.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Note {
+    pub text: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The notes of the records of a cluster.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Notes {
+    file: PathBuf,
+    cluster: String,
+    /// Notes by cluster, by `<topic>/<partition>/<offset>`
+    notes: BTreeMap<String, BTreeMap<String, Note>>,
+}
+
+impl Notes {
+    /// Reads the notes file, there is no note when it does not exist.
+    pub fn load(file: &Path, cluster: &str) -> Result<Self, Error> {
+        let notes = match file.exists() {
+            true => serde_json::from_str(&fs::read_to_string(file)?).map_err(|e| {
+                Error::Error(format!("Invalid notes file '{}': {e}", file.display()))
+            })?,
+            false => BTreeMap::default(),
+        };
+        Ok(Self {
+            file: file.to_path_buf(),
+            cluster: cluster.to_string(),
+            notes,
+        })
+    }
+
+    /// Topic, partition and offset are enough to identify a record of the cluster.
+    fn key(record: &KafkaRecord) -> String {
+        format!("{}/{}/{}", record.topic, record.partition, record.offset)
+    }
+
+    /// Returns the note attached to the record.
+    pub fn get(&self, record: &KafkaRecord) -> Option<&Note> {
+        self.notes
+            .get(&self.cluster)
+            .and_then(|notes| notes.get(&Self::key(record)))
+    }
+
+    /// Returns the text of the note attached to the record.
+    pub fn text_of(&self, record: &KafkaRecord) -> Option<String> {
+        self.get(record).map(|note| note.text.clone())
+    }
+
+    /// Attaches the text to the record and writes the notes file. A blank text removes the note.
+    pub fn set(&mut self, record: &KafkaRecord, text: &str) -> Result<(), Error> {
+        let notes = self.notes.entry(self.cluster.clone()).or_default();
+        match text.trim() {
+            "" => notes.remove(&Self::key(record)),
+            text => notes.insert(
+                Self::key(record),
+                Note {
+                    text: text.to_string(),
+                    updated_at: Utc::now(),
+                },
+            ),
+        };
+        if notes.is_empty() {
+            self.notes.remove(&self.cluster);
+        }
+        self.save()
+    }
+
+    /// The content goes to a temporary file first so a crash never leaves a truncated file.
+    fn save(&self) -> Result<(), Error> {
+        if let Some(parent) = self.file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let temporary = self.file.with_extension("json.tmp");
+        fs::write(&temporary, serde_json::to_string_pretty(&self.notes)?)?;
+        fs::rename(&temporary, &self.file)?;
+        Ok(())
+    }
+}
+
+#[test]
+fn test_notes() {
+    let directory = tempfile::tempdir().unwrap();
+    let file = directory.path().join("notes.json");
+    let mut record = KafkaRecord::default();
+    record.topic = "payments".to_string();
+    record.partition = 2;
+    record.offset = 42;
+
+    let mut notes = Notes::load(&file, "production").unwrap();
+    assert_eq!(notes.get(&record), None);
+    notes
+        .set(&record, "Refunded twice, see the incident of June 3rd ")
+        .unwrap();
+
+    let notes = Notes::load(&file, "production").unwrap();
+    assert_eq!(
+        notes.text_of(&record),
+        Some("Refunded twice, see the incident of June 3rd".to_string())
+    );
+    assert_eq!(Notes::load(&file, "staging").unwrap().get(&record), None);
+
+    let mut notes = notes;
+    notes.set(&record, "  ").unwrap();
+    assert_eq!(Notes::load(&file, "production").unwrap().get(&record), None);
+    assert_eq!(fs::read_to_string(&file).unwrap(), "{}");
+}
@@ -0,0 +1,137 @@
+//! Exports can be limited to the fields you care about: the fields of the `select` clause of the search query,
+//! or the `export.fields` of the configuration. Files are smaller and free of the personal data of the other fields.
+
+use std::path::Path;
+
+use lib::{Error, KafkaRecord, search::SearchQuery, search::symbol::Symbol};
+use serde_json::{Map, Value};
+
+use crate::configuration::ExportConfig;
+
+/// Columns of the CSV files when no field is selected.
+const CSV_FIELDS: [&str; 6] = ["topic", "partition", "offset", "timestamp", "key", "value"];
+
+/// Returns true when records are exported as CSV rows rather than JSON objects.
+pub fn is_csv(file: &Path) -> bool {
+    file.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("csv"))
+}
+
+/// Fields of the records written to the export files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Projection {
+    fields: Vec<Symbol>,
+}
+
+impl Projection {
+    /// The fields of the `select` clause of the search query, otherwise the fields of the export configuration.
+    /// `None` when both are empty, the whole records are exported.
+    pub fn of(search_query: &SearchQuery, config: &ExportConfig) -> Result<Option<Self>, Error> {
+        if !search_query.select.is_empty() {
+            return Ok(Some(Self {
+                fields: search_query.select.clone(),
+            }));
+        }
+        let fields = config
+            .fields
+            .iter()
+            .map(|field| {
+                Symbol::parse_field(field)
+                    .map_err(|e| Error::Error(format!("Invalid export field '{field}': {e}")))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((!fields.is_empty()).then_some(Self { fields }))
+    }
+
+    /// Like [`Projection::of`], with the main attributes of the records when no field is selected.
+    pub fn of_csv(search_query: &SearchQuery, config: &ExportConfig) -> Result<Self, Error> {
+        Ok(Self::of(search_query, config)?.unwrap_or_else(|| Self {
+            fields: CSV_FIELDS
+                .iter()
+                .map(|field| Symbol::parse_field(field).unwrap())
+                .collect(),
+        }))
+    }
+
+    /// The selected fields.
+    pub fn fields(&self) -> &[Symbol] {
+        &self.fields
+    }
+
+    /// The selected fields of the record, as a JSON object whose properties are named after the fields.
+    pub fn project(&self, record: &KafkaRecord) -> Value {
+        Value::Object(
+            self.fields
+                .iter()
+                .map(|field| (field.field(), field.value_of(record)))
+                .collect::<Map<_, _>>(),
+        )
+    }
+
+    /// The header of the CSV files.
+    pub fn header(&self) -> Vec<String> {
+        self.fields.iter().map(Symbol::field).collect()
+    }
+
+    /// The selected fields of the record as a CSV row, strings are written as they are and missing fields are empty.
+    pub fn row(&self, record: &KafkaRecord) -> Vec<String> {
+        self.fields
+            .iter()
+            .map(|field| match field.value_of(record) {
+                Value::Null => String::new(),
+                Value::String(s) => s,
+                value => value.to_string(),
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_projection() {
+    use lib::DataType;
+    use serde_json::json;
+
+    let mut record = KafkaRecord::default();
+    record.topic = "payments".to_string();
+    record.offset = 42;
+    record.value = DataType::Json(json!({"amount": 12.5, "card": {"number": "4970100000001234"}}));
+    record
+        .headers
+        .insert("tenant".to_string(), "acme".to_string());
+
+    let config = ExportConfig {
+        fields: vec!["offset".to_string(), "headers.tenant".to_string()],
+        ..Default::default()
+    };
+    let projection = Projection::of(&SearchQuery::default(), &config)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        projection.project(&record),
+        json!({"offset": 42, "headers.tenant": "acme"})
+    );
+
+    let (_, query) = lib::search::parse_search_query("from begin select value.amount").unwrap();
+    let projection = Projection::of(&query, &config).unwrap().unwrap();
+    assert_eq!(projection.header(), vec!["value.amount"]);
+    assert_eq!(projection.row(&record), vec!["12.5"]);
+
+    assert_eq!(
+        Projection::of(&SearchQuery::default(), &ExportConfig::default()).unwrap(),
+        None
+    );
+    let projection = Projection::of_csv(&SearchQuery::default(), &ExportConfig::default()).unwrap();
+    assert_eq!(
+        projection.row(&record),
+        vec![
+            "payments",
+            "0",
+            "42",
+            "",
+            "",
+            r#"{"amount":12.5,"card":{"number":"4970100000001234"}}"#
+        ]
+    );
+    assert!(is_csv(Path::new("exports/payments.CSV")));
+}
@@ -0,0 +1,230 @@
+//! Execution plan of a search query, previewed before running it:
+//! the partitions assigned to the consumer, where each of them is read from and how many records it holds up to its end.
+
+use std::fmt::Display;
+
+use lib::{
+    SearchQuery,
+    search::{
+        ScanLimit, compare::CompareExpression, filter::Filter, symbol::Symbol, visitor::Visitor,
+    },
+};
+use rdkafka::Offset;
+use thousands::Separable;
+
+/// What the expression of the query reads from each record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filtering {
+    /// Every record matches the query
+    None,
+    /// Only the topic, partition, offset, timestamp, size or headers, they are available before decoding the record
+    Metadata,
+    /// The decoded key or value, or a search filter receiving the whole record
+    Decoded,
+}
+
+impl Filtering {
+    pub fn of(query: &SearchQuery) -> Self {
+        let mut visitor = FilteringVisitor(Self::None);
+        visitor.visit_query(query);
+        visitor.0
+    }
+
+    fn read(&mut self, decoded: bool) {
+        *self = match (*self, decoded) {
+            (_, true) | (Self::Decoded, _) => Self::Decoded,
+            _ => Self::Metadata,
+        }
+    }
+}
+
+impl Display for Filtering {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "none, every record matches"),
+            Self::Metadata => write!(f, "pre-decode, on the metadata of the records"),
+            Self::Decoded => write!(f, "post-decode, on the decoded key and value"),
+        }
+    }
+}
+
+struct FilteringVisitor(Filtering);
+
+impl Visitor for FilteringVisitor {
+    fn visit_compare(&mut self, compare: &CompareExpression) {
+        self.0.read(
+            matches!(
+                compare,
+                CompareExpression::Key(..)
+                    | CompareExpression::KeyNumber(..)
+                    | CompareExpression::Value(..)
+                    | CompareExpression::In(Symbol::Key(_) | Symbol::Value(_), _)
+                    | CompareExpression::NotIn(Symbol::Key(_) | Symbol::Value(_), _)
+            ) || matches!(compare, CompareExpression::Operation(left, _, right)
+                if left.fields().into_iter().chain(right.fields())
+                    .any(|s| matches!(s, Symbol::Key(_) | Symbol::Value(_)))
+            ),
+        );
+    }
+
+    fn visit_filter(&mut self, _filter: &Filter) {
+        self.0.read(true);
+    }
+
+    fn visit_symbol(&mut self, symbol: &Symbol) {
+        self.0
+            .read(matches!(symbol, Symbol::Key(_) | Symbol::Value(_)));
+    }
+}
+
+/// A partition assigned to the consumer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartitionScan {
+    pub topic: String,
+    pub partition: i32,
+    /// Offset the partition is read from
+    pub start: i64,
+    /// High watermark of the partition
+    pub end: i64,
+}
+
+impl PartitionScan {
+    /// Resolves the offset assigned to the partition with its watermarks.
+    pub fn new(topic: &str, partition: i32, offset: Offset, (low, high): (i64, i64)) -> Self {
+        let start = match offset {
+            Offset::Beginning => low,
+            Offset::Offset(offset) => offset.clamp(low, high),
+            Offset::OffsetTail(count) => (high - count).max(low),
+            Offset::End | Offset::Stored | Offset::Invalid => high,
+        };
+        Self {
+            topic: topic.to_string(),
+            partition,
+            start,
+            end: high,
+        }
+    }
+
+    /// Number of records between the start offset and the end of the partition
+    pub fn records(&self) -> i64 {
+        self.end - self.start
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryPlan {
+    pub query: String,
+    /// Where the topics are read from, as written in the query
+    pub from: String,
+    pub partitions: Vec<PartitionScan>,
+    pub limit: Option<usize>,
+    /// Volume of records after which the consumer stops, from the query or the configuration
+    pub scan_limit: Option<ScanLimit>,
+    pub filtering: Filtering,
+    /// Group id the offsets of the records read are committed with, `None` when the partitions are only assigned
+    pub consumer_group: Option<String>,
+}
+
+impl QueryPlan {
+    /// Number of records read at most, the consumer stops earlier once `limit` records match or the scan limit is reached
+    pub fn records(&self) -> i64 {
+        self.partitions.iter().map(PartitionScan::records).sum()
+    }
+
+    /// Label and value of each step of the plan.
+    pub fn summary(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("Query", self.query.clone()),
+            ("From", self.from.clone()),
+            (
+                "Scan",
+                format!(
+                    "{} records over {} partitions",
+                    self.records().separate_with_underscores(),
+                    self.partitions.len()
+                ),
+            ),
+            ("Filtering", self.filtering.to_string()),
+            (
+                "Limit",
+                match self.limit {
+                    Some(limit) => format!("stops after {limit} matching records"),
+                    None => "none, the partitions are read up to their end".to_string(),
+                },
+            ),
+            (
+                "Scan limit",
+                match self.scan_limit {
+                    Some(scan_limit) => format!("stops after reading {scan_limit}"),
+                    None => "none".to_string(),
+                },
+            ),
+            (
+                "Offsets",
+                match &self.consumer_group {
+                    Some(group) => format!("committed with the consumer group '{group}'"),
+                    None => "not committed, no consumer group is joined".to_string(),
+                },
+            ),
+        ]
+    }
+
+    /// The plan as text, the summary followed by one line per partition.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = self
+            .summary()
+            .into_iter()
+            .map(|(label, value)| format!("{label:<12}{value}"))
+            .collect::<Vec<_>>();
+        lines.push(String::new());
+        lines.extend(self.partitions.iter().map(PartitionScan::to_string));
+        lines
+    }
+}
+
+impl Display for PartitionScan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}-{}  from offset {} to {}, {} records",
+            self.topic,
+            self.partition,
+            self.start,
+            self.end,
+            self.records().separate_with_underscores()
+        )
+    }
+}
+
+#[test]
+fn test_query_plan() {
+    use lib::parse_search_query;
+
+    let filtering = |query: &str| Filtering::of(&parse_search_query(query).unwrap().1);
+    assert_eq!(filtering("from begin"), Filtering::None);
+    assert_eq!(
+        filtering(r#"partition == 2 and headers.tenant == "acme""#),
+        Filtering::Metadata
+    );
+    assert_eq!(
+        filtering(r#"offset > 5 or value.status == "paid""#),
+        Filtering::Decoded
+    );
+
+    let plan = QueryPlan {
+        query: "from end - 10".to_string(),
+        from: "end - 10".to_string(),
+        partitions: vec![
+            PartitionScan::new("payments", 0, Offset::OffsetTail(10), (0, 1_000)),
+            PartitionScan::new("payments", 1, Offset::OffsetTail(10), (0, 4)),
+            PartitionScan::new("payments", 2, Offset::Beginning, (300, 2_300)),
+        ],
+        limit: None,
+        scan_limit: None,
+        filtering: Filtering::None,
+        consumer_group: None,
+    };
+    assert_eq!(plan.partitions[0].start, 990);
+    assert_eq!(plan.partitions[1].start, 0);
+    assert_eq!(plan.records(), 2_014);
+}
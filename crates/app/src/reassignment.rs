@@ -0,0 +1,147 @@
+//! Read-only planner of partition reassignments.
+//! It proposes replicas spreading the leaders and the replicas of the partitions evenly across the brokers and their racks.
+//! The plan is compatible with `kafka-reassign-partitions --reassignment-json-file`, it is never executed by the tool.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+/// A broker of the cluster and its rack, `None` when `broker.rack` is not set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrokerRack {
+    pub id: i32,
+    pub rack: Option<String>,
+}
+
+/// Current replicas of a partition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartitionAssignment {
+    pub topic: String,
+    pub partition: i32,
+    pub leader: i32,
+    /// The first replica is the preferred leader
+    pub replicas: Vec<i32>,
+    pub in_sync_replicas: Vec<i32>,
+}
+
+/// A reassignment, in the format of `kafka-reassign-partitions`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ReassignmentPlan {
+    pub version: u32,
+    pub partitions: Vec<PartitionReplicas>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PartitionReplicas {
+    pub topic: String,
+    pub partition: i32,
+    pub replicas: Vec<i32>,
+}
+
+impl ReassignmentPlan {
+    /// Proposes new replicas for the partitions, keeping their replication factor.
+    /// The preferred leader of each partition is the broker leading the fewest partitions,
+    /// the other replicas go to the brokers holding the fewest replicas, on racks the partition is not on yet.
+    /// Ties are broken in favor of the current replicas, so balanced partitions don't move.
+    /// Only the partitions whose replicas change are part of the plan.
+    pub fn balance(brokers: &[BrokerRack], assignments: &[PartitionAssignment]) -> Self {
+        let mut leaders: HashMap<i32, usize> = HashMap::new();
+        let mut replicas: HashMap<i32, usize> = HashMap::new();
+        let mut partitions = vec![];
+        for assignment in assignments {
+            let replication_factor = assignment.replicas.len().min(brokers.len());
+            let mut chosen: Vec<i32> = vec![];
+            let mut racks: HashSet<&str> = HashSet::new();
+            for position in 0..replication_factor {
+                let Some(broker) = brokers
+                    .iter()
+                    .filter(|b| !chosen.contains(&b.id))
+                    .min_by_key(|b| {
+                        (
+                            b.rack.as_deref().is_some_and(|r| racks.contains(r)),
+                            match position {
+                                0 => leaders.get(&b.id).copied().unwrap_or_default(),
+                                _ => 0,
+                            },
+                            replicas.get(&b.id).copied().unwrap_or_default(),
+                            assignment.replicas.get(position) != Some(&b.id),
+                            !assignment.replicas.contains(&b.id),
+                            b.id,
+                        )
+                    })
+                else {
+                    break;
+                };
+                if position == 0 {
+                    *leaders.entry(broker.id).or_default() += 1;
+                }
+                *replicas.entry(broker.id).or_default() += 1;
+                if let Some(rack) = &broker.rack {
+                    racks.insert(rack);
+                }
+                chosen.push(broker.id);
+            }
+            if chosen != assignment.replicas {
+                partitions.push(PartitionReplicas {
+                    topic: assignment.topic.clone(),
+                    partition: assignment.partition,
+                    replicas: chosen,
+                });
+            }
+        }
+        Self {
+            version: 1,
+            partitions,
+        }
+    }
+}
+
+#[test]
+fn test_balance() {
+    use std::collections::BTreeMap;
+
+    let brokers = [(1, "eu-west-1a"), (2, "eu-west-1b"), (3, "eu-west-1a")]
+        .map(|(id, rack)| BrokerRack {
+            id,
+            rack: Some(rack.to_string()),
+        })
+        .to_vec();
+    let assignments = (0..6)
+        .map(|partition| PartitionAssignment {
+            topic: "payments".to_string(),
+            partition,
+            leader: 1,
+            replicas: vec![1, 2],
+            in_sync_replicas: vec![1, 2],
+        })
+        .collect::<Vec<_>>();
+
+    let plan = ReassignmentPlan::balance(&brokers, &assignments);
+    let mut replicas = assignments
+        .iter()
+        .map(|a| (a.partition, a.replicas.clone()))
+        .collect::<BTreeMap<_, _>>();
+    for p in &plan.partitions {
+        replicas.insert(p.partition, p.replicas.clone());
+    }
+    let leaders = replicas.values().map(|r| r[0]).collect::<Vec<_>>();
+    for broker in &brokers {
+        assert_eq!(leaders.iter().filter(|l| **l == broker.id).count(), 2);
+    }
+    // Every partition has a replica in each rack
+    assert!(replicas.values().all(|r| r.len() == 2 && r.contains(&2)));
+    assert!(!plan.partitions.iter().any(|p| p.partition == 0));
+
+    let balanced = ReassignmentPlan::balance(
+        &brokers,
+        &replicas
+            .into_iter()
+            .map(|(partition, replicas)| PartitionAssignment {
+                partition,
+                replicas,
+                ..assignments[0].clone()
+            })
+            .collect::<Vec<_>>(),
+    );
+    assert_eq!(balanced.partitions.len(), 0);
+}
@@ -0,0 +1,103 @@
+//! Estimation of the number of records of a partition by sampling, see [`lib::CountMethod::Sampling`].
+//! Records are read from evenly spaced offsets of the partition, the ratio of offsets holding a record in these windows
+//! is applied to the whole partition. Offsets without record come from compaction, deleted records or transaction markers.
+
+use std::time::Duration;
+
+use lib::Error;
+use rdkafka::{
+    Message, Offset, TopicPartitionList,
+    consumer::{BaseConsumer, Consumer},
+    error::KafkaError,
+};
+
+/// Number of windows read from each partition
+const WINDOWS: usize = 8;
+
+/// Maximum number of records read in each window
+const WINDOW_SIZE: usize = 50;
+
+const POLL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Offsets of the records read from a given offset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Window {
+    start: i64,
+    offsets: Vec<i64>,
+}
+
+/// Estimates the number of records between the `low` and the `high` watermarks of a partition.
+/// Small partitions are read entirely, their count is exact.
+/// The consumer must be created with `enable.partition.eof`.
+pub(crate) fn sampled_count(
+    consumer: &BaseConsumer,
+    topic: &str,
+    partition: i32,
+    low: i64,
+    high: i64,
+) -> Result<i64, Error> {
+    let range = high - low;
+    if range <= 0 {
+        return Ok(0);
+    }
+    let (windows, limit) = match range <= (WINDOWS * WINDOW_SIZE) as i64 {
+        true => (1, range as usize),
+        false => (WINDOWS, WINDOW_SIZE),
+    };
+    let mut samples = vec![];
+    for window in 0..windows {
+        let start = low + range * window as i64 / windows as i64;
+        let mut assignment = TopicPartitionList::new();
+        assignment.add_partition_offset(topic, partition, Offset::Offset(start))?;
+        consumer.assign(&assignment)?;
+        let mut offsets = vec![];
+        while offsets.len() < limit {
+            match consumer.poll(POLL_TIMEOUT) {
+                Some(Ok(message)) if message.offset() < high => offsets.push(message.offset()),
+                Some(Ok(_)) | Some(Err(KafkaError::PartitionEOF(_))) | None => break,
+                Some(Err(e)) => return Err(e.into()),
+            }
+        }
+        samples.push(Window { start, offsets });
+    }
+    Ok(estimate(low, high, &samples, limit))
+}
+
+/// Applies the ratio of offsets holding a record in the windows to the whole partition.
+/// A window with less than `limit` records reached the end of the partition, it spans up to `high`.
+fn estimate(low: i64, high: i64, windows: &[Window], limit: usize) -> i64 {
+    let (records, span) = windows.iter().fold((0, 0), |(records, span), window| {
+        let end = match (window.offsets.len() >= limit, window.offsets.last()) {
+            (true, Some(last)) => last + 1,
+            _ => high,
+        };
+        (
+            records + window.offsets.len() as i64,
+            span + (end - window.start).max(0),
+        )
+    });
+    match span {
+        0 => 0,
+        _ => ((high - low) as f64 * records as f64 / span as f64).round() as i64,
+    }
+}
+
+#[test]
+fn test_estimate() {
+    // Every other offset holds a record
+    let windows = [0, 500, 1_000]
+        .map(|start| Window {
+            start,
+            offsets: (0..50).map(|i| start + 2 * i + 1).collect(),
+        })
+        .to_vec();
+    assert_eq!(estimate(0, 1_500, &windows, 50), 750);
+
+    // The partition is read entirely
+    let window = Window {
+        start: 10,
+        offsets: vec![10, 14, 15, 19],
+    };
+    assert_eq!(estimate(10, 20, &[window], 10), 4);
+    assert_eq!(estimate(0, 0, &[], 50), 0);
+}
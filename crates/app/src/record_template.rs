@@ -0,0 +1,245 @@
+//! Templates generating fake records, to produce realistic load to the topics of a development environment.
+//! A template is a file with the `.template` extension, typically a JSON document, containing placeholders such as `{{uuid}}`, `{{name}}` or `{{now}}`.
+//! Each rendering replaces the placeholders with new random values, the result is parsed as JSON, or kept as a string when it is not valid JSON.
+//!
+//! ```json
+//! { "id": "{{uuid}}", "customer": "{{name}}", "amount": {{float}}, "created_at": "{{now}}" }
+//! ```
+
+use std::{fs, path::Path, str::FromStr};
+
+use chrono::{SecondsFormat, Utc};
+use lib::Error;
+use rand::{Rng, seq::IndexedRandom};
+use serde_json::Value;
+
+use crate::{RecordMapping, RecordToProduce};
+
+const FIRST_NAMES: [&str; 16] = [
+    "Alice", "Bob", "Chloé", "David", "Emma", "Farid", "Grace", "Hugo", "Inès", "Jules", "Kenji",
+    "Léa", "Mohamed", "Nora", "Oscar", "Paula",
+];
+
+const LAST_NAMES: [&str; 16] = [
+    "Martin", "Bernard", "Dubois", "Smith", "Garcia", "Rossi", "Müller", "Nakamura", "Silva",
+    "Kowalski", "Haddad", "Nguyen", "Dupont", "Jensen", "Moreau", "Lopez",
+];
+
+const CITIES: [&str; 12] = [
+    "Paris",
+    "Lyon",
+    "Nantes",
+    "Lisbon",
+    "Berlin",
+    "Madrid",
+    "Rome",
+    "Tokyo",
+    "Montréal",
+    "Dakar",
+    "London",
+    "Oslo",
+];
+
+const WORDS: [&str; 12] = [
+    "paid",
+    "pending",
+    "refunded",
+    "shipped",
+    "cancelled",
+    "created",
+    "updated",
+    "deleted",
+    "active",
+    "inactive",
+    "failed",
+    "archived",
+];
+
+/// Returns `true` when the file is a template rendered to generate records.
+pub fn is_template(file: &Path) -> bool {
+    file.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("template"))
+}
+
+/// A value generated each time the template is rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Placeholder {
+    /// A random UUID v4
+    Uuid,
+    /// A first name followed by a last name
+    Name,
+    FirstName,
+    LastName,
+    Email,
+    City,
+    /// A word among a list of statuses
+    Word,
+    /// The current date time, RFC 3339 formatted
+    Now,
+    /// The current timestamp in milliseconds
+    Timestamp,
+    /// An integer between 0 and 1000
+    Int,
+    /// A number between 0 and 1000 with 2 decimals
+    Float,
+    Bool,
+    /// The index of the record, starting from 0
+    Index,
+}
+
+impl FromStr for Placeholder {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "uuid" => Ok(Self::Uuid),
+            "name" => Ok(Self::Name),
+            "first_name" => Ok(Self::FirstName),
+            "last_name" => Ok(Self::LastName),
+            "email" => Ok(Self::Email),
+            "city" => Ok(Self::City),
+            "word" => Ok(Self::Word),
+            "now" => Ok(Self::Now),
+            "timestamp" => Ok(Self::Timestamp),
+            "int" => Ok(Self::Int),
+            "float" => Ok(Self::Float),
+            "bool" => Ok(Self::Bool),
+            "index" => Ok(Self::Index),
+            p => Err(Error::Error(format!(
+                "Unknown placeholder '{{{{{p}}}}}', expected one of uuid, name, first_name, last_name, email, city, word, now, timestamp, int, float, bool or index"
+            ))),
+        }
+    }
+}
+
+impl Placeholder {
+    fn render(self, index: usize, rng: &mut impl Rng) -> String {
+        let pick = |names: &[&'static str], rng: &mut _| *names.choose(rng).unwrap();
+        match self {
+            Self::Uuid => {
+                let mut bytes: [u8; 16] = rng.random();
+                bytes[6] = (bytes[6] & 0x0f) | 0x40;
+                bytes[8] = (bytes[8] & 0x3f) | 0x80;
+                let hex = bytes.iter().map(|b| format!("{b:02x}")).collect::<String>();
+                format!(
+                    "{}-{}-{}-{}-{}",
+                    &hex[0..8],
+                    &hex[8..12],
+                    &hex[12..16],
+                    &hex[16..20],
+                    &hex[20..32]
+                )
+            }
+            Self::Name => format!("{} {}", pick(&FIRST_NAMES, rng), pick(&LAST_NAMES, rng)),
+            Self::FirstName => pick(&FIRST_NAMES, rng).to_string(),
+            Self::LastName => pick(&LAST_NAMES, rng).to_string(),
+            Self::Email => format!(
+                "{}.{}@example.com",
+                pick(&FIRST_NAMES, rng).to_lowercase(),
+                pick(&LAST_NAMES, rng).to_lowercase()
+            ),
+            Self::City => pick(&CITIES, rng).to_string(),
+            Self::Word => pick(&WORDS, rng).to_string(),
+            Self::Now => Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            Self::Timestamp => Utc::now().timestamp_millis().to_string(),
+            Self::Int => rng.random_range(0..=1000).to_string(),
+            Self::Float => format!("{:.2}", rng.random_range(0.0..1000.0)),
+            Self::Bool => rng.random_bool(0.5).to_string(),
+            Self::Index => index.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Text(String),
+    Placeholder(Placeholder),
+}
+
+/// A parsed template, see the module documentation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordTemplate {
+    segments: Vec<Segment>,
+}
+
+impl FromStr for RecordTemplate {
+    type Err = Error;
+
+    fn from_str(template: &str) -> Result<Self, Self::Err> {
+        let mut segments = vec![];
+        let mut rest = template;
+        while let Some(start) = rest.find("{{") {
+            let Some(end) = rest[start..].find("}}") else {
+                return Err(Error::Error(format!(
+                    "Unclosed placeholder '{}'",
+                    &rest[start..]
+                )));
+            };
+            segments.push(Segment::Text(rest[..start].to_string()));
+            segments.push(Segment::Placeholder(rest[start + 2..start + end].parse()?));
+            rest = &rest[start + end + 2..];
+        }
+        segments.push(Segment::Text(rest.to_string()));
+        segments.retain(|s| s != &Segment::Text(String::new()));
+        Ok(Self { segments })
+    }
+}
+
+impl RecordTemplate {
+    /// Reads the template from a file.
+    pub fn read(file: &Path) -> Result<Self, Error> {
+        fs::read_to_string(file)?.parse()
+    }
+
+    /// Renders the template, `index` is the index of the generated record.
+    pub fn render(&self, index: usize, rng: &mut impl Rng) -> Value {
+        let rendered = self
+            .segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Text(text) => text.clone(),
+                Segment::Placeholder(p) => p.render(index, rng),
+            })
+            .collect::<String>();
+        serde_json::from_str(&rendered).unwrap_or(Value::String(rendered))
+    }
+
+    /// Generates `count` records, the key and the headers are read from the rendered values.
+    pub fn records(&self, count: usize, mapping: &RecordMapping) -> Vec<RecordToProduce> {
+        let mut rng = rand::rng();
+        (0..count)
+            .map(|index| mapping.apply(&self.render(index, &mut rng)))
+            .collect()
+    }
+}
+
+#[test]
+fn test_render_template() {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    let template: RecordTemplate =
+        r#"{"id": "{{uuid}}", "index": {{ index }}, "customer": "{{name}}", "amount": {{float}}}"#
+            .parse()
+            .unwrap();
+    let mut rng = StdRng::seed_from_u64(42);
+    let value = template.render(7, &mut rng);
+    assert_eq!(value["index"], 7);
+    assert!(value["amount"].is_f64());
+    let id = value["id"].as_str().unwrap();
+    assert_eq!(id.len(), 36);
+    assert_eq!(&id[14..15], "4");
+    assert!(value["customer"].as_str().unwrap().contains(' '));
+    assert_ne!(template.render(7, &mut rng)["id"], value["id"]);
+
+    let records = "{{city}}"
+        .parse::<RecordTemplate>()
+        .unwrap()
+        .records(3, &RecordMapping::default());
+    assert_eq!(records.len(), 3);
+    assert!(CITIES.contains(&records[0].value.as_str().unwrap()));
+
+    assert!("{{phone}}".parse::<RecordTemplate>().is_err());
+    assert!("{\"id\": \"{{uuid\"}".parse::<RecordTemplate>().is_err());
+    assert!(is_template(Path::new("payments.json.template")));
+}
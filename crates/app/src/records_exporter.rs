@@ -0,0 +1,500 @@
+//! Streams kafka records into the export files.
+//! Unlike [`crate::App::export_record`], records are appended one by one at the end of the JSON array
+//! without reading the file again, so the memory stays flat whatever the number of exported records.
+//! Records are written in the order they are received, the file is a valid JSON array after each record.
+//! Both write the same files, their writes are serialized by [`lock_export_writes`].
+
+use std::{
+    collections::HashMap,
+    fs::{self, File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard, PoisonError},
+    time::SystemTime,
+};
+
+use itertools::Itertools;
+use lib::{Error, ExportedKafkaRecord, KafkaRecord, search::SearchQuery};
+use tracing::{info, warn};
+
+use crate::{
+    AuditAction, AuditEvent, AuditLog, Notes,
+    configuration::{AnonymizationProfile, InternalConfig, record_count, set_record_count},
+    projection::{Projection, is_csv},
+    sqlite_exporter::{SqliteDatabase, is_sqlite},
+};
+
+/// Export files are written by the live export and by the export of a single record, possibly at the same time.
+static EXPORT_WRITES: Mutex<()> = Mutex::new(());
+
+/// Serializes the writes to the export files, so a file is never written by two exports at once.
+pub(crate) fn lock_export_writes() -> MutexGuard<'static, ()> {
+    EXPORT_WRITES.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Exports kafka records as they come, typically every record matching the search query.
+pub struct RecordsExporter {
+    config: InternalConfig,
+    profile: Option<AnonymizationProfile>,
+    search_query: SearchQuery,
+    /// Notes attached to the records, written along with the exported records
+    notes: Notes,
+    /// Modification time and size of the notes file when the notes were read, they are read again when it changes
+    notes_version: Option<(SystemTime, u64)>,
+    /// Fields written to the export files, the whole records when `None`
+    projection: Option<Projection>,
+    /// Export file currently written, by topic
+    files: HashMap<String, ExportFile>,
+    /// SQLite databases records are inserted into, by topic, when the export file is a database
+    databases: HashMap<String, (PathBuf, SqliteDatabase)>,
+    /// CSV files records are written to, by topic, when the export file ends with `.csv`
+    csv_files: HashMap<String, CsvFile>,
+    exported: usize,
+}
+
+impl RecordsExporter {
+    /// Maximum number of records given at once to [`RecordsExporter::export_all`] by the live exports
+    pub const BATCH_SIZE: usize = 1_000;
+
+    pub fn new(config: InternalConfig, search_query: SearchQuery) -> Result<Self, Error> {
+        Ok(Self {
+            profile: config.anonymization_profile()?,
+            projection: Projection::of(&search_query, &config.workspace().config().export)?,
+            notes: Notes::default(),
+            notes_version: None,
+            config,
+            search_query,
+            files: HashMap::new(),
+            databases: HashMap::new(),
+            csv_files: HashMap::new(),
+            exported: 0,
+        })
+    }
+
+    /// Appends the record to the export file of its topic and returns the path of this file.
+    pub fn export(&mut self, record: &KafkaRecord) -> Result<PathBuf, Error> {
+        self.export_all(std::slice::from_ref(record))
+            .map(|mut paths| paths.remove(0))
+    }
+
+    /// Appends the records to the export files of their topic and returns the path of the file of each record.
+    /// Records are inserted into a SQLite database in a single transaction per topic.
+    pub fn export_all(&mut self, records: &[KafkaRecord]) -> Result<Vec<PathBuf>, Error> {
+        let _lock = lock_export_writes();
+        if is_sqlite(self.config.output_file()) {
+            return self.insert(records);
+        }
+        self.refresh_notes();
+        records
+            .iter()
+            .map(|record| match is_csv(self.config.output_file()) {
+                true => self.write_row(record),
+                false => self.append(record),
+            })
+            .collect()
+    }
+
+    /// Reads the notes again when they have been edited since they were read, like during a live export.
+    /// Records are exported without their note when the notes file is invalid.
+    fn refresh_notes(&mut self) {
+        let file = self.config.workspace().notes_file();
+        let version = fs::metadata(&file)
+            .and_then(|m| Ok((m.modified()?, m.len())))
+            .ok();
+        if version == self.notes_version {
+            return;
+        }
+        self.notes_version = version;
+        self.notes = Notes::load(&file, self.config.cluster()).unwrap_or_else(|e| {
+            warn!("Records are exported without their note: {e}");
+            Notes::default()
+        });
+    }
+
+    /// Appends the record to the JSON export file of its topic.
+    fn append(&mut self, record: &KafkaRecord) -> Result<PathBuf, Error> {
+        let export_config = &self.config.workspace().config().export;
+        let file = match self.files.remove(&record.topic) {
+            Some(file)
+                if !file.is_full(export_config.max_records, export_config.max_size)
+                    && !file.is_modified() =>
+            {
+                file
+            }
+            _ => ExportFile::open(&self.config.export_file_of(&record.topic)?)?,
+        };
+        let file = self.files.entry(record.topic.clone()).or_insert(file);
+
+        let record = match &self.profile {
+            Some(profile) => profile.apply(record),
+            None => record.clone(),
+        };
+        let json = match &self.projection {
+            Some(projection) => serde_json::to_string_pretty(&projection.project(&record))?,
+            None => {
+                let mut exported_record: ExportedKafkaRecord = (&record).into();
+                exported_record.set_search_query(&self.search_query);
+                exported_record.note = self.notes.text_of(&record);
+                if file.first_timestamp.is_none() {
+                    file.first_timestamp = record.timestamp;
+                }
+                exported_record.compute_deltas_ms(file.first_timestamp, file.previous_timestamp);
+                file.previous_timestamp = record.timestamp;
+                serde_json::to_string_pretty(&exported_record)?
+            }
+        };
+
+        file.append(&json)?;
+        self.exported += 1;
+        Ok(file.path.clone())
+    }
+
+    /// Inserts the records into the SQLite database of their topic.
+    fn insert(&mut self, records: &[KafkaRecord]) -> Result<Vec<PathBuf>, Error> {
+        let search_query = self.search_query.to_string();
+        for (topic, records) in records.iter().into_group_map_by(|r| &r.topic) {
+            if !self.databases.contains_key(topic) {
+                let path = self.config.export_file_of(topic)?;
+                let fields = self.projection.as_ref().map_or(&[][..], Projection::fields);
+                let database = SqliteDatabase::open(&path, fields)?;
+                self.databases.insert(topic.clone(), (path, database));
+            }
+            let (_, database) = self.databases.get_mut(topic).unwrap();
+            match &self.profile {
+                Some(profile) => database.insert_all(
+                    &records.into_iter().map(|r| profile.apply(r)).collect_vec(),
+                    &search_query,
+                )?,
+                None => database.insert_all(records, &search_query)?,
+            }
+        }
+        self.exported += records.len();
+        Ok(records
+            .iter()
+            .map(|r| self.databases[&r.topic].0.clone())
+            .collect())
+    }
+
+    /// Writes the selected fields of the record as a row of the CSV file of its topic.
+    fn write_row(&mut self, record: &KafkaRecord) -> Result<PathBuf, Error> {
+        let export_config = &self.config.workspace().config().export;
+        let file = match self.csv_files.remove(&record.topic) {
+            Some(file) if !file.is_full(export_config.max_records, export_config.max_size) => file,
+            _ => {
+                let projection = Projection::of_csv(&self.search_query, export_config)?;
+                let path = export_config.rotate_csv(
+                    &self.config.export_file_of(&record.topic)?,
+                    &projection.header(),
+                )?;
+                CsvFile::open(&path, projection)?
+            }
+        };
+        let file = self.csv_files.entry(record.topic.clone()).or_insert(file);
+        match &self.profile {
+            Some(profile) => file.write(&profile.apply(record))?,
+            None => file.write(record)?,
+        }
+        self.exported += 1;
+        Ok(file.path.clone())
+    }
+
+    /// Number of records exported so far.
+    pub fn exported(&self) -> usize {
+        self.exported
+    }
+}
+
+impl Drop for RecordsExporter {
+    fn drop(&mut self) {
+        for file in self.files.values() {
+            info!(
+                "{} records have been exported into file '{}'",
+                file.records,
+                file.path.display()
+            );
+        }
+        for file in self.csv_files.values() {
+            info!(
+                "{} records have been exported into file '{}'",
+                file.records,
+                file.path.display()
+            );
+        }
+        for (path, database) in self.databases.values() {
+            info!(
+                "{} records have been exported into database '{}'",
+                database.records,
+                path.display()
+            );
+        }
+        if self.exported == 0 {
+            return;
+        }
+        let event = AuditEvent::new(
+            self.config.cluster(),
+            AuditAction::Export,
+            &self
+                .files
+                .keys()
+                .chain(self.databases.keys())
+                .chain(self.csv_files.keys())
+                .sorted()
+                .cloned()
+                .collect_vec(),
+            &format!(
+                "{} records matching '{}' exported into '{}'",
+                self.exported,
+                self.search_query,
+                self.config.output_file().display()
+            ),
+        );
+        if let Err(e) = AuditLog::new(&self.config.workspace().audit_file()).append(&event) {
+            warn!("Cannot write the export to the audit log: {e}");
+        }
+    }
+}
+
+/// An export file open for appending records.
+struct ExportFile {
+    path: PathBuf,
+    file: File,
+    /// Position right after the last record, or after `[` when the array is empty
+    end: u64,
+    size: u64,
+    records: usize,
+    first_timestamp: Option<i64>,
+    previous_timestamp: Option<i64>,
+}
+
+impl ExportFile {
+    fn open(path: &Path) -> Result<Self, Error> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        let size = file.metadata()?.len();
+        if size == 0 {
+            file.write_all(b"[]")?;
+            return Ok(Self::new(path, file, 1, 2, 0));
+        }
+
+        // Look for the closing bracket of the array at the end of the file
+        let tail_length = size.min(4096);
+        let mut tail = vec![0; tail_length as usize];
+        file.seek(SeekFrom::Start(size - tail_length))?;
+        file.read_exact(&mut tail)?;
+        let before_bracket = tail
+            .trim_ascii_end()
+            .strip_suffix(b"]")
+            .map(<[u8]>::trim_ascii_end)
+            .ok_or(Error::Error(format!(
+                "'{}' is not a JSON array of kafka records",
+                path.display()
+            )))?;
+        let end = size - tail_length + before_bracket.len() as u64;
+
+        let records = match before_bracket.ends_with(b"[") {
+            true => 0,
+            false => record_count(path, size, || {
+                let records: Vec<serde::de::IgnoredAny> =
+                    serde_json::from_reader(fs::File::open(path)?)?;
+                Ok(records.len())
+            })?,
+        };
+        Ok(Self::new(path, file, end, size, records))
+    }
+
+    fn new(path: &Path, file: File, end: u64, size: u64, records: usize) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            file,
+            end,
+            size,
+            records,
+            first_timestamp: None,
+            previous_timestamp: None,
+        }
+    }
+
+    fn is_full(&self, max_records: Option<usize>, max_size: Option<u64>) -> bool {
+        max_records.is_some_and(|max| self.records >= max)
+            || max_size.is_some_and(|max| self.size >= max)
+    }
+
+    /// The file has been written by someone else, like [`crate::App::export_record`].
+    fn is_modified(&self) -> bool {
+        self.file.metadata().map_or(true, |m| m.len() != self.size)
+    }
+
+    fn append(&mut self, json: &str) -> Result<(), Error> {
+        let separator = match self.records {
+            0 => "\n",
+            _ => ",\n",
+        };
+        let record = format!("{separator}  {}", json.replace('\n', "\n  "));
+        self.file.seek(SeekFrom::Start(self.end))?;
+        self.file.write_all(format!("{record}\n]").as_bytes())?;
+        self.end += record.len() as u64;
+        self.size = self.end + 2;
+        self.file.set_len(self.size)?;
+        self.records += 1;
+        set_record_count(&self.path, self.size, self.records);
+        Ok(())
+    }
+}
+
+/// A CSV file open for appending rows, the header is written when the file is created.
+/// The header of an existing file is expected to be the one of the projection, see [`crate::configuration::ExportConfig::rotate_csv`].
+pub(crate) struct CsvFile {
+    path: PathBuf,
+    writer: csv::Writer<File>,
+    projection: Projection,
+    size: u64,
+    /// Number of rows of the file, the header excluded
+    records: usize,
+}
+
+impl CsvFile {
+    pub(crate) fn open(path: &Path, projection: Projection) -> Result<Self, Error> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let size = file.metadata()?.len();
+        let records = match size {
+            0 => 0,
+            _ => record_count(path, size, || {
+                Ok(csv::Reader::from_path(path)
+                    .map_err(|e| Error::Error(format!("Cannot read '{}': {e}", path.display())))?
+                    .records()
+                    .count())
+            })?,
+        };
+        let mut writer = csv::Writer::from_writer(file);
+        if size == 0 {
+            writer
+                .write_record(projection.header())
+                .map_err(|e| Error::Error(format!("Cannot write the CSV header: {e}")))?;
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            writer,
+            projection,
+            size,
+            records,
+        })
+    }
+
+    fn is_full(&self, max_records: Option<usize>, max_size: Option<u64>) -> bool {
+        max_records.is_some_and(|max| self.records >= max)
+            || max_size.is_some_and(|max| self.size >= max)
+    }
+
+    /// Writes the row and flushes it, the file is complete after each record.
+    pub(crate) fn write(&mut self, record: &KafkaRecord) -> Result<(), Error> {
+        self.writer
+            .write_record(self.projection.row(record))
+            .map_err(|e| Error::Error(format!("Cannot write the CSV row: {e}")))?;
+        self.writer.flush()?;
+        self.size = self.writer.get_ref().metadata()?.len();
+        self.records += 1;
+        set_record_count(&self.path, self.size, self.records);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_append_records() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("export.json");
+    let mut file = ExportFile::open(&path).unwrap();
+    file.append(r#"{"offset": 1}"#).unwrap();
+    file.append("{\n  \"offset\": 2\n}").unwrap();
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "[\n  {\"offset\": 1},\n  {\n    \"offset\": 2\n  }\n]"
+    );
+
+    fs::write(&path, "[\n  {\"offset\": 1}\n]\n").unwrap();
+    let mut file = ExportFile::open(&path).unwrap();
+    assert_eq!(file.records, 1);
+    file.append(r#"{"offset": 2}"#).unwrap();
+    let records: Vec<serde_json::Value> =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(records.len(), 2);
+    assert!(file.is_full(Some(2), None));
+}
+
+#[test]
+fn test_append_csv_rows() {
+    use crate::configuration::ExportConfig;
+    use lib::DataType;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("exports").join("orders.csv");
+    let config = ExportConfig {
+        fields: vec!["offset".to_string(), "key".to_string()],
+        ..Default::default()
+    };
+    let projection = Projection::of_csv(&SearchQuery::default(), &config).unwrap();
+    let record = |offset: i64, key: &str| {
+        let mut record = KafkaRecord::default();
+        record.offset = offset;
+        record.key = DataType::String(key.to_string());
+        record
+    };
+
+    let mut file = CsvFile::open(&path, projection.clone()).unwrap();
+    file.write(&record(1, "ord-1")).unwrap();
+    drop(file);
+    let mut file = CsvFile::open(&path, projection).unwrap();
+    assert_eq!(file.records, 1);
+    file.write(&record(2, "ord-2,ord-3")).unwrap();
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "offset,key\n1,ord-1\n2,\"ord-2,ord-3\"\n"
+    );
+    assert!(file.is_full(Some(2), None));
+}
+
+#[test]
+fn test_export_notes_added_during_the_export() {
+    use crate::configuration::{ClusterConfig, GlobalConfig, Workspace};
+
+    let dir = tempfile::tempdir().unwrap();
+    let workspace = Workspace::new(
+        dir.path(),
+        GlobalConfig::new(&dir.path().join("config.json")),
+        dir.path().join("logs"),
+    );
+    let path = dir.path().join("export.json");
+    let yozefu_config = ClusterConfig::default()
+        .create("localhost")
+        .with_export_file(path.clone());
+    let config = InternalConfig::new(yozefu_config, workspace.clone());
+    let record = |offset: i64| {
+        let mut record = KafkaRecord::default();
+        record.offset = offset;
+        record
+    };
+    let mut exporter = RecordsExporter::new(config, SearchQuery::default()).unwrap();
+
+    exporter.export(&record(1)).unwrap();
+    Notes::load(&workspace.notes_file(), "localhost")
+        .unwrap()
+        .set(&record(2), "refunded twice")
+        .unwrap();
+    exporter.export(&record(2)).unwrap();
+    fs::write(workspace.notes_file(), "{").unwrap();
+    exporter.export(&record(3)).unwrap();
+
+    let records: Vec<serde_json::Value> =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    let notes = records.iter().map(|r| r["note"].as_str()).collect_vec();
+    assert_eq!(notes, vec![None, Some("refunded twice"), None]);
+}
@@ -0,0 +1,307 @@
+//! Reads records from NDJSON or CSV files to produce them, typically to seed the topics of a development environment.
+//! Each line of a NDJSON file is a JSON value, each row of a CSV file is an object whose properties are the columns of the header.
+//! A [`RecordMapping`] tells which fields of the row are the key, the value and the headers of the record.
+
+use std::{fmt::Display, fs, num::NonZeroU32, path::Path, str::FromStr};
+
+use lib::{Error, kafka::SchemaResponse};
+use serde_json::{Map, Value};
+
+/// Format of the file records are read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    /// One JSON value per line
+    Ndjson,
+    /// Comma-separated values with a header
+    Csv,
+}
+
+impl InputFormat {
+    /// Guesses the format from the extension of the file, NDJSON unless it ends with `.csv`.
+    pub fn of(file: &Path) -> Self {
+        match file.extension().and_then(|e| e.to_str()) {
+            Some(e) if e.eq_ignore_ascii_case("csv") => Self::Csv,
+            _ => Self::Ndjson,
+        }
+    }
+}
+
+impl FromStr for InputFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ndjson" | "jsonl" | "json" => Ok(Self::Ndjson),
+            "csv" => Ok(Self::Csv),
+            _ => Err(Error::Error(format!(
+                "Unknown format '{s}', expected 'ndjson' or 'csv'"
+            ))),
+        }
+    }
+}
+
+/// Schema the values are serialized with, a schema id or the latest version of a subject.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValueSchema {
+    Id(u32),
+    Subject(String),
+}
+
+/// How the records are produced.
+#[derive(Debug, Clone, Default)]
+pub struct ProduceOptions {
+    /// Values are serialized with this schema and its id, as JSON otherwise
+    pub value_schema: Option<(u32, SchemaResponse)>,
+    /// Maximum number of records produced per second
+    pub rate: Option<NonZeroU32>,
+    /// Records are produced inside a transaction, aborted when a record cannot be produced
+    pub transactional: bool,
+    /// Text typed to confirm the production, the name of the cluster is required on protected clusters
+    pub confirmation: String,
+}
+
+/// Fields of a row, written as `customer.id`, that become the key, the value and the headers of the record.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecordMapping {
+    /// Field of the key, records have no key when it is not set
+    pub key: Option<String>,
+    /// Field of the value, the whole row when it is not set
+    pub value: Option<String>,
+    /// Name of each header and the field of its value
+    pub headers: Vec<(String, String)>,
+}
+
+/// A record ready to be produced.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordToProduce {
+    pub key: Option<String>,
+    /// Bytes of the key, sent as they are instead of [`RecordToProduce::key`].
+    /// A key serialized with a schema keeps its bytes, and so its partition
+    pub raw_key: Option<Vec<u8>>,
+    /// `null` values are produced as tombstones
+    pub value: Value,
+    pub headers: Vec<(String, String)>,
+}
+
+/// A change made to the headers of the records right before they are produced,
+/// to bump a `retry-count` or to drop a poisoned `trace-id` for instance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HeaderEdit {
+    /// Adds the header, or replaces its value
+    Set(String, String),
+    /// Removes the header
+    Remove(String),
+}
+
+impl HeaderEdit {
+    /// Parses an edit written `<name>=<value>` to set a header, or `-<name>` to remove it.
+    pub fn parse(edit: &str) -> Result<Self, Error> {
+        let edit = edit.trim();
+        let parsed = match (edit.strip_prefix('-'), edit.split_once('=')) {
+            (Some(name), _) => Self::Remove(name.trim().to_string()),
+            (None, Some((name, value))) => {
+                Self::Set(name.trim().to_string(), value.trim().to_string())
+            }
+            (None, None) => {
+                return Err(Error::Error(format!(
+                    "Invalid header '{edit}', expected '<name>=<value>' or '-<name>'"
+                )));
+            }
+        };
+        match parsed.name().is_empty() {
+            true => Err(Error::Error(format!("The header of '{edit}' has no name"))),
+            false => Ok(parsed),
+        }
+    }
+
+    /// Name of the edited header.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Set(name, _) | Self::Remove(name) => name,
+        }
+    }
+}
+
+impl Display for HeaderEdit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Set(name, value) => write!(f, "{name}={value}"),
+            Self::Remove(name) => write!(f, "-{name}"),
+        }
+    }
+}
+
+impl RecordToProduce {
+    /// Applies the edits, in order, to the headers of the record.
+    pub fn edit_headers(&mut self, edits: &[HeaderEdit]) {
+        for edit in edits {
+            self.headers.retain(|(name, _)| name != edit.name());
+            if let HeaderEdit::Set(name, value) = edit {
+                self.headers.push((name.clone(), value.clone()));
+            }
+        }
+    }
+}
+
+impl RecordMapping {
+    /// Parses a header mapping written `<name>=<field>`, or `<field>` when the header is named after the field.
+    pub fn parse_header(mapping: &str) -> (String, String) {
+        match mapping.split_once('=') {
+            Some((name, field)) => (name.trim().to_string(), field.trim().to_string()),
+            None => (mapping.trim().to_string(), mapping.trim().to_string()),
+        }
+    }
+
+    pub fn apply(&self, row: &Value) -> RecordToProduce {
+        RecordToProduce {
+            key: self
+                .key
+                .as_ref()
+                .and_then(|field| field_of(row, field))
+                .map(to_string),
+            raw_key: None,
+            value: match &self.value {
+                Some(field) => field_of(row, field).cloned().unwrap_or_default(),
+                None => row.clone(),
+            },
+            headers: self
+                .headers
+                .iter()
+                .filter_map(|(name, field)| {
+                    field_of(row, field).map(|value| (name.clone(), to_string(value)))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Reads the rows of the file and maps them to records.
+pub fn read_records(
+    file: &Path,
+    format: InputFormat,
+    mapping: &RecordMapping,
+) -> Result<Vec<RecordToProduce>, Error> {
+    let content = fs::read_to_string(file)?;
+    let rows = match format {
+        InputFormat::Ndjson => content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<Vec<Value>, _>>()?,
+        InputFormat::Csv => read_csv(&content)?,
+    };
+    Ok(rows.iter().map(|row| mapping.apply(row)).collect())
+}
+
+/// Columns are strings, empty cells are `null`.
+fn read_csv(content: &str) -> Result<Vec<Value>, Error> {
+    let mut reader = csv::Reader::from_reader(content.as_bytes());
+    let columns = reader
+        .headers()
+        .map_err(|e| Error::Error(format!("Invalid CSV header: {e}")))?
+        .clone();
+    reader
+        .records()
+        .map(|row| {
+            let row = row.map_err(|e| Error::Error(format!("Invalid CSV row: {e}")))?;
+            Ok(Value::Object(
+                columns
+                    .iter()
+                    .zip(row.iter())
+                    .map(|(column, cell)| {
+                        let cell = match cell.is_empty() {
+                            true => Value::Null,
+                            false => Value::String(cell.to_string()),
+                        };
+                        (column.to_string(), cell)
+                    })
+                    .collect::<Map<_, _>>(),
+            ))
+        })
+        .collect()
+}
+
+fn field_of<'a>(row: &'a Value, field: &str) -> Option<&'a Value> {
+    row.pointer(&format!("/{}", field.replace('.', "/")))
+        .filter(|v| !v.is_null())
+}
+
+fn to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        v => v.to_string(),
+    }
+}
+
+#[test]
+fn test_read_records() {
+    let dir = tempfile::tempdir().unwrap();
+    let mapping = RecordMapping {
+        key: Some("customer.id".to_string()),
+        value: None,
+        headers: vec![RecordMapping::parse_header("tenant")],
+    };
+
+    let file = dir.path().join("payments.ndjson");
+    fs::write(
+        &file,
+        "{\"customer\": {\"id\": 1}, \"tenant\": \"acme\"}\n\n{\"amount\": 3}\n",
+    )
+    .unwrap();
+    let records = read_records(&file, InputFormat::of(&file), &mapping).unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].key.as_deref(), Some("1"));
+    assert_eq!(
+        records[0].headers,
+        vec![("tenant".to_string(), "acme".to_string())]
+    );
+    assert_eq!(records[1].key, None);
+    assert_eq!(records[1].value, serde_json::json!({"amount": 3}));
+
+    let file = dir.path().join("payments.csv");
+    fs::write(&file, "id,status,note\np-1,paid,\np-2,failed,late\n").unwrap();
+    let mapping = RecordMapping {
+        key: Some("id".to_string()),
+        value: Some("status".to_string()),
+        headers: vec![RecordMapping::parse_header("x-note=note")],
+    };
+    let records = read_records(&file, InputFormat::of(&file), &mapping).unwrap();
+    assert_eq!(
+        records[1],
+        RecordToProduce {
+            key: Some("p-2".to_string()),
+            raw_key: None,
+            value: Value::String("failed".to_string()),
+            headers: vec![("x-note".to_string(), "late".to_string())],
+        }
+    );
+    assert!(records[0].headers.is_empty());
+}
+
+#[test]
+fn test_edit_headers() {
+    let mut record = RecordToProduce {
+        key: None,
+        raw_key: None,
+        value: Value::Null,
+        headers: vec![
+            ("trace-id".to_string(), "poisoned".to_string()),
+            ("retry-count".to_string(), "2".to_string()),
+        ],
+    };
+    let edits = ["retry-count=3", "-trace-id", " tenant = acme "]
+        .iter()
+        .map(|edit| HeaderEdit::parse(edit).unwrap())
+        .collect::<Vec<_>>();
+    record.edit_headers(&edits);
+    assert_eq!(
+        record.headers,
+        vec![
+            ("retry-count".to_string(), "3".to_string()),
+            ("tenant".to_string(), "acme".to_string())
+        ]
+    );
+    assert_eq!(edits[1].to_string(), "-trace-id");
+    assert!(HeaderEdit::parse("retry-count").is_err());
+    assert!(HeaderEdit::parse("=3").is_err());
+}
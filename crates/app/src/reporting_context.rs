@@ -0,0 +1,44 @@
+//! Consumer context reporting what librdkafka does in the background:
+//! the errors it hits, like authentication failures, and its statistics, like the state of the connection to each broker.
+
+use rdkafka::{
+    ClientContext, consumer::ConsumerContext, error::KafkaError, statistics::Statistics,
+};
+use tracing::error;
+
+use crate::{ConsumerError, ConsumerStatistics};
+
+/// How often librdkafka emits its statistics, in milliseconds
+pub(crate) const STATISTICS_INTERVAL_MS: &str = "5000";
+
+type Report<T> = Box<dyn Fn(T) + Send + Sync>;
+
+pub struct ReportingContext {
+    on_error: Report<ConsumerError>,
+    on_statistics: Report<ConsumerStatistics>,
+}
+
+impl ReportingContext {
+    pub fn new(
+        on_error: impl Fn(ConsumerError) + Send + Sync + 'static,
+        on_statistics: impl Fn(ConsumerStatistics) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            on_error: Box::new(on_error),
+            on_statistics: Box::new(on_statistics),
+        }
+    }
+}
+
+impl ClientContext for ReportingContext {
+    fn stats(&self, statistics: Statistics) {
+        (self.on_statistics)(ConsumerStatistics::from(&statistics));
+    }
+
+    fn error(&self, error: KafkaError, reason: &str) {
+        error!("librdkafka: {error}: {reason}");
+        (self.on_error)(ConsumerError::new(&error, reason));
+    }
+}
+
+impl ConsumerContext for ReportingContext {}
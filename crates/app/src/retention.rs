@@ -0,0 +1,63 @@
+//! A search whose `from` bound predates the oldest record of a topic reads fewer records than expected:
+//! the older records have been deleted by the retention policy of the topic.
+
+use std::fmt::Display;
+
+use chrono::DateTime;
+use itertools::Itertools;
+
+/// The records a search should start from have been deleted from a topic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetentionWarning {
+    pub topic: String,
+    /// Timestamp of the oldest retained record of the topic, in milliseconds, when the search starts from a date
+    pub earliest_timestamp: Option<i64>,
+    /// Partitions starting after the offset of the search, with their first retained offset
+    pub partitions: Vec<(i32, i64)>,
+}
+
+impl Display for RetentionWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(date) = self
+            .earliest_timestamp
+            .and_then(DateTime::from_timestamp_millis)
+        {
+            return write!(
+                f,
+                "Topic '{}': only data from {} onwards is retained",
+                self.topic,
+                date.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+            );
+        }
+        write!(
+            f,
+            "Topic '{}': the first retained records are {}, older records have been deleted",
+            self.topic,
+            self.partitions
+                .iter()
+                .map(|(partition, offset)| format!("offset {offset} of partition {partition}"))
+                .join(", ")
+        )
+    }
+}
+
+#[test]
+fn test_retention_warning() {
+    let warning = RetentionWarning {
+        topic: "payments".to_string(),
+        earliest_timestamp: Some(1_748_908_800_000),
+        partitions: vec![(0, 120), (2, 98)],
+    };
+    assert_eq!(
+        warning.to_string(),
+        "Topic 'payments': only data from 2025-06-03T00:00:00Z onwards is retained"
+    );
+    let warning = RetentionWarning {
+        earliest_timestamp: None,
+        ..warning
+    };
+    assert_eq!(
+        warning.to_string(),
+        "Topic 'payments': the first retained records are offset 120 of partition 0, offset 98 of partition 2, older records have been deleted"
+    );
+}
@@ -0,0 +1,132 @@
+//! The schemas the records of a topic are actually serialized with.
+//! The schema registry lists the versions of a subject, but not which of them producers still use:
+//! a sample of the records tells it, along with the records written with an outdated version.
+
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use lib::kafka::SubjectVersion;
+use serde::Serialize;
+
+/// Number of sampled records whose value is serialized with a given schema.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct SchemaUsage {
+    /// Id of the schema in the schema registry
+    pub id: u32,
+    /// Number of sampled records serialized with this schema
+    pub records: usize,
+    /// Subjects and versions the schema is registered under, empty when the schema registry does not know the schema
+    pub subjects: Vec<SubjectVersion>,
+    /// A newer version of one of the subjects has been registered
+    pub deprecated: bool,
+}
+
+/// The schemas of the values of a sample of the records of a topic.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Default)]
+pub struct SchemaUsageReport {
+    pub topic: String,
+    /// Number of records read
+    pub sampled: usize,
+    /// Number of records whose value has no schema id
+    pub without_schema: usize,
+    /// Schemas of the records, the most used first
+    pub schemas: Vec<SchemaUsage>,
+}
+
+impl SchemaUsageReport {
+    /// Counts the schema ids of the sampled records, `None` for a value without schema id.
+    pub fn new(topic: &str, ids: impl IntoIterator<Item = Option<u32>>) -> Self {
+        let ids = ids.into_iter().collect_vec();
+        let schemas = ids
+            .iter()
+            .flatten()
+            .counts()
+            .into_iter()
+            .map(|(id, records)| SchemaUsage {
+                id: *id,
+                records,
+                subjects: vec![],
+                deprecated: false,
+            })
+            .sorted_by_key(|usage| (usize::MAX - usage.records, usage.id))
+            .collect();
+        Self {
+            topic: topic.to_string(),
+            sampled: ids.len(),
+            without_schema: ids.iter().filter(|id| id.is_none()).count(),
+            schemas,
+        }
+    }
+
+    /// Flags the schemas registered under an older version than the latest version of their subject.
+    pub fn flag_deprecated(&mut self, latest_versions: &HashMap<String, u32>) {
+        for usage in &mut self.schemas {
+            usage.deprecated = usage.subjects.iter().any(|s| {
+                latest_versions
+                    .get(&s.subject)
+                    .is_some_and(|latest| s.version < *latest)
+            });
+        }
+    }
+
+    /// Proportion of the sampled records, in percent.
+    pub fn percentage(&self, records: usize) -> f64 {
+        match self.sampled {
+            0 => 0.,
+            sampled => records as f64 * 100. / sampled as f64,
+        }
+    }
+
+    /// Number of sampled records serialized with a deprecated schema version.
+    pub fn deprecated_records(&self) -> usize {
+        self.schemas
+            .iter()
+            .filter(|usage| usage.deprecated)
+            .map(|usage| usage.records)
+            .sum()
+    }
+}
+
+#[test]
+fn test_schema_usage_report() {
+    let mut report = SchemaUsageReport::new(
+        "payments",
+        [
+            Some(12),
+            Some(14),
+            None,
+            Some(14),
+            Some(12),
+            Some(14),
+            Some(3),
+        ],
+    );
+    assert_eq!(report.sampled, 7);
+    assert_eq!(report.without_schema, 1);
+    assert_eq!(
+        report
+            .schemas
+            .iter()
+            .map(|s| (s.id, s.records))
+            .collect_vec(),
+        vec![(14, 3), (12, 2), (3, 1)]
+    );
+
+    let subject = |version| {
+        vec![{
+            let mut new_version = SubjectVersion::default();
+            new_version.subject = "payments-value".to_string();
+            new_version.version = version;
+            new_version
+        }]
+    };
+    report.schemas[0].subjects = subject(3);
+    report.schemas[1].subjects = subject(2);
+    report.flag_deprecated(&HashMap::from([("payments-value".to_string(), 3)]));
+    assert_eq!(
+        report.schemas.iter().map(|s| s.deprecated).collect_vec(),
+        vec![false, true, false]
+    );
+    assert_eq!(report.deprecated_records(), 2);
+    assert_eq!(format!("{:.1}", report.percentage(2)), "28.6");
+}
@@ -2,11 +2,14 @@ use lib::{
     kafka::Comparable,
     search::{
         compare::{CompareExpression, NumberOperator, StringOperator},
-        filter::Filter,
+        filter::{Filter, Parameter},
         offset::FromOffset,
+        operand::as_operand,
     },
 };
 
+use serde_json::Value;
+
 use super::SearchContext;
 use crate::search::Search;
 
@@ -69,7 +72,18 @@ impl Search for CompareExpression {
                 NumberOperator::Equal => record.size == *s,
                 NumberOperator::NotEqual => record.size != *s,
             },
-            CompareExpression::Key(op, t) => record.key.compare(&None, op, t),
+            CompareExpression::Key(left, op, t) => record.key.compare(left, op, t),
+            CompareExpression::KeyNumber(op, n) => match record.key.as_i64() {
+                Some(key) => match op {
+                    NumberOperator::GreaterThan => key > *n,
+                    NumberOperator::GreaterOrEqual => key >= *n,
+                    NumberOperator::LowerThan => key < *n,
+                    NumberOperator::LowerOrEqual => key <= *n,
+                    NumberOperator::Equal => key == *n,
+                    NumberOperator::NotEqual => key != *n,
+                },
+                None => false,
+            },
             CompareExpression::Value(left, op, t) => record.value.compare(left, op, t),
             CompareExpression::Header(left, op, t) => {
                 let headers = &record.headers;
@@ -100,6 +114,27 @@ impl Search for CompareExpression {
                 let ts = record.timestamp_as_local_date_time().unwrap();
                 from <= &ts && &ts <= to
             }
+            CompareExpression::LogTimestamp(op, t) => {
+                let Some(ts) = record.log_timestamp_as_local_date_time() else {
+                    return false;
+                };
+                match op {
+                    NumberOperator::GreaterThan => ts > *t,
+                    NumberOperator::GreaterOrEqual => ts >= *t,
+                    NumberOperator::LowerThan => ts < *t,
+                    NumberOperator::LowerOrEqual => ts <= *t,
+                    NumberOperator::Equal => ts == *t,
+                    NumberOperator::NotEqual => ts != *t,
+                }
+            }
+            CompareExpression::LogTimestampBetween(from, to) => record
+                .log_timestamp_as_local_date_time()
+                .is_some_and(|ts| from <= &ts && &ts <= to),
+            CompareExpression::In(field, values) => is_one_of(&field.value_of(record), values),
+            CompareExpression::NotIn(field, values) => !is_one_of(&field.value_of(record), values),
+            CompareExpression::Operation(left, op, right) => {
+                compare_values(&left.evaluate(record), op, &right.evaluate(record))
+            }
         }
     }
 
@@ -108,6 +143,49 @@ impl Search for CompareExpression {
     }
 }
 
+/// Strings and numbers are compared as text, so `partition in ("0")` and `key in (42)` match too.
+/// A missing field is equal to none of the values.
+fn is_one_of(field: &Value, values: &[Parameter]) -> bool {
+    if field.is_null() {
+        return false;
+    }
+    let field = text(field);
+    values.iter().any(|v| match v {
+        Parameter::Number(n) => field == n.to_string(),
+        Parameter::String(s) => field == *s,
+        Parameter::Symbol(_) => false,
+    })
+}
+
+/// Numbers and RFC 3339 dates are compared as numbers, anything else as text. `null` never matches.
+fn compare_values(left: &Value, op: &NumberOperator, right: &Value) -> bool {
+    if left.is_null() || right.is_null() {
+        return false;
+    }
+    let ordering = match (as_operand(left), as_operand(right)) {
+        (Some(l), Some(r)) => l.as_f64().partial_cmp(&r.as_f64()),
+        _ => Some(text(left).cmp(&text(right))),
+    };
+    let Some(ordering) = ordering else {
+        return false;
+    };
+    match op {
+        NumberOperator::GreaterThan => ordering.is_gt(),
+        NumberOperator::GreaterOrEqual => ordering.is_ge(),
+        NumberOperator::LowerThan => ordering.is_lt(),
+        NumberOperator::LowerOrEqual => ordering.is_le(),
+        NumberOperator::Equal => ordering.is_eq(),
+        NumberOperator::NotEqual => ordering.is_ne(),
+    }
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        v => v.to_string(),
+    }
+}
+
 #[test]
 fn test_matches() {
     use crate::search::filter::CACHED_FILTERS;
@@ -115,20 +193,24 @@ fn test_matches() {
     use std::path::PathBuf;
 
     let compare = CompareExpression::Offset(NumberOperator::Equal, 42);
-    let record = KafkaRecord {
-        topic: "test-topic".to_string(),
-        partition: 0,
-        offset: 42,
-        key: lib::DataType::String("key".to_string()),
-        value: lib::DataType::String("value".to_string()),
-        timestamp: None,
-        headers: std::collections::BTreeMap::new(),
-        key_schema: None,
-        value_schema: None,
-        size: 12,
-        key_as_string: "key".to_string(),
-        value_as_string: "value".to_string(),
-    };
+    let mut record = KafkaRecord::default();
+    record.topic = "test-topic".to_string();
+    record.partition = 0;
+    record.offset = 42;
+    record.key = lib::DataType::String("key".to_string());
+    record.value = lib::DataType::String("value".to_string());
+    record.timestamp = None;
+    record.timestamp_type = None;
+    record.headers = std::collections::BTreeMap::new();
+    record.key_schema = None;
+    record.value_schema = None;
+    record.value_format = None;
+    record.size = 12;
+    record.key_as_string = "key".to_string();
+    record.value_as_string = "value".to_string();
+    record.raw_value = None;
+    record.readable_value = None;
+    record.truncated = false;
     let context = SearchContext {
         record: &record,
         filters: &CACHED_FILTERS,
@@ -137,3 +219,134 @@ fn test_matches() {
 
     assert!(compare.matches(&context))
 }
+
+#[test]
+fn test_matches_key_number() {
+    use crate::search::filter::CACHED_FILTERS;
+    use lib::kafka::KafkaRecord;
+    use std::path::PathBuf;
+
+    let mut record = KafkaRecord::default();
+    record.key = lib::DataType::Json(1_500.into());
+    let context = SearchContext {
+        record: &record,
+        filters: &CACHED_FILTERS,
+        filters_directory: PathBuf::from("."),
+    };
+
+    assert!(CompareExpression::KeyNumber(NumberOperator::GreaterThan, 1_000).matches(&context));
+    assert!(!CompareExpression::KeyNumber(NumberOperator::LowerThan, 1_000).matches(&context));
+}
+
+#[test]
+fn test_matches_key_path() {
+    use crate::search::filter::CACHED_FILTERS;
+    use lib::kafka::KafkaRecord;
+    use std::path::PathBuf;
+
+    let mut record = KafkaRecord::default();
+    record.key = lib::DataType::Json(serde_json::json!({"tenantId": "acme"}));
+    let context = SearchContext {
+        record: &record,
+        filters: &CACHED_FILTERS,
+        filters_directory: PathBuf::from("."),
+    };
+    let tenant =
+        |op, t: &str| CompareExpression::Key(Some(".tenantId".to_string()), op, t.to_string());
+
+    assert!(tenant(StringOperator::Equal, "acme").matches(&context));
+    assert!(!tenant(StringOperator::Equal, "globex").matches(&context));
+    assert!(
+        !CompareExpression::Key(None, StringOperator::Equal, "acme".to_string()).matches(&context)
+    );
+}
+
+#[test]
+fn test_matches_in() {
+    use crate::search::filter::CACHED_FILTERS;
+    use lib::{kafka::KafkaRecord, search::symbol::Symbol};
+    use std::path::PathBuf;
+
+    let mut record = KafkaRecord::default();
+    record.partition = 1;
+    record.value = lib::DataType::Json(serde_json::json!({"status": "TIMEOUT"}));
+    let context = SearchContext {
+        record: &record,
+        filters: &CACHED_FILTERS,
+        filters_directory: PathBuf::from("."),
+    };
+    let status = Symbol::Value(Some(".status".to_string()));
+    let statuses = vec![
+        Parameter::String("FAILED".to_string()),
+        Parameter::String("TIMEOUT".to_string()),
+    ];
+    let partitions = vec![Parameter::Number(0), Parameter::Number(1)];
+
+    assert!(CompareExpression::In(status.clone(), statuses.clone()).matches(&context));
+    assert!(!CompareExpression::NotIn(status, statuses).matches(&context));
+    assert!(!CompareExpression::NotIn(Symbol::Partition, partitions.clone()).matches(&context));
+    assert!(
+        !CompareExpression::In(Symbol::Value(Some(".missing".to_string())), partitions)
+            .matches(&context)
+    );
+}
+
+#[test]
+fn test_matches_operation() {
+    use crate::search::filter::CACHED_FILTERS;
+    use lib::{kafka::KafkaRecord, search::compare::parse_compare};
+    use std::path::PathBuf;
+
+    let mut record = KafkaRecord::default();
+    record.size = 20_480;
+    record.timestamp = Some(1_717_236_000_000);
+    record.value = lib::DataType::Json(serde_json::json!({
+        "items": [1, 2, 3, 4, 5, 6],
+        "type": "Order",
+        "delta": -0.75,
+        "shipped_at": "2024-06-01T12:00:45+02:00"
+    }));
+    let context = SearchContext {
+        record: &record,
+        filters: &CACHED_FILTERS,
+        filters_directory: PathBuf::from("."),
+    };
+    let matches = |input: &str| parse_compare(input).unwrap().1.matches(&context);
+
+    assert!(matches("size >= 1024 * 10"));
+    assert!(!matches("size > 1024 * 20"));
+    assert!(matches("len(value.items) > 5"));
+    assert!(matches(r#"lower(value.type) == "order""#));
+    assert!(matches("abs(value.delta) > 0.5"));
+    assert!(!matches("len(value.missing) != 0"));
+    assert!(matches("now() - timestamp > 1h"));
+    assert!(!matches("timestamp > now()"));
+    assert!(matches("value.shipped_at < now() - 1h"));
+    assert!(!matches("value.shipped_at > now() - 1h"));
+}
+
+#[test]
+fn test_matches_log_timestamp() {
+    use crate::search::filter::CACHED_FILTERS;
+    use lib::{TimestampType, kafka::KafkaRecord, search::compare::parse_compare};
+    use std::path::PathBuf;
+
+    let mut record = KafkaRecord::default();
+    record.timestamp = Some(1_717_236_000_000);
+    record.timestamp_type = Some(TimestampType::LogAppendTime);
+    let query = r#"log_ts > "2024-06-01T00:00:00.000Z""#;
+    let matches = |record: &KafkaRecord, input: &str| {
+        let context = SearchContext {
+            record,
+            filters: &CACHED_FILTERS,
+            filters_directory: PathBuf::from("."),
+        };
+        parse_compare(input).unwrap().1.matches(&context)
+    };
+
+    assert!(matches(&record, query));
+    assert!(!matches(&record, r#"log_ts < "2024-06-01T00:00:00.000Z""#));
+    record.timestamp_type = Some(TimestampType::CreateTime);
+    assert!(!matches(&record, query));
+    assert!(matches(&record, r#"ts > "2024-06-01T00:00:00.000Z""#));
+}
@@ -5,14 +5,13 @@ use std::{
 
 use extism::{Plugin, convert::Json};
 use itertools::Itertools;
-use lib::search::filter::Parameter;
 use lib::{
     FilterResult,
     search::filter::{Filter, FilterInput},
 };
 use tracing::error;
 
-use super::{Search, SearchContext};
+use super::{Search, SearchContext, matcher_of};
 
 pub const MATCHES_FUNCTION_NAME: &str = "matches";
 pub const PARSE_PARAMETERS_FUNCTION_NAME: &str = "parse_parameters";
@@ -23,11 +22,19 @@ pub(crate) static CACHED_FILTERS: LazyLock<Mutex<HashMap<String, Plugin>>> =
 
 impl Search for Filter {
     fn matches(&self, context: &SearchContext) -> bool {
+        let params = self
+            .parameters
+            .iter()
+            .map(|p| p.resolve(context.record))
+            .collect_vec();
+        if let Some(matcher) = matcher_of(&self.name) {
+            return matcher.matches(context.record, &params);
+        }
         let mut filters = context.filters.lock().unwrap();
         let plugin = &mut filters.get_mut(&self.name).unwrap();
         let input = FilterInput {
             record: context.record.clone(),
-            params: self.parameters.iter().map(Parameter::json).collect_vec(),
+            params,
         };
 
         match plugin
@@ -52,3 +59,26 @@ impl Search for Filter {
         vec![]
     }
 }
+
+#[test]
+fn test_matcher() {
+    use super::ValidSearchQuery;
+    use lib::{DataType, KafkaRecord, search::matcher::register_matcher};
+
+    register_matcher(
+        "is_even",
+        |_: &KafkaRecord, params: &[serde_json::Value]| {
+            params[0].as_i64().is_some_and(|n| n % 2 == 0)
+        },
+    );
+    let directory = std::path::PathBuf::from("/nonexistent");
+    let query = ValidSearchQuery::from("is_even(value.count)", &directory).unwrap();
+    let record = |count: i64| {
+        let mut record = KafkaRecord::default();
+        record.value = DataType::Json(serde_json::json!({ "count": count }));
+        record
+    };
+    assert!(query.matches(&SearchContext::new(&record(2), &directory)));
+    assert!(!query.matches(&SearchContext::new(&record(3), &directory)));
+    assert!(ValidSearchQuery::from("is_odd(value.count)", &directory).is_err());
+}
@@ -0,0 +1,143 @@
+//! The built-in `valid_against` search filter, matching the records whose value is valid against a local JSON Schema:
+//! ```sql
+//! from begin !valid_against("~/schemas/order.json")
+//! ```
+//! Relative paths are resolved from the current directory.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, LazyLock, Mutex},
+};
+
+use jsonschema::Validator;
+use lib::{
+    DataType, Error, KafkaRecord, SearchQuery,
+    search::{
+        filter::{Filter, Parameter},
+        matcher::Matcher,
+        visitor::Visitor,
+    },
+};
+use resolve_path::PathResolveExt;
+use serde_json::Value;
+
+/// Name of the search filter
+pub const VALID_AGAINST: &str = "valid_against";
+
+/// Compiled JSON schemas, by path.
+static VALIDATORS: LazyLock<Mutex<HashMap<PathBuf, Arc<Validator>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Matches the records whose value is valid against the JSON schema given as parameter.
+pub struct ValidAgainst;
+
+impl Matcher for ValidAgainst {
+    fn parse_parameters(&self, parameters: &[Value]) -> Result<(), String> {
+        match parameters {
+            [Value::String(path)] => validator_of(Path::new(path))
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            _ => Err("Expected the path of a JSON schema file".to_string()),
+        }
+    }
+
+    fn matches(&self, record: &KafkaRecord, parameters: &[Value]) -> bool {
+        let Some(path) = parameters.first().and_then(Value::as_str) else {
+            return false;
+        };
+        validator_of(Path::new(path))
+            .is_ok_and(|validator| validator.is_valid(&Value::from(record.value.clone())))
+    }
+}
+
+/// Loads and compiles the JSON schema, compiled schemas are cached.
+fn validator_of(path: &Path) -> Result<Arc<Validator>, Error> {
+    let path = path.resolve().to_path_buf();
+    let mut validators = VALIDATORS.lock().unwrap();
+    if let Some(validator) = validators.get(&path) {
+        return Ok(validator.clone());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| Error::Error(format!("Cannot read JSON schema '{}': {e}", path.display())))?;
+    let schema: Value = serde_json::from_str(&content)?;
+    let validator = jsonschema::validator_for(&schema)
+        .map_err(|e| Error::Error(format!("Invalid JSON schema '{}': {e}", path.display())))?;
+    let validator = Arc::new(validator);
+    validators.insert(path, validator.clone());
+    Ok(validator)
+}
+
+/// Returns the validation errors of the value against the JSON schema, empty when the value is valid.
+pub fn validate(schema: &Path, value: &DataType) -> Result<Vec<String>, Error> {
+    let value = Value::from(value.clone());
+    Ok(validator_of(schema)?
+        .iter_errors(&value)
+        .map(|e| match e.instance_path().as_str() {
+            "" => e.to_string(),
+            path => format!("{path}: {e}"),
+        })
+        .collect())
+}
+
+/// Returns the JSON schemas the search query validates the records against.
+pub fn json_schemas_of(query: &SearchQuery) -> Vec<PathBuf> {
+    struct JsonSchemas(Vec<PathBuf>);
+
+    impl Visitor for JsonSchemas {
+        fn visit_filter(&mut self, filter: &Filter) {
+            if let (VALID_AGAINST, [Parameter::String(path)]) =
+                (filter.name.as_str(), filter.parameters.as_slice())
+            {
+                self.0.push(PathBuf::from(path));
+            }
+        }
+    }
+
+    let mut schemas = JsonSchemas(vec![]);
+    schemas.visit_query(query);
+    schemas.0
+}
+
+#[test]
+fn test_valid_against() {
+    use lib::parse_search_query;
+
+    let directory = tempfile::tempdir().unwrap();
+    let schema = directory.path().join("order.json");
+    fs::write(
+        &schema,
+        r#"{"type": "object", "required": ["id"], "properties": {"id": {"type": "integer"}}}"#,
+    )
+    .unwrap();
+
+    let query = parse_search_query(&format!(
+        r#"from begin !valid_against("{}")"#,
+        schema.display()
+    ))
+    .unwrap()
+    .1;
+    assert_eq!(json_schemas_of(&query), vec![schema.clone()]);
+
+    let parameters = [Value::String(schema.display().to_string())];
+    assert!(ValidAgainst.parse_parameters(&parameters).is_ok());
+    assert!(ValidAgainst.parse_parameters(&[]).is_err());
+    assert!(
+        ValidAgainst
+            .parse_parameters(&[Value::String("/nonexistent.json".to_string())])
+            .is_err()
+    );
+
+    let record = |value: Value| {
+        let mut record = KafkaRecord::default();
+        record.value = DataType::Json(value);
+        record
+    };
+    assert!(ValidAgainst.matches(&record(serde_json::json!({ "id": 1 })), &parameters));
+    assert!(!ValidAgainst.matches(&record(serde_json::json!({ "id": "1" })), &parameters));
+    assert_eq!(
+        validate(&schema, &DataType::Json(serde_json::json!({ "id": "1" }))).unwrap(),
+        vec![r#"/id: "1" is not of type "integer""#]
+    );
+}
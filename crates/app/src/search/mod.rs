@@ -4,16 +4,19 @@ use extism::{Manifest, Plugin, Wasm};
 use filter::{CACHED_FILTERS, PARSE_PARAMETERS_FUNCTION_NAME};
 use itertools::Itertools;
 use lib::{
-    KafkaRecord, SearchQuery, parse_search_query,
+    KafkaRecord, SearchError, SearchQuery, parse_search_query,
     search::{
+        distinct::Distinct,
         filter::{Filter, Parameter},
+        matcher::{Matcher, matcher},
         offset::FromOffset,
+        sample::Sample,
     },
 };
 use std::{
     collections::HashMap,
     path::{Path, PathBuf},
-    sync::{LazyLock, Mutex},
+    sync::{Arc, LazyLock, Mutex},
 };
 use tracing::error;
 
@@ -21,6 +24,7 @@ pub mod atom;
 pub mod compare;
 pub mod expression;
 pub mod filter;
+pub mod json_schema;
 pub mod search_query;
 pub mod term;
 
@@ -58,29 +62,68 @@ impl SearchContext<'_> {
 }
 
 #[derive(Debug, Clone, PartialEq, Default)]
-pub struct ValidSearchQuery(SearchQuery);
+pub struct ValidSearchQuery {
+    query: SearchQuery,
+    /// The query as it was typed, see [`ValidSearchQuery::input`]
+    input: String,
+}
 
 impl ValidSearchQuery {
     pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
+        self.query.is_empty()
     }
 
     pub fn limit(&self) -> Option<usize> {
-        self.0.limit
+        self.query.limit
+    }
+
+    pub fn sample(&self) -> Option<Sample> {
+        self.query.sample
+    }
+
+    pub fn distinct(&self) -> Option<Distinct> {
+        self.query.distinct.clone()
     }
 
     pub fn query(&self) -> &SearchQuery {
-        &self.0
+        &self.query
+    }
+
+    /// Returns the query as it was typed, to be parsed again later, like in a link shared with someone else.
+    /// Unlike the display of [`SearchQuery`], strings are quoted and parentheses are kept.
+    pub fn input(&self) -> &str {
+        &self.input
     }
 }
 
 impl ValidSearchQuery {
     pub fn from(input: &str, filters_directory: &Path) -> Result<Self, lib::Error> {
         let query = parse_search_query(input).map_err(lib::Error::Search)?.1;
+        let mut valid = Self::from_query(query, filters_directory)?;
+        valid.input = input.trim().to_string();
+        Ok(valid)
+    }
+
+    /// Validates a query built or rewritten programmatically, its search filters are loaded from `filters_directory`.
+    /// A query cannot keep both a random sample and the latest record per distinct value.
+    pub fn from_query(query: SearchQuery, filters_directory: &Path) -> Result<Self, lib::Error> {
+        if query.sample.is_some() && query.distinct.is_some() {
+            return Err(lib::Error::Search(SearchError::IncompatibleClauses(
+                "sample",
+                "distinct by",
+            )));
+        }
         let filters = query.filters();
         for filter in filters {
             let name = filter.name;
-            let path = filters_directory.join(format!("{}.wasm", &name));
+            if let Some(matcher) = matcher_of(&name) {
+                let params = filter.parameters.iter().map(Parameter::json).collect_vec();
+                matcher
+                    .parse_parameters(&params)
+                    .map_err(|e| lib::Error::Error(format!("{name}: {e}")))?;
+                continue;
+            }
+            let path = filters_directory.join(format!("{name}.wasm"));
             let url = Wasm::file(&path);
             let manifest = Manifest::new([url]);
             let mut filters = CACHED_FILTERS.lock().unwrap();
@@ -106,26 +149,37 @@ impl ValidSearchQuery {
                 error!(
                     "Error when calling '{PARSE_PARAMETERS_FUNCTION_NAME}' from wasm module '{name}': {e:?}"
                 );
-                return Err(lib::Error::Error(format!("{}: {e}", &name)));
+                return Err(lib::Error::Error(format!("{name}: {e}")));
             }
         }
 
-        Ok(ValidSearchQuery(query))
+        Ok(ValidSearchQuery {
+            input: query.to_string(),
+            query,
+        })
     }
 }
 
+/// Returns the matcher registered under the given name, or the built-in one.
+pub(crate) fn matcher_of(name: &str) -> Option<Arc<dyn Matcher>> {
+    matcher(name).or_else(|| match name {
+        json_schema::VALID_AGAINST => Some(Arc::new(json_schema::ValidAgainst)),
+        _ => None,
+    })
+}
+
 impl Search for ValidSearchQuery {
     /// Returns the offset from which the search should start.
     fn offset(&self) -> Option<FromOffset> {
-        self.0.offset()
+        self.query.offset()
     }
 
     fn matches(&self, context: &SearchContext) -> bool {
-        self.0.matches(context)
+        self.query.matches(context)
     }
 
     fn filters(&self) -> Vec<Filter> {
-        self.0.filters()
+        self.query.filters()
     }
 }
 
@@ -141,11 +195,9 @@ mod tests {
         let input = "from begin";
         let query = ValidSearchQuery::from(input, &filters_directory).unwrap();
 
-        let record = KafkaRecord {
-            key: DataType::String("".into()),
-            value: DataType::String("".into()),
-            ..Default::default()
-        };
+        let mut record = KafkaRecord::default();
+        record.key = DataType::String("".into());
+        record.value = DataType::String("".into());
 
         let context = SearchContext::new(&record, &filters_directory);
         assert!(query.matches(&context));
@@ -158,6 +210,16 @@ mod tests {
         assert!(ValidSearchQuery::from(input, &filters_directory).is_err())
     }
 
+    #[test]
+    fn sample_and_distinct_are_incompatible() {
+        let filters_directory = PathBuf::from("tests/filters");
+        let input = "from begin distinct by key sample 10";
+        assert!(matches!(
+            ValidSearchQuery::from(input, &filters_directory),
+            Err(lib::Error::Search(SearchError::IncompatibleClauses(..)))
+        ));
+    }
+
     #[test]
     #[ignore]
     fn test_wasm_should_not_have_access_to_network() {
@@ -170,11 +232,9 @@ mod tests {
         let input = "from begin module()";
         let query = ValidSearchQuery::from(input, &filters_directory).unwrap();
 
-        let record = KafkaRecord {
-            key: DataType::String("".into()),
-            value: DataType::String("".into()),
-            ..Default::default()
-        };
+        let mut record = KafkaRecord::default();
+        record.key = DataType::String("".into());
+        record.value = DataType::String("".into());
 
         let context = SearchContext::new(&record, &filters_directory);
         assert!(!query.matches(&context));
@@ -207,20 +267,24 @@ mod tests {
             &filters_directory,
         )
         .unwrap();
-        let record = KafkaRecord {
-            topic: "test-topic".to_string(),
-            partition: 0,
-            offset: 42,
-            key: lib::DataType::String("key".to_string()),
-            value: lib::DataType::Json(json!({"myInteger": 42})),
-            timestamp: None,
-            headers: std::collections::BTreeMap::new(),
-            key_schema: None,
-            value_schema: None,
-            size: 12,
-            key_as_string: "key".to_string(),
-            value_as_string: "value".to_string(),
-        };
+        let mut record = KafkaRecord::default();
+        record.topic = "test-topic".to_string();
+        record.partition = 0;
+        record.offset = 42;
+        record.key = lib::DataType::String("key".to_string());
+        record.value = lib::DataType::Json(json!({"myInteger": 42}));
+        record.timestamp = None;
+        record.timestamp_type = None;
+        record.headers = std::collections::BTreeMap::new();
+        record.key_schema = None;
+        record.value_schema = None;
+        record.value_format = None;
+        record.size = 12;
+        record.key_as_string = "key".to_string();
+        record.value_as_string = "value".to_string();
+        record.raw_value = None;
+        record.readable_value = None;
+        record.truncated = false;
         let context = SearchContext {
             record: &record,
             filters: &CACHED_FILTERS,
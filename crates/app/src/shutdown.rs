@@ -0,0 +1,57 @@
+//! Signals asking the process to stop: ctrl-c, `SIGTERM` when the process is killed
+//! and `SIGHUP` when the terminal is closed.
+//! Listening to them replaces the default behavior, the process is no longer killed on the spot,
+//! it has a chance to complete the exports in progress and to restore the terminal.
+
+use lib::Error;
+
+#[cfg(unix)]
+use tokio::signal::unix::{Signal, SignalKind, signal};
+
+pub struct ShutdownSignals {
+    #[cfg(unix)]
+    interrupt: Signal,
+    #[cfg(unix)]
+    terminate: Signal,
+    #[cfg(unix)]
+    hangup: Signal,
+}
+
+impl ShutdownSignals {
+    /// Starts listening to the signals.
+    #[cfg(unix)]
+    pub fn new() -> Result<Self, Error> {
+        let listen = |kind: SignalKind| {
+            signal(kind)
+                .map_err(|e| Error::Error(format!("Cannot listen to the shutdown signals: {e}")))
+        };
+        Ok(Self {
+            interrupt: listen(SignalKind::interrupt())?,
+            terminate: listen(SignalKind::terminate())?,
+            hangup: listen(SignalKind::hangup())?,
+        })
+    }
+
+    /// Starts listening to the signals.
+    #[cfg(not(unix))]
+    pub fn new() -> Result<Self, Error> {
+        Ok(Self {})
+    }
+
+    /// Waits for the next signal and returns its name.
+    #[cfg(unix)]
+    pub async fn recv(&mut self) -> &'static str {
+        tokio::select! {
+            _ = self.interrupt.recv() => "SIGINT",
+            _ = self.terminate.recv() => "SIGTERM",
+            _ = self.hangup.recv() => "SIGHUP",
+        }
+    }
+
+    /// Waits for the next ctrl-c and returns its name.
+    #[cfg(not(unix))]
+    pub async fn recv(&mut self) -> &'static str {
+        let _ = tokio::signal::ctrl_c().await;
+        "CTRL_C"
+    }
+}
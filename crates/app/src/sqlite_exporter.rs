@@ -0,0 +1,246 @@
+//! Exports kafka records into a SQLite database, so they can be queried with SQL afterwards.
+//! Records are written to the `records` table, the key, the value and the headers are stored as JSON.
+//! The selected fields, the `select` clause of the search query or `export.fields`, are projected into their own column.
+//! A batch of records is inserted in a single transaction, SQLite would commit each record to the disk otherwise.
+//!
+//! ```sql
+//! select topic, offset, json_extract(value, '$.status') from records where "value.amount" > 100;
+//! ```
+
+use std::{fs, path::Path};
+
+use itertools::Itertools;
+use lib::{Error, KafkaRecord, search::symbol::Symbol};
+use rusqlite::{Connection, params_from_iter, types::Value as SqlValue};
+use serde_json::Value;
+
+/// Name of the table the records are written to
+pub const RECORDS_TABLE: &str = "records";
+
+/// Columns of the records table, before the projected columns
+const COLUMNS: [&str; 11] = [
+    "topic",
+    "partition",
+    "offset",
+    "timestamp",
+    "key",
+    "value",
+    "headers",
+    "key_schema",
+    "value_schema",
+    "size",
+    "search_query",
+];
+
+/// Returns `true` when records exported to this file should be written to a SQLite database.
+pub fn is_sqlite(file: &Path) -> bool {
+    file.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| ["db", "sqlite", "sqlite3"].contains(&e.to_lowercase().as_str()))
+}
+
+/// A SQLite database open for inserting records.
+pub(crate) struct SqliteDatabase {
+    connection: Connection,
+    select: Vec<Symbol>,
+    /// Number of records inserted since the database was opened
+    pub records: usize,
+}
+
+impl SqliteDatabase {
+    /// Opens the database, creates the records table and adds the columns of the selected fields if they are missing.
+    pub fn open(path: &Path, select: &[Symbol]) -> Result<Self, Error> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let connection = Connection::open(path).map_err(sqlite_error)?;
+        connection
+            .execute_batch(&format!(
+                "CREATE TABLE IF NOT EXISTS {RECORDS_TABLE} (
+                    topic TEXT NOT NULL,
+                    partition INTEGER NOT NULL,
+                    offset INTEGER NOT NULL,
+                    timestamp INTEGER,
+                    key,
+                    value,
+                    headers TEXT,
+                    key_schema TEXT,
+                    value_schema TEXT,
+                    size INTEGER,
+                    search_query TEXT,
+                    PRIMARY KEY (topic, partition, offset)
+                );"
+            ))
+            .map_err(sqlite_error)?;
+
+        let existing: Vec<String> = connection
+            .prepare(&format!(
+                "SELECT name FROM pragma_table_info('{RECORDS_TABLE}')"
+            ))
+            .and_then(|mut statement| {
+                statement
+                    .query_map([], |row| row.get(0))?
+                    .collect::<Result<_, _>>()
+            })
+            .map_err(sqlite_error)?;
+        let mut columns: Vec<Symbol> = vec![];
+        for field in select {
+            if columns.contains(field) || COLUMNS.contains(&field.field().as_str()) {
+                continue;
+            }
+            columns.push(field.clone());
+            let column = field.field();
+            if !existing.contains(&column) {
+                connection
+                    .execute(
+                        &format!(
+                            "ALTER TABLE {RECORDS_TABLE} ADD COLUMN {}",
+                            quote_identifier(&column)
+                        ),
+                        [],
+                    )
+                    .map_err(sqlite_error)?;
+            }
+        }
+        Ok(Self {
+            connection,
+            select: columns,
+            records: 0,
+        })
+    }
+
+    /// Inserts the record, a record already exported is replaced.
+    pub fn insert(&mut self, record: &KafkaRecord, search_query: &str) -> Result<(), Error> {
+        self.insert_all([record], search_query)
+    }
+
+    /// Inserts the records in a single transaction, records already exported are replaced.
+    pub fn insert_all<'a>(
+        &mut self,
+        records: impl IntoIterator<Item = &'a KafkaRecord>,
+        search_query: &str,
+    ) -> Result<(), Error> {
+        let columns = COLUMNS
+            .iter()
+            .map(|c| c.to_string())
+            .chain(self.select.iter().map(Symbol::field))
+            .map(|c| quote_identifier(&c))
+            .collect_vec();
+        let transaction = self.connection.transaction().map_err(sqlite_error)?;
+        let mut inserted = 0;
+        {
+            let mut statement = transaction
+                .prepare(&format!(
+                    "INSERT OR REPLACE INTO {RECORDS_TABLE} ({}) VALUES ({})",
+                    columns.join(", "),
+                    vec!["?"; columns.len()].join(", ")
+                ))
+                .map_err(sqlite_error)?;
+            for record in records {
+                statement
+                    .execute(params_from_iter(values(
+                        record,
+                        search_query,
+                        &self.select,
+                    )?))
+                    .map_err(sqlite_error)?;
+                inserted += 1;
+            }
+        }
+        transaction.commit().map_err(sqlite_error)?;
+        self.records += inserted;
+        Ok(())
+    }
+}
+
+/// Values of the columns of the records table, followed by the values of the selected fields.
+fn values(
+    record: &KafkaRecord,
+    search_query: &str,
+    select: &[Symbol],
+) -> Result<Vec<SqlValue>, Error> {
+    let mut values = vec![
+        SqlValue::Text(record.topic.clone()),
+        SqlValue::Integer(record.partition.into()),
+        SqlValue::Integer(record.offset),
+        record.timestamp.map_or(SqlValue::Null, SqlValue::Integer),
+        to_sql(record.key.clone().into()),
+        to_sql(record.value.clone().into()),
+        SqlValue::Text(serde_json::to_string(&record.headers)?),
+        to_sql(serde_json::to_value(&record.key_schema)?),
+        to_sql(serde_json::to_value(&record.value_schema)?),
+        SqlValue::Integer(i64::try_from(record.size).unwrap_or(i64::MAX)),
+        SqlValue::Text(search_query.to_string()),
+    ];
+    values.extend(select.iter().map(|field| to_sql(field.value_of(record))));
+    Ok(values)
+}
+
+/// JSON objects and arrays are stored as JSON text, so SQLite JSON functions such as `json_extract` can query them.
+fn to_sql(value: Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(b.into()),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => n.as_f64().map_or(SqlValue::Null, SqlValue::Real),
+        },
+        Value::String(s) => SqlValue::Text(s),
+        v => SqlValue::Text(v.to_string()),
+    }
+}
+
+fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+fn sqlite_error(e: rusqlite::Error) -> Error {
+    Error::Error(format!("SQLite error: {e}"))
+}
+
+#[test]
+fn test_export_to_sqlite() {
+    use lib::{DataType, parse_search_query};
+    use serde_json::json;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("export.db");
+    assert!(is_sqlite(&path));
+    let query = parse_search_query("from begin select value.amount, key")
+        .unwrap()
+        .1;
+    let record = |offset: i64, amount: Value| {
+        let mut record = KafkaRecord::default();
+        record.topic = "payments".to_string();
+        record.offset = offset;
+        record.key = DataType::String(offset.to_string());
+        record.value = DataType::Json(json!({"amount": amount, "status": "paid"}));
+        record
+    };
+
+    let mut database = SqliteDatabase::open(&path, &query.select).unwrap();
+    database.insert(&record(1, json!(12.5)), "").unwrap();
+    database
+        .insert_all(&[record(2, json!(30)), record(2, json!(40))], "")
+        .unwrap();
+    assert_eq!(database.records, 3);
+    drop(database);
+
+    let query = parse_search_query("select headers.tenant").unwrap().1;
+    let database = SqliteDatabase::open(&path, &query.select).unwrap();
+    let rows: Vec<(String, f64, String, Option<String>)> = database
+        .connection
+        .prepare(r#"SELECT key, "value.amount", json_extract(value, '$.status'), "headers.tenant" FROM records ORDER BY offset"#)
+        .unwrap()
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        rows,
+        vec![
+            ("1".to_string(), 12.5, "paid".to_string(), None),
+            ("2".to_string(), 40., "paid".to_string(), None)
+        ]
+    );
+}
@@ -0,0 +1,409 @@
+//! The watch mode turns yozefu into a lightweight topic monitor.
+//! Every record read by the consumer is checked against the alert rules of the configuration,
+//! whatever the current search query is. Records published before the search started,
+//! read from the beginning of the topic or replayed, never trigger an alert.
+//! Matching records are counted per minute, according to their timestamp.
+//! An alert is triggered once per minute and per rule, as soon as the count exceeds the threshold of the rule.
+//! Alerts can be sent to the HTTP notifiers of the configuration, with a templated message.
+
+use std::{collections::BTreeMap, io::Write, path::Path, process::Stdio};
+
+use chrono::{DateTime, Local, Utc};
+use indexmap::IndexMap;
+use itertools::Itertools;
+use lib::{Error, KafkaRecord, SearchQuery};
+use serde::Serialize;
+use serde_json::json;
+use tokio::process::Command;
+
+use crate::{
+    configuration::{AlertAction, AlertRule, Notifier},
+    search::{Search, SearchContext, ValidSearchQuery},
+};
+
+/// Number of minutes a count is kept, records can be read out of order across partitions
+const MINUTES_TO_KEEP: i64 = 10;
+
+/// Maximum number of characters of the value in the summary of a record
+const SUMMARY_VALUE_LENGTH: usize = 200;
+
+/// Alert rules and the number of records matching them per minute.
+#[derive(Debug, Clone, Default)]
+pub struct Watch {
+    rules: Vec<WatchedRule>,
+    /// Records older than this timestamp, in milliseconds since the epoch, are ignored
+    since: i64,
+}
+
+#[derive(Debug, Clone)]
+struct WatchedRule {
+    rule: AlertRule,
+    query: SearchQuery,
+    /// Notifiers the rule sends its alerts to
+    notifiers: IndexMap<String, Notifier>,
+    /// Number of matching records by minute since the epoch
+    counts: BTreeMap<i64, usize>,
+}
+
+/// An alert rule whose threshold has been exceeded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Alert {
+    /// Name of the rule
+    pub name: String,
+    pub query: String,
+    /// Number of records matching the query within the minute
+    pub count: usize,
+    pub threshold: usize,
+    /// Start of the minute, in milliseconds since the epoch
+    pub minute: i64,
+    /// Summary of the record that triggered the alert
+    pub record: String,
+    #[serde(skip)]
+    pub actions: Vec<AlertAction>,
+    #[serde(skip)]
+    pub notifiers: IndexMap<String, Notifier>,
+}
+
+impl Watch {
+    /// Parses the search queries of the rules and finds the notifiers they send their alerts to.
+    pub fn new(
+        rules: &[AlertRule],
+        notifiers: &IndexMap<String, Notifier>,
+        filters_directory: &Path,
+    ) -> Result<Self, Error> {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                let query =
+                    ValidSearchQuery::from(&rule.query, filters_directory).map_err(|e| {
+                        Error::Error(format!("Invalid query for alert '{}': {e}", rule.name))
+                    })?;
+                let notifiers = rule
+                    .actions
+                    .iter()
+                    .filter_map(|action| match action {
+                        AlertAction::Notify(name) => Some(name),
+                        _ => None,
+                    })
+                    .map(|name| match notifiers.get(name) {
+                        Some(notifier) => Ok((name.clone(), notifier.clone())),
+                        None => Err(Error::Error(format!(
+                            "Unknown notifier '{name}' for alert '{}'. Possible notifiers: [{}].",
+                            rule.name,
+                            notifiers.keys().join(", ")
+                        ))),
+                    })
+                    .collect::<Result<IndexMap<_, _>, _>>()?;
+                Ok(WatchedRule {
+                    rule: rule.clone(),
+                    query: query.query().clone(),
+                    notifiers,
+                    counts: BTreeMap::new(),
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(Self {
+            rules,
+            since: i64::MIN,
+        })
+    }
+
+    /// Ignores the records published before the given timestamp, in milliseconds since the epoch.
+    pub fn since(mut self, timestamp: i64) -> Self {
+        self.since = timestamp;
+        self
+    }
+
+    /// Counts the record for every rule it matches and returns the alerts it triggers.
+    /// Records without timestamp are counted at the current minute.
+    pub fn observe(&mut self, record: &KafkaRecord, filters_directory: &Path) -> Vec<Alert> {
+        if record.timestamp.is_some_and(|t| t < self.since) {
+            return vec![];
+        }
+        let minute = record
+            .timestamp
+            .unwrap_or_else(|| Utc::now().timestamp_millis())
+            .div_euclid(60_000);
+        let context = SearchContext::new(record, filters_directory);
+        let mut alerts = vec![];
+        for watched in &mut self.rules {
+            if !watched.query.matches(&context) {
+                continue;
+            }
+            let count = watched.counts.entry(minute).or_default();
+            *count += 1;
+            if *count == watched.rule.threshold + 1 {
+                alerts.push(Alert {
+                    name: watched.rule.name.clone(),
+                    query: watched.rule.query.clone(),
+                    count: *count,
+                    threshold: watched.rule.threshold,
+                    minute: minute * 60_000,
+                    record: summary_of(record),
+                    actions: watched.rule.actions.clone(),
+                    notifiers: watched.notifiers.clone(),
+                });
+            }
+            if let Some(latest) = watched.counts.keys().next_back().copied() {
+                watched.counts = watched.counts.split_off(&(latest - MINUTES_TO_KEEP));
+            }
+        }
+        alerts
+    }
+}
+
+impl Alert {
+    /// Runs an action of the alert.
+    /// The terminal bell is rung on the standard error, where the TUI is drawn.
+    pub async fn trigger(&self, action: &AlertAction) -> Result<(), Error> {
+        match action {
+            AlertAction::Bell => {
+                let mut output = std::io::stderr();
+                output.write_all(b"\x07")?;
+                output.flush()?;
+                Ok(())
+            }
+            AlertAction::Notification => {
+                let message = self.to_string();
+                let (program, args) = match cfg!(target_os = "macos") {
+                    true => (
+                        "osascript",
+                        vec![
+                            "-e".to_string(),
+                            format!("display notification {message:?} with title \"yozefu\""),
+                        ],
+                    ),
+                    false => ("notify-send", vec!["yozefu".to_string(), message]),
+                };
+                Self::run(Command::new(program).args(args), program).await
+            }
+            AlertAction::Command(command) => {
+                let (shell, flag) = match cfg!(target_os = "windows") {
+                    true => ("cmd", "/C"),
+                    false => ("sh", "-c"),
+                };
+                Self::run(
+                    Command::new(shell)
+                        .args([flag, command])
+                        .env("YOZEFU_ALERT_NAME", &self.name)
+                        .env("YOZEFU_ALERT_COUNT", self.count.to_string())
+                        .env("YOZEFU_ALERT_THRESHOLD", self.threshold.to_string()),
+                    command,
+                )
+                .await
+            }
+            AlertAction::Webhook(url) => {
+                reqwest::Client::new()
+                    .post(url.clone())
+                    .json(self)
+                    .send()
+                    .await
+                    .and_then(reqwest::Response::error_for_status)
+                    .map_err(|e| {
+                        Error::Error(format!("Cannot send alert '{}' to {url}: {e}", self.name))
+                    })?;
+                Ok(())
+            }
+            AlertAction::Notify(name) => match self.notifiers.get(name) {
+                Some(notifier) => self.notify(name, notifier).await,
+                None => Err(Error::Error(format!("Unknown notifier '{name}'"))),
+            },
+        }
+    }
+
+    /// Returns the message of the alert, the placeholders of the template are replaced by the details of the alert.
+    pub fn message(&self, template: &str) -> String {
+        template
+            .replace("{name}", &self.name)
+            .replace("{query}", &self.query)
+            .replace("{count}", &self.count.to_string())
+            .replace("{threshold}", &self.threshold.to_string())
+            .replace("{minute}", &self.local_minute())
+            .replace("{record}", &self.record)
+    }
+
+    async fn notify(&self, name: &str, notifier: &Notifier) -> Result<(), Error> {
+        let message = self.message(notifier.template());
+        let request = reqwest::Client::new().post(notifier.url().clone());
+        let request = match notifier {
+            Notifier::Webhook { headers, .. } => headers
+                .iter()
+                .fold(request, |request, (key, value)| request.header(key, value))
+                .json(&json!({
+                    "name": self.name,
+                    "query": self.query,
+                    "count": self.count,
+                    "threshold": self.threshold,
+                    "minute": self.minute,
+                    "record": self.record,
+                    "message": message,
+                })),
+            Notifier::Slack { .. } => request.json(&json!({ "text": message })),
+        };
+        request
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| {
+                Error::Error(format!(
+                    "Cannot send alert '{}' to notifier '{name}': {e}",
+                    self.name
+                ))
+            })?;
+        Ok(())
+    }
+
+    fn local_minute(&self) -> String {
+        DateTime::from_timestamp_millis(self.minute)
+            .unwrap_or_default()
+            .with_timezone(&Local)
+            .format("%H:%M")
+            .to_string()
+    }
+
+    async fn run(command: &mut Command, name: &str) -> Result<(), Error> {
+        let status = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await
+            .map_err(|e| Error::Error(format!("Cannot execute alert command '{name}': {e}")))?;
+        match status.success() {
+            true => Ok(()),
+            false => Err(Error::Error(format!(
+                "Alert command '{name}' failed ({status})"
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for Alert {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Alert '{}': more than {} records at {}",
+            self.name,
+            self.threshold,
+            self.local_minute()
+        )
+    }
+}
+
+/// Returns the topic, partition, offset, key and the beginning of the value of the record.
+fn summary_of(record: &KafkaRecord) -> String {
+    let mut value: String = record
+        .value_as_string
+        .chars()
+        .take(SUMMARY_VALUE_LENGTH)
+        .collect();
+    if value.len() < record.value_as_string.len() {
+        value.push('…');
+    }
+    format!(
+        "{}-{} offset {}, key {}, value {}",
+        record.topic, record.partition, record.offset, record.key_as_string, value
+    )
+}
+
+#[test]
+fn test_observe() {
+    let rules = vec![AlertRule {
+        name: "big records".to_string(),
+        query: "size > 100".to_string(),
+        threshold: 2,
+        actions: vec![],
+    }];
+    let mut watch = Watch::new(&rules, &IndexMap::new(), Path::new("/tmp")).unwrap();
+    let record = |size, timestamp| {
+        let mut record = KafkaRecord::default();
+        record.size = size;
+        record.timestamp = Some(timestamp);
+        record
+    };
+    let directory = Path::new("/tmp");
+    assert!(watch.observe(&record(1_000, 0), directory).is_empty());
+    assert!(watch.observe(&record(10, 1_000), directory).is_empty());
+    assert!(watch.observe(&record(1_000, 2_000), directory).is_empty());
+    let alerts = watch.observe(&record(1_000, 3_000), directory);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].count, 3);
+    assert_eq!(alerts[0].minute, 0);
+    // Once per minute
+    assert!(watch.observe(&record(1_000, 4_000), directory).is_empty());
+    for timestamp in [60_000, 61_000] {
+        assert!(
+            watch
+                .observe(&record(1_000, timestamp), directory)
+                .is_empty()
+        );
+    }
+    assert_eq!(watch.observe(&record(1_000, 62_000), directory).len(), 1);
+
+    // Records published before the search started are ignored
+    let mut watch = Watch::new(&rules, &IndexMap::new(), directory)
+        .unwrap()
+        .since(120_000);
+    for timestamp in [0, 1_000, 2_000, 119_000] {
+        assert!(
+            watch
+                .observe(&record(1_000, timestamp), directory)
+                .is_empty()
+        );
+    }
+    for timestamp in [120_000, 121_000] {
+        assert!(
+            watch
+                .observe(&record(1_000, timestamp), directory)
+                .is_empty()
+        );
+    }
+    assert_eq!(watch.observe(&record(1_000, 122_000), directory).len(), 1);
+
+    let rules = vec![AlertRule {
+        query: "size >".to_string(),
+        ..rules[0].clone()
+    }];
+    assert!(Watch::new(&rules, &IndexMap::new(), directory).is_err());
+}
+
+#[test]
+fn test_notifiers() {
+    let rules = vec![AlertRule {
+        name: "errors".to_string(),
+        query: "".to_string(),
+        threshold: 0,
+        actions: vec![AlertAction::Notify("slack".to_string())],
+    }];
+    let directory = Path::new("/tmp");
+    assert!(Watch::new(&rules, &IndexMap::new(), directory).is_err());
+
+    let notifier = Notifier::Slack {
+        url: "https://hooks.slack.com/services/T000/B000/XXXX"
+            .parse()
+            .unwrap(),
+        template: Some("{count} records matching `{query}`: {record}".to_string()),
+    };
+    let notifiers = IndexMap::from([("slack".to_string(), notifier.clone())]);
+    let mut watch = Watch::new(&rules, &notifiers, directory).unwrap();
+    let alerts = watch.observe(
+        &{
+            let mut record = KafkaRecord::default();
+            record.topic = "payments".to_string();
+            record.partition = 2;
+            record.offset = 42;
+            record.key_as_string = "abc".to_string();
+            record.value_as_string = "x".repeat(300);
+            record
+        },
+        directory,
+    );
+    assert_eq!(alerts[0].notifiers.get("slack"), Some(&notifier));
+    assert_eq!(
+        alerts[0].message(notifier.template()),
+        format!(
+            "1 records matching ``: payments-2 offset 42, key abc, value {}…",
+            "x".repeat(200)
+        )
+    );
+}
@@ -1,7 +1,10 @@
 use std::{fs, hash::DefaultHasher, path::PathBuf};
 
 use indexmap::IndexMap;
-use yozefu_app::configuration::{ConsumerConfig, GlobalConfig};
+use yozefu_app::configuration::{
+    ConsumerConfig, ExportConfig, GlobalConfig, HighlighterConfig, LayoutPreset, RefreshRates,
+    Units,
+};
 
 #[test]
 fn check_backwards_compatibility() {
@@ -14,11 +17,25 @@ fn check_backwards_compatibility() {
         initial_query: "from end - 10".to_string(),
         theme: "default".to_string(),
         default_kafka_config: IndexMap::new(),
+        client_id: "yozefu-{user}-{host}-{query}".to_string(),
         history: Vec::default(),
         show_shortcuts: false,
+        accessible: false,
         export_directory: PathBuf::from("./yozefu-exports"),
+        export: ExportConfig::default(),
         consumer: ConsumerConfig::default(),
         highlighter_theme: None,
+        highlighter: HighlighterConfig::default(),
+        layout: LayoutPreset::default(),
+        color_support: None,
+        clipboard: None,
+        units: Units::default(),
+        refresh_rates: RefreshRates::default(),
+        anonymization_profiles: IndexMap::new(),
+        alerts: vec![],
+        notifiers: IndexMap::new(),
+        macros: IndexMap::new(),
+        queries: IndexMap::new(),
     };
 
     let json = serde_json::to_string_pretty(&config).unwrap();
@@ -40,11 +40,23 @@ impl MyCli {
         ClusterConfig {
             url_template: None,
             schema_registry: None,
+            schema_registries: IndexMap::default(),
             kafka: IndexMap::from_iter(config.config_map().clone()),
             consumer: Some(ConsumerConfig {
                 buffer_capacity: 1000,
                 timeout_in_ms: 100,
+                value_preview_size: 64 * 1024,
+                scan_limit: None,
             }),
+            consumer_group: None,
+            producer: None,
+            secrets: IndexMap::default(),
+            key_decoders: IndexMap::default(),
+            value_decoders: IndexMap::default(),
+            masking_rules: IndexMap::default(),
+            traces: IndexMap::default(),
+            read_only: false,
+            protected: false,
         }
         .create(&cluster.to_string())
     }
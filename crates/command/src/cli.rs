@@ -7,13 +7,13 @@ use app::configuration::{
     ClusterConfig, GlobalConfig, SchemaRegistryConfig, Workspace, YozefuConfig,
 };
 use app::{APPLICATION_NAME, BINARY_NAME};
-use clap::command;
 use lib::Error;
 use reqwest::Url;
-use std::collections::HashMap;
 use std::fs;
+use std::io::{self, IsTerminal};
 use std::path::PathBuf;
 use tui::error::TuiError;
+use tui::{Onboarding, run_onboarding};
 
 pub use clap::Parser;
 use indexmap::IndexMap;
@@ -78,68 +78,114 @@ where
     }
 
     async fn run(&self, yozefu_config: Option<YozefuConfig>) -> Result<(), TuiError> {
-        self.init_files().await?;
+        let onboarding = match yozefu_config.is_none() && self.is_first_launch() {
+            true => run_onboarding()?,
+            false => None,
+        };
+        self.init_files(onboarding.as_ref()).await?;
         match &self.subcommands {
             Some(c) => c.execute().await.map_err(std::convert::Into::into),
             None => {
+                let mut command = self.default_command.clone();
+                command.open_link()?;
+                // The cluster configured with the wizard is used when '--cluster' is not provided
+                if let Some(onboarding) = onboarding
+                    && command.cluster().to_string().is_empty()
+                    && let Ok(cluster) = onboarding.cluster.parse()
+                {
+                    command.set_cluster(cluster);
+                }
                 // Load the config from the yozefu config file
                 let yozefu_config = match yozefu_config {
-                    None => self.default_command.yozefu_config()?,
+                    None => command.yozefu_config()?,
                     Some(c) => c,
                 };
-                let mut command = self.default_command.clone();
                 command.logs_file.clone_from(&self.logs_file);
-                command.execute(yozefu_config).await
+                let mut next_workspace = command.clone().execute(yozefu_config).await?;
+                // The TUI is started again when the user switches to another workspace
+                while let Some(name) = next_workspace {
+                    command.global.workspace = Some(name);
+                    command.global.config_file = None;
+                    let workspace = command.global.workspace();
+                    init_config_file(&workspace, None)?;
+                    init_themes_file(&workspace).await?;
+                    let yozefu_config = command.yozefu_config()?;
+                    next_workspace = command.clone().execute(yozefu_config).await?;
+                }
+                Ok(())
             }
         }
     }
 
+    /// Returns `true` when the TUI is started in a terminal and the configuration file does not exist yet.
+    fn is_first_launch(&self) -> bool {
+        self.subcommands.is_none()
+            && !self.default_command.headless
+            && io::stdin().is_terminal()
+            && io::stderr().is_terminal()
+            && fs::metadata(self.default_command.global.workspace().config_file()).is_err()
+    }
+
     /// Initializes a default configuration file if it does not exist.
-    /// The default cluster is `localhost`.
-    async fn init_files(&self) -> Result<(), Error> {
+    /// The default cluster is `localhost`, or the cluster configured with the onboarding wizard.
+    async fn init_files(&self, onboarding: Option<&Onboarding>) -> Result<(), Error> {
         let workspace = match &self.subcommands {
             Some(UtilityCommands::Config(c)) => &c.global,
             _ => &self.default_command.global,
         }
         .workspace();
 
-        init_config_file(&workspace)?;
+        init_config_file(&workspace, onboarding)?;
         init_themes_file(&workspace).await?;
         Ok(())
     }
 }
 
 /// Initializes a default configuration file if it does not exist.
-/// The default cluster is `localhost`.
-fn init_config_file(workspace: &Workspace) -> Result<PathBuf, Error> {
+/// The default cluster is `localhost` unless a cluster was configured with the onboarding wizard.
+fn init_config_file(
+    workspace: &Workspace,
+    onboarding: Option<&Onboarding>,
+) -> Result<PathBuf, Error> {
     let path = workspace.config_file();
     if fs::metadata(&path).is_ok() {
         return Ok(path);
     }
 
     let mut config = GlobalConfig::new(&path);
-    let mut localhost_config = IndexMap::new();
-    localhost_config.insert(
-        "bootstrap.servers".to_string(),
-        "localhost:9092".to_string(),
-    );
-    localhost_config.insert("security.protocol".to_string(), "plaintext".to_string());
-    localhost_config.insert("broker.address.family".to_string(), "v4".to_string());
     config
         .default_kafka_config
         .insert("fetch.min.bytes".to_string(), "10000".to_string());
+    if workspace.name() != Workspace::DEFAULT_WORKSPACE {
+        config.export_directory = workspace.path.join(Workspace::EXPORTS_DIRECTORY);
+    }
 
-    config.clusters.insert(
-        "localhost".into(),
-        ClusterConfig {
-            kafka: localhost_config,
-            schema_registry: Some(SchemaRegistryConfig {
-                url: Url::parse("http://localhost:8081").unwrap(),
-                headers: HashMap::default(),
-            }),
-            ..Default::default()
-        },
-    );
+    match onboarding {
+        Some(onboarding) => {
+            config
+                .clusters
+                .insert(onboarding.cluster.clone(), onboarding.config.clone());
+        }
+        None => {
+            let mut localhost_config = IndexMap::new();
+            localhost_config.insert(
+                "bootstrap.servers".to_string(),
+                "localhost:9092".to_string(),
+            );
+            localhost_config.insert("security.protocol".to_string(), "plaintext".to_string());
+            localhost_config.insert("broker.address.family".to_string(), "v4".to_string());
+            config.clusters.insert(
+                "localhost".into(),
+                ClusterConfig {
+                    kafka: localhost_config,
+                    schema_registry: Some(SchemaRegistryConfig::new(
+                        Url::parse("http://localhost:8081").unwrap(),
+                    )),
+                    ..Default::default()
+                },
+            );
+        }
+    }
 
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent).map_err(|e| {
@@ -208,7 +254,7 @@ fn initialize_config_file_on_readonly_root_partition() {
         perms.set_mode(0o000);
         let _ = fs::set_permissions(directory, perms);
         // suppose to return an error
-        let result = init_config_file(&workspace);
+        let result = init_config_file(&workspace, None);
         assert!(result.is_err());
     }
 }
@@ -0,0 +1,98 @@
+//! Command that prints the audit log of the workspace to `stdout`.
+//!
+//! ```bash
+//! yozf audit --cluster production --limit 20
+//! ```
+
+use app::{AuditEvent, AuditLog};
+use chrono::Local;
+use clap::Args;
+use lib::Error;
+use tracing::info;
+
+use crate::{GlobalArgs, command::Command};
+
+#[derive(Debug, Clone, Args)]
+pub struct AuditCommand {
+    /// Only print the actions run against this cluster
+    #[clap(long)]
+    cluster: Option<String>,
+    /// Only print the latest actions
+    #[clap(long)]
+    limit: Option<usize>,
+    /// Print the events as JSON, one per line
+    #[clap(long)]
+    json: bool,
+    #[command(flatten)]
+    global: GlobalArgs,
+}
+
+impl Command for AuditCommand {
+    async fn execute(&self) -> Result<(), Error> {
+        let path = self.global.workspace().audit_file();
+        info!("The audit log is located at '{}'", path.display());
+
+        for event in self.filter(AuditLog::new(&path).read()?) {
+            match self.json {
+                true => println!("{}", serde_json::to_string(&event)?),
+                false => println!(
+                    "{}  {}  {}  {}  [{}]  {}",
+                    event
+                        .timestamp
+                        .with_timezone(&Local)
+                        .format("%Y-%m-%d %H:%M:%S"),
+                    event.user,
+                    event.cluster,
+                    event.action,
+                    event.topics.join(", "),
+                    event.details
+                ),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl AuditCommand {
+    /// Keeps the events of the cluster, if any, and the latest `limit` ones.
+    fn filter(&self, events: Vec<AuditEvent>) -> Vec<AuditEvent> {
+        let events = events
+            .into_iter()
+            .filter(|event| self.cluster.as_ref().is_none_or(|c| &event.cluster == c))
+            .collect::<Vec<_>>();
+        let skip = events
+            .len()
+            .saturating_sub(self.limit.unwrap_or(usize::MAX));
+        events.into_iter().skip(skip).collect()
+    }
+}
+
+#[test]
+fn test_filter() {
+    use app::AuditAction;
+
+    let events = ["production", "staging", "production", "production"]
+        .iter()
+        .enumerate()
+        .map(|(i, cluster)| AuditEvent::new(cluster, AuditAction::Search, &[], &i.to_string()))
+        .collect::<Vec<_>>();
+    let command = AuditCommand {
+        cluster: Some("production".to_string()),
+        limit: Some(2),
+        json: false,
+        global: GlobalArgs::default(),
+    };
+    let details = command
+        .filter(events.clone())
+        .into_iter()
+        .map(|e| e.details)
+        .collect::<Vec<_>>();
+    assert_eq!(details, vec!["2", "3"]);
+
+    let command = AuditCommand {
+        cluster: None,
+        limit: None,
+        ..command
+    };
+    assert_eq!(command.filter(events).len(), 4);
+}
@@ -0,0 +1,89 @@
+//! Command that prints the configuration entries of the brokers of a cluster.
+//!
+//! ```bash
+//! yozf broker-configs --cluster localhost retention
+//! yozf broker-configs --cluster localhost --json > broker-configs.json
+//! yozf broker-configs --cluster localhost --quotas payments
+//! ```
+
+use app::App;
+use clap::Args;
+use itertools::Itertools;
+use lib::Error;
+
+use crate::{GlobalArgs, command::Command};
+
+#[derive(Debug, Clone, Args)]
+pub struct BrokerConfigsCommand {
+    /// Only print the entries whose name, value or source contains this text
+    filter: Option<String>,
+    /// The cluster of the brokers
+    #[clap(short, long)]
+    cluster: String,
+    /// Only print the entries whose value is not the default one
+    #[clap(long)]
+    non_default: bool,
+    /// Print the client quotas of the cluster instead of the configuration entries
+    #[clap(long)]
+    quotas: bool,
+    /// Print the entries as JSON
+    #[clap(long)]
+    json: bool,
+    #[command(flatten)]
+    global: GlobalArgs,
+}
+
+impl Command for BrokerConfigsCommand {
+    async fn execute(&self) -> Result<(), Error> {
+        let app = self.global.app(&self.cluster)?;
+        if self.quotas {
+            return self.print_quotas(&app).await;
+        }
+
+        let entries = app
+            .broker_configs()
+            .await?
+            .into_iter()
+            .filter(|e| e.matches(self.filter.as_deref().unwrap_or_default()))
+            .filter(|e| !self.non_default || e.source != "default")
+            .collect_vec();
+        match self.json {
+            true => println!("{}", serde_json::to_string_pretty(&entries)?),
+            false => {
+                for entry in entries {
+                    println!(
+                        "{:>6}  {}={}  ({})",
+                        entry.broker,
+                        entry.name,
+                        match entry.sensitive {
+                            true => "<sensitive>".to_string(),
+                            false => entry.value.unwrap_or_default(),
+                        },
+                        entry.source
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl BrokerConfigsCommand {
+    async fn print_quotas(&self, app: &App) -> Result<(), Error> {
+        let quotas = app
+            .client_quotas()
+            .await?
+            .into_iter()
+            .filter(|q| q.matches(self.filter.as_deref().unwrap_or_default()))
+            .collect_vec();
+        match self.json {
+            true => println!("{}", serde_json::to_string_pretty(&quotas)?),
+            false => {
+                for quota in quotas {
+                    println!("{}  {}={}", quota.entity, quota.name, quota.value);
+                }
+            }
+        }
+        Ok(())
+    }
+}
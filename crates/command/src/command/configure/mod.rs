@@ -2,6 +2,7 @@
 //! - `configure`
 //! - `configure get`
 //! - `configure set`
+//! - `configure themes`
 
 use std::{fs, process::Command};
 
@@ -13,9 +14,11 @@ use tracing::info;
 
 mod get_command;
 mod set_command;
+mod themes_command;
 
 pub use get_command::ConfigureGetCommand;
 pub use set_command::ConfigureSetCommand;
+pub use themes_command::ConfigureThemesCommand;
 
 use crate::GlobalArgs;
 
@@ -39,6 +42,8 @@ pub enum ConfigureSubCommand {
     Get(ConfigureGetCommand),
     /// Edit a specific property from the config file
     Set(ConfigureSetCommand),
+    /// Preview the themes of the themes file
+    Themes(ConfigureThemesCommand),
 }
 
 impl crate::command::Command for ConfigureSubCommand {
@@ -46,6 +51,7 @@ impl crate::command::Command for ConfigureSubCommand {
         match self {
             ConfigureSubCommand::Get(c) => c.execute().await,
             ConfigureSubCommand::Set(c) => c.execute().await,
+            ConfigureSubCommand::Themes(c) => c.execute().await,
         }
     }
 }
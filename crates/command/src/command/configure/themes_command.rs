@@ -0,0 +1,57 @@
+//! Commands related to the themes file.
+//!
+//! ```bash
+//! yozf config themes preview solarized-dark
+//! ```
+
+use app::configuration::read_themes;
+use clap::{Args, Subcommand};
+use itertools::Itertools;
+use lib::Error;
+use tui::{Theme, preview_theme};
+
+use crate::{GlobalArgs, command::Command as CliCommand, theme::init_themes_file};
+
+#[derive(Debug, Args, Clone)]
+pub struct ConfigureThemesCommand {
+    #[command(subcommand)]
+    subcommand: ThemesSubCommand,
+    #[clap(flatten)]
+    pub global: GlobalArgs,
+}
+
+#[derive(Debug, Subcommand, Clone)]
+pub enum ThemesSubCommand {
+    /// Render a sample screen with a theme. All the themes are rendered if no name is given.
+    /// 'auto' selects 'light' or 'dark' according to the background color of the terminal.
+    Preview {
+        /// Name of the theme
+        name: Option<String>,
+    },
+}
+
+impl CliCommand for ConfigureThemesCommand {
+    async fn execute(&self) -> Result<(), Error> {
+        let workspace = self.global.workspace();
+        let file = init_themes_file(&workspace).await?;
+        let color_support = workspace.config().color_support();
+        let themes = read_themes::<Theme>(&file)?;
+        match &self.subcommand {
+            ThemesSubCommand::Preview { name: Some(name) } => {
+                let name = Theme::resolve_name(name);
+                let theme = themes.get(name).ok_or(Error::Error(format!(
+                    "Theme '{}' not found. Available themes are [{}]",
+                    name,
+                    themes.keys().join(", ")
+                )))?;
+                preview_theme(&theme.clone().with_color_support(color_support))?;
+            }
+            ThemesSubCommand::Preview { name: None } => {
+                for theme in themes.into_values() {
+                    preview_theme(&theme.with_color_support(color_support))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
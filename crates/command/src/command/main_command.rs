@@ -11,21 +11,23 @@ use std::{fs, io};
 
 use app::configuration::{
     ClusterConfig, Configuration, GlobalConfig, InternalConfig, Workspace, YozefuConfig,
+    read_themes,
 };
 use app::search::ValidSearchQuery;
 
-use app::App;
+use app::{App, DeepLink};
 use clap::error::ErrorKind;
 use clap::{CommandFactory, Parser};
 use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
 use itertools::Itertools;
 use lib::Error;
+use lib::search::placeholder::{bind_placeholders, placeholders};
 use rdkafka::consumer::BaseConsumer;
 use strum::{Display, EnumString};
 use tracing::{debug, info, warn};
 use tui::Theme;
 use tui::error::TuiError;
-use tui::{State, Ui};
+use tui::{State, Ui, load_syntaxes};
 
 use crate::headless::Headless;
 use crate::headless::formatter::{
@@ -74,6 +76,12 @@ where
     pub headless: bool,
     /// The initial search query. If you start the query with the letter @, the rest should be a filename to read the data from, or - if you want yozefu to read the data from stdin.
     query: Vec<String>,
+    #[clap(long, value_name = "LINK", conflicts_with_all = ["topics", "query"])]
+    /// Link copied from the TUI, such as 'yozefu://cluster/prod?topics=orders&query=...'. It sets the cluster, the topics and the search query
+    pub uri: Option<String>,
+    #[clap(long = "param", value_name = "NAME=VALUE")]
+    /// Value of a ':name' placeholder of the search query, for instance '--param order_id=ord-42'
+    pub params: Vec<String>,
     #[clap(long)]
     /// Theme to use
     pub theme: Option<String>,
@@ -86,9 +94,33 @@ where
     #[clap(long, requires = "headless")]
     /// Export kafka records in the given file
     pub export: bool,
+    #[clap(long, requires = "headless")]
+    /// Print how the search query would be executed, the partitions, offsets and number of records to read, without consuming any record
+    pub explain: bool,
     #[clap(short, long)]
-    /// Name of the file to export kafka records
+    /// Name of the file to export kafka records. Supports the '{cluster}', '{topic}' and '{date}' placeholders. Records are written to a SQLite database when it ends with '.db', '.sqlite' or '.sqlite3'
     pub output: Option<PathBuf>,
+    #[clap(long)]
+    /// Name of the anonymization profile applied to exported kafka records
+    pub anonymize: Option<String>,
+    #[clap(long)]
+    /// Write the schemas of exported kafka records into a '.schemas.json' file next to the export file
+    pub export_schemas: bool,
+    #[clap(long)]
+    /// Disable the operations modifying the cluster, such as committing offsets or creating topics. It can also be set per cluster with the 'read_only' property
+    pub read_only: bool,
+    #[clap(long, conflicts_with = "headless")]
+    /// Record key strokes and kafka records of the TUI session in the given file
+    pub record_session: Option<PathBuf>,
+    #[clap(long, conflicts_with_all = ["headless", "record_session"])]
+    /// Replay a session recorded with '--record-session' instead of consuming kafka records
+    pub replay_session: Option<PathBuf>,
+    #[clap(long, default_value_t = 1.0, requires = "replay_session")]
+    /// Speed of the replay, '2' replays the session twice as fast
+    pub replay_speed: f64,
+    #[clap(long, conflicts_with = "replay_session")]
+    /// Resume the search from its last checkpoint instead of reading the topics from the beginning again. The search is checkpointed only with this flag and when it reads the history of the topics
+    pub resume: bool,
     #[command(flatten)]
     pub global: GlobalArgs,
     #[clap(skip)]
@@ -110,7 +142,12 @@ impl<T> MainCommand<T>
 where
     T: Cluster,
 {
-    pub async fn execute(self, mut yozefu_config: YozefuConfig) -> Result<(), TuiError> {
+    /// Runs the command.
+    /// It returns the name of the workspace the user switched to from the TUI, if any.
+    pub async fn execute(
+        self,
+        mut yozefu_config: YozefuConfig,
+    ) -> Result<Option<String>, TuiError> {
         for property in &self.properties {
             match property.split_once('=') {
                 Some((key, value)) => {
@@ -124,12 +161,30 @@ where
             }
         }
 
+        if let Some(profile) = &self.anonymize {
+            yozefu_config = yozefu_config.with_anonymization_profile(profile);
+        }
+        if let Some(output) = &self.output {
+            yozefu_config = yozefu_config.with_export_file(output.clone());
+        }
+        if self.export_schemas {
+            yozefu_config = yozefu_config.with_schemas_export();
+        }
+        if self.read_only {
+            yozefu_config = yozefu_config.with_read_only();
+        }
+
         match self.headless {
             true => {
-                let _ = init_logging_stderr(self.debug);
-                self.headless(&yozefu_config)
-                    .await
-                    .map_err(std::convert::Into::into)
+                let colored = self
+                    .global
+                    .workspace()
+                    .config()
+                    .color_support()
+                    .is_colored();
+                let _ = init_logging_stderr(self.debug, colored);
+                self.headless(&yozefu_config).await?;
+                Ok(None)
             }
             false => {
                 // Ignore the result, we just want to make sure the logger is initialized
@@ -142,14 +197,52 @@ where
         self.cluster.clone()
     }
 
+    pub(crate) fn set_cluster(&mut self, cluster: T) {
+        self.cluster = cluster;
+    }
+
+    /// Reads the cluster, the topics and the search query from the `--uri` link.
+    pub(crate) fn open_link(&mut self) -> Result<(), Error> {
+        let Some(uri) = &self.uri else {
+            return Ok(());
+        };
+        let link: DeepLink = uri.parse()?;
+        self.cluster = parse_cluster(&link.cluster)?;
+        self.query = vec![link.search_query()?];
+        self.topics = link.topics;
+        Ok(())
+    }
+
     /// Returns the kafka client config
     pub fn yozefu_config(&self) -> Result<YozefuConfig, Error> {
-        let cluster_config = self.cluster_config(&self.cluster)?;
+        let cluster_config = self.cluster_config(&self.cluster)?.resolve_secrets()?;
         Ok(cluster_config.create(&self.cluster.to_string()))
     }
 
-    /// Returns the search query to use.
+    /// Returns the search query to use, with the values of its placeholders.
     fn query(&self, initial_query: &str) -> Result<String, Error> {
+        let query = self.raw_query(initial_query)?;
+        if placeholders(&query).is_empty() {
+            return Ok(query);
+        }
+        let params = self
+            .params
+            .iter()
+            .map(|p| {
+                p.split_once('=')
+                    .map(|(name, value)| (name.trim().to_string(), value.to_string()))
+                    .ok_or_else(|| {
+                        Error::Error(format!(
+                            "Invalid parameter '{p}', it should be formatted as 'name=value'"
+                        ))
+                    })
+            })
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(bind_placeholders(&query, &params)?)
+    }
+
+    /// Returns the search query as typed, read from a file or from stdin.
+    fn raw_query(&self, initial_query: &str) -> Result<String, Error> {
         let q = self.query.join(" ").trim().to_string();
         if q.is_empty() {
             return Ok(initial_query.to_string());
@@ -187,19 +280,8 @@ where
                 .log_file
                 .clone()
                 .unwrap_or(workspace.log_file()),
-        ))
-    }
-
-    fn themes(file: &Path) -> Result<HashMap<String, Theme>, Error> {
-        let content = fs::read_to_string(file)?;
-        let themes: HashMap<String, Theme> = serde_json::from_str(&content).map_err(|e| {
-            Error::Error(format!(
-                "Error while parsing themes file '{}': {}",
-                file.display(),
-                e
-            ))
-        })?;
-        Ok(themes)
+        )
+        .with_root(workspace.root()))
     }
 
     // Validate the cluster name provided by the user.
@@ -233,25 +315,18 @@ where
     }
 
     fn read_config(&self) -> Result<GlobalConfig, Error> {
-        let workspace = self.global.workspace();
-
-        match GlobalConfig::read(workspace.config_file().as_path()) {
-            Ok(config) => {
-                //config.logs.clone_from(&self.logs_file);
-                Ok(config)
-            }
-            Err(e) => Err(e),
-        }
+        self.global.config()
     }
 
     async fn load_theme(workspace: &Workspace, name: &str) -> Result<Theme, Error> {
+        let name = Theme::resolve_name(name);
         let file = &workspace.themes_file();
-        let mut themes = Self::themes(file)?;
+        let mut themes = read_themes::<Theme>(file)?;
 
         if !themes.contains_key(name) {
             info!("Theme '{name}' not found. About to update theme file.");
             let _ = update_themes(workspace).await;
-            themes = Self::themes(file)?;
+            themes = read_themes::<Theme>(file)?;
         }
 
         let theme = match themes.get(name) {
@@ -282,7 +357,7 @@ where
     }
 
     /// Starts the app in TUI mode
-    async fn tui(&self, yozefu_config: &YozefuConfig) -> Result<(), TuiError> {
+    async fn tui(&self, yozefu_config: &YozefuConfig) -> Result<Option<String>, TuiError> {
         let cluster = self.cluster();
         let internal_config = self.internal_config(yozefu_config)?;
         let query = self.query(internal_config.initial_query())?;
@@ -292,6 +367,10 @@ where
             .theme
             .clone()
             .unwrap_or(internal_config.theme().to_string());
+        load_syntaxes(
+            &internal_config.workspace().config().highlighter,
+            &internal_config.workspace().themes_dir(),
+        );
         let color_palette = Self::load_theme(internal_config.workspace(), &theme_name).await?;
         let state = State::new(&cluster.to_string(), color_palette, &internal_config);
         let mut ui = Ui::new(
@@ -300,9 +379,18 @@ where
             self.topics.clone(),
             state.clone(),
         );
-
-        Self::check_connection(yozefu_config)?;
-        ui.run(self.topics.clone(), state).await
+        let global = self.global.clone();
+        ui = ui.with_config_overrides(Box::new(move |config| global.apply_overrides(config)));
+        if let Some(file) = &self.record_session {
+            ui = ui.with_session_recorder(file)?;
+        }
+        match &self.replay_session {
+            Some(file) => ui = ui.with_session_replay(file, self.replay_speed),
+            None => Self::check_connection(yozefu_config)?,
+        }
+        ui = ui.with_resume(self.resume);
+        ui.run(self.topics.clone(), state).await?;
+        Ok(ui.next_workspace())
     }
 
     fn check_connection(yozefu_config: &YozefuConfig) -> Result<(), Error> {
@@ -314,6 +402,7 @@ where
     fn app(&self, query: &str, config: InternalConfig) -> Result<App, Error> {
         debug!("{config:?}");
         let search_query = ValidSearchQuery::from(query, &config.workspace().filters_dir())?;
+        config.anonymization_profile()?;
 
         //let output_file = internal_config.output_file();
         Ok(App::new(self.cluster().to_string(), config, search_query))
@@ -329,12 +418,21 @@ where
         progress.set_draw_target(ProgressDrawTarget::hidden());
         progress.set_style(
             ProgressStyle::with_template(&format!(
-                "[{date} {{msg:.green}}  headless] {{pos}} records read {{per_sec}}"
+                "[{date} {{msg{}}}  headless] {{pos}} records read {{per_sec}}",
+                match internal_config.color_support().is_colored() {
+                    true => ":.green",
+                    false => "",
+                }
             ))
             .map_err(|e| Error::Error(e.to_string()))?,
         );
         progress.set_message("INFO");
         let topics = self.topics(yozefu_config)?;
+        if self.explain {
+            let plan = self.app(&query, internal_config)?.query_plan(&topics)?;
+            println!("{}", plan.lines().join("\n"));
+            return Ok(());
+        }
         if !self.disable_progress {
             progress.set_draw_target(ProgressDrawTarget::stderr());
         }
@@ -344,7 +442,8 @@ where
             self.formatter(),
             self.export,
             progress,
-        );
+        )
+        .with_resume(self.resume);
 
         self.print_full_command(&self.cluster().to_string(), &topics, &query);
 
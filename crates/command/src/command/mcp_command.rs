@@ -0,0 +1,27 @@
+//! Command that starts a Model Context Protocol server over `stdio`.
+//!
+//! ```bash
+//! yozf mcp --cluster localhost
+//! ```
+
+use clap::Args;
+use lib::Error;
+
+use crate::{GlobalArgs, command::Command, mcp::McpServer};
+
+#[derive(Debug, Clone, Args)]
+pub struct McpCommand {
+    /// The cluster the tools are run against
+    #[clap(short, long)]
+    cluster: String,
+    #[command(flatten)]
+    global: GlobalArgs,
+}
+
+impl Command for McpCommand {
+    async fn execute(&self) -> Result<(), Error> {
+        McpServer::new(self.global.cluster_config(&self.cluster)?)
+            .run()
+            .await
+    }
+}
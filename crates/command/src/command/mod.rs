@@ -3,11 +3,20 @@ use std::env::var;
 
 use lib::Error;
 
+mod audit_command;
+mod broker_configs_command;
 mod config_command;
 pub mod configure;
 mod create_filter;
 mod import_filter;
 mod main_command;
+mod mcp_command;
+mod offsets_command;
+mod plan_reassignment_command;
+mod probe_command;
+mod produce_command;
+mod schema_usage_command;
+mod serve_command;
 mod utility_commands;
 
 pub(crate) use create_filter::CreateFilterCommand;
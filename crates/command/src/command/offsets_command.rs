@@ -0,0 +1,73 @@
+//! Command that prints, for every partition of a topic, the offset corresponding to a given time.
+//! The output can be used to plan the reset of the offsets of a consumer group.
+//!
+//! ```bash
+//! yozf offsets payments --cluster localhost --timestamp "2024-09-17T06:44:59Z"
+//! yozf offsets payments --cluster localhost --timestamp "3 hours ago" --json
+//! ```
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use clap::Args;
+use lib::{Error, search::timestamp::parse_timestamp_argument};
+
+use crate::{GlobalArgs, command::Command};
+
+#[derive(Debug, Clone, Args)]
+pub struct OffsetsCommand {
+    /// The topic to look the offsets up in
+    topic: String,
+    /// The cluster of the topic
+    #[clap(short, long)]
+    cluster: String,
+    /// A RFC 3339 date time, a fuzzy date such as '3 hours ago', or a timestamp in milliseconds
+    #[clap(short, long, value_parser = parse_timestamp)]
+    timestamp: i64,
+    /// Print the offsets as JSON
+    #[clap(long)]
+    json: bool,
+    #[command(flatten)]
+    global: GlobalArgs,
+}
+
+impl Command for OffsetsCommand {
+    async fn execute(&self) -> Result<(), Error> {
+        let app = self.global.app(&self.cluster)?;
+
+        let offsets = app.offsets_for_timestamp(&self.topic, self.timestamp)?;
+        match self.json {
+            true => println!("{}", serde_json::to_string_pretty(&offsets)?),
+            false => {
+                let date_time = DateTime::<Utc>::from_timestamp_millis(offsets.timestamp)
+                    .map(|d| d.to_rfc3339_opts(SecondsFormat::Millis, true))
+                    .unwrap_or_default();
+                println!("Offsets of '{}' at {date_time}", offsets.topic);
+                for partition in offsets.partitions {
+                    println!("{:>9}  {}", partition.partition, partition.offset);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parses the timestamp argument into milliseconds since the epoch.
+fn parse_timestamp(input: &str) -> Result<i64, String> {
+    if let Ok(millis) = input.trim().parse::<i64>() {
+        return Ok(millis);
+    }
+    parse_timestamp_argument(input)
+        .map(|t| t.timestamp_millis())
+        .ok_or(format!(
+            "'{input}' is neither a RFC 3339 date time, a fuzzy date nor a timestamp in milliseconds"
+        ))
+}
+
+#[test]
+fn test_parse_timestamp() {
+    assert_eq!(parse_timestamp("1726555499000"), Ok(1_726_555_499_000));
+    assert_eq!(
+        parse_timestamp("2024-09-17T06:44:59Z"),
+        Ok(1_726_555_499_000)
+    );
+    assert!(parse_timestamp("yesterday at noon").is_err());
+}
@@ -0,0 +1,72 @@
+//! Command that prints the replicas of the partitions of topics and proposes a balanced reassignment.
+//! The reassignment is never executed, it is written in the format of `kafka-reassign-partitions`.
+//!
+//! ```bash
+//! yozf plan-reassignment payments orders --cluster localhost
+//! yozf plan-reassignment payments --cluster localhost --json > reassignment.json
+//! kafka-reassign-partitions --bootstrap-server localhost:9092 --reassignment-json-file reassignment.json --execute
+//! ```
+
+use app::ReassignmentPlan;
+use clap::Args;
+use itertools::Itertools;
+use lib::Error;
+
+use crate::{GlobalArgs, command::Command};
+
+#[derive(Debug, Clone, Args)]
+pub struct PlanReassignmentCommand {
+    /// The topics to reassign
+    #[clap(required = true)]
+    topics: Vec<String>,
+    /// The cluster of the topics
+    #[clap(short, long)]
+    cluster: String,
+    /// Only print the proposed reassignment, as JSON
+    #[clap(long)]
+    json: bool,
+    #[command(flatten)]
+    global: GlobalArgs,
+}
+
+impl Command for PlanReassignmentCommand {
+    async fn execute(&self) -> Result<(), Error> {
+        let app = self.global.app(&self.cluster)?;
+
+        let (brokers, assignments) = app.partition_assignments(&self.topics).await?;
+        let plan = ReassignmentPlan::balance(&brokers, &assignments);
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&plan)?);
+            return Ok(());
+        }
+
+        println!("Brokers");
+        for broker in &brokers {
+            println!(
+                "{:>9}  {}",
+                broker.id,
+                broker.rack.as_deref().unwrap_or("no rack")
+            );
+        }
+        println!("\nCurrent assignments");
+        for assignment in &assignments {
+            println!(
+                "{}  {:>9}  leader {}  replicas [{}]  isr [{}]",
+                assignment.topic,
+                assignment.partition,
+                assignment.leader,
+                assignment.replicas.iter().join(", "),
+                assignment.in_sync_replicas.iter().join(", ")
+            );
+        }
+        match plan.partitions.is_empty() {
+            true => println!("\nThe partitions are balanced, there is nothing to reassign"),
+            false => println!(
+                "\nProposed reassignment, {} partitions move\n{}",
+                plan.partitions.len(),
+                serde_json::to_string_pretty(&plan)?
+            ),
+        }
+        Ok(())
+    }
+}
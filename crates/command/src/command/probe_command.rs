@@ -0,0 +1,82 @@
+//! Command measuring the end-to-end latency of a cluster with canary records.
+//!
+//! ```bash
+//! yozf probe healthcheck --cluster localhost --interval-ms 500 --count 120
+//! yozf probe healthcheck --cluster production --confirm production
+//! ```
+
+use std::time::Duration;
+
+use app::LatencyStatistics;
+use clap::Args;
+use lib::Error;
+use tokio::sync::mpsc;
+
+use crate::{GlobalArgs, command::Command};
+
+/// Number of samples the percentiles are computed from
+const WINDOW: usize = 1_000;
+
+#[derive(Debug, Clone, Args)]
+pub struct ProbeCommand {
+    /// The topic canary records are produced to and consumed from
+    topic: String,
+    /// The cluster to probe
+    #[clap(short, long)]
+    cluster: String,
+    /// Time between two canary records, in milliseconds
+    #[clap(long, default_value_t = 1_000)]
+    interval_ms: u64,
+    /// Stops after this number of samples, the probe runs until it is interrupted otherwise
+    #[clap(long)]
+    count: Option<usize>,
+    /// Name of the cluster, required to probe a protected cluster
+    #[clap(long, default_value = "")]
+    confirm: String,
+    #[command(flatten)]
+    global: GlobalArgs,
+}
+
+impl Command for ProbeCommand {
+    async fn execute(&self) -> Result<(), Error> {
+        let app = self.global.app(&self.cluster)?;
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let topic = self.topic.clone();
+        let interval = Duration::from_millis(self.interval_ms.max(1));
+        let confirmation = self.confirm.clone();
+        let probe =
+            tokio::spawn(
+                async move { app.probe_latency(&topic, interval, &confirmation, tx).await },
+            );
+
+        let mut statistics = LatencyStatistics::new(WINDOW);
+        let mut received = 0;
+        while let Some(sample) = rx.recv().await {
+            statistics.push(sample);
+            received += 1;
+            println!(
+                "seq={} latency={}ms p50={}ms p95={}ms p99={}ms",
+                sample.sequence,
+                sample.latency.as_millis(),
+                millis(&statistics, 50.),
+                millis(&statistics, 95.),
+                millis(&statistics, 99.),
+            );
+            if self.count.is_some_and(|count| received >= count) {
+                break;
+            }
+        }
+        drop(rx);
+        probe
+            .await
+            .map_err(|e| Error::Error(format!("The latency probe failed: {e}")))?
+    }
+}
+
+fn millis(statistics: &LatencyStatistics, percentile: f64) -> u128 {
+    statistics
+        .percentile(percentile)
+        .unwrap_or_default()
+        .as_millis()
+}
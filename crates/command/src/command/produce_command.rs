@@ -0,0 +1,125 @@
+//! Command that produces records read from a NDJSON or CSV file, to seed the topics of a development environment.
+//!
+//! ```bash
+//! yozf produce payments --cluster localhost --file payments.ndjson --key id --header tenant
+//! yozf produce payments --cluster localhost --file payments.csv --key id --value-subject payments-value
+//! yozf produce payments --cluster localhost --template payment.json.template --count 10000 --rate 200 --key id
+//! yozf produce payments --cluster staging --file payments.ndjson --key id --transactional
+//! yozf produce payments --cluster production --file payments.ndjson --key id --confirm production
+//! ```
+
+use std::{num::NonZeroU32, path::PathBuf};
+
+use app::{InputFormat, ProduceOptions, RecordMapping, RecordTemplate, ValueSchema, read_records};
+use clap::Args;
+use lib::Error;
+use tracing::info;
+
+use crate::{GlobalArgs, command::Command};
+
+#[derive(Debug, Clone, Args)]
+pub struct ProduceCommand {
+    /// The topic records are produced to
+    topic: String,
+    /// The cluster records are produced to
+    #[clap(short, long)]
+    cluster: String,
+    /// NDJSON or CSV file to read the records from
+    #[clap(
+        short,
+        long,
+        required_unless_present = "template",
+        conflicts_with = "template"
+    )]
+    file: Option<PathBuf>,
+    /// Template generating the values of the records, with placeholders such as '{{uuid}}', '{{name}}' or '{{now}}'
+    #[clap(short, long)]
+    template: Option<PathBuf>,
+    /// Number of records generated from the template
+    #[clap(long, default_value_t = 1, requires = "template")]
+    count: usize,
+    /// Maximum number of records produced per second
+    #[clap(long)]
+    rate: Option<NonZeroU32>,
+    /// Produce the records inside a transaction, aborted when a record cannot be produced. Requires a 'transactional_id' in the producer configuration of the cluster
+    #[clap(long)]
+    transactional: bool,
+    /// Format of the file, 'ndjson' or 'csv'. It is guessed from the extension of the file by default
+    #[clap(long)]
+    format: Option<InputFormat>,
+    /// Field of each row used as the key of the record, for instance 'id' or 'customer.id'
+    #[clap(short, long)]
+    key: Option<String>,
+    /// Field of each row used as the value of the record, the whole row by default
+    #[clap(long)]
+    value: Option<String>,
+    /// Header of the record, written '<name>=<field>' or '<field>' when the header is named after the field
+    #[clap(long)]
+    header: Vec<String>,
+    /// Serialize the values with the schema of this id
+    #[clap(long, conflicts_with = "value_subject")]
+    value_schema_id: Option<u32>,
+    /// Serialize the values with the latest schema of this subject
+    #[clap(long)]
+    value_subject: Option<String>,
+    /// Name of the cluster, required to produce records to a protected cluster
+    #[clap(long, default_value = "")]
+    confirm: String,
+    #[command(flatten)]
+    global: GlobalArgs,
+}
+
+impl Command for ProduceCommand {
+    async fn execute(&self) -> Result<(), Error> {
+        let app = self.global.app(&self.cluster)?;
+
+        let (source, records) = match (&self.file, &self.template) {
+            (Some(file), _) => {
+                let format = self.format.unwrap_or_else(|| InputFormat::of(file));
+                (file, read_records(file, format, &self.mapping())?)
+            }
+            (None, Some(template)) => (
+                template,
+                RecordTemplate::read(template)?.records(self.count, &self.mapping()),
+            ),
+            (None, None) => unreachable!("clap requires a file or a template"),
+        };
+        let value_schema = match self.value_schema() {
+            Some(schema) => Some(app.value_schema(&self.topic, &schema).await?),
+            None => None,
+        };
+        let options = ProduceOptions {
+            value_schema,
+            rate: self.rate,
+            transactional: self.transactional,
+            confirmation: self.confirm.clone(),
+        };
+        let produced = app.produce(&self.topic, &records, &options).await?;
+        info!(
+            "{produced} records of '{}' have been produced to topic '{}'",
+            source.display(),
+            self.topic
+        );
+        Ok(())
+    }
+}
+
+impl ProduceCommand {
+    fn mapping(&self) -> RecordMapping {
+        RecordMapping {
+            key: self.key.clone(),
+            value: self.value.clone(),
+            headers: self
+                .header
+                .iter()
+                .map(|h| RecordMapping::parse_header(h))
+                .collect(),
+        }
+    }
+
+    fn value_schema(&self) -> Option<ValueSchema> {
+        self.value_schema_id
+            .map(ValueSchema::Id)
+            .or_else(|| self.value_subject.clone().map(ValueSchema::Subject))
+    }
+}
@@ -0,0 +1,87 @@
+//! Command that reports the schemas the values of a topic are actually serialized with, in a sample of its latest records.
+//!
+//! ```bash
+//! yozf schema-usage payments --cluster localhost --sample 5000
+//! yozf schema-usage payments --cluster localhost --json
+//! ```
+
+use app::SchemaUsageReport;
+use clap::Args;
+use itertools::Itertools;
+use lib::Error;
+use thousands::Separable;
+
+use crate::{GlobalArgs, command::Command};
+
+#[derive(Debug, Clone, Args)]
+pub struct SchemaUsageCommand {
+    /// The topic to sample
+    topic: String,
+    /// The cluster of the topic
+    #[clap(short, long)]
+    cluster: String,
+    /// Number of records read, the latest records of each partition
+    #[clap(long, default_value_t = 1_000)]
+    sample: usize,
+    /// Print the report as JSON
+    #[clap(long)]
+    json: bool,
+    #[command(flatten)]
+    global: GlobalArgs,
+}
+
+impl Command for SchemaUsageCommand {
+    async fn execute(&self) -> Result<(), Error> {
+        let app = self.global.app(&self.cluster)?;
+
+        let report = app.schema_usage(&self.topic, self.sample).await?;
+        match self.json {
+            true => println!("{}", serde_json::to_string_pretty(&report)?),
+            false => print_report(&report),
+        }
+        Ok(())
+    }
+}
+
+fn print_report(report: &SchemaUsageReport) {
+    println!(
+        "Schemas of '{}', {} records sampled",
+        report.topic,
+        report.sampled.separate_with_underscores()
+    );
+    for usage in &report.schemas {
+        let subjects = match usage.subjects.is_empty() {
+            true => "not registered".to_string(),
+            false => usage
+                .subjects
+                .iter()
+                .map(|s| format!("{} v{}", s.subject, s.version))
+                .join(", "),
+        };
+        println!(
+            "{:>9}  {:>9}  {:>5.1} %  {subjects}{}",
+            usage.id,
+            usage.records,
+            report.percentage(usage.records),
+            match usage.deprecated {
+                true => "  deprecated",
+                false => "",
+            }
+        );
+    }
+    if report.without_schema > 0 {
+        println!(
+            "{:>9}  {:>9}  {:>5.1} %  without schema",
+            "-",
+            report.without_schema,
+            report.percentage(report.without_schema)
+        );
+    }
+    let deprecated = report.deprecated_records();
+    if deprecated > 0 {
+        println!(
+            "{deprecated} records ({:.1} %) are serialized with a deprecated schema version",
+            report.percentage(deprecated)
+        );
+    }
+}
@@ -0,0 +1,57 @@
+//! Command that starts an HTTP API server.
+//!
+//! ```bash
+//! yozf serve --cluster localhost --address 127.0.0.1:8080
+//! curl 'http://127.0.0.1:8080/search?topics=payments&query=from%20begin%20limit%2010'
+//! ```
+
+use std::net::SocketAddr;
+
+use clap::Args;
+use lib::Error;
+
+use crate::{
+    GlobalArgs,
+    command::Command,
+    serve::{ApiServer, SearchBounds},
+};
+
+#[derive(Debug, Clone, Args)]
+pub struct ServeCommand {
+    /// The cluster the requests are run against
+    #[clap(short, long)]
+    cluster: String,
+    /// Address the server listens on
+    #[clap(long, default_value = "127.0.0.1:8080")]
+    address: SocketAddr,
+    /// Address the gRPC service streaming search results listens on, it is disabled by default
+    #[cfg(feature = "grpc")]
+    #[clap(long)]
+    grpc_address: Option<SocketAddr>,
+    /// Maximum number of records a search may ask for, larger limits are rejected
+    #[clap(long, default_value_t = 10_000)]
+    max_limit: usize,
+    /// Maximum time a search may spend reading the topics, in milliseconds, larger timeouts are rejected
+    #[clap(long, default_value_t = 60_000)]
+    max_timeout_ms: u64,
+    #[command(flatten)]
+    global: GlobalArgs,
+}
+
+impl Command for ServeCommand {
+    async fn execute(&self) -> Result<(), Error> {
+        let server = ApiServer::new(
+            self.global.cluster_config(&self.cluster)?,
+            SearchBounds {
+                max_limit: self.max_limit,
+                max_timeout_ms: self.max_timeout_ms,
+            },
+        );
+        #[cfg(feature = "grpc")]
+        if let Some(grpc_address) = self.grpc_address {
+            tokio::try_join!(server.run(self.address), server.run_grpc(grpc_address))?;
+            return Ok(());
+        }
+        server.run(self.address).await
+    }
+}
@@ -1,13 +1,18 @@
 //! Other CLI commands to make the tool user-friendly.
 
+use app::configuration::ColorSupport;
 use clap::Subcommand;
 use lib::Error;
 
 use crate::log::init_logging_stderr;
 
 use super::{
-    Command, CreateFilterCommand, ImportFilterCommand, config_command::ConfigCommand,
-    configure::ConfigureCommand,
+    Command, CreateFilterCommand, ImportFilterCommand, audit_command::AuditCommand,
+    broker_configs_command::BrokerConfigsCommand, config_command::ConfigCommand,
+    configure::ConfigureCommand, mcp_command::McpCommand, offsets_command::OffsetsCommand,
+    plan_reassignment_command::PlanReassignmentCommand, probe_command::ProbeCommand,
+    produce_command::ProduceCommand, schema_usage_command::SchemaUsageCommand,
+    serve_command::ServeCommand,
 };
 
 #[derive(Subcommand, Debug)]
@@ -22,16 +27,43 @@ pub enum UtilityCommands {
     Configure(ConfigureCommand),
     /// Print the config to `stdout`
     Config(ConfigCommand),
+    /// Print the actions run against the clusters: searches and exports
+    Audit(AuditCommand),
+    /// Start a Model Context Protocol server over stdio, exposing the search engine, topics and schemas as tools
+    Mcp(McpCommand),
+    /// Start an HTTP API server to list topics, search records and fetch schemas
+    Serve(ServeCommand),
+    /// Produce records read from a NDJSON or CSV file, to seed the topics of a development environment
+    Produce(ProduceCommand),
+    /// Measure the end-to-end latency of a cluster by producing canary records to a topic and consuming them back
+    Probe(ProbeCommand),
+    /// Print the offset corresponding to a timestamp for every partition of a topic, to plan the reset of the offsets of a consumer group
+    Offsets(OffsetsCommand),
+    /// Print the replicas of the partitions of topics and propose a balanced reassignment, without executing it
+    PlanReassignment(PlanReassignmentCommand),
+    /// Print the configuration entries of the brokers of a cluster
+    BrokerConfigs(BrokerConfigsCommand),
+    /// Print the schemas the values of a topic are serialized with, in a sample of its latest records, and flag the deprecated schema versions
+    SchemaUsage(SchemaUsageCommand),
 }
 
 impl Command for UtilityCommands {
     async fn execute(&self) -> Result<(), Error> {
-        let _ = init_logging_stderr(false);
+        let _ = init_logging_stderr(false, ColorSupport::detect().is_colored());
         match self {
             Self::ImportFilter(command) => command.execute().await,
             Self::CreateFilter(command) => command.execute().await,
             Self::Configure(command) => command.execute().await,
             Self::Config(command) => command.execute().await,
+            Self::Audit(command) => command.execute().await,
+            Self::Mcp(command) => command.execute().await,
+            Self::Serve(command) => command.execute().await,
+            Self::Produce(command) => command.execute().await,
+            Self::Probe(command) => command.execute().await,
+            Self::Offsets(command) => command.execute().await,
+            Self::PlanReassignment(command) => command.execute().await,
+            Self::BrokerConfigs(command) => command.execute().await,
+            Self::SchemaUsage(command) => command.execute().await,
         }
     }
 }
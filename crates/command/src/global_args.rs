@@ -1,7 +1,19 @@
-use app::configuration::{GlobalConfig, Workspace};
+use app::{
+    App,
+    configuration::{GlobalConfig, InternalConfig, Workspace},
+    search::ValidSearchQuery,
+};
 use clap::Args;
-use std::path::PathBuf;
-use tracing::debug;
+use itertools::Itertools;
+use lib::Error;
+use std::{env, path::PathBuf};
+use tracing::{debug, warn};
+
+/// Prefix of the environment variables overriding the configuration
+const ENV_PREFIX: &str = "YOZEFU_";
+
+/// Environment variables that are already bound to a command line argument
+const RESERVED_ENV_VARS: [&str; 3] = ["YOZEFU_CONFIG_DIR", "YOZEFU_LOG_FILE", "YOZEFU_WORKSPACE"];
 
 #[derive(Args, Clone, Debug, Default)]
 pub struct GlobalArgs {
@@ -11,23 +23,81 @@ pub struct GlobalArgs {
     #[arg(long, env = "YOZEFU_CONFIG_DIR", global = true)]
     /// Use a specific config directory to store the configuration, logs, search filters.
     pub config_dir: Option<PathBuf>,
+    #[arg(long, env = "YOZEFU_WORKSPACE", value_parser = parse_workspace, global = true)]
+    /// Use a named workspace: its configuration, history, themes, filters and exports are isolated in the 'workspaces' directory of the config directory.
+    pub workspace: Option<String>,
     #[arg(long, env = "YOZEFU_LOG_FILE", global = true)]
     /// Append logs to a specific log file
     pub log_file: Option<PathBuf>,
+    #[arg(long = "set", value_name = "PROPERTY=VALUE", value_parser = parse_override, global = true)]
+    /// Override a property of the configuration, for instance '--set /theme=dark'. It takes precedence over 'YOZEFU_*' environment variables.
+    pub overrides: Vec<(String, String)>,
+    #[arg(long, global = true)]
+    /// Disable colors in the TUI, the logs and the headless output, like the 'NO_COLOR' environment variable.
+    pub no_color: bool,
+}
+
+fn parse_override(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((property, value)) if !property.trim().is_empty() => {
+            Ok((property.trim().to_string(), value.to_string()))
+        }
+        _ => Err(format!(
+            "'{s}' is not a valid override, expected format is 'PROPERTY=VALUE'"
+        )),
+    }
+}
+
+fn parse_workspace(s: &str) -> Result<String, String> {
+    Workspace::validate_name(s)
+        .map(|()| s.to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// Converts `YOZEFU_*` environment variables to overrides.
+/// `__` separates the segments of the property: `YOZEFU_CONSUMER__BUFFER_CAPACITY` overrides `/consumer/buffer_capacity`.
+fn env_overrides(vars: impl Iterator<Item = (String, String)>) -> Vec<(String, String)> {
+    vars.filter(|(name, _)| !RESERVED_ENV_VARS.contains(&name.as_str()))
+        .filter_map(|(name, value)| {
+            let property = name.strip_prefix(ENV_PREFIX)?;
+            let property = property
+                .split("__")
+                .map(str::to_lowercase)
+                .collect::<Vec<_>>()
+                .join("/");
+            Some((format!("/{property}"), value))
+        })
+        .collect()
+}
+
+/// Unknown properties are dropped when the configuration is deserialized, an overridden property that does not exist anymore is unknown.
+fn has_property(config: &GlobalConfig, property: &str) -> bool {
+    serde_json::to_value(config)
+        .ok()
+        .is_some_and(|c| c.pointer(property).is_some())
 }
 
 impl GlobalArgs {
     pub fn workspace(&self) -> Workspace {
         let default_workspace = Workspace::default();
 
-        let (workspace_dir, config_file) = match (&self.config_dir, &self.config_file) {
-            (Some(dir), Some(file)) => (dir, file),
-            (Some(dir), None) => (dir, &dir.join(Workspace::CONFIG_FILENAME)),
-            (None, Some(file)) => (&default_workspace.path, file),
-            (None, None) => (&default_workspace.path, &default_workspace.config_file()),
+        let root = self.config_dir.as_ref().unwrap_or(&default_workspace.path);
+        let workspace_dir = &Workspace::directory_of(
+            root,
+            self.workspace
+                .as_deref()
+                .unwrap_or(Workspace::DEFAULT_WORKSPACE),
+        );
+        let config_file = match &self.config_file {
+            Some(file) => file,
+            None => &workspace_dir.join(Workspace::CONFIG_FILENAME),
         };
 
         let config = GlobalConfig::read(config_file).unwrap_or(GlobalConfig::new(config_file));
+        let config = self.apply_overrides(config.clone()).unwrap_or_else(|e| {
+            warn!("The overrides of the configuration are ignored: {e}");
+            config
+        });
 
         let log_file = match &self.log_file {
             Some(log_file) => log_file.clone(),
@@ -38,10 +108,70 @@ impl GlobalArgs {
                 .clone(),
         };
 
-        let workspace = Workspace::new(workspace_dir, config, log_file);
+        let workspace = Workspace::new(workspace_dir, config, log_file).with_root(root);
         debug!("Using config directory: {}", workspace.path.display());
         workspace
     }
+
+    /// Reads the configuration file and applies the overrides.
+    /// The precedence order is: `--set` arguments, then `YOZEFU_*` environment variables, then the configuration file.
+    pub fn config(&self) -> Result<GlobalConfig, Error> {
+        let config = GlobalConfig::read(&self.workspace().config_file())?;
+        self.apply_overrides(config)
+    }
+
+    /// Returns the configuration of the given cluster, its secrets resolved.
+    pub fn cluster_config(&self, cluster: &str) -> Result<InternalConfig, Error> {
+        let config = self.config()?;
+        let cluster_config = config.clusters.get(cluster).ok_or(Error::Error(format!(
+            "Unknown cluster '{cluster}'. Possible clusters: [{}].",
+            config.clusters.keys().join(", ")
+        )))?;
+        let yozefu_config = cluster_config.clone().resolve_secrets()?.create(cluster);
+        Ok(InternalConfig::new(yozefu_config, self.workspace()))
+    }
+
+    /// Returns the app of the given cluster for the commands that do not search records.
+    pub fn app(&self, cluster: &str) -> Result<App, Error> {
+        Ok(App::new(
+            cluster.to_string(),
+            self.cluster_config(cluster)?,
+            ValidSearchQuery::default(),
+        ))
+    }
+
+    /// Applies the overrides, both [`Self::workspace`] and [`Self::config`] rely on it,
+    /// and so does the TUI each time the configuration file is reloaded.
+    pub fn apply_overrides(&self, config: GlobalConfig) -> Result<GlobalConfig, Error> {
+        let mut env_vars = env::vars().collect::<Vec<_>>();
+        env_vars.sort();
+        self.apply_overrides_with(config, env_vars)
+    }
+
+    /// `YOZEFU_*` variables are not always meant for yozefu, those that do not match a property
+    /// or cannot be applied are ignored with a warning. An invalid `--set` argument is an error.
+    fn apply_overrides_with(
+        &self,
+        mut config: GlobalConfig,
+        env_vars: Vec<(String, String)>,
+    ) -> Result<GlobalConfig, Error> {
+        for (property, value) in env_overrides(env_vars.into_iter()) {
+            match config.clone().with_override(&property, &value) {
+                Ok(overridden) if has_property(&overridden, &property) => config = overridden,
+                Ok(_) => warn!(
+                    "Environment variable overriding '{property}' is ignored: there is no such property in the configuration"
+                ),
+                Err(e) => warn!("Environment variable overriding '{property}' is ignored: {e}"),
+            }
+        }
+        let no_color = self
+            .no_color
+            .then(|| ("/color_support".to_string(), "none".to_string()));
+        for (property, value) in self.overrides.iter().chain(&no_color) {
+            config = config.with_override(property, value)?;
+        }
+        Ok(config)
+    }
 }
 
 #[cfg(test)]
@@ -54,7 +184,10 @@ mod tests {
         let args = GlobalArgs {
             config_dir: Some(PathBuf::from("/tmp/config_dir")),
             config_file: Some(PathBuf::from("/tmp/config_dir/config.json")),
+            workspace: None,
             log_file: None,
+            overrides: vec![],
+            no_color: false,
         };
         let ws = args.workspace();
         assert_eq!(ws.path, PathBuf::from("/tmp/config_dir"));
@@ -69,7 +202,10 @@ mod tests {
         let args = GlobalArgs {
             config_dir: Some(PathBuf::from("/tmp/config_dir")),
             config_file: None,
+            workspace: None,
             log_file: None,
+            overrides: vec![],
+            no_color: false,
         };
         let ws = args.workspace();
         assert_eq!(ws.path, PathBuf::from("/tmp/config_dir"));
@@ -97,7 +233,10 @@ mod tests {
         let args = GlobalArgs {
             config_dir: None,
             config_file: Some(PathBuf::from("/tmp/config_dir/config.json")),
+            workspace: None,
             log_file: None,
+            overrides: vec![],
+            no_color: false,
         };
         let ws = args.workspace();
         assert_eq!(ws.path, default_ws.path);
@@ -113,19 +252,127 @@ mod tests {
         let args = GlobalArgs {
             config_dir: None,
             config_file: None,
+            workspace: None,
             log_file: None,
+            overrides: vec![],
+            no_color: false,
         };
         let ws = args.workspace();
         assert_eq!(ws.path, default_ws.path);
         assert_eq!(ws.config_file(), default_ws.config_file());
     }
 
+    #[test]
+    fn test_named_workspace() {
+        let args = GlobalArgs {
+            config_dir: Some(PathBuf::from("/tmp/config_dir")),
+            config_file: None,
+            workspace: Some("client-a".to_string()),
+            log_file: None,
+            overrides: vec![],
+            no_color: false,
+        };
+        let ws = args.workspace();
+        assert_eq!(
+            ws.path,
+            PathBuf::from("/tmp/config_dir/workspaces/client-a")
+        );
+        assert_eq!(ws.root(), PathBuf::from("/tmp/config_dir"));
+        assert_eq!(ws.name(), "client-a");
+        assert_eq!(
+            ws.config_file(),
+            PathBuf::from("/tmp/config_dir/workspaces/client-a/config.json")
+        );
+        assert_eq!(
+            ws.log_file(),
+            PathBuf::from("/tmp/config_dir/workspaces/client-a/application.log")
+        );
+        assert!(parse_workspace("../client-a").is_err());
+    }
+
+    #[test]
+    fn test_parse_override() {
+        assert_eq!(
+            parse_override("/theme=dark"),
+            Ok(("/theme".to_string(), "dark".to_string()))
+        );
+        assert_eq!(
+            parse_override("/initial_query=offset == 3"),
+            Ok(("/initial_query".to_string(), "offset == 3".to_string()))
+        );
+        assert!(parse_override("theme").is_err());
+        assert!(parse_override("=dark").is_err());
+    }
+
+    #[test]
+    fn test_no_color() {
+        let args = GlobalArgs {
+            config_dir: Some(PathBuf::from("/tmp/config_dir")),
+            config_file: None,
+            workspace: None,
+            log_file: None,
+            overrides: vec![("/color_support".to_string(), "256".to_string())],
+            no_color: true,
+        };
+        assert_eq!(
+            args.workspace().config().color_support(),
+            app::configuration::ColorSupport::Monochrome
+        );
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let vars = vec![
+            ("YOZEFU_THEME".to_string(), "dark".to_string()),
+            (
+                "YOZEFU_CONSUMER__BUFFER_CAPACITY".to_string(),
+                "42".to_string(),
+            ),
+            ("YOZEFU_CONFIG_DIR".to_string(), "/tmp".to_string()),
+            ("HOME".to_string(), "/root".to_string()),
+        ];
+        assert_eq!(
+            env_overrides(vars.into_iter()),
+            vec![
+                ("/theme".to_string(), "dark".to_string()),
+                ("/consumer/buffer_capacity".to_string(), "42".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_env_overrides() {
+        let args = GlobalArgs::default();
+        let vars = vec![
+            ("YOZEFU_THEME".to_string(), "dark".to_string()),
+            ("YOZEFU_VERSION".to_string(), "0.0.19".to_string()),
+            ("YOZEFU_SHOW_SHORTCUTS".to_string(), "[]".to_string()),
+        ];
+        let config = args
+            .apply_overrides_with(GlobalConfig::new(&PathBuf::from("config.json")), vars)
+            .unwrap();
+        assert_eq!(config.theme, "dark");
+        assert!(config.show_shortcuts);
+
+        let args = GlobalArgs {
+            overrides: vec![("/show_shortcuts".to_string(), "[]".to_string())],
+            ..Default::default()
+        };
+        assert!(
+            args.apply_overrides_with(GlobalConfig::new(&PathBuf::from("config.json")), vec![])
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_config_file_method() {
         let args = GlobalArgs {
             config_dir: Some(PathBuf::from("/tmp/config_dir")),
             config_file: Some(PathBuf::from("/tmp/config_dir/config.json")),
+            workspace: None,
             log_file: None,
+            overrides: vec![],
+            no_color: false,
         };
         assert_eq!(
             args.workspace().config_file(),
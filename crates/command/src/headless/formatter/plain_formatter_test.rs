@@ -12,20 +12,24 @@ fn test_plain_formatter() {
         env::set_var("TZ", "Europe/Paris");
     }
 
-    let record = KafkaRecord {
-        topic: "test-topic".to_string(),
-        partition: 0,
-        offset: 1,
-        key: DataType::String("key".to_string()),
-        value: DataType::String("value".to_string()),
-        timestamp: None,
-        headers: BTreeMap::default(),
-        key_schema: None,
-        value_schema: None,
-        size: 12,
-        key_as_string: "key".to_string(),
-        value_as_string: "value".to_string(),
-    };
+    let mut record = KafkaRecord::default();
+    record.topic = "test-topic".to_string();
+    record.partition = 0;
+    record.offset = 1;
+    record.key = DataType::String("key".to_string());
+    record.value = DataType::String("value".to_string());
+    record.timestamp = None;
+    record.timestamp_type = None;
+    record.headers = BTreeMap::default();
+    record.key_schema = None;
+    record.value_schema = None;
+    record.value_format = None;
+    record.size = 12;
+    record.key_as_string = "key".to_string();
+    record.value_as_string = "value".to_string();
+    record.raw_value = None;
+    record.readable_value = None;
+    record.truncated = false;
     let formatter = PlainFormatter::new();
     assert_eq!(
         formatter.fmt(&record),
@@ -33,10 +37,8 @@ fn test_plain_formatter() {
     );
 
     // long topic name
-    let record = KafkaRecord {
-        topic: "a.topic.with.more.than.sixteen.chars".to_string(),
-        ..record
-    };
+    let mut record = record;
+    record.topic = "a.topic.with.more.than.sixteen.chars".to_string();
     let formatted = formatter.fmt(&record);
     assert_eq!(
         formatted,
@@ -1,23 +1,25 @@
 //! Module gathering code for the headless mode.
 
-use app::App;
 use app::search::Search;
 use app::search::SearchContext;
+use app::{App, AuditAction, Checkpointer, RecordsExporter, ShutdownSignals, strip_ansi};
 use chrono::DateTime;
 use futures_batch::TryChunksTimeoutStreamExt;
 use rdkafka::Message;
 use rdkafka::message::OwnedMessage;
+use std::io::{self, Write};
 use std::time::Duration;
 use std::time::Instant;
 use thousands::Separable;
 use tokio::select;
 use tokio::sync::mpsc;
-use tracing::info;
+use tracing::{info, warn};
 
 use futures::{StreamExt, TryStreamExt};
 use indicatif::ProgressBar;
 use lib::Error;
 use lib::KafkaRecord;
+use lib::search::Sample;
 use rdkafka::consumer::Consumer;
 use tokio_util::sync::CancellationToken;
 
@@ -30,6 +32,8 @@ pub struct Headless {
     pub(crate) formatter: Box<dyn KafkaFormatter>,
     progress: ProgressBar,
     export_records: bool,
+    /// Resumes the search from its checkpoint
+    resume: bool,
 }
 
 impl Headless {
@@ -46,18 +50,53 @@ impl Headless {
             formatter,
             progress,
             export_records,
+            resume: false,
         }
     }
 
+    /// Resumes the search from the checkpoint of its previous run, see [`app::Checkpoint`].
+    pub fn with_resume(mut self, resume: bool) -> Self {
+        self.resume = resume;
+        self
+    }
+
     pub async fn run(&self) -> Result<(), Error> {
         if self.topics.is_empty() {
             return Err(Error::Error("Please specify topics to consume".into()));
         }
+        if let Err(e) = self.app.audit(
+            AuditAction::Search,
+            &self.topics,
+            self.app.search_query.input(),
+        ) {
+            warn!("Cannot write the search to the audit log: {e}");
+        }
         info!("Creating consumer for topics [{}]", self.topics.join(", "));
-        let consumer = self.app.create_consumer(&self.topics)?;
-        let mut records_channel = mpsc::unbounded_channel::<KafkaRecord>();
+        let mut checkpointer = self.app.checkpointer(&self.topics, self.resume)?;
+        let positions = checkpointer
+            .as_ref()
+            .map(Checkpointer::positions)
+            .unwrap_or_default();
+        if !positions.is_empty() {
+            info!("Resuming the search from its checkpoint at {positions:?}");
+        }
+        // The retention is checked with blocking metadata and watermarks requests
+        let (app, topics, resumed) = (self.app.clone(), self.topics.clone(), positions.clone());
+        let warnings =
+            tokio::task::spawn_blocking(move || app.retention_warnings(&topics, &resumed))
+                .await
+                .map_err(|e| Error::Tokio(e.to_string()))
+                .and_then(|warnings| warnings);
+        match warnings {
+            Ok(warnings) => warnings.iter().for_each(|w| warn!("{w}")),
+            Err(e) => warn!("Cannot check the retention of the topics: {e}"),
+        }
+        let consumer = self.app.create_resumed_consumer(&self.topics, &positions)?;
+        // Every processed record goes through the channel, the matching ones are printed before being checkpointed
+        let mut records_channel = mpsc::unbounded_channel::<(KafkaRecord, bool)>();
         let search_query = self.app.search_query.clone();
         let token = CancellationToken::new();
+        Self::listen_shutdown_signals(token.clone())?;
         let progress = self.progress.clone();
         progress.enable_steady_tick(Duration::from_secs(10));
         let count = self
@@ -67,29 +106,48 @@ impl Headless {
 
         let (tx_dd, mut rx_dd) = mpsc::unbounded_channel::<OwnedMessage>();
         let mut schema_registry = self.app.schema_registry().clone();
+        let app = self.app.clone();
         let token_cloned = token.clone();
+        let mut limit = checkpointer
+            .as_ref()
+            .map(Checkpointer::matched)
+            .unwrap_or_default();
 
         let filters_directory = self.app.config.workspace().filters_dir();
+        // Records are printed as they come, a sample of a given number of records would require to consume the topics entirely first
+        let percentage = match search_query.sample() {
+            Some(Sample::Percentage(p)) => Some(p),
+            Some(Sample::Records(_)) => {
+                warn!(
+                    "'sample <number>' is not supported in headless mode, use a percentage instead. All matching records are printed"
+                );
+                None
+            }
+            None => None,
+        };
+        if search_query.distinct().is_some() {
+            warn!(
+                "'distinct by' is not supported in headless mode. All matching records are printed"
+            );
+        }
         tokio::task::Builder::new()
             .name("headless-search-engine")
             .spawn(async move {
                 loop {
-                    let mut limit = 0;
                     select! {
-                        () = token_cloned.cancelled() => {
-                            return;
-                         },
+                        () = token_cloned.cancelled() => return,
                         Some(message) = rx_dd.recv() => {
-                            let record = KafkaRecord::parse(message, &mut schema_registry).await;
+                            let record = app.parse_record(message, &mut schema_registry).await;
                             let context = SearchContext::new(&record, &filters_directory);
-                            if search_query.matches(&context) {
-                                records_channel.0.send(record).unwrap();
+                            let sampled = percentage.is_none_or(|p| rand::random_bool(p / 100.0));
+                            let matched = sampled && search_query.matches(&context);
+                            if matched {
                                 limit += 1;
                             }
-                            if let Some(query_limit) = search_query.limit() {
-                                if limit >= query_limit {
-                                    token_cloned.cancel();
-                                }
+                            records_channel.0.send((record, matched)).unwrap();
+                            if search_query.limit().is_some_and(|query_limit| limit >= query_limit) {
+                                token_cloned.cancel();
+                                return;
                             }
                         }
                     }
@@ -98,12 +156,14 @@ impl Headless {
             .unwrap();
 
         let consumer_config = self.app.consumer_config();
+        let scan_limit = self.app.scan_limit();
         tokio::task::Builder::new()
             .name("headless-kafka-consumer")
             .spawn(async move {
                 let mut current_time = Instant::now();
                 let mut consumed = 0;
                 let mut total_consumed = 0;
+                let mut bytes_read = 0;
                 let task = consumer
                     .stream()
                     .take_until(token.cancelled())
@@ -116,9 +176,16 @@ impl Headless {
                         for message in messages {
                             consumed += 1;
                             total_consumed += 1;
+                            bytes_read += (message.key_len() + message.payload_len()) as u64;
                             let message = message.detach();
                             tx_dd.send(message).unwrap();
                         }
+                        if let Some(scan_limit) = scan_limit {
+                            if !token.is_cancelled() && scan_limit.is_reached(total_consumed, bytes_read) {
+                                warn!("Scan limit of {scan_limit} reached after reading {} records, the search is stopped", total_consumed.separate_with_underscores());
+                                token.cancel();
+                            }
+                        }
 
                         let elapsed = current_time.elapsed();
 
@@ -140,12 +207,80 @@ impl Headless {
             })
             .unwrap();
 
-        while let Some(record) = records_channel.1.recv().await {
-            println!("{}", self.formatter.fmt(&record));
-            if self.export_records {
-                self.app.export_record(&record)?;
+        // Once the search is stopped, the records already matched are still printed and exported
+        let mut exporter = self
+            .export_records
+            .then(|| self.app.records_exporter())
+            .transpose()?;
+        let colored = self.app.config.color_support().is_colored();
+        let mut matched_records = checkpointer
+            .as_ref()
+            .map(Checkpointer::matched)
+            .unwrap_or_default();
+        // The records received meanwhile are printed and exported together
+        let mut received = Vec::with_capacity(RecordsExporter::BATCH_SIZE);
+        while records_channel
+            .1
+            .recv_many(&mut received, RecordsExporter::BATCH_SIZE)
+            .await
+            > 0
+        {
+            let matching = received
+                .iter()
+                .filter(|(_, matched)| *matched)
+                .map(|(record, _)| record.clone())
+                .collect::<Vec<_>>();
+            for record in &matching {
+                let line = self.formatter.fmt(record);
+                match colored {
+                    true => println!("{line}"),
+                    false => println!("{}", strip_ansi(&line)),
+                }
+            }
+            io::stdout().flush()?;
+            if let Some(exporter) = exporter.as_mut() {
+                let export_files = exporter.export_all(&matching)?;
+                for (record, export_file) in matching.iter().zip(export_files) {
+                    self.app.export_schemas(record, &export_file).await?;
+                }
+            }
+            matched_records += matching.len();
+            for (record, matched) in received.drain(..) {
+                if let Some(checkpointer) = checkpointer.as_mut() {
+                    if let Err(e) = checkpointer.processed(&record, matched).await {
+                        warn!("Cannot save the checkpoint of the search: {e}");
+                    }
+                }
             }
         }
+        if let Some(checkpointer) = checkpointer.as_mut() {
+            let result = match self.app.search_query.limit() {
+                // The search is complete, there is nothing left to resume
+                Some(limit) if matched_records >= limit => checkpointer.complete().await,
+                _ => checkpointer.save().await,
+            };
+            if let Err(e) = result {
+                warn!("Cannot update the checkpoint of the search: {e}");
+            }
+        }
+        Ok(())
+    }
+
+    /// The first shutdown signal stops the search, the export files are completed before exiting.
+    /// The second one exits immediately.
+    fn listen_shutdown_signals(token: CancellationToken) -> Result<(), Error> {
+        let mut signals = ShutdownSignals::new()?;
+        tokio::task::Builder::new()
+            .name("shutdown-signals")
+            .spawn(async move {
+                let signal = signals.recv().await;
+                warn!("{signal} received, the search is stopped");
+                token.cancel();
+                let signal = signals.recv().await;
+                warn!("{signal} received again, exiting without completing the export");
+                std::process::exit(130);
+            })
+            .unwrap();
         Ok(())
     }
 }
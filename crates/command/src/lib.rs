@@ -7,6 +7,9 @@ mod command;
 mod global_args;
 mod headless;
 mod log;
+mod mcp;
+mod records_search;
+mod serve;
 mod theme;
 mod version;
 pub use clap::Parser;
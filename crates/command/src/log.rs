@@ -13,14 +13,16 @@ fn log_level(is_debug: bool) -> LevelFilter {
 }
 
 /// When the user starts the headless mode, it writes logs to `stderr`.
+/// `ansi` is `false` when colors are disabled.
 pub(crate) fn init_logging_stderr(
     is_debug: bool,
+    ansi: bool,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
     tracing_subscriber::fmt()
         .with_max_level(log_level(is_debug))
         .with_span_events(FmtSpan::CLOSE)
         .with_writer(std::io::stderr)
-        .with_ansi(true)
+        .with_ansi(ansi)
         .try_init()
 }
 
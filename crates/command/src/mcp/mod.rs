@@ -0,0 +1,311 @@
+//! Module gathering code for the Model Context Protocol server.
+//! The server reads JSON-RPC requests from `stdin`, one per line, and writes the responses to `stdout`.
+//! It exposes the search engine, the metadata of topics and the schema registry as tools,
+//! with the query language and the credentials of the cluster.
+//! See <https://modelcontextprotocol.io/specification/2025-06-18/basic/transports#stdio>.
+
+use std::{collections::HashSet, time::Duration};
+
+use app::{APPLICATION_NAME, App, configuration::InternalConfig, search::ValidSearchQuery};
+use lib::{CountMethod, Error, KafkaRecord};
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tracing::{info, warn};
+
+use crate::records_search::search_records;
+
+/// Version of the protocol implemented by the server
+const PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// Maximum number of records returned by the `search` tool, unless specified
+const DEFAULT_SEARCH_LIMIT: u64 = 20;
+
+/// Time spent reading the topics by the `search` tool, unless specified
+const DEFAULT_SEARCH_TIMEOUT_MS: u64 = 10_000;
+
+/// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+
+pub struct McpServer {
+    config: InternalConfig,
+}
+
+impl McpServer {
+    pub fn new(config: InternalConfig) -> Self {
+        Self { config }
+    }
+
+    /// Answers the requests until `stdin` is closed.
+    pub async fn run(&self) -> Result<(), Error> {
+        info!("MCP server started for cluster '{}'", self.config.cluster());
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+        let mut stdout = tokio::io::stdout();
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = self.handle(&line).await {
+                stdout.write_all(format!("{response}\n").as_bytes()).await?;
+                stdout.flush().await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the response to the request, notifications have no response.
+    async fn handle(&self, line: &str) -> Option<Value> {
+        let request: Value = match serde_json::from_str(line) {
+            Ok(request) => request,
+            Err(e) => return Some(error_response(&Value::Null, PARSE_ERROR, &e.to_string())),
+        };
+        let id = request.get("id")?;
+        let method = request["method"].as_str().unwrap_or_default();
+        let result = match method {
+            "initialize" => json!({
+                "protocolVersion": PROTOCOL_VERSION,
+                "capabilities": { "tools": {} },
+                "serverInfo": {
+                    "name": APPLICATION_NAME,
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+                "instructions": format!("Search kafka records of cluster '{}'. The search query language is documented at https://github.com/MAIF/yozefu/blob/main/docs/query-language/README.md", self.config.cluster()),
+            }),
+            "ping" => json!({}),
+            "tools/list" => json!({ "tools": tools() }),
+            "tools/call" => self.call_tool(&request["params"]).await,
+            _ => {
+                return Some(error_response(
+                    id,
+                    METHOD_NOT_FOUND,
+                    &format!("Method '{method}' not found"),
+                ));
+            }
+        };
+        Some(json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+    }
+
+    /// Runs a tool, errors are reported in the result so the assistant can read them.
+    async fn call_tool(&self, params: &Value) -> Value {
+        let arguments = &params["arguments"];
+        let name = params["name"].as_str().unwrap_or_default();
+        let result = match name {
+            "list_topics" => self.app("").and_then(|app| Ok(json!(app.list_topics()?))),
+            "topic_details" => self.topic_details(arguments),
+            "search" => self.search(arguments).await,
+            "get_schema" => self.schema(arguments).await,
+            _ => Err(Error::Error(format!("Unknown tool '{name}'"))),
+        };
+        let (text, is_error) = match result {
+            Ok(content) => (
+                serde_json::to_string_pretty(&content).unwrap_or_default(),
+                false,
+            ),
+            Err(e) => {
+                warn!("Tool '{name}' failed: {e}");
+                (e.to_string(), true)
+            }
+        };
+        json!({
+            "content": [{ "type": "text", "text": text }],
+            "isError": is_error,
+        })
+    }
+
+    fn app(&self, query: &str) -> Result<App, Error> {
+        let search_query = ValidSearchQuery::from(query, &self.config.workspace().filters_dir())?;
+        Ok(App::new(
+            self.config.cluster().to_string(),
+            self.config.clone(),
+            search_query,
+        ))
+    }
+
+    fn topic_details(&self, arguments: &Value) -> Result<Value, Error> {
+        let topics = strings_of(arguments, "topics")?;
+        let details = self.app("")?.topic_details(
+            topics.into_iter().collect::<HashSet<_>>(),
+            CountMethod::Offsets,
+        )?;
+        Ok(details
+            .iter()
+            .map(|detail| {
+                json!({
+                    "name": detail.name,
+                    "partitions": detail.partitions,
+                    "replicas": detail.replicas,
+                    "count": detail.count,
+                    "count_method": detail.count_method,
+                    "consumer_groups": detail.consumer_groups.iter().map(|group| json!({
+                        "name": group.name,
+                        "state": group.state,
+                        "lag": group.committed_lag,
+                    })).collect::<Vec<_>>(),
+                })
+            })
+            .collect())
+    }
+
+    /// Reads the topics until enough records match the search query or the timeout expires.
+    async fn search(&self, arguments: &Value) -> Result<Value, Error> {
+        let topics = strings_of(arguments, "topics")?;
+        let app = self.app(arguments["query"].as_str().unwrap_or_default())?;
+        let limit = arguments["limit"]
+            .as_u64()
+            .unwrap_or(DEFAULT_SEARCH_LIMIT)
+            .try_into()?;
+        let timeout = Duration::from_millis(
+            arguments["timeout_ms"]
+                .as_u64()
+                .unwrap_or(DEFAULT_SEARCH_TIMEOUT_MS),
+        );
+        let mut records: Vec<KafkaRecord> = vec![];
+        let read = search_records(&app, &topics, limit, timeout, |record| {
+            records.push(record);
+            true
+        })
+        .await?;
+        Ok(json!({
+            "read": read,
+            "records": records,
+        }))
+    }
+
+    async fn schema(&self, arguments: &Value) -> Result<Value, Error> {
+        let id = arguments["id"]
+            .as_u64()
+            .ok_or(Error::Error("'id' must be a schema id".to_string()))?
+            .try_into()?;
+        let mut schema_registry = self.app("")?.schema_registry().ok_or(Error::Error(format!(
+            "No schema registry is configured for cluster '{}'",
+            self.config.cluster()
+        )))?;
+        let schema = schema_registry
+            .schema(id)
+            .await?
+            .ok_or(Error::Error(format!("Schema {id} not found")))?;
+        Ok(json!({
+            "id": id,
+            "type": schema.schema_type,
+            "schema": schema.schema,
+            "subjects": schema_registry.subjects_of(id).await?,
+        }))
+    }
+}
+
+/// Tools of the server and the JSON schemas of their arguments.
+fn tools() -> Value {
+    json!([
+        {
+            "name": "list_topics",
+            "description": "List the topics of the kafka cluster",
+            "inputSchema": { "type": "object", "properties": {} },
+        },
+        {
+            "name": "topic_details",
+            "description": "Return the number of partitions, replicas, records and the consumer groups of kafka topics",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "topics": { "type": "array", "items": { "type": "string" } },
+                },
+                "required": ["topics"],
+            },
+        },
+        {
+            "name": "search",
+            "description": "Search kafka records with the yozefu query language, for instance `from begin value.status == \"error\" and timestamp > \"1 hour ago\" limit 10`. Without 'from', only new records are read",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "topics": { "type": "array", "items": { "type": "string" } },
+                    "query": { "type": "string", "description": "Search query, empty to match all records" },
+                    "limit": { "type": "integer", "description": "Maximum number of records to return", "default": DEFAULT_SEARCH_LIMIT },
+                    "timeout_ms": { "type": "integer", "description": "Time spent reading the topics", "default": DEFAULT_SEARCH_TIMEOUT_MS },
+                },
+                "required": ["topics"],
+            },
+        },
+        {
+            "name": "get_schema",
+            "description": "Return a schema of the schema registry and the subjects it is registered under",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "id": { "type": "integer", "description": "Schema id, as found in the key_schema and value_schema properties of records" },
+                },
+                "required": ["id"],
+            },
+        },
+    ])
+}
+
+fn strings_of(arguments: &Value, name: &str) -> Result<Vec<String>, Error> {
+    arguments[name]
+        .as_array()
+        .and_then(|values| {
+            values
+                .iter()
+                .map(|value| value.as_str().map(str::to_string))
+                .collect()
+        })
+        .ok_or(Error::Error(format!(
+            "'{name}' must be an array of strings"
+        )))
+}
+
+fn error_response(id: &Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+#[tokio::test]
+async fn test_handle() {
+    use app::configuration::{ClusterConfig, Workspace};
+
+    let server = McpServer::new(InternalConfig::new(
+        ClusterConfig::default().create("localhost"),
+        Workspace::default(),
+    ));
+    let response = server
+        .handle(r#"{"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}"#)
+        .await
+        .unwrap();
+    assert_eq!(response["id"], 1);
+    assert_eq!(response["result"]["protocolVersion"], PROTOCOL_VERSION);
+
+    assert_eq!(
+        server
+            .handle(r#"{"jsonrpc": "2.0", "method": "notifications/initialized"}"#)
+            .await,
+        None
+    );
+
+    let response = server
+        .handle(r#"{"jsonrpc": "2.0", "id": 2, "method": "tools/list"}"#)
+        .await
+        .unwrap();
+    assert_eq!(response["result"]["tools"].as_array().unwrap().len(), 4);
+
+    let response = server
+        .handle(r#"{"jsonrpc": "2.0", "id": 3, "method": "tools/call", "params": {"name": "search", "arguments": {"query": "from begin"}}}"#)
+        .await
+        .unwrap();
+    assert_eq!(response["result"]["isError"], true);
+    assert_eq!(
+        response["result"]["content"][0]["text"],
+        "'topics' must be an array of strings"
+    );
+
+    let response = server
+        .handle(r#"{"jsonrpc": "2.0", "id": 4, "method": "resources/list"}"#)
+        .await
+        .unwrap();
+    assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+    let response = server.handle("{").await.unwrap();
+    assert_eq!(response["error"]["code"], PARSE_ERROR);
+}
@@ -0,0 +1,68 @@
+//! Search shared by the servers of the tool.
+//! Unlike the TUI and the headless mode, it stops as soon as enough records match the search query,
+//! when the timeout expires or once the scan limit is reached, so that each request gets an answer.
+
+use std::time::{Duration, Instant};
+
+use app::{
+    App, AuditAction,
+    search::{Search, SearchContext},
+};
+use futures::StreamExt;
+use lib::{Error, KafkaRecord};
+use tracing::warn;
+
+/// Reads the topics until `limit` records match the search query of the app or the timeout expires.
+/// Matching records are passed to `on_record`, the search stops when it returns `false`.
+/// It returns the number of records read.
+pub(crate) async fn search_records(
+    app: &App,
+    topics: &[String],
+    limit: usize,
+    timeout: Duration,
+    mut on_record: impl FnMut(KafkaRecord) -> bool,
+) -> Result<usize, Error> {
+    if let Err(e) = app.audit(AuditAction::Search, topics, app.search_query.input()) {
+        warn!("Cannot write the search to the audit log: {e}");
+    }
+    let limit = app.search_query.limit().map_or(limit, |l| l.min(limit));
+    let consumer = {
+        let app = app.clone();
+        let topics = topics.to_vec();
+        tokio::task::spawn_blocking(move || app.create_consumer(&topics))
+            .await
+            .map_err(|e| Error::Error(e.to_string()))??
+    };
+    let mut schema_registry = app.schema_registry();
+    let filters_directory = app.config.workspace().filters_dir();
+    let deadline = Instant::now() + timeout;
+    let mut stream = consumer.stream();
+    let scan_limit = app.scan_limit();
+    let mut read = 0;
+    let mut bytes_read = 0;
+    let mut matching = 0;
+    while matching < limit {
+        if scan_limit.is_some_and(|l| l.is_reached(read as u64, bytes_read)) {
+            break;
+        }
+        let message = match tokio::time::timeout_at(deadline.into(), stream.next()).await {
+            Ok(Some(message)) => message?,
+            Ok(None) | Err(_) => break,
+        };
+        read += 1;
+        bytes_read += (message.key_len() + message.payload_len()) as u64;
+        let record = app
+            .parse_record(message.detach(), &mut schema_registry)
+            .await;
+        if app
+            .search_query
+            .matches(&SearchContext::new(&record, &filters_directory))
+        {
+            matching += 1;
+            if !on_record(record) {
+                break;
+            }
+        }
+    }
+    Ok(read)
+}
@@ -0,0 +1,119 @@
+//! gRPC service mirroring the `GET /search/stream` endpoint.
+//! The protocol buffers definitions are in the `proto` directory of the lib crate.
+
+use std::pin::Pin;
+
+use app::configuration::InternalConfig;
+use futures::{Stream, StreamExt};
+use lib::{
+    Error,
+    grpc::v1::{
+        SearchEnd, SearchRequest, SearchResponse,
+        search_response::Event,
+        search_service_server::{SearchService, SearchServiceServer},
+    },
+};
+use tokio::sync::mpsc;
+use tonic::{Request, Response, Status};
+use tracing::warn;
+
+use crate::records_search::search_records;
+
+use super::{SearchBounds, app_of};
+
+pub(crate) struct GrpcSearch {
+    config: InternalConfig,
+    bounds: SearchBounds,
+}
+
+impl GrpcSearch {
+    pub(crate) fn server(
+        config: InternalConfig,
+        bounds: SearchBounds,
+    ) -> SearchServiceServer<Self> {
+        SearchServiceServer::new(Self { config, bounds })
+    }
+}
+
+fn status_of(e: &Error) -> Status {
+    match e {
+        Error::Search(_) => Status::invalid_argument(e.to_string()),
+        _ => Status::internal(e.to_string()),
+    }
+}
+
+#[tonic::async_trait]
+impl SearchService for GrpcSearch {
+    type SearchStream = Pin<Box<dyn Stream<Item = Result<SearchResponse, Status>> + Send>>;
+
+    async fn search(
+        &self,
+        request: Request<SearchRequest>,
+    ) -> Result<Response<Self::SearchStream>, Status> {
+        let request = request.into_inner();
+        let app = app_of(&self.config, &request.query).map_err(|e| status_of(&e))?;
+        let limit = request
+            .limit
+            .map(usize::try_from)
+            .transpose()
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let (limit, timeout) = self
+            .bounds
+            .check(limit, request.timeout_ms)
+            .map_err(Status::invalid_argument)?;
+        let (tx, rx) = mpsc::unbounded_channel::<Result<SearchResponse, Status>>();
+        tokio::task::Builder::new()
+            .name("grpc-search")
+            .spawn(async move {
+                let result = search_records(&app, &request.topics, limit, timeout, |record| {
+                    tx.send(Ok(SearchResponse {
+                        event: Some(Event::Record((&record).into())),
+                    }))
+                    .is_ok()
+                })
+                .await;
+                let response = match result {
+                    Ok(read) => Ok(SearchResponse {
+                        event: Some(Event::End(SearchEnd { read: read as u64 })),
+                    }),
+                    Err(e) => {
+                        warn!("Search failed: {e}");
+                        Err(status_of(&e))
+                    }
+                };
+                let _ = tx.send(response);
+            })
+            .map_err(|e| Status::internal(e.to_string()))?;
+        let stream = futures::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|response| (response, rx))
+        });
+        Ok(Response::new(stream.boxed()))
+    }
+}
+
+#[tokio::test]
+async fn test_invalid_search_query() {
+    use app::configuration::{ClusterConfig, Workspace};
+
+    let service = GrpcSearch {
+        config: InternalConfig::new(
+            ClusterConfig::default().create("localhost"),
+            Workspace::default(),
+        ),
+        bounds: SearchBounds {
+            max_limit: 100,
+            max_timeout_ms: 1_000,
+        },
+    };
+    let status = service
+        .search(Request::new(SearchRequest {
+            topics: vec!["payments".to_string()],
+            query: "value ==".to_string(),
+            limit: None,
+            timeout_ms: None,
+        }))
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+}
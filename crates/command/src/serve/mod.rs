@@ -0,0 +1,364 @@
+//! Module gathering code for the HTTP API server.
+//! It exposes the consumer and the search engine of the tool:
+//!  - `GET /topics` lists the topics,
+//!  - `GET /topics/{topic}/partitions/{partition}/offsets/{offset}` returns a record,
+//!  - `GET /schemas/{id}` returns a schema of the schema registry,
+//!  - `GET /search?topics=...&query=...` returns the records matching a search query,
+//!  - `GET /search/stream?topics=...&query=...` streams them with server-sent events.
+//!
+//! With the `grpc` feature, the search stream is also available as a gRPC service.
+
+use std::{convert::Infallible, net::SocketAddr, time::Duration};
+
+use app::{App, configuration::InternalConfig, search::ValidSearchQuery};
+use axum::{
+    Json, Router,
+    extract::{FromRef, Path, Query, State},
+    http::StatusCode,
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
+    routing::get,
+};
+use futures::{Stream, StreamExt};
+use lib::{Error, KafkaRecord};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::records_search::search_records;
+
+#[cfg(feature = "grpc")]
+mod grpc;
+
+/// Maximum number of records returned by a search, unless specified
+const DEFAULT_SEARCH_LIMIT: usize = 100;
+
+/// Time spent reading the topics by a search, unless specified
+const DEFAULT_SEARCH_TIMEOUT_MS: u64 = 10_000;
+
+/// Upper bounds of the parameters of the searches, so one request cannot keep a consumer busy and buffer records forever.
+/// Requests beyond them are rejected.
+#[derive(Debug, Clone, Copy)]
+pub struct SearchBounds {
+    pub max_limit: usize,
+    pub max_timeout_ms: u64,
+}
+
+impl SearchBounds {
+    /// Returns the limit and the timeout of a search, the defaults are lowered to the bounds.
+    fn check(
+        &self,
+        limit: Option<usize>,
+        timeout_ms: Option<u64>,
+    ) -> Result<(usize, Duration), String> {
+        let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT.min(self.max_limit));
+        if limit > self.max_limit {
+            return Err(format!(
+                "The limit should be at most {}, got {limit}",
+                self.max_limit
+            ));
+        }
+        let timeout_ms = timeout_ms.unwrap_or(DEFAULT_SEARCH_TIMEOUT_MS.min(self.max_timeout_ms));
+        if timeout_ms > self.max_timeout_ms {
+            return Err(format!(
+                "The timeout should be at most {} ms, got {timeout_ms} ms",
+                self.max_timeout_ms
+            ));
+        }
+        Ok((limit, Duration::from_millis(timeout_ms)))
+    }
+}
+
+pub struct ApiServer {
+    config: InternalConfig,
+    bounds: SearchBounds,
+}
+
+/// State shared by the endpoints.
+#[derive(Clone)]
+struct ApiState {
+    config: InternalConfig,
+    bounds: SearchBounds,
+}
+
+impl FromRef<ApiState> for InternalConfig {
+    fn from_ref(state: &ApiState) -> Self {
+        state.config.clone()
+    }
+}
+
+impl FromRef<ApiState> for SearchBounds {
+    fn from_ref(state: &ApiState) -> Self {
+        state.bounds
+    }
+}
+
+/// Parameters of the search endpoints.
+#[derive(Debug, Deserialize)]
+struct SearchParameters {
+    /// Topics separated by commas
+    topics: String,
+    #[serde(default)]
+    query: String,
+    limit: Option<usize>,
+    timeout_ms: Option<u64>,
+}
+
+/// An error returned as JSON, invalid search queries and parameters are client errors.
+struct ApiError(StatusCode, Error);
+
+impl ApiError {
+    fn bad_request(message: String) -> Self {
+        Self(StatusCode::BAD_REQUEST, Error::Error(message))
+    }
+}
+
+impl From<Error> for ApiError {
+    fn from(e: Error) -> Self {
+        let status = match e {
+            Error::Search(_) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self(status, e)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1.to_string() }))).into_response()
+    }
+}
+
+impl ApiServer {
+    pub fn new(config: InternalConfig, bounds: SearchBounds) -> Self {
+        Self { config, bounds }
+    }
+
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/topics", get(topics))
+            .route(
+                "/topics/{topic}/partitions/{partition}/offsets/{offset}",
+                get(record),
+            )
+            .route("/schemas/{id}", get(schema))
+            .route("/search", get(search))
+            .route("/search/stream", get(search_stream))
+            .with_state(ApiState {
+                config: self.config.clone(),
+                bounds: self.bounds,
+            })
+    }
+
+    /// Serves the API until the process is stopped.
+    pub async fn run(&self, address: SocketAddr) -> Result<(), Error> {
+        let listener = tokio::net::TcpListener::bind(address).await?;
+        info!(
+            "API of cluster '{}' listening on http://{}",
+            self.config.cluster(),
+            listener.local_addr()?
+        );
+        axum::serve(listener, self.router()).await?;
+        Ok(())
+    }
+
+    /// Serves the gRPC service until the process is stopped.
+    #[cfg(feature = "grpc")]
+    pub async fn run_grpc(&self, address: SocketAddr) -> Result<(), Error> {
+        info!(
+            "gRPC service of cluster '{}' listening on {address}",
+            self.config.cluster(),
+        );
+        tonic::transport::Server::builder()
+            .add_service(grpc::GrpcSearch::server(self.config.clone(), self.bounds))
+            .serve(address)
+            .await
+            .map_err(|e| Error::Error(e.to_string()))
+    }
+}
+
+fn app_of(config: &InternalConfig, query: &str) -> Result<App, Error> {
+    let search_query = ValidSearchQuery::from(query, &config.workspace().filters_dir())?;
+    Ok(App::new(
+        config.cluster().to_string(),
+        config.clone(),
+        search_query,
+    ))
+}
+
+impl SearchParameters {
+    fn topics(&self) -> Vec<String> {
+        self.topics
+            .split(',')
+            .map(str::trim)
+            .filter(|topic| !topic.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+async fn topics(State(config): State<InternalConfig>) -> Result<Json<Vec<String>>, ApiError> {
+    let app = app_of(&config, "")?;
+    let topics = tokio::task::spawn_blocking(move || app.list_topics())
+        .await
+        .map_err(|e| Error::Error(e.to_string()))??;
+    Ok(Json(topics))
+}
+
+async fn record(
+    State(config): State<InternalConfig>,
+    Path((topic, partition, offset)): Path<(String, i32, i64)>,
+) -> Result<Json<KafkaRecord>, ApiError> {
+    let record = app_of(&config, "")?
+        .fetch_record(&topic, partition, offset)
+        .await?;
+    Ok(Json(record))
+}
+
+async fn schema(
+    State(config): State<InternalConfig>,
+    Path(id): Path<u32>,
+) -> Result<Response, ApiError> {
+    let Some(mut schema_registry) = app_of(&config, "")?.schema_registry() else {
+        return Err(Error::Error(format!(
+            "No schema registry is configured for cluster '{}'",
+            config.cluster()
+        ))
+        .into());
+    };
+    let Some(schema) = schema_registry.schema(id).await? else {
+        return Ok((
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": format!("Schema {id} not found") })),
+        )
+            .into_response());
+    };
+    Ok(Json(json!({
+        "id": id,
+        "type": schema.schema_type,
+        "schema": schema.schema,
+        "subjects": schema_registry.subjects_of(id).await?,
+    }))
+    .into_response())
+}
+
+async fn search(
+    State(config): State<InternalConfig>,
+    State(bounds): State<SearchBounds>,
+    Query(parameters): Query<SearchParameters>,
+) -> Result<Json<Value>, ApiError> {
+    let (limit, timeout) = bounds
+        .check(parameters.limit, parameters.timeout_ms)
+        .map_err(ApiError::bad_request)?;
+    let app = app_of(&config, &parameters.query)?;
+    let mut records = vec![];
+    let read = search_records(&app, &parameters.topics(), limit, timeout, |record| {
+        records.push(record);
+        true
+    })
+    .await?;
+    Ok(Json(json!({ "read": read, "records": records })))
+}
+
+/// Streams the matching records as `record` events, followed by an `end` event,
+/// or an `error` event if the search fails. The search stops when the client disconnects.
+async fn search_stream(
+    State(config): State<InternalConfig>,
+    State(bounds): State<SearchBounds>,
+    Query(parameters): Query<SearchParameters>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let (limit, timeout) = bounds
+        .check(parameters.limit, parameters.timeout_ms)
+        .map_err(ApiError::bad_request)?;
+    let app = app_of(&config, &parameters.query)?;
+    let (tx, rx) = mpsc::unbounded_channel::<Event>();
+    tokio::task::Builder::new()
+        .name("api-search")
+        .spawn(async move {
+            let result = search_records(&app, &parameters.topics(), limit, timeout, |record| {
+                match Event::default().event("record").json_data(&record) {
+                    Ok(event) => tx.send(event).is_ok(),
+                    Err(_) => true,
+                }
+            })
+            .await;
+            let event = match result {
+                Ok(read) => Event::default()
+                    .event("end")
+                    .data(json!({ "read": read }).to_string()),
+                Err(e) => {
+                    warn!("Search failed: {e}");
+                    Event::default().event("error").data(e.to_string())
+                }
+            };
+            let _ = tx.send(event);
+        })
+        .map_err(Error::from)?;
+    let stream = futures::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|event| (Ok(event), rx))
+    });
+    Ok(Sse::new(stream.boxed()).keep_alive(KeepAlive::default()))
+}
+
+#[test]
+fn test_search_parameters() {
+    let parameters = SearchParameters {
+        topics: "payments, orders,,".to_string(),
+        query: String::new(),
+        limit: None,
+        timeout_ms: Some(500),
+    };
+    assert_eq!(parameters.topics(), vec!["payments", "orders"]);
+}
+
+#[test]
+fn test_search_bounds() {
+    let bounds = SearchBounds {
+        max_limit: 50,
+        max_timeout_ms: 5_000,
+    };
+    assert_eq!(
+        bounds.check(None, Some(500)),
+        Ok((50, Duration::from_millis(500)))
+    );
+    assert_eq!(
+        bounds.check(Some(10), None),
+        Ok((10, Duration::from_millis(5_000)))
+    );
+    assert!(bounds.check(Some(51), None).is_err());
+    assert!(bounds.check(None, Some(5_001)).is_err());
+}
+
+#[tokio::test]
+async fn test_invalid_search_query() {
+    use app::configuration::{ClusterConfig, Workspace};
+
+    let config = InternalConfig::new(
+        ClusterConfig::default().create("localhost"),
+        Workspace::default(),
+    );
+    let bounds = SearchBounds {
+        max_limit: 100,
+        max_timeout_ms: 1_000,
+    };
+    let parameters = |query: &str, limit| SearchParameters {
+        topics: "payments".to_string(),
+        query: query.to_string(),
+        limit,
+        timeout_ms: None,
+    };
+    for parameters in [
+        parameters("value ==", None),
+        parameters("", Some(1_000_000)),
+    ] {
+        let response = search(State(config.clone()), State(bounds), Query(parameters))
+            .await
+            .err()
+            .unwrap()
+            .into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -1,6 +1,6 @@
 use std::{fs, path::PathBuf};
 
-use app::configuration::Workspace;
+use app::configuration::{Workspace, read_themes};
 use indexmap::IndexMap;
 use lib::Error;
 use tracing::{info, warn};
@@ -55,8 +55,7 @@ pub(crate) async fn update_themes(workspace: &Workspace) -> Result<PathBuf, Erro
         return init_themes_file(workspace).await;
     }
 
-    let content = fs::read_to_string(&path)?;
-    let mut local_themes: IndexMap<String, Theme> = serde_json::from_str(&content)?;
+    let mut local_themes: IndexMap<String, Theme> = read_themes(&path)?;
 
     info!("Updating themes file from {THEMES_URL}");
     let content = match reqwest::get(THEMES_URL).await {
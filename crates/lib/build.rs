@@ -0,0 +1,14 @@
+//! Generates the gRPC service of `proto/yozefu/v1/search.proto` when the `grpc` feature is enabled.
+//! The protocol buffers compiler is written in rust, `protoc` does not need to be installed.
+
+fn main() {
+    println!("cargo:rerun-if-changed=proto");
+    #[cfg(feature = "grpc")]
+    {
+        let descriptors = protox::compile(["yozefu/v1/search.proto"], ["proto"])
+            .expect("Cannot compile the protocol buffers definitions");
+        tonic_prost_build::configure()
+            .compile_fds(descriptors)
+            .expect("Cannot generate the gRPC service");
+    }
+}
@@ -9,20 +9,36 @@ use std::{
 
 /// All kinds of errors that may occur
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
+    /// Any other error, described by its message
     Error(String),
+    /// The kafka client failed
     KafkaError(KafkaError),
     IoError(std::io::Error),
     SerdeError(serde_json::Error),
+    /// The theme of the TUI is invalid
     ThemeError(String),
+    /// The search query is invalid
     Search(SearchError),
+    /// The schema registry could not be reached or returned an error
     SchemaRegistry(String),
+    /// A tokio task could not be spawned or joined
     Tokio(String),
 }
 
+/// Errors of the search query parser.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum SearchError {
+    /// The remaining input that could not be parsed
     Parse(String),
+    /// A `:name` placeholder of the query has no value
+    MissingPlaceholder(String),
+    /// The value of a `:name` placeholder contains both single and double quotes
+    UnquotablePlaceholder(String),
+    /// Two clauses of the query cannot be used together
+    IncompatibleClauses(&'static str, &'static str),
 }
 
 impl std::error::Error for Error {}
@@ -46,6 +62,16 @@ impl Display for SearchError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             SearchError::Parse(e) => write!(f, "Cannot parse the search query at '{e}'"),
+            SearchError::MissingPlaceholder(e) => {
+                write!(f, "The placeholder ':{e}' of the search query has no value")
+            }
+            SearchError::UnquotablePlaceholder(e) => write!(
+                f,
+                "The value of the placeholder ':{e}' cannot contain both single and double quotes"
+            ),
+            SearchError::IncompatibleClauses(a, b) => {
+                write!(f, "The '{a}' and '{b}' clauses cannot be used together")
+            }
         }
     }
 }
@@ -56,6 +82,12 @@ impl From<std::fmt::Error> for Error {
     }
 }
 
+impl From<SearchError> for Error {
+    fn from(e: SearchError) -> Self {
+        Error::Search(e)
+    }
+}
+
 impl From<KafkaError> for Error {
     fn from(e: KafkaError) -> Self {
         Error::KafkaError(e)
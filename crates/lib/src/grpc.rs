@@ -0,0 +1,45 @@
+//! gRPC service streaming the kafka records matching a search query.
+//! The protocol buffers definitions are shipped in the `proto` directory of the crate.
+
+use crate::KafkaRecord;
+
+#[allow(clippy::all, clippy::pedantic)]
+pub mod v1 {
+    tonic::include_proto!("yozefu.v1");
+}
+
+impl From<&KafkaRecord> for v1::Record {
+    fn from(record: &KafkaRecord) -> Self {
+        Self {
+            topic: record.topic.clone(),
+            partition: record.partition,
+            offset: record.offset,
+            timestamp: record.timestamp,
+            headers: record.headers.clone().into_iter().collect(),
+            key: record.key_as_string.clone(),
+            value: record.value_as_string.clone(),
+            size: record.size as u64,
+            key_schema_id: record.key_schema.as_ref().map(|s| s.id.0),
+            value_schema_id: record.value_schema.as_ref().map(|s| s.id.0),
+        }
+    }
+}
+
+#[test]
+fn test_record() {
+    let record = KafkaRecord {
+        topic: "payments".to_string(),
+        partition: 2,
+        offset: 42,
+        timestamp: Some(1_700_000_000_000),
+        key_as_string: "abc".to_string(),
+        value_as_string: r#"{"status": "error"}"#.to_string(),
+        size: 22,
+        ..Default::default()
+    };
+    let message = v1::Record::from(&record);
+    assert_eq!(message.topic, "payments");
+    assert_eq!(message.offset, 42);
+    assert_eq!(message.value, r#"{"status": "error"}"#);
+    assert_eq!(message.key_schema_id, None);
+}
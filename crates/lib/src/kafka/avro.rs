@@ -1,7 +1,9 @@
-use apache_avro::types::Value;
+use apache_avro::{Decimal, Schema, schema::NamesRef, types::Value};
+use chrono::{DateTime, NaiveTime, SecondsFormat};
 use serde_json::{Map, Number};
 
-/// Converts an Avro value to a JSON value.
+/// Converts an Avro value to a JSON value, logical types are rendered as they are encoded:
+/// the bytes of the decimals, the days since the epoch of the dates, the milliseconds of the timestamps...
 pub(crate) fn avro_to_json(value: Value) -> serde_json::Value {
     match value {
         Value::Null => serde_json::Value::Null,
@@ -55,10 +57,195 @@ pub(crate) fn avro_to_json(value: Value) -> serde_json::Value {
             map.insert("days".to_string(), serde_json::Value::Number(i.into()));
             serde_json::Value::Object(map)
         }
-        Value::Decimal(_decimal) => serde_json::Value::String(
-            "Yozefu error: I don't know how to encode a decimal to json. It fails silently"
-                .to_string(),
+        Value::Decimal(decimal) => serde_json::Value::Array(
+            <Vec<u8>>::try_from(&decimal)
+                .unwrap_or_default()
+                .iter()
+                .map(|b| serde_json::Value::Number(Number::from(*b)))
+                .collect(),
         ),
         Value::BigDecimal(big_decimal) => serde_json::Value::String(big_decimal.to_string()),
     }
 }
+
+/// Converts an Avro value to a JSON value, logical types are rendered as readable values:
+/// decimals as numbers, dates, times and timestamps as ISO 8601 strings and UUIDs as canonical text.
+/// The schema gives the scale of the decimals, `names` resolves the references to named types.
+pub(crate) fn avro_to_readable_json(
+    value: Value,
+    schema: &Schema,
+    names: &NamesRef,
+) -> serde_json::Value {
+    let schema = match schema {
+        Schema::Ref { name } => names.get(name).copied().unwrap_or(schema),
+        schema => schema,
+    };
+    match (value, schema) {
+        (Value::Union(i, value), Schema::Union(union)) => match union.variants().get(i as usize) {
+            Some(variant) => avro_to_readable_json(*value, variant, names),
+            None => avro_to_json(*value),
+        },
+        (Value::Record(fields), Schema::Record(record)) => serde_json::Value::Object(
+            fields
+                .into_iter()
+                .map(|(name, value)| {
+                    let value = match record.lookup.get(&name) {
+                        Some(i) => avro_to_readable_json(value, &record.fields[*i].schema, names),
+                        None => avro_to_json(value),
+                    };
+                    (name, value)
+                })
+                .collect(),
+        ),
+        (Value::Array(items), Schema::Array(array)) => serde_json::Value::Array(
+            items
+                .into_iter()
+                .map(|v| avro_to_readable_json(v, &array.items, names))
+                .collect(),
+        ),
+        (Value::Map(map), Schema::Map(map_schema)) => serde_json::Value::Object(
+            map.into_iter()
+                .map(|(k, v)| (k, avro_to_readable_json(v, &map_schema.types, names)))
+                .collect(),
+        ),
+        (Value::Decimal(decimal), Schema::Decimal(decimal_schema)) => {
+            decimal_to_json(&decimal, decimal_schema.scale)
+        }
+        (value, _) => readable_logical_type(value),
+    }
+}
+
+/// Dates, times and timestamps as ISO 8601 strings, the other values as they are.
+fn readable_logical_type(value: Value) -> serde_json::Value {
+    let time = |micros: i64, format: &str| {
+        NaiveTime::from_num_seconds_from_midnight_opt(
+            u32::try_from(micros.div_euclid(1_000_000)).ok()?,
+            u32::try_from(micros.rem_euclid(1_000_000) * 1_000).ok()?,
+        )
+        .map(|t| t.format(format).to_string())
+    };
+    let text = match &value {
+        Value::Date(days) => DateTime::from_timestamp(i64::from(*days) * 86_400, 0)
+            .map(|d| d.date_naive().to_string()),
+        Value::TimeMillis(millis) => time(i64::from(*millis) * 1_000, "%H:%M:%S%.3f"),
+        Value::TimeMicros(micros) => time(*micros, "%H:%M:%S%.6f"),
+        Value::TimestampMillis(millis) => DateTime::from_timestamp_millis(*millis)
+            .map(|d| d.to_rfc3339_opts(SecondsFormat::Millis, true)),
+        Value::TimestampMicros(micros) => DateTime::from_timestamp_micros(*micros)
+            .map(|d| d.to_rfc3339_opts(SecondsFormat::Micros, true)),
+        Value::TimestampNanos(nanos) => {
+            Some(DateTime::from_timestamp_nanos(*nanos).to_rfc3339_opts(SecondsFormat::Nanos, true))
+        }
+        Value::LocalTimestampMillis(millis) => DateTime::from_timestamp_millis(*millis)
+            .map(|d| d.naive_utc().format("%Y-%m-%dT%H:%M:%S%.3f").to_string()),
+        Value::LocalTimestampMicros(micros) => DateTime::from_timestamp_micros(*micros)
+            .map(|d| d.naive_utc().format("%Y-%m-%dT%H:%M:%S%.6f").to_string()),
+        Value::LocalTimestampNanos(nanos) => Some(
+            DateTime::from_timestamp_nanos(*nanos)
+                .naive_utc()
+                .format("%Y-%m-%dT%H:%M:%S%.9f")
+                .to_string(),
+        ),
+        _ => None,
+    };
+    match text {
+        Some(text) => serde_json::Value::String(text),
+        None => avro_to_json(value),
+    }
+}
+
+/// A decimal is an unscaled integer in two's complement, `12.50` is `1250` with a scale of 2.
+/// Numbers are exact up to 15 significant digits, larger decimals are rendered as strings.
+fn decimal_to_json(decimal: &Decimal, scale: usize) -> serde_json::Value {
+    let bytes = <Vec<u8>>::try_from(decimal).unwrap_or_default();
+    if bytes.len() > 16 {
+        return avro_to_json(Value::Decimal(decimal.clone()));
+    }
+    let negative = bytes.first().is_some_and(|b| b & 0x80 != 0);
+    let unscaled = bytes
+        .iter()
+        .fold(if negative { -1i128 } else { 0 }, |acc, b| {
+            (acc << 8) | i128::from(*b)
+        });
+    let digits = unscaled.unsigned_abs().to_string();
+    let significant_digits = digits.len();
+    let mut text = match scale {
+        0 => digits,
+        _ => {
+            let digits = format!("{digits:0>width$}", width = scale + 1);
+            let (integer, fraction) = digits.split_at(digits.len() - scale);
+            format!("{integer}.{fraction}")
+        }
+    };
+    if negative {
+        text.insert(0, '-');
+    }
+    match significant_digits <= 15 {
+        true => serde_json::from_str::<Number>(&text)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::String(text)),
+        false => serde_json::Value::String(text),
+    }
+}
+
+#[test]
+fn test_avro_logical_types() {
+    use serde_json::json;
+
+    let schema = Schema::parse_str(
+        r#"{"type": "record", "name": "Payment", "namespace": "com.acme", "fields": [
+            {"name": "id", "type": {"type": "string", "logicalType": "uuid"}},
+            {"name": "amount", "type": {"type": "bytes", "logicalType": "decimal", "precision": 10, "scale": 2}},
+            {"name": "refund", "type": ["null", {"type": "bytes", "logicalType": "decimal", "precision": 10, "scale": 3}]},
+            {"name": "day", "type": {"type": "int", "logicalType": "date"}},
+            {"name": "at", "type": {"type": "int", "logicalType": "time-millis"}},
+            {"name": "created_at", "type": {"type": "long", "logicalType": "timestamp-millis"}}
+        ]}"#,
+    )
+    .unwrap();
+    let value = Value::Record(vec![
+        (
+            "id".to_string(),
+            Value::Uuid(
+                apache_avro::Uuid::parse_str("1b4e28ba-2fa1-11d2-883f-0016d3cca427").unwrap(),
+            ),
+        ),
+        (
+            "amount".to_string(),
+            Value::Decimal(Decimal::from(vec![0x04, 0xE2])),
+        ),
+        (
+            "refund".to_string(),
+            Value::Union(1, Box::new(Value::Decimal(Decimal::from(vec![0xFF, 0x85])))),
+        ),
+        ("day".to_string(), Value::Date(19_983)),
+        ("at".to_string(), Value::TimeMillis(24_299_000)),
+        (
+            "created_at".to_string(),
+            Value::TimestampMillis(1_726_555_499_000),
+        ),
+    ]);
+
+    assert_eq!(
+        avro_to_readable_json(value.clone(), &schema, &NamesRef::new()),
+        json!({
+            "id": "1b4e28ba-2fa1-11d2-883f-0016d3cca427",
+            "amount": 12.5,
+            "refund": -0.123,
+            "day": "2024-09-17",
+            "at": "06:44:59.000",
+            "created_at": "2024-09-17T06:44:59.000Z"
+        })
+    );
+    assert_eq!(
+        avro_to_json(value),
+        json!({
+            "id": "1b4e28ba-2fa1-11d2-883f-0016d3cca427",
+            "amount": [4, 226],
+            "refund": [255, 133],
+            "day": 19_983,
+            "at": 24_299_000,
+            "created_at": 1_726_555_499_000i64
+        })
+    );
+}
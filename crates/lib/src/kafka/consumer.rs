@@ -0,0 +1,151 @@
+//! Kafka consumers reading topics from the offset of a search query,
+//! and a stream of parsed [`KafkaRecord`]s on top of them.
+
+use std::{collections::HashMap, time::Duration};
+
+use rdkafka::{
+    ClientConfig, Offset, TopicPartitionList,
+    config::RDKafkaLogLevel,
+    consumer::{Consumer, ConsumerContext, DefaultConsumerContext, StreamConsumer},
+    error::KafkaError,
+};
+
+use crate::{
+    Error, KafkaRecord, SearchQuery, kafka::SchemaRegistryClient, search::offset::FromOffset,
+};
+
+/// Builds a [`StreamConsumer`] assigned to all the partitions of the topics.
+///
+/// ```no_run
+/// use std::collections::HashMap;
+/// use yozefu_lib::{kafka::ConsumerBuilder, parse_search_query};
+///
+/// let query = parse_search_query("from begin value.status == 'error'").unwrap().1;
+/// let consumer = ConsumerBuilder::new(HashMap::from([(
+///     "bootstrap.servers".to_string(),
+///     "localhost:9092".to_string(),
+/// )]))
+/// .topics(&["payments".to_string()])
+/// .search_query(&query)
+/// .build()
+/// .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConsumerBuilder {
+    properties: HashMap<String, String>,
+    topics: Vec<String>,
+    offset: FromOffset,
+}
+
+impl ConsumerBuilder {
+    /// Creates a builder from kafka consumer properties, see <https://github.com/confluentinc/librdkafka/blob/master/CONFIGURATION.md>.
+    /// Topics are read from their end unless an offset is specified.
+    pub fn new(properties: HashMap<String, String>) -> Self {
+        Self {
+            properties,
+            topics: vec![],
+            offset: FromOffset::End,
+        }
+    }
+
+    /// Topics to read.
+    pub fn topics(mut self, topics: &[String]) -> Self {
+        self.topics = topics.to_vec();
+        self
+    }
+
+    /// Offset from which the partitions are read.
+    pub fn offset(mut self, offset: FromOffset) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Applies the `from` clause and the kafka properties of the `with` clause of the search query.
+    pub fn search_query(mut self, search_query: &SearchQuery) -> Self {
+        if let Some(offset) = &search_query.from {
+            self.offset = offset.clone();
+        }
+        self.properties
+            .extend(search_query.consumer_properties.iter().cloned());
+        self
+    }
+
+    /// Creates the consumer and assigns it the partitions of the topics.
+    pub fn build(self) -> Result<StreamConsumer, Error> {
+        self.build_with_context(DefaultConsumerContext)
+    }
+
+    /// Like [`ConsumerBuilder::build`], with a context receiving the events of the consumer.
+    pub fn build_with_context<C: ConsumerContext + 'static>(
+        self,
+        context: C,
+    ) -> Result<StreamConsumer<C>, Error> {
+        let mut config = ClientConfig::new();
+        config.set_log_level(RDKafkaLogLevel::Emerg);
+        for (key, value) in self.properties {
+            config.set(key, value);
+        }
+        let consumer = config.create_with_context(context)?;
+        assign_partitions(&consumer, &self.topics, &self.offset)?;
+        Ok(consumer)
+    }
+}
+
+/// Assigns all the partitions of the topics to the consumer, starting at the given offset.
+pub fn assign_partitions<C: ConsumerContext + 'static>(
+    consumer: &StreamConsumer<C>,
+    topics: &[String],
+    offset: &FromOffset,
+) -> Result<(), Error> {
+    let mut assignments = TopicPartitionList::new();
+    for topic in topics {
+        let metadata = consumer.fetch_metadata(Some(topic), Duration::from_secs(10))?;
+        for t in metadata.topics() {
+            if let Some(error) = t.error() {
+                return Err(KafkaError::MetadataFetch(error.into()).into());
+            }
+            for p in t.partitions() {
+                assignments.add_partition(topic, p.id());
+            }
+        }
+    }
+    match offset {
+        FromOffset::Beginning => assignments.set_all_offsets(Offset::Beginning)?,
+        FromOffset::End => assignments.set_all_offsets(Offset::End)?,
+        FromOffset::Offset(o) => assignments.set_all_offsets(Offset::Offset(*o))?,
+        FromOffset::OffsetTail(o) => assignments.set_all_offsets(Offset::OffsetTail(*o))?,
+        FromOffset::Timestamp(timestamp) => {
+            assignments.set_all_offsets(Offset::Offset(*timestamp))?;
+            assignments = consumer.offsets_for_times(assignments, Duration::from_secs(60))?;
+        }
+    }
+    consumer.assign(&assignments)?;
+    Ok(())
+}
+
+/// Reads the records of a consumer, decoding their keys and values with the schema registry.
+pub struct RecordStream<C: ConsumerContext + 'static = DefaultConsumerContext> {
+    consumer: StreamConsumer<C>,
+    schema_registry: Option<SchemaRegistryClient>,
+}
+
+impl<C: ConsumerContext + 'static> RecordStream<C> {
+    /// Records encoded with a schema are decoded as JSON when a schema registry is given.
+    pub fn new(consumer: StreamConsumer<C>, schema_registry: Option<SchemaRegistryClient>) -> Self {
+        Self {
+            consumer,
+            schema_registry,
+        }
+    }
+
+    /// Waits for the next record.
+    pub async fn next(&mut self) -> Result<KafkaRecord, Error> {
+        let message = self.consumer.recv().await?.detach();
+        Ok(KafkaRecord::parse(message, &mut self.schema_registry).await)
+    }
+
+    /// The underlying consumer, to commit offsets or fetch metadata for instance.
+    pub fn consumer(&self) -> &StreamConsumer<C> {
+        &self.consumer
+    }
+}
@@ -8,11 +8,15 @@ use serde::Serialize;
 
 use crate::search::compare::StringOperator;
 
+/// A decoded key or value.
 #[derive(Clone, Debug, Deserialize, Serialize, Hash, PartialEq, Eq)]
 #[cfg_attr(test, derive(schemars::JsonSchema))]
 #[serde(untagged)]
+#[non_exhaustive]
 pub enum DataType {
+    /// JSON, or a payload decoded to JSON such as avro, protobuf, MessagePack or CBOR
     Json(serde_json::Value),
+    /// Text, or an error message when the payload could not be decoded
     String(String),
 }
 
@@ -25,13 +29,20 @@ impl From<DataType> for serde_json::Value {
     }
 }
 
+/// Converts a path such as `.user.roles[0]` into the JSON pointer `/user/roles/0`.
+pub(crate) fn to_json_pointer(path: &str) -> String {
+    path.replace(['.', '['], "/").replace(']', "")
+}
+
 impl Default for DataType {
     fn default() -> Self {
         Self::String(String::new())
     }
 }
 
+/// Comparison of a key or a value with the right operand of a search query.
 pub trait Comparable {
+    /// `json_pointer` selects the field to compare, the whole key or value otherwise.
     fn compare(
         &self,
         json_pointer: &Option<String>,
@@ -64,22 +75,19 @@ impl DataType {
         right: &str,
     ) -> bool {
         let v = match json_pointer {
-            Some(path) => {
-                let path = path.replace(['.', '['], "/").replace(']', "");
-                match value.pointer(&path) {
-                    Some(d) => match d {
-                        serde_json::Value::Null => "null".to_string(),
-                        serde_json::Value::Bool(v) => v.to_string(),
-                        serde_json::Value::Number(v) => v.to_string(),
-                        serde_json::Value::String(v) => v.to_string(),
-                        serde_json::Value::Array(_) => return false,
-                        serde_json::Value::Object(_) => return false,
-                    },
-                    None => {
-                        return false;
-                    }
+            Some(path) => match value.pointer(&to_json_pointer(path)) {
+                Some(d) => match d {
+                    serde_json::Value::Null => "null".to_string(),
+                    serde_json::Value::Bool(v) => v.to_string(),
+                    serde_json::Value::Number(v) => v.to_string(),
+                    serde_json::Value::String(v) => v.to_string(),
+                    serde_json::Value::Array(_) => return false,
+                    serde_json::Value::Object(_) => return false,
+                },
+                None => {
+                    return false;
                 }
-            }
+            },
             None => serde_json::to_string(value).unwrap(),
         };
         match operator {
@@ -99,6 +107,16 @@ impl DataType {
         }
     }
 
+    /// Returns the data as a number, for keys decoded as integers or strings of digits.
+    pub fn as_i64(&self) -> Option<i64> {
+        match &self {
+            DataType::Json(serde_json::Value::Number(n)) => n.as_i64(),
+            DataType::Json(serde_json::Value::String(s)) | DataType::String(s) => s.parse().ok(),
+            DataType::Json(_) => None,
+        }
+    }
+
+    /// The value as a string, without quotes around JSON strings.
     pub fn raw(&self) -> String {
         match &self {
             DataType::Json(value) => match value {
@@ -113,6 +131,7 @@ impl DataType {
         }
     }
 
+    /// The value as a string, JSON is indented.
     pub fn to_string_pretty(&self) -> String {
         match &self {
             DataType::Json(value) => serde_json::to_string_pretty(value).unwrap_or_default(),
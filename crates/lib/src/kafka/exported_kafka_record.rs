@@ -9,15 +9,21 @@ use super::KafkaRecord;
 /// with additional fields for analytics purposes.
 #[derive(Clone, Debug, Deserialize, Serialize, Hash, PartialEq, Eq)]
 #[cfg_attr(test, derive(schemars::JsonSchema))]
+#[non_exhaustive]
 pub struct ExportedKafkaRecord {
     #[serde(flatten)]
     pub record: KafkaRecord,
+    /// Timestamp of the record in the local time zone
     date_time: Option<DateTime<Local>>,
     /// Milliseconds elapsed since the first exported kafka record.
     pub absolute_delta_in_ms: i64,
     /// Milliseconds elapsed since the previous exported kafka record.
     pub relative_delta_in_ms: i64,
+    /// The search query the record matched
     pub search_query: String,
+    /// Note attached to the record during the investigation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
 }
 
 impl ExportedKafkaRecord {
@@ -31,6 +37,7 @@ impl ExportedKafkaRecord {
         self.absolute_delta_in_ms = self.record.timestamp.unwrap_or(0) - first_ts.unwrap_or(0);
     }
 
+    /// Records the search query the record matched.
     pub fn set_search_query(&mut self, search_query: &SearchQuery) {
         self.search_query = search_query.to_string();
     }
@@ -45,6 +52,7 @@ impl From<&KafkaRecord> for ExportedKafkaRecord {
             absolute_delta_in_ms: 0,
             relative_delta_in_ms: 0,
             search_query: String::new(),
+            note: None,
         }
     }
 }
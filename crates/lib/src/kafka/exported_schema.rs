@@ -0,0 +1,37 @@
+//! Schemas referenced by exported kafka records,
+//! written next to the export file so records can be decoded offline.
+
+use serde::{Deserialize, Serialize};
+
+use super::{ResolvedReference, SchemaResponse, SubjectVersion, schema::SchemaType};
+
+/// A schema of the schema registry, as written in the schemas file.
+#[derive(Clone, Debug, Deserialize, Serialize, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ExportedSchema {
+    /// Id of the schema in the schema registry
+    pub id: u32,
+    /// Subjects and versions the schema is registered under
+    pub subjects: Vec<SubjectVersion>,
+    /// Type of the schema, the schema registry omits it for avro schemas
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_type: Option<SchemaType>,
+    /// Definition of the schema
+    pub schema: String,
+    /// Schemas referenced by the schema, needed to decode the records
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<ResolvedReference>,
+}
+
+impl ExportedSchema {
+    /// Creates the exported schema from the response of the schema registry.
+    pub fn new(id: u32, subjects: Vec<SubjectVersion>, response: SchemaResponse) -> Self {
+        Self {
+            id,
+            subjects,
+            schema_type: response.schema_type,
+            schema: response.schema,
+            references: response.resolved_references,
+        }
+    }
+}
@@ -5,6 +5,7 @@ use std::io::{Cursor, Error as IoError};
 use super::{OffsetCommitKey, group_metadata_key::GroupMetadataKey};
 
 #[derive(Debug, Serialize, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum ConsumerOffsetKey {
     OffsetCommitKey(OffsetCommitKey),
     GroupMetadataKey(GroupMetadataKey),
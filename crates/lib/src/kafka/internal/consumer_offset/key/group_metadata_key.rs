@@ -3,6 +3,7 @@ use serde::Serialize;
 use std::io::{Cursor, Error as IoError, Read};
 
 #[derive(Debug, Serialize, PartialEq, Eq)]
+#[non_exhaustive]
 pub struct GroupMetadataKey {
     pub group: String,
 }
@@ -5,6 +5,7 @@ use serde::Serialize;
 use std::io::Error as IoError;
 
 #[derive(Debug, Serialize, PartialEq, Eq)]
+#[non_exhaustive]
 pub struct OffsetCommitKey {
     group: String,
     topic: String,
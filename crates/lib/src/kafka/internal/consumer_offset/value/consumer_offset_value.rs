@@ -3,6 +3,7 @@ use serde::Serialize;
 use std::io::{Cursor, Error, ErrorKind, Read};
 
 #[derive(Debug, Serialize, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum ConsumerOffsetValue {
     OffsetAndMetadata(OffsetAndMetadata),
 }
@@ -29,6 +30,7 @@ impl TryFrom<&[u8]> for ConsumerOffsetValue {
 }
 
 #[derive(Debug, Serialize, PartialEq, Eq)]
+#[non_exhaustive]
 pub struct OffsetAndMetadata {
     offset: i64,
     leader_epoch: Option<i32>,
@@ -81,6 +83,7 @@ impl TryFrom<&[u8]> for OffsetAndMetadata {
     }
 }
 
+#[non_exhaustive]
 pub struct OffsetCommitValue {
     pub offset: i64,
     pub leader_epoch: i32,
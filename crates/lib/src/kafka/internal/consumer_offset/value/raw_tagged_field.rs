@@ -1,3 +1,4 @@
+#[non_exhaustive]
 pub struct RawTaggedField {
     tag: i32,
     data: Vec<u8>,
@@ -1,8 +1,11 @@
 #[cfg(feature = "native")]
 use super::SchemaRegistryClient;
 #[cfg(feature = "native")]
-use super::avro::avro_to_json;
+use super::avro::{avro_to_json, avro_to_readable_json};
 use super::data_type::DataType;
+use super::payload_format::PayloadFormat;
+#[cfg(feature = "native")]
+use super::payload_format::{Encoding, detect_and_decode};
 use super::schema::Schema;
 #[cfg(feature = "native")]
 use super::schema::SchemaId;
@@ -13,37 +16,51 @@ use super::schema_registry_client::SchemaResponse;
 #[cfg(feature = "native")]
 use crate::kafka::internal::extract_key_and_value_from_consumer_offsets_topics;
 #[cfg(feature = "native")]
-use apache_avro::from_avro_datum;
+use apache_avro::{from_avro_datum_schemata, schema::ResolvedSchema};
 #[cfg(feature = "native")]
 use chrono::{DateTime, Local, Utc};
 #[cfg(feature = "native")]
-use rdkafka::message::{Headers, Message, OwnedMessage};
+use rdkafka::Timestamp;
+#[cfg(feature = "native")]
+use rdkafka::message::{Headers, Message, OwnedHeaders, OwnedMessage};
 use serde::Deserialize;
 use serde::Serialize;
 #[cfg(feature = "native")]
 use serde_json::Error;
 use std::collections::BTreeMap;
+use std::fmt::Display;
 
 /// Inspired of the `[rdkafka::Message]` struct.
 /// Currently, we only support utf-8 string keys/values/headers.
 #[derive(Clone, Debug, Deserialize, Serialize, Hash, PartialEq, Eq, Default)]
 #[serde(rename_all = "lowercase")]
 #[cfg_attr(test, derive(schemars::JsonSchema))]
+#[non_exhaustive]
 pub struct KafkaRecord {
     pub topic: String,
+    /// Milliseconds since the unix epoch
     pub timestamp: Option<i64>,
+    /// Whether the timestamp has been set by the producer or by the broker
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_type: Option<TimestampType>,
     pub partition: i32,
     pub offset: i64,
     pub headers: BTreeMap<String, String>,
+    /// Schema the key has been serialized with, according to the schema registry
     #[serde(skip_serializing_if = "Option::is_none")]
     pub key_schema: Option<Schema>,
+    /// Schema the value has been serialized with, according to the schema registry
     #[serde(skip_serializing_if = "Option::is_none")]
     pub value_schema: Option<Schema>,
+    /// Format of the value, detected when the record is parsed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_format: Option<PayloadFormat>,
     /// Number of bytes for the key + the value
     #[serde(default)]
     pub size: usize,
     /// A human-readable representation of the key
     pub key: DataType,
+    /// The key as a string, needed to be displayed in the TUI
     #[serde(skip_serializing, default)]
     pub key_as_string: String,
     /// A human-readable representation of the value
@@ -51,26 +68,94 @@ pub struct KafkaRecord {
     #[serde(skip_serializing, default)]
     /// The value as a string. needed to be displayed in the TUI
     pub value_as_string: String,
+    /// The bytes of the value when they could not be decoded, the value is then an error message or a lossy string
+    #[serde(skip)]
+    pub raw_value: Option<Vec<u8>>,
+    /// The avro value with its logical types rendered as readable values, such as decimals as numbers or dates as ISO 8601 strings.
+    /// Only the record details show it, [`KafkaRecord::value`] keeps the logical types as they are encoded.
+    /// `None` when the value has no logical type.
+    #[serde(skip)]
+    pub readable_value: Option<serde_json::Value>,
+    /// The value has been truncated to save memory, the record must be read again to get the full value
+    #[serde(skip)]
+    pub truncated: bool,
+}
+
+/// Who set the timestamp of a record, according to the `message.timestamp.type` of the topic.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Hash, PartialEq, Eq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub enum TimestampType {
+    /// The producer set the timestamp, it can be anything the producer wants
+    CreateTime,
+    /// The broker set the timestamp when it appended the record to the log
+    LogAppendTime,
+}
+
+impl Display for TimestampType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimestampType::CreateTime => write!(f, "CreateTime"),
+            TimestampType::LogAppendTime => write!(f, "LogAppendTime"),
+        }
+    }
 }
 
 #[cfg(feature = "native")]
 impl KafkaRecord {
+    /// The timestamp of the record, `None` if it is out of range.
     pub fn timestamp_as_utc_date_time(&self) -> Option<DateTime<Utc>> {
         DateTime::from_timestamp_millis(self.timestamp.unwrap_or(0))
     }
 
+    /// The timestamp of the record in the local time zone.
     pub fn timestamp_as_local_date_time(&self) -> Option<DateTime<Local>> {
         self.timestamp_as_utc_date_time()
             .map(DateTime::<Local>::from)
     }
 
+    /// The time the broker appended the record to the log, `None` when the timestamp has been set by the producer.
+    pub fn log_timestamp_as_local_date_time(&self) -> Option<DateTime<Local>> {
+        match self.timestamp_type {
+            Some(TimestampType::LogAppendTime) => self.timestamp_as_local_date_time(),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the key or the value has been serialized with a schema.
     pub fn has_schemas(&self) -> bool {
         self.key_schema.is_some() || self.value_schema.is_some()
     }
+
+    /// Truncates the value to a preview of about `max` bytes, returns `true` if the value was longer.
+    pub fn truncate_value(&mut self, max: usize) -> bool {
+        if max == 0 || self.value_as_string.len() <= max {
+            return false;
+        }
+        let mut end = max;
+        while !self.value_as_string.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.value_as_string.truncate(end);
+        self.value_as_string.push('…');
+        self.value = DataType::String(self.value_as_string.clone());
+        self.raw_value = None;
+        self.truncated = true;
+        true
+    }
+
+    /// A record without value deletes its key from a compacted topic.
+    /// Once parsed, an empty value cannot be told apart from a missing one, so both are tombstones.
+    pub fn is_tombstone(&self) -> bool {
+        self.value_schema.is_none() && matches!(&self.value, DataType::String(s) if s.is_empty())
+    }
 }
 
 #[cfg(feature = "native")]
 impl KafkaRecord {
+    /// Converts a kafka message into a record.
+    /// Keys and values serialized with a schema are decoded with the schema registry, if any,
+    /// other payloads are decoded according to their detected format.
     pub async fn parse(
         owned_message: OwnedMessage,
         schema_registry: &mut Option<SchemaRegistryClient>,
@@ -93,17 +178,46 @@ impl KafkaRecord {
         let size = owned_message.payload().map_or(0, <[u8]>::len)
             + owned_message.key().map_or(0, <[u8]>::len);
 
-        let (key, key_schema, value, value_schema) = match owned_message.topic() {
-            "__consumer_offsets" => {
-                extract_key_and_value_from_consumer_offsets_topics(&owned_message)
-            }
-            _ => {
-                let (key, key_schema) =
-                    Self::extract_data_and_schema(owned_message.key(), schema_registry).await;
-                let (value, value_schema) =
-                    Self::extract_data_and_schema(owned_message.payload(), schema_registry).await;
-                (key, key_schema, value, value_schema)
+        let (key, key_schema, value, value_schema, value_format, readable_value) =
+            match owned_message.topic() {
+                "__consumer_offsets" => {
+                    let (key, key_schema, value, value_schema) =
+                        extract_key_and_value_from_consumer_offsets_topics(&owned_message);
+                    (key, key_schema, value, value_schema, None, None)
+                }
+                topic => {
+                    let mut schema_registry = schema_registry.as_mut().map(|s| s.for_topic(topic));
+                    let (key, key_schema, _, _) = Self::extract_data_and_schema(
+                        owned_message.key(),
+                        owned_message.headers(),
+                        true,
+                        &mut schema_registry,
+                    )
+                    .await;
+                    let (value, value_schema, value_format, readable_value) =
+                        Self::extract_data_and_schema(
+                            owned_message.payload(),
+                            owned_message.headers(),
+                            false,
+                            &mut schema_registry,
+                        )
+                        .await;
+                    (
+                        key,
+                        key_schema,
+                        value,
+                        value_schema,
+                        value_format,
+                        readable_value,
+                    )
+                }
+            };
+
+        let raw_value = match (&value, owned_message.payload()) {
+            (DataType::String(s), Some(payload)) if s.as_bytes() != payload => {
+                Some(payload.to_vec())
             }
+            _ => None,
         };
 
         Self {
@@ -113,12 +227,21 @@ impl KafkaRecord {
             key,
             topic: owned_message.topic().to_string(),
             timestamp: owned_message.timestamp().to_millis(),
+            timestamp_type: match owned_message.timestamp() {
+                Timestamp::NotAvailable => None,
+                Timestamp::CreateTime(_) => Some(TimestampType::CreateTime),
+                Timestamp::LogAppendTime(_) => Some(TimestampType::LogAppendTime),
+            },
             partition: owned_message.partition(),
             offset: owned_message.offset(),
             headers,
             key_schema,
             value_schema,
+            value_format,
             size,
+            raw_value,
+            readable_value,
+            truncated: false,
         }
     }
 
@@ -130,7 +253,7 @@ impl KafkaRecord {
         let schema = schema.as_ref().unwrap();
         match schema.schema_type {
             Some(SchemaType::Json) => Self::deserialize_json(payload),
-            Some(SchemaType::Avro) => Self::deserialize_avro(payload, &schema.schema),
+            Some(SchemaType::Avro) => Self::deserialize_avro(payload, schema).0,
             Some(SchemaType::Protobuf) => Self::deserialize_protobuf(payload, &schema.schema),
             None => Self::deserialize_json(payload),
         }
@@ -157,26 +280,44 @@ impl KafkaRecord {
         }
     }
 
-    fn deserialize_avro(payload: Option<&[u8]>, schema: &str) -> DataType {
+    /// Decodes an avro payload, the schemas referenced by the schema of the record are resolved by their full name.
+    /// Logical types are kept as they are encoded, the value with readable logical types is returned too when it differs.
+    fn deserialize_avro(
+        payload: Option<&[u8]>,
+        schema: &SchemaResponse,
+    ) -> (DataType, Option<serde_json::Value>) {
         let mut payload = payload.unwrap_or_default();
-        let parsed_schema = apache_avro::Schema::parse_str(schema);
+        let parsed_schema = schema.avro_schema();
         if let Err(e) = &parsed_schema {
-            return DataType::String(format!(
+            let error = DataType::String(format!(
                 "  Yozefu Error: The avro schema could not be parsed. Please check the schema in the schema registry.\n       Error: {}\n       Payload: {:?}\n        String: {}",
                 e,
                 payload,
                 String::from_utf8(payload.to_vec()).unwrap_or_default()
             ));
+            return (error, None);
         }
-        let parsed_schema = parsed_schema.unwrap();
-        match from_avro_datum(&parsed_schema, &mut payload, None) {
-            Ok(value) => DataType::Json(avro_to_json(value)),
-            Err(e) => DataType::String(format!(
-                "  Yozefu Error: According to the schema registry, the record is serialized as avro but there was an issue deserializing the payload: {:?}\n       Payload: {:?}\n        String: {}",
-                e,
-                payload,
-                String::from_utf8(payload.to_vec()).unwrap_or_default()
-            )),
+        let (parsed_schema, references) = parsed_schema.unwrap();
+        let schemata: Vec<_> = references.iter().chain([&parsed_schema]).collect();
+        match from_avro_datum_schemata(&parsed_schema, schemata.clone(), &mut payload, None) {
+            Ok(value) => {
+                let names = ResolvedSchema::try_from(schemata)
+                    .map(|resolved| resolved.get_names().clone())
+                    .unwrap_or_default();
+                let encoded = avro_to_json(value.clone());
+                let readable = avro_to_readable_json(value, &parsed_schema, &names);
+                let readable = (readable != encoded).then_some(readable);
+                (DataType::Json(encoded), readable)
+            }
+            Err(e) => (
+                DataType::String(format!(
+                    "  Yozefu Error: According to the schema registry, the record is serialized as avro but there was an issue deserializing the payload: {:?}\n       Payload: {:?}\n        String: {}",
+                    e,
+                    payload,
+                    String::from_utf8(payload.to_vec()).unwrap_or_default()
+                )),
+                None,
+            ),
         }
     }
 
@@ -202,20 +343,47 @@ impl KafkaRecord {
 
     async fn extract_data_and_schema(
         payload: Option<&[u8]>,
-        schema_registry: &mut Option<SchemaRegistryClient>,
-    ) -> (DataType, Option<Schema>) {
-        let schema_id = SchemaId::parse(payload);
+        headers: Option<&OwnedHeaders>,
+        key: bool,
+        schema_registry: &mut Option<&mut SchemaRegistryClient>,
+    ) -> (
+        DataType,
+        Option<Schema>,
+        Option<PayloadFormat>,
+        Option<serde_json::Value>,
+    ) {
+        let (schema_id, offset) = match schema_registry.as_ref() {
+            Some(schema_registry) => schema_registry.schema_id(payload, headers, key),
+            None => SchemaId::parse(payload).map(|id| (id, 5)),
+        }
+        .unzip();
         match (schema_id, schema_registry.as_mut()) {
-            (None, _) => (Self::payload_to_data_type(payload, None), None),
+            (None, _) => match payload {
+                Some(p) if !p.is_empty() => {
+                    let (data, format) = detect_and_decode(p);
+                    (data, None, Some(format), None)
+                }
+                _ => (Self::payload_to_data_type(payload, None), None, None, None),
+            },
             (Some(id), None) => {
                 let payload = payload.unwrap_or_default();
                 match serde_json::from_slice(payload) {
-                    Ok(e) => (DataType::Json(e), None),
+                    Ok(e) => (
+                        DataType::Json(e),
+                        None,
+                        Some(PayloadFormat::new(Encoding::Json)),
+                        None,
+                    ),
                     Err(_e) => {
                         match Self::try_deserialize_json(
                             Self::extract_data_from_payload_with_schema_header(payload),
                         ) {
-                            Ok(e) => (e, Some(Schema::new(id, None))),
+                            Ok(e) => (
+                                e,
+                                Some(Schema::new(id, None)),
+                                Some(PayloadFormat::with_wire_format(Encoding::Json)),
+                                None,
+                            ),
                             Err(_e) => (
                                 DataType::String(format!(
                                     "Yozefu was not able to retrieve the schema {} because there is no schema registry configured. Please visit https://github.com/MAIF/yozefu/blob/main/docs/schema-registry/README.md for more details.\nPayload: {:?}\n String: {}",
@@ -224,6 +392,8 @@ impl KafkaRecord {
                                     String::from_utf8(payload.to_vec()).unwrap_or_default()
                                 )),
                                 Some(Schema::new(id, None)),
+                                Some(PayloadFormat::with_wire_format(Encoding::Binary)),
+                                None,
                             ),
                         }
                     }
@@ -245,20 +415,39 @@ impl KafkaRecord {
                                 String::from_utf8(payload.to_vec()).unwrap_or_default()
                             )),
                             Some(Schema::new(s, None)),
+                            Some(PayloadFormat::with_wire_format(Encoding::Binary)),
+                            None,
                         );
                     }
                 };
-                match p.len() <= 5 {
-                    true => (
+                let format = Some(PayloadFormat::with_wire_format(
+                    match schema_response
+                        .as_ref()
+                        .and_then(|s| s.schema_type.as_ref())
+                    {
+                        Some(SchemaType::Avro) => Encoding::Avro,
+                        Some(SchemaType::Protobuf) => Encoding::Protobuf,
+                        Some(SchemaType::Json) | None => Encoding::Json,
+                    },
+                ));
+                let offset = offset.unwrap_or_default();
+                let payload = match p.len() <= offset {
+                    true => payload,
+                    false => payload.map(|e| e[offset..].as_ref()),
+                };
+                match schema_response
+                    .as_ref()
+                    .filter(|s| s.schema_type == Some(SchemaType::Avro))
+                {
+                    Some(avro_schema) => {
+                        let (data, readable_value) = Self::deserialize_avro(payload, avro_schema);
+                        (data, schema, format, readable_value)
+                    }
+                    None => (
                         Self::payload_to_data_type(payload, schema_response.as_ref()),
                         schema,
-                    ),
-                    false => (
-                        Self::payload_to_data_type(
-                            payload.map(|e| e[5..].as_ref()),
-                            schema_response.as_ref(),
-                        ),
-                        schema,
+                        format,
+                        None,
                     ),
                 }
             }
@@ -33,32 +33,42 @@ fn test_has_schemas() {
     let record = KafkaRecord {
         topic: "topic".into(),
         timestamp: None,
+        timestamp_type: None,
         partition: 1,
         offset: 32,
         headers: BTreeMap::default(),
         key_schema: Some(Schema::new(SchemaId(12), None)),
         value_schema: Some(Schema::new(SchemaId(13), None)),
+        value_format: None,
         size: 32,
         key_as_string: "".into(),
         key: DataType::String("".into()),
         value_as_string: "".into(),
         value: DataType::String("".into()),
+        raw_value: None,
+        readable_value: None,
+        truncated: false,
     };
     assert!(record.has_schemas());
 
     let record = KafkaRecord {
         topic: "topic".into(),
         timestamp: None,
+        timestamp_type: None,
         partition: 1,
         offset: 32,
         headers: BTreeMap::default(),
         key_schema: None,
         value_schema: None,
+        value_format: None,
         size: 32,
         key_as_string: "".into(),
         key: DataType::String("".into()),
         value_as_string: "".into(),
         value: DataType::String("".into()),
+        raw_value: None,
+        readable_value: None,
+        truncated: false,
     };
 
     assert!(!record.has_schemas());
@@ -81,3 +91,41 @@ fn generate_json_schema_for_kafka_record() {
     fs::create_dir_all(output_file.parent().unwrap()).unwrap();
     fs::write(output_file, serde_json::to_string_pretty(&schema).unwrap()).unwrap();
 }
+
+#[tokio::test]
+async fn test_raw_value_of_undecodable_payload() {
+    let message = |payload: &[u8]| {
+        OwnedMessage::new(
+            Some(payload.to_vec()),
+            None,
+            "my-awesome-topic".to_string(),
+            rdkafka::Timestamp::CreateTime(0),
+            0,
+            0,
+            None,
+        )
+    };
+    let record = KafkaRecord::parse(message(b"\xca\xfe\xba\xbe"), &mut None).await;
+    assert_eq!(record.raw_value, Some(b"\xca\xfe\xba\xbe".to_vec()));
+
+    let record = KafkaRecord::parse(message(b"hello"), &mut None).await;
+    assert_eq!(record.raw_value, None);
+}
+
+#[test]
+fn test_truncate_value() {
+    let mut record = KafkaRecord {
+        value: DataType::Json(serde_json::json!({"city": "Orléans"})),
+        value_as_string: r#"{"city":"Orléans"}"#.into(),
+        ..Default::default()
+    };
+    assert!(!record.truncate_value(0));
+    assert!(!record.truncate_value(100));
+    assert!(!record.truncated);
+
+    // 'é' takes 2 bytes, the value is truncated before it
+    assert!(record.truncate_value(13));
+    assert_eq!(record.value_as_string, r#"{"city":"Orl…"#);
+    assert_eq!(record.value, DataType::String(r#"{"city":"Orl…"#.into()));
+    assert!(record.truncated);
+}
@@ -1,28 +1,53 @@
+//! The kafka record model, its decoding, the schema registry client and the consumers reading records.
+#[cfg(feature = "native")]
+mod consumer;
+#[cfg(feature = "native")]
+pub use consumer::{ConsumerBuilder, RecordStream, assign_partitions};
 #[cfg(feature = "native")]
 pub mod exported_kafka_record;
 #[cfg(feature = "native")]
 pub use exported_kafka_record::ExportedKafkaRecord;
 #[cfg(feature = "native")]
+mod exported_schema;
+#[cfg(feature = "native")]
+pub use exported_schema::ExportedSchema;
+#[cfg(feature = "native")]
 mod schema_registry_client;
 #[cfg(feature = "native")]
 pub mod topic;
 #[cfg(feature = "native")]
 pub use schema_registry_client::SchemaRegistryClient;
 #[cfg(feature = "native")]
+pub use schema_registry_client::{
+    ApicurioOptions, SchemaRegistryAuth, SchemaRegistryKind, SchemaRegistryOptions,
+};
+#[cfg(feature = "native")]
 mod avro;
 #[cfg(feature = "native")]
 pub use schema_registry_client::SchemaResponse;
 #[cfg(feature = "native")]
+pub use schema_registry_client::{
+    ResolvedReference, SchemaReference, SchemaVersionResponse, SubjectVersion,
+};
+#[cfg(feature = "native")]
 mod internal;
+#[cfg(feature = "native")]
+mod snappy;
 
 mod data_type;
 
 mod kafka_record;
+mod payload_format;
 mod schema;
 pub use data_type::Comparable;
 pub use data_type::DataType;
-pub use kafka_record::KafkaRecord;
-pub use schema::SchemaId;
+#[cfg(feature = "native")]
+pub(crate) use data_type::to_json_pointer;
+pub use kafka_record::{KafkaRecord, TimestampType};
+pub use payload_format::{Compression, Encoding, PayloadFormat};
+#[cfg(feature = "native")]
+pub use payload_format::{from_cbor, from_message_pack};
+pub use schema::{Schema, SchemaId, SchemaType};
 
 #[cfg(test)]
 mod kafka_record_test;
@@ -0,0 +1,401 @@
+//! Detection of the format of the payload of kafka records.
+//! Payloads are not always JSON or serialized with a schema registry: they can be compressed by the producer
+//! or serialized with MessagePack, CBOR or protobuf without any schema.
+#[cfg(feature = "native")]
+use std::{fmt::Display, io::Cursor, io::Read};
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "native")]
+use super::DataType;
+
+/// Compression applied by the producer to the payload, detected with the magic bytes of the frame.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub enum Compression {
+    Gzip,
+    Zstd,
+    Snappy,
+    Lz4,
+}
+
+/// How the bytes of the payload are encoded, once decompressed.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Hash, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub enum Encoding {
+    Json,
+    Avro,
+    Protobuf,
+    MessagePack,
+    Cbor,
+    Text,
+    Binary,
+}
+
+/// The detected format of a payload.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Hash, PartialEq, Eq)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct PayloadFormat {
+    /// Compression applied by the producer, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compression: Option<Compression>,
+    /// Number of bytes of the payload once decompressed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uncompressed_size: Option<usize>,
+    /// Encoding of the payload once decompressed
+    pub encoding: Encoding,
+    /// The payload starts with the magic byte and the schema id of the Confluent wire format
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub wire_format: bool,
+}
+
+impl PayloadFormat {
+    /// A format without compression nor wire format.
+    pub fn new(encoding: Encoding) -> Self {
+        Self {
+            compression: None,
+            uncompressed_size: None,
+            encoding,
+            wire_format: false,
+        }
+    }
+
+    /// A format with the magic byte and the schema id of the Confluent wire format.
+    pub fn with_wire_format(encoding: Encoding) -> Self {
+        Self {
+            wire_format: true,
+            ..Self::new(encoding)
+        }
+    }
+}
+
+#[cfg(feature = "native")]
+impl Display for Compression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Compression::Gzip => write!(f, "gzip"),
+            Compression::Zstd => write!(f, "zstd"),
+            Compression::Snappy => write!(f, "snappy"),
+            Compression::Lz4 => write!(f, "lz4"),
+        }
+    }
+}
+
+#[cfg(feature = "native")]
+impl Display for Encoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Encoding::Json => write!(f, "JSON"),
+            Encoding::Avro => write!(f, "Avro"),
+            Encoding::Protobuf => write!(f, "Protobuf"),
+            Encoding::MessagePack => write!(f, "MessagePack"),
+            Encoding::Cbor => write!(f, "CBOR"),
+            Encoding::Text => write!(f, "Text"),
+            Encoding::Binary => write!(f, "Binary"),
+        }
+    }
+}
+
+#[cfg(feature = "native")]
+impl Display for PayloadFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(compression) = &self.compression {
+            write!(f, "{compression} + ")?;
+        }
+        write!(f, "{}", self.encoding)?;
+        if self.wire_format {
+            write!(f, " (Confluent wire format)")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "native")]
+const GZIP_MAGIC_BYTES: &[u8] = &[0x1f, 0x8b];
+#[cfg(feature = "native")]
+const ZSTD_MAGIC_BYTES: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+#[cfg(feature = "native")]
+const SNAPPY_MAGIC_BYTES: &[u8] = b"\xff\x06\x00\x00sNaPpY";
+#[cfg(feature = "native")]
+const LZ4_MAGIC_BYTES: &[u8] = &[0x04, 0x22, 0x4d, 0x18];
+
+/// Payloads larger than this once decompressed are not decoded, a small compressed payload can expand to gigabytes
+#[cfg(feature = "native")]
+pub(crate) const MAX_DECOMPRESSED_LEN: usize = 64 * 1024 * 1024;
+
+/// Maximum depth of nested protobuf messages decoded without schema
+#[cfg(feature = "native")]
+const MAX_PROTOBUF_DEPTH: usize = 8;
+
+/// Decodes a payload that is not in the Confluent wire format.
+/// The payload is decompressed first, then decoded as JSON, text, MessagePack, CBOR or protobuf, in that order.
+#[cfg(feature = "native")]
+pub(crate) fn detect_and_decode(payload: &[u8]) -> (DataType, PayloadFormat) {
+    let compression = [
+        (GZIP_MAGIC_BYTES, Compression::Gzip),
+        (ZSTD_MAGIC_BYTES, Compression::Zstd),
+        (SNAPPY_MAGIC_BYTES, Compression::Snappy),
+        (LZ4_MAGIC_BYTES, Compression::Lz4),
+    ]
+    .into_iter()
+    .find(|(magic, _)| payload.starts_with(magic))
+    .map(|(_, c)| c);
+
+    let Some(compression) = compression else {
+        return decode(payload);
+    };
+    let decompressed = match compression {
+        Compression::Gzip => read_to_limit(flate2::read::GzDecoder::new(payload)),
+        Compression::Zstd => zstd::stream::read::Decoder::new(payload)
+            .map_err(|e| e.to_string())
+            .and_then(read_to_limit),
+        Compression::Snappy => super::snappy::decompress_frames(payload),
+        Compression::Lz4 => Err(format!("{compression} decompression is not supported yet")),
+    };
+    match decompressed {
+        Ok(bytes) => {
+            let (data, format) = decode(&bytes);
+            (
+                data,
+                PayloadFormat {
+                    compression: Some(compression),
+                    uncompressed_size: Some(bytes.len()),
+                    ..format
+                },
+            )
+        }
+        Err(e) => (
+            DataType::String(format!(
+                "Yozefu Error: The payload looks compressed with {compression} but it could not be decompressed: {e}"
+            )),
+            PayloadFormat {
+                compression: Some(compression),
+                ..PayloadFormat::new(Encoding::Binary)
+            },
+        ),
+    }
+}
+
+/// Reads the decompressed bytes, an error is returned when there are more than [`MAX_DECOMPRESSED_LEN`] bytes.
+#[cfg(feature = "native")]
+fn read_to_limit(reader: impl Read) -> Result<Vec<u8>, String> {
+    let mut bytes = vec![];
+    reader
+        .take(MAX_DECOMPRESSED_LEN as u64 + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| e.to_string())?;
+    if bytes.len() > MAX_DECOMPRESSED_LEN {
+        return Err(too_large());
+    }
+    Ok(bytes)
+}
+
+#[cfg(feature = "native")]
+pub(crate) fn too_large() -> String {
+    format!("the payload is larger than {MAX_DECOMPRESSED_LEN} bytes once decompressed")
+}
+
+#[cfg(feature = "native")]
+fn decode(payload: &[u8]) -> (DataType, PayloadFormat) {
+    if let Ok(value) = serde_json::from_slice(payload) {
+        return (DataType::Json(value), PayloadFormat::new(Encoding::Json));
+    }
+    if let Some(text) = as_text(payload) {
+        return (DataType::String(text), PayloadFormat::new(Encoding::Text));
+    }
+    if let Some(value) = from_message_pack(payload).filter(is_map_or_array) {
+        return (
+            DataType::Json(value),
+            PayloadFormat::new(Encoding::MessagePack),
+        );
+    }
+    if let Some(value) = from_cbor(payload).filter(is_map_or_array) {
+        return (DataType::Json(value), PayloadFormat::new(Encoding::Cbor));
+    }
+    if let Some(value) = decode_protobuf(payload, 0) {
+        return (
+            DataType::Json(value),
+            PayloadFormat::new(Encoding::Protobuf),
+        );
+    }
+    (
+        DataType::String(String::from_utf8_lossy(payload).to_string()),
+        PayloadFormat::new(Encoding::Binary),
+    )
+}
+
+/// UTF-8 without control characters, other than whitespaces.
+#[cfg(feature = "native")]
+fn as_text(payload: &[u8]) -> Option<String> {
+    let text = std::str::from_utf8(payload).ok()?;
+    match text.chars().any(|c| c.is_control() && !c.is_whitespace()) {
+        true => None,
+        false => Some(text.to_string()),
+    }
+}
+
+/// Decodes a MessagePack payload into JSON, all the bytes must be read.
+#[cfg(feature = "native")]
+pub fn from_message_pack(payload: &[u8]) -> Option<serde_json::Value> {
+    let mut cursor = Cursor::new(payload);
+    let value: serde_json::Value = rmp_serde::from_read(&mut cursor).ok()?;
+    (cursor.position() as usize == payload.len()).then_some(value)
+}
+
+/// Decodes a CBOR payload into JSON, all the bytes must be read.
+#[cfg(feature = "native")]
+pub fn from_cbor(payload: &[u8]) -> Option<serde_json::Value> {
+    let mut cursor = Cursor::new(payload);
+    let value: serde_json::Value = ciborium::from_reader(&mut cursor).ok()?;
+    (cursor.position() as usize == payload.len()).then_some(value)
+}
+
+/// Scalars are valid MessagePack or CBOR payloads more often than not, only maps and arrays are detected.
+#[cfg(feature = "native")]
+fn is_map_or_array(value: &serde_json::Value) -> bool {
+    value.is_object() || value.is_array()
+}
+
+/// Decodes a protobuf message without its schema, fields are named after their number.
+/// Length-delimited fields are decoded as text, then as nested messages, otherwise as bytes in hexadecimal.
+#[cfg(feature = "native")]
+fn decode_protobuf(mut payload: &[u8], depth: usize) -> Option<serde_json::Value> {
+    if payload.is_empty() || depth > MAX_PROTOBUF_DEPTH {
+        return None;
+    }
+    let mut fields = serde_json::Map::new();
+    while !payload.is_empty() {
+        let tag = read_varint(&mut payload)?;
+        let number = tag >> 3;
+        if number == 0 || number > 536_870_911 {
+            return None;
+        }
+        let value: serde_json::Value = match tag & 0x7 {
+            0 => read_varint(&mut payload)?.into(),
+            1 => u64::from_le_bytes(take(&mut payload, 8)?.try_into().ok()?).into(),
+            2 => {
+                let length = usize::try_from(read_varint(&mut payload)?).ok()?;
+                let bytes = take(&mut payload, length)?;
+                match as_text(bytes) {
+                    Some(text) => text.into(),
+                    None => decode_protobuf(bytes, depth + 1).unwrap_or_else(|| {
+                        bytes
+                            .iter()
+                            .map(|b| format!("{b:02x}"))
+                            .collect::<String>()
+                            .into()
+                    }),
+                }
+            }
+            5 => u32::from_le_bytes(take(&mut payload, 4)?.try_into().ok()?).into(),
+            _ => return None,
+        };
+        // Repeated fields are grouped in an array
+        match fields.get_mut(&number.to_string()) {
+            Some(serde_json::Value::Array(values)) => values.push(value),
+            Some(previous) => *previous = serde_json::Value::Array(vec![previous.take(), value]),
+            None => {
+                fields.insert(number.to_string(), value);
+            }
+        }
+    }
+    Some(serde_json::Value::Object(fields))
+}
+
+#[cfg(feature = "native")]
+fn read_varint(payload: &mut &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+    for (i, byte) in payload.iter().enumerate().take(10) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            *payload = &payload[i + 1..];
+            return Some(value);
+        }
+    }
+    None
+}
+
+#[cfg(feature = "native")]
+fn take<'a>(payload: &mut &'a [u8], length: usize) -> Option<&'a [u8]> {
+    if payload.len() < length {
+        return None;
+    }
+    let (bytes, rest) = payload.split_at(length);
+    *payload = rest;
+    Some(bytes)
+}
+
+#[test]
+fn test_detect_and_decode() {
+    use serde_json::json;
+    use std::io::Write;
+
+    let value = json!({"name": "alice"});
+    let mut gzip = flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
+    gzip.write_all(value.to_string().as_bytes()).unwrap();
+    let (data, format) = detect_and_decode(&gzip.finish().unwrap());
+    assert_eq!(data, DataType::Json(value.clone()));
+    assert_eq!(format.to_string(), "gzip + JSON");
+
+    let zstd = zstd::encode_all(value.to_string().as_bytes(), 0).unwrap();
+    assert_eq!(
+        detect_and_decode(&zstd).1.compression,
+        Some(Compression::Zstd)
+    );
+
+    let (data, format) = detect_and_decode(&rmp_serde::to_vec(&value).unwrap());
+    assert_eq!(data, DataType::Json(value.clone()));
+    assert_eq!(format, PayloadFormat::new(Encoding::MessagePack));
+
+    let mut cbor = vec![];
+    ciborium::into_writer(&value, &mut cbor).unwrap();
+    let (data, format) = detect_and_decode(&cbor);
+    assert_eq!(data, DataType::Json(value));
+    assert_eq!(format, PayloadFormat::new(Encoding::Cbor));
+
+    let (data, format) = detect_and_decode(b"\x08\x96\x01\x12\x07testing\x08\x01");
+    assert_eq!(data, DataType::Json(json!({"1": [150, 1], "2": "testing"})));
+    assert_eq!(format, PayloadFormat::new(Encoding::Protobuf));
+
+    assert_eq!(
+        detect_and_decode(b"hello world").1,
+        PayloadFormat::new(Encoding::Text)
+    );
+    assert_eq!(
+        detect_and_decode(b"\xff\xfe\x00").1,
+        PayloadFormat::new(Encoding::Binary)
+    );
+    assert_eq!(
+        detect_and_decode(b"\xff\x06\x00\x00sNaPpY\x01\x06\x00\x00\x00\x00\x00\x00hi").1,
+        PayloadFormat {
+            compression: Some(Compression::Snappy),
+            uncompressed_size: Some(2),
+            ..PayloadFormat::new(Encoding::Text)
+        }
+    );
+    assert_eq!(
+        detect_and_decode(b"\x04\x22\x4d\x18\x00").1.to_string(),
+        "lz4 + Binary"
+    );
+}
+
+#[test]
+fn test_decompress_oversized_payload() {
+    use std::io::Write;
+
+    let zeros = vec![0; MAX_DECOMPRESSED_LEN + 1];
+    let mut gzip = flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
+    gzip.write_all(&zeros).unwrap();
+    let gzip = gzip.finish().unwrap();
+    let zstd = zstd::encode_all(zeros.as_slice(), 0).unwrap();
+    for payload in [gzip, zstd] {
+        let (data, format) = detect_and_decode(&payload);
+        assert_eq!(format.uncompressed_size, None);
+        assert!(data.to_string().contains(&too_large()));
+    }
+}
@@ -4,14 +4,17 @@ use std::{fmt::Display, io::Read};
 
 use serde::{Deserialize, Serialize};
 
+/// Id of a schema in the schema registry.
 #[derive(Clone, Debug, Deserialize, Serialize, Hash, PartialEq, Eq, Default)]
 #[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct SchemaId(pub u32);
 
+/// Types of schemas supported by the schema registry.
 #[derive(Clone, Debug, Deserialize, Serialize, Hash, PartialEq, Eq)]
 #[serde(rename_all = "UPPERCASE")]
 #[cfg_attr(test, derive(schemars::JsonSchema))]
 #[derive(Default)]
+#[non_exhaustive]
 pub enum SchemaType {
     #[default]
     Json,
@@ -19,8 +22,10 @@ pub enum SchemaType {
     Protobuf,
 }
 
+/// The schema a key or a value has been serialized with.
 #[derive(Clone, Debug, Deserialize, Serialize, Hash, PartialEq, Eq)]
 #[cfg_attr(test, derive(schemars::JsonSchema))]
+#[non_exhaustive]
 pub struct Schema {
     pub id: SchemaId,
     #[serde(skip_serializing_if = "Option::is_none")]
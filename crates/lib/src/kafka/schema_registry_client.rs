@@ -1,42 +1,138 @@
-use reqwest::header::{self, HeaderMap, HeaderName, HeaderValue};
+use itertools::Itertools;
+use rdkafka::message::{Headers, OwnedHeaders};
+use reqwest::{
+    Certificate, Identity, RequestBuilder,
+    header::{self, HeaderMap, HeaderName, HeaderValue},
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::{collections::HashMap, str::FromStr, time::Duration};
+use std::{
+    collections::{HashMap, VecDeque},
+    str::FromStr,
+    time::Duration,
+};
 use url::Url;
 
 use crate::Error;
 
-use super::schema::SchemaType;
+use super::schema::{SchemaId, SchemaType};
+
+const MAGIC_BYTE: u8 = 0;
+
+/// How the client authenticates to the schema registry.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SchemaRegistryAuth {
+    #[default]
+    None,
+    /// HTTP basic authentication
+    Basic {
+        username: String,
+        password: Option<String>,
+    },
+    /// Static token sent in the `Authorization: Bearer <token>` header
+    Bearer(String),
+}
+
+/// The implementation of the schema registry, they expose different REST APIs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SchemaRegistryKind {
+    #[default]
+    Confluent,
+    /// Apicurio registry, through its REST API v2
+    Apicurio(ApicurioOptions),
+}
+
+/// How the Apicurio serializers reference the schema of a record.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ApicurioOptions {
+    /// Records reference the content id of the schema instead of its global id
+    pub use_content_id: bool,
+    /// The id is written on 4 bytes after the magic byte instead of 8, like the `Legacy4ByteIdHandler`
+    pub legacy_id: bool,
+}
+
+/// Options of the HTTP client of the schema registry.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct SchemaRegistryOptions {
+    pub kind: SchemaRegistryKind,
+    /// HTTP headers sent with every request
+    pub headers: HashMap<String, String>,
+    pub auth: SchemaRegistryAuth,
+    /// PEM encoded certificate authority trusted in addition to the system ones
+    pub ca_certificate: Option<Vec<u8>>,
+    /// PEM encoded client certificate and PKCS #8 private key, for registries requiring mutual TLS
+    pub client_certificate: Option<(Vec<u8>, Vec<u8>)>,
+}
 
 #[derive(Clone, Debug)]
 /// A HTTP client to communicate with a confluent schema registry
 struct SimpleSchemaRegistryClient {
     url: Url,
     client: reqwest::Client,
+    auth: SchemaRegistryAuth,
+    kind: SchemaRegistryKind,
 }
 
 impl SimpleSchemaRegistryClient {
-    fn new(url: Url, headers: &HashMap<String, String>) -> Self {
+    fn new(url: Url, options: &SchemaRegistryOptions) -> Result<Self, Error> {
         let mut default_headers = HeaderMap::new();
-        // https://docs.confluent.io/platform/current/schema-registry/develop/api.html#content-types
-        default_headers.insert(
-            header::ACCEPT,
-            HeaderValue::from_static("application/vnd.schemaregistry.v1+json"),
-        );
-        for (key, value) in headers {
+        if options.kind == SchemaRegistryKind::Confluent {
+            // https://docs.confluent.io/platform/current/schema-registry/develop/api.html#content-types
+            default_headers.insert(
+                header::ACCEPT,
+                HeaderValue::from_static("application/vnd.schemaregistry.v1+json"),
+            );
+        }
+        for (key, value) in &options.headers {
             default_headers.insert(
-                HeaderName::from_str(key).unwrap(),
-                HeaderValue::from_str(value).unwrap(),
+                HeaderName::from_str(key).map_err(|e| {
+                    Error::SchemaRegistry(format!("Invalid HTTP header '{key}': {e}"))
+                })?,
+                HeaderValue::from_str(value).map_err(|e| {
+                    Error::SchemaRegistry(format!("Invalid value of the HTTP header '{key}': {e}"))
+                })?,
             );
         }
-        let builder = reqwest::Client::builder()
+        let mut builder = reqwest::Client::builder()
             .timeout(Duration::from_secs(5))
             .connect_timeout(Duration::from_secs(5))
             .read_timeout(Duration::from_secs(5))
             .default_headers(default_headers);
-        Self {
+        if let Some(ca) = &options.ca_certificate {
+            builder = builder.add_root_certificate(
+                Certificate::from_pem(ca)
+                    .map_err(|e| Error::SchemaRegistry(format!("Invalid CA certificate: {e}")))?,
+            );
+        }
+        if let Some((certificate, key)) = &options.client_certificate {
+            builder =
+                builder.identity(Identity::from_pkcs8_pem(certificate, key).map_err(|e| {
+                    Error::SchemaRegistry(format!("Invalid client certificate or key: {e}"))
+                })?);
+        }
+        Ok(Self {
             url,
-            client: builder.build().unwrap(),
+            client: builder
+                .build()
+                .map_err(|e| Error::SchemaRegistry(e.to_string()))?,
+            auth: options.auth.clone(),
+            kind: options.kind,
+        })
+    }
+
+    /// Creates a GET request with the credentials of the client.
+    fn request(&self, url: Url) -> RequestBuilder {
+        let request = self.client.get(url);
+        match &self.auth {
+            SchemaRegistryAuth::None => request,
+            SchemaRegistryAuth::Basic { username, password } => {
+                request.basic_auth(username, password.as_ref())
+            }
+            SchemaRegistryAuth::Bearer(token) => request.bearer_auth(token),
         }
     }
 
@@ -70,9 +166,12 @@ impl SimpleSchemaRegistryClient {
     }
 
     async fn schema(&self, id: u32) -> Result<Option<SchemaResponse>, Error> {
+        if let SchemaRegistryKind::Apicurio(_) = self.kind {
+            return self.apicurio_schema(id).await;
+        }
         // TODO https://github.com/servo/rust-url/issues/333
         let url = self.schema_url(id);
-        let response = self.client.get(url).send().await;
+        let response = self.request(url).send().await;
 
         match response {
             Ok(response) => {
@@ -88,13 +187,271 @@ impl SimpleSchemaRegistryClient {
         }
     }
 
-    fn schema_url(&self, id: u32) -> String {
+    /// URL of an endpoint, relative to the base URL of the registry
+    fn endpoint(&self, segments: &[&str]) -> Url {
         // TODO https://github.com/servo/rust-url/issues/333
         let mut url = self.url.clone();
-        if let Ok(mut segments) = url.path_segments_mut() {
-            segments.extend(vec!["schemas", "ids", &id.to_string()]);
+        if let Ok(mut s) = url.path_segments_mut() {
+            s.extend(segments);
+        }
+        url
+    }
+
+    async fn get<T>(&self, segments: &[&str]) -> Result<Option<T>, Error>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        self.get_url(self.endpoint(segments)).await
+    }
+
+    async fn get_url<T>(&self, url: Url) -> Result<Option<T>, Error>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let response = self
+            .request(url)
+            .send()
+            .await
+            .map_err(|e| Error::SchemaRegistry(e.to_string()))?;
+        if !response.status().is_success() {
+            return Ok(None);
+        }
+        response
+            .json::<T>()
+            .await
+            .map(Some)
+            .map_err(|e| Error::SchemaRegistry(e.to_string()))
+    }
+
+    async fn subjects_of(&self, id: u32) -> Result<Vec<SubjectVersion>, Error> {
+        if let SchemaRegistryKind::Apicurio(options) = self.kind {
+            return self.apicurio_subjects_of(id, options).await;
+        }
+        let mut url = self.endpoint(&["schemas", "ids", &id.to_string(), "versions"]);
+        url.set_query(Some("deleted=true"));
+        Ok(self.get_url(url).await?.unwrap_or_default())
+    }
+
+    async fn versions(&self, subject: &str) -> Result<Vec<u32>, Error> {
+        if let SchemaRegistryKind::Apicurio(_) = self.kind {
+            return Ok(self
+                .apicurio_versions(subject)
+                .await?
+                .into_iter()
+                .filter_map(|v| v.version.parse().ok())
+                .collect());
+        }
+        Ok(self
+            .get(&["subjects", subject, "versions"])
+            .await?
+            .unwrap_or_default())
+    }
+
+    /// Versions of a subject that have been soft-deleted, they can still be fetched but are not listed anymore.
+    /// Apicurio does not soft-delete versions.
+    async fn deleted_versions(&self, subject: &str) -> Result<Vec<u32>, Error> {
+        if let SchemaRegistryKind::Apicurio(_) = self.kind {
+            return Ok(vec![]);
         }
-        url.to_string()
+        let mut url = self.endpoint(&["subjects", subject, "versions"]);
+        url.set_query(Some("deleted=true"));
+        let all: Vec<u32> = self.get_url(url).await?.unwrap_or_default();
+        let versions = self.versions(subject).await?;
+        Ok(all.into_iter().filter(|v| !versions.contains(v)).collect())
+    }
+
+    async fn schema_version(
+        &self,
+        subject: &str,
+        version: u32,
+    ) -> Result<Option<SchemaVersionResponse>, Error> {
+        if let SchemaRegistryKind::Apicurio(options) = self.kind {
+            return self
+                .apicurio_schema_version(subject, version, options)
+                .await;
+        }
+        let mut url = self.endpoint(&["subjects", subject, "versions", &version.to_string()]);
+        url.set_query(Some("deleted=true"));
+        let response: Option<SchemaVersionResponse> = self.get_url(url).await?;
+        Ok(response.map(|mut r| {
+            r.schema.schema_type = Self::compute_schema_type(&r.schema);
+            r
+        }))
+    }
+
+    fn schema_url(&self, id: u32) -> Url {
+        match self.kind {
+            SchemaRegistryKind::Confluent => self.endpoint(&["schemas", "ids", &id.to_string()]),
+            SchemaRegistryKind::Apicurio(options) => self.endpoint(&[
+                "ids",
+                match options.use_content_id {
+                    true => "contentIds",
+                    false => "globalIds",
+                },
+                &id.to_string(),
+            ]),
+        }
+    }
+
+    /// Apicurio returns the content of the schema as is, its type is given by the `X-Registry-ArtifactType` header.
+    /// <https://www.apicur.io/registry/docs/apicurio-registry/2.6.x/assets-attachments/registry-rest-api.htm>
+    async fn apicurio_schema(&self, id: u32) -> Result<Option<SchemaResponse>, Error> {
+        let response = self
+            .request(self.schema_url(id))
+            .send()
+            .await
+            .map_err(|e| Error::SchemaRegistry(e.to_string()))?;
+        if !response.status().is_success() {
+            return Ok(None);
+        }
+        let schema_type = response
+            .headers()
+            .get("X-Registry-ArtifactType")
+            .and_then(|t| t.to_str().ok())
+            .and_then(|t| serde_json::from_value(Value::String(t.to_string())).ok());
+        let mut schema = SchemaResponse {
+            schema: response
+                .text()
+                .await
+                .map_err(|e| Error::SchemaRegistry(e.to_string()))?,
+            schema_type,
+            references: vec![],
+            resolved_references: vec![],
+        };
+        if schema.schema_type.is_none() {
+            schema.schema_type = Self::compute_schema_type(&schema);
+        }
+        Ok(Some(schema))
+    }
+
+    async fn apicurio_versions(&self, subject: &str) -> Result<Vec<ApicurioVersion>, Error> {
+        let (group, artifact) = apicurio_artifact(subject);
+        let mut url = self.endpoint(&["groups", group, "artifacts", artifact, "versions"]);
+        url.set_query(Some("limit=1000"));
+        Ok(self
+            .get_url::<ApicurioVersions>(url)
+            .await?
+            .map(|v| v.versions)
+            .unwrap_or_default())
+    }
+
+    async fn apicurio_subjects_of(
+        &self,
+        id: u32,
+        options: ApicurioOptions,
+    ) -> Result<Vec<SubjectVersion>, Error> {
+        let mut url = self.endpoint(&["search", "artifacts"]);
+        url.query_pairs_mut()
+            .append_pair(
+                match options.use_content_id {
+                    true => "contentId",
+                    false => "globalId",
+                },
+                &id.to_string(),
+            )
+            .append_pair("limit", "100");
+        let artifacts = self
+            .get_url::<ApicurioArtifacts>(url)
+            .await?
+            .map(|a| a.artifacts)
+            .unwrap_or_default();
+        let mut subjects = vec![];
+        for artifact in artifacts {
+            let subject = match artifact.group_id.as_deref() {
+                None | Some(APICURIO_DEFAULT_GROUP) => artifact.id,
+                Some(group) => format!("{group}/{}", artifact.id),
+            };
+            for version in self.apicurio_versions(&subject).await? {
+                if version.id(options) == Some(id)
+                    && let Ok(v) = version.version.parse()
+                {
+                    subjects.push(SubjectVersion {
+                        subject: subject.clone(),
+                        version: v,
+                    });
+                }
+            }
+        }
+        Ok(subjects)
+    }
+
+    async fn apicurio_schema_version(
+        &self,
+        subject: &str,
+        version: u32,
+        options: ApicurioOptions,
+    ) -> Result<Option<SchemaVersionResponse>, Error> {
+        let (group, artifact) = apicurio_artifact(subject);
+        let meta: Option<ApicurioVersion> = self
+            .get(&[
+                "groups",
+                group,
+                "artifacts",
+                artifact,
+                "versions",
+                &version.to_string(),
+                "meta",
+            ])
+            .await?;
+        let Some(id) = meta.and_then(|m| m.id(options)) else {
+            return Ok(None);
+        };
+        Ok(self
+            .apicurio_schema(id)
+            .await?
+            .map(|schema| SchemaVersionResponse {
+                subject: subject.to_string(),
+                version,
+                id,
+                schema,
+            }))
+    }
+}
+
+const APICURIO_DEFAULT_GROUP: &str = "default";
+
+/// Subjects of Apicurio are artifact ids, prefixed with their group when it is not the default one: `<group>/<artifact-id>`.
+fn apicurio_artifact(subject: &str) -> (&str, &str) {
+    subject
+        .split_once('/')
+        .unwrap_or((APICURIO_DEFAULT_GROUP, subject))
+}
+
+#[derive(Deserialize)]
+struct ApicurioArtifacts {
+    #[serde(default)]
+    artifacts: Vec<ApicurioArtifact>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApicurioArtifact {
+    id: String,
+    group_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ApicurioVersions {
+    #[serde(default)]
+    versions: Vec<ApicurioVersion>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApicurioVersion {
+    version: String,
+    global_id: u64,
+    content_id: u64,
+}
+
+impl ApicurioVersion {
+    /// The id referenced by the records, `None` when it does not fit on 4 bytes.
+    fn id(&self, options: ApicurioOptions) -> Option<u32> {
+        u32::try_from(match options.use_content_id {
+            true => self.content_id,
+            false => self.global_id,
+        })
+        .ok()
     }
 }
 
@@ -104,21 +461,104 @@ impl SimpleSchemaRegistryClient {
 pub struct SchemaRegistryClient {
     client: SimpleSchemaRegistryClient,
     cache: HashMap<u32, SchemaResponse>,
+    /// Registries of the topics starting with a given prefix, see [`SchemaRegistryClient::for_topic`]
+    routes: Vec<(String, SchemaRegistryClient)>,
 }
 
 impl SchemaRegistryClient {
-    pub fn new(base_url: Url, headers: &HashMap<String, String>) -> Self {
-        Self {
-            client: SimpleSchemaRegistryClient::new(base_url, headers),
+    pub fn new(base_url: Url, options: &SchemaRegistryOptions) -> Result<Self, Error> {
+        Ok(Self {
+            client: SimpleSchemaRegistryClient::new(base_url, options)?,
             cache: HashMap::default(),
+            routes: vec![],
+        })
+    }
+
+    /// Uses another schema registry for the topics starting with `prefix`.
+    pub fn with_route(mut self, prefix: &str, client: SchemaRegistryClient) -> Self {
+        self.routes.push((prefix.to_string(), client));
+        self
+    }
+
+    /// Returns the schema registry of the topic: the route with the longest prefix matching the topic, this registry otherwise.
+    /// Schema ids are only unique within a registry, records must be decoded with the registry of their topic.
+    pub fn for_topic(&mut self, topic: &str) -> &mut SchemaRegistryClient {
+        let route = self
+            .routes
+            .iter()
+            .enumerate()
+            .filter(|(_, (prefix, _))| topic.starts_with(prefix.as_str()))
+            .max_by_key(|(_, (prefix, _))| prefix.len())
+            .map(|(i, _)| i);
+        match route {
+            Some(i) => &mut self.routes[i].1,
+            None => self,
+        }
+    }
+
+    /// Base URL of the schema registry
+    pub fn url(&self) -> &Url {
+        &self.client.url
+    }
+
+    pub fn kind(&self) -> SchemaRegistryKind {
+        self.client.kind
+    }
+
+    /// Returns the schema id of a key or a value and the number of bytes preceding the data in the payload.
+    /// Confluent serializers write a magic byte and the id on 4 bytes,
+    /// Apicurio serializers write the id in the `apicurio.<key|value>.<globalId|contentId>` header or after the magic byte, on 8 bytes by default.
+    /// Ids that don't fit on 4 bytes are not supported.
+    pub fn schema_id(
+        &self,
+        payload: Option<&[u8]>,
+        headers: Option<&OwnedHeaders>,
+        key: bool,
+    ) -> Option<(SchemaId, usize)> {
+        let SchemaRegistryKind::Apicurio(options) = self.client.kind else {
+            return SchemaId::parse(payload).map(|id| (id, 5));
+        };
+        let header = format!(
+            "apicurio.{}.{}",
+            match key {
+                true => "key",
+                false => "value",
+            },
+            match options.use_content_id {
+                true => "contentId",
+                false => "globalId",
+            }
+        );
+        let from_header = headers
+            .and_then(|h| h.iter().find(|h| h.key == header))
+            .and_then(|h| h.value)
+            .and_then(|v| <[u8; 8]>::try_from(v).ok())
+            .and_then(|v| u32::try_from(u64::from_be_bytes(v)).ok());
+        if let Some(id) = from_header {
+            return Some((SchemaId(id), 0));
+        }
+        let payload = payload.unwrap_or_default();
+        let length = match options.legacy_id {
+            true => 4,
+            false => 8,
+        };
+        if payload.len() <= length || payload[0] != MAGIC_BYTE {
+            return None;
         }
+        let id = payload[1..=length]
+            .iter()
+            .fold(0u64, |id, byte| (id << 8) | u64::from(*byte));
+        u32::try_from(id).ok().map(|id| (SchemaId(id), length + 1))
     }
 
     pub async fn schema(&mut self, id: u32) -> Result<Option<SchemaResponse>, Error> {
         match self.cache.get(&id) {
             Some(schema) => Ok(Some(schema.clone())),
             None => {
-                let schema = self.client.schema(id).await?;
+                let mut schema = self.client.schema(id).await?;
+                if let Some(schema) = schema.as_mut() {
+                    self.resolve_references(schema).await?;
+                }
                 if let Some(schema) = schema.clone() {
                     self.cache.insert(id, schema.clone());
                 }
@@ -128,26 +568,336 @@ impl SchemaRegistryClient {
     }
 
     pub fn schema_url(&self, id: u32) -> String {
-        self.client.schema_url(id)
+        self.client.schema_url(id).to_string()
+    }
+
+    /// Returns the subjects and versions associated to a schema id, soft-deleted versions included.
+    pub async fn subjects_of(&self, id: u32) -> Result<Vec<SubjectVersion>, Error> {
+        self.client.subjects_of(id).await
+    }
+
+    /// Returns the version numbers of a subject.
+    /// Only the numbers are fetched, contents are fetched with [`SchemaRegistryClient::schema_version`].
+    pub async fn versions(&self, subject: &str) -> Result<Vec<u32>, Error> {
+        self.client.versions(subject).await
+    }
+
+    /// Returns the version numbers of a subject that have been soft-deleted.
+    pub async fn deleted_versions(&self, subject: &str) -> Result<Vec<u32>, Error> {
+        self.client.deleted_versions(subject).await
+    }
+
+    /// Returns a specific version of a subject, even when it has been soft-deleted.
+    pub async fn schema_version(
+        &mut self,
+        subject: &str,
+        version: u32,
+    ) -> Result<Option<SchemaVersionResponse>, Error> {
+        let mut response = self.client.schema_version(subject, version).await?;
+        if let Some(r) = response.as_mut() {
+            self.resolve_references(&mut r.schema).await?;
+            self.cache.insert(r.id, r.schema.clone());
+        }
+        Ok(response)
+    }
+
+    /// Fetches the schemas referenced by the schema, then the schemas they reference in turn.
+    /// Each subject version is fetched once, even when several schemas reference it.
+    async fn resolve_references(&self, schema: &mut SchemaResponse) -> Result<(), Error> {
+        let mut to_resolve: VecDeque<SchemaReference> = schema.references.iter().cloned().collect();
+        let mut resolved: Vec<ResolvedReference> = vec![];
+        while let Some(reference) = to_resolve.pop_front() {
+            if resolved.iter().any(|r| {
+                r.reference.subject == reference.subject && r.reference.version == reference.version
+            }) {
+                continue;
+            }
+            let response = self
+                .client
+                .schema_version(&reference.subject, reference.version)
+                .await?
+                .ok_or(Error::SchemaRegistry(format!(
+                    "The schema '{}' references version {} of subject '{}', this version does not exist",
+                    reference.name, reference.version, reference.subject
+                )))?;
+            to_resolve.extend(response.schema.references);
+            resolved.push(ResolvedReference {
+                reference,
+                schema: response.schema.schema,
+            });
+        }
+        schema.resolved_references = resolved;
+        Ok(())
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, Hash, PartialEq, Eq)]
+/// A subject and the version of a schema registered under this subject
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SubjectVersion {
+    pub subject: String,
+    pub version: u32,
+}
+
+/// A version of a subject
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SchemaVersionResponse {
+    pub subject: String,
+    pub version: u32,
+    pub id: u32,
+    #[serde(flatten)]
+    pub schema: SchemaResponse,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Hash, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
+#[non_exhaustive]
 pub struct SchemaResponse {
     pub schema: String,
     pub schema_type: Option<SchemaType>,
+    /// Other schemas this schema depends on, such as an avro record type or an imported protobuf file
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<SchemaReference>,
+    /// Contents of the referenced schemas and of the schemas they reference in turn, fetched by [`SchemaRegistryClient`]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub resolved_references: Vec<ResolvedReference>,
+}
+
+/// A reference of a schema to a version of another subject
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SchemaReference {
+    /// Name the schema refers to: the full name of an avro type, the path of an imported protobuf file or the URL of a JSON schema
+    pub name: String,
+    pub subject: String,
+    pub version: u32,
+}
+
+/// A referenced schema and its definition
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Hash, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ResolvedReference {
+    #[serde(flatten)]
+    pub reference: SchemaReference,
+    /// Definition of the referenced schema
+    pub schema: String,
 }
 
 impl SchemaResponse {
+    /// Serializes a JSON value with the schema, in the wire format of the schema registry:
+    /// a magic byte, the schema id on 4 bytes and the payload.
+    /// Protobuf schemas are not supported.
+    pub fn encode(&self, id: u32, value: &Value) -> Result<Vec<u8>, Error> {
+        let mut payload = vec![0];
+        payload.extend(id.to_be_bytes());
+        match self.schema_type {
+            None | Some(SchemaType::Avro) => {
+                let (schema, references) = self
+                    .avro_schema()
+                    .map_err(|e| Error::SchemaRegistry(format!("Invalid avro schema {id}: {e}")))?;
+                let schemata = references.iter().chain([&schema]).collect_vec();
+                let value = apache_avro::types::Value::from(value.clone())
+                    .resolve_schemata(&schema, schemata.clone())
+                    .map_err(|e| {
+                        Error::Error(format!(
+                            "The value does not match the avro schema {id}: {e}"
+                        ))
+                    })?;
+                let datum =
+                    apache_avro::to_avro_datum_schemata(&schema, schemata, value).map_err(|e| {
+                        Error::Error(format!(
+                            "Cannot serialize the value with the avro schema {id}: {e}"
+                        ))
+                    })?;
+                payload.extend(datum);
+            }
+            Some(SchemaType::Json) => payload.extend(serde_json::to_vec(value)?),
+            Some(SchemaType::Protobuf) => {
+                return Err(Error::Error(format!(
+                    "Schema {id} is a protobuf schema, serializing values with protobuf schemas is not supported"
+                )));
+            }
+        }
+        Ok(payload)
+    }
+
+    /// Parses the avro schema and the schemas it references, dependencies first.
+    /// Avro resolves the names of a schema in order, a schema must come after the schemas it references.
+    pub fn avro_schema(
+        &self,
+    ) -> Result<(apache_avro::Schema, Vec<apache_avro::Schema>), apache_avro::Error> {
+        match self.resolved_references.is_empty() {
+            true => apache_avro::Schema::parse_str(&self.schema).map(|s| (s, vec![])),
+            false => apache_avro::Schema::parse_str_with_list(
+                &self.schema,
+                self.resolved_references
+                    .iter()
+                    .rev()
+                    .map(|r| r.schema.as_str()),
+            ),
+        }
+    }
+
     pub fn schema_to_string_pretty(&self) -> String {
+        self.to_string_pretty(&self.schema)
+    }
+
+    /// Pretty prints a referenced schema, it has the type of this schema.
+    pub fn reference_to_string_pretty(&self, reference: &ResolvedReference) -> String {
+        self.to_string_pretty(&reference.schema)
+    }
+
+    fn to_string_pretty(&self, schema: &str) -> String {
         match self.schema_type {
             Some(SchemaType::Avro | SchemaType::Json) => {
-                let json = serde_json::from_str::<Value>(&self.schema)
-                    .unwrap_or(Value::String(String::new()));
+                let json =
+                    serde_json::from_str::<Value>(schema).unwrap_or(Value::String(String::new()));
                 serde_json::to_string_pretty(&json).unwrap()
             }
-            _ => self.schema.clone(),
+            _ => schema.to_string(),
         }
     }
 }
+
+#[test]
+fn test_encode_with_avro_schema() {
+    use serde_json::json;
+
+    let schema = SchemaResponse {
+        schema: r#"{"type": "record", "name": "Payment", "fields": [{"name": "id", "type": "string"}, {"name": "amount", "type": "double"}]}"#.to_string(),
+        schema_type: None,
+        references: vec![],
+        resolved_references: vec![],
+    };
+    let payload = schema
+        .encode(42, &json!({"id": "p-1", "amount": 12.5}))
+        .unwrap();
+    assert_eq!(payload[..5], [0, 0, 0, 0, 42]);
+    let value = apache_avro::from_avro_datum(
+        &apache_avro::Schema::parse_str(&schema.schema).unwrap(),
+        &mut &payload[5..],
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        super::avro::avro_to_json(value),
+        json!({"id": "p-1", "amount": 12.5})
+    );
+    assert!(schema.encode(42, &json!({"id": "p-1"})).is_err());
+}
+
+#[test]
+fn test_avro_schema_with_references() {
+    use serde_json::json;
+
+    let schema = SchemaResponse {
+        schema: r#"{"type": "record", "name": "Payment", "namespace": "com.acme", "fields": [{"name": "id", "type": "string"}, {"name": "amount", "type": "com.acme.Money"}]}"#.to_string(),
+        schema_type: Some(SchemaType::Avro),
+        references: vec![SchemaReference {
+            name: "com.acme.Money".to_string(),
+            subject: "money".to_string(),
+            version: 1,
+        }],
+        resolved_references: vec![ResolvedReference {
+            reference: SchemaReference {
+                name: "com.acme.Money".to_string(),
+                subject: "money".to_string(),
+                version: 1,
+            },
+            schema: r#"{"type": "record", "name": "Money", "namespace": "com.acme", "fields": [{"name": "value", "type": "double"}, {"name": "currency", "type": "string"}]}"#.to_string(),
+        }],
+    };
+    let value = json!({"id": "p-1", "amount": {"value": 12.5, "currency": "EUR"}});
+    let payload = schema.encode(42, &value).unwrap();
+    let (avro_schema, references) = schema.avro_schema().unwrap();
+    let decoded = apache_avro::from_avro_datum_schemata(
+        &avro_schema,
+        references.iter().chain([&avro_schema]).collect(),
+        &mut &payload[5..],
+        None,
+    )
+    .unwrap();
+    assert_eq!(super::avro::avro_to_json(decoded), value);
+
+    let without_references = SchemaResponse {
+        resolved_references: vec![],
+        ..schema
+    };
+    assert!(without_references.avro_schema().is_err());
+}
+
+#[test]
+fn test_apicurio_schema_id() {
+    use rdkafka::message::Header;
+
+    let client = |legacy_id| {
+        SchemaRegistryClient::new(
+            Url::parse("http://apicurio:8080/apis/registry/v2").unwrap(),
+            &SchemaRegistryOptions {
+                kind: SchemaRegistryKind::Apicurio(ApicurioOptions {
+                    use_content_id: false,
+                    legacy_id,
+                }),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    };
+    let payload = [0, 0, 0, 0, 0, 0, 0, 0, 42, 1, 2];
+    assert_eq!(
+        client(false).schema_id(Some(&payload), None, false),
+        Some((SchemaId(42), 9))
+    );
+    assert_eq!(
+        client(true).schema_id(Some(&[0, 0, 0, 0, 42, 1]), None, false),
+        Some((SchemaId(42), 5))
+    );
+    assert_eq!(client(false).schema_id(Some(b"{}"), None, false), None);
+
+    let id = 7u64.to_be_bytes();
+    let headers = OwnedHeaders::new().insert(Header {
+        key: "apicurio.value.globalId",
+        value: Some(&id),
+    });
+    assert_eq!(
+        client(false).schema_id(Some(b"{}"), Some(&headers), false),
+        Some((SchemaId(7), 0))
+    );
+    assert_eq!(
+        client(false).schema_id(Some(b"{}"), Some(&headers), true),
+        None
+    );
+    assert_eq!(
+        client(false).schema_url(7),
+        "http://apicurio:8080/apis/registry/v2/ids/globalIds/7"
+    );
+    assert_eq!(
+        apicurio_artifact("payments-value"),
+        ("default", "payments-value")
+    );
+    assert_eq!(apicurio_artifact("acme/payments"), ("acme", "payments"));
+}
+
+#[test]
+fn test_for_topic() {
+    let client = |url: &str| {
+        SchemaRegistryClient::new(Url::parse(url).unwrap(), &SchemaRegistryOptions::default())
+            .unwrap()
+    };
+    let mut registry = client("http://default:8081")
+        .with_route("legacy.", client("http://legacy:8081"))
+        .with_route("legacy.payments", client("http://payments:8081"));
+    assert_eq!(
+        registry.for_topic("orders").url().as_str(),
+        "http://default:8081/"
+    );
+    assert_eq!(
+        registry.for_topic("legacy.orders").url().as_str(),
+        "http://legacy:8081/"
+    );
+    assert_eq!(
+        registry.for_topic("legacy.payments-dlq").url().as_str(),
+        "http://payments:8081/"
+    );
+}
@@ -0,0 +1,138 @@
+//! Decompression of payloads compressed with the snappy framing format.
+//! More details at <https://github.com/google/snappy/blob/main/framing_format.txt>.
+//! The checksums of the chunks are not verified.
+
+use super::payload_format::{MAX_DECOMPRESSED_LEN, too_large};
+
+const STREAM_IDENTIFIER: &[u8] = b"sNaPpY";
+
+/// The framing format never stores more than 65536 uncompressed bytes in a chunk
+const MAX_CHUNK_LENGTH: usize = 65_536;
+
+/// Decompresses a snappy stream: a stream identifier followed by compressed or uncompressed chunks.
+pub(crate) fn decompress_frames(mut payload: &[u8]) -> Result<Vec<u8>, String> {
+    let mut decompressed = vec![];
+    while !payload.is_empty() {
+        let header = take(&mut payload, 4)?;
+        let length =
+            usize::from(header[1]) | usize::from(header[2]) << 8 | usize::from(header[3]) << 16;
+        let chunk = take(&mut payload, length)?;
+        match header[0] {
+            0xff if chunk == STREAM_IDENTIFIER => (),
+            0xff => return Err("Invalid stream identifier".to_string()),
+            0x00 => {
+                decompressed.extend(decompress_block(chunk.get(4..).ok_or("Truncated chunk")?)?)
+            }
+            0x01 => decompressed.extend_from_slice(chunk.get(4..).ok_or("Truncated chunk")?),
+            0x80..=0xfe => (),
+            t => return Err(format!("Unsupported chunk type {t:#04x}")),
+        }
+        if decompressed.len() > MAX_DECOMPRESSED_LEN {
+            return Err(too_large());
+        }
+    }
+    Ok(decompressed)
+}
+
+/// Decompresses a block of the raw snappy format: the uncompressed length followed by literals and copies.
+fn decompress_block(mut block: &[u8]) -> Result<Vec<u8>, String> {
+    let length = usize::try_from(read_varint(&mut block)?).map_err(|e| e.to_string())?;
+    // The length comes from the payload, it is checked before allocating anything
+    if length > MAX_CHUNK_LENGTH {
+        return Err(format!(
+            "Chunk of {length} bytes once decompressed, at most {MAX_CHUNK_LENGTH} bytes are expected"
+        ));
+    }
+    let mut output: Vec<u8> = Vec::with_capacity(length);
+    while !block.is_empty() {
+        if output.len() > length {
+            break;
+        }
+        let tag = take(&mut block, 1)?[0];
+        let (copy_length, offset) = match tag & 0x03 {
+            0 => {
+                let mut literal_length = usize::from(tag >> 2);
+                if literal_length >= 60 {
+                    let bytes = take(&mut block, literal_length - 59)?;
+                    literal_length = bytes
+                        .iter()
+                        .rev()
+                        .fold(0, |acc, b| (acc << 8) | usize::from(*b));
+                }
+                output.extend_from_slice(take(&mut block, literal_length + 1)?);
+                continue;
+            }
+            1 => (
+                4 + usize::from((tag >> 2) & 0x07),
+                usize::from(tag >> 5) << 8 | usize::from(take(&mut block, 1)?[0]),
+            ),
+            2 => {
+                let bytes = take(&mut block, 2)?;
+                (
+                    1 + usize::from(tag >> 2),
+                    usize::from(u16::from_le_bytes([bytes[0], bytes[1]])),
+                )
+            }
+            _ => {
+                let bytes = take(&mut block, 4)?;
+                (
+                    1 + usize::from(tag >> 2),
+                    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize,
+                )
+            }
+        };
+        if offset == 0 || offset > output.len() {
+            return Err(format!("Invalid copy offset {offset}"));
+        }
+        // The copy can overlap the bytes it produces
+        let start = output.len() - offset;
+        for i in 0..copy_length {
+            output.push(output[start + i]);
+        }
+    }
+    match output.len() == length {
+        true => Ok(output),
+        false => Err(format!(
+            "Expected {length} bytes once decompressed, got {}",
+            output.len()
+        )),
+    }
+}
+
+fn read_varint(block: &mut &[u8]) -> Result<u64, String> {
+    let mut value = 0u64;
+    for i in 0..5 {
+        let byte = take(block, 1)?[0];
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err("Invalid uncompressed length".to_string())
+}
+
+fn take<'a>(payload: &mut &'a [u8], length: usize) -> Result<&'a [u8], String> {
+    if payload.len() < length {
+        return Err("Unexpected end of the payload".to_string());
+    }
+    let (bytes, rest) = payload.split_at(length);
+    *payload = rest;
+    Ok(bytes)
+}
+
+#[test]
+fn test_decompress_frames() {
+    // "hello hello hello!": the literal "hello ", a copy of 11 bytes at offset 6 and the literal "!"
+    let payload =
+        b"\xff\x06\x00\x00sNaPpY\x00\x10\x00\x00\x00\x00\x00\x00\x12\x14hello \x1d\x06\x00!";
+    assert_eq!(
+        decompress_frames(payload),
+        Ok(b"hello hello hello!".to_vec())
+    );
+    let uncompressed = b"\xff\x06\x00\x00sNaPpY\x01\x06\x00\x00\x00\x00\x00\x00hi";
+    assert_eq!(decompress_frames(uncompressed), Ok(b"hi".to_vec()));
+    assert!(decompress_frames(b"\xff\x06\x00\x00sNaPpY\x00\x05\x00\x00\x00").is_err());
+    // An uncompressed length of 4 GiB is rejected before allocating
+    let huge = b"\xff\x06\x00\x00sNaPpY\x00\x09\x00\x00\x00\x00\x00\x00\xff\xff\xff\xff\x0f";
+    assert!(decompress_frames(huge).is_err());
+}
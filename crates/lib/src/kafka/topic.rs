@@ -3,25 +3,153 @@
 //!  - Number of partitions
 //!  - Number of replicas
 
+use std::io::{Cursor, Read};
+
+use byteorder::{BigEndian, ReadBytesExt};
 use serde::{Deserialize, Serialize};
 use strum::{Display, EnumIter, EnumString};
 
+use super::KafkaRecord;
+
 /// Information regarding a given topic, their consumers, the number of partitions...
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Default, Ord)]
+#[non_exhaustive]
 pub struct TopicDetail {
     pub name: String,
     pub partitions: usize,
     pub replicas: usize,
     pub consumer_groups: Vec<ConsumerGroupDetail>,
     pub count: i64,
+    /// How `count` was computed
+    pub count_method: CountMethod,
+}
+
+/// Methods counting the records of a topic.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Display,
+    Deserialize,
+    Serialize,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Default,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum CountMethod {
+    /// The difference between the end offset and the start offset of each partition.
+    /// It is exact for topics with the `delete` cleanup policy, but it overestimates compacted topics
+    /// since the offsets of the removed records are not reused.
+    #[default]
+    Offsets,
+    /// Records are read from several offsets of each partition to estimate the ratio of offsets still holding a record.
+    Sampling,
+}
+
+impl CountMethod {
+    pub fn toggle(self) -> Self {
+        match self {
+            Self::Offsets => Self::Sampling,
+            Self::Sampling => Self::Offsets,
+        }
+    }
+}
+
+/// A lightweight overview of a topic, displayed before subscribing to it.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub struct TopicPreview {
+    pub name: String,
+    pub partitions: usize,
+    /// Estimated number of records, based on the watermarks of each partition
+    pub count: i64,
+    /// Timestamp of the most recent record
+    pub latest_timestamp: Option<i64>,
+    /// The most recent record of the topic
+    pub sample: Option<KafkaRecord>,
+}
+
+/// The offsets of the partitions of a topic at a given time, to plan the reset of the offsets of a consumer group.
+#[derive(Clone, Debug, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct TopicOffsets {
+    pub topic: String,
+    /// Timestamp in milliseconds the offsets were looked up for
+    pub timestamp: i64,
+    pub partitions: Vec<PartitionOffset>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct PartitionOffset {
+    pub partition: i32,
+    /// Offset of the first record whose timestamp is greater than or equal to the timestamp,
+    /// the end of the partition when there is no such record
+    pub offset: i64,
 }
 
 /// Information regarding a given consumer
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Default, Ord)]
+#[non_exhaustive]
 pub struct ConsumerGroupDetail {
     pub name: String,
     pub members: Vec<ConsumerGroupMember>,
     pub state: ConsumerGroupState,
+    /// Partition assignment strategy chosen during the last rebalance, such as `range` or `cooperative-sticky`
+    pub protocol: String,
+    /// Number of records between the committed offsets of the group and the end of the topic,
+    /// `None` when the group has no committed offset on the topic
+    pub committed_lag: Option<usize>,
+}
+
+/// A consumer group of the cluster, with the topics it consumes
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Default, Ord)]
+#[non_exhaustive]
+pub struct ConsumerGroupOverview {
+    pub name: String,
+    pub state: ConsumerGroupState,
+    /// Rebalance protocol of the group
+    pub group_type: ConsumerGroupType,
+    /// Partition assignment strategy of the group, such as `range` or `cooperative-sticky`
+    pub protocol: String,
+    pub members: Vec<ConsumerGroupMember>,
+    /// Topics the group has committed offsets on or is assigned to, sorted by name
+    pub topics: Vec<String>,
+    /// Number of records between the committed offsets of the group and the end of the partitions,
+    /// `None` when the group has no committed offset
+    pub lag: Option<usize>,
+}
+
+/// Rebalance protocols of consumer groups
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Display,
+    Deserialize,
+    Serialize,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Default,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum ConsumerGroupType {
+    #[default]
+    Unknown,
+    /// Rebalances are coordinated by the group leader, with the `JoinGroup` and `SyncGroup` APIs
+    Classic,
+    /// Rebalances are coordinated by the broker with the `ConsumerGroupHeartbeat` API, see KIP-848
+    Consumer,
 }
 
 /// All the different states of a kafka consumer
@@ -43,6 +171,7 @@ pub struct ConsumerGroupDetail {
 #[strum(serialize_all = "PascalCase")]
 #[serde(rename_all = "PascalCase")]
 #[derive(Default)]
+#[non_exhaustive]
 pub enum ConsumerGroupState {
     #[default]
     Unknown,
@@ -53,6 +182,10 @@ pub enum ConsumerGroupState {
     CompletingRebalance,
     Rebalancing,
     UnknownRebalance,
+    /// The broker is computing the target assignment of a group using the consumer protocol
+    Assigning,
+    /// The members of a group using the consumer protocol are converging to their target assignment
+    Reconciling,
 }
 
 impl ConsumerGroupDetail {
@@ -70,15 +203,46 @@ impl ConsumerGroupDetail {
 
 /// Information regarding a consumer group member.
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Default, Ord)]
+#[non_exhaustive]
 pub struct ConsumerGroupMember {
     pub member: String,
+    pub client_id: String,
+    pub host: String,
+    /// `group.instance.id` of the consumer when it is a static member, it keeps its assignment across restarts
+    pub instance_id: Option<String>,
     pub start_offset: usize,
     pub end_offset: usize,
     pub assignments: Vec<MemberAssignment>,
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Default, Ord)]
+#[non_exhaustive]
 pub struct MemberAssignment {
     pub topic: String,
     pub partitions: Vec<i32>,
 }
+
+impl MemberAssignment {
+    /// Parses the assignment of a member of a group using the `consumer` protocol type.
+    /// More details at <https://kafka.apache.org/protocol#The_Messages_SyncGroup>
+    pub fn parse(assignment: &[u8]) -> std::io::Result<Vec<Self>> {
+        let mut reader = Cursor::new(assignment);
+        let _version = reader.read_i16::<BigEndian>()?;
+        let topics = reader.read_i32::<BigEndian>()?.max(0);
+        let mut assignments = vec![];
+        for _ in 0..topics {
+            let length = reader.read_i16::<BigEndian>()?.max(0);
+            let mut topic = vec![0; length as usize];
+            reader.read_exact(&mut topic)?;
+            let partitions = reader.read_i32::<BigEndian>()?.max(0);
+            let partitions = (0..partitions)
+                .map(|_| reader.read_i32::<BigEndian>())
+                .collect::<Result<Vec<_>, _>>()?;
+            assignments.push(Self {
+                topic: String::from_utf8_lossy(&topic).to_string(),
+                partitions,
+            });
+        }
+        Ok(assignments)
+    }
+}
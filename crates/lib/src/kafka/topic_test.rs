@@ -1,4 +1,4 @@
-use crate::{ConsumerGroupDetail, ConsumerGroupMember, ConsumerGroupState};
+use crate::{ConsumerGroupDetail, ConsumerGroupMember, ConsumerGroupState, MemberAssignment};
 
 #[test]
 fn test_lag() {
@@ -7,18 +7,42 @@ fn test_lag() {
         members: vec![
             ConsumerGroupMember {
                 member: "member-1".to_string(),
+                client_id: "client-1".to_string(),
+                host: "/10.0.0.1".to_string(),
+                instance_id: None,
                 start_offset: 0,
                 end_offset: 100,
                 assignments: vec![],
             },
             ConsumerGroupMember {
                 member: "member-2".to_string(),
+                client_id: "client-2".to_string(),
+                host: "/10.0.0.2".to_string(),
+                instance_id: Some("member-2".to_string()),
                 start_offset: 45,
                 end_offset: 50,
                 assignments: vec![],
             },
         ],
         state: ConsumerGroupState::Empty,
+        protocol: String::new(),
+        committed_lag: None,
     };
     assert_eq!(consumer_detail.lag(), 105);
 }
+
+#[test]
+fn test_parse_member_assignment() {
+    let mut assignment = vec![0, 3, 0, 0, 0, 1, 0, 8];
+    assignment.extend(b"payments");
+    assignment.extend([0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 2]);
+    assignment.extend([0, 0, 0, 0]);
+    assert_eq!(
+        MemberAssignment::parse(&assignment).unwrap(),
+        vec![MemberAssignment {
+            topic: "payments".to_string(),
+            partitions: vec![0, 2]
+        }]
+    );
+    assert!(MemberAssignment::parse(&assignment[..10]).is_err());
+}
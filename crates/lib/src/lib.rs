@@ -1,19 +1,57 @@
 //! This crate contains the core structs and enums for the tool.
 //! It heavily relies on the [`rdkafka` crate](https://github.com/fede1024/rust-rdkafka).
+//!
+//! It can be embedded by other tools, without the TUI, to read and decode kafka records:
+//!  - [`KafkaRecord`] is the record model, serialized as JSON by the exports,
+//!  - [`parse_search_query`] parses the [query language](https://github.com/MAIF/yozefu/tree/main/docs/query-language/README.md) into a [`SearchQuery`],
+//!  - [`kafka::ConsumerBuilder`] creates consumers reading the topics from the offset of a search query,
+//!    [`kafka::RecordStream`] decodes their messages,
+//!  - [`ExportedKafkaRecord`] and [`kafka::ExportedSchema`] are the structures of the export files.
+//!
+//! Evaluating the search queries against records, with the search filters, is the job of the `yozefu-app` crate.
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), yozefu_lib::Error> {
+//! use std::collections::HashMap;
+//! use yozefu_lib::{kafka::{ConsumerBuilder, RecordStream}, parse_search_query};
+//!
+//! let query = parse_search_query("from begin limit 10")?.1;
+//! let consumer = ConsumerBuilder::new(HashMap::from([(
+//!     "bootstrap.servers".to_string(),
+//!     "localhost:9092".to_string(),
+//! )]))
+//! .topics(&["payments".to_string()])
+//! .search_query(&query)
+//! .build()?;
+//! let mut records = RecordStream::new(consumer, None);
+//! let record = records.next().await?;
+//! println!("{}", serde_json::to_string_pretty(&record)?);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The items of this crate follow semantic versioning, except the modules generated from the protocol buffers definitions
+//! and the syntax tree of the search query in [`search`], which gains variants with the query language.
+//! Most of them require the `native` feature.
+//! The records, the topics, the schemas and the errors are `#[non_exhaustive]`: new fields and variants are added in minor releases.
 
 #[cfg(feature = "native")]
 pub mod error;
 
 #[cfg(feature = "native")]
 pub use {
-    error::Error, kafka::ExportedKafkaRecord, kafka::topic::*, search::SearchQuery,
-    search::parse_search_query,
+    error::Error, error::SearchError, kafka::ExportedKafkaRecord, kafka::topic::*,
+    search::SearchQuery, search::parse_search_query,
 };
 
+#[cfg(feature = "grpc")]
+pub mod grpc;
 pub mod kafka;
 pub mod search;
 pub use kafka::Comparable;
 pub use kafka::DataType;
 pub use kafka::KafkaRecord;
+pub use kafka::PayloadFormat;
+pub use kafka::TimestampType;
 pub use search::FilterResult;
 pub use search::compare::StringOperator;
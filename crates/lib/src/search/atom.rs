@@ -23,8 +23,9 @@ pub enum Atom {
 
 pub(crate) fn parse_atom(input: &str) -> IResult<&str, Atom> {
     alt((
-        map(wsi(parse_filter), Atom::Filter),
+        // Before filters, `len(value.items) > 5` is a comparison, not the filter `len`
         map(wsi(parse_compare), Atom::Compare),
+        map(wsi(parse_filter), Atom::Filter),
         map(
             delimited(wsi(tag("(")), parse_or_expression, wsi(tag(")"))),
             |expr: Expression| Atom::Parenthesis(Box::new(expr)),
@@ -1,30 +1,46 @@
 /// Clauses are similar to clauses in the SQL language.
 use nom::Parser;
-use nom::bytes::complete::tag_no_case;
+use nom::bytes::complete::{tag, tag_no_case, take_while1};
+use nom::multi::separated_list1;
 use nom::{
     IResult,
     branch::alt,
     combinator::{map, opt},
-    sequence::{pair, preceded},
+    sequence::{delimited, pair, preceded, separated_pair},
 };
 
 use crate::search::number::parse_number;
+use crate::search::string::parse_string;
 
+use super::distinct::{Distinct, parse_distinct};
 use super::expression::{Expression, parse_or_expression};
 use super::offset::{FromOffset, parse_from_offset};
 use super::order::{Order, OrderKeyword, parse_order, parse_order_keyword};
+use super::sample::{Sample, parse_sample};
+use super::scan::{ScanLimit, parse_scan_limit};
+use super::symbol::{Symbol, parse_field};
 use super::wsi::wsi;
 
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) enum SearchClause {
     /// Clause that Limits the number of kafka records to consume
     Limit(usize),
+    /// Clause stopping the consumer once it has read a volume of kafka records, matching or not
+    ScanLimit(ScanLimit),
     /// Clause containing the search expression
     Expression(Expression),
     /// Clause for telling the consumer where to start consuming from
     From(FromOffset),
     /// Clause defining how to sort the kafka records in the UI
     OrderBy(Order, Option<OrderKeyword>),
+    /// Clause overriding kafka consumer properties for this search only
+    With(Vec<(String, String)>),
+    /// Clause keeping a random sample of the matching kafka records
+    Sample(Sample),
+    /// Clause keeping only the latest kafka record for each key or field
+    Distinct(Distinct),
+    /// Clause listing the fields projected when the kafka records are exported
+    Select(Vec<Symbol>),
 }
 
 pub(crate) fn parse_expression(input: &str) -> IResult<&str, SearchClause> {
@@ -59,6 +75,32 @@ pub(crate) fn parse_limit(input: &str) -> IResult<&str, SearchClause> {
     .parse(input)
 }
 
+pub(crate) fn parse_scan_limit_clause(input: &str) -> IResult<&str, SearchClause> {
+    map(parse_scan_limit, SearchClause::ScanLimit).parse(input)
+}
+
+pub(crate) fn parse_sample_clause(input: &str) -> IResult<&str, SearchClause> {
+    map(parse_sample, SearchClause::Sample).parse(input)
+}
+
+pub(crate) fn parse_distinct_clause(input: &str) -> IResult<&str, SearchClause> {
+    map(parse_distinct, SearchClause::Distinct).parse(input)
+}
+
+/// ```sql
+/// select value.amount, key, headers.tenant
+/// ```
+pub(crate) fn parse_select(input: &str) -> IResult<&str, SearchClause> {
+    map(
+        preceded(
+            wsi(tag_no_case("select")),
+            separated_list1(wsi(tag(",")), wsi(parse_field)),
+        ),
+        SearchClause::Select,
+    )
+    .parse(input)
+}
+
 pub(crate) fn parse_order_by(input: &str) -> IResult<&str, SearchClause> {
     map(
         preceded(
@@ -73,6 +115,75 @@ pub(crate) fn parse_order_by(input: &str) -> IResult<&str, SearchClause> {
     .parse(input)
 }
 
+/// ```sql
+/// with (fetch.max.bytes = 10485760, isolation.level = "read_committed")
+/// ```
+pub(crate) fn parse_with(input: &str) -> IResult<&str, SearchClause> {
+    let property = |input| {
+        take_while1(|c: char| c.is_alphanumeric() || c == '.' || c == '_' || c == '-').parse(input)
+    };
+    let property_value = alt((
+        parse_string,
+        map(
+            take_while1(|c: char| !c.is_whitespace() && c != ',' && c != ')'),
+            |s: &str| s.to_string(),
+        ),
+    ));
+    map(
+        preceded(
+            wsi(tag_no_case("with")),
+            delimited(
+                wsi(tag("(")),
+                separated_list1(
+                    wsi(tag(",")),
+                    separated_pair(wsi(property), wsi(tag("=")), wsi(property_value)),
+                ),
+                wsi(tag(")")),
+            ),
+        ),
+        |properties| {
+            SearchClause::With(
+                properties
+                    .into_iter()
+                    .map(|(k, v)| (k.to_string(), v))
+                    .collect(),
+            )
+        },
+    )
+    .parse(input)
+}
+
+#[test]
+fn test_parse_with() {
+    assert_eq!(
+        parse_with(r#"with (fetch.max.bytes = 10485760, isolation.level = "read_committed")"#),
+        Ok((
+            "",
+            SearchClause::With(vec![
+                ("fetch.max.bytes".to_string(), "10485760".to_string()),
+                ("isolation.level".to_string(), "read_committed".to_string())
+            ])
+        ))
+    );
+    assert!(parse_with(r#"with ()"#).is_err());
+}
+
+#[test]
+fn test_parse_select() {
+    assert_eq!(
+        parse_select("select value.amount, key,headers.tenant limit 10"),
+        Ok((
+            " limit 10",
+            SearchClause::Select(vec![
+                Symbol::Value(Some(".amount".to_string())),
+                Symbol::Key(None),
+                Symbol::Header("tenant".to_string())
+            ])
+        ))
+    );
+    assert!(parse_select("select").is_err());
+}
+
 #[test]
 fn test_parse_offset_clause() {
     assert_eq!(
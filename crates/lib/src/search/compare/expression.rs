@@ -2,23 +2,33 @@
 /// ```bash
 /// offset != 234
 /// key == "my-key"
+/// key.tenantId == "acme"
+/// key > 1000
 /// timestamp between "2 hours ago" and "1 hour ago"
+/// value.status in ("FAILED", "TIMEOUT")
+/// partition not in (0, 1)
+/// len(value.items) > 5
 /// ```
 use std::fmt::Display;
 
 #[cfg(feature = "native")]
 use chrono::{DateTime, Local};
+use itertools::Itertools;
 use nom::Parser;
 use nom::bytes::complete::tag_no_case;
 use nom::{
     IResult,
     branch::alt,
     bytes::complete::tag,
-    combinator::{map, value},
+    combinator::{map, value, verify},
+    multi::separated_list1,
+    sequence::delimited,
 };
 
 use super::number::NumberOperator;
 use super::string::StringOperator;
+#[cfg(feature = "native")]
+use crate::search::{filter::Parameter, operand::Operand, symbol::Symbol};
 
 #[cfg(feature = "native")]
 #[derive(Debug, PartialEq, Clone, Eq)]
@@ -27,12 +37,24 @@ pub enum CompareExpression {
     OffsetTail(i64),
     Offset(NumberOperator, i64),
     Topic(StringOperator, String),
-    Key(StringOperator, String),
+    /// Compares the key, or a field of JSON keys
+    Key(Option<String>, StringOperator, String),
+    /// Compares keys decoded as numbers, `==` and `!=` are string comparisons
+    KeyNumber(NumberOperator, i64),
     Value(Option<String>, StringOperator, String),
     Header(String, StringOperator, String),
     Size(NumberOperator, usize),
     Timestamp(NumberOperator, DateTime<Local>),
     TimestampBetween(DateTime<Local>, DateTime<Local>),
+    /// Compares the timestamp set by the broker, records whose timestamp has been set by the producer never match
+    LogTimestamp(NumberOperator, DateTime<Local>),
+    LogTimestampBetween(DateTime<Local>, DateTime<Local>),
+    /// The field is equal to one of the strings or numbers
+    In(Symbol, Vec<Parameter>),
+    /// The field is equal to none of the strings or numbers
+    NotIn(Symbol, Vec<Parameter>),
+    /// Compares operands computed with arithmetic or functions, `size > 1024 * 10`
+    Operation(Operand, NumberOperator, Operand),
 }
 
 #[cfg(feature = "native")]
@@ -43,7 +65,10 @@ impl Display for CompareExpression {
             CompareExpression::OffsetTail(r) => write!(f, "offsetTail - {r}"),
             CompareExpression::Offset(op, r) => write!(f, "offset {op} {r}"),
             CompareExpression::Topic(op, r) => write!(f, "topic {op} {r}"),
-            CompareExpression::Key(op, r) => write!(f, "key {op} {r}"),
+            CompareExpression::Key(left, op, r) => {
+                write!(f, "key{} {} {}", left.as_deref().unwrap_or_default(), op, r)
+            }
+            CompareExpression::KeyNumber(op, r) => write!(f, "key {op} {r}"),
             CompareExpression::Value(left, op, r) => write!(
                 f,
                 "value{} {} {}",
@@ -63,22 +88,72 @@ impl Display for CompareExpression {
             ),
             CompareExpression::TimestampBetween(l, r) => write!(
                 f,
-                r#"timestamp between "{}" and "{}"#,
+                r#"timestamp between "{}" and "{}""#,
+                l.to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+                r.to_rfc3339_opts(chrono::SecondsFormat::Millis, false)
+            ),
+            CompareExpression::LogTimestamp(op, r) => write!(
+                f,
+                r#"log_timestamp {} "{}""#,
+                op,
+                r.to_rfc3339_opts(chrono::SecondsFormat::Millis, false)
+            ),
+            CompareExpression::LogTimestampBetween(l, r) => write!(
+                f,
+                r#"log_timestamp between "{}" and "{}""#,
                 l.to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
                 r.to_rfc3339_opts(chrono::SecondsFormat::Millis, false)
             ),
+            CompareExpression::In(field, values) => {
+                write!(f, "{} in ({})", field.field(), values.iter().join(", "))
+            }
+            CompareExpression::NotIn(field, values) => {
+                write!(f, "{} not in ({})", field.field(), values.iter().join(", "))
+            }
+            CompareExpression::Operation(left, op, right) => write!(f, "{left} {op} {right}"),
         }
     }
 }
 
+/// Parses the right side of string comparisons, a string or a number compared as text: `key == 42`.
+#[cfg(feature = "native")]
+fn parse_text(input: &str) -> IResult<&str, String> {
+    use crate::search::{number::parse_unsigned_number_as_string, string::parse_string};
+
+    alt((
+        parse_string,
+        map(parse_unsigned_number_as_string, str::to_string),
+    ))
+    .parse(input)
+}
+
+/// Parses a list of strings and numbers between parentheses: `("FAILED", "TIMEOUT")`, `(0, 1)`.
+#[cfg(feature = "native")]
+fn parse_list(input: &str) -> IResult<&str, Vec<Parameter>> {
+    use crate::search::{number::parse_number, string::parse_string, wsi::wsi};
+
+    delimited(
+        wsi(tag("(")),
+        separated_list1(
+            wsi(tag(",")),
+            wsi(alt((
+                map(parse_number, Parameter::Number),
+                map(parse_string, Parameter::String),
+            ))),
+        ),
+        wsi(tag(")")),
+    )
+    .parse(input)
+}
+
 #[cfg(feature = "native")]
 pub fn parse_compare(input: &str) -> IResult<&str, CompareExpression> {
     use crate::search::{
         compare::{parse_equal, string::parse_string_operator},
         number::parse_number,
-        string::parse_string,
         symbol::{
-            Symbol, parse_header_symbol, parse_key, parse_offset, parse_partition, parse_size,
+            parse_field, parse_header_symbol, parse_key, parse_key_symbol,
+            parse_log_timestamp_symbol, parse_offset, parse_partition, parse_size,
             parse_timestamp_symbol, parse_topic, parse_value_symbol,
         },
         timestamp::parse_timestamp,
@@ -86,8 +161,29 @@ pub fn parse_compare(input: &str) -> IResult<&str, CompareExpression> {
     };
 
     use super::number::parse_number_operator;
+    use crate::search::operand::parse_operand;
 
     alt((
+        map(
+            verify(
+                (parse_operand, wsi(parse_number_operator), parse_operand),
+                |(left, _, right)| left.is_computed() || right.is_computed(),
+            ),
+            |(left, op, right)| CompareExpression::Operation(left, op, right),
+        ),
+        map(
+            (wsi(parse_field), wsi(tag_no_case("in")), parse_list),
+            |(field, _, values)| CompareExpression::In(field, values),
+        ),
+        map(
+            (
+                wsi(parse_field),
+                wsi(tag_no_case("not")),
+                wsi(tag_no_case("in")),
+                parse_list,
+            ),
+            |(field, _, _, values)| CompareExpression::NotIn(field, values),
+        ),
         map(
             (parse_offset, wsi(parse_number_operator), wsi(parse_number)),
             |(_, op, r)| CompareExpression::Offset(op, r),
@@ -113,18 +209,32 @@ pub fn parse_compare(input: &str) -> IResult<&str, CompareExpression> {
             |(_, op, partition)| CompareExpression::Partition(op, partition),
         ),
         map(
-            (parse_topic, wsi(parse_string_operator), wsi(parse_string)),
+            (parse_topic, wsi(parse_string_operator), wsi(parse_text)),
             |(_, op, r)| CompareExpression::Topic(op, r),
         ),
         map(
-            (parse_key, wsi(parse_string_operator), wsi(parse_string)),
-            |(_, op, r)| CompareExpression::Key(op, r),
+            (
+                parse_key,
+                wsi(verify(parse_number_operator, |op| {
+                    !matches!(op, NumberOperator::Equal | NumberOperator::NotEqual)
+                })),
+                wsi(parse_number),
+            ),
+            |(_, op, r)| CompareExpression::KeyNumber(op, r),
+        ),
+        map(
+            (
+                parse_key_symbol,
+                wsi(parse_string_operator),
+                wsi(parse_text),
+            ),
+            |(left, op, r)| CompareExpression::Key(left.1, op, r),
         ),
         map(
             (
                 parse_value_symbol,
                 wsi(parse_string_operator),
-                wsi(parse_string),
+                wsi(parse_text),
             ),
             |(left, op, r)| CompareExpression::Value(left.1, op, r),
         ),
@@ -132,7 +242,7 @@ pub fn parse_compare(input: &str) -> IResult<&str, CompareExpression> {
             (
                 parse_header_symbol,
                 wsi(parse_string_operator),
-                wsi(parse_string),
+                wsi(parse_text),
             ),
             |(left, op, r)| CompareExpression::Header(left.1, op, r),
         ),
@@ -154,6 +264,24 @@ pub fn parse_compare(input: &str) -> IResult<&str, CompareExpression> {
             ),
             |(_, _, from, _, to)| CompareExpression::TimestampBetween(from, to),
         ),
+        map(
+            (
+                parse_log_timestamp_symbol,
+                wsi(parse_number_operator),
+                wsi(parse_timestamp),
+            ),
+            |(_, op, r)| CompareExpression::LogTimestamp(op, r),
+        ),
+        map(
+            (
+                parse_log_timestamp_symbol,
+                wsi(tag_no_case("between")),
+                wsi(parse_timestamp),
+                wsi(tag_no_case("and")),
+                wsi(parse_timestamp),
+            ),
+            |(_, _, from, _, to)| CompareExpression::LogTimestampBetween(from, to),
+        ),
     ))
     .parse(input)
 }
@@ -1,4 +1,7 @@
-use crate::search::{compare::parse_compare, parse_search_query};
+use crate::search::{
+    compare::{CompareExpression, NumberOperator, StringOperator, parse_compare},
+    parse_search_query,
+};
 
 #[test]
 fn test_parse_compare() {
@@ -20,3 +23,106 @@ fn test_parse_search_query() {
         .is_ok()
     );
 }
+
+#[test]
+fn test_parse_key_number() {
+    assert_eq!(
+        parse_compare("key >= 1_000"),
+        Ok((
+            "",
+            CompareExpression::KeyNumber(NumberOperator::GreaterOrEqual, 1000)
+        ))
+    );
+    assert!(matches!(
+        parse_compare(r#"key == "42""#),
+        Ok(("", CompareExpression::Key(None, _, _)))
+    ));
+    assert_eq!(
+        parse_compare("key == 42"),
+        Ok((
+            "",
+            CompareExpression::Key(None, StringOperator::Equal, "42".to_string())
+        ))
+    );
+}
+
+#[test]
+fn test_parse_key_path() {
+    assert_eq!(
+        parse_compare(r#"key.tenantId == "acme""#),
+        Ok((
+            "",
+            CompareExpression::Key(
+                Some(".tenantId".to_string()),
+                StringOperator::Equal,
+                "acme".to_string()
+            )
+        ))
+    );
+    assert_eq!(
+        parse_compare(r#"key=="acme""#),
+        Ok((
+            "",
+            CompareExpression::Key(None, StringOperator::Equal, "acme".to_string())
+        ))
+    );
+}
+
+#[test]
+fn test_parse_in() {
+    use crate::search::{filter::Parameter, symbol::Symbol};
+
+    assert_eq!(
+        parse_compare(r#"value.status in ("FAILED", 'TIMEOUT')"#),
+        Ok((
+            "",
+            CompareExpression::In(
+                Symbol::Value(Some(".status".to_string())),
+                vec![
+                    Parameter::String("FAILED".to_string()),
+                    Parameter::String("TIMEOUT".to_string())
+                ]
+            )
+        ))
+    );
+    assert_eq!(
+        parse_compare("partition not in (0,1)"),
+        Ok((
+            "",
+            CompareExpression::NotIn(
+                Symbol::Partition,
+                vec![Parameter::Number(0), Parameter::Number(1)]
+            )
+        ))
+    );
+    assert_eq!(
+        parse_compare("partition not in (0,1)")
+            .unwrap()
+            .1
+            .to_string(),
+        "partition not in (0, 1)"
+    );
+    assert!(parse_compare("partition in ()").is_err());
+}
+
+#[test]
+fn test_parse_log_timestamp() {
+    assert!(matches!(
+        parse_compare(r#"log_ts > "2024-05-28T17:55:08.145+02:00""#),
+        Ok((
+            "",
+            CompareExpression::LogTimestamp(NumberOperator::GreaterThan, _)
+        ))
+    ));
+    assert!(matches!(
+        parse_compare(r#"log_timestamp between "2024-05-28T17:55:08.145+02:00" and now"#),
+        Ok(("", CompareExpression::LogTimestampBetween(_, _)))
+    ));
+    assert!(matches!(
+        parse_compare(r#"ts > "2024-05-28T17:55:08.145+02:00""#),
+        Ok((
+            "",
+            CompareExpression::Timestamp(NumberOperator::GreaterThan, _)
+        ))
+    ));
+}
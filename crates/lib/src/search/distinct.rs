@@ -0,0 +1,110 @@
+use std::fmt::Display;
+
+use nom::{
+    IResult, Parser,
+    branch::alt,
+    bytes::complete::tag_no_case,
+    combinator::map,
+    sequence::{pair, preceded},
+};
+
+use crate::KafkaRecord;
+
+use super::{
+    symbol::{parse_header_symbol, parse_key, parse_value_symbol},
+    wsi::wsi,
+};
+
+/// Keeps only the latest record for each distinct key, header or JSON property of the value,
+/// like a compacted topic would.
+///
+/// ```sql
+/// distinct by key
+/// distinct by value.customer.id
+/// distinct by headers.trace-id
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Distinct {
+    Key,
+    /// The value or one of its JSON properties
+    Value(Option<String>),
+    Header(String),
+}
+
+impl Distinct {
+    /// Returns the value records are deduplicated by, `None` when the record doesn't have it.
+    pub fn of(&self, record: &KafkaRecord) -> Option<String> {
+        match self {
+            Distinct::Key => Some(record.key.raw()),
+            Distinct::Value(None) => Some(record.value.raw()),
+            Distinct::Value(Some(path)) => {
+                let value: serde_json::Value = record.value.clone().into();
+                let pointer = path.replace(['.', '['], "/").replace(']', "");
+                value.pointer(&pointer).map(|v| match v {
+                    serde_json::Value::String(s) => s.clone(),
+                    v => v.to_string(),
+                })
+            }
+            Distinct::Header(name) => record.headers.get(name).cloned(),
+        }
+    }
+}
+
+impl Display for Distinct {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Distinct::Key => write!(f, "key"),
+            Distinct::Value(path) => write!(f, "value{}", path.as_deref().unwrap_or_default()),
+            Distinct::Header(name) => write!(f, "headers.{name}"),
+        }
+    }
+}
+
+/// Parses the clause deduplicating the matching records.
+pub(crate) fn parse_distinct(input: &str) -> IResult<&str, Distinct> {
+    preceded(
+        pair(wsi(tag_no_case("distinct")), wsi(tag_no_case("by"))),
+        wsi(alt((
+            map(parse_header_symbol, |(_, name)| Distinct::Header(name)),
+            map(parse_value_symbol, |(_, path)| Distinct::Value(path)),
+            map(parse_key, |_| Distinct::Key),
+        ))),
+    )
+    .parse(input)
+}
+
+#[test]
+fn test_parse_distinct() {
+    assert_eq!(parse_distinct("distinct by key"), Ok(("", Distinct::Key)));
+    assert_eq!(
+        parse_distinct("DISTINCT BY value.customer.id limit 10"),
+        Ok((
+            " limit 10",
+            Distinct::Value(Some(".customer.id".to_string()))
+        ))
+    );
+    assert_eq!(
+        parse_distinct("distinct by headers.trace"),
+        Ok(("", Distinct::Header("trace".to_string())))
+    );
+    assert!(parse_distinct("distinct by offset").is_err());
+}
+
+#[test]
+fn test_distinct_of() {
+    let record = KafkaRecord {
+        key: crate::DataType::String("1".to_string()),
+        value: crate::DataType::Json(serde_json::json!({"customer": {"id": 42}})),
+        ..Default::default()
+    };
+    assert_eq!(Distinct::Key.of(&record), Some("1".to_string()));
+    assert_eq!(
+        Distinct::Value(Some(".customer.id".to_string())).of(&record),
+        Some("42".to_string())
+    );
+    assert_eq!(Distinct::Header("trace".to_string()).of(&record), None);
+    assert_eq!(
+        Distinct::Value(Some(".customer.id".to_string())).to_string(),
+        "value.customer.id"
+    );
+}
@@ -5,7 +5,8 @@
 //! from beginning offset > 50 && contains("rust")
 //! ```
 //!
-//! In the example, the filter `contains` take 1 string parameter. Filters support string and number parameters for now.
+//! In the example, the filter `contains` take 1 string parameter. Filters support string and number parameters,
+//! and fields of the record such as `key`, `value.token` or `headers.tenant`, replaced by their value for each record.
 //! Let's define this `contains` filter.
 //!
 //! this filter is not Rust code, nor assembly code but a wasm module. The wasm module must have the following requirements:
@@ -16,6 +17,8 @@
 //!
 //! The library uses [Extism](https://extism.org/) to develop wasm modules.
 //! You can also find the source code of  the `contains` WebAssembly module written in different supported programming languages.
+//!
+//! Programs embedding the search engine can also register filters written in Rust, see [`super::matcher`].
 
 use itertools::Itertools;
 use nom::{
@@ -33,7 +36,12 @@ use std::fmt::Display;
 
 use crate::KafkaRecord;
 
-use super::{number::parse_number, string::parse_string, wsi::wsi};
+use super::{
+    number::parse_number,
+    string::parse_string,
+    symbol::{Symbol, parse_field},
+    wsi::wsi,
+};
 
 #[derive(Debug, PartialEq, Clone, Default)]
 pub struct Filter {
@@ -59,6 +67,8 @@ impl Display for Filter {
 pub enum Parameter {
     Number(i64),
     String(String),
+    /// A field of the record
+    Symbol(Symbol),
 }
 
 impl Display for Parameter {
@@ -66,17 +76,32 @@ impl Display for Parameter {
         match self {
             Parameter::Number(i) => write!(f, "{i}"),
             Parameter::String(i) => write!(f, "'{i}'"),
+            Parameter::Symbol(symbol @ (Symbol::Key(_) | Symbol::Value(_))) => {
+                write!(f, "{}", symbol.field())
+            }
+            Parameter::Symbol(Symbol::Header(name)) => write!(f, "headers.{name}"),
+            Parameter::Symbol(symbol) => write!(f, "{}", symbol.to_string().to_lowercase()),
         }
     }
 }
 
 impl Parameter {
+    /// The parameter as JSON, fields of the record are `null` since there is no record to read them from.
     pub fn json(&self) -> Value {
         match self {
             Parameter::Number(i) => Value::Number(Number::from(*i)),
             Parameter::String(i) => Value::String(i.to_string()),
+            Parameter::Symbol(_) => Value::Null,
         }
     }
+
+    /// The parameter as JSON, fields of the record are replaced by their value, `null` when the record has no such field.
+    pub fn resolve(&self, record: &KafkaRecord) -> Value {
+        let Parameter::Symbol(symbol) = self else {
+            return self.json();
+        };
+        symbol.value_of(record)
+    }
 }
 
 fn parse_filter_name(input: &str) -> IResult<&str, String> {
@@ -91,6 +116,7 @@ fn parse_parameter(input: &str) -> IResult<&str, Parameter> {
     wsi(alt((
         map(parse_number, Parameter::Number),
         map(parse_string, Parameter::String),
+        map(parse_field, Parameter::Symbol),
     )))
     .parse(input)
 }
@@ -11,3 +11,63 @@ fn test_filter_to_string() {
         "my_filter(10, 'value')"
     )
 }
+
+#[test]
+fn test_parse_symbol_parameters() {
+    use crate::{DataType, KafkaRecord, search::symbol::Symbol};
+
+    let (_, filter) =
+        super::filter::parse_filter("jwt_expired(value.token, headers.tenant, key, 3)").unwrap();
+    assert_eq!(
+        filter.parameters,
+        vec![
+            Parameter::Symbol(Symbol::Value(Some(".token".into()))),
+            Parameter::Symbol(Symbol::Header("tenant".into())),
+            Parameter::Symbol(Symbol::Key(None)),
+            Parameter::Number(3),
+        ]
+    );
+    assert_eq!(
+        filter.to_string(),
+        "jwt_expired(value.token, headers.tenant, key, 3)"
+    );
+
+    let record = KafkaRecord {
+        key: DataType::String("1234".into()),
+        value: DataType::Json(serde_json::json!({ "token": "abc" })),
+        ..Default::default()
+    };
+    let resolved = filter
+        .parameters
+        .iter()
+        .map(|p| p.resolve(&record))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        resolved,
+        vec![
+            serde_json::json!("abc"),
+            serde_json::Value::Null,
+            serde_json::json!("1234"),
+            serde_json::json!(3)
+        ]
+    );
+}
+
+#[test]
+fn test_function_compared_is_not_a_filter() {
+    use crate::search::{
+        atom::Atom,
+        expression::{AndExpression, Expression, parse_or_expression},
+        term::Term,
+    };
+
+    let (remaining, expression) = parse_or_expression("len(value.items) > 5").unwrap();
+    assert_eq!(remaining, "");
+    assert_eq!(expression.to_string(), "len(value.items) > 5");
+
+    let (_, expression) = parse_or_expression("key_ends_with('x')").unwrap();
+    assert!(matches!(
+        expression,
+        Expression::OrTerm(AndExpression::AndTerm(Term::Atom(Atom::Filter(_))))
+    ));
+}
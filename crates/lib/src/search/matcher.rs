@@ -0,0 +1,88 @@
+//! Search filters written in Rust by the programs embedding the search engine.
+//!
+//! A matcher is called from search queries like a WebAssembly [search filter](super::filter),
+//! once it is registered under the name of the filter:
+//! ```
+//! use serde_json::Value;
+//! use yozefu_lib::{KafkaRecord, search::matcher::register_matcher};
+//!
+//! register_matcher("is_uuid", |_record: &KafkaRecord, parameters: &[Value]| {
+//!     parameters.first().and_then(Value::as_str).is_some_and(|s| {
+//!         s.len() == 36 && s.chars().all(|c| c.is_ascii_hexdigit() || c == '-')
+//!     })
+//! });
+//! // from begin is_uuid(key) and topic == "payments"
+//! ```
+//! Matchers take precedence over the WebAssembly modules with the same name.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock, RwLock},
+};
+
+use serde_json::Value;
+
+use crate::KafkaRecord;
+
+/// A predicate evaluated against each consumed record.
+pub trait Matcher: Send + Sync {
+    /// Returns an error message when the parameters are invalid, it is called when the search query is parsed.
+    /// Fields of the record passed as parameters are `null` at this point.
+    fn parse_parameters(&self, _parameters: &[Value]) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Returns `true` if the record matches, fields of the record passed as parameters are replaced by their value.
+    fn matches(&self, record: &KafkaRecord, parameters: &[Value]) -> bool;
+}
+
+impl<F> Matcher for F
+where
+    F: Fn(&KafkaRecord, &[Value]) -> bool + Send + Sync,
+{
+    fn matches(&self, record: &KafkaRecord, parameters: &[Value]) -> bool {
+        self(record, parameters)
+    }
+}
+
+/// Registered matchers, by name.
+static MATCHERS: LazyLock<RwLock<HashMap<String, Arc<dyn Matcher>>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Registers the matcher under the given name, replacing the previous one.
+pub fn register_matcher(name: &str, matcher: impl Matcher + 'static) {
+    MATCHERS
+        .write()
+        .unwrap()
+        .insert(name.to_string(), Arc::new(matcher));
+}
+
+/// Returns the matcher registered under the given name.
+pub fn matcher(name: &str) -> Option<Arc<dyn Matcher>> {
+    MATCHERS.read().unwrap().get(name).cloned()
+}
+
+#[test]
+fn test_register_matcher() {
+    struct Positive;
+
+    impl Matcher for Positive {
+        fn parse_parameters(&self, parameters: &[Value]) -> Result<(), String> {
+            match parameters.len() {
+                1 => Ok(()),
+                _ => Err("Expected 1 parameter".to_string()),
+            }
+        }
+
+        fn matches(&self, _record: &KafkaRecord, parameters: &[Value]) -> bool {
+            parameters[0].as_i64().is_some_and(|n| n > 0)
+        }
+    }
+
+    assert!(matcher("positive").is_none());
+    register_matcher("positive", Positive);
+    let positive = matcher("positive").unwrap();
+    assert!(positive.parse_parameters(&[]).is_err());
+    assert!(positive.matches(&KafkaRecord::default(), &[Value::from(3)]));
+    assert!(!positive.matches(&KafkaRecord::default(), &[Value::from(-3)]));
+}
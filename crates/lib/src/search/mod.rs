@@ -3,7 +3,7 @@
 //!
 //! ```bnf
 //! search-query      ::= clause+
-//! clause            ::= or-expression | limit-clause | from-clause | order-clause
+//! clause            ::= or-expression | limit-clause | scan-clause | from-clause | order-clause | with-clause | sample-clause | distinct-clause | select-clause
 //! or-expression     ::= And-expression | and-expression 'or' and-expression
 //! and-expression    ::= atom | atom 'and' atom
 //! term              ::= atom | '!' atom
@@ -11,19 +11,37 @@
 //! number-symbol     ::= 'offset' | 'partition' | 'size'
 //! string-symbol     ::= 'topic' | 'key' | 'timestamp' | 'value'
 //! symbol            ::= number-symbol | string-symbol
-//! comparison        ::= number-comparison | string-comparison | time-comparison
+//! comparison        ::= number-comparison | key-comparison | string-comparison | time-comparison | list-comparison | operation
 //! number-comparison ::= number-symbol number-operator number
+//! key-comparison    ::= 'key' ('>' | '<' | '>=' | '<=') number
 //! string-comparison ::= string-symbol string-operator string
 //! time-comparison   ::= 'between' string 'and' string
+//! list-comparison   ::= field 'not'? 'in' '(' filter-parameter (',' filter-parameter)* ')'
+//! operation         ::= operand number-operator operand
+//! operand           ::= product (('+' | '-') product)*
+//! product           ::= factor (('*' | '/' | '%') factor)*
+//! factor            ::= duration | number ('.' [0-9]+)? | string | 'now()' | function '(' operand ')' | field | '(' operand ')'
+//! duration          ::= number ('.' [0-9]+)? ('ms' | 's' | 'm' | 'h' | 'd')
+//! function          ::= 'len' | 'lower' | 'upper' | 'abs'
 //! number-operator   ::=  '==' | '!=' | '>' | '<' | '>=' | '<='
 //! string-operator   ::= 'starts with' | '==' | '!=' | '=~' | 'contains' | 'contain' | 'includes' | 'include'
 //! filter            ::= .+ '('filter-parameters')'
 //! filter-parameter  ::= string | number
 //! filter-parameters ::= filter-parameter  (',' filter-parameter)*
 //! limit-clause      ::= 'limit' number
+//! scan-clause       ::= 'scan limit' number ('records' | byte-unit)?
+//! byte-unit         ::= 'B' | 'bytes' | 'KB' | 'KiB' | 'MB' | 'MiB' | 'GB' | 'GiB' | 'TB' | 'TiB'
+//! sample-clause     ::= 'sample' number | 'sample' percentage
+//! percentage        ::= [0-9]+ ('.' [0-9]+)? '%'
+//! distinct-clause   ::= 'distinct by' ('key' | 'value' json-path? | 'headers.' header)
+//! select-clause     ::= 'select' field (',' field)*
+//! field             ::= 'key' json-path? | 'topic' | 'partition' | 'offset' | 'timestamp' | 'size' | 'value' json-path? | 'headers.' header
 //! order-clause      ::= 'order by' symbol order-keyword
 //! order-keyword     ::= 'asc' | 'desc'
 //! from-clause       ::= 'from' offset
+//! with-clause       ::= 'with' '(' property '=' property-value (',' property '=' property-value)* ')'
+//! property          ::= [a-zA-Z0-9._-]+
+//! property-value    ::= string | [^,)]+
 //! offset            ::= 'beginning' | 'begin' | 'end' | 'end' '-' number | string | number
 //! number            ::= [0-9_]+
 //! string            ::= '"' [^"]+ '"' | "'" [^']+ "'"
@@ -35,16 +53,28 @@ pub mod atom;
 #[cfg(feature = "native")]
 pub mod clause;
 #[cfg(feature = "native")]
+pub mod distinct;
+#[cfg(feature = "native")]
 pub mod expression;
 #[cfg(feature = "native")]
 pub mod filter;
 #[cfg(feature = "native")]
+pub mod matcher;
+#[cfg(feature = "native")]
 pub mod number;
 #[cfg(feature = "native")]
 pub mod offset;
 #[cfg(feature = "native")]
+pub mod operand;
+#[cfg(feature = "native")]
 pub mod order;
 #[cfg(feature = "native")]
+pub mod placeholder;
+#[cfg(feature = "native")]
+pub mod sample;
+#[cfg(feature = "native")]
+pub mod scan;
+#[cfg(feature = "native")]
 pub mod search_query;
 #[cfg(feature = "native")]
 pub mod string;
@@ -55,17 +85,27 @@ pub mod term;
 #[cfg(feature = "native")]
 pub mod timestamp;
 #[cfg(feature = "native")]
+pub mod visitor;
+#[cfg(feature = "native")]
 pub mod wsi;
 
 pub mod compare;
 
+#[cfg(feature = "native")]
+pub use distinct::Distinct;
 #[cfg(feature = "native")]
 pub use order::Order;
 #[cfg(feature = "native")]
 pub use order::OrderBy;
 #[cfg(feature = "native")]
+pub use sample::Sample;
+#[cfg(feature = "native")]
+pub use scan::ScanLimit;
+#[cfg(feature = "native")]
 pub use search_query::SearchQuery;
 #[cfg(feature = "native")]
+pub use search_query::append_predicate;
+#[cfg(feature = "native")]
 pub use search_query::parse_search_query;
 use serde::Deserialize;
 use serde::Serialize;
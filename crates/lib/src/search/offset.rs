@@ -1,5 +1,7 @@
 use std::fmt::Display;
 
+use chrono::{DateTime, Local, SecondsFormat};
+
 use nom::{
     IResult, Parser,
     branch::alt,
@@ -38,7 +40,15 @@ impl Display for FromOffset {
             FromOffset::End => write!(f, "end"),
             FromOffset::Offset(o) => write!(f, "{o}"),
             FromOffset::OffsetTail(o) => write!(f, "end - {o}"),
-            FromOffset::Timestamp(_) => write!(f, ""),
+            FromOffset::Timestamp(t) => match DateTime::from_timestamp_millis(*t) {
+                Some(d) => write!(
+                    f,
+                    r#""{}""#,
+                    d.with_timezone(&Local)
+                        .to_rfc3339_opts(SecondsFormat::Millis, false)
+                ),
+                None => write!(f, "{t}"),
+            },
         }
     }
 }
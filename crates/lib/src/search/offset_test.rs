@@ -9,3 +9,10 @@ fn test_parse_from_offset() {
 fn test_parse_from_end_minus_number() {
     assert!(parse_from_offset(r#"from end - 10"#).is_ok());
 }
+
+#[test]
+fn test_display_from_timestamp() {
+    let (_, offset) = parse_from_offset(r#"from "2024-05-28T17:55:08.145+02:00""#).unwrap();
+    let (_, parsed) = parse_from_offset(&format!("from {offset}")).unwrap();
+    assert_eq!(parsed, offset);
+}
@@ -0,0 +1,415 @@
+//! Operands of arithmetic comparisons: literals, fields of the record, arithmetic and functions.
+//! ```sql
+//! size > 1024 * 10
+//! len(value.items) > 5
+//! lower(value.type) == "order"
+//! abs(value.delta) > 0.5
+//! now() - timestamp > 1h
+//! value.processedAt - timestamp > 30s
+//! ```
+//! Timestamps are numbers of milliseconds since the epoch and durations are numbers of milliseconds,
+//! strings formatted as RFC 3339 dates are converted to timestamps when they are used in arithmetic.
+use std::fmt::Display;
+
+use chrono::{DateTime, Local};
+use nom::{
+    IResult, Parser,
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{alphanumeric1, char, digit1, one_of},
+    combinator::{map, map_opt, not, opt, recognize, value},
+    multi::many0,
+    sequence::{delimited, pair},
+};
+use serde_json::{Number, Value};
+
+use crate::KafkaRecord;
+
+use super::{
+    number::parse_unsigned_number_as_string,
+    string::parse_string,
+    symbol::{Symbol, parse_field},
+    wsi::wsi,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ArithmeticOperator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+}
+
+/// Functions available in comparisons, they take one argument.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Function {
+    /// Number of characters of a string, of items of an array or of properties of an object
+    Len,
+    Lower,
+    Upper,
+    Abs,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Operand {
+    /// A number or a string
+    Literal(Value),
+    /// A duration in milliseconds, written with a unit: `500ms`, `30s`, `5m`, `1h`, `7d`
+    Duration(i64),
+    /// The current time, in milliseconds since the epoch
+    Now,
+    /// A field of the record
+    Field(Symbol),
+    Arithmetic(Box<Operand>, ArithmeticOperator, Box<Operand>),
+    Function(Function, Box<Operand>),
+}
+
+impl Display for ArithmeticOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArithmeticOperator::Add => write!(f, "+"),
+            ArithmeticOperator::Subtract => write!(f, "-"),
+            ArithmeticOperator::Multiply => write!(f, "*"),
+            ArithmeticOperator::Divide => write!(f, "/"),
+            ArithmeticOperator::Remainder => write!(f, "%"),
+        }
+    }
+}
+
+impl Display for Function {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Function::Len => write!(f, "len"),
+            Function::Lower => write!(f, "lower"),
+            Function::Upper => write!(f, "upper"),
+            Function::Abs => write!(f, "abs"),
+        }
+    }
+}
+
+impl Display for Operand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operand::Literal(Value::String(s)) => write!(f, "\"{s}\""),
+            Operand::Literal(v) => write!(f, "{v}"),
+            Operand::Duration(millis) => {
+                let (amount, unit) = DURATION_UNITS
+                    .iter()
+                    .rev()
+                    .find(|(_, m)| millis % m == 0)
+                    .map_or((*millis, "ms"), |(unit, m)| (millis / m, unit));
+                write!(f, "{amount}{unit}")
+            }
+            Operand::Now => write!(f, "now()"),
+            Operand::Field(symbol) => write!(f, "{}", symbol.field()),
+            Operand::Arithmetic(left, op, right) => {
+                for (i, operand) in [left, right].into_iter().enumerate() {
+                    if i == 1 {
+                        write!(f, " {op} ")?;
+                    }
+                    match operand.as_ref() {
+                        Operand::Arithmetic(..) => write!(f, "({operand})")?,
+                        operand => write!(f, "{operand}")?,
+                    }
+                }
+                Ok(())
+            }
+            Operand::Function(function, argument) => write!(f, "{function}({argument})"),
+        }
+    }
+}
+
+impl Operand {
+    /// Whether the operand is computed, with arithmetic or a function, or is a duration or the current time.
+    /// Plain fields and literals are compared by the other comparison expressions.
+    pub fn is_computed(&self) -> bool {
+        matches!(
+            self,
+            Operand::Arithmetic(..) | Operand::Function(..) | Operand::Duration(_) | Operand::Now
+        )
+    }
+
+    /// The fields of the record the operand reads.
+    pub fn fields(&self) -> Vec<&Symbol> {
+        match self {
+            Operand::Literal(_) | Operand::Duration(_) | Operand::Now => vec![],
+            Operand::Field(symbol) => vec![symbol],
+            Operand::Arithmetic(left, _, right) => {
+                let mut fields = left.fields();
+                fields.extend(right.fields());
+                fields
+            }
+            Operand::Function(_, argument) => argument.fields(),
+        }
+    }
+
+    /// Evaluates the operand for the record, `null` when a field is missing or the operation makes no sense.
+    pub fn evaluate(&self, record: &KafkaRecord) -> Value {
+        match self {
+            Operand::Literal(v) => v.clone(),
+            Operand::Duration(millis) => (*millis).into(),
+            Operand::Now => Local::now().timestamp_millis().into(),
+            Operand::Field(symbol) => symbol.value_of(record),
+            Operand::Arithmetic(left, op, right) => {
+                arithmetic(&left.evaluate(record), *op, &right.evaluate(record))
+            }
+            Operand::Function(function, argument) => apply(*function, argument.evaluate(record)),
+        }
+    }
+}
+
+/// The value as a number, strings of digits are numbers too.
+pub fn as_number(value: &Value) -> Option<Number> {
+    match value {
+        Value::Number(n) => Some(n.clone()),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Units of durations and their number of milliseconds, from the smallest to the largest.
+const DURATION_UNITS: [(&str, i64); 5] = [
+    ("ms", 1),
+    ("s", 1_000),
+    ("m", 60_000),
+    ("h", 3_600_000),
+    ("d", 86_400_000),
+];
+
+/// The value as a number for arithmetic and comparisons, RFC 3339 dates are timestamps in milliseconds.
+pub fn as_operand(value: &Value) -> Option<Number> {
+    as_number(value).or_else(|| match value {
+        Value::String(s) => DateTime::parse_from_rfc3339(s.trim())
+            .ok()
+            .map(|d| d.timestamp_millis().into()),
+        _ => None,
+    })
+}
+
+fn arithmetic(left: &Value, op: ArithmeticOperator, right: &Value) -> Value {
+    let (Some(left), Some(right)) = (as_operand(left), as_operand(right)) else {
+        return Value::Null;
+    };
+    if let (Some(l), Some(r)) = (left.as_i64(), right.as_i64()) {
+        let integer = match op {
+            ArithmeticOperator::Add => l.checked_add(r),
+            ArithmeticOperator::Subtract => l.checked_sub(r),
+            ArithmeticOperator::Multiply => l.checked_mul(r),
+            ArithmeticOperator::Remainder => l.checked_rem(r),
+            ArithmeticOperator::Divide => None,
+        };
+        if let Some(integer) = integer {
+            return integer.into();
+        }
+    }
+    let (Some(l), Some(r)) = (left.as_f64(), right.as_f64()) else {
+        return Value::Null;
+    };
+    let result = match op {
+        ArithmeticOperator::Add => l + r,
+        ArithmeticOperator::Subtract => l - r,
+        ArithmeticOperator::Multiply => l * r,
+        ArithmeticOperator::Divide => l / r,
+        ArithmeticOperator::Remainder => l % r,
+    };
+    Number::from_f64(result).map_or(Value::Null, Value::Number)
+}
+
+fn apply(function: Function, argument: Value) -> Value {
+    match (function, argument) {
+        (Function::Len, Value::String(s)) => s.chars().count().into(),
+        (Function::Len, Value::Array(a)) => a.len().into(),
+        (Function::Len, Value::Object(o)) => o.len().into(),
+        (Function::Lower, Value::String(s)) => s.to_lowercase().into(),
+        (Function::Upper, Value::String(s)) => s.to_uppercase().into(),
+        (Function::Abs, argument) => match as_number(&argument) {
+            Some(n) => match n.as_i64() {
+                Some(i) => i.checked_abs().map_or(Value::Null, Value::from),
+                None => n
+                    .as_f64()
+                    .and_then(|f| Number::from_f64(f.abs()))
+                    .map_or(Value::Null, Value::Number),
+            },
+            None => Value::Null,
+        },
+        _ => Value::Null,
+    }
+}
+
+/// Parses a number, with an optional decimal part: `1_024`, `0.5`.
+fn parse_decimal(input: &str) -> IResult<&str, Value> {
+    map_opt(
+        recognize(pair(
+            parse_unsigned_number_as_string,
+            opt(pair(char('.'), digit1)),
+        )),
+        |n: &str| {
+            let n = n.replace('_', "");
+            match n.parse::<i64>() {
+                Ok(i) => Some(i.into()),
+                Err(_) => n.parse().ok().and_then(Number::from_f64).map(Value::Number),
+            }
+        },
+    )
+    .parse(input)
+}
+
+/// Parses a duration, a number followed by a unit: `1h`, `1.5s`.
+fn parse_duration(input: &str) -> IResult<&str, i64> {
+    map_opt(
+        (
+            parse_decimal,
+            alt(DURATION_UNITS.map(|(unit, millis)| value(millis, tag(unit)))),
+            not(alphanumeric1),
+        ),
+        |(amount, millis, ())| {
+            let amount = amount.as_f64()? * millis as f64;
+            Some(amount.round() as i64)
+        },
+    )
+    .parse(input)
+}
+
+fn parse_function(input: &str) -> IResult<&str, Function> {
+    alt((
+        value(Function::Len, tag("len")),
+        value(Function::Lower, tag("lower")),
+        value(Function::Upper, tag("upper")),
+        value(Function::Abs, tag("abs")),
+    ))
+    .parse(input)
+}
+
+fn parse_factor(input: &str) -> IResult<&str, Operand> {
+    wsi(alt((
+        map(parse_duration, Operand::Duration),
+        map(parse_decimal, Operand::Literal),
+        value(Operand::Now, (tag("now"), wsi(tag("(")), wsi(tag(")")))),
+        map(parse_string, |s| Operand::Literal(Value::String(s))),
+        map(
+            pair(
+                parse_function,
+                delimited(wsi(tag("(")), parse_operand, wsi(tag(")"))),
+            ),
+            |(function, argument)| Operand::Function(function, Box::new(argument)),
+        ),
+        map(parse_field, Operand::Field),
+        delimited(tag("("), parse_operand, wsi(tag(")"))),
+    )))
+    .parse(input)
+}
+
+fn parse_operation<'a>(
+    operand: fn(&'a str) -> IResult<&'a str, Operand>,
+    operators: &'static str,
+) -> impl Parser<&'a str, Output = Operand, Error = nom::error::Error<&'a str>> {
+    map(
+        pair(
+            operand,
+            many0(pair(
+                wsi(map(one_of(operators), |op| match op {
+                    '+' => ArithmeticOperator::Add,
+                    '-' => ArithmeticOperator::Subtract,
+                    '*' => ArithmeticOperator::Multiply,
+                    '/' => ArithmeticOperator::Divide,
+                    _ => ArithmeticOperator::Remainder,
+                })),
+                operand,
+            )),
+        ),
+        |(first, rest)| {
+            rest.into_iter().fold(first, |left, (op, right)| {
+                Operand::Arithmetic(Box::new(left), op, Box::new(right))
+            })
+        },
+    )
+}
+
+fn parse_term(input: &str) -> IResult<&str, Operand> {
+    parse_operation(parse_factor, "*/%").parse(input)
+}
+
+/// Parses an arithmetic expression, `*`, `/` and `%` take precedence over `+` and `-`.
+pub(crate) fn parse_operand(input: &str) -> IResult<&str, Operand> {
+    parse_operation(parse_term, "+-").parse(input)
+}
+
+#[test]
+fn test_parse_operand() {
+    assert_eq!(
+        parse_operand("1024 * 10"),
+        Ok((
+            "",
+            Operand::Arithmetic(
+                Box::new(Operand::Literal(1024.into())),
+                ArithmeticOperator::Multiply,
+                Box::new(Operand::Literal(10.into()))
+            )
+        ))
+    );
+    assert_eq!(
+        parse_operand("size - 2 * offset").unwrap().1.to_string(),
+        "size - (2 * offset)"
+    );
+    assert_eq!(
+        parse_operand("abs(value.delta)"),
+        Ok((
+            "",
+            Operand::Function(
+                Function::Abs,
+                Box::new(Operand::Field(Symbol::Value(Some(".delta".to_string()))))
+            )
+        ))
+    );
+    assert_eq!(parse_operand("0.5"), Ok(("", Operand::Literal(0.5.into()))));
+}
+
+#[test]
+fn test_evaluate() {
+    let record = KafkaRecord {
+        size: 2_048,
+        value: crate::DataType::Json(serde_json::json!({
+            "items": [1, 2, 3],
+            "type": "ORDER",
+            "delta": -0.75
+        })),
+        ..Default::default()
+    };
+    let evaluate = |input: &str| parse_operand(input).unwrap().1.evaluate(&record);
+
+    assert_eq!(evaluate("size / 2"), Value::from(1_024.0));
+    assert_eq!(evaluate("(size + 2) % 10"), Value::from(0));
+    assert_eq!(evaluate("len(value.items)"), Value::from(3));
+    assert_eq!(evaluate("lower(value.type)"), Value::from("order"));
+    assert_eq!(evaluate("abs(value.delta)"), Value::from(0.75));
+    assert_eq!(evaluate("value.missing * 2"), Value::Null);
+    assert_eq!(evaluate("size / 0"), Value::Null);
+}
+
+#[test]
+fn test_durations() {
+    assert_eq!(parse_duration("1h"), Ok(("", 3_600_000)));
+    assert_eq!(parse_duration("1.5s"), Ok(("", 1_500)));
+    assert_eq!(parse_duration("250ms"), Ok(("", 250)));
+    assert!(parse_duration("5min").is_err());
+    assert_eq!(Operand::Duration(90_000).to_string(), "90s");
+    assert_eq!(Operand::Duration(7_200_000).to_string(), "2h");
+
+    let record = KafkaRecord {
+        timestamp: Some(1_717_236_000_000),
+        value: crate::DataType::Json(serde_json::json!({
+            "processedAt": "2024-06-01T12:00:45+02:00"
+        })),
+        ..Default::default()
+    };
+    let evaluate = |input: &str| parse_operand(input).unwrap().1.evaluate(&record);
+    assert_eq!(
+        evaluate("value.processedAt - timestamp"),
+        Value::from(45_000)
+    );
+    assert_eq!(
+        parse_operand("now() - timestamp").unwrap().1.to_string(),
+        "now() - timestamp"
+    );
+}
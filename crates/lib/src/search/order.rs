@@ -64,11 +64,11 @@ pub(crate) fn parse_order(input: &str) -> IResult<&str, Order> {
 
     let o = match t.1 {
         Symbol::Offset => Order::Offset,
-        Symbol::Key => Order::Key,
+        Symbol::Key(_) => Order::Key,
         Symbol::Topic => Order::Topic,
         Symbol::Value(_) => Order::Value,
         Symbol::Partition => Order::Partition,
-        Symbol::Header(_) | Symbol::OffsetTail => unreachable!("nope"),
+        Symbol::Header(_) | Symbol::OffsetTail | Symbol::LogTimestamp => unreachable!("nope"),
         Symbol::Size => Order::Size,
         Symbol::Timestamp => Order::Timestamp,
     };
@@ -0,0 +1,119 @@
+//! Placeholders turn a search query into a template shared by a team, the values are given when the query is run.
+//! ```sql
+//! from begin key == :order_id and partition == :partition
+//! ```
+//! A placeholder is a `:` followed by a name made of letters, digits and `_`. Placeholders within strings are left untouched.
+//! Numbers are substituted as they are, any other value becomes a string.
+//! A string cannot contain both single and double quotes.
+
+use std::collections::HashMap;
+
+use crate::error::SearchError;
+use crate::search::string::quote;
+
+/// Splits the query into the text between placeholders and the placeholders, the text comes first.
+fn tokens(query: &str) -> Vec<(&str, Option<&str>)> {
+    let mut tokens = vec![];
+    let mut quote = None;
+    let mut start = 0;
+    let mut chars = query.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, ':') if chars.peek().is_some_and(|(_, n)| is_name(*n)) => {
+                let mut end = i + 1;
+                while let Some((j, n)) = chars.peek().copied().filter(|(_, n)| is_name(*n)) {
+                    end = j + n.len_utf8();
+                    chars.next();
+                }
+                tokens.push((&query[start..i], Some(&query[i + 1..end])));
+                start = end;
+            }
+            _ => (),
+        }
+    }
+    tokens.push((&query[start..], None));
+    tokens
+}
+
+fn is_name(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Lists the names of the placeholders of the query, in order of appearance and without duplicates.
+pub fn placeholders(query: &str) -> Vec<String> {
+    let mut names: Vec<String> = vec![];
+    for name in tokens(query).into_iter().filter_map(|(_, name)| name) {
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// Replaces the placeholders of the query with their value.
+pub fn bind_placeholders(
+    query: &str,
+    values: &HashMap<String, String>,
+) -> Result<String, SearchError> {
+    let mut bound = String::new();
+    for (text, name) in tokens(query) {
+        bound.push_str(text);
+        if let Some(name) = name {
+            let value = values
+                .get(name)
+                .ok_or_else(|| SearchError::MissingPlaceholder(name.to_string()))?;
+            let literal = literal(value)
+                .ok_or_else(|| SearchError::UnquotablePlaceholder(name.to_string()))?;
+            bound.push_str(&literal);
+        }
+    }
+    Ok(bound)
+}
+
+/// Numbers are written as they are, other values are quoted, see [`quote`].
+fn literal(value: &str) -> Option<String> {
+    let is_number = !value.is_empty()
+        && value.chars().all(|c| c.is_ascii_digit() || c == '_')
+        && value.starts_with(|c: char| c.is_ascii_digit());
+    match is_number {
+        true => Some(value.to_string()),
+        false => quote(value),
+    }
+}
+
+#[test]
+fn test_placeholders() {
+    assert_eq!(
+        placeholders(
+            r#"key == :order_id and value.note == ":ignored" or partition == :p and key != :order_id"#
+        ),
+        vec!["order_id", "p"]
+    );
+    assert_eq!(
+        placeholders("from begin key == 'a:b'"),
+        Vec::<String>::new()
+    );
+}
+
+#[test]
+fn test_bind_placeholders() {
+    let values = HashMap::from([
+        ("order_id".to_string(), "ord-42".to_string()),
+        ("p".to_string(), "3".to_string()),
+    ]);
+    assert_eq!(
+        bind_placeholders("key == :order_id and partition == :p limit 5", &values).unwrap(),
+        r#"key == "ord-42" and partition == 3 limit 5"#
+    );
+    assert!(matches!(
+        bind_placeholders("key == :tenant", &values),
+        Err(SearchError::MissingPlaceholder(name)) if name == "tenant"
+    ));
+    let values = HashMap::from([("note".to_string(), r#"it's "fine""#.to_string())]);
+    assert!(matches!(
+        bind_placeholders("value.note == :note", &values),
+        Err(SearchError::UnquotablePlaceholder(name)) if name == "note"
+    ));
+}
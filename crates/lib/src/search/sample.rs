@@ -0,0 +1,84 @@
+use std::fmt::Display;
+
+use nom::{
+    IResult, Parser,
+    branch::alt,
+    bytes::complete::tag_no_case,
+    character::complete::{char, digit1},
+    combinator::{map, map_res, not, opt, recognize, verify},
+    sequence::{pair, preceded, terminated},
+};
+
+use super::{number::parse_number, wsi::wsi};
+
+/// Keeps a random sample of the matching records instead of all of them.
+///
+/// ```sql
+/// sample 100
+/// sample 2.5%
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sample {
+    /// Keeps this number of records, picked at random among the matching records
+    Records(usize),
+    /// Keeps each matching record with this probability, between 0 and 100
+    Percentage(f64),
+}
+
+impl Display for Sample {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Sample::Records(n) => write!(f, "{n}"),
+            Sample::Percentage(p) => write!(f, "{p}%"),
+        }
+    }
+}
+
+/// Parses a percentage such as `10%` or `0.5%`.
+fn parse_percentage(input: &str) -> IResult<&str, f64> {
+    verify(
+        terminated(
+            map_res(
+                recognize(pair(digit1, opt(pair(char('.'), digit1)))),
+                |d: &str| d.parse::<f64>(),
+            ),
+            char('%'),
+        ),
+        |p| *p > 0.0 && *p <= 100.0,
+    )
+    .parse(input)
+}
+
+/// Parses the clause sampling the matching records.
+pub(crate) fn parse_sample(input: &str) -> IResult<&str, Sample> {
+    preceded(
+        wsi(tag_no_case("sample")),
+        wsi(alt((
+            map(parse_percentage, Sample::Percentage),
+            map(
+                terminated(verify(parse_number, |n| *n > 0), not(char('%'))),
+                Sample::Records,
+            ),
+        ))),
+    )
+    .parse(input)
+}
+
+#[test]
+fn test_parse_sample() {
+    assert_eq!(parse_sample("sample 100"), Ok(("", Sample::Records(100))));
+    assert_eq!(
+        parse_sample("sample 1_000"),
+        Ok(("", Sample::Records(1000)))
+    );
+    assert_eq!(
+        parse_sample("sample 10%"),
+        Ok(("", Sample::Percentage(10.0)))
+    );
+    assert_eq!(
+        parse_sample("SAMPLE 0.5%"),
+        Ok(("", Sample::Percentage(0.5)))
+    );
+    assert!(parse_sample("sample 0").is_err());
+    assert!(parse_sample("sample 150%").is_err());
+}
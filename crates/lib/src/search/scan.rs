@@ -0,0 +1,174 @@
+use std::{fmt::Display, str::FromStr};
+
+use nom::{
+    IResult, Parser,
+    branch::alt,
+    bytes::complete::tag_no_case,
+    character::complete::{alphanumeric1, space0},
+    combinator::{eof, map, not, opt, verify},
+    error::{Error, ErrorKind},
+    sequence::{pair, preceded, terminated},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::error::SearchError;
+
+use super::{number::parse_number, wsi::wsi};
+
+/// Byte units, the largest first. Decimal units are powers of 1000, binary units powers of 1024.
+const UNITS: [(&str, u64); 10] = [
+    ("TiB", 1 << 40),
+    ("TB", 1_000_000_000_000),
+    ("GiB", 1 << 30),
+    ("GB", 1_000_000_000),
+    ("MiB", 1 << 20),
+    ("MB", 1_000_000),
+    ("KiB", 1 << 10),
+    ("KB", 1_000),
+    ("B", 1),
+    ("bytes", 1),
+];
+
+/// Stops the consumer once it has read this volume of records, whether they match the query or not.
+/// It protects shared clusters from searches reading whole topics by mistake.
+///
+/// ```sql
+/// scan limit 2GB
+/// scan limit 500 MiB
+/// scan limit 1_000_000 records
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum ScanLimit {
+    /// Sum of the sizes of the keys and the values read
+    Bytes(u64),
+    /// Number of records read
+    Records(u64),
+}
+
+impl ScanLimit {
+    /// Returns `true` once `records` records weighing `bytes` bytes have been read.
+    pub fn is_reached(&self, records: u64, bytes: u64) -> bool {
+        match self {
+            Self::Bytes(limit) => bytes >= *limit,
+            Self::Records(limit) => records >= *limit,
+        }
+    }
+}
+
+impl Display for ScanLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Records(n) => write!(f, "{n} records"),
+            Self::Bytes(n) => {
+                let (unit, size) = UNITS.iter().find(|(_, size)| n % size == 0).unwrap();
+                write!(f, "{}{unit}", n / size)
+            }
+        }
+    }
+}
+
+impl FromStr for ScanLimit {
+    type Err = SearchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        terminated(parse_scan_limit_value, wsi(eof))
+            .parse(s)
+            .map(|(_, limit)| limit)
+            .map_err(|_| SearchError::Parse(s.to_string()))
+    }
+}
+
+impl TryFrom<String> for ScanLimit {
+    type Error = SearchError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<ScanLimit> for String {
+    fn from(value: ScanLimit) -> Self {
+        value.to_string()
+    }
+}
+
+/// Parses a unit, it must not be followed by a letter or a digit.
+fn parse_unit(input: &str) -> IResult<&str, u64> {
+    UNITS
+        .iter()
+        .find_map(|(unit, size)| {
+            terminated(tag_no_case::<_, _, Error<&str>>(*unit), not(alphanumeric1))
+                .parse(input)
+                .ok()
+                .map(|(rest, _)| (rest, *size))
+        })
+        .ok_or_else(|| nom::Err::Error(Error::new(input, ErrorKind::Tag)))
+}
+
+/// Parses a number of records or a volume of bytes: `10GB`, `500 MiB`, `1000 records`.
+/// A number without unit is a number of records.
+fn parse_scan_limit_value(input: &str) -> IResult<&str, ScanLimit> {
+    verify(
+        map(
+            pair(
+                parse_number::<u64>,
+                opt(preceded(
+                    space0,
+                    alt((
+                        map(parse_unit, Some),
+                        map(alt((tag_no_case("records"), tag_no_case("record"))), |_| {
+                            None
+                        }),
+                    )),
+                )),
+            ),
+            |(n, unit)| match unit.flatten() {
+                Some(size) => ScanLimit::Bytes(n.saturating_mul(size)),
+                None => ScanLimit::Records(n),
+            },
+        ),
+        |limit| !matches!(limit, ScanLimit::Bytes(0) | ScanLimit::Records(0)),
+    )
+    .parse(input)
+}
+
+/// Parses the clause limiting the volume of records read by the consumer.
+pub(crate) fn parse_scan_limit(input: &str) -> IResult<&str, ScanLimit> {
+    preceded(
+        pair(wsi(tag_no_case("scan")), wsi(tag_no_case("limit"))),
+        wsi(parse_scan_limit_value),
+    )
+    .parse(input)
+}
+
+#[test]
+fn test_parse_scan_limit() {
+    assert_eq!(
+        parse_scan_limit("scan limit 2GB"),
+        Ok(("", ScanLimit::Bytes(2_000_000_000)))
+    );
+    assert_eq!(
+        parse_scan_limit("SCAN LIMIT 500 MiB"),
+        Ok(("", ScanLimit::Bytes(500 << 20)))
+    );
+    assert_eq!(
+        parse_scan_limit("scan limit 1_000 records"),
+        Ok(("", ScanLimit::Records(1_000)))
+    );
+    assert_eq!(
+        parse_scan_limit("scan limit 10 limit 5"),
+        Ok((" limit 5", ScanLimit::Records(10)))
+    );
+    assert!(parse_scan_limit("scan limit 0 records").is_err());
+    assert!("3KBx".parse::<ScanLimit>().is_err());
+}
+
+#[test]
+fn test_display_scan_limit() {
+    for limit in ["2GB", "512MiB", "1500B", "42 records"] {
+        assert_eq!(limit.parse::<ScanLimit>().unwrap().to_string(), limit);
+    }
+    assert!(ScanLimit::Bytes(1_000).is_reached(3, 1_200));
+    assert!(!ScanLimit::Records(10).is_reached(9, 1_200));
+}
@@ -1,6 +1,6 @@
 use itertools::Itertools;
 use nom::{
-    Parser,
+    IResult, Parser,
     branch::alt,
     combinator::{eof, map},
     multi::many_till,
@@ -9,28 +9,74 @@ use nom::{
 use crate::error::SearchError;
 
 use super::{
+    atom::Atom,
     clause::{
-        SearchClause, parse_expression, parse_from_offset_clause, parse_limit, parse_order_by,
+        SearchClause, parse_distinct_clause, parse_expression, parse_from_offset_clause,
+        parse_limit, parse_order_by, parse_sample_clause, parse_scan_limit_clause, parse_select,
+        parse_with,
     },
-    expression::Expression,
+    distinct::Distinct,
+    expression::{AndExpression, Expression, OrExpression},
     offset::FromOffset,
     order::{Order, OrderBy, OrderKeyword},
+    sample::Sample,
+    scan::ScanLimit,
+    string::quote,
+    symbol::Symbol,
+    term::Term,
     wsi::wsi,
 };
 
-/// A `SearchQuery` is a combination of an expression, a limit, an offset and an order by clause.
+/// A `SearchQuery` is a combination of an expression, a limit, a scan limit, an offset, an order by clause, a sample, a distinct and a select clause.
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct SearchQuery {
+    /// Condition the records must match
     pub expression: Expression,
+    /// Maximum number of matching records
     pub limit: Option<usize>,
+    /// Volume of records read after which the consumer stops, whether they match or not
+    pub scan_limit: Option<ScanLimit>,
+    /// Offset from which the topics are read, from their end by default
     pub from: Option<FromOffset>,
+    /// How the matching records are sorted
     pub order_by: OrderBy,
+    /// Random sample of the matching records to keep
+    pub sample: Option<Sample>,
+    /// Keeps only the latest matching record for each key or field
+    pub distinct: Option<Distinct>,
+    /// Kafka consumer properties overridden for this search only
+    pub consumer_properties: Vec<(String, String)>,
+    /// Fields written to the export files, they get their own column when the records are exported to a SQLite database
+    pub select: Vec<Symbol>,
     //pub group_by_key: bool,
 }
 
 impl SearchQuery {
+    /// Returns `true` if the query has no clause, every record matches it.
     pub fn is_empty(&self) -> bool {
-        self.limit.is_none() && self.from.is_none() && self.expression.is_empty()
+        self.limit.is_none()
+            && self.scan_limit.is_none()
+            && self.from.is_none()
+            && self.sample.is_none()
+            && self.distinct.is_none()
+            && self.expression.is_empty()
+    }
+
+    /// Restricts the query to the records also matching the predicate, for instance to add a tenant restriction.
+    /// Both expressions are wrapped in parentheses so the precedence of `or` is preserved.
+    pub fn and(mut self, predicate: Expression) -> Self {
+        if predicate.is_empty() {
+            return self;
+        }
+        self.expression = match self.expression.is_empty() {
+            true => predicate,
+            false => OrExpression::OrTerm(AndExpression::AndExpression(vec![
+                Term::Atom(Atom::Parenthesis(Box::new(self.expression))),
+                Term::Atom(Atom::Parenthesis(Box::new(predicate))),
+            ])),
+        };
+        self
     }
 }
 
@@ -49,7 +95,32 @@ impl std::fmt::Display for SearchQuery {
         clauses.push(from.to_string());
         clauses.push(format!("{}", self.expression));
         clauses.push(format!("{}", self.order_by));
+        if let Some(distinct) = &self.distinct {
+            clauses.push(format!("distinct by {distinct}"));
+        }
+        if !self.select.is_empty() {
+            clauses.push(format!(
+                "select {}",
+                self.select.iter().map(Symbol::field).join(", ")
+            ));
+        }
         clauses.push(limit.to_string());
+        if let Some(scan_limit) = self.scan_limit {
+            clauses.push(format!("scan limit {scan_limit}"));
+        }
+        if let Some(sample) = self.sample {
+            clauses.push(format!("sample {sample}"));
+        }
+        if !self.consumer_properties.is_empty() {
+            clauses.push(format!(
+                "with ({})",
+                self.consumer_properties
+                    .iter()
+                    // A value with both quotes cannot be quoted, it is written as is
+                    .map(|(k, v)| format!("{k} = {}", quote(v).unwrap_or_else(|| v.clone())))
+                    .join(", ")
+            ));
+        }
         let clauses = clauses.into_iter().filter(|e| !e.is_empty()).collect_vec();
         write!(f, "{}", clauses.join(" "))
     }
@@ -60,39 +131,65 @@ impl Default for SearchQuery {
         Self {
             expression: Expression::OrExpression(vec![]),
             limit: None,
+            scan_limit: None,
             from: None,
             order_by: OrderBy::new(Order::Timestamp, OrderKeyword::Asc),
+            sample: None,
+            distinct: None,
+            consumer_properties: vec![],
+            select: vec![],
             //group_by_key: false,
         }
     }
 }
 
+/// Parses one clause of a search query, shared by [`parse_search_query`] and [`append_predicate`].
+fn parse_clause(input: &str) -> IResult<&str, SearchClause> {
+    alt((
+        parse_from_offset_clause,
+        parse_limit,
+        parse_scan_limit_clause,
+        parse_sample_clause,
+        parse_distinct_clause,
+        parse_select,
+        parse_with,
+        parse_expression,
+        parse_order_by,
+    ))
+    .parse(input)
+}
+
+/// Parses a search query, see the grammar in [`crate::search`].
+/// The remaining input is empty when the whole query has been parsed.
+///
+/// ```
+/// use yozefu_lib::parse_search_query;
+///
+/// let (_, query) = parse_search_query("from begin offset > 42 limit 10").unwrap();
+/// assert_eq!(query.limit, Some(10));
+/// assert!(parse_search_query("offset >").is_err());
+/// ```
 pub fn parse_search_query(input: &str) -> Result<(&str, SearchQuery), SearchError> {
-    map(
-        many_till(
-            alt((
-                parse_from_offset_clause,
-                parse_limit,
-                parse_expression,
-                parse_order_by,
-            )),
-            wsi(eof),
-        ),
-        |clauses| {
-            let mut s = SearchQuery::default();
-            for c in clauses.0 {
-                match c {
-                    SearchClause::Limit(i) => s.limit = Some(i),
-                    SearchClause::From(f) => s.from = Some(f),
-                    SearchClause::Expression(u) => s.expression = u,
-                    SearchClause::OrderBy(order, k) => {
-                        s.order_by = OrderBy::new(order, k.unwrap_or(OrderKeyword::Asc));
-                    } //SearchClause::GroupByKey => s.group_by_key = true,
+    map(many_till(parse_clause, wsi(eof)), |clauses| {
+        let mut s = SearchQuery::default();
+        for c in clauses.0 {
+            match c {
+                SearchClause::Limit(i) => s.limit = Some(i),
+                SearchClause::ScanLimit(limit) => s.scan_limit = Some(limit),
+                SearchClause::From(f) => s.from = Some(f),
+                SearchClause::Expression(u) => s.expression = u,
+                SearchClause::OrderBy(order, k) => {
+                    s.order_by = OrderBy::new(order, k.unwrap_or(OrderKeyword::Asc));
                 }
+                SearchClause::With(properties) => s.consumer_properties.extend(properties),
+                SearchClause::Sample(sample) => s.sample = Some(sample),
+                SearchClause::Distinct(distinct) => s.distinct = Some(distinct),
+                SearchClause::Select(fields) => s.select = fields,
+                //SearchClause::GroupByKey => s.group_by_key = true,
             }
-            s
-        },
-    )
+        }
+        s
+    })
     .parse(input)
     .map_err(|e| {
         let remaining = match e {
@@ -104,6 +201,61 @@ pub fn parse_search_query(input: &str) -> Result<(&str, SearchQuery), SearchErro
     })
 }
 
+/// Appends a predicate to the expression of the search query with `and`, the other clauses are kept as they are.
+/// ```sql
+/// from begin key == "1" or key == "2" limit 10
+/// -- becomes with the predicate `offset > 5`
+/// from begin (key == "1" or key == "2") and offset > 5 limit 10
+/// ```
+pub fn append_predicate(input: &str, predicate: &str) -> Result<String, SearchError> {
+    let mut clauses = vec![];
+    let mut expression = None;
+    let mut after_from = 0;
+    let mut remaining = input;
+    while wsi(eof::<&str, nom::error::Error<&str>>)
+        .parse(remaining)
+        .is_err()
+    {
+        let (rest, clause) =
+            parse_clause(remaining).map_err(|_| SearchError::Parse(remaining.to_string()))?;
+        if rest.len() == remaining.len() {
+            return Err(SearchError::Parse(remaining.to_string()));
+        }
+        let text = remaining[..remaining.len() - rest.len()].trim();
+        remaining = rest;
+        match clause {
+            SearchClause::Expression(e) if e.is_empty() => (),
+            SearchClause::Expression(e) => {
+                let text = match text.get(..5) {
+                    Some(keyword) if keyword.eq_ignore_ascii_case("where") => text[5..].trim(),
+                    _ => text,
+                };
+                let text = match e {
+                    Expression::OrExpression(_) => format!("({text}) and {predicate}"),
+                    Expression::OrTerm(_) => format!("{text} and {predicate}"),
+                };
+                // Like `parse_search_query`, the last expression wins
+                match expression {
+                    Some(index) => clauses[index] = text,
+                    None => {
+                        expression = Some(clauses.len());
+                        clauses.push(text);
+                    }
+                }
+            }
+            SearchClause::From(_) => {
+                clauses.push(text.to_string());
+                after_from = clauses.len();
+            }
+            _ => clauses.push(text.to_string()),
+        }
+    }
+    if expression.is_none() {
+        clauses.insert(after_from, predicate.to_string());
+    }
+    Ok(clauses.join(" "))
+}
+
 #[test]
 fn test_parse_search_query() {
     assert!(parse_search_query(r#"   from end - 10"#).is_ok());
@@ -113,3 +265,104 @@ fn test_parse_search_query() {
 fn test_parse_search_query_with_json_path() {
     assert!(parse_search_query(r#"from end - 10 value.sequenceNum == "115568969""#).is_ok());
 }
+
+#[test]
+fn test_parse_search_query_with_consumer_properties() {
+    let query = parse_search_query(
+        r#"from begin with (isolation.level = "read_committed") key == "abc" limit 10"#,
+    )
+    .unwrap()
+    .1;
+    assert_eq!(
+        query.consumer_properties,
+        vec![("isolation.level".to_string(), "read_committed".to_string())]
+    );
+    assert_eq!(query.limit, Some(10));
+    assert!(
+        query
+            .to_string()
+            .ends_with(r#"with (isolation.level = "read_committed")"#)
+    );
+}
+
+#[test]
+fn test_display_consumer_properties_with_quotes() {
+    let query =
+        parse_search_query(r#"with (client.rack = 'eu "west"', group.id = "ops") offset > 10"#)
+            .unwrap()
+            .1;
+    let parsed = parse_search_query(&query.to_string()).unwrap().1;
+    assert_eq!(parsed.consumer_properties, query.consumer_properties);
+    assert_eq!(
+        query.consumer_properties[0],
+        ("client.rack".to_string(), r#"eu "west""#.to_string())
+    );
+}
+
+#[test]
+fn test_parse_search_query_with_sample() {
+    let query = parse_search_query(r#"from begin key == "abc" sample 5% limit 1000"#)
+        .unwrap()
+        .1;
+    assert_eq!(query.sample, Some(Sample::Percentage(5.0)));
+    assert_eq!(query.limit, Some(1000));
+    assert!(query.to_string().ends_with("limit 1000 sample 5%"));
+}
+
+#[test]
+fn test_parse_search_query_with_distinct() {
+    let query = parse_search_query(r#"from begin distinct by value.customer.id key == "abc""#)
+        .unwrap()
+        .1;
+    assert_eq!(
+        query.distinct,
+        Some(Distinct::Value(Some(".customer.id".to_string())))
+    );
+    assert!(query.to_string().ends_with("distinct by value.customer.id"));
+}
+
+#[test]
+fn test_parse_search_query_with_select() {
+    let query =
+        parse_search_query(r#"from begin key == "abc" select value.amount, headers.tenant"#)
+            .unwrap()
+            .1;
+    assert_eq!(
+        query.select,
+        vec![
+            Symbol::Value(Some(".amount".to_string())),
+            Symbol::Header("tenant".to_string())
+        ]
+    );
+    assert!(
+        query
+            .to_string()
+            .ends_with("select value.amount, headers.tenant")
+    );
+}
+
+#[test]
+fn test_append_predicate() {
+    assert_eq!(
+        append_predicate("", r#"key == "abc""#).unwrap(),
+        r#"key == "abc""#
+    );
+    assert_eq!(
+        append_predicate("from end - 10 limit 5", r#"key == "abc""#).unwrap(),
+        r#"from end - 10 key == "abc" limit 5"#
+    );
+    assert_eq!(
+        append_predicate(r#"where offset > 10"#, r#"key == "abc""#).unwrap(),
+        r#"offset > 10 and key == "abc""#
+    );
+    let query = append_predicate(
+        r#"from begin offset > 10 || partition == 2 order by key desc"#,
+        r#"headers.trace == "1""#,
+    )
+    .unwrap();
+    assert_eq!(
+        query,
+        r#"from begin (offset > 10 || partition == 2) and headers.trace == "1" order by key desc"#
+    );
+    assert!(parse_search_query(&query).is_ok());
+}
@@ -22,8 +22,27 @@ pub(crate) fn parse_string(input: &str) -> IResult<&str, String> {
     .parse(input)
 }
 
+/// Quotes a string of the search query, `None` when it contains both double and single quotes:
+/// strings cannot escape their delimiter.
+pub fn quote(value: &str) -> Option<String> {
+    if !value.contains('"') {
+        return Some(format!(r#""{value}""#));
+    }
+    if !value.contains('\'') {
+        return Some(format!("'{value}'"));
+    }
+    None
+}
+
 #[test]
 fn test_parse_string() {
     assert_eq!(parse_string(r#"'halo'"#), Ok(("", "halo".to_string())));
     assert_eq!(parse_string(r#""hola""#), Ok(("", "hola".to_string())));
 }
+
+#[test]
+fn test_quote() {
+    assert_eq!(quote("halo"), Some(r#""halo""#.to_string()));
+    assert_eq!(quote(r#"say "hola""#), Some(r#"'say "hola"'"#.to_string()));
+    assert_eq!(quote(r#"it's "hola""#), None);
+}
@@ -4,12 +4,17 @@ use nom::{
     IResult, Parser,
     branch::alt,
     bytes::complete::{tag, tag_no_case, take_while},
-    combinator::{map, opt, recognize, value},
+    character::complete::{alphanumeric1, one_of},
+    combinator::{map, opt, recognize, value, verify},
     error::ErrorKind,
+    multi::{many0, many1},
     sequence::preceded,
 };
+use serde_json::Value;
 use strum::Display;
 
+use crate::{DataType, KafkaRecord, TimestampType, error::SearchError, kafka::to_json_pointer};
+
 use super::wsi::wsi;
 
 #[derive(Debug, Display, PartialEq, Eq, Clone)]
@@ -18,13 +23,103 @@ pub enum Symbol {
     Topic,
     Partition,
     OffsetTail,
-    Key,
+    Key(Option<String>),
     Size,
     Timestamp,
+    /// The timestamp set by the broker, only for topics whose timestamps are `LogAppendTime`
+    LogTimestamp,
     Value(Option<String>),
     Header(String),
 }
 
+impl Symbol {
+    /// Parses a field of a record written in full: `key`, `topic`, `value.user.id`, `headers.tenant`...
+    pub fn parse_field(input: &str) -> Result<Self, SearchError> {
+        match parse_field(input.trim()) {
+            Ok(("", symbol)) => Ok(symbol),
+            Ok((remaining, _)) => Err(SearchError::Parse(remaining.to_string())),
+            Err(_) => Err(SearchError::Parse(input.to_string())),
+        }
+    }
+
+    /// The field written in full, as [`Symbol::parse_field`] parses it.
+    pub fn field(&self) -> String {
+        match self {
+            Symbol::Offset => "offset".to_string(),
+            Symbol::Topic => "topic".to_string(),
+            Symbol::Partition => "partition".to_string(),
+            Symbol::OffsetTail => "offset_tail".to_string(),
+            Symbol::Key(path) => format!("key{}", path.as_deref().unwrap_or_default()),
+            Symbol::Size => "size".to_string(),
+            Symbol::Timestamp => "timestamp".to_string(),
+            Symbol::LogTimestamp => "log_timestamp".to_string(),
+            Symbol::Value(path) => format!("value{}", path.as_deref().unwrap_or_default()),
+            Symbol::Header(name) => format!("headers.{name}"),
+        }
+    }
+
+    /// The value of the field for the record, `null` when the record has no such field.
+    pub fn value_of(&self, record: &KafkaRecord) -> Value {
+        match self {
+            Symbol::Offset => record.offset.into(),
+            Symbol::Topic => record.topic.clone().into(),
+            Symbol::Partition => record.partition.into(),
+            Symbol::OffsetTail => Value::Null,
+            Symbol::Key(None) => record.key.clone().into(),
+            Symbol::Key(Some(path)) => Self::pointer(&record.key, path),
+            Symbol::Size => record.size.into(),
+            Symbol::Timestamp => record.timestamp.into(),
+            Symbol::LogTimestamp => match record.timestamp_type {
+                Some(TimestampType::LogAppendTime) => record.timestamp.into(),
+                _ => Value::Null,
+            },
+            Symbol::Value(None) => record.value.clone().into(),
+            Symbol::Value(Some(path)) => Self::pointer(&record.value, path),
+            Symbol::Header(name) => record.headers.get(name).cloned().into(),
+        }
+    }
+
+    fn pointer(data: &DataType, path: &str) -> Value {
+        match data {
+            DataType::Json(value) => value
+                .pointer(&to_json_pointer(path))
+                .cloned()
+                .unwrap_or_default(),
+            DataType::String(_) => Value::Null,
+        }
+    }
+}
+
+/// Fields of the record are written in full, `headers` is followed by a name and `key` and `value` by an optional path: `value.user.id`, `key.tenantId`, `headers.tenant`.
+pub(crate) fn parse_field(input: &str) -> IResult<&str, Symbol> {
+    let path_characters = || alt((alphanumeric1, recognize(one_of("_-."))));
+    let json_path = || {
+        opt(recognize((
+            one_of(".["),
+            many0(alt((path_characters(), recognize(one_of("[]"))))),
+        )))
+    };
+    alt((
+        map(
+            preceded(tag("headers."), recognize(many1(path_characters()))),
+            |name: &str| Symbol::Header(name.to_string()),
+        ),
+        map(preceded(tag("value"), json_path()), |path: Option<&str>| {
+            Symbol::Value(path.map(str::to_string))
+        }),
+        map(preceded(tag("key"), json_path()), |path: Option<&str>| {
+            Symbol::Key(path.map(str::to_string))
+        }),
+        value(Symbol::Topic, tag("topic")),
+        value(Symbol::Partition, tag("partition")),
+        value(Symbol::Offset, tag("offset")),
+        value(Symbol::Timestamp, tag("timestamp")),
+        value(Symbol::LogTimestamp, tag("log_timestamp")),
+        value(Symbol::Size, tag("size")),
+    ))
+    .parse(input)
+}
+
 // pub(crate) fn parse_symbol(input: &str) -> IResult<&str, Symbol> {
 //     alt((
 //         parse_offset,
@@ -61,13 +156,36 @@ pub(crate) fn parse_topic(input: &str) -> IResult<&str, Symbol> {
 }
 
 pub(crate) fn parse_key(input: &str) -> IResult<&str, Symbol> {
-    value(Symbol::Key, wsi(alt((tag("key"), tag("k"))))).parse(input)
+    value(Symbol::Key(None), wsi(alt((tag("key"), tag("k"))))).parse(input)
+}
+
+/// Parses the key followed by an optional JSON path, `key.tenantId`.
+/// Unlike values, the path must start with `.` or `[` so that `key=="abc"` is still a comparison of the whole key.
+pub(crate) fn parse_key_symbol(input: &str) -> IResult<&str, (Symbol, Option<String>)> {
+    map(
+        preceded(
+            wsi(alt((tag("key"), tag("k")))),
+            opt(verify(parse_json_path, |path: &String| {
+                path.starts_with(['.', '['])
+            })),
+        ),
+        |json_path| (Symbol::Key(json_path.clone()), json_path),
+    )
+    .parse(input)
 }
 
 pub(crate) fn parse_timestamp_symbol(input: &str) -> IResult<&str, Symbol> {
     value(Symbol::Timestamp, wsi(alt((tag("timestamp"), tag("ts"))))).parse(input)
 }
 
+pub(crate) fn parse_log_timestamp_symbol(input: &str) -> IResult<&str, Symbol> {
+    value(
+        Symbol::LogTimestamp,
+        wsi(alt((tag("log_timestamp"), tag("log_ts")))),
+    )
+    .parse(input)
+}
+
 pub(crate) fn parse_value_symbol(input: &str) -> IResult<&str, (Symbol, Option<String>)> {
     map(
         preceded(wsi(alt((tag("value"), tag("v")))), opt(parse_json_path)),
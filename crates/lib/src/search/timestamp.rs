@@ -31,9 +31,30 @@ pub(crate) fn parse_timestamp(input: &str) -> IResult<&str, DateTime<Local>> {
     .parse(input)
 }
 
+/// Parses a timestamp given outside of a search query, such as a command line argument, the quotes are optional.
+pub fn parse_timestamp_argument(input: &str) -> Option<DateTime<Local>> {
+    let input = input.trim();
+    let quoted = match input.starts_with(['"', '\'']) {
+        true => input.to_string(),
+        false => format!("'{input}'"),
+    };
+    match parse_timestamp(&quoted) {
+        Ok(("", timestamp)) => Some(timestamp),
+        _ => None,
+    }
+}
+
 #[test]
 fn test_parse_timestamp() {
     assert!(parse_timestamp(r#"'3 hours ago'"#).is_ok());
     assert!(parse_timestamp(r#"now"#).is_ok());
     assert!(parse_timestamp(r#""2024-09-17T06:44:59Z""#).is_ok());
 }
+
+#[test]
+fn test_parse_timestamp_argument() {
+    assert!(parse_timestamp_argument("3 hours ago").is_some());
+    assert!(parse_timestamp_argument("2024-09-17T06:44:59Z").is_some());
+    assert!(parse_timestamp_argument("'2024-09-17T06:44:59Z'").is_some());
+    assert!(parse_timestamp_argument("not a date").is_none());
+}
@@ -0,0 +1,209 @@
+//! Walks the abstract syntax tree of a search query, to inspect or rewrite it before it is executed.
+//!
+//! Each method of [`Visitor`] and [`VisitorMut`] visits the children of the node by default,
+//! override the methods of the nodes you are interested in and call the `walk_*` functions to keep visiting their children.
+//!
+//! ```
+//! use yozefu_lib::{
+//!     parse_search_query,
+//!     search::{compare::CompareExpression, visitor::Visitor},
+//! };
+//!
+//! /// Collects the topics the query is restricted to.
+//! struct Topics(Vec<String>);
+//!
+//! impl Visitor for Topics {
+//!     fn visit_compare(&mut self, compare: &CompareExpression) {
+//!         if let CompareExpression::Topic(_, topic) = compare {
+//!             self.0.push(topic.clone());
+//!         }
+//!     }
+//! }
+//!
+//! let query = parse_search_query(r#"topic == "payments" or (offset > 5 and topic == "orders")"#).unwrap().1;
+//! let mut topics = Topics(vec![]);
+//! topics.visit_query(&query);
+//! assert_eq!(topics.0, vec!["payments", "orders"]);
+//! ```
+
+use super::{
+    SearchQuery,
+    atom::Atom,
+    compare::CompareExpression,
+    expression::{AndExpression, Expression, OrExpression},
+    filter::Filter,
+    symbol::Symbol,
+    term::Term,
+};
+
+/// Visits the nodes of a search query.
+pub trait Visitor {
+    fn visit_query(&mut self, query: &SearchQuery) {
+        self.visit_expression(&query.expression);
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) {
+        walk_expression(self, expression);
+    }
+
+    fn visit_and_expression(&mut self, expression: &AndExpression) {
+        walk_and_expression(self, expression);
+    }
+
+    fn visit_term(&mut self, term: &Term) {
+        walk_term(self, term);
+    }
+
+    fn visit_atom(&mut self, atom: &Atom) {
+        walk_atom(self, atom);
+    }
+
+    fn visit_compare(&mut self, _compare: &CompareExpression) {}
+
+    fn visit_filter(&mut self, _filter: &Filter) {}
+
+    fn visit_symbol(&mut self, _symbol: &Symbol) {}
+}
+
+/// Visits the `or` operands of the expression.
+pub fn walk_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &Expression) {
+    match expression {
+        OrExpression::OrTerm(e) => visitor.visit_and_expression(e),
+        OrExpression::OrExpression(v) => v.iter().for_each(|e| visitor.visit_and_expression(e)),
+    }
+}
+
+/// Visits the `and` operands of the expression.
+pub fn walk_and_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &AndExpression) {
+    match expression {
+        AndExpression::AndTerm(t) => visitor.visit_term(t),
+        AndExpression::AndExpression(v) => v.iter().for_each(|t| visitor.visit_term(t)),
+    }
+}
+
+/// Visits the atom of the term, negated or not.
+pub fn walk_term<V: Visitor + ?Sized>(visitor: &mut V, term: &Term) {
+    match term {
+        Term::Not(a) | Term::Atom(a) => visitor.visit_atom(a),
+    }
+}
+
+/// Visits the comparison, the filter, the symbol or the expression between parentheses.
+pub fn walk_atom<V: Visitor + ?Sized>(visitor: &mut V, atom: &Atom) {
+    match atom {
+        Atom::Symbol(s) => visitor.visit_symbol(s),
+        Atom::Compare(c) => visitor.visit_compare(c),
+        Atom::Filter(f) => visitor.visit_filter(f),
+        Atom::Parenthesis(e) => visitor.visit_expression(e),
+    }
+}
+
+/// Visits and rewrites the nodes of a search query.
+pub trait VisitorMut {
+    fn visit_query_mut(&mut self, query: &mut SearchQuery) {
+        self.visit_expression_mut(&mut query.expression);
+    }
+
+    fn visit_expression_mut(&mut self, expression: &mut Expression) {
+        walk_expression_mut(self, expression);
+    }
+
+    fn visit_and_expression_mut(&mut self, expression: &mut AndExpression) {
+        walk_and_expression_mut(self, expression);
+    }
+
+    fn visit_term_mut(&mut self, term: &mut Term) {
+        walk_term_mut(self, term);
+    }
+
+    fn visit_atom_mut(&mut self, atom: &mut Atom) {
+        walk_atom_mut(self, atom);
+    }
+
+    fn visit_compare_mut(&mut self, _compare: &mut CompareExpression) {}
+
+    fn visit_filter_mut(&mut self, _filter: &mut Filter) {}
+
+    fn visit_symbol_mut(&mut self, _symbol: &mut Symbol) {}
+}
+
+/// Visits the `or` operands of the expression.
+pub fn walk_expression_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expression: &mut Expression) {
+    match expression {
+        OrExpression::OrTerm(e) => visitor.visit_and_expression_mut(e),
+        OrExpression::OrExpression(v) => v
+            .iter_mut()
+            .for_each(|e| visitor.visit_and_expression_mut(e)),
+    }
+}
+
+/// Visits the `and` operands of the expression.
+pub fn walk_and_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut AndExpression,
+) {
+    match expression {
+        AndExpression::AndTerm(t) => visitor.visit_term_mut(t),
+        AndExpression::AndExpression(v) => v.iter_mut().for_each(|t| visitor.visit_term_mut(t)),
+    }
+}
+
+/// Visits the atom of the term, negated or not.
+pub fn walk_term_mut<V: VisitorMut + ?Sized>(visitor: &mut V, term: &mut Term) {
+    match term {
+        Term::Not(a) | Term::Atom(a) => visitor.visit_atom_mut(a),
+    }
+}
+
+/// Visits the comparison, the filter, the symbol or the expression between parentheses.
+pub fn walk_atom_mut<V: VisitorMut + ?Sized>(visitor: &mut V, atom: &mut Atom) {
+    match atom {
+        Atom::Symbol(s) => visitor.visit_symbol_mut(s),
+        Atom::Compare(c) => visitor.visit_compare_mut(c),
+        Atom::Filter(f) => visitor.visit_filter_mut(f),
+        Atom::Parenthesis(e) => visitor.visit_expression_mut(e),
+    }
+}
+
+#[test]
+fn test_visitor_mut() {
+    use super::parse_search_query;
+
+    /// Prefixes the topics with the name of the tenant.
+    struct Tenant;
+
+    impl VisitorMut for Tenant {
+        fn visit_compare_mut(&mut self, compare: &mut CompareExpression) {
+            if let CompareExpression::Topic(_, topic) = compare {
+                topic.insert_str(0, "tenant-");
+            }
+        }
+    }
+
+    let mut query = parse_search_query(r#"!(topic == "payments") or offset > 5"#)
+        .unwrap()
+        .1;
+    Tenant.visit_query_mut(&mut query);
+    let expected = parse_search_query(r#"!(topic == "tenant-payments") or offset > 5"#)
+        .unwrap()
+        .1;
+    assert_eq!(query, expected);
+
+    let restriction = parse_search_query(r#"headers.tenant == "acme""#)
+        .unwrap()
+        .1
+        .expression;
+    let query = query.and(restriction);
+    let expected = parse_search_query(
+        r#"(!(topic == "tenant-payments") or offset > 5) and (headers.tenant == "acme")"#,
+    )
+    .unwrap()
+    .1;
+    assert_eq!(query, expected);
+    assert_eq!(
+        SearchQuery::default()
+            .and(expected.expression.clone())
+            .expression,
+        expected.expression
+    );
+}
@@ -0,0 +1,4 @@
+from end - 1_000
+with (fetch.max.bytes = 10485760, isolation.level = "read_committed")
+key == "order-42"
+limit 10
@@ -0,0 +1,3 @@
+from begin
+value.customer.id == "c-123"
+scan limit 500 MiB
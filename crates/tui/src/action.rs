@@ -1,9 +1,22 @@
-use app::search::ValidSearchQuery;
-use std::collections::HashSet;
+use app::{
+    Alert, BrokerConfigEntry, ClientQuota, ConsumerError, ConsumerStatistics, HeaderEdit,
+    LatencySample, Notes, QueryPlan, RecordMapping, configuration::LayoutPreset,
+    search::ValidSearchQuery,
+};
+use crossterm::event::KeyEvent;
+use ratatui::text::Line;
+use std::{collections::HashSet, num::NonZeroU32, path::PathBuf};
 
-use lib::{KafkaRecord, TopicDetail, kafka::SchemaId, search::OrderBy};
+use lib::{
+    ConsumerGroupOverview, CountMethod, KafkaRecord, TopicDetail, TopicPreview, kafka::SchemaId,
+    search::OrderBy,
+};
 
-use crate::schema_detail::SchemaDetail;
+use crate::{
+    Theme,
+    report::ReportFormat,
+    schema_detail::{SchemaDetail, SchemaVersions},
+};
 
 use super::component::{ComponentName, Shortcut};
 
@@ -19,14 +32,48 @@ pub(crate) enum Action {
     Quit,
     /// Request the app to export the given record into the file
     Export(KafkaRecord),
+    /// Request the app to export all the records of the current search into the file
+    ExportAll,
+    /// Request the app to start or stop exporting the records as they match the search query
+    ToggleLiveExport,
+    /// Request the app to produce the records of the file to the selected topic
+    Produce(ProduceRequest),
+    /// Request the app to republish the record of a dead letter queue to its original topic, once its headers are edited.
+    /// The last field is the confirmation typed by the user, the name of the cluster on protected clusters
+    Republish(KafkaRecord, Vec<HeaderEdit>, String),
+    /// Request the app to start or stop probing the end-to-end latency of the selected topic.
+    /// The field is the confirmation typed by the user to start it, the name of the cluster on protected clusters
+    ToggleLatencyProbe(String),
+    /// Notify the UI the topic probed by the latency probe, `None` when the probe is stopped
+    LatencyProbe(Option<String>),
+    /// Notify the UI a new end-to-end latency sample
+    LatencySample(LatencySample),
     /// Dispatch the new shortcuts to the UI
     Shortcuts(Vec<Shortcut>, bool),
+    /// Notify the components the component that has the focus
+    Focused(ComponentName),
     /// Request the UI to show a new notification
     Notification(Notification),
+    /// Describe the newly selected item in the status line, for screen readers
+    Announce(String),
     /// Request the UI to start searching for kafka records
     Search(ValidSearchQuery),
     ///  notification to the UI
     ShowRecord(KafkaRecord),
+    /// Request the app to read again the given record, its value was truncated in the list of records
+    RequestFullRecord(KafkaRecord),
+    /// Notify the UI the record read again with its full value
+    FullRecord(KafkaRecord),
+    /// Notify the record details the next lines of a value highlighted in the background
+    HighlightedLines(ValueId, Vec<Line<'static>>),
+    /// Request the records list to select the next record with the key of the given record, the previous one when `false`
+    RecordWithSameKey(KafkaRecord, bool),
+    /// Request the app to read the record at the next offset of the partition of the given record, the previous offset when `false`
+    RequestAdjacentRecord(KafkaRecord, bool),
+    /// Request the app to attach the note to the given record, an empty note removes it
+    SetNote(KafkaRecord, String),
+    /// Notify the UI the notes attached to the records of the cluster
+    Notes(Notes),
     /// Request the app to set up a new kafka consumer
     NewConsumer(),
     /// Request the app to start consuming
@@ -37,10 +84,21 @@ pub(crate) enum Action {
     RefreshShortcuts,
     /// Request to close the kafka consumer
     StopConsuming(),
-    /// Request the app to fetch details (consumer groups, members...) of the given topics
-    RequestTopicDetails(HashSet<String>),
-    RequestSchemasOf(Option<SchemaId>, Option<SchemaId>),
+    /// Request the app to fetch details (consumer groups, members...) of the given topics, counting their records with the given method
+    RequestTopicDetails(HashSet<String>, CountMethod),
+    /// Request the app to fetch an overview of the given topic
+    RequestTopicPreview(String),
+    /// Request the app to fetch the key and value schemas of a record of the given topic
+    RequestSchemasOf(String, Option<SchemaId>, Option<SchemaId>),
     Schemas(Option<SchemaDetail>, Option<SchemaDetail>),
+    /// Request the app to list the versions of the subject of the given schema id, in the schema registry of the topic
+    RequestSchemaVersions(String, u32),
+    /// Notify the UI the versions of a subject
+    SchemaVersions(SchemaVersions),
+    /// Request the app to fetch a specific version of a subject, in the schema registry of the topic
+    RequestSchemaVersion(String, String, u32),
+    /// Notify the UI the content of a version of a subject
+    SchemaVersion(String, u32, SchemaDetail),
     /// Notify the UI the list of topics
     Topics(Vec<String>),
     /// Request the list of kafka records to be sorted in a specific way
@@ -49,6 +107,16 @@ pub(crate) enum Action {
     SelectedTopics(Vec<String>),
     /// Copy the given record to the clipboard
     CopyToClipboard(String),
+    /// Copy the given record to the clipboard as JSON, a truncated value is read again first
+    CopyRecord(KafkaRecord),
+    /// Request the app to copy a `yozefu://` link reproducing the search to the clipboard
+    CopyLink,
+    /// Request the app to copy a report of the matching records, or of the given record with its schemas, to the clipboard
+    Report(ReportFormat, Option<KafkaRecord>),
+    /// Notify the UI of records read from the beginning of the selected topics, to keep the latest record of each key
+    CompactedRecords(Vec<KafkaRecord>),
+    /// Notify the UI that the selected topics have been read up to their end
+    CompactedTopicsRead,
     /// Notify the UI that a new component has been displayed
     NewView(ComponentName),
     /// Notify the UI the visible components and their order in the stack view
@@ -57,12 +125,66 @@ pub(crate) enum Action {
     Open(KafkaRecord),
     /// Notify the UI some details (consumer groups, members...) of a given topic
     TopicDetails(Vec<TopicDetail>),
+    /// Notify the UI the configuration entries of the brokers
+    BrokerConfigs(Vec<BrokerConfigEntry>),
+    /// Notify the UI the client quotas of the cluster
+    ClientQuotas(Vec<ClientQuota>),
+    /// Notify the UI the consumer groups of the cluster
+    ConsumerGroups(Vec<ConsumerGroupOverview>),
+    /// Request the app to compute how the search query would be executed on the selected topics
+    RequestQueryPlan(ValidSearchQuery),
+    /// Notify the UI how the search query would be executed
+    QueryPlan(QueryPlan),
+    /// Request the UI to press the keys one after the other, as if the user typed them
+    PressKeys(Vec<KeyEvent>),
+    /// Notify the UI an overview of a given topic
+    TopicPreview(TopicPreview),
     /// Notify the UI that the user typed a new search query
     NewSearchPrompt(String),
+    /// Request the search component to append the predicate to the search query and run it again
+    AppendToSearch(String),
+    /// Request the search component to run a saved query, after prompting the values of its placeholders
+    SavedQuery(String),
+    /// Request the app to search the related topics for the records sharing the correlation id of the record
+    Trace(KafkaRecord),
+    /// The topics to select and the search query listing the records sharing a correlation id
+    TraceSearch(Vec<String>, String),
+    /// Request the app to switch to the next theme of the themes file
+    NextTheme,
+    /// Request the app to quit and start again with the next workspace
+    NextWorkspace,
+    /// Notify the UI to use a new theme and a new syntax highlighting theme
+    Theme(Theme, Option<syntect::highlighting::Theme>),
+    /// Notify the app that the user switched to another layout, so it is saved
+    Layout(LayoutPreset),
+    /// Notify the UI that the kafka consumer reported an error
+    ConsumerError(ConsumerError),
+    /// Notify the UI of the latest statistics of the kafka consumer, including the state of the connection to each broker
+    Statistics(ConsumerStatistics),
+    /// Notify the UI that an alert rule of the watch mode has been triggered
+    Alert(Alert),
     /// Notify the progress bar an estimate of the kafka records to consume in total according to the search query
     RecordsToRead(usize),
 }
 
+/// Identifies the value of a record as it is rendered: topic, partition, offset and a hash of the rendered value
+pub(crate) type ValueId = (String, i32, i64, u64);
+
+/// Records of a file to produce to the selected topic, see [`Action::Produce`].
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ProduceRequest {
+    pub file: PathBuf,
+    pub mapping: RecordMapping,
+    /// Number of records generated when the file is a template
+    pub count: usize,
+    /// Maximum number of records produced per second
+    pub rate: Option<NonZeroU32>,
+    /// Edits of the headers of each record
+    pub header_edits: Vec<HeaderEdit>,
+    /// Text typed by the user to confirm the production
+    pub confirmation: String,
+}
+
 /// A notification is a message displayed in the bottom-right corner of the TUI.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Notification {
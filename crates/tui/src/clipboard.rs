@@ -0,0 +1,77 @@
+//! Copies content to the clipboard, with the system clipboard or the OSC 52 escape sequence.
+
+use std::{
+    env,
+    io::{Write, stderr},
+};
+
+use app::{configuration::ClipboardBackend, strip_ansi};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use copypasta::{ClipboardContext, ClipboardProvider};
+use lib::Error;
+use tracing::warn;
+
+use crate::error::TuiError;
+
+/// Copies the content to the clipboard.
+/// When the system clipboard is not available, the OSC 52 escape sequence is used instead.
+/// ANSI escape sequences of the content are removed.
+pub(crate) fn copy(content: &str, backend: ClipboardBackend) -> Result<(), TuiError> {
+    let content = &strip_ansi(content);
+    match backend {
+        ClipboardBackend::Osc52 => copy_with_osc52(content),
+        ClipboardBackend::System => match copy_with_system_clipboard(content) {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                warn!("Cannot use the system clipboard, falling back to OSC 52: {e}");
+                copy_with_osc52(content)
+            }
+        },
+    }
+}
+
+fn copy_with_system_clipboard(content: &str) -> Result<(), TuiError> {
+    let mut ctx = ClipboardContext::new().map_err(|e| Error::Error(e.to_string()))?;
+    ctx.set_contents(content.to_string())
+        .map_err(|e| Error::Error(e.to_string()))?;
+    Ok(())
+}
+
+/// The terminal is the one writing to the clipboard, it works through SSH.
+fn copy_with_osc52(content: &str) -> Result<(), TuiError> {
+    let sequence = osc52_sequence(
+        content,
+        env::var_os("TMUX").is_some(),
+        env::var("TERM").is_ok_and(|term| term.starts_with("screen")),
+    );
+    let mut output = stderr();
+    output.write_all(sequence.as_bytes())?;
+    output.flush()?;
+    Ok(())
+}
+
+/// tmux and screen only forward escape sequences wrapped in a DCS passthrough sequence.
+fn osc52_sequence(content: &str, tmux: bool, screen: bool) -> String {
+    let sequence = format!("\x1b]52;c;{}\x07", STANDARD.encode(content));
+    match (tmux, screen) {
+        (true, _) => format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b")),
+        (false, true) => format!("\x1bP{sequence}\x1b\\"),
+        (false, false) => sequence,
+    }
+}
+
+#[test]
+fn test_osc52_sequence() {
+    assert_eq!(
+        osc52_sequence("yozefu", false, false),
+        "\x1b]52;c;eW96ZWZ1\x07"
+    );
+    assert_eq!(
+        osc52_sequence("yozefu", true, false),
+        "\x1bPtmux;\x1b\x1b]52;c;eW96ZWZ1\x07\x1b\\"
+    );
+    assert_eq!(
+        osc52_sequence("yozefu", false, true),
+        "\x1bP\x1b]52;c;eW96ZWZ1\x07\x1b\\"
+    );
+}
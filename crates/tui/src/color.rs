@@ -0,0 +1,156 @@
+//! Degrades colors to what the terminal is able to display.
+
+use app::configuration::ColorSupport;
+use ratatui::style::Color;
+
+/// The 16 ANSI colors with their usual RGB values (xterm).
+const ANSI_COLORS: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// Intensities of the 6x6x6 color cube of the 256 colors palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Returns the closest color the terminal is able to display.
+pub(crate) fn degrade(color: Color, support: ColorSupport) -> Color {
+    match (support, color) {
+        (ColorSupport::TrueColor, _) => color,
+        (ColorSupport::Monochrome, _) => Color::Reset,
+        (ColorSupport::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(to_256(r, g, b)),
+        (ColorSupport::Ansi16, Color::Rgb(..) | Color::Indexed(_)) => {
+            rgb(color).map_or(color, |(r, g, b)| to_16(r, g, b))
+        }
+        _ => color,
+    }
+}
+
+/// When two different colors become the same color once degraded,
+/// the foreground is replaced by black or white depending on the background.
+pub(crate) fn readable(fg: Color, bg: Color, original_fg: Color, original_bg: Color) -> Color {
+    if fg != bg || original_fg == original_bg {
+        return fg;
+    }
+    match rgb(bg) {
+        Some((r, g, b)) if luminance(r, g, b) < 128 => Color::White,
+        Some(_) => Color::Black,
+        None => fg,
+    }
+}
+
+/// Returns the index of the color in the 256 colors palette.
+pub(crate) fn index(color: Color) -> Option<u8> {
+    match color {
+        Color::Indexed(i) => Some(i),
+        color => ANSI_COLORS
+            .iter()
+            .position(|(c, _)| *c == color)
+            .map(|i| i as u8),
+    }
+}
+
+fn rgb(color: Color) -> Option<(u8, u8, u8)> {
+    match color {
+        Color::Rgb(r, g, b) => Some((r, g, b)),
+        Color::Indexed(i) if i < 16 => Some(ANSI_COLORS[i as usize].1),
+        Color::Indexed(i) if i < 232 => {
+            let i = i - 16;
+            Some((
+                CUBE_LEVELS[(i / 36) as usize],
+                CUBE_LEVELS[(i / 6 % 6) as usize],
+                CUBE_LEVELS[(i % 6) as usize],
+            ))
+        }
+        Color::Indexed(i) => {
+            let level = 8 + 10 * (i - 232);
+            Some((level, level, level))
+        }
+        color => ANSI_COLORS
+            .iter()
+            .find(|(c, _)| *c == color)
+            .map(|(_, rgb)| *rgb),
+    }
+}
+
+fn luminance(r: u8, g: u8, b: u8) -> u32 {
+    (299 * r as u32 + 587 * g as u32 + 114 * b as u32) / 1000
+}
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let dr = r1.abs_diff(r2) as u32;
+    let dg = g1.abs_diff(g2) as u32;
+    let db = b1.abs_diff(b2) as u32;
+    dr * dr + dg * dg + db * db
+}
+
+fn to_256(r: u8, g: u8, b: u8) -> u8 {
+    let closest_level = |v: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|i| CUBE_LEVELS[*i].abs_diff(v))
+            .unwrap() as u8
+    };
+    let (ri, gi, bi) = (closest_level(r), closest_level(g), closest_level(b));
+    let cube = 16 + 36 * ri + 6 * gi + bi;
+
+    let average = ((r as u32 + g as u32 + b as u32) / 3) as u8;
+    let gray = 232 + (average.saturating_sub(3) / 10).min(23);
+
+    [cube, gray]
+        .into_iter()
+        .min_by_key(|i| distance(rgb(Color::Indexed(*i)).unwrap(), (r, g, b)))
+        .unwrap()
+}
+
+fn to_16(r: u8, g: u8, b: u8) -> Color {
+    ANSI_COLORS
+        .iter()
+        .min_by_key(|(_, rgb)| distance(*rgb, (r, g, b)))
+        .map(|(color, _)| *color)
+        .unwrap()
+}
+
+#[test]
+fn test_degrade_colors() {
+    let orange = Color::Rgb(255, 135, 0);
+    assert_eq!(degrade(orange, ColorSupport::TrueColor), orange);
+    assert_eq!(degrade(orange, ColorSupport::Ansi256), Color::Indexed(208));
+    assert_eq!(degrade(orange, ColorSupport::Ansi16), Color::Yellow);
+    assert_eq!(
+        degrade(Color::Rgb(40, 40, 40), ColorSupport::Ansi256),
+        Color::Indexed(235)
+    );
+    assert_eq!(
+        degrade(Color::Indexed(231), ColorSupport::Ansi16),
+        Color::White
+    );
+    assert_eq!(degrade(Color::Blue, ColorSupport::Ansi16), Color::Blue);
+}
+
+#[test]
+fn test_readable_colors() {
+    let bg = degrade(Color::Rgb(0, 0, 40), ColorSupport::Ansi16);
+    let fg = degrade(Color::Rgb(0, 0, 80), ColorSupport::Ansi16);
+    assert_eq!(fg, bg);
+    assert_eq!(
+        readable(fg, bg, Color::Rgb(0, 0, 80), Color::Rgb(0, 0, 40)),
+        Color::White
+    );
+    assert_eq!(
+        readable(Color::Red, Color::Red, Color::Red, Color::Red),
+        Color::Red
+    );
+}
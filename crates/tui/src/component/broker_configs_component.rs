@@ -0,0 +1,329 @@
+//! Component listing the configuration entries of the brokers, see [`app::App::broker_configs`],
+//! followed by the client quotas of the cluster, see [`app::App::client_quotas`].
+//! Typing filters the entries by name, value or source, and the quotas by entity or name.
+use app::{BrokerConfigEntry, ClientQuota};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Layout, Position, Rect},
+    style::{Style, Stylize},
+    text::Text,
+    widgets::{
+        Block, BorderType, Borders, Cell, Clear, Padding, Paragraph, Row, Table, TableState,
+    },
+};
+use tokio::sync::mpsc::UnboundedSender;
+use tui_input::{Input, backend::crossterm::EventHandler};
+
+use super::{Component, ComponentName, Shortcut, State};
+use crate::{Action, error::TuiError};
+
+#[derive(Default)]
+pub(crate) struct BrokerConfigsComponent {
+    entries: Vec<BrokerConfigEntry>,
+    /// Indexes of the entries matching the filter
+    visible: Vec<usize>,
+    quotas: Vec<ClientQuota>,
+    /// Indexes of the quotas matching the filter
+    visible_quotas: Vec<usize>,
+    state: TableState,
+    input: Input,
+    loading: bool,
+    action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl BrokerConfigsComponent {
+    fn set_entries(&mut self, entries: Vec<BrokerConfigEntry>) {
+        self.entries = entries;
+        self.loading = false;
+        self.filter();
+    }
+
+    fn set_quotas(&mut self, quotas: Vec<ClientQuota>) {
+        self.quotas = quotas;
+        self.filter();
+    }
+
+    fn filter(&mut self) {
+        let filter = self.input.value();
+        self.visible = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.matches(filter))
+            .map(|(i, _)| i)
+            .collect();
+        self.visible_quotas = self
+            .quotas
+            .iter()
+            .enumerate()
+            .filter(|(_, q)| q.matches(filter))
+            .map(|(i, _)| i)
+            .collect();
+        match self.visible.is_empty() {
+            true => self.state.select(None),
+            false => {
+                let selected = self.state.selected().unwrap_or(0);
+                self.state
+                    .select(Some(selected.min(self.visible.len() - 1)));
+            }
+        }
+    }
+
+    /// Copies the entries and the quotas matching the filter to the clipboard, as JSON.
+    fn copy(&self) -> Result<(), TuiError> {
+        let configs = serde_json::json!({
+            "configs": self.visible.iter().map(|i| &self.entries[*i]).collect::<Vec<_>>(),
+            "quotas": self.visible_quotas.iter().map(|i| &self.quotas[*i]).collect::<Vec<_>>(),
+        });
+        self.action_tx
+            .as_ref()
+            .unwrap()
+            .send(Action::CopyToClipboard(
+                serde_json::to_string_pretty(&configs).expect("Unable to serialize the configs"),
+            ))?;
+        Ok(())
+    }
+}
+
+impl BrokerConfigsComponent {
+    fn draw_quotas(&self, f: &mut Frame<'_>, rect: Rect) {
+        if self.visible_quotas.is_empty() {
+            return;
+        }
+        let header = Row::new(vec![
+            Cell::new(Text::from("Entity")).bold(),
+            Cell::new(Text::from("Name")).bold(),
+            Cell::new(Text::from("Value").alignment(Alignment::Right)).bold(),
+        ])
+        .height(1)
+        .bottom_margin(1);
+        let rows = self.visible_quotas.iter().map(|i| {
+            let quota = &self.quotas[*i];
+            Row::new(vec![
+                Cell::new(quota.entity.clone()),
+                Cell::new(quota.name.clone()),
+                Cell::new(Text::from(quota.value.to_string()).alignment(Alignment::Right)),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Fill(3),
+                Constraint::Fill(2),
+                Constraint::Length(14),
+            ],
+        )
+        .header(header)
+        .column_spacing(2)
+        .block(
+            Block::default()
+                .borders(Borders::TOP)
+                .title(format!(" Client quotas [{}] ", self.visible_quotas.len())),
+        );
+        f.render_widget(table, rect);
+    }
+}
+
+impl Component for BrokerConfigsComponent {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) {
+        self.action_tx = Some(tx);
+    }
+
+    fn id(&self) -> ComponentName {
+        ComponentName::BrokerConfigs
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>, TuiError> {
+        match key.code {
+            KeyCode::Up => self.state.select_previous(),
+            KeyCode::Down if self.state.selected() < Some(self.visible.len().saturating_sub(1)) => {
+                self.state.select_next();
+            }
+            KeyCode::Enter => self.copy()?,
+            KeyCode::Esc | KeyCode::Down => (),
+            _ => {
+                if !key.modifiers.contains(KeyModifiers::CONTROL) {
+                    self.input.handle_event(&Event::Key(key));
+                    self.filter();
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>, TuiError> {
+        match action {
+            Action::NewView(ComponentName::BrokerConfigs) => self.loading = true,
+            Action::BrokerConfigs(entries) => self.set_entries(entries),
+            Action::ClientQuotas(quotas) => self.set_quotas(quotas),
+            _ => (),
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect, state: &State) -> Result<(), TuiError> {
+        let focused = state.is_focused(&self.id());
+        f.render_widget(Clear, rect);
+        let title = match self.loading {
+            true => " Broker configs [loading...] ".to_string(),
+            false => format!(
+                " Broker configs [{}/{}] ",
+                self.visible.len(),
+                self.entries.len()
+            ),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(title);
+        let block = self.make_block_focused_with_state(state, block);
+        let inner = block.inner(rect);
+        f.render_widget(block, rect);
+
+        let [table_area, quotas_area, filter_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(match self.visible_quotas.is_empty() {
+                true => 0,
+                false => (self.visible_quotas.len() as u16 + 4).min(12),
+            }),
+            Constraint::Length(match self.input.value().is_empty() {
+                true => 0,
+                false => 3,
+            }),
+        ])
+        .areas(inner);
+
+        let header = Row::new(vec![
+            Cell::new(Text::from("Broker").alignment(Alignment::Right)).bold(),
+            Cell::new(Text::from("Name")).bold(),
+            Cell::new(Text::from("Value")).bold(),
+            Cell::new(Text::from("Source")).bold(),
+        ])
+        .height(1)
+        .bottom_margin(1);
+
+        let rows = self.visible.iter().map(|i| {
+            let entry = &self.entries[*i];
+            let value = match (entry.sensitive, &entry.value) {
+                (true, _) => Text::from("sensitive").italic(),
+                (false, Some(value)) => Text::from(value.clone()),
+                (false, None) => Text::from("null").fg(state.theme.orange),
+            };
+            let source = Text::from(entry.source.clone());
+            Row::new(vec![
+                Cell::new(Text::from(entry.broker.to_string()).alignment(Alignment::Right)),
+                Cell::new(entry.name.clone()),
+                Cell::new(value),
+                Cell::new(match entry.source.as_str() {
+                    "default" => source,
+                    _ => source.fg(state.theme.green),
+                }),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(6),
+                Constraint::Fill(3),
+                Constraint::Fill(2),
+                Constraint::Length(22),
+            ],
+        )
+        .header(header)
+        .column_spacing(2)
+        .row_highlight_style(match focused {
+            true => Style::default()
+                .bg(state.theme.bg_focused_selected)
+                .fg(state.theme.fg_focused_selected)
+                .bold(),
+            false => Style::default()
+                .bg(state.theme.bg_unfocused_selected)
+                .fg(state.theme.fg_unfocused_selected),
+        });
+        f.render_stateful_widget(table, table_area, &mut self.state);
+        self.draw_quotas(f, quotas_area);
+
+        if !self.input.value().is_empty() {
+            let filter = Paragraph::new(self.input.value()).block(
+                Block::default()
+                    .title(" Filter ")
+                    .padding(Padding::left(1))
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded),
+            );
+            if focused {
+                f.set_cursor_position(Position {
+                    x: (filter_area.x + self.input.visual_cursor() as u16 + 2)
+                        .min(filter_area.right().saturating_sub(2)),
+                    y: filter_area.y + 1,
+                });
+            }
+            f.render_widget(filter, filter_area);
+        }
+        Ok(())
+    }
+
+    fn shortcuts(&self) -> Vec<Shortcut> {
+        vec![Shortcut::new("ENTER", "Copy as JSON")]
+    }
+}
+
+#[cfg(test)]
+use crate::assert_draw;
+
+#[test]
+fn test_draw() {
+    let entry = |broker, name: &str, value: Option<&str>, source: &str| BrokerConfigEntry {
+        broker,
+        name: name.to_string(),
+        value: value.map(str::to_string),
+        source: source.to_string(),
+        read_only: false,
+        sensitive: false,
+    };
+    let mut component = BrokerConfigsComponent::default();
+    component.set_entries(vec![
+        entry(1, "auto.create.topics.enable", Some("true"), "default"),
+        entry(1, "log.retention.hours", Some("168"), "default"),
+        entry(1, "broker.rack", None, "default"),
+        entry(2, "log.retention.hours", Some("72"), "dynamic broker"),
+        BrokerConfigEntry {
+            sensitive: true,
+            ..entry(2, "ssl.keystore.password", None, "static")
+        },
+    ]);
+    component.input = Input::new("retention".to_string());
+    component.filter();
+    assert_draw!(component, 90, 10)
+}
+
+#[test]
+fn test_draw_quotas() {
+    let quota = |entity: &str, name: &str, value| ClientQuota {
+        entity: entity.to_string(),
+        name: name.to_string(),
+        value,
+    };
+    let mut component = BrokerConfigsComponent::default();
+    component.set_entries(vec![BrokerConfigEntry {
+        broker: 1,
+        name: "quota.window.num".to_string(),
+        value: Some("11".to_string()),
+        source: "default".to_string(),
+        read_only: true,
+        sensitive: false,
+    }]);
+    component.set_quotas(vec![
+        quota("client-id=payments", "producer_byte_rate", 1_048_576.),
+        quota("user=<default>", "request_percentage", 25.),
+        quota(
+            "user=alice, client-id=<default>",
+            "consumer_byte_rate",
+            2_097_152.,
+        ),
+    ]);
+    component.input = Input::new("rate".to_string());
+    component.filter();
+    assert_draw!(component, 90, 14)
+}
@@ -0,0 +1,335 @@
+//! Command palette listing the actions of the TUI, the macros and the saved queries of the configuration.
+//! Typing filters the commands with a fuzzy search, <kbd>Enter</kbd> runs the selected command by pressing its keys.
+//! Saved queries focus the search input that prompts the values of their placeholders.
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use indexmap::IndexMap;
+use lib::search::placeholder::placeholders;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Position, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Padding, Paragraph},
+};
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::warn;
+use tui_input::{Input, backend::crossterm::EventHandler};
+
+use super::{Component, ComponentName, Shortcut, State, root_component::CONTROL_KEY_BINDINGS};
+use crate::{Action, error::TuiError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Command {
+    name: String,
+    /// Key strokes as written in the help, `CTRL + R` or `ctrl+e enter`
+    label: String,
+    keys: Vec<KeyEvent>,
+    /// The saved query run once the keys are pressed
+    query: Option<String>,
+}
+
+#[derive(Default)]
+pub(crate) struct CommandPaletteComponent {
+    commands: Vec<Command>,
+    /// Indexes of the commands matching the input, the best match first
+    visible: Vec<usize>,
+    state: ListState,
+    input: Input,
+    action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl CommandPaletteComponent {
+    /// Lists the built-in commands followed by the macros and the saved queries. Macros with an unknown key are ignored.
+    pub fn new(macros: &IndexMap<String, Vec<String>>, queries: &IndexMap<String, String>) -> Self {
+        let mut commands = CONTROL_KEY_BINDINGS
+            .iter()
+            // The palette is already open
+            .filter(|(_, c, _)| *c != ' ')
+            .map(|(label, c, name)| Command {
+                name: (*name).to_string(),
+                label: (*label).to_string(),
+                keys: vec![KeyEvent::new(KeyCode::Char(*c), KeyModifiers::CONTROL)],
+                query: None,
+            })
+            .collect::<Vec<_>>();
+        for (name, keys) in macros {
+            match keys
+                .iter()
+                .map(|k| parse_key(k))
+                .collect::<Option<Vec<_>>>()
+            {
+                Some(events) => commands.push(Command {
+                    name: name.clone(),
+                    label: keys.join(" "),
+                    keys: events,
+                    query: None,
+                }),
+                None => warn!(
+                    "Macro '{name}' is ignored, one of its keys [{}] is unknown",
+                    keys.join(", ")
+                ),
+            }
+        }
+        for (name, query) in queries {
+            let label = placeholders(query)
+                .iter()
+                .map(|p| format!(":{p}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            commands.push(Command {
+                name: name.clone(),
+                label: format!("query {label}").trim_end().to_string(),
+                keys: vec![KeyEvent::new(KeyCode::Char('f'), KeyModifiers::CONTROL)],
+                query: Some(query.clone()),
+            });
+        }
+        let mut component = Self {
+            commands,
+            ..Default::default()
+        };
+        component.filter();
+        component
+    }
+
+    fn filter(&mut self) {
+        let pattern = self.input.value();
+        let mut scored = self
+            .commands
+            .iter()
+            .enumerate()
+            .filter_map(|(i, c)| fuzzy_score(pattern, &c.name).map(|score| (score, i)))
+            .collect::<Vec<_>>();
+        scored.sort();
+        self.visible = scored.into_iter().map(|(_, i)| i).collect();
+        self.state.select((!self.visible.is_empty()).then_some(0));
+    }
+
+    fn run(&mut self) -> Result<(), TuiError> {
+        let Some(index) = self.state.selected().and_then(|i| self.visible.get(i)) else {
+            return Ok(());
+        };
+        let command = self.commands[*index].clone();
+        self.input.reset();
+        self.filter();
+        let action_tx = self.action_tx.as_ref().unwrap();
+        action_tx.send(Action::PressKeys(command.keys))?;
+        if let Some(query) = command.query {
+            action_tx.send(Action::SavedQuery(query))?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses a key stroke of a macro: `c`, `E`, `enter`, `esc`, `tab`, `up`, `ctrl+e`...
+fn parse_key(key: &str) -> Option<KeyEvent> {
+    let (modifiers, key) = match key.split_once('+') {
+        Some((modifier, key)) if modifier.eq_ignore_ascii_case("ctrl") && !key.is_empty() => {
+            (KeyModifiers::CONTROL, key)
+        }
+        _ => (KeyModifiers::NONE, key),
+    };
+    let mut chars = key.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => KeyCode::Char(c),
+        _ => match key.to_lowercase().as_str() {
+            "enter" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "space" => KeyCode::Char(' '),
+            "backspace" => KeyCode::Backspace,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            _ => return None,
+        },
+    };
+    Some(KeyEvent::new(code, modifiers))
+}
+
+/// Returns a score when the characters of the pattern appear in order in the text, ignoring the case.
+/// The score is the number of characters skipped, the lower the better.
+fn fuzzy_score(pattern: &str, text: &str) -> Option<usize> {
+    let text = text.to_lowercase();
+    let mut score = 0;
+    let mut position = 0;
+    for c in pattern
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+    {
+        let found = text[position..].find(c)?;
+        score += found;
+        position += found + c.len_utf8();
+    }
+    Some(score)
+}
+
+impl Component for CommandPaletteComponent {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) {
+        self.action_tx = Some(tx);
+    }
+
+    fn id(&self) -> ComponentName {
+        ComponentName::CommandPalette
+    }
+
+    /// The search being written receives every key, `ESC` clears it.
+    fn is_typing(&self) -> bool {
+        !self.input.value().is_empty()
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>, TuiError> {
+        match key.code {
+            KeyCode::Up => self.state.select_previous(),
+            KeyCode::Down if self.state.selected() < Some(self.visible.len().saturating_sub(1)) => {
+                self.state.select_next();
+            }
+            KeyCode::Enter => self.run()?,
+            KeyCode::Esc if !self.input.value().is_empty() => {
+                self.input.reset();
+                self.filter();
+            }
+            KeyCode::Esc | KeyCode::Down => (),
+            _ => {
+                if !key.modifiers.contains(KeyModifiers::CONTROL) {
+                    self.input.handle_event(&Event::Key(key));
+                    self.filter();
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect, state: &State) -> Result<(), TuiError> {
+        let focused = state.is_focused(&self.id());
+        f.render_widget(Clear, rect);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .padding(Padding::horizontal(1))
+            .title(format!(
+                " Commands [{}/{}] ",
+                self.visible.len(),
+                self.commands.len()
+            ));
+        let block = self.make_block_focused_with_state(state, block);
+        let inner = block.inner(rect);
+        f.render_widget(block, rect);
+
+        let [input_area, list_area] =
+            Layout::vertical([Constraint::Length(2), Constraint::Fill(1)]).areas(inner);
+        f.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::from("> ").bold(),
+                Span::from(self.input.value().to_string()),
+            ])),
+            input_area,
+        );
+        if focused {
+            f.set_cursor_position(Position {
+                x: input_area.x + 2 + self.input.visual_cursor() as u16,
+                y: input_area.y,
+            });
+        }
+
+        let width = list_area.width as usize;
+        let items = self.visible.iter().map(|i| {
+            let command = &self.commands[*i];
+            let padding = width.saturating_sub(command.name.len() + command.label.len() + 1);
+            ListItem::new(Line::from(vec![
+                Span::from(command.name.clone()),
+                Span::from(" ".repeat(padding + 1)),
+                Span::from(command.label.clone()).fg(state.theme.green),
+            ]))
+        });
+        let list = List::new(items).highlight_style(match focused {
+            true => Style::default()
+                .bg(state.theme.bg_focused_selected)
+                .fg(state.theme.fg_focused_selected)
+                .bold(),
+            false => Style::default()
+                .bg(state.theme.bg_unfocused_selected)
+                .fg(state.theme.fg_unfocused_selected),
+        });
+        f.render_stateful_widget(list, list_area, &mut self.state);
+        Ok(())
+    }
+
+    fn shortcuts(&self) -> Vec<Shortcut> {
+        vec![Shortcut::new("ENTER", "Run")]
+    }
+}
+
+#[cfg(test)]
+use crate::assert_draw;
+
+#[test]
+fn test_parse_key() {
+    assert_eq!(
+        parse_key("ctrl+e"),
+        Some(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::CONTROL))
+    );
+    assert_eq!(
+        parse_key("E"),
+        Some(KeyEvent::new(KeyCode::Char('E'), KeyModifiers::NONE))
+    );
+    assert_eq!(
+        parse_key("Enter"),
+        Some(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE))
+    );
+    assert_eq!(
+        parse_key("+"),
+        Some(KeyEvent::new(KeyCode::Char('+'), KeyModifiers::NONE))
+    );
+    assert_eq!(parse_key("alt+e"), None);
+    assert_eq!(parse_key("f13"), None);
+}
+
+#[test]
+fn test_fuzzy_score() {
+    assert_eq!(fuzzy_score("", "Quit"), Some(0));
+    assert_eq!(fuzzy_score("Quit", "Quit"), Some(0));
+    assert_eq!(fuzzy_score("nt th", "Switch to the next theme"), Some(17));
+    assert!(
+        fuzzy_score("lat", "Show/Hide the latency probe")
+            < fuzzy_score("lat", "Switch to the next layout")
+    );
+    assert_eq!(fuzzy_score("xyz", "Quit"), None);
+}
+
+#[test]
+fn test_draw() {
+    let mut macros = IndexMap::new();
+    macros.insert(
+        "Export the selected record".to_string(),
+        vec!["E".to_string()],
+    );
+    macros.insert("Unknown".to_string(), vec!["hyper+e".to_string()]);
+    let mut queries = IndexMap::new();
+    queries.insert(
+        "Show the orders of a customer".to_string(),
+        "value.customer == :customer and value.total > :total".to_string(),
+    );
+    let mut component = CommandPaletteComponent::new(&macros, &queries);
+    component.input = Input::new("show".to_string());
+    component.filter();
+    assert_draw!(component, 80, 18)
+}
+
+#[test]
+fn test_clear_search() {
+    let mut component = CommandPaletteComponent::new(&IndexMap::new(), &IndexMap::new());
+    let all = component.visible.len();
+    component.input = Input::new("theme".to_string());
+    component.filter();
+    assert!(component.is_typing());
+    assert!(component.visible.len() < all);
+
+    component
+        .handle_key_events(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE))
+        .unwrap();
+    assert!(!component.is_typing());
+    assert_eq!(component.visible.len(), all);
+}
@@ -0,0 +1,62 @@
+//! Input confirming an operation modifying the cluster.
+//! On protected clusters, the name of the cluster must be typed, see [`app::configuration::YozefuConfig::check_destructive`].
+
+use crossterm::event::{Event, KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::{Position, Rect},
+    style::Style,
+    widgets::{Block, BorderType, Borders, Padding, Paragraph},
+};
+use tui_input::{Input, backend::crossterm::EventHandler};
+
+use super::State;
+
+/// What the user did with the confirmation.
+pub(crate) enum Confirmed {
+    /// The operation is confirmed with the typed text
+    Yes(String),
+    /// The operation is cancelled
+    No,
+}
+
+#[derive(Default)]
+pub(crate) struct Confirmation {
+    input: Input,
+}
+
+impl Confirmation {
+    /// `ENTER` confirms with the typed text, `ESC` cancels, `None` while the user is typing.
+    pub fn handle_key_events(&mut self, key: KeyEvent) -> Option<Confirmed> {
+        match key.code {
+            KeyCode::Enter => Some(Confirmed::Yes(self.input.value().to_string())),
+            KeyCode::Esc => Some(Confirmed::No),
+            _ => {
+                self.input.handle_event(&Event::Key(key));
+                None
+            }
+        }
+    }
+
+    /// Draws the input, the title tells what `operation` the user confirms.
+    pub fn draw(&self, f: &mut Frame<'_>, rect: Rect, operation: &str, state: &State) {
+        let title = match state.config.protected() {
+            true => format!(
+                " Type '{}' and ENTER to {operation}, ESC cancels ",
+                state.cluster
+            ),
+            false => format!(" ENTER to {operation}, ESC cancels "),
+        };
+        let block = Block::default()
+            .title(title)
+            .padding(Padding::left(1))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(state.theme.orange));
+        f.set_cursor_position(Position {
+            x: (rect.x + self.input.visual_cursor() as u16 + 2).min(rect.right().saturating_sub(2)),
+            y: rect.y + 1,
+        });
+        f.render_widget(Paragraph::new(self.input.value()).block(block), rect);
+    }
+}
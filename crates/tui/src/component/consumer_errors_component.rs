@@ -0,0 +1,179 @@
+//! Component listing the state of the connection to each broker
+//! and the errors of the kafka consumer, with the broker, the error code and suggestions to fix them.
+use app::{BrokerHealth, ConsumerError};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Padding, Paragraph, Wrap},
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{Component, ComponentName, Shortcut, State, scroll_state::ScrollState, styles};
+use crate::{Action, error::TuiError};
+
+/// Errors are listed once, with the number of times librdkafka reported them
+#[derive(Default)]
+pub(crate) struct ConsumerErrorsComponent {
+    errors: Vec<(ConsumerError, usize)>,
+    brokers: Vec<BrokerHealth>,
+    scroll: ScrollState,
+    action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl ConsumerErrorsComponent {
+    fn push(&mut self, error: ConsumerError) -> Result<(), TuiError> {
+        if let Some((_, count)) = self
+            .errors
+            .iter_mut()
+            .find(|(e, _)| e.broker == error.broker && e.code == error.code)
+        {
+            *count += 1;
+            return Ok(());
+        }
+        self.errors.push((error, 1));
+        self.action_tx
+            .as_ref()
+            .unwrap()
+            .send(Action::NewView(ComponentName::ConsumerErrors))?;
+        Ok(())
+    }
+
+    fn lines(&self, state: &State) -> Vec<Line<'static>> {
+        let mut lines = vec![];
+        if !self.brokers.is_empty() {
+            lines.push(Line::default());
+            lines.push(Line::from("Brokers").bold());
+            for broker in &self.brokers {
+                let color = styles::connection_color(broker.state, &state.theme);
+                lines.push(Line::from(vec![
+                    Span::from(format!("{:>9}  ", "●")).fg(color),
+                    Span::from(format!("{}  ", broker.name)),
+                    Span::from(broker.state.to_string()).fg(color),
+                ]));
+            }
+        }
+        if self.errors.is_empty() {
+            lines.push(Line::default());
+            lines.push(Line::from("The kafka consumer did not report any error.").italic());
+            return lines;
+        }
+        for (error, count) in &self.errors {
+            lines.push(Line::default());
+            let mut title = vec![Span::from(error.code.clone()).fg(state.theme.red).bold()];
+            if *count > 1 {
+                title.push(Span::from(format!("  ×{count}")));
+            }
+            lines.push(Line::from(title));
+            if let Some(broker) = &error.broker {
+                lines.push(Self::generate_line("Broker", broker.clone()));
+            }
+            lines.push(Self::generate_line("Message", error.message.clone()));
+            for (i, suggestion) in error.suggestions.iter().enumerate() {
+                let key = match i {
+                    0 => "Try",
+                    _ => "",
+                };
+                lines.push(Self::generate_line(key, format!("• {suggestion}")));
+            }
+        }
+        lines
+    }
+
+    fn generate_line(key: &str, value: String) -> Line<'static> {
+        Line::from(vec![
+            Span::styled(format!("{key:>9}  "), Style::default().bold()),
+            Span::from(value),
+        ])
+    }
+}
+
+impl Component for ConsumerErrorsComponent {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) {
+        self.action_tx = Some(tx);
+    }
+
+    fn id(&self) -> ComponentName {
+        ComponentName::ConsumerErrors
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>, TuiError> {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.scroll.scroll_to_next_line(),
+            KeyCode::Char('k') | KeyCode::Up => self.scroll.scroll_to_previous_line(),
+            _ => (),
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>, TuiError> {
+        match action {
+            Action::ConsumerError(error) => self.push(error)?,
+            Action::Statistics(statistics) => self.brokers = statistics.brokers,
+            Action::StopConsuming() => self.brokers.clear(),
+            Action::NewConsumer() => {
+                self.errors.clear();
+                self.brokers.clear();
+                self.scroll.reset();
+            }
+            _ => (),
+        }
+        Ok(None)
+    }
+
+    fn shortcuts(&self) -> Vec<Shortcut> {
+        vec![Shortcut::new("J/K", "Scroll")]
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect, state: &State) -> Result<(), TuiError> {
+        let lines = self.lines(state);
+        f.render_widget(Clear, rect);
+        let block = Block::new()
+            .borders(Borders::ALL)
+            .padding(Padding::symmetric(4, 0))
+            .title(" Connection ");
+        let block = self.make_block_focused_with_state(state, block);
+        let paragraph = Paragraph::new(lines.clone())
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll.value(), 0));
+        f.render_widget(paragraph.block(block), rect);
+        self.scroll.draw(f, rect, lines.len() + 2);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+use crate::assert_draw;
+
+#[test]
+fn test_draw() {
+    use app::ConnectionState;
+
+    let mut component = ConsumerErrorsComponent {
+        brokers: vec![
+            BrokerHealth {
+                name: "kafka-1:9092/1".to_string(),
+                state: ConnectionState::Connected,
+            },
+            BrokerHealth {
+                name: "kafka-2:9092/2".to_string(),
+                state: ConnectionState::Disconnected,
+            },
+        ],
+        errors: vec![(
+            ConsumerError {
+                broker: Some("sasl_ssl://kafka:9092/1".to_string()),
+                code: "Authentication".to_string(),
+                message: "SASL authentication error: Authentication failed".to_string(),
+                suggestions: vec![
+                    "Check the 'sasl.mechanism', 'sasl.username' and 'sasl.password' properties of the cluster",
+                ],
+            },
+            3,
+        )],
+        ..Default::default()
+    };
+    assert_draw!(component, 120, 14)
+}
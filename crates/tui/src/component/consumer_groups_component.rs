@@ -0,0 +1,432 @@
+//! Component listing the consumer groups of the cluster, see [`app::App::consumer_groups`].
+//! Typing filters the groups by name or topic, `ENTER` shows the members of the selected group and their assignments.
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use itertools::Itertools;
+use lib::{ConsumerGroupOverview, ConsumerGroupState, ConsumerGroupType};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Layout, Margin, Position, Rect},
+    style::{Color, Style, Stylize},
+    text::{Line, Text},
+    widgets::{
+        Block, BorderType, Borders, Cell, Clear, Padding, Paragraph, Row, Table, TableState,
+    },
+};
+use thousands::Separable;
+use tui_input::{Input, backend::crossterm::EventHandler};
+
+use super::{Component, ComponentName, Shortcut, State};
+use crate::{Action, error::TuiError};
+
+#[derive(Default)]
+pub(crate) struct ConsumerGroupsComponent {
+    groups: Vec<ConsumerGroupOverview>,
+    /// Indexes of the groups matching the filter
+    visible: Vec<usize>,
+    state: TableState,
+    input: Input,
+    loading: bool,
+    /// The members of the selected group are displayed instead of the list of groups
+    members: bool,
+}
+
+impl ConsumerGroupsComponent {
+    fn set_groups(&mut self, groups: Vec<ConsumerGroupOverview>) {
+        self.groups = groups;
+        self.loading = false;
+        self.members = false;
+        self.filter();
+    }
+
+    fn filter(&mut self) {
+        let filter = self.input.value();
+        self.visible = self
+            .groups
+            .iter()
+            .enumerate()
+            .filter(|(_, g)| g.name.contains(filter) || g.topics.iter().any(|t| t.contains(filter)))
+            .map(|(i, _)| i)
+            .collect();
+        match self.visible.is_empty() {
+            true => self.state.select(None),
+            false => {
+                let selected = self.state.selected().unwrap_or(0);
+                self.state
+                    .select(Some(selected.min(self.visible.len() - 1)));
+            }
+        }
+    }
+
+    fn selected(&self) -> Option<&ConsumerGroupOverview> {
+        self.state
+            .selected()
+            .and_then(|i| self.visible.get(i))
+            .map(|i| &self.groups[*i])
+    }
+
+    fn state_color(group_state: ConsumerGroupState, state: &State) -> Color {
+        match group_state {
+            ConsumerGroupState::Stable => state.theme.green,
+            ConsumerGroupState::PreparingRebalance
+            | ConsumerGroupState::CompletingRebalance
+            | ConsumerGroupState::Rebalancing
+            | ConsumerGroupState::Assigning
+            | ConsumerGroupState::Reconciling => state.theme.yellow,
+            ConsumerGroupState::Empty
+            | ConsumerGroupState::Dead
+            | ConsumerGroupState::Unknown
+            | ConsumerGroupState::UnknownRebalance
+            | _ => state.theme.red,
+        }
+    }
+
+    /// Rebalance protocol of the group followed by its partition assignor,
+    /// such as `consumer (KIP-848), assignor uniform`.
+    fn protocol(group: &ConsumerGroupOverview) -> String {
+        let group_type = match group.group_type {
+            ConsumerGroupType::Classic => Some("classic".to_string()),
+            ConsumerGroupType::Consumer => Some("consumer (KIP-848)".to_string()),
+            _ => None,
+        };
+        let assignor = (!group.protocol.is_empty()).then(|| format!("assignor {}", group.protocol));
+        match [group_type, assignor].into_iter().flatten().join(", ") {
+            protocol if protocol.is_empty() => "-".to_string(),
+            protocol => protocol,
+        }
+    }
+
+    fn draw_groups(&mut self, f: &mut Frame<'_>, rect: Rect, state: &State) {
+        let header = Row::new(vec![
+            Cell::new(Text::from("Group")).bold(),
+            Cell::new(Text::from("State")).bold(),
+            Cell::new(Text::from("Type")).bold(),
+            Cell::new(Text::from("Members").alignment(Alignment::Right)).bold(),
+            Cell::new(Text::from("Lag").alignment(Alignment::Right)).bold(),
+            Cell::new(Text::from("Topics")).bold(),
+        ])
+        .height(1)
+        .bottom_margin(1);
+
+        let rows = self.visible.iter().map(|i| {
+            let group = &self.groups[*i];
+            let lag = match group.lag {
+                Some(lag) => Text::from(lag.separate_with_underscores()),
+                None => Text::from("?").dim(),
+            };
+            Row::new(vec![
+                Cell::new(group.name.clone()),
+                Cell::new(
+                    Text::from(group.state.to_string()).fg(Self::state_color(group.state, state)),
+                ),
+                Cell::new(match group.group_type {
+                    ConsumerGroupType::Unknown => Text::from("?").dim(),
+                    group_type => Text::from(group_type.to_string()),
+                }),
+                Cell::new(Text::from(group.members.len().to_string()).alignment(Alignment::Right)),
+                Cell::new(lag.alignment(Alignment::Right)),
+                Cell::new(group.topics.join(", ")),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Fill(2),
+                Constraint::Length(19),
+                Constraint::Length(8),
+                Constraint::Length(7),
+                Constraint::Length(14),
+                Constraint::Fill(3),
+            ],
+        )
+        .header(header)
+        .column_spacing(2)
+        .row_highlight_style(match state.is_focused(&self.id()) {
+            true => Style::default()
+                .bg(state.theme.bg_focused_selected)
+                .fg(state.theme.fg_focused_selected)
+                .bold(),
+            false => Style::default()
+                .bg(state.theme.bg_unfocused_selected)
+                .fg(state.theme.fg_unfocused_selected),
+        });
+        f.render_stateful_widget(table, rect, &mut self.state);
+    }
+
+    fn draw_members(&self, f: &mut Frame<'_>, rect: Rect, state: &State) {
+        let Some(group) = self.selected() else {
+            return;
+        };
+        let [summary_area, table_area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Fill(1)]).areas(rect);
+        let summary = vec![
+            Line::from(vec![
+                "Group     ".bold(),
+                group.name.clone().into(),
+                "  ".into(),
+                group
+                    .state
+                    .to_string()
+                    .fg(Self::state_color(group.state, state)),
+            ]),
+            Line::from(vec!["Protocol  ".bold(), Self::protocol(group).into()]),
+            Line::from(vec![
+                "Lag       ".bold(),
+                group
+                    .lag
+                    .map_or("?".to_string(), |l| l.separate_with_underscores())
+                    .into(),
+            ]),
+        ];
+        f.render_widget(Paragraph::new(summary), summary_area);
+
+        let header = Row::new(vec![
+            Cell::new(Text::from("Client")).bold(),
+            Cell::new(Text::from("Instance id")).bold(),
+            Cell::new(Text::from("Host")).bold(),
+            Cell::new(Text::from("Assignments")).bold(),
+        ])
+        .height(1)
+        .bottom_margin(1);
+        let rows = group.members.iter().map(|member| {
+            let assignments = member
+                .assignments
+                .iter()
+                .map(|a| format!("{} [{}]", a.topic, a.partitions.iter().join(", ")))
+                .collect_vec();
+            Row::new(vec![
+                Cell::new(member.client_id.clone()),
+                Cell::new(match &member.instance_id {
+                    Some(instance_id) => Text::from(instance_id.clone()),
+                    None => Text::from("-").dim(),
+                }),
+                Cell::new(member.host.trim_start_matches('/').to_string()),
+                Cell::new(Text::from(
+                    assignments.iter().cloned().map(Line::from).collect_vec(),
+                )),
+            ])
+            .height(assignments.len().max(1) as u16)
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Fill(2),
+                Constraint::Fill(2),
+                Constraint::Length(16),
+                Constraint::Fill(3),
+            ],
+        )
+        .header(header)
+        .column_spacing(2);
+        f.render_widget(table, table_area.inner(Margin::new(0, 1)));
+    }
+}
+
+impl Component for ConsumerGroupsComponent {
+    fn id(&self) -> ComponentName {
+        ComponentName::ConsumerGroups
+    }
+
+    /// The filter being written receives every key, `ESC` clears it.
+    fn is_typing(&self) -> bool {
+        !self.members && !self.input.value().is_empty()
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>, TuiError> {
+        match key.code {
+            KeyCode::Enter => self.members = !self.members && self.selected().is_some(),
+            _ if self.members => (),
+            KeyCode::Up => self.state.select_previous(),
+            KeyCode::Down if self.state.selected() < Some(self.visible.len().saturating_sub(1)) => {
+                self.state.select_next();
+            }
+            KeyCode::Esc if !self.input.value().is_empty() => {
+                self.input.reset();
+                self.filter();
+            }
+            KeyCode::Esc | KeyCode::Down => (),
+            _ => {
+                if !key.modifiers.contains(KeyModifiers::CONTROL) {
+                    self.input.handle_event(&Event::Key(key));
+                    self.filter();
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>, TuiError> {
+        match action {
+            Action::NewView(ComponentName::ConsumerGroups) => self.loading = true,
+            Action::ConsumerGroups(groups) => self.set_groups(groups),
+            _ => (),
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect, state: &State) -> Result<(), TuiError> {
+        let focused = state.is_focused(&self.id());
+        f.render_widget(Clear, rect);
+        let title = match (self.loading, self.selected()) {
+            (true, _) => " Consumer groups [loading...] ".to_string(),
+            (false, Some(group)) if self.members => {
+                format!(
+                    " Consumer groups > {} [{}] ",
+                    group.name,
+                    group.members.len()
+                )
+            }
+            (false, _) => format!(
+                " Consumer groups [{}/{}] ",
+                self.visible.len(),
+                self.groups.len()
+            ),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .padding(Padding::horizontal(1))
+            .title(title);
+        let block = self.make_block_focused_with_state(state, block);
+        let inner = block.inner(rect);
+        f.render_widget(block, rect);
+
+        if self.members {
+            self.draw_members(f, inner, state);
+            return Ok(());
+        }
+
+        let [table_area, filter_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(match self.input.value().is_empty() {
+                true => 0,
+                false => 3,
+            }),
+        ])
+        .areas(inner);
+        self.draw_groups(f, table_area, state);
+
+        if !self.input.value().is_empty() {
+            let filter = Paragraph::new(self.input.value()).block(
+                Block::default()
+                    .title(" Filter ")
+                    .padding(Padding::left(1))
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded),
+            );
+            if focused {
+                f.set_cursor_position(Position {
+                    x: (filter_area.x + self.input.visual_cursor() as u16 + 2)
+                        .min(filter_area.right().saturating_sub(2)),
+                    y: filter_area.y + 1,
+                });
+            }
+            f.render_widget(filter, filter_area);
+        }
+        Ok(())
+    }
+
+    fn shortcuts(&self) -> Vec<Shortcut> {
+        match self.members {
+            true => vec![Shortcut::new("ENTER", "Back to groups")],
+            false => vec![Shortcut::new("ENTER", "Members")],
+        }
+    }
+}
+
+#[cfg(test)]
+use crate::assert_draw;
+
+#[cfg(test)]
+fn groups() -> Vec<ConsumerGroupOverview> {
+    use lib::{ConsumerGroupMember, MemberAssignment};
+
+    let member = |client_id: &str, host: &str, partitions: Vec<i32>| {
+        let mut member = ConsumerGroupMember::default();
+        member.member = format!("{client_id}-8c1e");
+        member.client_id = client_id.to_string();
+        member.host = host.to_string();
+        member.instance_id = (client_id == "billing-1").then(|| "billing-eu-west-1a".to_string());
+        member.start_offset = 0;
+        member.end_offset = 0;
+        member.assignments = vec![{
+            let mut assignment = MemberAssignment::default();
+            assignment.topic = "payments".to_string();
+            assignment.partitions = partitions;
+            assignment
+        }];
+        member
+    };
+    vec![
+        {
+            let mut group = ConsumerGroupOverview::default();
+            group.name = "billing".to_string();
+            group.state = ConsumerGroupState::Stable;
+            group.group_type = ConsumerGroupType::Consumer;
+            group.protocol = "uniform".to_string();
+            group.members = vec![
+                member("billing-1", "/10.0.0.12", vec![0, 1]),
+                member("billing-2", "/10.0.0.13", vec![2]),
+            ];
+            group.topics = vec!["payments".to_string()];
+            group.lag = Some(12_430);
+            group
+        },
+        {
+            let mut group = ConsumerGroupOverview::default();
+            group.name = "audit".to_string();
+            group.state = ConsumerGroupState::Empty;
+            group.group_type = ConsumerGroupType::Classic;
+            group.protocol = String::new();
+            group.members = vec![];
+            group.topics = vec!["orders".to_string(), "payments".to_string()];
+            group.lag = Some(3);
+            group
+        },
+        {
+            let mut group = ConsumerGroupOverview::default();
+            group.name = "connect-cluster".to_string();
+            group.state = ConsumerGroupState::Stable;
+            group.group_type = ConsumerGroupType::Unknown;
+            group.protocol = "sessioned".to_string();
+            group.members = vec![];
+            group.topics = vec![];
+            group.lag = None;
+            group
+        },
+    ]
+}
+
+#[test]
+fn test_draw() {
+    let mut component = ConsumerGroupsComponent::default();
+    component.set_groups(groups());
+    assert_draw!(component, 100, 10)
+}
+
+#[test]
+fn test_draw_members() {
+    let mut component = ConsumerGroupsComponent::default();
+    component.set_groups(groups());
+    component
+        .handle_key_events(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE))
+        .unwrap();
+    assert_draw!(component, 90, 12)
+}
+
+#[test]
+fn test_clear_filter() {
+    let mut component = ConsumerGroupsComponent::default();
+    component.set_groups(groups());
+    for c in "conn".chars() {
+        component
+            .handle_key_events(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+            .unwrap();
+    }
+    assert!(component.is_typing());
+    assert_eq!(component.visible.len(), 1);
+    component
+        .handle_key_events(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE))
+        .unwrap();
+    assert!(!component.is_typing());
+    assert_eq!(component.visible.len(), 3);
+}
@@ -0,0 +1,337 @@
+//! Component grouping the listed records of a dead letter queue by error type, see [`app::DeadLetter`].
+//! The selected record is republished to its original topic, once its headers are edited and the republication confirmed.
+use app::DeadLetter;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use lib::KafkaRecord;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Position, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{
+        Block, BorderType, Borders, Cell, Clear, Padding, Paragraph, Row, Table, TableState, Wrap,
+    },
+};
+use tokio::sync::mpsc::UnboundedSender;
+use tui_input::backend::crossterm::EventHandler;
+
+use super::{
+    Component, ComponentName, ConcurrentRecordsBuffer, Shortcut, State,
+    confirmation::{Confirmation, Confirmed},
+    header_editor::{HEADER_INPUT_TITLE, HeaderEditor},
+};
+use crate::{
+    Action,
+    action::{Level, Notification},
+    error::TuiError,
+};
+
+pub(crate) struct DeadLettersComponent {
+    records: &'static ConcurrentRecordsBuffer,
+    /// Selected record, among the records of all the groups
+    state: TableState,
+    /// Edits applied to the headers of the record before it is republished
+    headers: HeaderEditor,
+    /// Confirmation of the republication being written
+    confirmation: Option<Confirmation>,
+    action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl DeadLettersComponent {
+    pub fn new(records: &'static ConcurrentRecordsBuffer) -> Self {
+        Self {
+            records,
+            state: TableState::default(),
+            headers: HeaderEditor::default(),
+            confirmation: None,
+            action_tx: None,
+        }
+    }
+
+    /// The records of the dead letter queue, grouped by error type, in the order they are listed.
+    fn dead_letters(&self) -> Vec<(String, Vec<(KafkaRecord, DeadLetter)>)> {
+        let records = self.records.lock().unwrap();
+        DeadLetter::group(records.iter())
+            .into_iter()
+            .map(|(error, records)| {
+                let records = records
+                    .into_iter()
+                    .map(|(record, dead_letter)| (record.clone(), dead_letter))
+                    .collect();
+                (error, records)
+            })
+            .collect()
+    }
+
+    /// The selected record and where it comes from.
+    fn selected(&self) -> Option<(KafkaRecord, DeadLetter)> {
+        let selected = self.state.selected()?;
+        self.dead_letters()
+            .into_iter()
+            .flat_map(|(_, records)| records)
+            .nth(selected)
+    }
+
+    /// Republishes the selected record, `confirmation` is the text typed to confirm it.
+    fn republish(&self, confirmation: String) -> Result<(), TuiError> {
+        let Some((record, _)) = self.selected() else {
+            return Ok(());
+        };
+        self.action_tx.as_ref().unwrap().send(Action::Republish(
+            record,
+            self.headers.edits.clone(),
+            confirmation,
+        ))?;
+        Ok(())
+    }
+
+    /// Adds the header edit being written to the table, see [`HeaderEditor::add`].
+    fn add_header_edit(&mut self) -> Result<(), TuiError> {
+        if let Err(e) = self.headers.add() {
+            self.action_tx
+                .as_ref()
+                .unwrap()
+                .send(Action::Notification(Notification::new(
+                    Level::Warn,
+                    e.to_string(),
+                )))?;
+        }
+        Ok(())
+    }
+
+    fn handle_confirmation_key_events(&mut self, key: KeyEvent) -> Result<(), TuiError> {
+        let Some(confirmed) = self
+            .confirmation
+            .as_mut()
+            .and_then(|c| c.handle_key_events(key))
+        else {
+            return Ok(());
+        };
+        self.confirmation = None;
+        if let Confirmed::Yes(confirmation) = confirmed {
+            self.republish(confirmation)?;
+        }
+        Ok(())
+    }
+}
+
+impl Component for DeadLettersComponent {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) {
+        self.action_tx = Some(tx);
+    }
+
+    fn id(&self) -> ComponentName {
+        ComponentName::DeadLetters
+    }
+
+    /// The confirmation and the header edit being written receive every key.
+    fn is_typing(&self) -> bool {
+        self.confirmation.is_some() || !self.headers.input.value().is_empty()
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>, TuiError> {
+        if self.confirmation.is_some() {
+            self.handle_confirmation_key_events(key)?;
+            return Ok(None);
+        }
+        match key.code {
+            KeyCode::Up => self.state.select_previous(),
+            KeyCode::Down => self.state.select_next(),
+            KeyCode::Enter if !self.headers.input.value().is_empty() => self.add_header_edit()?,
+            KeyCode::Enter if self.state.selected().is_some() => {
+                self.confirmation = Some(Confirmation::default());
+            }
+            KeyCode::Backspace if self.headers.input.value().is_empty() => self.headers.pop(),
+            KeyCode::Esc if !self.headers.input.value().is_empty() => self.headers.input.reset(),
+            KeyCode::Esc | KeyCode::Enter => (),
+            _ => {
+                if !key.modifiers.contains(KeyModifiers::CONTROL) {
+                    self.headers.input.handle_event(&Event::Key(key));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect, state: &State) -> Result<(), TuiError> {
+        f.render_widget(Clear, rect);
+        let dead_letters = self.dead_letters();
+        let count = dead_letters.iter().map(|(_, records)| records.len()).sum();
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(
+                " Dead letters [{count} records, {} errors] ",
+                dead_letters.len()
+            ))
+            .padding(Padding::horizontal(1));
+        let block = self.make_block_focused_with_state(state, block);
+        let inner = block.inner(rect);
+        f.render_widget(block, rect);
+
+        let [help_area, table_area, headers_area, header_area] = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Fill(1),
+            Constraint::Length(self.headers.height()),
+            Constraint::Length(3),
+        ])
+        .areas(inner);
+
+        f.render_widget(
+            Paragraph::new(
+                "Records with Kafka Connect or Spring Kafka dead letter headers, ENTER republishes the selected record to its original topic.",
+            )
+            .wrap(Wrap { trim: true }),
+            help_area,
+        );
+
+        if count == 0 {
+            f.render_widget(
+                Paragraph::new(Line::from("No listed record has dead letter headers").italic()),
+                table_area,
+            );
+        } else {
+            if self.state.selected().is_none_or(|s| s >= count) {
+                self.state.select(Some(0));
+            }
+            let rows = dead_letters.iter().flat_map(|(error, records)| {
+                records
+                    .iter()
+                    .enumerate()
+                    .map(move |(i, (_, dead_letter))| {
+                        let error = match i {
+                            0 => Cell::from(format!("{error} ×{}", records.len()))
+                                .fg(state.theme.red)
+                                .bold(),
+                            _ => Cell::default(),
+                        };
+                        Row::new(vec![
+                            error,
+                            Cell::from(dead_letter.origin()),
+                            Cell::from(
+                                dead_letter
+                                    .message
+                                    .as_deref()
+                                    .and_then(|m| m.lines().next())
+                                    .unwrap_or_default()
+                                    .to_string(),
+                            ),
+                        ])
+                    })
+            });
+            let header = Row::new(vec![
+                Cell::from("Error").bold(),
+                Cell::from("Original record").bold(),
+                Cell::from("Message").bold(),
+            ]);
+            let table = Table::new(
+                rows,
+                [
+                    Constraint::Length(28),
+                    Constraint::Length(24),
+                    Constraint::Fill(1),
+                ],
+            )
+            .header(header)
+            .column_spacing(2)
+            .row_highlight_style(match state.is_focused(&self.id()) {
+                true => Style::default()
+                    .bg(state.theme.bg_focused_selected)
+                    .fg(state.theme.fg_focused_selected),
+                false => Style::default()
+                    .bg(state.theme.bg_unfocused_selected)
+                    .fg(state.theme.fg_unfocused_selected),
+            });
+            f.render_stateful_widget(table, table_area, &mut self.state);
+        }
+
+        self.headers.draw_edits(f, headers_area, state);
+
+        // While confirming, the input of the header edits becomes the input of the confirmation
+        if let (Some(confirmation), Some((_, dead_letter))) = (&self.confirmation, self.selected())
+        {
+            let operation = format!("republish to '{}'", dead_letter.topic);
+            confirmation.draw(f, header_area, &operation, state);
+            return Ok(());
+        }
+        let mut block = Block::default()
+            .title(HEADER_INPUT_TITLE)
+            .padding(Padding::left(1))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded);
+        if state.is_focused(&self.id()) {
+            block = block.border_style(Style::default().fg(state.theme.focused_border));
+            f.set_cursor_position(Position {
+                x: (header_area.x + self.headers.input.visual_cursor() as u16 + 2)
+                    .min(header_area.right().saturating_sub(2)),
+                y: header_area.y + 1,
+            });
+        }
+        f.render_widget(
+            Paragraph::new(self.headers.input.value()).block(block),
+            header_area,
+        );
+        Ok(())
+    }
+
+    fn shortcuts(&self) -> Vec<Shortcut> {
+        if self.confirmation.is_some() {
+            return vec![
+                Shortcut::new("ENTER", "Confirm"),
+                Shortcut::new("ESC", "Cancel"),
+            ];
+        }
+        vec![
+            Shortcut::new("ENTER", "Republish"),
+            Shortcut::new("↑↓", "Previous/next record"),
+        ]
+    }
+}
+
+#[cfg(test)]
+use crate::assert_draw;
+
+#[test]
+fn test_draw() {
+    use crate::records_buffer::RecordsBuffer;
+    use std::sync::{Arc, LazyLock, Mutex};
+
+    static BUFFER: ConcurrentRecordsBuffer =
+        LazyLock::new(|| Arc::new(Mutex::new(RecordsBuffer::new())));
+    for (offset, (topic, exception, message)) in [
+        (
+            "orders",
+            "java.lang.NullPointerException",
+            "customer is null",
+        ),
+        (
+            "orders",
+            "org.apache.kafka.connect.errors.DataException",
+            "Unknown field 'amount'",
+        ),
+        ("payments", "java.lang.NullPointerException", "card is null"),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let mut record = KafkaRecord::default();
+        record.topic = "orders-dlq".to_string();
+        record.offset = offset as i64;
+        for (name, value) in [
+            ("__connect.errors.topic", topic),
+            ("__connect.errors.partition", "0"),
+            ("__connect.errors.offset", &(1040 + offset).to_string()),
+            ("__connect.errors.exception.class.name", exception),
+            ("__connect.errors.exception.message", message),
+        ] {
+            record.headers.insert(name.to_string(), value.to_string());
+        }
+        BUFFER.lock().unwrap().push(record);
+    }
+    let mut component = DeadLettersComponent::new(&BUFFER);
+    component.headers.edits = vec![app::HeaderEdit::Set(
+        "retry-count".to_string(),
+        "1".to_string(),
+    )];
+    assert_draw!(component, 100, 16)
+}
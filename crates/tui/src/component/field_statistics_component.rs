@@ -0,0 +1,365 @@
+//! Component computing statistics of a field across the records of the buffer:
+//! the number of distinct values, the most frequent values and, for numbers, the minimum, the maximum and the average.
+//! Only the values of the field are read while the buffer is locked, the statistics are computed again when the records change.
+use std::collections::HashMap;
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use itertools::Itertools;
+use lib::search::symbol::Symbol;
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Layout, Position, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span, Text},
+    widgets::{Block, BorderType, Borders, Cell, Clear, Padding, Paragraph, Row, Table},
+};
+use serde_json::Value;
+use thousands::Separable;
+use tui_input::{Input, backend::crossterm::EventHandler};
+
+use super::{Component, ComponentName, ConcurrentRecordsBuffer, Shortcut, State};
+use crate::{Action, error::TuiError};
+
+/// Number of most frequent values listed
+const TOP: usize = 10;
+
+/// Statistics of a field across a set of records.
+#[derive(Debug, Default, PartialEq)]
+struct FieldStatistics {
+    records: usize,
+    /// Records without the field
+    missing: usize,
+    /// Number of occurrences of each value
+    values: HashMap<String, usize>,
+    /// Number of numeric values
+    numbers: usize,
+    min: f64,
+    max: f64,
+    sum: f64,
+}
+
+impl FieldStatistics {
+    /// Statistics of the values of the field, `Value::Null` when a record does not have the field.
+    fn compute(values: impl Iterator<Item = Value>) -> Self {
+        let mut statistics = Self {
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            ..Default::default()
+        };
+        for value in values {
+            statistics.records += 1;
+            let number = match &value {
+                Value::Null => {
+                    statistics.missing += 1;
+                    continue;
+                }
+                Value::Number(n) => n.as_f64(),
+                Value::String(s) => s.trim().parse::<f64>().ok().filter(|n| n.is_finite()),
+                _ => None,
+            };
+            if let Some(number) = number {
+                statistics.numbers += 1;
+                statistics.min = statistics.min.min(number);
+                statistics.max = statistics.max.max(number);
+                statistics.sum += number;
+            }
+            let value = match value {
+                Value::String(s) => s,
+                v => v.to_string(),
+            };
+            *statistics.values.entry(value).or_default() += 1;
+        }
+        statistics
+    }
+
+    fn cardinality(&self) -> usize {
+        self.values.len()
+    }
+
+    /// The most frequent values, ties are sorted by value
+    fn top(&self, n: usize) -> Vec<(&str, usize)> {
+        self.values
+            .iter()
+            .map(|(value, count)| (value.as_str(), *count))
+            .sorted_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)))
+            .take(n)
+            .collect()
+    }
+
+    fn average(&self) -> Option<f64> {
+        match self.numbers {
+            0 => None,
+            n => Some(self.sum / n as f64),
+        }
+    }
+}
+
+pub(crate) struct FieldStatisticsComponent {
+    records: &'static ConcurrentRecordsBuffer,
+    input: Input,
+    field: Option<Symbol>,
+    error: Option<String>,
+    /// Statistics of the field and the version of the records they were computed from
+    statistics: Option<(u64, FieldStatistics)>,
+}
+
+impl FieldStatisticsComponent {
+    pub fn new(records: &'static ConcurrentRecordsBuffer) -> Self {
+        Self {
+            records,
+            input: Input::default(),
+            field: None,
+            error: None,
+            statistics: None,
+        }
+    }
+
+    fn parse_field(&mut self) {
+        self.statistics = None;
+        match Symbol::parse_field(self.input.value()) {
+            Ok(field) => {
+                self.field = Some(field);
+                self.error = None;
+            }
+            Err(_) => {
+                self.field = None;
+                self.error = Some(format!("'{}' is not a field", self.input.value().trim()));
+            }
+        }
+    }
+
+    /// Computes the statistics of the field again when the records of the buffer changed.
+    fn refresh(&mut self, field: &Symbol) -> &FieldStatistics {
+        let values = {
+            let records = self.records.lock().unwrap();
+            match &self.statistics {
+                Some((version, _)) if *version == records.version() => None,
+                _ => Some((
+                    records.version(),
+                    records.iter().map(|r| field.value_of(r)).collect_vec(),
+                )),
+            }
+        };
+        if let Some((version, values)) = values {
+            self.statistics = Some((version, FieldStatistics::compute(values.into_iter())));
+        }
+        &self.statistics.as_ref().unwrap().1
+    }
+}
+
+impl Component for FieldStatisticsComponent {
+    fn id(&self) -> ComponentName {
+        ComponentName::FieldStatistics
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>, TuiError> {
+        match key.code {
+            KeyCode::Enter => self.parse_field(),
+            KeyCode::Esc => (),
+            _ => {
+                if !key.modifiers.contains(KeyModifiers::CONTROL) {
+                    self.input.handle_event(&Event::Key(key));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect, state: &State) -> Result<(), TuiError> {
+        f.render_widget(Clear, rect);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" Field statistics ");
+        let block = self.make_block_focused_with_state(state, block);
+        let inner = block.inner(rect);
+        f.render_widget(block, rect);
+
+        let [input_area, summary_area, values_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(7),
+            Constraint::Fill(1),
+        ])
+        .areas(inner);
+
+        let input = Paragraph::new(self.input.value()).block(
+            Block::default()
+                .title(" Field, for instance 'value.amount', 'key' or 'headers.tenant' ")
+                .padding(Padding::left(1))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded),
+        );
+        f.render_widget(input, input_area);
+        if state.is_focused(&self.id()) {
+            f.set_cursor_position(Position {
+                x: (input_area.x + self.input.visual_cursor() as u16 + 2)
+                    .min(input_area.right().saturating_sub(2)),
+                y: input_area.y + 1,
+            });
+        }
+
+        if let Some(error) = &self.error {
+            f.render_widget(
+                Paragraph::new(error.as_str())
+                    .style(Style::default().fg(state.theme.red))
+                    .block(Block::default().padding(Padding::horizontal(1))),
+                summary_area,
+            );
+            return Ok(());
+        }
+        let Some(field) = self.field.clone() else {
+            f.render_widget(
+                Paragraph::new("Type the path of a field and press ENTER.")
+                    .block(Block::default().padding(Padding::horizontal(1))),
+                summary_area,
+            );
+            return Ok(());
+        };
+
+        let statistics = self.refresh(&field);
+        let line = |label: &str, value: String| {
+            Line::from(vec![
+                Span::from(format!("{label:>16}: ")).bold(),
+                Span::from(value),
+            ])
+        };
+        let mut summary = vec![
+            line("Records", statistics.records.separate_with_underscores()),
+            line("Missing", statistics.missing.separate_with_underscores()),
+            line(
+                "Distinct values",
+                statistics.cardinality().separate_with_underscores(),
+            ),
+        ];
+        if let Some(average) = statistics.average() {
+            summary.extend([
+                line(
+                    "Numeric values",
+                    statistics.numbers.separate_with_underscores(),
+                ),
+                line("Min", statistics.min.to_string()),
+                line("Max", statistics.max.to_string()),
+                line("Average", format!("{average:.2}")),
+            ]);
+        }
+        f.render_widget(
+            Paragraph::new(summary).block(Block::default().padding(Padding::horizontal(1))),
+            summary_area,
+        );
+
+        let present = (statistics.records - statistics.missing).max(1);
+        let rows = statistics.top(TOP).into_iter().map(|(value, count)| {
+            Row::new(vec![
+                Cell::new(
+                    Text::from(count.separate_with_underscores()).alignment(Alignment::Right),
+                ),
+                Cell::new(
+                    Text::from(format!("{:.1}%", count as f64 * 100. / present as f64))
+                        .alignment(Alignment::Right),
+                ),
+                Cell::new(value.to_string()),
+            ])
+        });
+        let header = Row::new(vec![
+            Cell::new(Text::from("Count").alignment(Alignment::Right)).bold(),
+            Cell::new(Text::from("%").alignment(Alignment::Right)).bold(),
+            Cell::new(Text::from(format!("Top {TOP} values"))).bold(),
+        ])
+        .bottom_margin(1);
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(8),
+                Constraint::Length(6),
+                Constraint::Percentage(100),
+            ],
+        )
+        .header(header)
+        .column_spacing(2)
+        .block(Block::default().padding(Padding::horizontal(1)));
+        f.render_widget(table, values_area);
+        Ok(())
+    }
+
+    fn shortcuts(&self) -> Vec<Shortcut> {
+        vec![Shortcut::new("ENTER", "Compute")]
+    }
+}
+
+#[cfg(test)]
+use crate::assert_draw;
+
+#[cfg(test)]
+fn records() -> Vec<lib::KafkaRecord> {
+    use lib::DataType;
+    use serde_json::json;
+
+    [
+        json!({"status": "paid", "amount": 12.5}),
+        json!({"status": "paid", "amount": 30}),
+        json!({"status": "refunded", "amount": "7.5"}),
+        json!({"status": "pending"}),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(offset, value)| {
+        let mut record = lib::KafkaRecord::default();
+        record.topic = "payments".to_string();
+        record.offset = offset as i64;
+        record.value_as_string = value.to_string();
+        record.value = DataType::Json(value);
+        record
+    })
+    .collect()
+}
+
+#[test]
+fn test_field_statistics() {
+    let records = records();
+    let amount = Symbol::parse_field("value.amount").unwrap();
+    let statistics = FieldStatistics::compute(records.iter().map(|r| amount.value_of(r)));
+    assert_eq!(statistics.records, 4);
+    assert_eq!(statistics.missing, 1);
+    assert_eq!(statistics.cardinality(), 3);
+    assert_eq!((statistics.min, statistics.max), (7.5, 30.));
+    assert_eq!(statistics.average(), Some(50. / 3.));
+
+    let status = Symbol::parse_field("value.status").unwrap();
+    let statistics = FieldStatistics::compute(records.iter().map(|r| status.value_of(r)));
+    assert_eq!(statistics.top(2), vec![("paid", 2), ("pending", 1)]);
+    assert_eq!(statistics.average(), None);
+}
+
+#[test]
+fn test_draw() {
+    use crate::records_buffer::RecordsBuffer;
+    use std::sync::{Arc, LazyLock, Mutex};
+
+    static BUFFER: ConcurrentRecordsBuffer =
+        LazyLock::new(|| Arc::new(Mutex::new(RecordsBuffer::new())));
+    for record in records() {
+        BUFFER.lock().unwrap().push(record);
+    }
+    let mut component = FieldStatisticsComponent::new(&BUFFER);
+    component.input = Input::new("value.amount".to_string());
+    component.parse_field();
+    assert_draw!(component, 80, 20)
+}
+
+#[test]
+fn test_refresh_when_records_change() {
+    use crate::records_buffer::RecordsBuffer;
+    use std::sync::{Arc, LazyLock, Mutex};
+
+    static BUFFER: ConcurrentRecordsBuffer =
+        LazyLock::new(|| Arc::new(Mutex::new(RecordsBuffer::new())));
+    let mut records = records().into_iter();
+    BUFFER.lock().unwrap().push(records.next().unwrap());
+    let mut component = FieldStatisticsComponent::new(&BUFFER);
+    let amount = Symbol::parse_field("value.amount").unwrap();
+    assert_eq!(component.refresh(&amount).records, 1);
+    assert_eq!(component.refresh(&amount).records, 1);
+
+    BUFFER.lock().unwrap().push(records.next().unwrap());
+    assert_eq!(component.refresh(&amount).records, 2);
+}
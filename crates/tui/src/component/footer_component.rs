@@ -1,4 +1,5 @@
-//! The footer component displays contextual information: the current cluster, shortcuts and the last notifications
+//! The footer component displays contextual information: the current cluster, shortcuts and the last notifications.
+//! In accessible mode, it starts with a status line describing the selected item.
 use crossterm::event::KeyEvent;
 
 use ratatui::{
@@ -19,6 +20,8 @@ pub struct FooterComponent {
     main_component: ComponentName,
     action_tx: Option<UnboundedSender<Action>>,
     show_shortcuts: bool,
+    /// Description of the selected item, shown in accessible mode
+    announcement: Option<String>,
 }
 
 impl FooterComponent {
@@ -79,7 +82,9 @@ impl Component for FooterComponent {
             }
             Action::ViewStack((main_component, _views)) => {
                 self.main_component = main_component;
+                self.announcement = None;
             }
+            Action::Announce(announcement) => self.announcement = Some(announcement),
             _ => (),
         }
         Ok(None)
@@ -87,6 +92,10 @@ impl Component for FooterComponent {
 
     fn draw(&mut self, f: &mut Frame<'_>, rect: Rect, state: &State) -> Result<(), TuiError> {
         let mut help: Vec<Span<'static>> = vec![];
+        if let Some(announcement) = self.announcement.as_ref().filter(|_| state.accessible) {
+            help.push(announcement.clone().bold());
+            help.push(Span::from("   "));
+        }
         if self.show_shortcuts {
             help.extend(self.generate_shortcuts(state));
         }
@@ -113,3 +122,20 @@ fn test_draw() {
         .unwrap();
     assert_draw!(component, 60, 3)
 }
+
+#[test]
+fn test_draw_announcement() {
+    let mut component = FooterComponent::default();
+    component
+        .update(Action::Announce(
+            "Topic 2 of 12, orders, selected".to_string(),
+        ))
+        .unwrap();
+    let mut state = crate::component::default_state();
+    state.accessible = true;
+    let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(60, 1)).unwrap();
+    terminal
+        .draw(|f| component.draw(f, f.area(), &state).unwrap())
+        .unwrap();
+    insta::assert_snapshot!(terminal.backend());
+}
@@ -1,9 +1,13 @@
 //! The footer component displays contextual information: the current cluster, shortcuts and the last notifications
+use std::time::{Duration, Instant};
+
+use app::configuration::Workspace;
+use app::{BrokerHealth, ConnectionState};
 use crossterm::event::KeyEvent;
 
 use ratatui::{
     Frame,
-    layout::Rect,
+    layout::{Constraint, Layout, Rect},
     style::{Style, Stylize},
     text::{Line, Span},
 };
@@ -14,7 +18,10 @@ use crate::{
     error::TuiError,
 };
 
-use super::{Component, ComponentName, State};
+use super::{Component, ComponentName, State, styles};
+
+/// How long a notification is displayed
+const NOTIFICATION_DURATION: Duration = Duration::from_secs(5);
 
 #[derive(Default)]
 pub struct HeaderComponent {
@@ -22,7 +29,10 @@ pub struct HeaderComponent {
     state: Vec<ComponentName>,
     notification: Option<Notification>,
     action_tx: Option<UnboundedSender<Action>>,
-    ticks: u64,
+    /// When the current notification was received
+    notified_at: Option<Instant>,
+    /// State of the connection to the brokers of the running consumer
+    brokers: Vec<BrokerHealth>,
 }
 
 impl Component for HeaderComponent {
@@ -45,14 +55,18 @@ impl Component for HeaderComponent {
                 self.state = views;
             }
             Action::Notification(notification) => {
-                self.ticks = 0;
+                self.notified_at = Some(Instant::now());
                 self.notification = Some(notification);
             }
-            Action::Tick => {
-                self.ticks += 1;
-                if self.ticks > 20 {
-                    self.notification = None;
-                }
+            Action::Statistics(statistics) => self.brokers = statistics.brokers,
+            Action::NewConsumer() | Action::StopConsuming() => self.brokers.clear(),
+            Action::Tick
+                if self
+                    .notified_at
+                    .is_some_and(|at| at.elapsed() > NOTIFICATION_DURATION) =>
+            {
+                self.notified_at = None;
+                self.notification = None;
             }
             _ => (),
         }
@@ -64,12 +78,17 @@ impl Component for HeaderComponent {
         view_stack.dedup();
         view_stack.push(state.focused.clone());
         let mut help = vec![];
-        help.push(
-            format!(" {} ", state.cluster)
-                .black()
-                .bold()
-                .bg(state.theme.white),
-        );
+        let workspace = state.workspace().name();
+        let cluster = match workspace == Workspace::DEFAULT_WORKSPACE {
+            true => state.cluster.clone(),
+            false => format!("{workspace} · {}", state.cluster),
+        };
+        help.push(format!(" {cluster} ").black().bold().bg(state.theme.white));
+        if state.config.read_only() {
+            help.push(" READ-ONLY ".black().bold().bg(state.theme.red));
+        } else if state.config.protected() {
+            help.push(" PROTECTED ".black().bold().bg(state.theme.orange));
+        }
         help.push(" ".into());
         for v in view_stack.iter().enumerate() {
             let colors = match v.0 == view_stack.len() - 1 {
@@ -83,6 +102,7 @@ impl Component for HeaderComponent {
                 help.push("".to_string().bg(colors.0).fg(state.theme.bg));
             }
             let prefix = match v.0 {
+                _ if state.accessible => "",
                 0 if self.main_component == ComponentName::TopicsAndRecords => "◧ ",
                 0 if self.main_component == ComponentName::Records => "□ ",
                 _ => "",
@@ -100,19 +120,32 @@ impl Component for HeaderComponent {
 
         help.push(Span::from("  "));
 
+        if let Some(connection) = ConnectionState::of(&self.brokers) {
+            // One dot per broker, the state of the connection is spelled out anyway
+            if !state.accessible {
+                for broker in &self.brokers {
+                    help.push(
+                        Span::from("●").fg(styles::connection_color(broker.state, &state.theme)),
+                    );
+                }
+            }
+            help.push(
+                Span::from(format!(" {connection}"))
+                    .fg(styles::connection_color(connection, &state.theme)),
+            );
+        }
+
         let line = Line::from(help);
         f.render_widget(line, rect);
 
         if let Some(n) = &self.notification {
             let notification =
                 Span::styled(n.message.to_string(), Style::default().italic().not_bold());
-            let r = Rect::new(
-                rect.width
-                    .saturating_sub(u16::try_from(n.message.len().checked_sub(1).unwrap_or(1))?),
-                rect.y,
-                n.message.len() as u16,
-                1,
-            );
+            let width = u16::try_from(notification.width())
+                .unwrap_or(u16::MAX)
+                .min(rect.width);
+            let [_, r] = Layout::horizontal([Constraint::Fill(1), Constraint::Length(width)])
+                .areas(Rect { height: 1, ..rect });
             let notification = match n.level {
                 Level::Error => notification.fg(state.theme.red).underlined(),
                 Level::Warn => notification.fg(state.theme.yellow),
@@ -132,3 +165,36 @@ fn test_draw() {
     let mut component = HeaderComponent::default();
     assert_draw!(component, 50, 3)
 }
+
+#[test]
+fn test_draw_brokers_health() {
+    let mut component = HeaderComponent::default();
+    component
+        .update(Action::Statistics(app::ConsumerStatistics {
+            brokers: vec![
+                BrokerHealth {
+                    name: "kafka-1:9092/1".to_string(),
+                    state: ConnectionState::Connected,
+                },
+                BrokerHealth {
+                    name: "kafka-2:9092/2".to_string(),
+                    state: ConnectionState::Degraded,
+                },
+            ],
+            ..Default::default()
+        }))
+        .unwrap();
+    assert_draw!(component, 50, 3)
+}
+
+#[test]
+fn test_draw_notification_wider_than_the_terminal() {
+    let mut component = HeaderComponent::default();
+    component
+        .update(Action::Notification(Notification::new(
+            Level::Info,
+            "A notification that does not fit in a narrow tmux pane".to_string(),
+        )))
+        .unwrap();
+    assert_draw!(component, 20, 3)
+}
@@ -0,0 +1,84 @@
+//! Input editing the headers of the records before they are produced or republished, see [`app::HeaderEdit`].
+
+use app::HeaderEdit;
+use lib::Error;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::Stylize,
+    widgets::{Block, BorderType, Borders, Cell, Padding, Row, Table},
+};
+use tui_input::Input;
+
+use super::State;
+
+/// Title of the input of the header edits
+pub(crate) const HEADER_INPUT_TITLE: &str =
+    " Header: 'name=value' or '-name', ENTER adds it, BACKSPACE removes the last one ";
+
+#[derive(Default)]
+pub(crate) struct HeaderEditor {
+    /// Header edit being written, added to `edits` with ENTER
+    pub input: Input,
+    /// Edits applied to the headers of the records
+    pub edits: Vec<HeaderEdit>,
+}
+
+impl HeaderEditor {
+    /// Adds the header edit being written, it replaces the previous edit of the same header.
+    pub fn add(&mut self) -> Result<(), Error> {
+        let edit = HeaderEdit::parse(self.input.value())?;
+        self.edits.retain(|e| e.name() != edit.name());
+        self.edits.push(edit);
+        self.input.reset();
+        Ok(())
+    }
+
+    /// Removes the last edit.
+    pub fn pop(&mut self) {
+        self.edits.pop();
+    }
+
+    /// Height of the table of the edits, nothing is drawn without edit.
+    pub fn height(&self) -> u16 {
+        match self.edits.is_empty() {
+            true => 0,
+            false => self.edits.len() as u16 + 2,
+        }
+    }
+
+    /// Draws the edits as a table of the headers and their new value.
+    pub fn draw_edits(&self, f: &mut Frame<'_>, rect: Rect, state: &State) {
+        if self.edits.is_empty() {
+            return;
+        }
+        let rows = self.edits.iter().map(|edit| match edit {
+            HeaderEdit::Set(name, value) => {
+                Row::new(vec![Cell::from(name.as_str()), Cell::from(value.as_str())])
+            }
+            HeaderEdit::Remove(name) => Row::new(vec![
+                Cell::from(name.as_str()),
+                Cell::from("removed").fg(state.theme.red),
+            ]),
+        });
+        let longest_name = self
+            .edits
+            .iter()
+            .map(|e| e.name().len())
+            .max()
+            .unwrap_or_default();
+        let table = Table::new(
+            rows,
+            [Constraint::Length(longest_name as u16), Constraint::Fill(1)],
+        )
+        .column_spacing(2)
+        .block(
+            Block::default()
+                .title(" Headers ")
+                .padding(Padding::left(1))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded),
+        );
+        f.render_widget(table, rect);
+    }
+}
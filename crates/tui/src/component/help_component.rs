@@ -1,51 +1,383 @@
-//! Component showing the help
+//! Component showing the help: the key bindings, the query language, the configuration and the paths of the workspace.
+//! Each section is a table that can be collapsed, typing filters the rows of every section.
 
-use app::configuration::{Configuration, SENSITIVE_KAFKA_PROPERTIES};
-use crossterm::event::{KeyCode, KeyEvent};
+use app::configuration::Configuration;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
 use itertools::Itertools;
 use ratatui::{
     Frame,
-    layout::Rect,
-    style::Stylize,
-    text::{Line, Span},
-    widgets::{Block, BorderType, Borders, Clear, Padding, Paragraph, Wrap},
+    layout::{Constraint, Layout, Position, Rect},
+    style::{Style, Stylize},
+    text::{Line, Text},
+    widgets::{
+        Block, BorderType, Borders, Cell, Clear, Padding, Paragraph, Row, Table, TableState,
+    },
 };
-use std::time::Instant;
+use std::collections::HashSet;
+use tui_input::{Input, backend::crossterm::EventHandler};
 
 use crate::{Action, error::TuiError, records_buffer::BUFFER_SIZE};
 
 use super::{
     Component, ComponentName, Shortcut, State, issue_component::IssueComponent,
-    scroll_state::ScrollState,
+    root_component::global_key_bindings,
 };
 
 const TEN_MINUTES_FRAME: usize = 30 * 60 * 10;
 const REPOSITORY_URL: &str = concat!(
-    "      https://github.com/MAIF/yozefu/tree/v",
+    "https://github.com/MAIF/yozefu/tree/v",
     env!("CARGO_PKG_VERSION")
 );
 
+/// Maximum width of the first column, longer names are wrapped
+const MAX_NAME_WIDTH: usize = 48;
+
+/// A section of the help, made of tables.
+struct Section {
+    title: &'static str,
+    tables: Vec<HelpTable>,
+}
+
+/// A table of two columns, a name and its description.
+struct HelpTable {
+    header: [&'static str; 2],
+    rows: Vec<[String; 2]>,
+}
+
+impl HelpTable {
+    fn new<'a>(header: [&'static str; 2], rows: impl IntoIterator<Item = [&'a str; 2]>) -> Self {
+        Self {
+            header,
+            rows: rows
+                .into_iter()
+                .map(|[name, description]| [name.to_string(), description.to_string()])
+                .collect(),
+        }
+    }
+
+    /// The rows containing the filter, case insensitive.
+    fn matching_rows(&self, filter: &str) -> Vec<&[String; 2]> {
+        self.rows
+            .iter()
+            .filter(|row| row.iter().any(|cell| cell.to_lowercase().contains(filter)))
+            .collect()
+    }
+}
+
+/// A line of the table once the sections are flattened.
+enum HelpLine<'a> {
+    Section(&'static str, usize),
+    Header([&'static str; 2]),
+    Row(&'a [String; 2]),
+}
+
 #[derive(Default)]
 pub(crate) struct HelpComponent {
-    scroll: ScrollState,
+    state: TableState,
+    input: Input,
+    /// Titles of the collapsed sections
+    collapsed: HashSet<&'static str>,
+    /// Section of each line of the table, as drawn the last time
+    sections_of_lines: Vec<&'static str>,
     rendered: usize,
-    last_g_key: Option<Instant>,
 }
 
 impl HelpComponent {
-    fn truncate_str(rect: &Rect, s: &str) -> String {
-        let max_len = (rect.width as usize).checked_sub(84).unwrap_or(30);
-        if s.len() <= max_len {
-            s.to_string()
-        } else {
-            let idx = s
-                .char_indices()
-                .nth(max_len)
-                .map(|(i, _)| i)
-                .unwrap_or(s.len());
-            s[..idx].to_string()
+    fn sections(state: &State) -> Vec<Section> {
+        let consumer = state
+            .config
+            .specific
+            .config()
+            .consumer
+            .clone()
+            .unwrap_or_default();
+        let yozefu = vec![
+            [
+                "Export file".to_string(),
+                state.config.output_file().display().to_string(),
+            ],
+            ["Ring buffer capacity".to_string(), BUFFER_SIZE.to_string()],
+            [
+                "Stream buffer capacity".to_string(),
+                consumer.buffer_capacity.to_string(),
+            ],
+            [
+                "Stream buffer timeout (ms)".to_string(),
+                consumer.timeout_in_ms.to_string(),
+            ],
+            [
+                "Value preview size (bytes)".to_string(),
+                consumer.value_preview_size.to_string(),
+            ],
+        ];
+        let mut kafka = state
+            .config
+            .kafka_config_map()
+            .into_iter()
+            .filter(|(k, _)| !state.config.specific.config().is_sensitive(k))
+            .sorted_by(|a, b| a.0.cmp(&b.0))
+            .map(|(k, v)| [k, v])
+            .collect_vec();
+        if let Some(schema_registry) = state.config.specific.schema_registry() {
+            kafka.push([
+                "Schema registry".to_string(),
+                schema_registry.url.to_string(),
+            ]);
         }
+        let highlighter = state
+            .highlighter_theme
+            .as_ref()
+            .and_then(|t| t.name.clone())
+            .unwrap_or("disabled".to_string());
+
+        vec![
+            Section {
+                title: "Keys",
+                tables: vec![HelpTable::new(
+                    ["Key", "Description"],
+                    global_key_bindings()
+                        .iter()
+                        .map(|shortcut| [shortcut.key, shortcut.description]),
+                )],
+            },
+            Section {
+                title: "Query language",
+                tables: vec![
+                    HelpTable::new(
+                        ["Variable", "Description"],
+                        [
+                            ["topic, t", "Kafka topic, a string"],
+                            ["offset, o", "Offset of the record, a number"],
+                            ["key, k", "Key of the record"],
+                            ["value, v", "Value of the record"],
+                            ["partition, p", "Partition of the record, a number"],
+                            [
+                                "timestamp, ts",
+                                "Timestamp of the record, a RFC 3339 date such as 2025-06-01T12:00:00.000+02:00",
+                            ],
+                            ["size, si", "Size of the record, a number"],
+                            ["headers, h", "Headers of the record, a map of strings"],
+                            [
+                                "key.<path>, value.<path>",
+                                "JSON property of the key or the value, such as value.order.id",
+                            ],
+                            ["now()", "Current date, in milliseconds"],
+                            [
+                                ":<name>",
+                                "Placeholder of a saved query, its value is asked when the query runs",
+                            ],
+                        ],
+                    ),
+                    HelpTable::new(
+                        ["Operator", "Description"],
+                        [
+                            ["== | != | > | >= | < | <=", "Compare numbers or strings"],
+                            [
+                                "contains | ~=",
+                                "Test if the variable contains the specified string",
+                            ],
+                            [
+                                "starts with",
+                                "Test if the variable starts with the specified string",
+                            ],
+                            [
+                                "in (…) | not in (…)",
+                                "Test if the variable is equal to one of the listed values",
+                            ],
+                            [
+                                "+ | - | * | / | %",
+                                "Compute numbers, dates and durations before comparing them",
+                            ],
+                            [
+                                "len(…) | lower(…) | upper(…) | abs(…)",
+                                "Length of a string or an array, lowercase, uppercase, absolute value",
+                            ],
+                            [
+                                "500ms | 30s | 5m | 1h | 7d",
+                                "Durations, in milliseconds, to compare or to compute with dates",
+                            ],
+                        ],
+                    ),
+                    HelpTable::new(
+                        ["Clause", "Description"],
+                        [
+                            [
+                                "limit <number>",
+                                "Limit the number of kafka records to receive",
+                            ],
+                            [
+                                "from <begin|end|date|offset>",
+                                "Start consuming records from the beginning, the end or a date",
+                            ],
+                            ["order by <var> <asc|desc>", "Sort kafka records"],
+                            [
+                                "sample <number|percentage>",
+                                "Keep a random sample of the matching records",
+                            ],
+                            [
+                                "distinct by <key|value.path>",
+                                "Keep only the latest record for each key or JSON property",
+                            ],
+                        ],
+                    ),
+                    HelpTable::new(
+                        ["Example", "Description"],
+                        [
+                            [
+                                r#"timestamp >= "1 hours ago""#,
+                                "All records published within the last hour",
+                            ],
+                            [
+                                r#"v contains "rust" and partition == 2 from beginning limit 1000"#,
+                                "The first 1_000 kafka records from partition 2 containing 'rust' in the value",
+                            ],
+                            [
+                                r#"(key == "ABC") || (key ~= "XYZ") from end - 5000"#,
+                                r#"Among the latest 5_000 records, return the records where the key is "ABC" or the key contains "XYZ""#,
+                            ],
+                            [
+                                r#"value.hello == "world" order by key desc"#,
+                                r#"Any kafka JSON record with a JSON property "hello" with the value "world", sorted by key in descending order"#,
+                            ],
+                            [
+                                r#"value.status in ("FAILED", "TIMEOUT") and timestamp > now() - 1h"#,
+                                "The failed or timed out records published within the last hour",
+                            ],
+                            [
+                                "value.processedAt - timestamp > 30s",
+                                "The records processed more than 30 seconds after being published",
+                            ],
+                            [
+                                r#"len(value.items) > 5 and lower(key.tenant) == "acme""#,
+                                r#"The records with more than 5 items for the tenant "acme""#,
+                            ],
+                        ],
+                    ),
+                ],
+            },
+            Section {
+                title: "Configuration",
+                tables: vec![
+                    HelpTable {
+                        header: ["Yozefu", "Value"],
+                        rows: yozefu,
+                    },
+                    HelpTable {
+                        header: ["Kafka consumer", "Value"],
+                        rows: kafka,
+                    },
+                ],
+            },
+            Section {
+                title: "Paths",
+                tables: vec![HelpTable {
+                    header: ["Name", "Value"],
+                    rows: vec![
+                        [
+                            "Theme".to_string(),
+                            format!(
+                                "{}, run `yozf config get themes` to list available themes",
+                                state.theme.name
+                            ),
+                        ],
+                        ["Highlighter".to_string(), highlighter],
+                        [
+                            "Configuration".to_string(),
+                            state.configuration_file.display().to_string(),
+                        ],
+                        [
+                            "Logs".to_string(),
+                            state.workspace().log_file().display().to_string(),
+                        ],
+                        [
+                            "Filters".to_string(),
+                            state.workspace().filters_dir().display().to_string(),
+                        ],
+                        [
+                            "Themes".to_string(),
+                            state.workspace().themes_file().display().to_string(),
+                        ],
+                        ["Version".to_string(), REPOSITORY_URL.to_string()],
+                    ],
+                }],
+            },
+        ]
     }
+
+    /// Flattens the sections into the lines of the table.
+    /// While filtering, sections are expanded and only the matching rows are listed.
+    fn lines<'a>(&self, sections: &'a [Section]) -> Vec<(&'static str, HelpLine<'a>)> {
+        let filter = self.input.value().trim().to_lowercase();
+        let mut lines = vec![];
+        for section in sections {
+            let tables = section
+                .tables
+                .iter()
+                .map(|table| (table, table.matching_rows(&filter)))
+                .filter(|(_, rows)| !rows.is_empty())
+                .collect_vec();
+            let count = tables.iter().map(|(_, rows)| rows.len()).sum();
+            if count == 0 && !filter.is_empty() {
+                continue;
+            }
+            lines.push((section.title, HelpLine::Section(section.title, count)));
+            if filter.is_empty() && self.collapsed.contains(section.title) {
+                continue;
+            }
+            for (table, rows) in tables {
+                lines.push((section.title, HelpLine::Header(table.header)));
+                lines.extend(
+                    rows.into_iter()
+                        .map(|row| (section.title, HelpLine::Row(row))),
+                );
+            }
+        }
+        lines
+    }
+
+    fn toggle_selected_section(&mut self) {
+        let Some(section) = self
+            .state
+            .selected()
+            .and_then(|i| self.sections_of_lines.get(i))
+        else {
+            return;
+        };
+        if !self.collapsed.remove(section) {
+            self.collapsed.insert(section);
+        }
+        // Keep the header of the section selected, the lines below it have changed
+        self.state
+            .select(self.sections_of_lines.iter().position(|s| s == section));
+    }
+}
+
+/// Splits the text into lines of at most `width` characters, on spaces when possible.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = vec![];
+    let mut line = String::new();
+    for word in text.split(' ') {
+        let mut word = word.to_string();
+        while word.chars().count() > width {
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            let rest = word.chars().skip(width).collect::<String>();
+            lines.push(word.chars().take(width).collect());
+            word = rest;
+        }
+        match line.is_empty() {
+            true => line = word,
+            false if line.chars().count() + 1 + word.chars().count() <= width => {
+                line.push(' ');
+                line.push_str(&word);
+            }
+            false => lines.push(std::mem::replace(&mut line, word)),
+        }
+    }
+    lines.push(line);
+    lines
 }
 
 impl Component for HelpComponent {
@@ -54,45 +386,45 @@ impl Component for HelpComponent {
     }
 
     fn shortcuts(&self) -> Vec<Shortcut> {
-        vec![]
+        match self.input.value().is_empty() {
+            true => vec![
+                Shortcut::new("↑↓", "Select"),
+                Shortcut::new("ENTER", "Collapse/Expand"),
+                Shortcut::new("TYPE", "Search"),
+            ],
+            false => vec![Shortcut::new("↑↓", "Select")],
+        }
+    }
+
+    /// The search being written receives every key, `ESC` clears it.
+    fn is_typing(&self) -> bool {
+        !self.input.value().is_empty()
     }
 
     fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>, TuiError> {
         self.rendered = 0;
+        let last = self.sections_of_lines.len().saturating_sub(1);
         match key.code {
-            KeyCode::Char('j') | KeyCode::Down => {
-                self.scroll.scroll_to_next_line();
-                self.last_g_key = None;
-            }
-            KeyCode::Char('k') | KeyCode::Up => {
-                self.scroll.scroll_to_previous_line();
-                self.last_g_key = None;
-            }
-            KeyCode::Char('g') => {
-                if let Some(last_g) = self.last_g_key {
-                    if last_g.elapsed().as_millis() < 500 {
-                        // Double 'g' pressed - go to top
-                        self.scroll.scroll_to_top();
-                        self.last_g_key = None;
-                        return Ok(None);
-                    }
-                }
-                self.last_g_key = Some(Instant::now());
-            }
-            KeyCode::Char('G') => {
-                self.scroll.scroll_to_bottom();
-                self.last_g_key = None;
+            KeyCode::Up => self.state.select_previous(),
+            KeyCode::Down if self.state.selected() < Some(last) => self.state.select_next(),
+            KeyCode::PageUp => self.state.scroll_up_by(10),
+            KeyCode::PageDown => {
+                let selected = self.state.selected().unwrap_or(0);
+                self.state.select(Some((selected + 10).min(last)));
             }
-            KeyCode::Char('[') => {
-                self.scroll.scroll_to_top();
-                self.last_g_key = None;
-            }
-            KeyCode::Char(']') => {
-                self.scroll.scroll_to_bottom();
-                self.last_g_key = None;
+            KeyCode::Home => self.state.select_first(),
+            KeyCode::End => self.state.select(Some(last)),
+            KeyCode::Enter if self.input.value().is_empty() => self.toggle_selected_section(),
+            KeyCode::Esc if !self.input.value().is_empty() => {
+                self.input.reset();
+                self.state.select_first();
             }
+            KeyCode::Esc | KeyCode::Down | KeyCode::Enter => (),
             _ => {
-                self.last_g_key = None;
+                if !key.modifiers.contains(KeyModifiers::CONTROL) {
+                    self.input.handle_event(&Event::Key(key));
+                    self.state.select_first();
+                }
             }
         }
         Ok(None)
@@ -100,174 +432,167 @@ impl Component for HelpComponent {
 
     fn draw(&mut self, f: &mut Frame<'_>, rect: Rect, state: &State) -> Result<(), TuiError> {
         f.render_widget(Clear, rect);
+        let focused = state.is_focused(&self.id());
 
         let block = Block::default()
             .borders(Borders::ALL)
-            .padding(Padding::horizontal(2))
+            .padding(Padding::horizontal(1))
             .border_type(BorderType::Rounded)
             .title(" Help ");
-
         let block = self.make_block_focused_with_state(state, block);
+        let inner = block.inner(rect);
+        f.render_widget(block, rect);
 
-        let consumer_properties = state
-            .config
-            .kafka_config_map()
-            .iter()
-            .sorted_by(|a, b| a.0.cmp(b.0))
-            .filter_map(
-                |(k, v)| match SENSITIVE_KAFKA_PROPERTIES.contains(&k.as_str()) {
-                    false => Some(Line::from(vec![
-                        Span::raw(format!("{:>62}      ", k)),
-                        Span::from(Self::truncate_str(&rect, v)),
-                    ])),
-                    true => None,
-                },
-            )
-            .collect_vec();
+        let [filter_area, table_area] =
+            Layout::vertical([Constraint::Length(2), Constraint::Fill(1)]).areas(inner);
+        let filter = match self.input.value().is_empty() {
+            true => Line::from("Type to search, ENTER collapses or expands a section").italic(),
+            false => Line::from(format!("Search: {}", self.input.value())),
+        };
+        f.render_widget(Paragraph::new(filter), filter_area);
+        if focused && !self.input.value().is_empty() {
+            f.set_cursor_position(Position {
+                x: (filter_area.x + self.input.visual_cursor() as u16 + 8)
+                    .min(filter_area.right().saturating_sub(1)),
+                y: filter_area.y,
+            });
+        }
 
-        let yozefu_config = state
-            .config
-            .specific
-            .config()
-            .consumer
-            .clone()
-            .unwrap_or_default();
+        let sections = Self::sections(state);
+        let lines = self.lines(&sections);
+        let section_label = |title: &str, count: usize| {
+            let marker = match (
+                self.input.value().is_empty() && self.collapsed.contains(title),
+                state.accessible,
+            ) {
+                (true, false) => "▸",
+                (false, false) => "▾",
+                (true, true) => "[+]",
+                (false, true) => "[-]",
+            };
+            format!("{marker} {title} ({count})")
+        };
+        // Sections titles are rendered in the first column, it must be wide enough for them
+        let name_width = lines
+            .iter()
+            .map(|(_, line)| match line {
+                HelpLine::Section(title, count) => section_label(title, *count).chars().count(),
+                HelpLine::Header(header) => header[0].chars().count(),
+                HelpLine::Row(row) => row[0].chars().count(),
+            })
+            .max()
+            .unwrap_or(0)
+            .min(MAX_NAME_WIDTH)
+            .min(usize::from(table_area.width) / 2);
+        let description_width = usize::from(table_area.width).saturating_sub(name_width + 3);
 
-        let mut text = vec![
-            Line::from(Span::raw("")),
-            Line::from("                                                        Yozefu      Value")
-                .bold(),
-            Line::from(vec![
-                Span::raw(format!("{:>62}      ", "Export file")),
-                Span::from(state.config.output_file().display().to_string()),
-            ]),
-            Line::from(vec![
-                Span::raw(format!("{:>62}      ", "Ring buffer capacity")),
-                Span::from(BUFFER_SIZE.to_string()),
-            ]),
-            Line::from(vec![
-                Span::raw(format!("{:>62}      ", "Stream Buffer capacity")),
-                Span::from(yozefu_config.buffer_capacity.to_string()),
-            ]),
-            Line::from(vec![
-                Span::raw(format!("{:>62}      ", "Stream Buffer timeout (ms)")),
-                Span::from(yozefu_config.timeout_in_ms.to_string()),
-            ]),
-            Line::from(Span::raw("")),
-            Line::from("                                                Kafka consumer      Value")
-                .bold(),
-        ];
-        text.extend(consumer_properties);
+        let rows = lines.iter().enumerate().map(|(i, (_, line))| match line {
+            HelpLine::Section(title, count) => {
+                let row = Row::new(vec![Cell::new(
+                    Line::from(section_label(title, *count)).bold(),
+                )]);
+                match i {
+                    0 => row,
+                    _ => row.top_margin(1),
+                }
+            }
+            HelpLine::Header(header) => {
+                Row::new(header.map(|h| Cell::new(h).bold())).fg(state.theme.blue)
+            }
+            HelpLine::Row(row) => {
+                let name = wrap(&row[0], name_width);
+                let description = wrap(&row[1], description_width);
+                let height = name.len().max(description.len());
+                Row::new(vec![
+                    Cell::new(Text::from(name.join("\n"))),
+                    Cell::new(Text::from(description.join("\n"))),
+                ])
+                .height(u16::try_from(height).unwrap_or(1))
+            }
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(u16::try_from(name_width).unwrap_or(u16::MAX)),
+                Constraint::Fill(1),
+            ],
+        )
+        .column_spacing(3)
+        .row_highlight_style(match focused {
+            true => Style::default()
+                .bg(state.theme.bg_focused_selected)
+                .fg(state.theme.fg_focused_selected),
+            false => Style::default(),
+        });
 
-        if let Some(schema_registry) = state.config.specific.schema_registry() {
-            text.push(Line::from(vec![
-                Span::raw(format!("{:>62}      ", "Schema Registry")),
-                Span::from(Self::truncate_str(&rect, schema_registry.url.as_str())),
-            ]));
+        self.sections_of_lines = lines.iter().map(|(section, _)| *section).collect();
+        if self.state.selected().is_none_or(|s| s >= lines.len()) {
+            self.state.select((!lines.is_empty()).then_some(0));
         }
-
-        text.extend(vec![
-            Line::from(Span::raw("")),
-            Line::from("                                                           Key      Description").bold(),
-            Line::from("                                                             /      Focus search input"),
-            Line::from("                                                           ESC      Close the window/app"),
-            Line::from("                                                           TAB      Focus next window"),
-            Line::from("                                                   SHIFT + TAB      Focus previous window"),
-            Line::from(""),
-
-            Line::from("                                                      Variable      Type                        Alias       Description").bold(),
-            Line::from(vec![Span::raw("                                                         topic      "), Span::from("String").fg(state.theme.green), Span::from("                          t").fg(state.theme.blue), Span::from("        Kafka topic")]),
-            Line::from(vec![Span::raw("                                                        offset      "), Span::from("Number").fg(state.theme.green), Span::from("                          o").fg(state.theme.blue), Span::from("       Offset of the record")]),
-            Line::from(vec![Span::raw("                                                           key      "), Span::from(""), Span::from("                                k").fg(state.theme.blue), Span::from("       Key of the record")]),
-            Line::from(vec![Span::raw("                                                         value      "), Span::from(""), Span::from("                                v").fg(state.theme.blue), Span::from("       Value of the record")]),
-            Line::from(vec![Span::raw("                                                     partition      "), Span::from("Number").fg(state.theme.green), Span::from("                          p").fg(state.theme.blue), Span::from("       Partition of the record")]),
-            Line::from(vec![Span::raw("                                                     timestamp      "), Span::from("String").fg(state.theme.green), Span::from("                         ts").fg(state.theme.blue), Span::from("       Timestamp of the record (RFC 3339) → 2025-06-01T12:00:00.000+02:00")]),
-            Line::from(vec![Span::raw("                                                          size      "), Span::from("Number").fg(state.theme.green), Span::from("                         si").fg(state.theme.blue), Span::from("       Size of the record")]),
-            Line::from(vec![Span::raw("                                                       headers      "), Span::from("Map<String, String>").fg(state.theme.green), Span::from("             h").fg(state.theme.blue), Span::from("       Headers of the record")]),
-            Line::from(Span::raw("")),
-
-            Line::from(vec![Span::from("                                                      Operator"), Span::from("      Type").fg(state.theme.green), Span::from("                                    Description").bold()]),
-            Line::from(vec![Span::from("                                     == | != | > | >= | < | <="), Span::from("      Number | String").fg(state.theme.green), Span::from("                         Wayne's world, party time! Excellent!")]),
-            Line::from(vec![Span::from("                                                 contains | ~="), Span::from("      String").fg(state.theme.green), Span::from("                                  Test if the variable contains the specified string")]),
-            Line::from(vec![Span::from("                                                   starts with"), Span::from("      String").fg(state.theme.green), Span::from("                                  Test if the variable starts with the specified string")]),
-            Line::from(""),
-
-
-            Line::from(vec![Span::from("                                                        Clause      Syntax                                  Description").bold()]),
-            Line::from(vec![Span::from("                                                         limit      limit <"), Span::from("number").fg(state.theme.yellow), Span::from(">                          Limit the number of kafka records to receive")]),
-            Line::from(vec![Span::from("                                                          from      from <"), Span::from("begin").fg(state.theme.yellow), Span::from("|"), Span::from("end").fg(state.theme.yellow), Span::from("|"), Span::from("date").fg(state.theme.yellow), Span::from("|"), Span::from("offset").fg(state.theme.yellow), Span::from(">            Start consuming records from the beginning, the end or a date")]),
-            Line::from(vec![Span::from("                                                      order by      order by <"), Span::from("var").fg(state.theme.yellow), Span::from("> <"), Span::from("asc").fg(state.theme.yellow), Span::from("|"), Span::from("desc").fg(state.theme.yellow), Span::from(">               Sort kafka records")]),
-            Line::from(""),
-
-            Line::from("                                                         Input      Description").bold(),
-            Line::from(r#"                                    timestamp >= "1 hours ago"      All records published within the last hour"#),
-            Line::from(r#"v contains "rust" and partition == 2 from beginning limit 1000      The first 1_000 kafka records from partition 2 containing 'rust' in the value"#),
-            Line::from(r#"              (key == "ABC") || (key ~= "XYZ") from end - 5000      Among the latest 5_000 records, return the records where the key is "ABC" or the key contains "XYZ""#),
-            Line::from(r#"                      value.hello == "world" order by key desc      Any kafka JSON record with a JSON property "hello" with the value "world", sorted by key in descending order"#),
-            Line::from(""),
-            Line::from(vec![
-                Span::from("                                                         Theme").bold(),
-                Span::from(format!(
-                                        "      Theme is '{}'. run `yozf config get themes` to list available themes.",
-                                        state.theme.name
-                                    ))
-            ]),
-            Line::from(vec![
-                Span::from("                                                   Highlighter").bold(),
-                Span::from(format!(
-                                        "      {}",
-                                        match state.highlighter_theme {
-                                            Some(ref theme) => theme.name.as_deref(),
-                                            None => Some("unknown"),
-                                        }.unwrap()
-                                    ))
-            ]),
-            Line::from(vec![
-                Span::from("                                                 Configuration").bold(),
-                Span::from(format!("      '{}'", state.configuration_file.display()))
-            ]),
-            Line::from(vec![
-                Span::from("                                                          Logs").bold(),
-                Span::from(format!("      '{}'", state.workspace().log_file().display()))
-            ]),
-            Line::from(vec![
-                Span::from("                                                       Filters").bold(),
-                Span::from(format!("      '{}'", state.workspace().filters_dir().display()))
-            ]),
-            Line::from(vec![
-                Span::from("                                                        Themes").bold(),
-                Span::from(format!("      '{}'", state.workspace().themes_file().display()))
-            ]),
-            Line::from(vec![
-                Span::from("                                                       Version").bold(),
-                Span::from(REPOSITORY_URL)
-            ]),
-            Line::from(""),
-        ]);
-
-        let number_of_lines = text.len();
-        let paragraph = Paragraph::new(text)
-            .wrap(Wrap { trim: false })
-            .scroll((self.scroll.value(), 0));
-        f.render_widget(paragraph.block(block), rect);
+        f.render_stateful_widget(table, table_area, &mut self.state);
 
         if self.rendered > TEN_MINUTES_FRAME {
             let mut issue = IssueComponent::default();
             issue.draw(f, rect, state)?;
         }
-
-        self.scroll.draw(f, rect, number_of_lines + 2);
         self.rendered += 1;
 
         Ok(())
     }
 }
 
+#[test]
+fn test_wrap() {
+    assert_eq!(
+        wrap("Kafka topic, a string", 12),
+        vec!["Kafka topic,", "a string"]
+    );
+    assert_eq!(wrap("abcdefgh", 3), vec!["abc", "def", "gh"]);
+    assert_eq!(wrap("", 3), vec![""]);
+}
+
 #[cfg(test)]
 use crate::assert_draw;
 
 #[test]
 fn test_draw() {
     let mut component = HelpComponent::default();
-    assert_draw!(component, 300, 60)
+    component.collapsed.extend(["Query language", "Paths"]);
+    assert_draw!(component, 100, 40)
+}
+
+#[test]
+fn test_draw_search() {
+    let mut component = HelpComponent {
+        input: Input::new("theme".to_string()),
+        ..Default::default()
+    };
+    assert_draw!(component, 80, 16)
+}
+
+#[test]
+fn test_query_examples_parse() {
+    let sections = HelpComponent::sections(&super::default_state());
+    let examples = sections
+        .iter()
+        .flat_map(|s| &s.tables)
+        .filter(|t| t.header[0] == "Example")
+        .flat_map(|t| &t.rows);
+    for [example, _] in examples {
+        let (remaining, _) = lib::parse_search_query(example).unwrap();
+        assert_eq!(remaining.trim(), "", "{example}");
+    }
+}
+
+#[test]
+fn test_clear_search() {
+    let mut component = HelpComponent {
+        input: Input::new("theme".to_string()),
+        ..Default::default()
+    };
+    assert!(component.is_typing());
+    component
+        .handle_key_events(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE))
+        .unwrap();
+    assert!(!component.is_typing());
 }
@@ -0,0 +1,108 @@
+//! Overlay opened with `?`, it lists the key bindings of the focused component only.
+//! Any key closes it.
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    text::Line,
+    widgets::{Block, BorderType, Borders, Clear, Padding, Row, Table},
+};
+
+use crate::error::TuiError;
+
+use super::{Component, ComponentName, Shortcut, State};
+
+pub(crate) struct KeyBindingsComponent {
+    /// The component the key bindings belong to
+    component: ComponentName,
+    bindings: Vec<Shortcut>,
+}
+
+impl KeyBindingsComponent {
+    pub fn new(component: ComponentName, bindings: Vec<Shortcut>) -> Self {
+        Self {
+            component,
+            bindings,
+        }
+    }
+}
+
+impl Component for KeyBindingsComponent {
+    fn id(&self) -> ComponentName {
+        ComponentName::KeyBindings
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect, state: &State) -> Result<(), TuiError> {
+        let title = format!(" Key bindings of {} ", self.component.label());
+        let key_width = self
+            .bindings
+            .iter()
+            .map(|b| b.key.chars().count())
+            .max()
+            .unwrap_or(0);
+        let description_width = self
+            .bindings
+            .iter()
+            .map(|b| b.description.chars().count())
+            .max()
+            .unwrap_or(0);
+        // Borders, paddings and the spacing between the columns
+        let width = (key_width + description_width + 3 + 4).max(title.chars().count() + 4);
+        let height = self.bindings.len().max(1) + 2;
+
+        let [area] =
+            Layout::horizontal([Constraint::Length(u16::try_from(width).unwrap_or(u16::MAX))])
+                .flex(Flex::Center)
+                .areas(rect);
+        let [area] = Layout::vertical([Constraint::Length(
+            u16::try_from(height).unwrap_or(u16::MAX),
+        )])
+        .flex(Flex::Center)
+        .areas(area);
+
+        f.render_widget(Clear, area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .padding(Padding::horizontal(1))
+            .title(title)
+            .title_bottom(Line::from(" Press any key to close ").right_aligned());
+        let block = self.make_block_focused(state, block);
+
+        let rows = match self.bindings.is_empty() {
+            true => vec![Row::new(vec!["", "No key bindings"]).italic()],
+            false => self
+                .bindings
+                .iter()
+                .map(|b| Row::new(vec![b.key.bold(), b.description.into()]))
+                .collect(),
+        };
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(u16::try_from(key_width).unwrap_or(u16::MAX)),
+                Constraint::Fill(1),
+            ],
+        )
+        .column_spacing(3)
+        .block(block);
+        f.render_widget(table, area);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+use crate::assert_draw;
+
+#[test]
+fn test_draw() {
+    let mut component = KeyBindingsComponent::new(
+        ComponentName::Topics,
+        vec![
+            Shortcut::new("↑↓", "Previous/next topic"),
+            Shortcut::new("ENTER", "Select/unselect the topic"),
+            Shortcut::new("CTRL + P", "Show the details of the topics"),
+        ],
+    );
+    assert_draw!(component, 60, 9)
+}
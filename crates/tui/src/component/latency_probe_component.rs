@@ -0,0 +1,303 @@
+//! Component drawing the end-to-end latency of the cluster over time,
+//! measured by producing canary records to the selected topic and consuming them back, see [`app::App::probe_latency`].
+//! Starting the probe must be confirmed since it produces to the cluster.
+use std::time::Duration;
+
+use app::{LatencySample, LatencyStatistics, configuration::Units};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    symbols::Marker,
+    text::{Line, Span},
+    widgets::{Axis, Block, Borders, Chart, Clear, Dataset, GraphType, Padding, Paragraph, Wrap},
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{
+    Component, ComponentName, Shortcut, State,
+    confirmation::{Confirmation, Confirmed},
+};
+use crate::{Action, error::TuiError};
+
+/// Number of samples kept, 10 minutes with a canary record per second
+const HISTORY: usize = 600;
+
+pub(crate) struct LatencyProbeComponent {
+    statistics: LatencyStatistics,
+    /// Topic the canary records are produced to, while the probe is running
+    topic: Option<String>,
+    /// Input confirming the start of the probe
+    confirmation: Option<Confirmation>,
+    action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl Default for LatencyProbeComponent {
+    fn default() -> Self {
+        Self {
+            statistics: LatencyStatistics::new(HISTORY),
+            topic: None,
+            confirmation: None,
+            action_tx: None,
+        }
+    }
+}
+
+impl LatencyProbeComponent {
+    fn latency(latency: Option<Duration>, units: &Units) -> String {
+        match latency {
+            Some(latency) => units.format_duration(latency),
+            None => "-".to_string(),
+        }
+    }
+
+    fn summary(&self, state: &State) -> Line<'_> {
+        let label = |label: &'static str| Span::from(format!("{label} ")).bold();
+        let status = match &self.topic {
+            Some(topic) => Span::from(format!("Probing '{topic}'"))
+                .style(Style::default().fg(state.theme.green)),
+            None => Span::from("Stopped").style(Style::default().fg(state.theme.orange)),
+        };
+        Line::from(vec![
+            status,
+            Span::from("   "),
+            label("Samples"),
+            Span::from(self.statistics.len().to_string()),
+            Span::from("   "),
+            label("p50"),
+            Span::from(Self::latency(self.statistics.percentile(50.), &state.units)),
+            Span::from("   "),
+            label("p95"),
+            Span::from(Self::latency(self.statistics.percentile(95.), &state.units)),
+            Span::from("   "),
+            label("p99"),
+            Span::from(Self::latency(self.statistics.percentile(99.), &state.units)),
+            Span::from("   "),
+            label("Max"),
+            Span::from(Self::latency(self.statistics.max(), &state.units)),
+        ])
+    }
+
+    /// Latency of each sample in milliseconds, by seconds elapsed since the first sample
+    fn points(&self) -> Vec<(f64, f64)> {
+        let start = self
+            .statistics
+            .samples()
+            .next()
+            .map(|s| s.timestamp)
+            .unwrap_or_default();
+        self.statistics
+            .samples()
+            .map(|s| {
+                (
+                    (s.timestamp - start) as f64 / 1000.,
+                    s.latency.as_secs_f64() * 1000.,
+                )
+            })
+            .collect()
+    }
+
+    fn push(&mut self, sample: LatencySample) {
+        self.statistics.push(sample);
+    }
+
+    fn toggle(&self, confirmation: String) -> Result<(), TuiError> {
+        self.action_tx
+            .as_ref()
+            .unwrap()
+            .send(Action::ToggleLatencyProbe(confirmation))?;
+        Ok(())
+    }
+}
+
+impl Component for LatencyProbeComponent {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) {
+        self.action_tx = Some(tx);
+    }
+
+    fn id(&self) -> ComponentName {
+        ComponentName::LatencyProbe
+    }
+
+    /// The confirmation receives every key.
+    fn is_typing(&self) -> bool {
+        self.confirmation.is_some()
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>, TuiError> {
+        if let Some(confirmation) = self.confirmation.as_mut() {
+            if let Some(confirmed) = confirmation.handle_key_events(key) {
+                self.confirmation = None;
+                if let Confirmed::Yes(confirmation) = confirmed {
+                    self.toggle(confirmation)?;
+                }
+                self.action_tx
+                    .as_ref()
+                    .unwrap()
+                    .send(Action::RefreshShortcuts)?;
+            }
+            return Ok(None);
+        }
+        if key.code == KeyCode::Enter {
+            match self.topic {
+                Some(_) => self.toggle(String::new())?,
+                None => {
+                    self.confirmation = Some(Confirmation::default());
+                    self.action_tx
+                        .as_ref()
+                        .unwrap()
+                        .send(Action::RefreshShortcuts)?;
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>, TuiError> {
+        match action {
+            Action::LatencySample(sample) => self.push(sample),
+            Action::LatencyProbe(topic) => {
+                if topic.is_some() {
+                    self.statistics = LatencyStatistics::new(HISTORY);
+                }
+                self.topic = topic;
+                if let Some(action_tx) = &self.action_tx {
+                    action_tx.send(Action::RefreshShortcuts)?;
+                }
+            }
+            _ => (),
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect, state: &State) -> Result<(), TuiError> {
+        f.render_widget(Clear, rect);
+        let block = Block::new()
+            .borders(Borders::ALL)
+            .padding(Padding::symmetric(2, 1))
+            .title(" Latency probe ");
+        let block = self.make_block_focused_with_state(state, block);
+        let inner = block.inner(rect);
+        f.render_widget(block, rect);
+
+        let inner = match &self.confirmation {
+            Some(confirmation) => {
+                let [confirmation_area, inner] =
+                    Layout::vertical([Constraint::Length(3), Constraint::Fill(1)]).areas(inner);
+                confirmation.draw(f, confirmation_area, "start the probe", state);
+                inner
+            }
+            None => inner,
+        };
+
+        if self.statistics.is_empty() {
+            let message = match &self.topic {
+                Some(topic) => format!("Waiting for the first canary record of '{topic}'..."),
+                None => "ENTER produces a canary record every second to the selected topic and measures the time it takes to consume it back.".to_string(),
+            };
+            f.render_widget(
+                Paragraph::new(message).italic().wrap(Wrap { trim: true }),
+                inner,
+            );
+            return Ok(());
+        }
+
+        let [summary_area, chart_area] =
+            Layout::vertical([Constraint::Length(2), Constraint::Fill(1)]).areas(inner);
+        f.render_widget(Paragraph::new(self.summary(state)), summary_area);
+
+        let points = self.points();
+        let duration = points.last().map(|p| p.0).unwrap_or_default().max(1.);
+        let max = self
+            .statistics
+            .max()
+            .map(|m| m.as_secs_f64() * 1000.)
+            .unwrap_or_default()
+            .max(1.);
+        let dataset = Dataset::default()
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(state.theme.blue))
+            .data(&points);
+        let chart = Chart::new(vec![dataset])
+            .x_axis(
+                Axis::default()
+                    .title("seconds")
+                    .bounds([0., duration])
+                    .labels(["0".to_string(), format!("{duration:.0}")]),
+            )
+            .y_axis(
+                Axis::default()
+                    .title("ms")
+                    .bounds([0., max * 1.1])
+                    .labels(["0".to_string(), format!("{max:.0}")]),
+            );
+        f.render_widget(chart, chart_area);
+        Ok(())
+    }
+
+    fn shortcuts(&self) -> Vec<Shortcut> {
+        if self.confirmation.is_some() {
+            return vec![
+                Shortcut::new("ENTER", "Confirm"),
+                Shortcut::new("ESC", "Cancel"),
+            ];
+        }
+        match self.topic {
+            Some(_) => vec![Shortcut::new("ENTER", "Stop")],
+            None => vec![Shortcut::new("ENTER", "Start")],
+        }
+    }
+}
+
+#[cfg(test)]
+use crate::assert_draw;
+
+#[test]
+fn test_draw() {
+    let mut component = LatencyProbeComponent::default();
+    component
+        .update(Action::LatencyProbe(Some("healthcheck".to_string())))
+        .unwrap();
+    for (sequence, latency) in [12, 15, 11, 40, 13, 12].into_iter().enumerate() {
+        component.push(LatencySample {
+            sequence: sequence as u64,
+            timestamp: 1_000 * sequence as i64,
+            latency: Duration::from_millis(latency),
+        });
+    }
+    assert_draw!(component, 90, 16)
+}
+
+#[test]
+fn test_confirm_start() {
+    use crossterm::event::KeyModifiers;
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut component = LatencyProbeComponent::default();
+    component.register_action_handler(tx);
+    let press = |component: &mut LatencyProbeComponent, code| {
+        component
+            .handle_key_events(KeyEvent::new(code, KeyModifiers::NONE))
+            .unwrap();
+    };
+
+    press(&mut component, KeyCode::Enter);
+    assert!(component.is_typing());
+    press(&mut component, KeyCode::Esc);
+    assert!(!component.is_typing());
+
+    press(&mut component, KeyCode::Enter);
+    for c in "localhost".chars() {
+        press(&mut component, KeyCode::Char(c));
+    }
+    press(&mut component, KeyCode::Enter);
+    let toggles = std::iter::from_fn(|| rx.try_recv().ok())
+        .filter_map(|action| match action {
+            Action::ToggleLatencyProbe(confirmation) => Some(confirmation),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(toggles, vec!["localhost".to_string()]);
+}
@@ -1,8 +1,20 @@
+mod broker_configs_component;
+mod command_palette_component;
+mod confirmation;
+mod consumer_errors_component;
+mod consumer_groups_component;
+mod dead_letters_component;
+mod field_statistics_component;
 mod footer_component;
 mod header_component;
+mod header_editor;
 mod help_component;
 mod issue_component;
+mod key_bindings_component;
+mod latency_probe_component;
+mod produce_component;
 mod progress_bar_component;
+mod query_plan_component;
 mod record_details_component;
 mod records_component;
 mod root_component;
@@ -11,7 +23,10 @@ mod scroll_state;
 mod search_component;
 mod shortcut;
 mod state;
+mod statistics_component;
 mod styles;
+mod table_component;
+mod timeline_component;
 mod topic_details_component;
 mod topics_and_records_component;
 mod topics_component;
@@ -27,6 +42,7 @@ use ratatui::{
     Frame,
     layout::Rect,
     style::Style,
+    text::Line,
     widgets::{Block, BorderType},
 };
 pub(crate) use root_component::RootComponent;
@@ -63,6 +79,19 @@ pub(crate) enum ComponentName {
     Dialog,
     Help,
     Schemas,
+    ConsumerErrors,
+    Statistics,
+    Table,
+    FieldStatistics,
+    Produce,
+    LatencyProbe,
+    BrokerConfigs,
+    QueryPlan,
+    CommandPalette,
+    ConsumerGroups,
+    KeyBindings,
+    DeadLetters,
+    Timeline,
 }
 
 impl ComponentName {
@@ -70,6 +99,16 @@ impl ComponentName {
         match &self {
             ComponentName::RecordDetails => "Record".to_string(),
             ComponentName::TopicDetails => "Topic".to_string(),
+            ComponentName::ConsumerErrors => "Connection".to_string(),
+            ComponentName::FieldStatistics => "Field statistics".to_string(),
+            ComponentName::LatencyProbe => "Latency probe".to_string(),
+            ComponentName::BrokerConfigs => "Broker configs".to_string(),
+            ComponentName::QueryPlan => "Query plan".to_string(),
+            ComponentName::CommandPalette => "Command palette".to_string(),
+            ComponentName::ConsumerGroups => "Consumer groups".to_string(),
+            ComponentName::KeyBindings => "Key bindings".to_string(),
+            ComponentName::DeadLetters => "Dead letters".to_string(),
+            ComponentName::Timeline => "Timeline".to_string(),
             _ => self.to_string(),
         }
     }
@@ -94,10 +133,15 @@ pub(crate) trait Component: Send + Sync {
     }
 
     fn make_block_focused<'a>(&self, state: &State, block: Block<'a>) -> Block<'a> {
-        block
+        let block = block
             .border_style(Style::default().fg(state.theme.focused_border))
             .border_type(BorderType::Thick)
-            .title_style(Style::default().bold())
+            .title_style(Style::default().bold());
+        // The thick border is not enough for a screen reader
+        match state.accessible {
+            true => block.title_top(Line::from(" [focused] ").right_aligned()),
+            false => block,
+        }
     }
 
     fn init(&mut self) -> Result<(), TuiError> {
@@ -117,6 +161,12 @@ pub(crate) trait Component: Send + Sync {
         Ok(None)
     }
 
+    /// Returns `true` while the user types some text in the component,
+    /// the global key bindings (`ESC`, `?`, `/`...) are then sent to the component.
+    fn is_typing(&self) -> bool {
+        false
+    }
+
     fn handle_mouse_events(&mut self, _mouse: MouseEvent) -> Result<Option<Action>, TuiError> {
         Ok(None)
     }
@@ -130,6 +180,12 @@ pub(crate) trait Component: Send + Sync {
     fn shortcuts(&self) -> Vec<Shortcut> {
         vec![]
     }
+
+    /// All the key bindings of the component, listed in the overlay opened with `?`.
+    /// The footer only has room for the most useful ones, returned by [`Component::shortcuts`].
+    fn key_bindings(&self) -> Vec<Shortcut> {
+        self.shortcuts()
+    }
 }
 
 #[cfg(test)]
@@ -141,7 +197,7 @@ pub mod root_component_test;
 
 #[cfg(test)]
 pub fn default_workspace() -> Workspace {
-    use app::configuration::{ConsumerConfig, Workspace};
+    use app::configuration::{ConsumerConfig, ExportConfig, Workspace};
 
     let temp_dir = tempfile::tempdir().unwrap();
     let temp_path = temp_dir.path().to_path_buf();
@@ -155,12 +211,26 @@ pub fn default_workspace() -> Workspace {
             initial_query: String::new(),
             theme: "light".to_string(),
             highlighter_theme: None,
+            highlighter: app::configuration::HighlighterConfig::default(),
+            layout: app::configuration::LayoutPreset::default(),
+            color_support: None,
+            clipboard: None,
+            units: app::configuration::Units::default(),
+            refresh_rates: app::configuration::RefreshRates::default(),
             clusters: indexmap::IndexMap::default(),
             default_kafka_config: indexmap::IndexMap::default(),
+            client_id: "yozefu-{cluster}".to_string(),
             history: vec![],
             show_shortcuts: true,
+            accessible: false,
             export_directory: std::path::PathBuf::from(""),
+            export: ExportConfig::default(),
             consumer: ConsumerConfig::default(),
+            anonymization_profiles: indexmap::IndexMap::default(),
+            alerts: vec![],
+            notifiers: indexmap::IndexMap::default(),
+            macros: indexmap::IndexMap::default(),
+            queries: indexmap::IndexMap::default(),
         },
         temp_path.join(Workspace::LOGS_FILENAME),
     )
@@ -0,0 +1,459 @@
+//! Component listing the NDJSON, CSV and template files of the current directory,
+//! the selected file is produced to the selected topic, see [`app::read_records`] and [`app::RecordTemplate`].
+//! The headers of the records can be edited before they are sent, see [`app::HeaderEdit`].
+//! The production is confirmed before the records are sent.
+use std::{
+    num::NonZeroU32,
+    path::{Path, PathBuf},
+};
+
+use app::{RecordMapping, is_template};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Position, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Borders, Clear, List, ListState, Padding, Paragraph, Wrap},
+};
+use tokio::sync::mpsc::UnboundedSender;
+use tui_input::{Input, backend::crossterm::EventHandler};
+
+use super::{
+    Component, ComponentName, Shortcut, State,
+    confirmation::{Confirmation, Confirmed},
+    header_editor::{HEADER_INPUT_TITLE, HeaderEditor},
+};
+use crate::{
+    Action,
+    action::{Level, Notification, ProduceRequest},
+    error::TuiError,
+};
+
+/// Extensions of the files that can be produced
+const EXTENSIONS: [&str; 4] = ["ndjson", "jsonl", "csv", "template"];
+
+/// Number of inputs: the key, the value, the header fields, the count, the rate and the header edits
+const INPUTS: usize = 6;
+
+pub(crate) struct ProduceComponent {
+    directory: PathBuf,
+    files: Vec<PathBuf>,
+    state: ListState,
+    /// Field of each row used as the key of the records
+    key: Input,
+    /// Field of each row used as the value of the records, the whole row when empty
+    value: Input,
+    /// Headers read from the fields of each row, comma separated `<name>=<field>` or `<field>`
+    header_fields: Input,
+    /// Number of records generated from a template
+    count: Input,
+    /// Maximum number of records produced per second, unlimited when empty
+    rate: Input,
+    /// Edits applied to the headers of each record before it is produced
+    headers: HeaderEditor,
+    /// Index of the input receiving the key events, see [`INPUTS`]
+    selected_input: usize,
+    /// Confirmation of the production being written
+    confirmation: Option<Confirmation>,
+    action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl Default for ProduceComponent {
+    fn default() -> Self {
+        Self {
+            directory: PathBuf::default(),
+            files: vec![],
+            state: ListState::default(),
+            key: Input::default(),
+            value: Input::default(),
+            header_fields: Input::default(),
+            count: Input::new("100".to_string()),
+            rate: Input::default(),
+            headers: HeaderEditor::default(),
+            selected_input: 0,
+            confirmation: None,
+            action_tx: None,
+        }
+    }
+}
+
+impl ProduceComponent {
+    /// Lists the files that can be produced in the directory, sorted by name.
+    fn list(&mut self, directory: &Path) {
+        self.directory = directory.to_path_buf();
+        self.files = std::fs::read_dir(directory)
+            .map(|entries| {
+                entries
+                    .filter_map(Result::ok)
+                    .map(|e| e.path())
+                    .filter(|p| {
+                        p.is_file()
+                            && p.extension()
+                                .and_then(|e| e.to_str())
+                                .is_some_and(|e| EXTENSIONS.contains(&e.to_lowercase().as_str()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.files.sort();
+        self.state.select(match self.files.is_empty() {
+            true => None,
+            false => Some(0),
+        });
+    }
+
+    /// Produces the selected file, `confirmation` is the text typed to confirm it.
+    fn produce(&self, confirmation: String) -> Result<(), TuiError> {
+        let Some(file) = self.state.selected().and_then(|i| self.files.get(i)) else {
+            return Ok(());
+        };
+        let action_tx = self.action_tx.as_ref().unwrap();
+        let count = self.count.value().trim();
+        let Ok(count) = count.parse::<usize>() else {
+            action_tx.send(Action::Notification(Notification::new(
+                Level::Warn,
+                format!("'{count}' is not a valid number of records"),
+            )))?;
+            return Ok(());
+        };
+        let rate = match self.rate.value().trim() {
+            "" => None,
+            rate => match rate.parse::<NonZeroU32>() {
+                Ok(rate) => Some(rate),
+                Err(_) => {
+                    action_tx.send(Action::Notification(Notification::new(
+                        Level::Warn,
+                        format!("'{rate}' is not a valid number of records per second"),
+                    )))?;
+                    return Ok(());
+                }
+            },
+        };
+        let field = |input: &Input| {
+            let field = input.value().trim();
+            (!field.is_empty()).then(|| field.to_string())
+        };
+        let mapping = RecordMapping {
+            key: field(&self.key),
+            value: field(&self.value),
+            headers: self
+                .header_fields
+                .value()
+                .split(',')
+                .filter(|h| !h.trim().is_empty())
+                .map(RecordMapping::parse_header)
+                .collect(),
+        };
+        action_tx.send(Action::Produce(ProduceRequest {
+            file: file.clone(),
+            mapping,
+            count,
+            rate,
+            header_edits: self.headers.edits.clone(),
+            confirmation,
+        }))?;
+        Ok(())
+    }
+
+    /// Adds the header edit being written to the table, see [`HeaderEditor::add`].
+    fn add_header_edit(&mut self) -> Result<(), TuiError> {
+        if let Err(e) = self.headers.add() {
+            self.action_tx
+                .as_ref()
+                .unwrap()
+                .send(Action::Notification(Notification::new(
+                    Level::Warn,
+                    e.to_string(),
+                )))?;
+        }
+        Ok(())
+    }
+
+    fn inputs(&self) -> [&Input; INPUTS] {
+        [
+            &self.key,
+            &self.value,
+            &self.header_fields,
+            &self.count,
+            &self.rate,
+            &self.headers.input,
+        ]
+    }
+
+    fn input(&mut self) -> &mut Input {
+        match self.selected_input {
+            0 => &mut self.key,
+            1 => &mut self.value,
+            2 => &mut self.header_fields,
+            3 => &mut self.count,
+            4 => &mut self.rate,
+            _ => &mut self.headers.input,
+        }
+    }
+
+    /// Whether the input of the header edits is selected.
+    fn header_edit_selected(&self) -> bool {
+        self.selected_input == INPUTS - 1
+    }
+
+    fn handle_confirmation_key_events(&mut self, key: KeyEvent) -> Result<(), TuiError> {
+        let Some(confirmed) = self
+            .confirmation
+            .as_mut()
+            .and_then(|c| c.handle_key_events(key))
+        else {
+            return Ok(());
+        };
+        self.confirmation = None;
+        if let Confirmed::Yes(confirmation) = confirmed {
+            self.produce(confirmation)?;
+        }
+        Ok(())
+    }
+
+    fn draw_input(&self, f: &mut Frame<'_>, rect: Rect, index: usize, title: &str, state: &State) {
+        let input = self.inputs()[index];
+        let mut block = Block::default()
+            .title(title)
+            .padding(Padding::left(1))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded);
+        if index == self.selected_input {
+            block = block.border_style(Style::default().fg(state.theme.focused_border));
+            if state.is_focused(&self.id()) {
+                f.set_cursor_position(Position {
+                    x: (rect.x + input.visual_cursor() as u16 + 2)
+                        .min(rect.right().saturating_sub(2)),
+                    y: rect.y + 1,
+                });
+            }
+        }
+        f.render_widget(Paragraph::new(input.value()).block(block), rect);
+    }
+}
+
+impl Component for ProduceComponent {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) {
+        self.action_tx = Some(tx);
+    }
+
+    fn id(&self) -> ComponentName {
+        ComponentName::Produce
+    }
+
+    /// The confirmation and the input being written receive every key.
+    fn is_typing(&self) -> bool {
+        self.confirmation.is_some() || !self.inputs()[self.selected_input].value().is_empty()
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>, TuiError> {
+        if self.confirmation.is_some() {
+            self.handle_confirmation_key_events(key)?;
+            return Ok(None);
+        }
+        match key.code {
+            KeyCode::Up => self.state.select_previous(),
+            KeyCode::Down if self.state.selected() < Some(self.files.len().saturating_sub(1)) => {
+                self.state.select_next();
+            }
+            KeyCode::Enter
+                if self.header_edit_selected() && !self.headers.input.value().is_empty() =>
+            {
+                self.add_header_edit()?;
+            }
+            KeyCode::Enter if self.state.selected().is_some() => {
+                self.confirmation = Some(Confirmation::default());
+            }
+            KeyCode::Backspace
+                if self.header_edit_selected() && self.headers.input.value().is_empty() =>
+            {
+                self.headers.pop();
+            }
+            KeyCode::Right if key.modifiers.contains(KeyModifiers::SHIFT) => {
+                self.selected_input = (self.selected_input + 1) % INPUTS;
+            }
+            KeyCode::Left if key.modifiers.contains(KeyModifiers::SHIFT) => {
+                self.selected_input = (self.selected_input + INPUTS - 1) % INPUTS;
+            }
+            KeyCode::Esc if !self.inputs()[self.selected_input].value().is_empty() => {
+                self.input().reset();
+            }
+            KeyCode::Esc | KeyCode::Down | KeyCode::Enter => (),
+            _ => {
+                if !key.modifiers.contains(KeyModifiers::CONTROL) {
+                    self.input().handle_event(&Event::Key(key));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>, TuiError> {
+        if action == Action::NewView(ComponentName::Produce) {
+            self.list(&std::env::current_dir().unwrap_or_default());
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect, state: &State) -> Result<(), TuiError> {
+        let focused = state.is_focused(&self.id());
+        f.render_widget(Clear, rect);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" Produce ")
+            .padding(Padding::horizontal(1));
+        let block = self.make_block_focused_with_state(state, block);
+        let inner = block.inner(rect);
+        f.render_widget(block, rect);
+
+        let [
+            help_area,
+            files_area,
+            headers_area,
+            inputs_area,
+            header_area,
+        ] = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Fill(1),
+            Constraint::Length(self.headers.height()),
+            Constraint::Length(3),
+            Constraint::Length(3),
+        ])
+        .areas(inner);
+
+        f.render_widget(
+            Paragraph::new(format!(
+                "NDJSON, CSV and template files of '{}', ENTER produces the selected file to the selected topic.",
+                self.directory.display()
+            ))
+            .wrap(Wrap { trim: true }),
+            help_area,
+        );
+
+        let files: Vec<Line> = match self.files.is_empty() {
+            true => vec![Line::from("No .ndjson, .jsonl, .csv or .template file found").italic()],
+            false => self
+                .files
+                .iter()
+                .map(|p| {
+                    let name = p.file_name().unwrap_or_default().to_string_lossy();
+                    match is_template(p) {
+                        true => Line::from(format!("{name} (template)")),
+                        false => Line::from(name),
+                    }
+                })
+                .collect(),
+        };
+        let list = List::new(files).highlight_style(match focused {
+            true => Style::default()
+                .bg(state.theme.bg_focused_selected)
+                .fg(state.theme.fg_focused_selected)
+                .bold(),
+            false => Style::default()
+                .bg(state.theme.bg_unfocused_selected)
+                .fg(state.theme.fg_unfocused_selected),
+        });
+        f.render_stateful_widget(list, files_area, &mut self.state);
+
+        self.headers.draw_edits(f, headers_area, state);
+
+        let [
+            key_area,
+            value_area,
+            header_fields_area,
+            count_area,
+            rate_area,
+        ] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+            Constraint::Length(18),
+            Constraint::Length(13),
+        ])
+        .areas(inputs_area);
+        self.draw_input(f, key_area, 0, " Key field ", state);
+        self.draw_input(f, value_area, 1, " Value field ", state);
+        self.draw_input(f, header_fields_area, 2, " Header fields ", state);
+        self.draw_input(f, count_area, 3, " Template count ", state);
+        self.draw_input(f, rate_area, 4, " Records/s ", state);
+        // While confirming, the input of the header edits becomes the input of the confirmation
+        match (
+            &self.confirmation,
+            self.state.selected().and_then(|i| self.files.get(i)),
+        ) {
+            (Some(confirmation), Some(file)) => {
+                let name = file.file_name().unwrap_or_default().to_string_lossy();
+                confirmation.draw(f, header_area, &format!("produce '{name}'"), state);
+            }
+            _ => self.draw_input(f, header_area, INPUTS - 1, HEADER_INPUT_TITLE, state),
+        }
+        Ok(())
+    }
+
+    fn shortcuts(&self) -> Vec<Shortcut> {
+        if self.confirmation.is_some() {
+            return vec![
+                Shortcut::new("ENTER", "Confirm"),
+                Shortcut::new("ESC", "Cancel"),
+            ];
+        }
+        vec![
+            Shortcut::new("ENTER", "Produce"),
+            Shortcut::new("SHIFT + ←→", "Prev/next input"),
+        ]
+    }
+}
+
+#[cfg(test)]
+use crate::assert_draw;
+
+#[test]
+fn test_draw() {
+    let dir = tempfile::tempdir().unwrap();
+    for file in [
+        "customers.csv",
+        "notes.txt",
+        "payment.json.template",
+        "payments.ndjson",
+    ] {
+        std::fs::write(dir.path().join(file), "").unwrap();
+    }
+    let mut component = ProduceComponent::default();
+    component.list(dir.path());
+    component.directory = PathBuf::from("/seeds");
+    component.key = Input::new("customer.id".to_string());
+    component.rate = Input::new("50".to_string());
+    assert_draw!(component, 90, 15)
+}
+
+#[test]
+fn test_draw_header_edits() {
+    use app::HeaderEdit;
+
+    let mut component = ProduceComponent {
+        directory: PathBuf::from("/seeds"),
+        headers: HeaderEditor {
+            input: Input::new("tenant=acme".to_string()),
+            edits: vec![
+                HeaderEdit::Set("retry-count".to_string(), "3".to_string()),
+                HeaderEdit::Remove("trace-id".to_string()),
+            ],
+        },
+        selected_input: 5,
+        ..Default::default()
+    };
+    assert_draw!(component, 90, 15)
+}
+
+#[test]
+fn test_draw_confirmation() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("payments.ndjson"), "").unwrap();
+    let mut component = ProduceComponent::default();
+    component.list(dir.path());
+    component.directory = PathBuf::from("/seeds");
+    component.confirmation = Some(Confirmation::default());
+    assert_draw!(component, 90, 15)
+}
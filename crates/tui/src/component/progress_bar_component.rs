@@ -34,8 +34,8 @@ impl Widget for ProgressBarComponent {
         if self.progress == 0 || self.length == 0 {
             return;
         }
-        let percent = 100 * self.progress / self.length;
-        let dd = u64::from(area.right() - area.left()) * percent / 100;
+        let percent = (100 * self.progress / self.length).min(100);
+        let dd = u64::from(area.width) * percent / 100;
         buf.set_string(
             area.left(),
             area.top(),
@@ -0,0 +1,133 @@
+//! Component previewing how the search query would be executed, see [`app::App::query_plan`]:
+//! the partitions assigned to the consumer, their starting offsets, the number of records to read
+//! and whether the records are filtered before or after decoding.
+use app::{Filtering, QueryPlan};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Padding, Paragraph},
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{Component, ComponentName, Shortcut, State, scroll_state::ScrollState};
+use crate::{Action, error::TuiError};
+
+#[derive(Default)]
+pub(crate) struct QueryPlanComponent {
+    plan: Option<QueryPlan>,
+    loading: bool,
+    scroll: ScrollState,
+    action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl QueryPlanComponent {
+    fn lines(&self, state: &State) -> Vec<Line<'static>> {
+        let Some(plan) = &self.plan else {
+            return vec![Line::from("Computing the query plan...").italic()];
+        };
+        let mut lines = plan
+            .summary()
+            .into_iter()
+            .map(|(label, value)| {
+                let value = Span::from(value);
+                Line::from(vec![
+                    Span::from(format!("{label:<12}")).bold(),
+                    match (label, plan.filtering) {
+                        ("Filtering", Filtering::Decoded) => value.fg(state.theme.orange),
+                        _ => value,
+                    },
+                ])
+            })
+            .collect::<Vec<_>>();
+        lines.push(Line::default());
+        lines.extend(plan.partitions.iter().map(|p| Line::from(p.to_string())));
+        lines
+    }
+}
+
+impl Component for QueryPlanComponent {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) {
+        self.action_tx = Some(tx);
+    }
+
+    fn id(&self) -> ComponentName {
+        ComponentName::QueryPlan
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>, TuiError> {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.scroll.scroll_to_next_line(),
+            KeyCode::Char('k') | KeyCode::Up => self.scroll.scroll_to_previous_line(),
+            _ => (),
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>, TuiError> {
+        match action {
+            Action::NewView(ComponentName::QueryPlan) => {
+                self.plan = None;
+                self.loading = true;
+                self.scroll.reset();
+            }
+            Action::QueryPlan(plan) => {
+                self.plan = Some(plan);
+                self.loading = false;
+            }
+            _ => (),
+        }
+        Ok(None)
+    }
+
+    fn shortcuts(&self) -> Vec<Shortcut> {
+        vec![Shortcut::new("J/K", "Scroll")]
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect, state: &State) -> Result<(), TuiError> {
+        let lines = self.lines(state);
+        f.render_widget(Clear, rect);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .padding(Padding::symmetric(2, 0))
+            .title(match self.loading {
+                true => " Query plan [loading...] ",
+                false => " Query plan ",
+            });
+        let block = self.make_block_focused_with_state(state, block);
+        let paragraph = Paragraph::new(lines.clone()).scroll((self.scroll.value(), 0));
+        f.render_widget(paragraph.block(block), rect);
+        self.scroll.draw(f, rect, lines.len() + 2);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+use crate::assert_draw;
+
+#[test]
+fn test_draw() {
+    use app::PartitionScan;
+    use lib::search::ScanLimit;
+    use rdkafka::Offset;
+
+    let mut component = QueryPlanComponent::default();
+    component
+        .update(Action::QueryPlan(QueryPlan {
+            query: r#"from begin value.status == "paid" limit 100 scan limit 2GiB"#.to_string(),
+            from: "beginning".to_string(),
+            partitions: vec![
+                PartitionScan::new("payments", 0, Offset::Beginning, (0, 120_000)),
+                PartitionScan::new("payments", 1, Offset::Beginning, (3_000, 98_500)),
+            ],
+            limit: Some(100),
+            scan_limit: Some(ScanLimit::Bytes(2 << 30)),
+            filtering: Filtering::Decoded,
+            consumer_group: None,
+        }))
+        .unwrap();
+    assert_draw!(component, 90, 14)
+}
@@ -1,24 +1,41 @@
 //! Component showing all the details of a given kafka record.
 use core::time;
 
-use bytesize::ByteSize;
+use base64::{Engine, engine::general_purpose::STANDARD};
 use chrono::{DateTime, Utc};
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{Event, KeyCode, KeyEvent};
 
+use app::{
+    Notes,
+    configuration::Units,
+    search::json_schema::{json_schemas_of, validate},
+};
 use itertools::Itertools;
-use lib::{ExportedKafkaRecord, KafkaRecord};
+use lib::{DataType, KafkaRecord, search::string::quote};
 use ratatui::{
     Frame,
-    layout::Rect,
+    layout::{Constraint, Layout, Position, Rect},
     style::{Style, Stylize},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, Padding, Paragraph, Wrap},
+    widgets::{
+        Block, BorderType, Borders, Clear, List, ListItem, ListState, Padding, Paragraph, Wrap,
+    },
+};
+use serde_json::Value;
+use std::{
+    collections::VecDeque,
+    hash::{DefaultHasher, Hash, Hasher},
+    path::PathBuf,
+    time::Instant,
 };
-use std::time::Instant;
 use tokio::sync::mpsc::UnboundedSender;
+use tokio_util::sync::CancellationToken;
+use tui_input::{Input, backend::crossterm::EventHandler};
 
 use super::{Component, ComponentName, Shortcut, State, scroll_state::ScrollState, styles};
-use crate::{Action, Theme, error::TuiError, highlighter::Highlighter};
+use crate::{
+    Action, Theme, action::ValueId, error::TuiError, highlighter::Highlighter, report::ReportFormat,
+};
 
 #[derive(Default)]
 pub(crate) struct RecordDetailsComponent<'a> {
@@ -27,11 +44,55 @@ pub(crate) struct RecordDetailsComponent<'a> {
     search_query: String,
     scroll: ScrollState,
     theme: Option<Theme>,
+    /// Units of the size and of the publication date, see [`State::units`]
+    units: Units,
     action_tx: Option<UnboundedSender<Action>>,
     highlighter: Highlighter,
     last_g_key: Option<Instant>,
+    /// Predicates the user can append to the search query, the picker is open when `Some`
+    filter_picker: Option<(Vec<String>, ListState)>,
+    /// Values that could not be decoded are shown as Base64 instead of an hexadecimal dump
+    raw_as_base64: bool,
+    /// Avro logical types are shown as they are encoded instead of readable values
+    raw_logical_types: bool,
+    /// JSON schemas the search query validates the values against, with `valid_against`
+    json_schemas: Vec<PathBuf>,
+    /// Notes attached to the records of the cluster
+    notes: Notes,
+    /// Note of the record being written, the editor is open when `Some`
+    note_editor: Option<Input>,
+    /// Highlighted values of the records shown lately
+    highlighted: VecDeque<(ValueId, Vec<Line<'static>>)>,
+    /// Value of the shown record being highlighted in the background
+    highlighting: Option<Highlighting>,
 }
 
+/// A large value highlighted in the background, chunk by chunk.
+/// Until a line is highlighted, it is drawn without colors.
+struct Highlighting {
+    id: ValueId,
+    /// Index of the first line of the value in the rendered lines
+    first_line: usize,
+    /// Number of lines of the value
+    total: usize,
+    /// Lines highlighted so far
+    lines: Vec<Line<'static>>,
+    cancellation_token: CancellationToken,
+}
+
+/// Maximum number of JSON fields listed in the filter picker
+const MAX_JSON_FIELDS: usize = 50;
+
+/// Number of bytes per line of the hexadecimal dump
+const BYTES_PER_LINE: usize = 16;
+
+/// Values up to this number of lines are highlighted right away, larger ones in the background by chunks of this size.
+/// The first chunk fills the viewport of most terminals.
+const HIGHLIGHT_CHUNK_LINES: usize = 200;
+
+/// Number of highlighted values kept, so going back to a record does not highlight its value again
+const HIGHLIGHTED_VALUES: usize = 20;
+
 impl<'a> RecordDetailsComponent<'a> {
     pub fn new(highlighter: Highlighter) -> Self {
         Self {
@@ -62,6 +123,7 @@ impl<'a> RecordDetailsComponent<'a> {
             .as_ref()
             .unwrap()
             .send(Action::RequestSchemasOf(
+                r.topic.clone(),
                 r.key_schema.as_ref().map(|s| s.id.clone()),
                 r.value_schema.as_ref().map(|s| s.id.clone()),
             ))?;
@@ -73,6 +135,194 @@ impl<'a> RecordDetailsComponent<'a> {
         Ok(())
     }
 
+    fn toggle_filter_picker(&mut self) {
+        if self.filter_picker.take().is_some() {
+            return;
+        }
+        let filters = self.record.as_ref().map(filters_of).unwrap_or_default();
+        if !filters.is_empty() {
+            self.filter_picker = Some((filters, ListState::default().with_selected(Some(0))));
+        }
+    }
+
+    fn handle_filter_picker_key_events(&mut self, key: KeyEvent) -> Result<(), TuiError> {
+        let Some((filters, state)) = self.filter_picker.as_mut() else {
+            return Ok(());
+        };
+        match key.code {
+            KeyCode::Char('j') => state.select_next(),
+            KeyCode::Char('k') => state.select_previous(),
+            KeyCode::Enter => {
+                if let Some(filter) = state.selected().and_then(|i| filters.get(i)) {
+                    self.action_tx
+                        .as_ref()
+                        .unwrap()
+                        .send(Action::AppendToSearch(filter.clone()))?;
+                }
+                self.filter_picker = None;
+            }
+            KeyCode::Char('f') => self.filter_picker = None,
+            _ => (),
+        }
+        Ok(())
+    }
+
+    fn open_note_editor(&mut self) {
+        if let Some(record) = &self.record {
+            let text = self.notes.text_of(record).unwrap_or_default();
+            self.note_editor = Some(Input::new(text));
+        }
+    }
+
+    fn handle_note_editor_key_events(&mut self, key: KeyEvent) -> Result<(), TuiError> {
+        let Some(input) = self.note_editor.as_mut() else {
+            return Ok(());
+        };
+        match key.code {
+            KeyCode::Enter => {
+                if let Some(record) = &self.record {
+                    self.action_tx
+                        .as_ref()
+                        .unwrap()
+                        .send(Action::SetNote(record.clone(), input.value().to_string()))?;
+                }
+                self.note_editor = None;
+            }
+            KeyCode::Esc => self.note_editor = None,
+            _ => {
+                input.handle_event(&Event::Key(key));
+            }
+        }
+        Ok(())
+    }
+
+    fn draw_note_editor(&mut self, f: &mut Frame<'_>, rect: Rect, state: &State) {
+        let Some(input) = self.note_editor.as_ref() else {
+            return;
+        };
+        let [_, area] = Layout::vertical([Constraint::Fill(1), Constraint::Length(3)])
+            .areas(rect.inner(ratatui::layout::Margin::new(2, 1)));
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Thick)
+            .border_style(Style::default().fg(state.theme.dialog_border))
+            .padding(Padding::left(1))
+            .title(" Note, ENTER saves it, an empty note removes it ");
+        f.render_widget(Clear, area);
+        f.set_cursor_position(Position {
+            x: (area.x + input.visual_cursor() as u16 + 2).min(area.right().saturating_sub(2)),
+            y: area.y + 1,
+        });
+        f.render_widget(Paragraph::new(input.value()).block(block), area);
+    }
+
+    /// Returns the lines of the value, highlighted when it has been highlighted lately or when it is small.
+    /// Otherwise, the value is highlighted in the background and the lines are drawn without colors in the meantime.
+    fn value_lines(&mut self, id: ValueId, value: &str, first_line: usize) -> Vec<Line<'a>> {
+        if let Some((_, lines)) = self.highlighted.iter().find(|(i, _)| i == &id) {
+            return lines.clone();
+        }
+        let plain = |line: &str| Line::from(line.to_string());
+        if let Some(highlighting) = self.highlighting.as_mut().filter(|h| h.id == id) {
+            highlighting.first_line = first_line;
+            let mut lines = highlighting.lines.clone();
+            lines.extend(value.lines().skip(lines.len()).map(plain));
+            return lines;
+        }
+        self.cancel_highlighting();
+
+        let total = value.lines().count();
+        let Some(action_tx) = self
+            .action_tx
+            .clone()
+            .filter(|_| total > HIGHLIGHT_CHUNK_LINES)
+        else {
+            let lines = self.highlighter.highlight(value).lines;
+            self.cache_highlighted(id, lines.clone());
+            return lines;
+        };
+        let cancellation_token = CancellationToken::new();
+        let token = cancellation_token.clone();
+        let highlighter = self.highlighter.clone();
+        let content = value.to_string();
+        let value_id = id.clone();
+        tokio::task::Builder::new()
+            .name("value-highlighter")
+            .spawn_blocking(move || {
+                highlighter.highlight_by_chunks(&content, HIGHLIGHT_CHUNK_LINES, |lines| {
+                    !token.is_cancelled()
+                        && action_tx
+                            .send(Action::HighlightedLines(value_id.clone(), lines))
+                            .is_ok()
+                });
+            })
+            .unwrap();
+        self.highlighting = Some(Highlighting {
+            id,
+            first_line,
+            total,
+            lines: vec![],
+            cancellation_token,
+        });
+        value.lines().map(plain).collect()
+    }
+
+    /// Replaces the lines drawn without colors with the lines highlighted in the background.
+    fn on_highlighted_lines(&mut self, id: &ValueId, lines: Vec<Line<'static>>) {
+        let Some(highlighting) = self.highlighting.as_mut().filter(|h| &h.id == id) else {
+            return;
+        };
+        let start = highlighting.first_line + highlighting.lines.len();
+        for (line, highlighted) in self.lines.iter_mut().skip(start).zip(&lines) {
+            *line = highlighted.clone();
+        }
+        highlighting.lines.extend(lines);
+        if highlighting.lines.len() >= highlighting.total {
+            let highlighting = self.highlighting.take().unwrap();
+            self.cache_highlighted(highlighting.id, highlighting.lines);
+        }
+    }
+
+    fn cache_highlighted(&mut self, id: ValueId, lines: Vec<Line<'static>>) {
+        self.highlighted.retain(|(i, _)| i != &id);
+        self.highlighted.push_back((id, lines));
+        while self.highlighted.len() > HIGHLIGHTED_VALUES {
+            self.highlighted.pop_front();
+        }
+    }
+
+    fn cancel_highlighting(&mut self) {
+        if let Some(highlighting) = self.highlighting.take() {
+            highlighting.cancellation_token.cancel();
+        }
+    }
+
+    fn draw_filter_picker(&mut self, f: &mut Frame<'_>, rect: Rect, state: &State) {
+        let Some((filters, list_state)) = self.filter_picker.as_mut() else {
+            return;
+        };
+        let height = (filters.len() as u16 + 2).min(rect.height / 2);
+        let [_, area] = Layout::vertical([Constraint::Fill(1), Constraint::Length(height)])
+            .areas(rect.inner(ratatui::layout::Margin::new(2, 1)));
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Thick)
+            .border_style(Style::default().fg(state.theme.dialog_border))
+            .title(" Filter records with ");
+        let list = List::new(filters.iter().map(|e| ListItem::new(e.as_str())))
+            .highlight_style(
+                Style::default()
+                    .bg(state.theme.bg_focused_selected)
+                    .fg(state.theme.fg_focused_selected)
+                    .bold(),
+            )
+            .block(block);
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(list, area, list_state);
+    }
+
     fn compute_record_rendering(&mut self) {
         if self.record.is_none() {
             self.record = Some(KafkaRecord::default());
@@ -80,7 +330,8 @@ impl<'a> RecordDetailsComponent<'a> {
 
         let theme = self.theme.clone().unwrap_or(Theme::light());
         let record = self.record.as_ref().unwrap();
-        let ago_formatter = timeago::Formatter::new();
+        let mut ago_formatter = timeago::Formatter::new();
+        ago_formatter.num_items(self.units.duration_precision.max(1));
         let timestamp_in_millis = record.timestamp.unwrap_or(0);
 
         let published_at = DateTime::from_timestamp_millis(timestamp_in_millis).unwrap();
@@ -91,7 +342,14 @@ impl<'a> RecordDetailsComponent<'a> {
         let mut to_render = vec![
             Line::default(),
             Self::generate_span("Topic", record.topic.clone().into()),
-            Self::generate_span("Timestamp", format!("{timestamp_in_millis} ms").into()),
+            Self::generate_span(
+                "Timestamp",
+                match record.timestamp_type {
+                    Some(timestamp_type) => format!("{timestamp_in_millis} ms ({timestamp_type})"),
+                    None => format!("{timestamp_in_millis} ms"),
+                }
+                .into(),
+            ),
             Self::generate_span("DateTime", styles::colorize_timestamp(record, &theme)),
             Self::generate_span("Published", ago_formatter.convert(duration).into()),
             Self::generate_span("Offset", record.offset.to_string().into()),
@@ -99,7 +357,7 @@ impl<'a> RecordDetailsComponent<'a> {
                 "Partition",
                 record.partition.to_string().fg(theme.yellow).into(),
             ),
-            Self::generate_span("Size", ByteSize(record.size as u64).to_string().into()),
+            Self::generate_span("Size", size_of(record, &self.units).into()),
             //Self::generate_span("Headers", "".to_string().into()),
         ];
 
@@ -151,6 +409,13 @@ impl<'a> RecordDetailsComponent<'a> {
             to_render.push(Line::from(ppp.collect_vec()));
         }
 
+        if let Some(note) = self.notes.text_of(record) {
+            to_render.push(Self::generate_span(
+                "Note",
+                note.fg(theme.yellow).italic().into(),
+            ));
+        }
+
         if let Some(s) = &record.key_schema {
             match &s.schema_type {
                 Some(t) => to_render.push(Self::generate_span(
@@ -171,14 +436,80 @@ impl<'a> RecordDetailsComponent<'a> {
                 }
             }
         }
+        if !record.truncated {
+            for schema in &self.json_schemas {
+                let name = schema.display();
+                let status = match validate(schema, &record.value) {
+                    Ok(errors) if errors.is_empty() => {
+                        format!("✓ valid against {name}").fg(theme.green)
+                    }
+                    Ok(errors) => {
+                        format!("✗ {} error(s) against {name}, {}", errors.len(), errors[0])
+                            .fg(theme.red)
+                    }
+                    Err(e) => e.to_string().fg(theme.red),
+                };
+                to_render.push(Self::generate_span("JSON schema", status.into()));
+            }
+        }
+        if let Some(format) = &record.value_format {
+            to_render.push(Self::generate_span(
+                "Value format",
+                format.to_string().into(),
+            ));
+        }
 
-        to_render.extend(vec![
-            Self::generate_span("Key", record.key_as_string.clone().fg(theme.green).into()),
-            Self::generate_span("Value", "".into()),
-        ]);
-        let value = &record.value;
-        let highlighted = self.highlighter.highlight_data_type(value);
-        to_render.extend(highlighted.lines);
+        to_render.push(Self::generate_span(
+            "Key",
+            record.key_as_string.clone().fg(theme.green).into(),
+        ));
+        // The value to highlight, once the record is no longer borrowed
+        let mut value = None;
+        match &record.raw_value {
+            Some(bytes) => {
+                let (format, lines) = match self.raw_as_base64 {
+                    true => ("Base64", base64_lines(bytes)),
+                    false => ("hexadecimal", hex_dump(bytes)),
+                };
+                to_render.push(Self::generate_span(
+                    "Value",
+                    format!("Cannot be decoded, shown as {format}")
+                        .italic()
+                        .into(),
+                ));
+                to_render.extend(lines.into_iter().map(Line::from));
+            }
+            None if record.truncated => {
+                to_render.push(Self::generate_span(
+                    "Value",
+                    "Truncated, reading the full value…".italic().into(),
+                ));
+                to_render.extend(
+                    record
+                        .value_as_string
+                        .lines()
+                        .map(|l| Line::from(l.to_string())),
+                );
+            }
+            None => match &record.readable_value {
+                Some(readable) if !self.raw_logical_types => {
+                    to_render.push(Self::generate_span(
+                        "Value",
+                        "Avro logical types as readable values".italic().into(),
+                    ));
+                    value = Some(DataType::Json(readable.clone()).to_string_pretty());
+                }
+                _ => {
+                    to_render.push(Self::generate_span("Value", "".into()));
+                    value = Some(record.value.to_string_pretty());
+                }
+            },
+        }
+        if let Some(value) = value {
+            let id = value_id(record, &value);
+            let first_line = to_render.len();
+            to_render.extend(self.value_lines(id, &value, first_line));
+        }
         self.lines = to_render;
 
         self.scroll.reset();
@@ -194,7 +525,27 @@ impl Component for RecordDetailsComponent<'_> {
         ComponentName::RecordDetails
     }
 
+    fn is_typing(&self) -> bool {
+        self.note_editor.is_some()
+    }
+
     fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>, TuiError> {
+        if self.note_editor.is_some() {
+            self.handle_note_editor_key_events(key)?;
+            self.action_tx
+                .as_ref()
+                .unwrap()
+                .send(Action::RefreshShortcuts)?;
+            return Ok(None);
+        }
+        if self.filter_picker.is_some() {
+            self.handle_filter_picker_key_events(key)?;
+            self.action_tx
+                .as_ref()
+                .unwrap()
+                .send(Action::RefreshShortcuts)?;
+            return Ok(None);
+        }
         match key.code {
             KeyCode::Char('j') => {
                 self.scroll.scroll_to_next_line();
@@ -242,18 +593,47 @@ impl Component for RecordDetailsComponent<'_> {
             }
             KeyCode::Char('c') => {
                 if let Some(record) = &self.record {
-                    let mut exported_record: ExportedKafkaRecord = record.into();
-                    exported_record.search_query = self.search_query.to_string();
                     self.action_tx
                         .as_ref()
                         .unwrap()
-                        .send(Action::CopyToClipboard(
-                            serde_json::to_string_pretty(&exported_record)
-                                .expect("Unable to serialize record as json for the clipboard"),
-                        ))?;
+                        .send(Action::CopyRecord(record.clone()))?;
+                }
+                self.last_g_key = None;
+            }
+            KeyCode::Char('f') => {
+                self.toggle_filter_picker();
+                self.action_tx
+                    .as_ref()
+                    .unwrap()
+                    .send(Action::RefreshShortcuts)?;
+                self.last_g_key = None;
+            }
+            KeyCode::Char('b') => {
+                if self.record.as_ref().is_some_and(|r| r.raw_value.is_some()) {
+                    self.raw_as_base64 = !self.raw_as_base64;
+                    self.compute_record_rendering();
                 }
                 self.last_g_key = None;
             }
+            KeyCode::Char('l') => {
+                if self
+                    .record
+                    .as_ref()
+                    .is_some_and(|r| r.readable_value.is_some())
+                {
+                    self.raw_logical_types = !self.raw_logical_types;
+                    self.compute_record_rendering();
+                }
+                self.last_g_key = None;
+            }
+            KeyCode::Char('a') => {
+                self.open_note_editor();
+                self.action_tx
+                    .as_ref()
+                    .unwrap()
+                    .send(Action::RefreshShortcuts)?;
+                self.last_g_key = None;
+            }
             KeyCode::Char('e') => {
                 if let Some(record) = &self.record {
                     self.action_tx
@@ -263,6 +643,37 @@ impl Component for RecordDetailsComponent<'_> {
                 }
                 self.last_g_key = None;
             }
+            KeyCode::Char(c @ ('+' | '-')) => {
+                if let Some(record) = &self.record {
+                    self.action_tx
+                        .as_ref()
+                        .unwrap()
+                        .send(Action::RequestAdjacentRecord(record.clone(), c == '+'))?;
+                }
+                self.last_g_key = None;
+            }
+            KeyCode::Char(c @ ('n' | 'N')) => {
+                if let Some(record) = &self.record {
+                    self.action_tx
+                        .as_ref()
+                        .unwrap()
+                        .send(Action::RecordWithSameKey(record.clone(), c == 'n'))?;
+                }
+                self.last_g_key = None;
+            }
+            KeyCode::Char(c @ ('r' | 'R')) => {
+                if let Some(record) = &self.record {
+                    let format = match c {
+                        'r' => ReportFormat::Markdown,
+                        _ => ReportFormat::Html,
+                    };
+                    self.action_tx
+                        .as_ref()
+                        .unwrap()
+                        .send(Action::Report(format, Some(record.clone())))?;
+                }
+                self.last_g_key = None;
+            }
             _ => {
                 self.last_g_key = None;
             }
@@ -273,19 +684,77 @@ impl Component for RecordDetailsComponent<'_> {
     fn update(&mut self, action: Action) -> Result<Option<Action>, TuiError> {
         match action {
             Action::ShowRecord(record) => {
+                if record.truncated {
+                    self.action_tx
+                        .as_ref()
+                        .unwrap()
+                        .send(Action::RequestFullRecord(record.clone()))?;
+                }
                 self.record = Some(record);
+                self.filter_picker = None;
+                self.note_editor = None;
                 self.compute_record_rendering();
             }
-            Action::Search(e) => self.search_query = e.query().to_string(),
+            Action::FullRecord(record) => {
+                // The user may have opened another record in the meantime
+                let is_shown = self.record.as_ref().is_some_and(|r| {
+                    (&r.topic, r.partition, r.offset)
+                        == (&record.topic, record.partition, record.offset)
+                });
+                if is_shown {
+                    self.record = Some(record);
+                    self.compute_record_rendering();
+                }
+            }
+            Action::Notes(notes) => {
+                self.notes = notes;
+                if self.record.is_some() {
+                    self.compute_record_rendering();
+                }
+            }
+            Action::Search(e) => {
+                self.search_query = e.query().to_string();
+                self.json_schemas = json_schemas_of(e.query());
+            }
+            Action::HighlightedLines(id, lines) => self.on_highlighted_lines(&id, lines),
+            Action::Theme(theme, highlighter_theme) => {
+                self.theme = Some(theme);
+                self.highlighter = Highlighter::new(highlighter_theme);
+                self.highlighted.clear();
+                self.cancel_highlighting();
+                if self.record.is_some() {
+                    self.compute_record_rendering();
+                }
+            }
             _ => {}
         }
         Ok(None)
     }
 
     fn shortcuts(&self) -> Vec<Shortcut> {
+        if self.note_editor.is_some() {
+            return vec![
+                Shortcut::new("ENTER", "Save the note"),
+                Shortcut::new("ESC", "Cancel"),
+            ];
+        }
+        if self.filter_picker.is_some() {
+            return vec![
+                Shortcut::new("J/K", "Select"),
+                Shortcut::new("ENTER", "Append to the query"),
+                Shortcut::new("F", "Close"),
+            ];
+        }
         let mut shortcuts = vec![
             Shortcut::new("J/K", "Scroll"),
             Shortcut::new("↑↓", "Prev/next record"),
+            Shortcut::new("N", "Next with this key"),
+            Shortcut::new("+/-", "Next/previous offset"),
+            Shortcut::new("C", "Copy"),
+            Shortcut::new("O", "Open"),
+            Shortcut::new("E", "Export"),
+            Shortcut::new("A", "Note"),
+            Shortcut::new("R", "Report"),
         ];
 
         if self
@@ -295,14 +764,68 @@ impl Component for RecordDetailsComponent<'_> {
         {
             shortcuts.push(Shortcut::new("S", "Schemas"));
         }
+        shortcuts.push(Shortcut::new("F", "Filter"));
+        if self.record.as_ref().is_some_and(|r| r.raw_value.is_some()) {
+            shortcuts.push(Shortcut::new(
+                "B",
+                match self.raw_as_base64 {
+                    true => "Hexadecimal",
+                    false => "Base64",
+                },
+            ));
+        }
+        if self
+            .record
+            .as_ref()
+            .is_some_and(|r| r.readable_value.is_some())
+        {
+            shortcuts.push(Shortcut::new(
+                "L",
+                match self.raw_logical_types {
+                    true => "Readable",
+                    false => "Raw",
+                },
+            ));
+        }
 
         shortcuts
     }
 
+    fn key_bindings(&self) -> Vec<Shortcut> {
+        if self.note_editor.is_some() || self.filter_picker.is_some() {
+            return self.shortcuts();
+        }
+        vec![
+            Shortcut::new("J/K", "Scroll"),
+            Shortcut::new("[ or gg", "Scroll to top"),
+            Shortcut::new("] or G", "Scroll to bottom"),
+            Shortcut::new("↑↓", "Previous/next record"),
+            Shortcut::new("N", "Next record with the same key"),
+            Shortcut::new("SHIFT + N", "Previous record with the same key"),
+            Shortcut::new("+/-", "Record at the next/previous offset of the partition"),
+            Shortcut::new("C", "Copy the record to the clipboard"),
+            Shortcut::new("O", "Open the record in the web browser"),
+            Shortcut::new("E", "Export the record to the file"),
+            Shortcut::new("A", "Write a note about the record"),
+            Shortcut::new("R", "Copy as a Markdown report with schemas"),
+            Shortcut::new("SHIFT + R", "Copy as an HTML report with schemas"),
+            Shortcut::new("S", "Show the schemas"),
+            Shortcut::new("F", "Filter by a field of the record"),
+            Shortcut::new("B", "Undecodable value as hexadecimal/Base64"),
+            Shortcut::new("L", "Avro logical types as readable/raw values"),
+        ]
+    }
+
     fn draw(&mut self, f: &mut Frame<'_>, rect: Rect, state: &State) -> Result<(), TuiError> {
         if self.theme.is_none() {
             self.theme = Some(state.theme.clone());
         }
+        if self.units != state.units {
+            self.units = state.units;
+            if self.record.is_some() {
+                self.compute_record_rendering();
+            }
+        }
         let p = Paragraph::new(self.lines.clone())
             .wrap(Wrap { trim: false })
             .scroll((self.scroll.value(), 0));
@@ -316,6 +839,214 @@ impl Component for RecordDetailsComponent<'_> {
 
         f.render_widget(p.block(block), rect);
         self.scroll.draw(f, rect, self.lines.len() + 2);
+        self.draw_filter_picker(f, rect, state);
+        self.draw_note_editor(f, rect, state);
         Ok(())
     }
 }
+
+/// Identifies the value of the record as it is rendered.
+/// The hash tells apart the renderings of the same record: the preview of a truncated value and the full value,
+/// the avro logical types as they are encoded or readable, the value before and after the masking rules are reloaded...
+fn value_id(record: &KafkaRecord, value: &str) -> ValueId {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    (
+        record.topic.clone(),
+        record.partition,
+        record.offset,
+        hasher.finish(),
+    )
+}
+
+/// The size of the record, and the size of the value once decompressed when the producer compressed it.
+fn size_of(record: &KafkaRecord, units: &Units) -> String {
+    let size = units.format_size(record.size as u64);
+    match record
+        .value_format
+        .and_then(|f| f.compression.zip(f.uncompressed_size))
+    {
+        Some((compression, uncompressed_size)) => format!(
+            "{size} (value compressed with {compression}, {} once decompressed)",
+            units.format_size(uncompressed_size as u64)
+        ),
+        None => size,
+    }
+}
+
+/// Formats the bytes like `xxd`: the offset, 16 bytes in hexadecimal grouped by 2 and their ASCII representation.
+fn hex_dump(bytes: &[u8]) -> Vec<String> {
+    bytes
+        .chunks(BYTES_PER_LINE)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let hex = chunk
+                .chunks(2)
+                .map(|pair| pair.iter().map(|b| format!("{b:02x}")).join(""))
+                .join(" ");
+            let ascii: String = chunk
+                .iter()
+                .map(|b| match b.is_ascii_graphic() || *b == b' ' {
+                    true => *b as char,
+                    false => '.',
+                })
+                .collect();
+            format!("{:08x}: {hex:<39}  {ascii}", i * BYTES_PER_LINE)
+        })
+        .collect()
+}
+
+/// Encodes the bytes in Base64, split in lines of 76 characters like MIME does.
+fn base64_lines(bytes: &[u8]) -> Vec<String> {
+    STANDARD
+        .encode(bytes)
+        .as_bytes()
+        .chunks(76)
+        .map(|line| String::from_utf8_lossy(line).to_string())
+        .collect()
+}
+
+/// Lists the predicates matching the key, the headers and the JSON fields of the record.
+/// JSON objects and arrays used as keys are listed field by field.
+/// Headers and fields that cannot be expressed with the search syntax are ignored.
+fn filters_of(record: &KafkaRecord) -> Vec<String> {
+    let mut filters = vec![];
+    match &record.key {
+        DataType::Json(key @ (Value::Object(_) | Value::Array(_))) => {
+            let mut fields = vec![];
+            json_fields_of(key, "key", &mut fields);
+            filters.extend(
+                fields
+                    .into_iter()
+                    .filter_map(|(path, v)| quote(&v).map(|v| format!("{path} == {v}")))
+                    .take(MAX_JSON_FIELDS),
+            );
+        }
+        key => {
+            if let Some(key) = quote(&key.to_string()) {
+                filters.push(format!("key == {key}"));
+            }
+        }
+    }
+    for (name, value) in &record.headers {
+        if is_path_segment(name) {
+            if let Some(value) = quote(value) {
+                filters.push(format!("headers.{name} == {value}"));
+            }
+        }
+    }
+    if let DataType::Json(value) = &record.value {
+        let mut fields = vec![];
+        json_fields_of(value, "value", &mut fields);
+        filters.extend(
+            fields
+                .into_iter()
+                .filter_map(|(path, v)| quote(&v).map(|v| format!("{path} == {v}")))
+                .take(MAX_JSON_FIELDS),
+        );
+    }
+    filters
+}
+
+fn json_fields_of(value: &Value, path: &str, fields: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(map) => {
+            for (name, v) in map.iter().filter(|(name, _)| is_path_segment(name)) {
+                json_fields_of(v, &format!("{path}.{name}"), fields);
+            }
+        }
+        Value::Array(values) => {
+            for (i, v) in values.iter().enumerate() {
+                json_fields_of(v, &format!("{path}[{i}]"), fields);
+            }
+        }
+        Value::String(v) => fields.push((path.to_string(), v.clone())),
+        Value::Null | Value::Bool(_) | Value::Number(_) => {
+            fields.push((path.to_string(), value.to_string()));
+        }
+    }
+}
+
+/// JSON paths are converted into JSON pointers by the search engine.
+fn is_path_segment(name: &str) -> bool {
+    !name.is_empty()
+        && !name
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '.' | '[' | ']' | '/' | '~'))
+}
+
+#[test]
+fn test_filters_of() {
+    let mut record = KafkaRecord::default();
+    record.key = DataType::String("1234".to_string());
+    record.headers = [
+        ("trace".to_string(), "abc".to_string()),
+        ("content.type".to_string(), "json".to_string()),
+    ]
+    .into();
+    record.value = DataType::Json(serde_json::json!({
+        "name": "Rue de la Paix",
+        "quote": "\"Paris\"",
+        "tags": [1, true],
+        "weird key": 1
+    }));
+    assert_eq!(
+        filters_of(&record),
+        vec![
+            r#"key == "1234""#,
+            r#"headers.trace == "abc""#,
+            r#"value.name == "Rue de la Paix""#,
+            r#"value.quote == '"Paris"'"#,
+            r#"value.tags[0] == "1""#,
+            r#"value.tags[1] == "true""#,
+        ]
+    );
+}
+
+#[test]
+fn test_filters_of_json_key() {
+    let mut record = KafkaRecord::default();
+    record.key = DataType::Json(serde_json::json!({"tenantId": "acme", "id": 42}));
+    assert_eq!(
+        filters_of(&record),
+        vec![r#"key.tenantId == "acme""#, r#"key.id == "42""#]
+    );
+}
+
+#[test]
+fn test_hex_dump() {
+    assert_eq!(
+        hex_dump(b"Hello, world!\n\x00\xff\xca\xfe"),
+        vec![
+            "00000000: 4865 6c6c 6f2c 2077 6f72 6c64 210a 00ff  Hello, world!...",
+            "00000010: cafe                                     ..",
+        ]
+    );
+    assert_eq!(base64_lines(b"\xca\xfe\xba\xbe"), vec!["yv66vg=="]);
+}
+
+#[test]
+fn test_size_of() {
+    let mut record = KafkaRecord::default();
+    record.size = 2_048;
+    let units = Units::default();
+    assert_eq!(size_of(&record, &units), "2.0 KiB");
+    record.value_format = Some({
+        let mut format = lib::PayloadFormat::new(lib::kafka::Encoding::Json);
+        format.compression = Some(lib::kafka::Compression::Gzip);
+        format.uncompressed_size = Some(20_480);
+        format
+    });
+    assert_eq!(
+        size_of(&record, &units),
+        "2.0 KiB (value compressed with gzip, 20.0 KiB once decompressed)"
+    );
+    let units = Units {
+        size: app::configuration::SizeUnit::Si,
+        ..units
+    };
+    assert_eq!(
+        size_of(&record, &units),
+        "2.0 kB (value compressed with gzip, 20.5 kB once decompressed)"
+    );
+}
@@ -1,15 +1,20 @@
 //! Component showing in real time incoming kafka records.
 
-use app::search::ValidSearchQuery;
-use copypasta::{ClipboardContext, ClipboardProvider};
+use std::collections::HashMap;
+
+use app::{Notes, configuration::LayoutPreset, search::ValidSearchQuery};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use lib::ExportedKafkaRecord;
+use itertools::Itertools;
+use lib::{KafkaRecord, search::string::quote};
 use ratatui::{
     Frame,
-    layout::{Alignment, Constraint, Rect},
+    layout::{Alignment, Constraint, Layout, Rect},
     style::{Style, Stylize},
-    text::{Span, Text},
-    widgets::{Block, BorderType, Borders, Cell, Row, Table, TableState},
+    text::{Line, Span, Text},
+    widgets::{
+        Block, BorderType, Borders, Cell, Paragraph, Row, Sparkline, SparklineBar, Table,
+        TableState,
+    },
 };
 use thousands::Separable;
 use throbber_widgets_tui::ThrobberState;
@@ -17,14 +22,19 @@ use tokio::sync::mpsc::UnboundedSender;
 use tokio::sync::watch::Receiver;
 
 use crate::{
-    Action,
-    action::{Level, Notification},
+    Action, Notification,
+    action::Level,
     error::TuiError,
+    histogram::Bucket,
     records_buffer::{BufferAction, Stats},
+    report::ReportFormat,
 };
 
 use super::{Component, ComponentName, ConcurrentRecordsBuffer, Shortcut, State, styles};
 
+/// Number of topics listed in the summary of the matching records, the most matched first
+const MAX_MATCH_COUNTS_TOPICS: usize = 5;
+
 pub(crate) struct RecordsComponent<'a> {
     records: &'a ConcurrentRecordsBuffer,
     state: TableState,
@@ -33,10 +43,55 @@ pub(crate) struct RecordsComponent<'a> {
     consuming: bool,
     stats: Stats,
     follow: bool,
+    /// Records matching the search query are exported as they come
+    live_export: bool,
     action_tx: Option<UnboundedSender<Action>>,
     buffer_tx: Receiver<BufferAction>,
     selected_topics: usize,
     key_events_buffer: Vec<KeyEvent>,
+    /// Topic, partition and offset of the record shown in the record details
+    shown_record: Option<(String, i32, i64)>,
+    /// Version of the records and selected index the record details were last synced with
+    synced: Option<(u64, usize)>,
+    /// The number of matching records per minute or per hour is drawn above the records
+    show_histogram: bool,
+    /// Buckets of the histogram drawn the last time
+    buckets: Vec<Bucket>,
+    /// Start of the selected bucket of the histogram
+    selected_bucket: Option<i64>,
+    /// The number of matching records per topic and partition is drawn above the records
+    show_match_counts: bool,
+    /// Key the topic has been searched again for, when the list had no other record with this key
+    searched_key: Option<String>,
+    /// Notes attached to the records, noted records are marked in the list
+    notes: Notes,
+    /// Rows of the table, built again only when the records change
+    rows: RowsCache,
+}
+
+/// What the rows of the table are built from.
+#[derive(PartialEq, Eq)]
+struct RowsKey {
+    /// Version of the records buffer
+    version: u64,
+    width: u16,
+    layout: LayoutPreset,
+    theme: String,
+}
+
+impl RowsKey {
+    /// The rows of the records look the same
+    fn same_style(&self, other: &Self) -> bool {
+        (self.width, self.layout, &self.theme) == (other.width, other.layout, &other.theme)
+    }
+}
+
+/// Rows of the table and the row of each record, by topic, partition and offset.
+#[derive(Default)]
+struct RowsCache {
+    key: Option<RowsKey>,
+    rows: Vec<Row<'static>>,
+    by_record: HashMap<(String, i32, i64), Row<'static>>,
 }
 
 impl<'a> RecordsComponent<'a> {
@@ -51,10 +106,20 @@ impl<'a> RecordsComponent<'a> {
             consuming: false,
             stats: Stats::default(),
             follow: false,
+            live_export: false,
             action_tx: None,
             buffer_tx,
             selected_topics: 0,
             key_events_buffer: Vec::default(),
+            shown_record: None,
+            synced: None,
+            show_histogram: false,
+            buckets: vec![],
+            selected_bucket: None,
+            show_match_counts: false,
+            searched_key: None,
+            notes: Notes::default(),
+            rows: RowsCache::default(),
         }
     }
 
@@ -98,10 +163,90 @@ impl<'a> RecordsComponent<'a> {
     fn set_event_dialog(&mut self) -> Result<(), TuiError> {
         if let Some(s) = self.state.selected() {
             let record = self.records.lock().unwrap().get(s).unwrap().clone();
+            self.shown_record = Some((record.topic.clone(), record.partition, record.offset));
+            let action_tx = self.action_tx.as_ref().unwrap();
+            action_tx.send(Action::Announce(format!(
+                "Record {} of {}, topic {}, partition {}, offset {}, key {}",
+                s + 1,
+                self.buffer_len(),
+                record.topic,
+                record.partition,
+                record.offset,
+                record.key_as_string
+            )))?;
+            action_tx.send(Action::ShowRecord(record))?;
+        }
+        Ok(())
+    }
+
+    /// Keeps the record details in sync with the selected record, whatever moved the selection.
+    /// Nothing is done when neither the records nor the selection changed since the last time.
+    fn sync_event_dialog(&mut self) -> Result<(), TuiError> {
+        let Some(s) = self.state.selected() else {
+            return Ok(());
+        };
+        let selected = {
+            let records = self.records.lock().unwrap();
+            let synced = Some((records.version(), s));
+            if self.synced == synced {
+                return Ok(());
+            }
+            self.synced = synced;
+            records
+                .get(s)
+                .map(|r| (r.topic.clone(), r.partition, r.offset))
+        };
+        if selected.is_some() && selected != self.shown_record {
+            self.set_event_dialog()?;
+        }
+        Ok(())
+    }
+
+    /// Selects the next or the previous listed record with the key of the given record, to follow the history of an entity.
+    /// When the list has no such record, the topic is searched again for the records with this key.
+    fn select_record_with_same_key(
+        &mut self,
+        record: &KafkaRecord,
+        next: bool,
+    ) -> Result<(), TuiError> {
+        if record.key_as_string.is_empty() {
             self.action_tx
                 .as_ref()
                 .unwrap()
-                .send(Action::ShowRecord(record))?;
+                .send(Action::Notification(Notification::new(
+                    Level::Info,
+                    "This record has no key".to_string(),
+                )))?;
+            return Ok(());
+        }
+        let index = {
+            let records = self.records.lock().unwrap();
+            neighbor_with_same_key(&records.iter().collect_vec(), record, next)
+        };
+        if let Some(index) = index {
+            self.follow(false)?;
+            self.state.select(Some(index));
+            return self.set_event_dialog();
+        }
+        let action_tx = self.action_tx.as_ref().unwrap();
+        let direction = match next {
+            true => "next",
+            false => "previous",
+        };
+        let predicate = quote(&record.key_as_string).map(|key| format!("key == {key}"));
+        match predicate {
+            Some(predicate) if self.searched_key.as_ref() != Some(&record.key_as_string) => {
+                self.searched_key = Some(record.key_as_string.clone());
+                action_tx.send(Action::Notification(Notification::new(
+                    Level::Info,
+                    format!("No {direction} record with this key in the list, searching the topic"),
+                )))?;
+                action_tx.send(Action::AppendToSearch(predicate))?;
+            }
+            _ => action_tx.send(Action::Notification(Notification::new(
+                Level::Info,
+                format!("No {direction} record with this key"),
+            )))?,
         }
         Ok(())
     }
@@ -197,8 +342,180 @@ impl<'a> RecordsComponent<'a> {
         Ok(())
     }
 
-    fn truncate_value(value: &str, rect: Rect) -> String {
-        let split_at = rect.width.checked_sub(68).unwrap_or(3) as usize;
+    /// Selects the previous or the next bucket of the histogram, the latest one when none is selected.
+    fn select_bucket(&mut self, next: bool) -> Result<(), TuiError> {
+        let position = self
+            .selected_bucket
+            .and_then(|start| self.buckets.iter().position(|b| b.start == start));
+        let index = match (position, next) {
+            (None, _) => self.buckets.len().checked_sub(1),
+            (Some(i), true) => Some((i + 1).min(self.buckets.len() - 1)),
+            (Some(i), false) => Some(i.saturating_sub(1)),
+        };
+        self.selected_bucket = index.and_then(|i| self.buckets.get(i)).map(|b| b.start);
+        self.action_tx
+            .as_ref()
+            .unwrap()
+            .send(Action::RefreshShortcuts)?;
+        Ok(())
+    }
+
+    fn draw_histogram(&mut self, f: &mut Frame<'_>, rect: Rect, state: &State) {
+        let [caption_area, sparkline_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Length(3)]).areas(rect);
+        self.buckets = self
+            .records
+            .lock()
+            .unwrap()
+            .histogram()
+            .buckets(sparkline_area.width as usize);
+        let selected = self
+            .selected_bucket
+            .and_then(|start| self.buckets.iter().find(|b| b.start == start));
+        let caption = match (selected, self.buckets.first()) {
+            (Some(bucket), _) => {
+                let (from, to) = bucket.range();
+                format!(
+                    "{} records between {from} and {to}",
+                    bucket.count.separate_with_underscores()
+                )
+            }
+            (None, Some(bucket)) => {
+                format!("Records per {} since {}", bucket.unit(), bucket.range().0)
+            }
+            (None, None) => "No records with a timestamp".to_string(),
+        };
+        let bars = self.buckets.iter().map(|b| {
+            let bar = SparklineBar::from(b.count as u64);
+            match Some(b.start) == self.selected_bucket {
+                true => bar.style(Style::default().fg(state.theme.orange)),
+                false => bar,
+            }
+        });
+        f.render_widget(Paragraph::new(caption).bold(), caption_area);
+        f.render_widget(
+            Sparkline::default()
+                .data(bars)
+                .style(Style::default().fg(state.theme.blue)),
+            sparkline_area,
+        );
+    }
+
+    /// Height of the summary of the matching records per topic and partition, with its caption.
+    fn match_counts_height(&self) -> u16 {
+        let topics = self.records.lock().unwrap().match_counts().topics().len();
+        topics.clamp(1, MAX_MATCH_COUNTS_TOPICS + 1) as u16 + 1
+    }
+
+    fn draw_match_counts(&self, f: &mut Frame<'_>, rect: Rect, state: &State) {
+        let topics = self.records.lock().unwrap().match_counts().topics();
+        let longest_topic = topics.iter().map(|t| t.topic.len()).max().unwrap_or(0);
+        let mut lines = vec![Line::from("Matching records per topic and partition").bold()];
+        for topic in topics.iter().take(MAX_MATCH_COUNTS_TOPICS) {
+            let mut spans = vec![
+                Span::raw(format!("{:<longest_topic$}", topic.topic)),
+                Span::styled(
+                    format!("{:>9}  ", topic.count.separate_with_underscores()),
+                    Style::default().bold(),
+                ),
+            ];
+            for (partition, count) in &topic.partitions {
+                spans.push(Span::styled(
+                    format!(" #{partition} {} ", count.separate_with_underscores()),
+                    Style::default()
+                        .bg(state.theme.bg_active)
+                        .fg(state.theme.fg_active),
+                ));
+                spans.push(Span::raw(" "));
+            }
+            lines.push(Line::from(spans));
+        }
+        match topics.len().checked_sub(MAX_MATCH_COUNTS_TOPICS) {
+            Some(0) | None => (),
+            Some(others) => lines.push(Line::from(format!("and {others} other topic(s)")).italic()),
+        }
+        if topics.is_empty() {
+            lines.push(Line::from("No matching records").italic());
+        }
+        f.render_widget(Paragraph::new(lines), rect);
+    }
+
+    /// Builds the rows of the table again when the records changed since the last frame.
+    /// The row of a record still in the buffer is reused, unless the width, the layout or the theme changed.
+    fn build_rows(&mut self, rect: Rect, state: &State) {
+        let records = self.records.lock().unwrap();
+        let key = RowsKey {
+            version: records.version(),
+            width: rect.width,
+            layout: state.layout,
+            theme: state.theme.name.clone(),
+        };
+        match &self.rows.key {
+            Some(built) if built == &key => return,
+            Some(built) if built.same_style(&key) => (),
+            _ => self.rows.by_record.clear(),
+        }
+        let mut by_record = HashMap::with_capacity(records.stats().buffer_size);
+        self.rows.rows = records
+            .iter()
+            .map(|record| {
+                let id = (record.topic.clone(), record.partition, record.offset);
+                let row = self
+                    .rows
+                    .by_record
+                    .remove(&id)
+                    .unwrap_or_else(|| Self::row_of(record, &self.notes, rect, state));
+                by_record.insert(id, row.clone());
+                row
+            })
+            .collect();
+        self.rows.by_record = by_record;
+        self.rows.key = Some(key);
+    }
+
+    fn row_of(record: &KafkaRecord, notes: &Notes, rect: Rect, state: &State) -> Row<'static> {
+        let cells = vec![
+            Cell::new(match state.layout == LayoutPreset::Compact {
+                true => styles::colorize_time(record, &state.theme),
+                false => styles::colorize_timestamp(record, &state.theme),
+            }),
+            Cell::new(
+                Text::from(styles::colorize_and_shorten_topic(
+                    &record.topic,
+                    record.partition,
+                    &state.theme,
+                ))
+                .alignment(Alignment::Right),
+            ),
+            Cell::new(
+                Text::from(match notes.get(record) {
+                    Some(_) => Line::from(vec![
+                        Span::styled("✎ ", Style::default().fg(state.theme.yellow)),
+                        Span::raw(record.offset.to_string()),
+                    ]),
+                    None => Line::from(record.offset.to_string()),
+                })
+                .alignment(Alignment::Right),
+            ),
+            Cell::new(
+                styles::colorize_key(&record.key_as_string, &state.theme)
+                    .alignment(Alignment::Right),
+            ),
+            Cell::new(Text::from(Self::truncate_value(
+                &record.value_as_string,
+                rect,
+                state.layout,
+            ))),
+        ];
+        Row::new(cells).height(1_u16)
+    }
+
+    fn truncate_value(value: &str, rect: Rect, layout: LayoutPreset) -> String {
+        let other_columns = match layout {
+            LayoutPreset::Compact => 47,
+            _ => 68,
+        };
+        let split_at = rect.width.checked_sub(other_columns).unwrap_or(3) as usize;
         match value.len() > split_at {
             true => value.chars().take(split_at).collect(),
             false => value.to_string(),
@@ -206,6 +523,22 @@ impl<'a> RecordsComponent<'a> {
     }
 }
 
+/// Index of the next or the previous record with the same key as the given record, in the order of the list.
+fn neighbor_with_same_key(
+    records: &[&KafkaRecord],
+    record: &KafkaRecord,
+    next: bool,
+) -> Option<usize> {
+    let current = records.iter().position(|r| {
+        r.topic == record.topic && r.partition == record.partition && r.offset == record.offset
+    })?;
+    let same_key = |i: &usize| records[*i].key_as_string == record.key_as_string;
+    match next {
+        true => (current + 1..records.len()).find(same_key),
+        false => (0..current).rev().find(same_key),
+    }
+}
+
 impl Component for RecordsComponent<'_> {
     fn register_action_handler(&mut self, tx: UnboundedSender<Action>) {
         self.action_tx = Some(tx.clone());
@@ -221,36 +554,59 @@ impl Component for RecordsComponent<'_> {
                 if let Some(s) = self.state.selected() {
                     let r = self.records.lock().unwrap();
                     let record = r.get(s).unwrap();
-                    let mut ctx = ClipboardContext::new().unwrap();
-                    let exported_record: ExportedKafkaRecord = record.into();
-                    self.action_tx.as_ref().unwrap().send(Action::Notification(
-                        Notification::new(Level::Info, "Copied to clipboard".to_string()),
-                    ))?;
-                    ctx.set_contents(serde_json::to_string_pretty(&exported_record)?)
-                        .unwrap();
+                    self.action_tx
+                        .as_ref()
+                        .unwrap()
+                        .send(Action::CopyRecord(record.clone()))?;
                 }
             }
+            KeyCode::Char('l') => self.action_tx.as_ref().unwrap().send(Action::CopyLink)?,
+            KeyCode::Char('r') => self
+                .action_tx
+                .as_ref()
+                .unwrap()
+                .send(Action::Report(ReportFormat::Markdown, None))?,
+            KeyCode::Char('R') => self
+                .action_tx
+                .as_ref()
+                .unwrap()
+                .send(Action::Report(ReportFormat::Html, None))?,
             KeyCode::Char('f') => self.follow(!self.follow)?,
             KeyCode::Char('v') | KeyCode::Enter => {
                 self.show_details()?;
             }
             KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let records = self.records.lock().unwrap();
-                for record in records.iter() {
+                self.action_tx.as_ref().unwrap().send(Action::ExportAll)?;
+            }
+            KeyCode::Char('E') => {
+                self.live_export = !self.live_export;
+                self.action_tx
+                    .as_ref()
+                    .unwrap()
+                    .send(Action::ToggleLiveExport)?;
+                self.action_tx
+                    .as_ref()
+                    .unwrap()
+                    .send(Action::RefreshShortcuts)?;
+            }
+            KeyCode::Char('e') => {
+                if let Some(s) = self.state.selected() {
+                    let r = self.records.lock().unwrap();
+                    let record = r.get(s).unwrap();
                     self.action_tx
                         .as_ref()
                         .unwrap()
                         .send(Action::Export(record.clone()))?;
                 }
             }
-            KeyCode::Char('e') => {
+            KeyCode::Char('T') => {
                 if let Some(s) = self.state.selected() {
                     let r = self.records.lock().unwrap();
                     let record = r.get(s).unwrap();
                     self.action_tx
                         .as_ref()
                         .unwrap()
-                        .send(Action::Export(record.clone()))?;
+                        .send(Action::Trace(record.clone()))?;
                 }
             }
             KeyCode::Char('o') => {
@@ -274,16 +630,44 @@ impl Component for RecordsComponent<'_> {
             KeyCode::Down => {
                 self.follow(false)?;
                 self.next();
-                self.set_event_dialog()?;
             }
             KeyCode::Up => {
                 self.follow(false)?;
                 self.previous();
-                self.set_event_dialog()?;
             }
             KeyCode::Char('g' | 'G') => self.buffer_key_event(key)?,
+            KeyCode::Char('h') => {
+                self.show_histogram = !self.show_histogram;
+                self.selected_bucket = None;
+                self.action_tx
+                    .as_ref()
+                    .unwrap()
+                    .send(Action::RefreshShortcuts)?;
+            }
+            KeyCode::Char('m') => {
+                self.show_match_counts = !self.show_match_counts;
+                self.action_tx
+                    .as_ref()
+                    .unwrap()
+                    .send(Action::RefreshShortcuts)?;
+            }
+            KeyCode::Left if self.show_histogram => self.select_bucket(false)?,
+            KeyCode::Right if self.show_histogram => self.select_bucket(true)?,
+            KeyCode::Char('t') => {
+                if let Some(bucket) = self
+                    .selected_bucket
+                    .and_then(|start| self.buckets.iter().find(|b| b.start == start))
+                {
+                    self.action_tx
+                        .as_ref()
+                        .unwrap()
+                        .send(Action::AppendToSearch(bucket.predicate()))?;
+                    self.selected_bucket = None;
+                }
+            }
             _ => (),
         }
+        self.sync_event_dialog()?;
         Ok(None)
     }
 
@@ -295,8 +679,13 @@ impl Component for RecordsComponent<'_> {
             Action::NewConsumer() => {
                 self.stats = Stats::default();
             }
-            Action::Tick => self.status.calc_next(),
-            Action::SelectedTopics(topics) => self.selected_topics = topics.len(),
+            Action::Tick => {
+                self.status.calc_next();
+                self.sync_event_dialog()?;
+            }
+            Action::SelectedTopics(topics) | Action::TraceSearch(topics, _) => {
+                self.selected_topics = topics.len();
+            }
             Action::Consuming => self.consuming = true,
             Action::StopConsuming() => {
                 self.consuming = false;
@@ -304,8 +693,16 @@ impl Component for RecordsComponent<'_> {
             }
             Action::Search(search_query) => {
                 self.state.select(None);
+                self.selected_bucket = None;
                 self.search_query = search_query;
             }
+            Action::RecordWithSameKey(record, next) => {
+                self.select_record_with_same_key(&record, next)?;
+            }
+            Action::Notes(notes) => {
+                self.notes = notes;
+                self.rows = RowsCache::default();
+            }
             _ => (),
         }
         Ok(None)
@@ -321,8 +718,13 @@ impl Component for RecordsComponent<'_> {
         let block = self.make_block_focused_with_state(state, block);
 
         let normal_style = Style::default();
+        let compact = state.layout == LayoutPreset::Compact;
         let header_cells = vec![
-            Cell::new(Text::from("Timestamp")).bold(),
+            Cell::new(Text::from(match compact {
+                true => "Time",
+                false => "Timestamp",
+            }))
+            .bold(),
             Cell::new(Text::from("Topic").alignment(Alignment::Right)).bold(),
             Cell::new(Text::from("Offset").alignment(Alignment::Right)).bold(),
             Cell::new(Text::from("Key").alignment(Alignment::Right)).bold(),
@@ -333,48 +735,27 @@ impl Component for RecordsComponent<'_> {
             .height(1)
             .bottom_margin(1);
 
-        let mut records = Vec::with_capacity(500);
-        {
-            let r = self.records.lock().unwrap();
-            records.extend(r.iter().cloned());
-        }
-
-        // TODO render only records in the viewport
-        let rows = records.iter().enumerate().map(|(index, item)| {
-            if let Some(s) = self.state.selected() {
-                let is_visible = (s + rect.height as usize) > index
-                    && s.saturating_sub(rect.height as usize) <= index;
-                if !is_visible {
-                    return Row::new(Vec::<Cell>::new()).height(1_u16);
-                }
-            }
-
-            let cells = vec![
-                Cell::new(styles::colorize_timestamp(item, &state.theme)),
-                Cell::new(
-                    Text::from(styles::colorize_and_shorten_topic(
-                        &item.topic,
-                        item.partition,
-                        &state.theme,
-                    ))
-                    .alignment(Alignment::Right),
-                ),
-                Cell::new(Text::from(item.offset.to_string()).alignment(Alignment::Right)),
-                Cell::new(
-                    styles::colorize_key(&item.key_as_string, &state.theme)
-                        .alignment(Alignment::Right),
-                ),
-                Cell::new(Text::from(Self::truncate_value(
-                    &item.value_as_string,
-                    rect,
-                ))),
-            ];
-            Row::new(cells).height(1_u16)
-        });
+        self.build_rows(rect, state);
+        let visible = match self.state.selected() {
+            Some(s) => s.saturating_sub(rect.height as usize)..s + rect.height as usize,
+            None => self.state.offset()..self.state.offset() + rect.height as usize,
+        };
+        let rows =
+            self.rows
+                .rows
+                .iter()
+                .enumerate()
+                .map(|(index, row)| match visible.contains(&index) {
+                    true => row.clone(),
+                    false => Row::new(Vec::<Cell>::new()).height(1_u16),
+                });
         let table = Table::new(
             rows,
             [
-                Constraint::Min(29),
+                Constraint::Min(match compact {
+                    true => 8,
+                    false => 29,
+                }),
                 Constraint::Min(12),
                 Constraint::Min(7),
                 Constraint::Min(10),
@@ -411,24 +792,51 @@ impl Component for RecordsComponent<'_> {
         let inner = block.inner(rect);
         f.render_widget(block, rect);
 
-        f.render_stateful_widget(table, inner, &mut self.state);
-        let metrics_area = Rect::new(
-            inner
-                .right()
-                .checked_sub(u16::try_from(metrics.width())?)
-                .unwrap_or(1000)
-                .checked_sub(11)
-                .unwrap_or(1000),
-            inner.y,
-            metrics.width() as u16,
-            1,
-        );
+        let table_area = match self.show_histogram {
+            true => {
+                let [histogram_area, table_area] =
+                    Layout::vertical([Constraint::Length(5), Constraint::Fill(1)])
+                        .spacing(1)
+                        .areas(inner);
+                self.draw_histogram(f, histogram_area, state);
+                table_area
+            }
+            false => inner,
+        };
+        let table_area = match self.show_match_counts {
+            true => {
+                let [match_counts_area, table_area] = Layout::vertical([
+                    Constraint::Length(self.match_counts_height()),
+                    Constraint::Fill(1),
+                ])
+                .spacing(1)
+                .areas(table_area);
+                self.draw_match_counts(f, match_counts_area, state);
+                table_area
+            }
+            false => table_area,
+        };
+        f.render_stateful_widget(table, table_area, &mut self.state);
+        let first_line = Rect { height: 1, ..inner };
+        let [_, metrics_area, _, live_area, _] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Length(u16::try_from(metrics.width())?),
+            Constraint::Length(2),
+            Constraint::Length(8),
+            Constraint::Length(1),
+        ])
+        .areas(first_line);
+        let [_, throbber_area, _] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Length(2),
+            Constraint::Length(1),
+        ])
+        .areas(first_line);
 
         if self.consuming && self.stats.read != 0 {
             f.render_widget(metrics, metrics_area);
         }
         if self.consuming {
-            let simple = throbber_widgets_tui::Throbber::default();
             let ss = Span::styled(
                 " Live    ",
                 Style::default()
@@ -436,26 +844,29 @@ impl Component for RecordsComponent<'_> {
                     .bg(state.theme.orange),
             )
             .bold();
-            f.render_widget(
-                ss,
-                Rect::new(inner.right().saturating_sub(9), inner.y, 8, 1),
-            );
-            f.render_stateful_widget(
-                simple,
-                Rect::new(inner.right().saturating_sub(3), inner.y, 2, 1),
-                &mut self.status,
-            );
+            f.render_widget(ss, live_area);
+            if !state.accessible {
+                let simple = throbber_widgets_tui::Throbber::default();
+                f.render_stateful_widget(simple, throbber_area, &mut self.status);
+            }
         }
         Ok(())
     }
 
     fn shortcuts(&self) -> Vec<Shortcut> {
-        let shortcuts = vec![
+        let mut shortcuts = vec![
             Shortcut::new("C", "Copy"),
             Shortcut::new("O", "Open"),
             // Shortcut::new("[", "First record"),
             // Shortcut::new("]", "Last record"),
             Shortcut::new("E", "Export"),
+            Shortcut::new(
+                "SHIFT + E",
+                match self.live_export {
+                    true => "Stop exporting",
+                    false => "Export as you match",
+                },
+            ),
             Shortcut::new(
                 "F",
                 match self.follow {
@@ -464,8 +875,76 @@ impl Component for RecordsComponent<'_> {
                 },
             ),
             //Shortcut::new("↑↓", "Scroll"),
+            Shortcut::new(
+                "H",
+                match self.show_histogram {
+                    true => "Hide histogram",
+                    false => "Histogram",
+                },
+            ),
         ];
+        if self.selected_bucket.is_some() {
+            shortcuts.push(Shortcut::new("T", "Narrow time range"));
+        }
+        shortcuts.push(Shortcut::new(
+            "M",
+            match self.show_match_counts {
+                true => "Hide matches",
+                false => "Matches per topic",
+            },
+        ));
+        shortcuts.push(Shortcut::new("L", "Link"));
+        shortcuts.push(Shortcut::new("R", "Report"));
 
         shortcuts
     }
+
+    fn key_bindings(&self) -> Vec<Shortcut> {
+        vec![
+            Shortcut::new("↑↓", "Previous/next record"),
+            Shortcut::new("ENTER", "Open the record"),
+            Shortcut::new("[ or gg", "First record"),
+            Shortcut::new("] or G", "Last record"),
+            Shortcut::new("C", "Copy the record to the clipboard"),
+            Shortcut::new("O", "Open the record in the web browser"),
+            Shortcut::new("E", "Export the record to the file"),
+            Shortcut::new("CTRL + E", "Export all the records to the file"),
+            Shortcut::new("SHIFT + E", "Start/stop exporting records as they match"),
+            Shortcut::new("F", "Keep selecting the last consumed record"),
+            Shortcut::new("L", "Copy a link reproducing the search"),
+            Shortcut::new("R", "Copy the results as a Markdown report"),
+            Shortcut::new("SHIFT + R", "Copy the results as an HTML report"),
+            Shortcut::new("H", "Show/Hide the histogram of matching records"),
+            Shortcut::new("←→", "Previous/next bucket of the histogram"),
+            Shortcut::new("T", "Narrow the search to the selected bucket"),
+            Shortcut::new(
+                "M",
+                "Show/Hide the matching records per topic and partition",
+            ),
+            Shortcut::new("SHIFT + T", "Trace the record across the related topics"),
+        ]
+    }
+}
+
+#[test]
+fn test_neighbor_with_same_key() {
+    let records = [
+        ("ord-1", 10),
+        ("ord-2", 11),
+        ("ord-1", 12),
+        ("ord-3", 13),
+        ("ord-1", 14),
+    ]
+    .map(|(key, offset)| {
+        let mut record = KafkaRecord::default();
+        record.topic = "orders".to_string();
+        record.offset = offset;
+        record.key_as_string = key.to_string();
+        record
+    });
+    let records = records.iter().collect_vec();
+    assert_eq!(neighbor_with_same_key(&records, records[2], true), Some(4));
+    assert_eq!(neighbor_with_same_key(&records, records[2], false), Some(0));
+    assert_eq!(neighbor_with_same_key(&records, records[4], true), None);
+    assert_eq!(neighbor_with_same_key(&records, records[1], false), None);
 }
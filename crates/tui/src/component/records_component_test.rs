@@ -16,18 +16,21 @@ fn test_draw() {
 
     let mut component = RecordsComponent::new(&BUFFER);
     BUFFER.lock().unwrap().reset();
-    BUFFER.lock().unwrap().push(KafkaRecord {
-        topic: "movie-trailers".into(),
-        timestamp: None,
-        partition: 0,
-        offset: 314,
-        headers: BTreeMap::default(),
-        key_schema: None,
-        value_schema: None,
-        size: 4348,
-        key: DataType::String("7f12bd3b-4c96-4ba1-b010-8092234eec13".into()),
-        key_as_string: "7f12bd3b-4c96-4ba1-b010-8092234eec13".into(),
-        value: DataType::Json(json!(
+    BUFFER.lock().unwrap().push({
+        let mut record = KafkaRecord::default();
+        record.topic = "movie-trailers".into();
+        record.timestamp = None;
+        record.timestamp_type = None;
+        record.partition = 0;
+        record.offset = 314;
+        record.headers = BTreeMap::default();
+        record.key_schema = None;
+        record.value_schema = None;
+        record.value_format = None;
+        record.size = 4348;
+        record.key = DataType::String("7f12bd3b-4c96-4ba1-b010-8092234eec13".into());
+        record.key_as_string = "7f12bd3b-4c96-4ba1-b010-8092234eec13".into();
+        record.value = DataType::Json(json!(
             r#"{
             {
             "title" : "Swiss Army Man",
@@ -35,9 +38,44 @@ fn test_draw() {
             }
 
             }"#
-        )),
-        value_as_string: String::default(),
+        ));
+        record.value_as_string = String::default();
+        record.raw_value = None;
+        record.readable_value = None;
+        record.truncated = false;
+        record
     });
 
     assert_draw!(component, 120, 5)
 }
+
+#[test]
+fn test_sync_event_dialog_on_change() {
+    use crate::Action;
+
+    static SYNC_BUFFER: ConcurrentRecordsBuffer =
+        LazyLock::new(|| Arc::new(Mutex::new(RecordsBuffer::new())));
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut component = RecordsComponent::new(&SYNC_BUFFER);
+    component.register_action_handler(tx);
+    {
+        let mut buffer = SYNC_BUFFER.lock().unwrap();
+        buffer.push(KafkaRecord::default());
+        buffer.dispatch_metrics();
+    }
+    let mut shown = || {
+        let mut shown = 0;
+        while let Ok(action) = rx.try_recv() {
+            if matches!(action, Action::ShowRecord(_)) {
+                shown += 1;
+            }
+        }
+        shown
+    };
+
+    component.update(Action::Tick).unwrap();
+    assert_eq!(shown(), 1);
+    component.update(Action::Tick).unwrap();
+    component.update(Action::Tick).unwrap();
+    assert_eq!(shown(), 0);
+}
@@ -1,12 +1,12 @@
 //! This component is handles the main layout of the TUI
 //! and renders components based on the current context.
-use copypasta::{ClipboardContext, ClipboardProvider};
 use std::{
     collections::HashMap,
     sync::{Arc, Mutex},
 };
 use tracing::warn;
 
+use app::configuration::LayoutPreset;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
     Frame,
@@ -16,19 +16,112 @@ use ratatui::{
 use tokio::sync::{mpsc::UnboundedSender, watch::Receiver};
 
 use crate::{
-    Action, Notification, action::Level, error::TuiError, highlighter::Highlighter,
+    Action, Notification, action::Level, clipboard, error::TuiError, highlighter::Highlighter,
     records_buffer::BufferAction,
 };
 
 use super::{
-    Component, ComponentName, ConcurrentRecordsBuffer, State, footer_component::FooterComponent,
+    Component, ComponentName, ConcurrentRecordsBuffer, Shortcut, State,
+    broker_configs_component::BrokerConfigsComponent,
+    command_palette_component::CommandPaletteComponent,
+    consumer_errors_component::ConsumerErrorsComponent,
+    consumer_groups_component::ConsumerGroupsComponent,
+    dead_letters_component::DeadLettersComponent,
+    field_statistics_component::FieldStatisticsComponent, footer_component::FooterComponent,
     header_component::HeaderComponent, help_component::HelpComponent,
-    progress_bar_component::ProgressBarComponent, record_details_component::RecordDetailsComponent,
+    key_bindings_component::KeyBindingsComponent, latency_probe_component::LatencyProbeComponent,
+    produce_component::ProduceComponent, progress_bar_component::ProgressBarComponent,
+    query_plan_component::QueryPlanComponent, record_details_component::RecordDetailsComponent,
     records_component::RecordsComponent, schemas_component::SchemasComponent,
-    search_component::SearchComponent, topic_details_component::TopicDetailsComponent,
+    search_component::SearchComponent, statistics_component::StatisticsComponent,
+    table_component::TableComponent, timeline_component::TimelineComponent,
+    topic_details_component::TopicDetailsComponent,
     topics_and_records_component::TopicsAndRecordsComponent, topics_component::TopicsComponent,
 };
 
+/// Key bindings handled from every view: the key, the character pressed with `CTRL` and what it does.
+/// The command palette and the help are generated from them.
+pub(crate) const CONTROL_KEY_BINDINGS: [(&str, char, &str); 20] = [
+    ("CTRL + F", 'f', "Focus the search query"),
+    ("CTRL + R", 'r', "Refresh the list of topics"),
+    ("CTRL + O", 'o', "Show/Hide the topics"),
+    ("CTRL + T", 't', "Switch to the next theme"),
+    ("CTRL + L", 'l', "Switch to the next layout"),
+    ("CTRL + W", 'w', "Switch to the next workspace"),
+    (
+        "CTRL + X",
+        'x',
+        "Show/Hide the brokers and the errors of the consumer",
+    ),
+    ("CTRL + G", 'g', "Show/Hide the statistics of the consumer"),
+    (
+        "CTRL + K",
+        'k',
+        "Show/Hide the latest record of each key of the selected topics",
+    ),
+    (
+        "CTRL + S",
+        's',
+        "Show/Hide the statistics of a field across the listed records",
+    ),
+    (
+        "CTRL + N",
+        'n',
+        "Show/Hide the NDJSON and CSV files to produce to the selected topic",
+    ),
+    (
+        "CTRL + Y",
+        'y',
+        "Show/Hide the end-to-end latency probe of the selected topic",
+    ),
+    (
+        "CTRL + B",
+        'b',
+        "Show/Hide the configuration of the brokers",
+    ),
+    (
+        "CTRL + D",
+        'd',
+        "Show/Hide how the search query would be executed",
+    ),
+    (
+        "CTRL + Q",
+        'q',
+        "Show/Hide the consumer groups of the cluster",
+    ),
+    (
+        "CTRL + A",
+        'a',
+        "Show/Hide the records of a dead letter queue grouped by error",
+    ),
+    (
+        "CTRL + V",
+        'v',
+        "Show/Hide the listed records of all topics ordered by timestamp",
+    ),
+    ("CTRL + SPACE", ' ', "Show/Hide the command palette"),
+    ("CTRL + H", 'h', "Show/Hide the help"),
+    ("CTRL + C", 'c', "Quit"),
+];
+
+/// The key bindings handled from every view, whatever the focused component.
+pub(crate) fn global_key_bindings() -> Vec<Shortcut> {
+    [
+        Shortcut::new("/", "Focus the search query"),
+        Shortcut::new("?", "Show the key bindings of the focused window"),
+        Shortcut::new("ESC", "Close the window/app"),
+        Shortcut::new("TAB", "Focus next window"),
+        Shortcut::new("SHIFT + TAB", "Focus previous window"),
+    ]
+    .into_iter()
+    .chain(
+        CONTROL_KEY_BINDINGS
+            .iter()
+            .map(|(key, _, description)| Shortcut::new(key, description)),
+    )
+    .collect()
+}
+
 pub(crate) struct RootComponent {
     components: HashMap<ComponentName, Arc<Mutex<dyn Component>>>,
     views: Vec<ComponentName>,
@@ -38,6 +131,8 @@ pub(crate) struct RootComponent {
     progress_bar: ProgressBarComponent,
     buffer_rx: Receiver<BufferAction>,
     action_tx: Option<UnboundedSender<Action>>,
+    /// Overlay listing the key bindings of the focused component
+    key_bindings: Option<KeyBindingsComponent>,
 }
 
 impl RootComponent {
@@ -46,7 +141,7 @@ impl RootComponent {
         query: &str,
         selected_topics: Vec<String>,
         records: &'static ConcurrentRecordsBuffer,
-        state: State,
+        mut state: State,
     ) -> Self {
         let config = state.workspace().config();
         let buffer_rx = records.lock().map(|e| e.channels.clone().1).ok().unwrap();
@@ -57,7 +152,7 @@ impl RootComponent {
 
         let highlighter = Highlighter::new(state.highlighter_theme.clone());
 
-        let mut components: [Arc<Mutex<dyn Component>>; 10] = [
+        let mut components: [Arc<Mutex<dyn Component>>; 22] = [
             topics_component.clone(),
             Arc::new(Mutex::new(RecordsComponent::new(records))),
             Arc::new(Mutex::new(TopicDetailsComponent::default())),
@@ -71,6 +166,21 @@ impl RootComponent {
             Arc::new(Mutex::new(HelpComponent::default())),
             Arc::new(Mutex::new(SchemasComponent::new(highlighter))),
             Arc::new(Mutex::new(HeaderComponent::default())),
+            Arc::new(Mutex::new(ConsumerErrorsComponent::default())),
+            Arc::new(Mutex::new(StatisticsComponent::default())),
+            Arc::new(Mutex::new(TableComponent::default())),
+            Arc::new(Mutex::new(FieldStatisticsComponent::new(records))),
+            Arc::new(Mutex::new(ProduceComponent::default())),
+            Arc::new(Mutex::new(LatencyProbeComponent::default())),
+            Arc::new(Mutex::new(BrokerConfigsComponent::default())),
+            Arc::new(Mutex::new(QueryPlanComponent::default())),
+            Arc::new(Mutex::new(CommandPaletteComponent::new(
+                &config.macros,
+                &config.queries,
+            ))),
+            Arc::new(Mutex::new(ConsumerGroupsComponent::default())),
+            Arc::new(Mutex::new(DeadLettersComponent::new(records))),
+            Arc::new(Mutex::new(TimelineComponent::new(records))),
             Arc::new(Mutex::new(FooterComponent::default())),
         ];
 
@@ -86,15 +196,21 @@ impl RootComponent {
                 (id, c)
             })
             .collect();
+        let base_view = base_view_of(state.layout);
+        let focus_order = focus_order_of(&base_view, state.layout);
+        if !focus_order.contains(&state.focused) {
+            state.focused = focus_order.first().unwrap().clone();
+        }
         Self {
             components,
             buffer_rx,
             progress_bar: ProgressBarComponent::new(400),
-            views: vec![ComponentName::TopicsAndRecords],
-            focus_order: focus_order_of(&ComponentName::TopicsAndRecords),
+            focus_order,
+            views: vec![base_view],
             focus_history: vec![],
             state,
             action_tx: None,
+            key_bindings: None,
         }
     }
 
@@ -112,6 +228,10 @@ impl RootComponent {
 
     fn focus(&mut self, current: ComponentName) -> Result<(), TuiError> {
         self.state.focused = current;
+        self.action_tx
+            .as_ref()
+            .unwrap()
+            .send(Action::Focused(self.state.focused.clone()))?;
         let mut shortcuts = self
             .components
             .get(&self.state.focused)
@@ -119,16 +239,9 @@ impl RootComponent {
             .lock()
             .unwrap()
             .shortcuts();
-
-        if self.state.focused == ComponentName::RecordDetails {
-            shortcuts.extend(
-                self.components
-                    .get(&ComponentName::Records)
-                    .unwrap()
-                    .lock()
-                    .unwrap()
-                    .shortcuts(),
-            );
+        // '?' is a regular character in the search input
+        if self.state.focused != ComponentName::Search {
+            shortcuts.push(Shortcut::new("?", "Key bindings"));
         }
 
         self.action_tx
@@ -143,7 +256,7 @@ impl RootComponent {
         if self.views.is_empty() {
             self.action_tx.as_ref().unwrap().send(Action::Quit).unwrap();
         } else {
-            self.focus_order = focus_order_of(self.views.last().unwrap());
+            self.focus_order = focus_order_of(self.views.last().unwrap(), self.state.layout);
 
             let last_focused_component = self
                 .focus_history
@@ -183,6 +296,25 @@ impl RootComponent {
         }
     }
 
+    /// Switches to the next layout, the topics panel is hidden in the compact and zen layouts.
+    fn next_layout(&mut self) -> Result<(), TuiError> {
+        self.state.layout = self.state.layout.next();
+        self.views[0] = base_view_of(self.state.layout);
+        self.focus_order = focus_order_of(self.views.last().unwrap(), self.state.layout);
+        if !self.focus_order.contains(&self.state.focused) {
+            self.state.focused = self.focus_order.first().unwrap().clone();
+        }
+        self.notify_footer()?;
+        let action_tx = self.action_tx.as_ref().unwrap();
+        action_tx.send(Action::Layout(self.state.layout))?;
+        action_tx.send(Action::RefreshShortcuts)?;
+        action_tx.send(Action::Notification(Notification::new(
+            Level::Info,
+            format!("Layout '{}'", self.state.layout),
+        )))?;
+        Ok(())
+    }
+
     fn notify_footer(&self) -> Result<(), TuiError> {
         if !self.views.is_empty() {
             self.action_tx.as_ref().unwrap().send(Action::ViewStack((
@@ -209,21 +341,26 @@ impl Component for RootComponent {
         ComponentName::Main
     }
 
+    fn key_bindings(&self) -> Vec<Shortcut> {
+        global_key_bindings()
+    }
+
     fn init(&mut self) -> Result<(), TuiError> {
         self.notify_footer()?;
-        self.action_tx.as_ref().unwrap().send(Action::Shortcuts(
-            self.components
-                .get(&self.state.focused)
-                .unwrap()
-                .lock()
-                .unwrap()
-                .shortcuts(),
-            true,
-        ))?;
-        Ok(())
+        self.focus(self.state.focused.clone())
     }
 
     fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>, TuiError> {
+        let quit = key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+        if self.key_bindings.is_some() && !quit {
+            self.key_bindings = None;
+            return Ok(None);
+        }
+        let focused_component = self.components.get(&self.state.focused).unwrap();
+        if !quit && focused_component.lock().unwrap().is_typing() {
+            focused_component.lock().unwrap().handle_key_events(key)?;
+            return Ok(None);
+        }
         match key.code {
             KeyCode::Tab => {
                 let new_focus = self.focus_next(&self.state.focused.clone());
@@ -237,11 +374,25 @@ impl Component for RootComponent {
                 self.action_tx.as_ref().unwrap().send(Action::Refresh)?;
             }
             KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                self.state.focused = ComponentName::Search;
+                self.focus(ComponentName::Search)?;
                 return Ok(None);
             }
             KeyCode::Char('/' | ':') if self.state.focused != ComponentName::Search => {
-                self.state.focused = ComponentName::Search;
+                self.focus(ComponentName::Search)?;
+                return Ok(None);
+            }
+            KeyCode::Char('?') if self.state.focused != ComponentName::Search => {
+                let bindings = self
+                    .components
+                    .get(&self.state.focused)
+                    .unwrap()
+                    .lock()
+                    .unwrap()
+                    .key_bindings();
+                self.key_bindings = Some(KeyBindingsComponent::new(
+                    self.state.focused.clone(),
+                    bindings,
+                ));
                 return Ok(None);
             }
             KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
@@ -260,7 +411,7 @@ impl Component for RootComponent {
                 }
                 self.notify_footer()?;
                 if self.views.len() == 1 {
-                    self.focus_order = focus_order_of(&self.views[0]);
+                    self.focus_order = focus_order_of(&self.views[0], self.state.layout);
                     self.state.focused = match self.focus_order.contains(&self.state.focused) {
                         true => self.state.focused.clone(),
                         false => self.focus_order.first().unwrap().clone(),
@@ -272,6 +423,69 @@ impl Component for RootComponent {
                 }
                 return Ok(None);
             }
+            KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.action_tx.as_ref().unwrap().send(Action::NextTheme)?;
+                return Ok(None);
+            }
+            KeyCode::Char('w') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.action_tx
+                    .as_ref()
+                    .unwrap()
+                    .send(Action::NextWorkspace)?;
+                return Ok(None);
+            }
+            KeyCode::Char('l') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.next_layout()?;
+                return Ok(None);
+            }
+            KeyCode::Char('x') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.toggle_view(ComponentName::ConsumerErrors)?;
+                return Ok(None);
+            }
+            KeyCode::Char('g') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.toggle_view(ComponentName::Statistics)?;
+                return Ok(None);
+            }
+            KeyCode::Char('k') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.toggle_view(ComponentName::Table)?;
+                return Ok(None);
+            }
+            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.toggle_view(ComponentName::FieldStatistics)?;
+                return Ok(None);
+            }
+            KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.toggle_view(ComponentName::Produce)?;
+                return Ok(None);
+            }
+            KeyCode::Char('y') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.toggle_view(ComponentName::LatencyProbe)?;
+                return Ok(None);
+            }
+            KeyCode::Char('b') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.toggle_view(ComponentName::BrokerConfigs)?;
+                return Ok(None);
+            }
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.toggle_view(ComponentName::QueryPlan)?;
+                return Ok(None);
+            }
+            KeyCode::Char('q') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.toggle_view(ComponentName::ConsumerGroups)?;
+                return Ok(None);
+            }
+            KeyCode::Char('a') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.toggle_view(ComponentName::DeadLetters)?;
+                return Ok(None);
+            }
+            KeyCode::Char('v') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.toggle_view(ComponentName::Timeline)?;
+                return Ok(None);
+            }
+            KeyCode::Char(' ') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.toggle_view(ComponentName::CommandPalette)?;
+                return Ok(None);
+            }
             KeyCode::Char('h') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 self.toggle_view(ComponentName::Help)?;
                 return Ok(None);
@@ -309,7 +523,7 @@ impl Component for RootComponent {
                 self.focus_history.push(self.state.focused.clone());
                 self.focus_history.dedup();
                 let last_focused = self.focus_history.last().unwrap().clone();
-                self.focus_order = focus_order_of(action);
+                self.focus_order = focus_order_of(action, self.state.layout);
                 self.views = self
                     .views
                     .iter()
@@ -331,22 +545,28 @@ impl Component for RootComponent {
                 }?;
                 self.notify_footer()?;
             }
+            Action::PressKeys(_) if self.views.last() == Some(&ComponentName::CommandPalette) => {
+                self.close();
+            }
+            Action::Theme(ref theme, ref highlighter_theme) => {
+                self.state.theme = theme.clone();
+                self.state.highlighter_theme.clone_from(highlighter_theme);
+            }
             Action::RecordsToRead(length) => {
                 self.progress_bar.set_length(length);
-                let mut a = self.buffer_rx.clone();
-                let BufferAction::Stats(mut stats) = *a.borrow_and_update();
-                stats.total_to_read = length;
             }
             Action::CopyToClipboard(ref content) => {
-                let mut ctx = ClipboardContext::new().unwrap();
+                let notification = match clipboard::copy(content, self.state.config.clipboard()) {
+                    Ok(()) => Notification::new(Level::Info, "Copied to clipboard".to_string()),
+                    Err(e) => Notification::new(
+                        Level::Error,
+                        format!("Cannot copy to the clipboard: {e}"),
+                    ),
+                };
                 self.action_tx
                     .as_ref()
                     .unwrap()
-                    .send(Action::Notification(Notification::new(
-                        Level::Info,
-                        "Copied to clipboard".to_string(),
-                    )))?;
-                ctx.set_contents(content.to_string()).unwrap();
+                    .send(Action::Notification(notification))?;
             }
             _ => (),
         }
@@ -384,6 +604,17 @@ impl Component for RootComponent {
             .get(last_view)
             .unwrap_or_else(|| panic!("Unable to find component '{last_view}'"));
 
+        if self.state.layout == LayoutPreset::Zen && last_view == &ComponentName::RecordDetails {
+            main_component
+                .lock()
+                .unwrap()
+                .draw(f, rect.inner(Margin::new(1, 1)), &self.state)?;
+            if let Some(key_bindings) = self.key_bindings.as_mut() {
+                key_bindings.draw(f, rect, &self.state)?;
+            }
+            return Ok(());
+        }
+
         let chunks: std::rc::Rc<[Rect]> = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
@@ -394,10 +625,27 @@ impl Component for RootComponent {
             ])
             .split(rect.inner(Margin::new(1, 1)));
 
-        main_component
-            .lock()
-            .unwrap()
-            .draw(f, chunks[1], &self.state)?;
+        match (self.state.layout, last_view) {
+            (LayoutPreset::Wide, ComponentName::RecordDetails) => {
+                let [records, details] =
+                    Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                        .areas(chunks[1]);
+                self.components
+                    .get(&ComponentName::Records)
+                    .unwrap()
+                    .lock()
+                    .unwrap()
+                    .draw(f, records, &self.state)?;
+                main_component
+                    .lock()
+                    .unwrap()
+                    .draw(f, details, &self.state)?;
+            }
+            _ => main_component
+                .lock()
+                .unwrap()
+                .draw(f, chunks[1], &self.state)?,
+        }
         self.components
             .get_mut(&ComponentName::Header)
             .unwrap()
@@ -416,6 +664,9 @@ impl Component for RootComponent {
             .lock()
             .unwrap()
             .draw(f, chunks[3].inner(Margin::new(1, 0)), &self.state)?;
+        if let Some(key_bindings) = self.key_bindings.as_mut() {
+            key_bindings.draw(f, chunks[1], &self.state)?;
+        }
 
         //f.render_widget(self.progress_bar.clone(), rect);
         //f.render_widget(search_block, chunks[1]);
@@ -424,8 +675,22 @@ impl Component for RootComponent {
     }
 }
 
-pub fn focus_order_of(component: &ComponentName) -> Vec<ComponentName> {
+/// The first view of the stack, depending on the layout.
+fn base_view_of(layout: LayoutPreset) -> ComponentName {
+    match layout {
+        LayoutPreset::Default | LayoutPreset::Wide => ComponentName::TopicsAndRecords,
+        LayoutPreset::Compact | LayoutPreset::Zen => ComponentName::Records,
+    }
+}
+
+pub fn focus_order_of(component: &ComponentName, layout: LayoutPreset) -> Vec<ComponentName> {
     match component {
+        // Split view, the records list stays next to the details of the selected record
+        ComponentName::RecordDetails if layout == LayoutPreset::Wide => vec![
+            ComponentName::Records,
+            ComponentName::RecordDetails,
+            ComponentName::Search,
+        ],
         ComponentName::RecordDetails => vec![ComponentName::RecordDetails, ComponentName::Search],
         ComponentName::Records => vec![ComponentName::Records, ComponentName::Search],
         ComponentName::Schemas => vec![ComponentName::Schemas, ComponentName::Search],
@@ -436,12 +701,33 @@ pub fn focus_order_of(component: &ComponentName) -> Vec<ComponentName> {
         ],
         ComponentName::TopicDetails => vec![ComponentName::TopicDetails, ComponentName::Search],
         ComponentName::Help => vec![ComponentName::Help, ComponentName::Search],
+        ComponentName::ConsumerErrors => {
+            vec![ComponentName::ConsumerErrors, ComponentName::Search]
+        }
+        ComponentName::Statistics => vec![ComponentName::Statistics, ComponentName::Search],
+        ComponentName::Table => vec![ComponentName::Table, ComponentName::Search],
+        ComponentName::FieldStatistics => {
+            vec![ComponentName::FieldStatistics, ComponentName::Search]
+        }
+        ComponentName::Produce => vec![ComponentName::Produce, ComponentName::Search],
+        ComponentName::LatencyProbe => vec![ComponentName::LatencyProbe, ComponentName::Search],
+        ComponentName::BrokerConfigs => vec![ComponentName::BrokerConfigs, ComponentName::Search],
+        ComponentName::QueryPlan => vec![ComponentName::QueryPlan, ComponentName::Search],
+        ComponentName::CommandPalette => {
+            vec![ComponentName::CommandPalette, ComponentName::Search]
+        }
+        ComponentName::ConsumerGroups => {
+            vec![ComponentName::ConsumerGroups, ComponentName::Search]
+        }
+        ComponentName::DeadLetters => vec![ComponentName::DeadLetters, ComponentName::Search],
+        ComponentName::Timeline => vec![ComponentName::Timeline, ComponentName::Search],
         ComponentName::Dialog
         | ComponentName::Search
         | ComponentName::Main
         | ComponentName::RecordsView
         | ComponentName::Footer
         | ComponentName::Header
+        | ComponentName::KeyBindings
         | ComponentName::Topics => vec![],
     }
 }
@@ -20,3 +20,35 @@ fn test_draw() {
     );
     assert_draw!(component, 120, 20)
 }
+
+#[test]
+fn test_split_view_focus_order() {
+    use crate::component::{ComponentName, root_component::focus_order_of};
+    use app::configuration::LayoutPreset;
+
+    assert_eq!(
+        focus_order_of(&ComponentName::RecordDetails, LayoutPreset::Default),
+        vec![ComponentName::RecordDetails, ComponentName::Search]
+    );
+    assert_eq!(
+        focus_order_of(&ComponentName::RecordDetails, LayoutPreset::Wide),
+        vec![
+            ComponentName::Records,
+            ComponentName::RecordDetails,
+            ComponentName::Search
+        ]
+    );
+}
+
+#[test]
+fn test_control_key_bindings() {
+    use crate::component::root_component::CONTROL_KEY_BINDINGS;
+
+    for (label, c, _) in CONTROL_KEY_BINDINGS {
+        let key = match c {
+            ' ' => "SPACE".to_string(),
+            c => c.to_ascii_uppercase().to_string(),
+        };
+        assert_eq!(label, format!("CTRL + {key}"));
+    }
+}
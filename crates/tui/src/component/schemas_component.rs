@@ -7,7 +7,7 @@ use crate::{
     Action,
     error::TuiError,
     highlighter::Highlighter,
-    schema_detail::{ExportedSchemasDetails, SchemaDetail},
+    schema_detail::{ExportedSchemasDetails, SchemaDetail, SchemaVersions},
 };
 use crossterm::event::{KeyCode, KeyEvent};
 use lib::kafka::SchemaResponse;
@@ -33,9 +33,13 @@ pub(crate) struct SchemasComponent<'a> {
     scroll: ScrollState,
     highlighter: Highlighter,
     last_g_key: Option<Instant>,
+    /// Versions of the subject of the value schema, `Some(None)` while being listed
+    versions: Option<Option<SchemaVersions>>,
+    /// Topic of the record, the versions are listed from its schema registry
+    topic: String,
 }
 
-impl SchemasComponent<'_> {
+impl<'a> SchemasComponent<'a> {
     pub fn new(highlighter: Highlighter) -> Self {
         Self {
             highlighter,
@@ -45,6 +49,10 @@ impl SchemasComponent<'_> {
     }
 
     fn compute_schemas_rendering(&mut self) {
+        if let Some(versions) = &self.versions {
+            self.lines = self.versions_rendering(versions.as_ref());
+            return;
+        }
         let mut to_render = vec![];
 
         if let Some(s) = &self.key {
@@ -74,6 +82,7 @@ impl SchemasComponent<'_> {
 
             let highlighted = self.highlighter.highlight(&schema_content);
             to_render.extend(highlighted.lines);
+            to_render.extend(self.references_rendering(s.response.as_ref()));
         }
 
         if let Some(s) = &self.value {
@@ -93,10 +102,139 @@ impl SchemasComponent<'_> {
 
             let highlighted = self.highlighter.highlight(&schema_content);
             to_render.extend(highlighted.lines);
+            to_render.extend(self.references_rendering(s.response.as_ref()));
         }
         self.lines = to_render;
     }
 
+    /// Renders the schemas referenced by a schema, one after the other.
+    fn references_rendering(&self, response: Option<&SchemaResponse>) -> Vec<Line<'a>> {
+        let Some(response) = response else {
+            return vec![];
+        };
+        let mut to_render = vec![];
+        for reference in &response.resolved_references {
+            to_render.push(Line::default());
+            to_render.push(Line::from(vec![
+                Span::styled("Reference: ", Style::default().bold()),
+                Span::from(format!(
+                    "{} ({} v{})",
+                    reference.reference.name,
+                    reference.reference.subject,
+                    reference.reference.version
+                )),
+            ]));
+            let schema_content = response.reference_to_string_pretty(reference);
+            to_render.extend(self.highlighter.highlight(&schema_content).lines);
+        }
+        to_render
+    }
+
+    fn versions_rendering(&self, versions: Option<&SchemaVersions>) -> Vec<Line<'a>> {
+        let Some(versions) = versions else {
+            return vec![Line::from("[/] Loading versions...")];
+        };
+        let (start, page) = versions.page_items();
+        let mut subject = vec![
+            Span::styled("Subject : ", Style::default().bold()),
+            Span::from(versions.subject.clone()),
+        ];
+        if versions.is_subject_deleted() {
+            subject.push(Span::from(" (soft-deleted)").dim().italic());
+        }
+        let mut to_render = vec![
+            Line::from(subject),
+            Line::from(vec![
+                Span::styled("Versions: ", Style::default().bold()),
+                Span::from(format!(
+                    "{}-{} of {}, page {}/{}",
+                    start + 1,
+                    start + page.len(),
+                    versions.count(),
+                    versions.page() + 1,
+                    versions.pages()
+                )),
+            ]),
+            Line::default(),
+        ];
+        let mut spans = vec![];
+        for (i, version) in page.iter().enumerate() {
+            let span = match versions.is_deleted(*version) {
+                true => Span::from(format!(" v{version} ")).dim().crossed_out(),
+                false => Span::from(format!(" v{version} ")),
+            };
+            spans.push(match start + i == versions.selected_index() {
+                true => span.reversed().bold(),
+                false => span,
+            });
+        }
+        to_render.push(Line::from(spans));
+        to_render.push(Line::default());
+
+        match versions.content() {
+            Some(Some(detail)) => {
+                to_render.push(Line::from(vec![
+                    Span::styled("Schema URL: ", Style::default().bold()),
+                    Span::from(detail.url.clone()),
+                ]));
+                if versions.is_deleted(versions.selected().unwrap_or_default()) {
+                    to_render.push(Line::from("This version has been soft-deleted.").italic());
+                }
+                to_render.push(Line::default());
+                let schema_content = detail
+                    .response
+                    .as_ref()
+                    .map(SchemaResponse::schema_to_string_pretty)
+                    .unwrap_or("This version is unavailable.".to_string());
+                to_render.extend(self.highlighter.highlight(&schema_content).lines);
+                to_render.extend(self.references_rendering(detail.response.as_ref()));
+            }
+            _ => to_render.push(Line::from(format!(
+                "[/] Loading version {}...",
+                versions.selected().unwrap_or_default()
+            ))),
+        }
+        to_render
+    }
+
+    /// Requests the content of the selected version if it has not been fetched yet.
+    fn fetch_selected_version(&mut self) -> Result<(), TuiError> {
+        if let Some(Some(versions)) = &mut self.versions {
+            if let Some(version) = versions.version_to_fetch() {
+                self.action_tx
+                    .as_ref()
+                    .unwrap()
+                    .send(Action::RequestSchemaVersion(
+                        self.topic.clone(),
+                        versions.subject.clone(),
+                        version,
+                    ))?;
+            }
+        }
+        self.compute_schemas_rendering();
+        Ok(())
+    }
+
+    fn toggle_versions(&mut self) -> Result<(), TuiError> {
+        if self.versions.take().is_none() {
+            let Some(schema) = self.value.as_ref().or(self.key.as_ref()) else {
+                return Ok(());
+            };
+            self.versions = Some(None);
+            self.action_tx
+                .as_ref()
+                .unwrap()
+                .send(Action::RequestSchemaVersions(self.topic.clone(), schema.id))?;
+        }
+        self.action_tx
+            .as_ref()
+            .unwrap()
+            .send(Action::RefreshShortcuts)?;
+        self.scroll.reset();
+        self.compute_schemas_rendering();
+        Ok(())
+    }
+
     //fn highlight_schema<'b>(&self, schema: &'b SchemaDetail) -> Text<'b> {
     //    let schema_content =     schema.response
     //                .as_ref()
@@ -119,17 +257,59 @@ impl Component for SchemasComponent<'_> {
     }
 
     fn update(&mut self, action: Action) -> Result<Option<Action>, TuiError> {
-        if let Action::Schemas(key, value) = action {
-            self.key = key;
-            self.value = value;
-            self.compute_schemas_rendering();
-            self.scroll.reset();
+        match action {
+            Action::RequestSchemasOf(topic, _, _) => self.topic = topic,
+            Action::Schemas(key, value) => {
+                self.key = key;
+                self.value = value;
+                self.versions = None;
+                self.compute_schemas_rendering();
+                self.scroll.reset();
+            }
+            Action::SchemaVersions(versions) => {
+                if self.versions.is_some() {
+                    self.versions = Some(Some(versions));
+                    self.fetch_selected_version()?;
+                }
+            }
+            Action::SchemaVersion(subject, version, detail) => {
+                if let Some(Some(versions)) = &mut self.versions {
+                    if versions.subject == subject {
+                        versions.set_content(version, detail);
+                        self.compute_schemas_rendering();
+                    }
+                }
+            }
+            Action::Theme(_, highlighter_theme) => {
+                self.highlighter = Highlighter::new(highlighter_theme);
+                self.compute_schemas_rendering();
+            }
+            _ => (),
         }
         Ok(None)
     }
 
     fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>, TuiError> {
+        if let Some(Some(versions)) = &mut self.versions {
+            let selected = versions.selected_index();
+            match key.code {
+                KeyCode::Left | KeyCode::Char('h') => versions.select(selected.saturating_sub(1)),
+                KeyCode::Right | KeyCode::Char('l') => versions.select(selected + 1),
+                KeyCode::PageUp => versions.previous_page(),
+                KeyCode::PageDown => versions.next_page(),
+                _ => (),
+            }
+            if versions.selected_index() != selected {
+                self.scroll.reset();
+                self.fetch_selected_version()?;
+                return Ok(None);
+            }
+        }
         match key.code {
+            KeyCode::Char('v') => {
+                self.toggle_versions()?;
+                self.last_g_key = None;
+            }
             KeyCode::Char('j') | KeyCode::Down => {
                 self.scroll.scroll_to_next_line();
                 self.last_g_key = None;
@@ -201,6 +381,25 @@ impl Component for SchemasComponent<'_> {
     }
 
     fn shortcuts(&self) -> Vec<Shortcut> {
-        vec![Shortcut::new("C", "Copy")]
+        match self.versions {
+            Some(_) => vec![
+                Shortcut::new("←→", "Prev/next version"),
+                Shortcut::new("PAGE UP/DOWN", "Prev/next page"),
+                Shortcut::new("V", "Hide versions"),
+            ],
+            None => vec![Shortcut::new("C", "Copy"), Shortcut::new("V", "Versions")],
+        }
+    }
+
+    fn key_bindings(&self) -> Vec<Shortcut> {
+        vec![
+            Shortcut::new("J/K or ↑↓", "Scroll"),
+            Shortcut::new("[ or gg", "Scroll to top"),
+            Shortcut::new("] or G", "Scroll to bottom"),
+            Shortcut::new("C", "Copy the schemas to the clipboard"),
+            Shortcut::new("V", "Show/Hide the versions of the subject"),
+            Shortcut::new("←→", "Previous/next version, when versions are visible"),
+            Shortcut::new("PAGE UP/DOWN", "Previous/next page of versions"),
+        ]
     }
 }
@@ -2,13 +2,21 @@
 //! It comes with the following features:
 //!  - all queries are stored into a history.
 //!  - The component suggests queries based on your history.
+//!  - It prompts the values of the `:name` placeholders of saved queries before running them.
 
-use std::{path::PathBuf, time::Duration};
+use std::{collections::HashMap, path::PathBuf, time::Duration};
 
 use app::search::ValidSearchQuery;
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
 use itertools::Itertools;
-use lib::{Error, error::SearchError};
+use lib::{
+    Error,
+    error::SearchError,
+    search::{
+        append_predicate,
+        placeholder::{bind_placeholders, placeholders},
+    },
+};
 use ratatui::prelude::Stylize;
 use ratatui::{
     Frame,
@@ -42,6 +50,23 @@ pub(crate) struct SearchComponent {
     autocomplete: Option<String>,
     // A hack to detect copy-paste events and replace \n with a space
     entered: Option<Instant>,
+    /// The saved query whose placeholders are being prompted
+    prompt: Option<Prompt>,
+    /// Whether the search input has the focus
+    focused: bool,
+}
+
+/// A saved query waiting for the values of its placeholders, prompted one after the other.
+struct Prompt {
+    query: String,
+    placeholders: Vec<String>,
+    values: HashMap<String, String>,
+}
+
+impl Prompt {
+    fn placeholder(&self) -> Option<&String> {
+        self.placeholders.get(self.values.len())
+    }
 }
 
 impl SearchComponent {
@@ -156,6 +181,66 @@ impl SearchComponent {
         Ok(())
     }
 
+    /// Runs a saved query, once the values of its placeholders are typed.
+    fn run_saved_query(&mut self, query: String) -> Result<(), TuiError> {
+        self.prompt = Some(Prompt {
+            placeholders: placeholders(&query),
+            query,
+            values: HashMap::new(),
+        });
+        self.prompt_next(None)
+    }
+
+    /// Cancels the prompt of the placeholders, if any.
+    fn cancel_prompt(&mut self) {
+        if self.prompt.take().is_some() {
+            self.input.reset();
+        }
+    }
+
+    /// Stores the value of the current placeholder, if any, and runs the query when no placeholder is left.
+    fn prompt_next(&mut self, value: Option<String>) -> Result<(), TuiError> {
+        let Some(prompt) = self.prompt.as_mut() else {
+            return Ok(());
+        };
+        if let (Some(value), Some(name)) = (value, prompt.placeholder().cloned()) {
+            prompt.values.insert(name, value);
+        }
+        self.autocomplete = None;
+        self.remaining_input = None;
+        if prompt.placeholder().is_some() {
+            self.input.reset();
+            return Ok(());
+        }
+        let prompt = self.prompt.take().unwrap();
+        match bind_placeholders(&prompt.query, &prompt.values) {
+            Ok(query) => {
+                self.input = Input::from(query);
+                self.search()
+            }
+            Err(e) => {
+                self.action_tx
+                    .as_ref()
+                    .unwrap()
+                    .send(Action::Notification(Notification::new(
+                        Level::Error,
+                        Error::Search(e).to_string(),
+                    )))?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Requests the plan of the typed query, without running it.
+    fn explain(&self) -> Result<(), TuiError> {
+        let action = match ValidSearchQuery::from(self.input.value(), &self.filters_directory) {
+            Ok(search_query) => Action::RequestQueryPlan(search_query),
+            Err(e) => Action::Notification(Notification::new(Level::Error, e.to_string())),
+        };
+        self.action_tx.as_ref().unwrap().send(action)?;
+        Ok(())
+    }
+
     #[allow(dead_code)]
     fn pretty_error_message(error: &nom::Err<nom::error::Error<&str>>) -> String {
         match error {
@@ -175,8 +260,63 @@ impl Component for SearchComponent {
         ComponentName::Search
     }
 
+    fn update(&mut self, action: Action) -> Result<Option<Action>, TuiError> {
+        match action {
+            Action::AppendToSearch(predicate) => {
+                match append_predicate(self.input.value(), &predicate) {
+                    Ok(query) => {
+                        self.input = Input::from(query);
+                        self.autocomplete = None;
+                        self.remaining_input = None;
+                        self.search()?;
+                    }
+                    Err(e) => {
+                        self.action_tx.as_ref().unwrap().send(Action::Notification(
+                            Notification::new(Level::Error, Error::Search(e).to_string()),
+                        ))?;
+                    }
+                }
+            }
+            Action::NewView(ComponentName::QueryPlan) => self.explain()?,
+            Action::SavedQuery(query) => self.run_saved_query(query)?,
+            Action::NewSearchPrompt(_) => self.prompt = None,
+            Action::Focused(component) => {
+                let focused = component == ComponentName::Search;
+                if self.focused && !focused {
+                    self.cancel_prompt();
+                }
+                self.focused = focused;
+            }
+            Action::TraceSearch(_, query) => {
+                self.prompt = None;
+                self.input = Input::from(query);
+                self.autocomplete = None;
+                self.remaining_input = None;
+                self.search()?;
+            }
+            _ => (),
+        }
+        Ok(None)
+    }
+
+    /// The keys are sent to the input while the values of the placeholders are prompted, `ESC` cancels the prompt.
+    fn is_typing(&self) -> bool {
+        self.prompt.is_some()
+    }
+
     fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>, TuiError> {
         self.remaining_input = None;
+        if self.prompt.is_some() {
+            match key.code {
+                KeyCode::Enter => self.prompt_next(Some(self.input.value().to_string()))?,
+                KeyCode::Esc => self.cancel_prompt(),
+                KeyCode::Up | KeyCode::Down => (),
+                _ => {
+                    self.input.handle_event(&Event::Key(key));
+                }
+            }
+            return Ok(None);
+        }
         match key.code {
             KeyCode::Right => {
                 if self.input.value().len() == self.input.cursor() {
@@ -250,7 +390,7 @@ impl Component for SearchComponent {
         }
 
         let position_cursor = Position {
-            x: rect.x + 2 + (self.input.visual_cursor()) as u16,
+            x: (rect.x + 2 + self.input.visual_cursor() as u16).min(rect.right().saturating_sub(2)),
             y: rect.y + 1,
         };
 
@@ -270,7 +410,15 @@ impl Component for SearchComponent {
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .padding(Padding::left(1))
-            .title(" Search ");
+            .title(match &self.prompt {
+                Some(prompt) => format!(
+                    " Value of :{} [{}/{}] ",
+                    prompt.placeholder().map_or("", |p| p.as_str()),
+                    prompt.values.len() + 1,
+                    prompt.placeholders.len()
+                ),
+                None => " Search ".to_string(),
+            });
         let block = self.make_block_focused_with_state(state, block);
 
         let selected_style = Paragraph::new(line)
@@ -303,6 +451,63 @@ fn test_draw() {
     assert_draw!(component, 60, 3)
 }
 
+#[test]
+fn test_draw_saved_query_prompt() {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut component = SearchComponent::default();
+    component.register_action_handler(tx);
+    component
+        .update(Action::SavedQuery(
+            "key == :order_id and partition == :partition".to_string(),
+        ))
+        .unwrap();
+    for c in "ord-42".chars() {
+        component
+            .handle_key_events(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+            .unwrap();
+    }
+    component
+        .handle_key_events(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE))
+        .unwrap();
+    assert!(rx.try_recv().is_err());
+    assert_draw!(component, 60, 3);
+
+    component
+        .handle_key_events(KeyEvent::new(KeyCode::Char('3'), KeyModifiers::NONE))
+        .unwrap();
+    component
+        .handle_key_events(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE))
+        .unwrap();
+    assert_eq!(
+        component.input.value(),
+        r#"key == "ord-42" and partition == 3"#
+    );
+}
+
+#[test]
+fn test_cancel_saved_query_prompt() {
+    let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut component = SearchComponent::default();
+    component.register_action_handler(tx);
+    let prompt = Action::SavedQuery("key == :order_id".to_string());
+    component.update(prompt.clone()).unwrap();
+    assert!(component.is_typing());
+    component
+        .handle_key_events(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE))
+        .unwrap();
+    assert!(!component.is_typing());
+    assert_eq!(component.input.value(), "");
+
+    component
+        .update(Action::Focused(ComponentName::Search))
+        .unwrap();
+    component.update(prompt).unwrap();
+    component
+        .update(Action::Focused(ComponentName::Records))
+        .unwrap();
+    assert!(!component.is_typing());
+}
+
 #[test]
 fn test_empty_history_underflow() {
     let component = SearchComponent::new("from begin", vec![], PathBuf::from("."));
@@ -1,6 +1,6 @@
 //! The state is a struct containing various information.
 //! It is passed to all components.
-use app::configuration::{InternalConfig, Workspace};
+use app::configuration::{InternalConfig, LayoutPreset, Units, Workspace};
 use std::path::PathBuf;
 
 use crate::{highlighter::Highlighter, theme::Theme};
@@ -17,23 +17,34 @@ pub struct State {
     pub highlighter_theme: Option<syntect::highlighting::Theme>,
     pub configuration_file: PathBuf,
     pub config: InternalConfig,
+    pub layout: LayoutPreset,
+    /// Screen-reader friendly mode, see [`app::configuration::GlobalConfig::accessible`]
+    pub accessible: bool,
+    /// Units of the sizes and durations, see [`app::configuration::GlobalConfig::units`]
+    pub units: Units,
 }
 
 impl State {
     pub fn new(cluster: &str, theme: Theme, config: &InternalConfig) -> Self {
         let temp = theme.highlighter_theme.clone();
+        let color_support = config.color_support();
         Self {
             focused: ComponentName::default(),
             cluster: cluster.to_string(),
-            theme,
+            theme: theme.with_color_support(color_support),
             highlighter_theme: Highlighter::theme(
                 temp.as_deref(),
                 config.workspace().config().highlighter_theme.as_deref(),
-            ),
+            )
+            .filter(|_| color_support.is_colored())
+            .map(|t| Highlighter::with_color_support(t, color_support)),
             internal_config: config.clone(),
             themes: config.workspace().themes(),
             configuration_file: config.workspace().config_file(),
             config: config.clone(),
+            layout: config.layout(),
+            accessible: config.workspace().config().accessible,
+            units: config.workspace().config().units,
         }
     }
 
@@ -0,0 +1,167 @@
+//! Component drawing the statistics of the kafka consumer over time:
+//! the latency of the brokers, the request rate, the size of the fetch queue and the consumer lag.
+use std::{collections::VecDeque, time::Duration};
+
+use app::ConsumerStatistics;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    widgets::{Block, Borders, Clear, Padding, Paragraph, Sparkline},
+};
+use thousands::Separable;
+
+use super::{Component, ComponentName, State};
+use crate::{Action, error::TuiError};
+
+/// Number of statistics kept, 10 minutes with the default interval of 5 seconds
+const HISTORY: usize = 120;
+
+#[derive(Default)]
+pub(crate) struct StatisticsComponent {
+    history: VecDeque<ConsumerStatistics>,
+}
+
+impl StatisticsComponent {
+    fn push(&mut self, statistics: ConsumerStatistics) {
+        if self.history.len() == HISTORY {
+            self.history.pop_front();
+        }
+        self.history.push_back(statistics);
+    }
+
+    /// Requests sent per second between two consecutive statistics
+    fn request_rates(&self) -> Vec<u64> {
+        self.history
+            .iter()
+            .zip(self.history.iter().skip(1))
+            .map(|(previous, current)| {
+                let seconds = (current.time - previous.time).max(1) as u64;
+                current.requests.saturating_sub(previous.requests) / seconds
+            })
+            .collect()
+    }
+
+    fn series(&self, f: impl Fn(&ConsumerStatistics) -> u64) -> Vec<u64> {
+        self.history.iter().map(f).collect()
+    }
+
+    fn draw_sparkline(f: &mut Frame<'_>, rect: Rect, title: String, data: &[u64], state: &State) {
+        let block = Block::new().title(title.bold());
+        let width = block.inner(rect).width as usize;
+        let data = &data[data.len().saturating_sub(width)..];
+        f.render_widget(
+            Sparkline::default()
+                .block(block)
+                .data(data)
+                .style(Style::default().fg(state.theme.blue)),
+            rect,
+        );
+    }
+}
+
+impl Component for StatisticsComponent {
+    fn id(&self) -> ComponentName {
+        ComponentName::Statistics
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>, TuiError> {
+        match action {
+            Action::Statistics(statistics) => self.push(statistics),
+            Action::NewConsumer() => self.history.clear(),
+            _ => (),
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect, state: &State) -> Result<(), TuiError> {
+        f.render_widget(Clear, rect);
+        let block = Block::new()
+            .borders(Borders::ALL)
+            .padding(Padding::symmetric(4, 1))
+            .title(" Statistics ");
+        let block = self.make_block_focused_with_state(state, block);
+        let inner = block.inner(rect);
+        f.render_widget(block, rect);
+
+        let Some(last) = self.history.back() else {
+            f.render_widget(
+                Paragraph::new("No statistics yet, librdkafka emits them while consuming.")
+                    .italic(),
+                inner,
+            );
+            return Ok(());
+        };
+
+        let requests = self.request_rates();
+        let [latency, rate, queue, lag] = Layout::vertical([Constraint::Fill(1); 4])
+            .spacing(1)
+            .areas(inner);
+        Self::draw_sparkline(
+            f,
+            latency,
+            format!(
+                "Broker latency  {}",
+                state
+                    .units
+                    .format_duration(Duration::from_millis(last.latency))
+            ),
+            &self.series(|s| s.latency),
+            state,
+        );
+        Self::draw_sparkline(
+            f,
+            rate,
+            format!(
+                "Requests  {}/s",
+                requests
+                    .last()
+                    .copied()
+                    .unwrap_or(0)
+                    .separate_with_underscores()
+            ),
+            &requests,
+            state,
+        );
+        Self::draw_sparkline(
+            f,
+            queue,
+            format!(
+                "Fetch queue  {} records",
+                last.fetch_queue.separate_with_underscores()
+            ),
+            &self.series(|s| s.fetch_queue),
+            state,
+        );
+        Self::draw_sparkline(
+            f,
+            lag,
+            format!(
+                "Consumer lag  {} records",
+                last.lag.separate_with_underscores()
+            ),
+            &self.series(|s| s.lag),
+            state,
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+use crate::assert_draw;
+
+#[test]
+fn test_draw() {
+    let mut component = StatisticsComponent::default();
+    for i in 0..10 {
+        component.push(ConsumerStatistics {
+            time: i * 5,
+            latency: 10 + (i as u64 % 3) * 5,
+            requests: (i as u64) * 100,
+            fetch_queue: 500 - (i as u64) * 50,
+            lag: 10_000 - (i as u64) * 1_000,
+            ..Default::default()
+        });
+    }
+    assert_draw!(component, 80, 20)
+}
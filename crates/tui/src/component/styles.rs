@@ -1,6 +1,7 @@
+use app::ConnectionState;
 use lib::KafkaRecord;
 use ratatui::{
-    style::Stylize,
+    style::{Color, Stylize},
     text::{Line, Span},
 };
 
@@ -28,6 +29,22 @@ pub(crate) fn colorize_timestamp<'a>(kafka_record: &KafkaRecord, theme: &Theme)
     }
 }
 
+/// Only the time of the record, for narrow terminals.
+pub(crate) fn colorize_time<'a>(kafka_record: &KafkaRecord, theme: &Theme) -> Line<'a> {
+    match kafka_record.timestamp_as_local_date_time() {
+        Some(t) => Line::from(t.format("%H:%M:%S").to_string().fg(theme.blue)),
+        None => Line::from(""),
+    }
+}
+
+pub(crate) fn connection_color(connection: ConnectionState, theme: &Theme) -> Color {
+    match connection {
+        ConnectionState::Connected => theme.green,
+        ConnectionState::Degraded => theme.yellow,
+        ConnectionState::Disconnected => theme.red,
+    }
+}
+
 pub(crate) fn colorize_key<'a>(key: &str, theme: &Theme) -> Line<'a> {
     let pouet = match key.len() > 11 {
         true => {
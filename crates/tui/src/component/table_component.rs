@@ -0,0 +1,277 @@
+//! Component showing the latest record of each key of the selected topics, like a compacted topic.
+//! The topics are read from the beginning and tombstones delete their key from the table.
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use indexmap::IndexMap;
+use lib::KafkaRecord;
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Layout, Position, Rect},
+    style::{Style, Stylize},
+    text::Text,
+    widgets::{
+        Block, BorderType, Borders, Cell, Clear, Padding, Paragraph, Row, Table, TableState,
+    },
+};
+use thousands::Separable;
+use tokio::sync::mpsc::UnboundedSender;
+use tui_input::{Input, backend::crossterm::EventHandler};
+
+use super::{Component, ComponentName, Shortcut, State, styles};
+use crate::{Action, error::TuiError};
+
+#[derive(Default)]
+pub(crate) struct TableComponent {
+    /// Latest record of each topic and key
+    rows: IndexMap<(String, String), KafkaRecord>,
+    /// Indexes of the rows matching the filter
+    visible: Vec<usize>,
+    state: TableState,
+    input: Input,
+    reading: bool,
+    action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl TableComponent {
+    fn apply(&mut self, records: Vec<KafkaRecord>) {
+        for record in records {
+            let key = (record.topic.clone(), record.key_as_string.clone());
+            match record.is_tombstone() {
+                true => self.rows.shift_remove(&key),
+                false => self.rows.insert(key, record),
+            };
+        }
+        self.filter();
+    }
+
+    fn filter(&mut self) {
+        let filter = self.input.value().trim();
+        self.visible = self
+            .rows
+            .values()
+            .enumerate()
+            .filter(|(_, r)| {
+                filter.is_empty()
+                    || r.key_as_string.contains(filter)
+                    || r.value_as_string.contains(filter)
+            })
+            .map(|(i, _)| i)
+            .collect();
+        match self.visible.is_empty() {
+            true => self.state.select(None),
+            false => {
+                let selected = self.state.selected().unwrap_or(0);
+                self.state
+                    .select(Some(selected.min(self.visible.len() - 1)));
+            }
+        }
+    }
+
+    fn selected(&self) -> Option<&KafkaRecord> {
+        let index = self.visible.get(self.state.selected()?)?;
+        self.rows.get_index(*index).map(|(_, r)| r)
+    }
+
+    fn show_details(&self) -> Result<(), TuiError> {
+        if let Some(record) = self.selected() {
+            let action_tx = self.action_tx.as_ref().unwrap();
+            action_tx.send(Action::NewView(ComponentName::RecordDetails))?;
+            action_tx.send(Action::ShowRecord(record.clone()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Component for TableComponent {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) {
+        self.action_tx = Some(tx);
+    }
+
+    fn id(&self) -> ComponentName {
+        ComponentName::Table
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>, TuiError> {
+        match key.code {
+            KeyCode::Up => self.state.select_previous(),
+            KeyCode::Down if self.state.selected() < Some(self.visible.len().saturating_sub(1)) => {
+                self.state.select_next();
+            }
+            KeyCode::Enter => self.show_details()?,
+            KeyCode::Esc | KeyCode::Down => (),
+            _ => {
+                if !key.modifiers.contains(KeyModifiers::CONTROL) {
+                    self.input.handle_event(&Event::Key(key));
+                    self.filter();
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>, TuiError> {
+        match action {
+            Action::NewView(ComponentName::Table) => {
+                self.rows.clear();
+                self.visible.clear();
+                self.state.select(None);
+                self.reading = true;
+            }
+            Action::CompactedRecords(records) => self.apply(records),
+            Action::CompactedTopicsRead => self.reading = false,
+            _ => (),
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect, state: &State) -> Result<(), TuiError> {
+        let focused = state.is_focused(&self.id());
+        f.render_widget(Clear, rect);
+        let title = match self.reading {
+            true => format!(
+                " Table [{} keys, reading...] ",
+                self.rows.len().separate_with_underscores()
+            ),
+            false => format!(
+                " Table [{} keys] ",
+                self.rows.len().separate_with_underscores()
+            ),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(title);
+        let block = self.make_block_focused_with_state(state, block);
+        let inner = block.inner(rect);
+        f.render_widget(block, rect);
+
+        let [table_area, filter_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(match self.input.value().is_empty() {
+                true => 0,
+                false => 3,
+            }),
+        ])
+        .areas(inner);
+
+        let header = Row::new(vec![
+            Cell::new(Text::from("Topic").alignment(Alignment::Right)).bold(),
+            Cell::new(Text::from("Offset").alignment(Alignment::Right)).bold(),
+            Cell::new(Text::from("Key").alignment(Alignment::Right)).bold(),
+            Cell::new(Text::from("Value")).bold(),
+        ])
+        .height(1)
+        .bottom_margin(1);
+
+        // Only the rows in the viewport are rendered, compacted topics can have many keys
+        let selected = self.state.selected().unwrap_or(0);
+        let height = table_area.height as usize;
+        let rows = self.visible.iter().enumerate().map(|(index, i)| {
+            if index + height < selected || index > selected + height {
+                return Row::new(Vec::<Cell>::new());
+            }
+            let (_, record) = self.rows.get_index(*i).unwrap();
+            Row::new(vec![
+                Cell::new(
+                    Text::from(styles::colorize_and_shorten_topic(
+                        &record.topic,
+                        record.partition,
+                        &state.theme,
+                    ))
+                    .alignment(Alignment::Right),
+                ),
+                Cell::new(Text::from(record.offset.to_string()).alignment(Alignment::Right)),
+                Cell::new(
+                    styles::colorize_key(&record.key_as_string, &state.theme)
+                        .alignment(Alignment::Right),
+                ),
+                Cell::new(record.value_as_string.clone()),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Min(12),
+                Constraint::Min(7),
+                Constraint::Min(10),
+                Constraint::Percentage(100),
+            ],
+        )
+        .header(header)
+        .column_spacing(2)
+        .row_highlight_style(match focused {
+            true => Style::default()
+                .bg(state.theme.bg_focused_selected)
+                .fg(state.theme.fg_focused_selected)
+                .bold(),
+            false => Style::default()
+                .bg(state.theme.bg_unfocused_selected)
+                .fg(state.theme.fg_unfocused_selected),
+        });
+        f.render_stateful_widget(table, table_area, &mut self.state);
+
+        if !self.input.value().is_empty() {
+            let filter = Paragraph::new(self.input.value()).block(
+                Block::default()
+                    .title(" Filter ")
+                    .padding(Padding::left(1))
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded),
+            );
+            if focused {
+                f.set_cursor_position(Position {
+                    x: (filter_area.x + self.input.visual_cursor() as u16 + 2)
+                        .min(filter_area.right().saturating_sub(2)),
+                    y: filter_area.y + 1,
+                });
+            }
+            f.render_widget(filter, filter_area);
+        }
+        Ok(())
+    }
+
+    fn shortcuts(&self) -> Vec<Shortcut> {
+        vec![Shortcut::new("ENTER", "Show details")]
+    }
+}
+
+#[cfg(test)]
+use crate::assert_draw;
+
+#[cfg(test)]
+fn record(offset: i64, key: &str, value: &str) -> KafkaRecord {
+    {
+        let mut record = KafkaRecord::default();
+        record.topic = "customers".to_string();
+        record.offset = offset;
+        record.key = lib::DataType::String(key.to_string());
+        record.key_as_string = key.to_string();
+        record.value = lib::DataType::String(value.to_string());
+        record.value_as_string = value.to_string();
+        record
+    }
+}
+
+#[test]
+fn test_tombstones() {
+    let mut component = TableComponent::default();
+    component.apply(vec![
+        record(0, "1", "alice"),
+        record(1, "2", "bob"),
+        record(2, "1", "alice v2"),
+        record(3, "2", ""),
+    ]);
+    assert_eq!(component.rows.len(), 1);
+    assert_eq!(component.selected().map(|r| r.offset), Some(2));
+}
+
+#[test]
+fn test_draw() {
+    let mut component = TableComponent::default();
+    component.apply(vec![
+        record(0, "1", r#"{"name":"alice"}"#),
+        record(1, "2", r#"{"name":"bob"}"#),
+        record(2, "3", r#"{"name":"carol"}"#),
+        record(3, "2", ""),
+    ]);
+    assert_draw!(component, 80, 8)
+}
@@ -0,0 +1,309 @@
+//! Component merging the listed records of several topics into a single timeline, ordered by timestamp.
+//! Each topic has its own color and lane, and the unusually long gaps between two records are highlighted.
+use std::time::Duration;
+
+use app::configuration::Units;
+use crossterm::event::{KeyCode, KeyEvent};
+use itertools::Itertools;
+use lib::KafkaRecord;
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    text::{Line, Span, Text},
+    widgets::{
+        Block, BorderType, Borders, Cell, Clear, Padding, Paragraph, Row, Table, TableState,
+    },
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{Component, ComponentName, ConcurrentRecordsBuffer, Shortcut, State};
+use crate::{Action, Theme, error::TuiError};
+
+/// A gap is highlighted when it is longer than this number of times the median gap.
+const GAP_FACTOR: i64 = 3;
+
+/// Gaps shorter than a second are never highlighted.
+const MIN_HIGHLIGHTED_GAP_MS: i64 = 1_000;
+
+pub(crate) struct TimelineComponent {
+    records: &'static ConcurrentRecordsBuffer,
+    state: TableState,
+    action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl TimelineComponent {
+    pub fn new(records: &'static ConcurrentRecordsBuffer) -> Self {
+        Self {
+            records,
+            state: TableState::default(),
+            action_tx: None,
+        }
+    }
+
+    /// The listed records with a timestamp, the oldest first.
+    fn timeline(&self) -> Vec<KafkaRecord> {
+        let records = self.records.lock().unwrap();
+        records
+            .iter()
+            .filter(|record| record.timestamp.is_some())
+            .sorted_by_key(|record| {
+                (
+                    record.timestamp,
+                    record.topic.clone(),
+                    record.partition,
+                    record.offset,
+                )
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn show_details(&self) -> Result<(), TuiError> {
+        let Some(record) = self
+            .state
+            .selected()
+            .and_then(|s| self.timeline().into_iter().nth(s))
+        else {
+            return Ok(());
+        };
+        let action_tx = self.action_tx.as_ref().unwrap();
+        action_tx.send(Action::NewView(ComponentName::RecordDetails))?;
+        action_tx.send(Action::ShowRecord(record))?;
+        Ok(())
+    }
+}
+
+/// Colors of the topics, in the alphabetical order of their names.
+fn topic_colors(theme: &Theme) -> [Color; 7] {
+    [
+        theme.blue,
+        theme.green,
+        theme.magenta,
+        theme.cyan,
+        theme.orange,
+        theme.yellow,
+        theme.red,
+    ]
+}
+
+/// Time elapsed since the previous record: `+850 ms`, `+1 s 250 ms`, `+3 m 12 s`.
+fn format_gap(gap: i64, units: &Units) -> String {
+    format!(
+        "+{}",
+        units.format_duration(Duration::from_millis(gap.max(0) as u64))
+    )
+}
+
+/// Gaps between each record and the previous one, in milliseconds, and the threshold above which a gap is highlighted.
+fn gaps(timeline: &[KafkaRecord]) -> (Vec<Option<i64>>, i64) {
+    let gaps = [None]
+        .into_iter()
+        .chain(
+            timeline
+                .iter()
+                .tuple_windows()
+                .map(|(previous, record)| Some(record.timestamp? - previous.timestamp?)),
+        )
+        .collect_vec();
+    let sorted = gaps.iter().flatten().sorted().collect_vec();
+    let median = sorted.get(sorted.len() / 2).copied().copied();
+    let threshold = (median.unwrap_or_default() * GAP_FACTOR).max(MIN_HIGHLIGHTED_GAP_MS);
+    (gaps, threshold)
+}
+
+impl Component for TimelineComponent {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) {
+        self.action_tx = Some(tx);
+    }
+
+    fn id(&self) -> ComponentName {
+        ComponentName::Timeline
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>, TuiError> {
+        match key.code {
+            KeyCode::Up => self.state.select_previous(),
+            KeyCode::Down => self.state.select_next(),
+            KeyCode::Char('[') => self.state.select_first(),
+            KeyCode::Char(']') => self.state.select_last(),
+            KeyCode::Enter => self.show_details()?,
+            _ => (),
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect, state: &State) -> Result<(), TuiError> {
+        f.render_widget(Clear, rect);
+        let timeline = self.timeline();
+        let topics = timeline
+            .iter()
+            .map(|record| record.topic.as_str())
+            .unique()
+            .sorted()
+            .collect_vec();
+        let colors = topic_colors(&state.theme);
+        let color_of = |topic: &str| {
+            let lane = topics.iter().position(|t| *t == topic).unwrap_or_default();
+            (lane, colors[lane % colors.len()])
+        };
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(
+                " Timeline [{} records, {} topics] ",
+                timeline.len(),
+                topics.len()
+            ))
+            .padding(Padding::horizontal(1));
+        let block = self.make_block_focused_with_state(state, block);
+        let inner = block.inner(rect);
+        f.render_widget(block, rect);
+
+        let [legend_area, table_area] =
+            Layout::vertical([Constraint::Length(2), Constraint::Fill(1)]).areas(inner);
+
+        if timeline.is_empty() {
+            f.render_widget(
+                Paragraph::new(Line::from("No listed record has a timestamp").italic()),
+                legend_area,
+            );
+            return Ok(());
+        }
+
+        let legend = topics
+            .iter()
+            .flat_map(|topic| {
+                let (_, color) = color_of(topic);
+                [
+                    Span::from("● ").fg(color),
+                    Span::from(format!("{topic}   ")),
+                ]
+            })
+            .collect_vec();
+        f.render_widget(Paragraph::new(Line::from(legend)), legend_area);
+
+        if self.state.selected().is_none_or(|s| s >= timeline.len()) {
+            self.state.select(Some(0));
+        }
+        let (gaps, threshold) = gaps(&timeline);
+        let rows = timeline.iter().zip(gaps).map(|(record, gap)| {
+            let (lane, color) = color_of(&record.topic);
+            let lanes = (0..topics.len())
+                .map(|l| match l == lane {
+                    true => Span::from("● ").fg(color),
+                    false => Span::from("│ ").fg(colors[l % colors.len()]),
+                })
+                .collect_vec();
+            let gap = match gap {
+                Some(gap) if gap > threshold => Line::from(format_gap(gap, &state.units))
+                    .fg(state.theme.red)
+                    .bold(),
+                Some(gap) => Line::from(format_gap(gap, &state.units)),
+                None => Line::default(),
+            };
+            Row::new(vec![
+                Cell::from(
+                    record
+                        .timestamp_as_local_date_time()
+                        .map(|t| t.format("%H:%M:%S%.3f").to_string())
+                        .unwrap_or_default(),
+                )
+                .fg(state.theme.blue),
+                Cell::from(Text::from(gap).alignment(Alignment::Right)),
+                Cell::from(Line::from(lanes)),
+                Cell::from(format!("{}[{}]", record.topic, record.partition)).fg(color),
+                Cell::from(Text::from(record.offset.to_string()).alignment(Alignment::Right)),
+                Cell::from(record.key_as_string.clone()).fg(state.theme.green),
+            ])
+        });
+        let header = Row::new(vec![
+            Cell::from("Time").bold(),
+            Cell::from(Text::from("Gap").alignment(Alignment::Right)).bold(),
+            Cell::default(),
+            Cell::from("Topic").bold(),
+            Cell::from(Text::from("Offset").alignment(Alignment::Right)).bold(),
+            Cell::from("Key").bold(),
+        ]);
+        let topic_width = timeline
+            .iter()
+            .map(|record| format!("{}[{}]", record.topic, record.partition).len())
+            .max()
+            .unwrap_or_default();
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(12),
+                Constraint::Length(12),
+                Constraint::Length(topics.len() as u16 * 2),
+                Constraint::Length(topic_width as u16),
+                Constraint::Length(8),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(header)
+        .column_spacing(2)
+        .row_highlight_style(match state.is_focused(&self.id()) {
+            true => Style::default()
+                .bg(state.theme.bg_focused_selected)
+                .fg(state.theme.fg_focused_selected),
+            false => Style::default()
+                .bg(state.theme.bg_unfocused_selected)
+                .fg(state.theme.fg_unfocused_selected),
+        });
+        f.render_stateful_widget(table, table_area, &mut self.state);
+        Ok(())
+    }
+
+    fn shortcuts(&self) -> Vec<Shortcut> {
+        vec![
+            Shortcut::new("ENTER", "Open"),
+            Shortcut::new("↑↓", "Previous/next record"),
+        ]
+    }
+}
+
+#[cfg(test)]
+use crate::assert_draw;
+
+#[test]
+fn test_format_gap() {
+    let units = Units::default();
+    assert_eq!(format_gap(850, &units), "+850 ms");
+    assert_eq!(format_gap(1_250, &units), "+1 s 250 ms");
+    assert_eq!(format_gap(192_000, &units), "+3 m 12 s");
+    assert_eq!(format_gap(7_500_000, &units), "+2 h 5 m");
+    let units = Units {
+        duration_precision: 1,
+        ..units
+    };
+    assert_eq!(format_gap(192_000, &units), "+3 m");
+}
+
+#[test]
+fn test_draw() {
+    use crate::records_buffer::RecordsBuffer;
+    use std::sync::{Arc, LazyLock, Mutex};
+
+    static BUFFER: ConcurrentRecordsBuffer =
+        LazyLock::new(|| Arc::new(Mutex::new(RecordsBuffer::new())));
+    for (topic, offset, timestamp, key) in [
+        ("orders", 12, 1_748_908_800_000, "checkout-7"),
+        ("payments", 4, 1_748_908_800_120, "checkout-7"),
+        ("orders", 13, 1_748_908_800_200, "checkout-8"),
+        ("shipments", 2, 1_748_908_812_500, "checkout-7"),
+        ("payments", 5, 1_748_908_812_650, "checkout-8"),
+    ] {
+        BUFFER.lock().unwrap().push({
+            let mut record = KafkaRecord::default();
+            record.topic = topic.to_string();
+            record.offset = offset;
+            record.timestamp = Some(timestamp);
+            record.key_as_string = key.to_string();
+            record
+        });
+    }
+    let mut component = TimelineComponent::new(&BUFFER);
+    assert_draw!(component, 90, 12)
+}
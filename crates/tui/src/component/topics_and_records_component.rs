@@ -86,18 +86,21 @@ fn test_draw() {
     let topics_component = TopicsComponent::new(vec!["topic1".to_string()]);
     let records_component = RecordsComponent::new(&BUFFER);
     BUFFER.lock().unwrap().reset();
-    BUFFER.lock().unwrap().push(KafkaRecord {
-        topic: "movie-trailers".into(),
-        timestamp: None,
-        partition: 0,
-        offset: 314,
-        headers: BTreeMap::default(),
-        key_schema: None,
-        value_schema: None,
-        size: 4348,
-        key: DataType::String("7f12bd3b-4c96-4ba1-b010-8092234eec13".into()),
-        key_as_string: "7f12bd3b-4c96-4ba1-b010-8092234eec13".into(),
-        value: DataType::Json(json!(
+    BUFFER.lock().unwrap().push({
+        let mut record = KafkaRecord::default();
+        record.topic = "movie-trailers".into();
+        record.timestamp = None;
+        record.timestamp_type = None;
+        record.partition = 0;
+        record.offset = 314;
+        record.headers = BTreeMap::default();
+        record.key_schema = None;
+        record.value_schema = None;
+        record.value_format = None;
+        record.size = 4348;
+        record.key = DataType::String("7f12bd3b-4c96-4ba1-b010-8092234eec13".into());
+        record.key_as_string = "7f12bd3b-4c96-4ba1-b010-8092234eec13".into();
+        record.value = DataType::Json(json!(
             r#"{
             {
             "title" : "Swiss Army Man",
@@ -105,8 +108,12 @@ fn test_draw() {
             }
 
             }"#
-        )),
-        value_as_string: String::default(),
+        ));
+        record.value_as_string = String::default();
+        record.raw_value = None;
+        record.readable_value = None;
+        record.truncated = false;
+        record
     });
 
     let mut component = TopicsAndRecordsComponent::new(
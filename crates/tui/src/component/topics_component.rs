@@ -1,22 +1,31 @@
 //! Component listing all the kafa topics that can be consumed
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
 use itertools::Itertools;
+use lib::{CountMethod, TopicPreview};
 use ratatui::prelude::Stylize;
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Position, Rect},
     style::Style,
+    text::{Line, Span},
     widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Padding, Paragraph},
 };
+use thousands::Separable;
 use tokio::sync::mpsc::UnboundedSender;
 use tui_input::{Input, backend::crossterm::EventHandler};
 
 use crate::component::topics_list::TopicList;
 use crate::{Action, error::TuiError};
 
-use super::{Component, ComponentName, Shortcut, State};
+use super::{Component, ComponentName, Shortcut, State, styles};
+
+/// Delay before fetching the preview of the hovered topic
+const PREVIEW_DELAY: Duration = Duration::from_millis(400);
+/// Minimum height of the component to display the preview pane
+const PREVIEW_MIN_HEIGHT: u16 = 15;
 
 #[derive(Default)]
 pub(crate) struct TopicsComponent {
@@ -25,6 +34,10 @@ pub(crate) struct TopicsComponent {
     action_tx: Option<UnboundedSender<Action>>,
     input: Input,
     loading: bool,
+    /// The hovered topic and since when
+    hovered: Option<(String, Instant)>,
+    /// Previews of topics, `None` while being fetched
+    previews: HashMap<String, Option<TopicPreview>>,
 }
 
 impl TopicsComponent {
@@ -78,6 +91,95 @@ impl TopicsComponent {
         }
     }
 
+    /// Describes the hovered topic in the status line.
+    fn announce(&self) -> Result<(), TuiError> {
+        let topics = self.topics.get_with_selection();
+        if let (Some(i), Some(tx)) = (self.state.selected(), &self.action_tx)
+            && let Some((topic, selected)) = topics.get(i)
+        {
+            tx.send(Action::Announce(format!(
+                "Topic {} of {}, {topic}, {}",
+                i + 1,
+                topics.len(),
+                match selected {
+                    true => "selected",
+                    false => "not selected",
+                }
+            )))?;
+        }
+        Ok(())
+    }
+
+    fn hovered_topic(&self) -> Option<String> {
+        self.state
+            .selected()
+            .and_then(|i| self.topics.get().get(i).map(|t| t.to_string()))
+    }
+
+    /// Requests the preview of the hovered topic once the user stopped moving in the list.
+    fn request_preview(&mut self) -> Result<(), TuiError> {
+        let topic = self.hovered_topic();
+        match (&self.hovered, topic) {
+            (_, None) => self.hovered = None,
+            (Some((hovered, since)), Some(topic)) if *hovered == topic => {
+                if since.elapsed() >= PREVIEW_DELAY && !self.previews.contains_key(&topic) {
+                    self.previews.insert(topic.clone(), None);
+                    if let Some(tx) = &self.action_tx {
+                        tx.send(Action::RequestTopicPreview(topic))?;
+                    }
+                }
+            }
+            (_, Some(topic)) => self.hovered = Some((topic, Instant::now())),
+        }
+        Ok(())
+    }
+
+    fn draw_preview(&self, f: &mut Frame<'_>, rect: Rect, state: &State) {
+        let Some((topic, _)) = &self.hovered else {
+            return;
+        };
+        let Some(preview) = self.previews.get(topic) else {
+            return;
+        };
+        let block = Block::default()
+            .borders(Borders::TOP)
+            .border_type(BorderType::Rounded)
+            .padding(Padding::left(1))
+            .title(" Preview ");
+
+        let lines = match preview {
+            None => vec![Line::from("[/] Loading preview...")],
+            Some(preview) => {
+                let mut lines = vec![Line::from(vec![
+                    Span::from(preview.partitions.to_string()).bold(),
+                    Span::from(match preview.partitions {
+                        1 => " partition, ~",
+                        _ => " partitions, ~",
+                    }),
+                    Span::from(preview.count.separate_with_underscores()).bold(),
+                    Span::from(" records"),
+                ])];
+                match &preview.sample {
+                    Some(record) => {
+                        let mut timestamp = styles::colorize_timestamp(record, &state.theme);
+                        timestamp.spans.insert(0, Span::from("Latest: "));
+                        lines.push(timestamp);
+                        lines.push(Line::from(vec![
+                            Span::from(record.key_as_string.clone()).fg(state.theme.green),
+                            Span::from(" → "),
+                            Span::from(record.value_as_string.replace('\n', " ")),
+                        ]));
+                    }
+                    None => lines.push(Line::from("No records").italic()),
+                }
+                lines
+            }
+        };
+
+        f.render_widget(Clear, rect);
+        f.render_widget(Paragraph::new(lines).block(block), rect);
+    }
+
     fn filter_topics(&mut self) {
         self.topics.set_filter(self.input.value().trim());
         if self.topics.get().is_empty() {
@@ -109,7 +211,7 @@ impl Component for TopicsComponent {
                     self.action_tx
                         .as_ref()
                         .unwrap()
-                        .send(Action::RequestTopicDetails(h))?;
+                        .send(Action::RequestTopicDetails(h, CountMethod::default()))?;
                 }
             }
             KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
@@ -125,8 +227,14 @@ impl Component for TopicsComponent {
                     .unwrap()
                     .send(Action::SelectedTopics(vec![]))?;
             }
-            KeyCode::Up => self.previous(),
-            KeyCode::Down => self.next(),
+            KeyCode::Up => {
+                self.previous();
+                self.announce()?;
+            }
+            KeyCode::Down => {
+                self.next();
+                self.announce()?;
+            }
             KeyCode::Enter => {
                 if self.state.selected().is_none() {
                     return Ok(None);
@@ -153,6 +261,12 @@ impl Component for TopicsComponent {
                     .clone()
                     .unwrap()
                     .send(Action::RefreshShortcuts)?;
+                self.action_tx.clone().unwrap().send(Action::Announce(
+                    match self.topics.selected().contains(&topic) {
+                        true => format!("Topic {topic} selected"),
+                        false => format!("Topic {topic} not selected"),
+                    },
+                ))?;
             }
             KeyCode::Esc => (),
             _ => {
@@ -166,16 +280,25 @@ impl Component for TopicsComponent {
     }
 
     fn update(&mut self, action: Action) -> Result<Option<Action>, TuiError> {
-        if let Action::Topics(new_topics) = action {
-            self.topics.refresh_topics(new_topics);
-            self.loading = false;
-            if !self.topics.get().is_empty() {
-                let selected = self.state.selected().unwrap_or(0);
-                match selected < self.topics.get().len() {
-                    true => self.state.select(Some(selected)),
-                    false => self.state.select(Some(0)),
+        match action {
+            Action::Topics(new_topics) => {
+                self.topics.refresh_topics(new_topics);
+                self.loading = false;
+                self.previews.clear();
+                if !self.topics.get().is_empty() {
+                    let selected = self.state.selected().unwrap_or(0);
+                    match selected < self.topics.get().len() {
+                        true => self.state.select(Some(selected)),
+                        false => self.state.select(Some(0)),
+                    }
                 }
             }
+            Action::TraceSearch(topics, _) => self.topics.select_only(&topics),
+            Action::TopicPreview(preview) => {
+                self.previews.insert(preview.name.clone(), Some(preview));
+            }
+            Action::Tick => self.request_preview()?,
+            _ => (),
         }
         Ok(None)
     }
@@ -230,9 +353,18 @@ impl Component for TopicsComponent {
             .style(Style::default())
             .block(filter_block);
 
-        let inner = outer_block.inner(rect);
+        let mut inner = outer_block.inner(rect);
         f.render_widget(outer_block, rect);
 
+        if rect.height >= PREVIEW_MIN_HEIGHT {
+            let layout = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(4)])
+                .split(inner);
+            inner = layout[0];
+            self.draw_preview(f, layout[1], state);
+        }
+
         match self.input.value().is_empty() {
             true => f.render_stateful_widget(list, inner, &mut self.state),
             false => {
@@ -243,7 +375,8 @@ impl Component for TopicsComponent {
                     .split(inner);
                 if is_focused {
                     f.set_cursor_position(Position {
-                        x: layout[1].x + (self.input.visual_cursor()) as u16 + 2,
+                        x: (layout[1].x + self.input.visual_cursor() as u16 + 2)
+                            .min(layout[1].right().saturating_sub(2)),
                         y: layout[1].y + 1,
                     });
                 }
@@ -272,6 +405,16 @@ impl Component for TopicsComponent {
         }
         shortcuts
     }
+
+    fn key_bindings(&self) -> Vec<Shortcut> {
+        vec![
+            Shortcut::new("↑↓", "Previous/next topic"),
+            Shortcut::new("ENTER", "Select/unselect the topic"),
+            Shortcut::new("CTRL + P", "Show the details of the selected topics"),
+            Shortcut::new("CTRL + U", "Unselect all topics"),
+            Shortcut::new("Any character", "Filter the topics"),
+        ]
+    }
 }
 
 #[cfg(test)]
@@ -289,3 +432,34 @@ fn test_draw() {
         .unwrap();
     assert_draw!(component, 60, 5)
 }
+
+#[test]
+fn test_draw_preview() {
+    use lib::{DataType, KafkaRecord};
+
+    let mut component = TopicsComponent::default();
+    component
+        .update(Action::Topics(vec!["public-french-addresses".to_string()]))
+        .unwrap();
+    component.state.select(Some(0));
+    component.hovered = Some(("public-french-addresses".to_string(), Instant::now()));
+    component
+        .update(Action::TopicPreview({
+            let mut preview = TopicPreview::default();
+            preview.name = "public-french-addresses".to_string();
+            preview.partitions = 3;
+            preview.count = 12_345;
+            preview.latest_timestamp = Some(1_700_000_000_000);
+            preview.sample = Some({
+                let mut record = KafkaRecord::default();
+                record.key_as_string = "75001".to_string();
+                record.value_as_string = r#"{"city":"Paris"}"#.to_string();
+                record.key = DataType::String("75001".to_string());
+                record.timestamp = Some(1_700_000_000_000);
+                record
+            });
+            preview
+        }))
+        .unwrap();
+    assert_draw!(component, 60, 15)
+}
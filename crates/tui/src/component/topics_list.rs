@@ -90,6 +90,14 @@ impl TopicList {
         self.selected.clear();
     }
 
+    /// Selects the given topics, the other topics are unselected.
+    pub fn select_only(&mut self, topics: &[String]) {
+        self.clear_selected();
+        for topic in topics {
+            self.toggle_topics(topic);
+        }
+    }
+
     pub fn toggle_topics(&mut self, topic: &str) {
         if self.selected.contains(&topic.to_string()) {
             self.selected.retain(|t| t != topic);
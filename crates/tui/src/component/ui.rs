@@ -1,49 +1,113 @@
 //! Module gathering the code to run the terminal user interface.
 
-use app::App;
-use app::search::{Search, SearchContext};
-use chrono::DateTime;
+use app::configuration::{AlertAction, GlobalConfig, Workspace};
+use app::search::{Search, SearchContext, ValidSearchQuery};
+use app::{
+    Alert, App, AuditAction, Checkpointer, ConsumerError, DeepLink, HeaderEdit, InputFormat,
+    ProduceOptions, RecordTemplate, RecordsExporter, ReportingContext, ShutdownSignals, Watch,
+    is_template, read_records,
+};
+use chrono::{DateTime, Utc};
 use crossterm::event::KeyEvent;
-use futures::{StreamExt, future};
+use futures::StreamExt;
 use futures_batch::TryChunksTimeoutStreamExt;
 use itertools::Itertools;
-use lib::KafkaRecord;
+use lib::{CountMethod, ExportedKafkaRecord, KafkaRecord};
 use ratatui::prelude::Rect;
 use rdkafka::Message;
 use rdkafka::consumer::{Consumer, StreamConsumer};
+use rdkafka::error::KafkaError;
 use rdkafka::message::OwnedMessage;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::pin::pin;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use tokio::sync::mpsc::{self, UnboundedSender};
+use thousands::Separable;
+use tokio::sync::{
+    Notify,
+    mpsc::{self, UnboundedSender},
+};
+use tokio::task::JoinHandle;
 use tokio::time::Instant;
 use tokio::{select, time};
 use tokio_util::sync::CancellationToken;
 use tracing::{error, info, trace_span, warn};
 
-use crate::action::{Action, Level, Notification};
+use crate::action::{Action, Level, Notification, ProduceRequest};
 use crate::component::{Component, RootComponent};
+use crate::config_watcher::{ConfigOverrides, ConfigWatcher};
 use crate::error::TuiError;
-use crate::schema_detail::SchemaDetail;
-use crate::tui;
+use crate::highlighter::Highlighter;
+use crate::report::{record_report, records_report};
+use crate::schema_detail::{SchemaDetail, SchemaVersions};
+use crate::session::{self, SessionEvent, SessionRecorder};
+use crate::{Theme, tui};
 
-use super::{BUFFER, ConcurrentRecordsBuffer, State};
+use super::{BUFFER, ComponentName, ConcurrentRecordsBuffer, State};
 
 pub struct Ui {
     app: App,
     should_quit: bool,
     root: RootComponent,
     worker: CancellationToken,
+    /// Cancels the reading of the topics shown in the table view
+    table_worker: CancellationToken,
     topics: Vec<String>,
     last_tick_key_events: Vec<KeyEvent>,
     records_sender: Option<UnboundedSender<KafkaRecord>>,
     records: &'static ConcurrentRecordsBuffer,
+    watcher: ConfigWatcher,
+    /// Name of the current theme
+    theme: String,
+    /// When the configuration and themes files were checked for the last time
+    config_checked_at: Instant,
+    recorder: Option<Arc<SessionRecorder>>,
+    /// Session file to replay and the speed of the replay
+    replay: Option<(PathBuf, f64)>,
+    /// Resumes the first search from its checkpoint
+    resume: bool,
+    /// Records exporter receiving the records matching the search query, when enabled
+    live_export: Arc<Mutex<Option<UnboundedSender<KafkaRecord>>>>,
+    /// Tasks writing into the export files, they are awaited before quitting
+    exporters: Mutex<Vec<JoinHandle<()>>>,
+    /// Workspace the user switched to before quitting
+    next_workspace: Option<String>,
+    /// Stops the latency probe, when it is running
+    latency_probe: Option<CancellationToken>,
+    /// Wakes up the render loop when records are read while the TUI is idle, see [`tui::Tui::activity`]
+    activity: Arc<Notify>,
 }
 
+/// Time between 2 checks of the configuration and themes files
+const CONFIG_WATCHER_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Time between two canary records of the latency probe
+const LATENCY_PROBE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Maximum time spent completing the exports in progress before quitting
+const EXPORTS_FLUSH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Number of consecutive failures of the consumer before the search is stopped
+const MAX_RECONNECTIONS: u32 = 10;
+
 impl Ui {
     pub fn new(app: App, query: &str, selected_topics: Vec<String>, state: State) -> Self {
         Self {
             should_quit: false,
             worker: CancellationToken::new(),
+            table_worker: CancellationToken::new(),
+            watcher: ConfigWatcher::new(app.config.workspace()),
+            theme: state.theme.name.clone(),
+            config_checked_at: Instant::now(),
+            recorder: None,
+            replay: None,
+            resume: false,
+            live_export: Arc::new(Mutex::new(None)),
+            exporters: Mutex::new(vec![]),
+            next_workspace: None,
+            latency_probe: None,
+            activity: Arc::new(Notify::new()),
             app,
             records: &BUFFER,
             topics: vec![],
@@ -53,35 +117,160 @@ impl Ui {
         }
     }
 
+    /// Records key strokes and kafka records in the given file.
+    pub fn with_session_recorder(mut self, file: &Path) -> Result<Self, TuiError> {
+        self.recorder = Some(Arc::new(SessionRecorder::new(file)?));
+        Ok(self)
+    }
+
+    /// Applies the overrides of the command line each time the configuration file is reloaded.
+    pub fn with_config_overrides(mut self, overrides: ConfigOverrides) -> Self {
+        self.watcher = self.watcher.with_overrides(overrides);
+        self
+    }
+
+    /// Replays a recorded session instead of consuming kafka records.
+    pub fn with_session_replay(mut self, file: &Path, speed: f64) -> Self {
+        self.replay = Some((file.to_path_buf(), speed));
+        self
+    }
+
+    /// Resumes the initial search from the checkpoint of its previous run, see [`app::Checkpoint`].
+    pub fn with_resume(mut self, resume: bool) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    fn record(&self, event: SessionEvent) {
+        if let Some(recorder) = &self.recorder {
+            recorder.record(event);
+        }
+    }
+
+    fn replay_session(
+        &self,
+        action_tx: UnboundedSender<Action>,
+        event_tx: UnboundedSender<tui::Event>,
+    ) {
+        let Some((file, speed)) = self.replay.clone() else {
+            return;
+        };
+        let records = self.records;
+        let activity = self.activity.clone();
+        tokio::task::Builder::new()
+            .name("session-replay")
+            .spawn(async move {
+                let result = session::replay(&file, speed, |event| match event {
+                    SessionEvent::Key { key } => {
+                        let _ = event_tx.send(tui::Event::Key(key));
+                    }
+                    SessionEvent::Topics { topics } => {
+                        let _ = action_tx.send(Action::Topics(topics));
+                    }
+                    SessionEvent::Record { record } => {
+                        let mut buffer = records.lock().unwrap();
+                        buffer.new_record_read();
+                        buffer.push(record);
+                        buffer.dispatch_metrics();
+                        activity.notify_waiters();
+                    }
+                })
+                .await;
+                let notification = match result {
+                    Ok(()) => Notification::new(Level::Info, "End of the replay".to_string()),
+                    Err(e) => Notification::new(Level::Error, e.to_string()),
+                };
+                let _ = action_tx.send(Action::Notification(notification));
+            })
+            .unwrap();
+    }
+
+    /// Creates a kafka consumer, the errors librdkafka reports in the background
+    /// and the state of the connection to the brokers are shown to the user.
     pub(crate) fn create_consumer(
         app: &App,
         topics: Vec<String>,
+        positions: &HashMap<(String, i32), i64>,
         tx: UnboundedSender<Action>,
-    ) -> Result<StreamConsumer, TuiError> {
-        match app.create_consumer(&topics) {
+    ) -> Result<StreamConsumer<ReportingContext>, TuiError> {
+        let on_error = tx.clone();
+        let on_statistics = tx.clone();
+        let context = ReportingContext::new(
+            move |error| {
+                let _ = on_error.send(Action::ConsumerError(error));
+            },
+            move |statistics| {
+                let _ = on_statistics.send(Action::Statistics(statistics));
+            },
+        );
+        match app.create_reporting_consumer(&topics, context, positions) {
             Ok(c) => Ok(c),
             Err(e) => {
-                tx.send(Action::Notification(Notification::new(
-                    Level::Error,
-                    e.to_string(),
-                )))?;
+                tx.send(match &e {
+                    lib::Error::KafkaError(k) => Action::ConsumerError(k.into()),
+                    _ => Action::Notification(Notification::new(Level::Error, e.to_string())),
+                })?;
                 error!("Something went wrong when trying to consume topics: {e}");
                 Err(e.into())
             }
         }
     }
 
+    /// Waits before connecting again, the delay doubles after each failed attempt, up to a minute.
+    /// Returns `false` when the search has been cancelled in the meantime
+    /// or when the consumer failed [`MAX_RECONNECTIONS`] times in a row, the error is then reported.
+    async fn backoff(
+        attempt: &mut u32,
+        error: &str,
+        token: &CancellationToken,
+        tx: &UnboundedSender<Action>,
+    ) -> bool {
+        if *attempt >= MAX_RECONNECTIONS {
+            let _ = tx.send(Action::Notification(Notification::new(
+                Level::Error,
+                format!(
+                    "The consumer failed {attempt} times in a row, the search is stopped: {error}"
+                ),
+            )));
+            return false;
+        }
+        let delay = Duration::from_secs(2_u64.pow((*attempt).min(6)).min(60));
+        *attempt += 1;
+        let _ = tx.send(Action::Notification(Notification::new(
+            Level::Warn,
+            format!("Reconnecting in {}s, attempt {attempt}", delay.as_secs()),
+        )));
+        select! {
+            () = token.cancelled() => false,
+            () = time::sleep(delay) => true,
+        }
+    }
+
     pub(crate) fn consume_topics(&mut self, tx: UnboundedSender<Action>) -> Result<(), TuiError> {
         self.worker.cancel();
         {
-            self.records.lock().unwrap().reset();
+            let mut records = self.records.lock().unwrap();
+            records.reset();
+            records.set_sample(self.app.search_query.sample());
+            records.set_distinct(self.app.search_query.distinct());
         }
 
-        if self.topics.is_empty() {
+        if self.topics.is_empty() || self.replay.is_some() {
             tx.send(Action::StopConsuming())?;
             return Ok(());
         }
 
+        if let Err(e) = self.app.audit(
+            AuditAction::Search,
+            &self.topics,
+            self.app.search_query.input(),
+        ) {
+            tx.send(Action::Notification(Notification::new(
+                Level::Error,
+                format!("Cannot write the search to the audit log: {e}"),
+            )))?;
+        }
+
         let message = match self.app.search_query.is_empty() {
             true => "Waiting for new events".to_string(),
             false => "Searching".to_string(),
@@ -91,6 +280,36 @@ impl Ui {
             Level::Info,
             message,
         )))?;
+        // Only the initial search resumes, the next ones are new searches
+        let resume = std::mem::take(&mut self.resume);
+        let mut checkpointer = self
+            .app
+            .checkpointer(&self.topics, resume)
+            .unwrap_or_else(|e| {
+                let _ = tx.send(Action::Notification(Notification::new(
+                    Level::Error,
+                    format!("Cannot resume the search: {e}"),
+                )));
+                None
+            });
+        let resumed_positions = checkpointer
+            .as_ref()
+            .map(Checkpointer::positions)
+            .unwrap_or_default();
+        // Records matched before the resume still count towards the limit of the query
+        let resumed_matches = checkpointer
+            .as_ref()
+            .map(Checkpointer::matched)
+            .unwrap_or_default();
+        if !resumed_positions.is_empty() {
+            tx.send(Action::Notification(Notification::new(
+                Level::Info,
+                format!(
+                    "Search resumed from its checkpoint on {} partitions",
+                    resumed_positions.len()
+                ),
+            )))?;
+        }
         self.worker = CancellationToken::new();
 
         let query = self.app.search_query.query().clone();
@@ -112,6 +331,24 @@ impl Ui {
                 }
             })
             .unwrap();
+        let app = self.app.clone();
+        let topics = self.topics.clone();
+        let positions = resumed_positions.clone();
+        let warning_tx = tx.clone();
+        tokio::task::Builder::new()
+            .name("retention-warnings")
+            .spawn_blocking(move || match app.retention_warnings(&topics, &positions) {
+                Ok(warnings) => {
+                    for warning in warnings {
+                        let _ = warning_tx.send(Action::Notification(Notification::new(
+                            Level::Warn,
+                            warning.to_string(),
+                        )));
+                    }
+                }
+                Err(e) => warn!("Cannot check the retention of the topics: {e}"),
+            })
+            .unwrap();
         let r = self.records;
         let token = self.worker.clone();
         let search_query = self.app.search_query.query().clone();
@@ -124,16 +361,40 @@ impl Ui {
         let token_cloned = token.clone();
 
         let filters_directory = self.app.config.workspace().filters_dir();
+        let recorder = self.recorder.clone();
+        let activity = self.activity.clone();
+        let live_export = self.live_export.clone();
+        let parser = self.app.clone();
+        let value_preview_size = self.app.consumer_config().value_preview_size;
+        let config = self.app.config.workspace().config();
+        let mut watch = Watch::new(&config.alerts, &config.notifiers, &filters_directory)
+            .map(|watch| watch.since(Utc::now().timestamp_millis()))
+            .unwrap_or_else(|e| {
+                let _ = tx.send(Action::Notification(Notification::new(
+                    Level::Error,
+                    e.to_string(),
+                )));
+                Watch::default()
+            });
+        let alert_tx = tx.clone();
         tokio::task::Builder::new()
             .name("search-engine")
         .spawn(async move {
             loop {
                 select! {
                     _ = token_cloned.cancelled() => {
+                        if let Some(checkpointer) = checkpointer.as_mut() {
+                            if let Err(e) = checkpointer.save().await {
+                                warn!("Cannot save the checkpoint of the search: {e}");
+                            }
+                        }
                         return;
                      },
                     Some(message) = rx_dd.recv() => {
-                        let record = KafkaRecord::parse(message, &mut schema_registry).await;
+                        let record = parser.parse_record(message, &mut schema_registry).await;
+                        for alert in watch.observe(&record, &filters_directory) {
+                            let _ = alert_tx.send(Action::Alert(alert));
+                        }
                         let context = SearchContext::new(&record, &filters_directory);
                         let span = trace_span!("matching", offset = %record.offset, partition = %record.partition, topic = %record.topic);
                         let search_span = span.enter();
@@ -142,19 +403,41 @@ impl Ui {
                             matched = true;
                         }
                         drop(search_span);
+                        // Recorded before the buffer is locked, the session file is written outside of it
+                        if matched {
+                            if let Some(recorder) = &recorder {
+                                recorder.record(SessionEvent::Record { record: record.clone() });
+                            }
+                        }
                         let stats = {
                             let push_span = trace_span!("push-to-buffer", offset = %record.offset, partition = %record.partition, topic = %record.topic);
                             let _ = push_span.enter();
                             let mut ll = r.lock().unwrap();
                             ll.new_record_read();
                             if matched {
-                                ll.push(record.clone());
+                                if let Some(exporter) = live_export.lock().unwrap().as_ref() {
+                                    let _ = exporter.send(record.clone());
+                                }
+                                ll.push_preview(record.clone(), value_preview_size);
                             }
                             ll.dispatch_metrics();
                             ll.stats()
                         };
+                        activity.notify_waiters();
+                        // The record is checkpointed once it is in the buffer
+                        if let Some(checkpointer) = checkpointer.as_mut() {
+                            if let Err(e) = checkpointer.processed(&record, matched).await {
+                                warn!("Cannot save the checkpoint of the search: {e}");
+                            }
+                        }
                         if let Some(limit) = query.limit {
-                            if Some(stats.matched) >= Some(limit) {
+                            if stats.matched + resumed_matches >= limit {
+                                // The search is complete, there is nothing left to resume
+                                if let Some(checkpointer) = checkpointer.take() {
+                                    if let Err(e) = checkpointer.complete().await {
+                                        warn!("Cannot delete the checkpoint of the search: {e}");
+                                    }
+                                }
                                 token_cloned.cancel();
                             }
                         }
@@ -164,49 +447,107 @@ impl Ui {
         }).unwrap();
 
         let consumer_config = self.app.consumer_config();
+        let scan_limit = self.app.scan_limit();
         tokio::task::Builder::new()
             .name("kafka-consumer")
             .spawn(async move {
                 let _ = tx.send(Action::Consuming);
-                let consumer = match Self::create_consumer(&app, topics.clone(), txx.clone()) {
-                    Ok(c) => c,
-                    Err(e) => {
-                        let _ = tx.send(Action::StopConsuming());
-                        warn!("I was not able to create a consumer: {e}");
-                        return Err("I was not able to create a consumer after 5 attempts...");
-                    }
-                };
-                let _ = tx.send(Action::Consuming);
-                let assignments = consumer.assignment().unwrap();
-                let txx = tx.clone();
-                tokio::task::Builder::new()
-                    .name("records-to-read")
-                    .spawn(async move {
-                        let count = app
-                            .estimate_number_of_records_to_read(&assignments)
-                            .unwrap_or(0);
-                        let _ = txx.send(Action::RecordsToRead(count as usize));
-                    })
-                    .unwrap();
+                // Offset of the last consumed record of each partition, to resume where the consumer stopped
+                let mut positions: HashMap<(String, i32), i64> = resumed_positions;
+                // Number of records and bytes read, checked against the scan limit
+                let (mut records_read, mut bytes_read) = (0, 0);
+                let mut attempt = 0;
+                let mut estimate_records = true;
                 let mut current_time = Instant::now();
+                loop {
+                    let consumer = match Self::create_consumer(
+                        &app,
+                        topics.clone(),
+                        &positions,
+                        txx.clone(),
+                    ) {
+                        Ok(c) => c,
+                        Err(e) => {
+                            warn!("I was not able to create a consumer: {e}");
+                            match Self::backoff(&mut attempt, &e.to_string(), &token, &tx).await {
+                                true => continue,
+                                false => break,
+                            }
+                        }
+                    };
+                    let _ = tx.send(Action::Consuming);
+                    // The number of records to read is estimated once, reconnections resume the same search
+                    if std::mem::take(&mut estimate_records) {
+                        match consumer.assignment() {
+                            Ok(assignments) => {
+                                let txx = tx.clone();
+                                let app = app.clone();
+                                tokio::task::Builder::new()
+                                    .name("records-to-read")
+                                    .spawn(async move {
+                                        let count = app
+                                            .estimate_number_of_records_to_read(&assignments)
+                                            .unwrap_or(0);
+                                        let _ = txx.send(Action::RecordsToRead(count as usize));
+                                    })
+                                    .unwrap();
+                            }
+                            Err(e) => warn!("Cannot estimate the number of records to read: {e}"),
+                        }
+                    }
 
-                let _ = consumer
-                    .stream()
-                    .take_until(token.cancelled())
-                    .try_chunks_timeout(
-                        consumer_config.buffer_capacity,
-                        Duration::from_millis(consumer_config.timeout_in_ms),
-                    )
-                    .for_each(|bulk_of_records| {
-                        let bulk_of_records = bulk_of_records.unwrap();
+                    let mut stream = pin!(
+                        consumer
+                            .stream()
+                            .take_until(token.cancelled())
+                            .try_chunks_timeout(
+                                consumer_config.buffer_capacity,
+                                Duration::from_millis(consumer_config.timeout_in_ms),
+                            )
+                    );
+                    let mut fatal = None;
+                    while let Some(bulk_of_records) = stream.next().await {
+                        let bulk_of_records = match bulk_of_records {
+                            Ok(records) => records,
+                            Err(e) => {
+                                warn!("Cannot consume records: {e}");
+                                let _ = tx.send(Action::ConsumerError(ConsumerError::from(&e)));
+                                if matches!(e, KafkaError::MessageConsumptionFatal(_)) {
+                                    fatal = Some(e);
+                                    break;
+                                }
+                                continue;
+                            }
+                        };
+                        // The consumer works again, the next failure starts a new series of attempts
+                        attempt = 0;
                         info!("Received a bulk of records: {}", bulk_of_records.len());
                         let timestamp = bulk_of_records
                             .last()
                             .and_then(|r| r.timestamp().to_millis())
                             .unwrap_or(0);
                         for record in bulk_of_records {
+                            positions.insert(
+                                (record.topic().to_string(), record.partition()),
+                                record.offset(),
+                            );
+                            records_read += 1;
+                            bytes_read += (record.key_len() + record.payload_len()) as u64;
                             tx_dd.send(record.detach()).unwrap();
                         }
+                        if let Some(scan_limit) = scan_limit {
+                            if !token.is_cancelled()
+                                && scan_limit.is_reached(records_read, bytes_read)
+                            {
+                                let _ = tx.send(Action::Notification(Notification::new(
+                                    Level::Warn,
+                                    format!(
+                                        "Scan limit of {scan_limit} reached, the search is stopped"
+                                    ),
+                                )));
+                                token.cancel();
+                            }
+                        }
                         if current_time.elapsed() > Duration::from_secs(13) {
                             current_time = Instant::now();
 
@@ -219,30 +560,92 @@ impl Ui {
                             )))
                             .unwrap();
                         }
-                        future::ready(())
-                    })
-                    .await;
-                consumer.unassign().unwrap();
+                    }
+                    let _ = consumer.unassign();
+                    let Some(e) = fatal else {
+                        break;
+                    };
+                    if !Self::backoff(&mut attempt, &e.to_string(), &token, &tx).await {
+                        break;
+                    }
+                }
                 info!("Consumer is terminated");
                 token.cancel();
                 r.lock().unwrap().sort(&query.order_by);
                 let _ = tx.send(Action::StopConsuming());
-                Ok(())
             })
             .unwrap();
         Ok(())
     }
 
+    /// Reads the selected topics from the beginning up to their end,
+    /// the table view keeps the latest record of each key.
+    pub(crate) fn read_compacted_topics(&mut self, tx: UnboundedSender<Action>) {
+        self.table_worker.cancel();
+        self.table_worker = CancellationToken::new();
+        if self.topics.is_empty() || self.replay.is_some() {
+            let _ = tx.send(Action::CompactedTopicsRead);
+            return;
+        }
+        let app = self.app.clone();
+        let topics = self.topics.clone();
+        let token = self.table_worker.clone();
+        tokio::task::Builder::new()
+            .name("compacted-topics-reader")
+            .spawn(async move {
+                let consumer = match app.create_compacted_consumer(&topics) {
+                    Ok(consumer) => consumer,
+                    Err(e) => {
+                        let _ = tx.send(Action::Notification(Notification::new(
+                            Level::Error,
+                            format!("Cannot read the topics: {e}"),
+                        )));
+                        let _ = tx.send(Action::CompactedTopicsRead);
+                        return;
+                    }
+                };
+                let partitions = consumer.assignment().map(|a| a.count()).unwrap_or(0);
+                let mut schema_registry = app.schema_registry();
+                let mut read_partitions = 0;
+                let mut stream = pin!(
+                    consumer
+                        .stream()
+                        .take_until(token.cancelled())
+                        .ready_chunks(1_000)
+                );
+                while let Some(messages) = stream.next().await {
+                    let mut records = Vec::with_capacity(messages.len());
+                    for message in messages {
+                        match message {
+                            Ok(message) => records.push(
+                                app.parse_record(message.detach(), &mut schema_registry)
+                                    .await,
+                            ),
+                            Err(KafkaError::PartitionEOF(_)) => read_partitions += 1,
+                            Err(e) => warn!("Cannot read the compacted topics: {e}"),
+                        }
+                    }
+                    let _ = tx.send(Action::CompactedRecords(records));
+                    if read_partitions >= partitions {
+                        break;
+                    }
+                }
+                let _ = tx.send(Action::CompactedTopicsRead);
+            })
+            .unwrap();
+    }
+
     pub(crate) fn topics_details(
         &mut self,
         topics: HashSet<String>,
+        count_method: CountMethod,
         action_tx: UnboundedSender<Action>,
     ) {
         let app = self.app.clone();
         tokio::task::Builder::new()
             .name("topics-details")
             .spawn(async move {
-                match app.topic_details(topics) {
+                match app.topic_details(topics, count_method) {
                     Ok(details) => action_tx.send(Action::TopicDetails(details)).unwrap(),
                     Err(e) => action_tx
                         .send(Action::Notification(Notification::new(
@@ -255,20 +658,773 @@ impl Ui {
             .unwrap();
     }
 
+    pub(crate) fn broker_configs(&mut self, action_tx: UnboundedSender<Action>) {
+        let app = self.app.clone();
+        tokio::task::Builder::new()
+            .name("broker-configs")
+            .spawn(async move {
+                let action = match app.broker_configs().await {
+                    Ok(entries) => Action::BrokerConfigs(entries),
+                    Err(e) => Action::Notification(Notification::new(Level::Error, e.to_string())),
+                };
+                let _ = action_tx.send(action);
+                // The configs are listed even when the quotas cannot be described
+                let action = match app.client_quotas().await {
+                    Ok(quotas) => Action::ClientQuotas(quotas),
+                    Err(e) => Action::Notification(Notification::new(
+                        Level::Warn,
+                        format!("Cannot describe the client quotas: {e}"),
+                    )),
+                };
+                let _ = action_tx.send(action);
+            })
+            .unwrap();
+    }
+
+    pub(crate) fn consumer_groups(&mut self, action_tx: UnboundedSender<Action>) {
+        let app = self.app.clone();
+        tokio::task::Builder::new()
+            .name("consumer-groups")
+            .spawn(async move {
+                let action = match app.consumer_groups() {
+                    Ok(groups) => Action::ConsumerGroups(groups),
+                    Err(e) => Action::Notification(Notification::new(Level::Error, e.to_string())),
+                };
+                let _ = action_tx.send(action);
+            })
+            .unwrap();
+    }
+
+    /// Computes how the search query would be executed on the selected topics.
+    pub(crate) fn query_plan(
+        &mut self,
+        search_query: ValidSearchQuery,
+        action_tx: UnboundedSender<Action>,
+    ) {
+        let mut app = self.app.clone();
+        app.search_query = search_query;
+        let topics = self.topics.clone();
+        tokio::task::Builder::new()
+            .name("query-plan")
+            .spawn(async move {
+                let action = match app.query_plan(&topics) {
+                    Ok(plan) => Action::QueryPlan(plan),
+                    Err(e) => Action::Notification(Notification::new(Level::Error, e.to_string())),
+                };
+                let _ = action_tx.send(action);
+            })
+            .unwrap();
+    }
+
+    pub(crate) fn topic_preview(&mut self, topic: String, action_tx: UnboundedSender<Action>) {
+        let app = self.app.clone();
+        tokio::task::Builder::new()
+            .name("topic-preview")
+            .spawn(async move {
+                match app.topic_preview(&topic).await {
+                    Ok(preview) => action_tx.send(Action::TopicPreview(preview)).unwrap(),
+                    Err(e) => action_tx
+                        .send(Action::Notification(Notification::new(
+                            Level::Error,
+                            e.to_string(),
+                        )))
+                        .unwrap(),
+                }
+            })
+            .unwrap();
+    }
+
+    /// Reads again the record whose value was truncated and dispatches the action built with the full record.
+    pub(crate) fn full_record(
+        &self,
+        record: KafkaRecord,
+        action: fn(KafkaRecord) -> Action,
+        action_tx: UnboundedSender<Action>,
+    ) {
+        let app = self.app.clone();
+        tokio::task::Builder::new()
+            .name("full-record")
+            .spawn(async move {
+                match app
+                    .fetch_record(&record.topic, record.partition, record.offset)
+                    .await
+                {
+                    Ok(record) => action_tx.send(action(record)).unwrap(),
+                    Err(e) => action_tx
+                        .send(Action::Notification(Notification::new(
+                            Level::Error,
+                            format!("Cannot read the full value of the record: {e}"),
+                        )))
+                        .unwrap(),
+                }
+            })
+            .unwrap();
+    }
+
+    /// Reads the record before or after the given record on its partition, and shows it in the record details.
+    pub(crate) fn adjacent_record(
+        &self,
+        record: KafkaRecord,
+        next: bool,
+        action_tx: UnboundedSender<Action>,
+    ) {
+        let app = self.app.clone();
+        tokio::task::Builder::new()
+            .name("adjacent-record")
+            .spawn(async move {
+                match app
+                    .fetch_adjacent_record(&record.topic, record.partition, record.offset, next)
+                    .await
+                {
+                    Ok(record) => action_tx.send(Action::ShowRecord(record)).unwrap(),
+                    Err(e) => action_tx
+                        .send(Action::Notification(Notification::new(
+                            Level::Info,
+                            e.to_string(),
+                        )))
+                        .unwrap(),
+                }
+            })
+            .unwrap();
+    }
+
+    pub(crate) fn schema_versions(
+        &self,
+        topic: String,
+        id: u32,
+        action_tx: UnboundedSender<Action>,
+    ) {
+        let Some(mut schema_registry) = self.app.schema_registry() else {
+            let _ = action_tx.send(Action::Notification(Notification::new(
+                Level::Info,
+                "No schema registry configured for this cluster".to_string(),
+            )));
+            return;
+        };
+        tokio::task::Builder::new()
+            .name("schema-versions")
+            .spawn(async move {
+                let schema_registry = schema_registry.for_topic(&topic);
+                let versions = async {
+                    let subject = schema_registry
+                        .subjects_of(id)
+                        .await?
+                        .into_iter()
+                        .next()
+                        .ok_or(lib::Error::Error(format!(
+                            "No subject found for schema {id}"
+                        )))?
+                        .subject;
+                    let versions = schema_registry.versions(&subject).await?;
+                    let deleted = schema_registry.deleted_versions(&subject).await?;
+                    Ok::<_, lib::Error>(
+                        SchemaVersions::new(subject, versions).with_deleted(deleted),
+                    )
+                };
+                let action = match versions.await {
+                    Ok(versions) => Action::SchemaVersions(versions),
+                    Err(e) => Action::Notification(Notification::new(Level::Error, e.to_string())),
+                };
+                let _ = action_tx.send(action);
+            })
+            .unwrap();
+    }
+
+    pub(crate) fn schema_version(
+        &self,
+        topic: String,
+        subject: String,
+        version: u32,
+        action_tx: UnboundedSender<Action>,
+    ) {
+        let Some(mut schema_registry) = self.app.schema_registry() else {
+            return;
+        };
+        tokio::task::Builder::new()
+            .name("schema-version")
+            .spawn(async move {
+                let schema_registry = schema_registry.for_topic(&topic);
+                let action = match schema_registry.schema_version(&subject, version).await {
+                    Ok(response) => {
+                        let detail = match response {
+                            Some(r) => SchemaDetail {
+                                url: schema_registry.schema_url(r.id),
+                                id: r.id,
+                                response: Some(r.schema),
+                            },
+                            None => SchemaDetail::default(),
+                        };
+                        Action::SchemaVersion(subject, version, detail)
+                    }
+                    Err(e) => Action::Notification(Notification::new(Level::Error, e.to_string())),
+                };
+                let _ = action_tx.send(action);
+            })
+            .unwrap();
+    }
+
+    /// Shows the alert and runs its actions.
+    /// The terminal bell is rung from here, between 2 renderings of the TUI.
+    pub(crate) async fn alert(
+        &self,
+        alert: Alert,
+        action_tx: &UnboundedSender<Action>,
+    ) -> Result<(), TuiError> {
+        action_tx.send(Action::Notification(Notification::new(
+            Level::Warn,
+            alert.to_string(),
+        )))?;
+        if alert.actions.contains(&AlertAction::Bell) {
+            alert.trigger(&AlertAction::Bell).await?;
+        }
+        let action_tx = action_tx.clone();
+        tokio::task::Builder::new()
+            .name("alert")
+            .spawn(async move {
+                for action in alert.actions.iter().filter(|a| **a != AlertAction::Bell) {
+                    if let Err(e) = alert.trigger(action).await {
+                        let _ = action_tx.send(Action::Notification(Notification::new(
+                            Level::Error,
+                            e.to_string(),
+                        )));
+                    }
+                }
+            })
+            .unwrap();
+        Ok(())
+    }
+
     pub(crate) fn export_record(
         &mut self,
         record: &KafkaRecord,
         action_tx: &UnboundedSender<Action>,
     ) -> Result<(), TuiError> {
-        self.app.export_record(record)?;
+        let export_file = self.app.export_record(record)?;
         action_tx.send(Action::Notification(Notification::new(
             Level::Info,
             "Record exported to the file".to_string(),
         )))?;
+        if record.has_schemas() && self.app.config.schemas_file_of(&export_file).is_some() {
+            let app = self.app.clone();
+            let record = record.clone();
+            let action_tx = action_tx.clone();
+            let task = tokio::task::Builder::new()
+                .name("schemas-exporter")
+                .spawn(async move {
+                    if let Err(e) = app.export_schemas(&record, &export_file).await {
+                        let _ = action_tx.send(Action::Notification(Notification::new(
+                            Level::Error,
+                            format!("Cannot export the schemas: {e}"),
+                        )));
+                    }
+                })
+                .unwrap();
+            self.track_exporter(task);
+        }
+        Ok(())
+    }
+
+    /// Keeps the handle of a task writing into the export files, to wait for it before quitting.
+    fn track_exporter(&self, task: JoinHandle<()>) {
+        let mut exporters = self.exporters.lock().unwrap();
+        exporters.retain(|e| !e.is_finished());
+        exporters.push(task);
+    }
+
+    /// Stops the live export and waits for the exports in progress,
+    /// so the export files are not truncated when quitting.
+    async fn flush_exports(&self) {
+        self.live_export.lock().unwrap().take();
+        let exporters = std::mem::take(&mut *self.exporters.lock().unwrap())
+            .into_iter()
+            .filter(|e| !e.is_finished())
+            .collect_vec();
+        if exporters.is_empty() {
+            return;
+        }
+        info!("Waiting for {} exports to complete", exporters.len());
+        if time::timeout(EXPORTS_FLUSH_TIMEOUT, futures::future::join_all(exporters))
+            .await
+            .is_err()
+        {
+            warn!(
+                "The exports did not complete within {}s, the export files might be incomplete",
+                EXPORTS_FLUSH_TIMEOUT.as_secs()
+            );
+        }
+    }
+
+    /// The first shutdown signal quits the TUI like the ESC key, the export files are completed before exiting.
+    /// The second one restores the terminal and exits immediately.
+    fn listen_shutdown_signals(&self, event_tx: UnboundedSender<tui::Event>) {
+        let mut signals = match ShutdownSignals::new() {
+            Ok(signals) => signals,
+            Err(e) => {
+                warn!("{e}");
+                return;
+            }
+        };
+        tokio::task::Builder::new()
+            .name("shutdown-signals")
+            .spawn(async move {
+                let signal = signals.recv().await;
+                info!("{signal} received, quitting");
+                let _ = event_tx.send(tui::Event::Quit);
+                let signal = signals.recv().await;
+                warn!("{signal} received again, exiting without completing the exports");
+                let _ = tui::Tui::restore_tui();
+                std::process::exit(130);
+            })
+            .unwrap();
+    }
+
+    /// Spawns a task producing the records of the file to the selected topic.
+    /// `count` records are generated when the file is a template.
+    pub(crate) fn produce(
+        &self,
+        request: ProduceRequest,
+        action_tx: UnboundedSender<Action>,
+    ) -> Result<(), TuiError> {
+        let ProduceRequest {
+            file,
+            mapping,
+            count,
+            rate,
+            header_edits,
+            confirmation,
+        } = request;
+        let [topic] = self.topics.as_slice() else {
+            action_tx.send(Action::Notification(Notification::new(
+                Level::Warn,
+                "Select a single topic to produce records to".to_string(),
+            )))?;
+            return Ok(());
+        };
+        let app = self.app.clone();
+        let topic = topic.clone();
+        tokio::task::Builder::new()
+            .name("producer")
+            .spawn(async move {
+                let records = match is_template(&file) {
+                    true => RecordTemplate::read(&file).map(|t| t.records(count, &mapping)),
+                    false => read_records(&file, InputFormat::of(&file), &mapping),
+                }
+                .map(|mut records| {
+                    records
+                        .iter_mut()
+                        .for_each(|record| record.edit_headers(&header_edits));
+                    records
+                });
+                // Produces inside a transaction as soon as the cluster has a transactional producer
+                let options = ProduceOptions {
+                    rate,
+                    transactional: app
+                        .config
+                        .specific
+                        .producer_config()
+                        .transactional_id
+                        .is_some(),
+                    confirmation,
+                    ..Default::default()
+                };
+                let produced = match records {
+                    Ok(records) => app.produce(&topic, &records, &options).await,
+                    Err(e) => Err(e),
+                };
+                let notification = match produced {
+                    Ok(n) => {
+                        Notification::new(Level::Info, format!("{n} records produced to '{topic}'"))
+                    }
+                    Err(e) => Notification::new(Level::Error, format!("Cannot produce: {e}")),
+                };
+                let _ = action_tx.send(Action::Notification(notification));
+            })
+            .unwrap();
+        Ok(())
+    }
+
+    pub(crate) fn republish(
+        &self,
+        record: KafkaRecord,
+        header_edits: Vec<HeaderEdit>,
+        confirmation: String,
+        action_tx: UnboundedSender<Action>,
+    ) {
+        let app = self.app.clone();
+        tokio::task::Builder::new()
+            .name("republisher")
+            .spawn(async move {
+                let notification = match app.republish(&record, &header_edits, &confirmation).await
+                {
+                    Ok(topic) => {
+                        Notification::new(Level::Info, format!("Record republished to '{topic}'"))
+                    }
+                    Err(e) => Notification::new(Level::Error, format!("Cannot republish: {e}")),
+                };
+                let _ = action_tx.send(Action::Notification(notification));
+            })
+            .unwrap();
+    }
+
+    /// Spawns a task streaming the received records into the export files.
+    /// The number of exported records is notified once the sender is dropped.
+    fn spawn_records_exporter(
+        &self,
+        action_tx: &UnboundedSender<Action>,
+    ) -> Result<UnboundedSender<KafkaRecord>, TuiError> {
+        let mut exporter = self.app.records_exporter()?;
+        let (tx, mut rx) = mpsc::unbounded_channel::<KafkaRecord>();
+        let app = self.app.clone();
+        let action_tx = action_tx.clone();
+        let task = tokio::task::Builder::new()
+            .name("records-exporter")
+            .spawn(async move {
+                // The records received meanwhile are exported together
+                let mut records = Vec::with_capacity(RecordsExporter::BATCH_SIZE);
+                while rx
+                    .recv_many(&mut records, RecordsExporter::BATCH_SIZE)
+                    .await
+                    > 0
+                {
+                    let exported = async {
+                        let export_files = exporter.export_all(&records)?;
+                        for (record, export_file) in records.iter().zip(export_files) {
+                            app.export_schemas(record, &export_file).await?;
+                        }
+                        Ok::<(), lib::Error>(())
+                    }
+                    .await;
+                    records.clear();
+                    if let Err(e) = exported {
+                        let _ = action_tx.send(Action::Notification(Notification::new(
+                            Level::Error,
+                            format!("Cannot export the record: {e}"),
+                        )));
+                        return;
+                    }
+                }
+                let _ = action_tx.send(Action::Notification(Notification::new(
+                    Level::Info,
+                    format!(
+                        "{} records exported to the file",
+                        exporter.exported().separate_with_underscores()
+                    ),
+                )));
+            })
+            .unwrap();
+        self.track_exporter(task);
+        Ok(tx)
+    }
+
+    /// Exports all the records of the current search.
+    fn export_all(&self, action_tx: &UnboundedSender<Action>) -> Result<(), TuiError> {
+        let exporter = self.spawn_records_exporter(action_tx)?;
+        let records = self.records.lock().unwrap().iter().cloned().collect_vec();
+        let app = self.app.clone();
+        // Truncated values are read again, the records are sent to the exporter in a task
+        tokio::task::Builder::new()
+            .name("export-all")
+            .spawn(async move {
+                for record in records {
+                    let record = match record.truncated {
+                        true => app
+                            .fetch_record(&record.topic, record.partition, record.offset)
+                            .await
+                            .unwrap_or(record),
+                        false => record,
+                    };
+                    if exporter.send(record).is_err() {
+                        return;
+                    }
+                }
+            })
+            .unwrap();
+        Ok(())
+    }
+
+    /// Copies a link reproducing the search, pinned to the timestamps of the records read so far.
+    fn copy_link(&self, action_tx: &UnboundedSender<Action>) -> Result<(), TuiError> {
+        let (from, to) = self
+            .records
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|r| r.timestamp.and_then(DateTime::from_timestamp_millis))
+            .minmax()
+            .into_option()
+            .unzip();
+        let link = DeepLink::new(
+            &self.app.cluster,
+            &self.topics,
+            self.app.search_query.input(),
+        )
+        .with_time_bounds(from, to);
+        action_tx.send(Action::CopyToClipboard(link.to_string()))?;
+        Ok(())
+    }
+
+    /// Copies the record to the clipboard as JSON, with the search query and its note.
+    fn copy_record(
+        &self,
+        record: &KafkaRecord,
+        action_tx: &UnboundedSender<Action>,
+    ) -> Result<(), TuiError> {
+        let mut exported_record: ExportedKafkaRecord = record.into();
+        exported_record.search_query = self.app.search_query.input().to_string();
+        exported_record.note = self
+            .app
+            .notes()
+            .ok()
+            .and_then(|notes| notes.text_of(record));
+        action_tx.send(Action::CopyToClipboard(serde_json::to_string_pretty(
+            &exported_record,
+        )?))?;
+        Ok(())
+    }
+
+    /// Notifies the UI the notes attached to the records of the cluster.
+    fn load_notes(&self, action_tx: &UnboundedSender<Action>) -> Result<(), TuiError> {
+        match self.app.notes() {
+            Ok(notes) => action_tx.send(Action::Notes(notes))?,
+            Err(e) => action_tx.send(Action::Notification(Notification::new(
+                Level::Error,
+                format!("Cannot read the notes: {e}"),
+            )))?,
+        }
+        Ok(())
+    }
+
+    /// Attaches the note to the record in the notes file of the workspace.
+    fn set_note(
+        &self,
+        record: &KafkaRecord,
+        text: &str,
+        action_tx: &UnboundedSender<Action>,
+    ) -> Result<(), TuiError> {
+        match self.app.notes().and_then(|mut notes| {
+            notes.set(record, text)?;
+            Ok(notes)
+        }) {
+            Ok(notes) => action_tx.send(Action::Notes(notes))?,
+            Err(e) => action_tx.send(Action::Notification(Notification::new(
+                Level::Error,
+                format!("Cannot save the note: {e}"),
+            )))?,
+        }
+        Ok(())
+    }
+
+    /// Starts or stops exporting the records as they match the search query.
+    fn toggle_live_export(&self, action_tx: &UnboundedSender<Action>) -> Result<(), TuiError> {
+        let mut live_export = self.live_export.lock().unwrap();
+        // Dropping the sender stops the exporter
+        if live_export.take().is_none() {
+            *live_export = Some(self.spawn_records_exporter(action_tx)?);
+            action_tx.send(Action::Notification(Notification::new(
+                Level::Info,
+                "Exporting the records matching the search query".to_string(),
+            )))?;
+        }
+        Ok(())
+    }
+
+    /// Starts probing the end-to-end latency of the selected topic, or stops the running probe.
+    /// `confirmation` is the text typed by the user to start the probe.
+    fn toggle_latency_probe(
+        &mut self,
+        confirmation: String,
+        action_tx: &UnboundedSender<Action>,
+    ) -> Result<(), TuiError> {
+        if let Some(token) = self.latency_probe.take()
+            && !token.is_cancelled()
+        {
+            token.cancel();
+            action_tx.send(Action::LatencyProbe(None))?;
+            return Ok(());
+        }
+        let [topic] = self.topics.as_slice() else {
+            action_tx.send(Action::Notification(Notification::new(
+                Level::Warn,
+                "Select a single topic to probe".to_string(),
+            )))?;
+            return Ok(());
+        };
+        let token = CancellationToken::new();
+        self.latency_probe = Some(token.clone());
+        action_tx.send(Action::LatencyProbe(Some(topic.clone())))?;
+
+        let app = self.app.clone();
+        let topic = topic.clone();
+        let action_tx = action_tx.clone();
+        tokio::task::Builder::new()
+            .name("latency-probe")
+            .spawn(async move {
+                let (tx, mut samples) = mpsc::unbounded_channel();
+                let mut probe =
+                    pin!(app.probe_latency(&topic, LATENCY_PROBE_INTERVAL, &confirmation, tx));
+                loop {
+                    select! {
+                        _ = token.cancelled() => break,
+                        result = &mut probe => {
+                            if let Err(e) = result {
+                                let _ = action_tx.send(Action::Notification(Notification::new(
+                                    Level::Error,
+                                    format!("The latency probe stopped: {e}"),
+                                )));
+                            }
+                            token.cancel();
+                            let _ = action_tx.send(Action::LatencyProbe(None));
+                            break;
+                        }
+                        Some(sample) = samples.recv() => {
+                            let _ = action_tx.send(Action::LatencySample(sample));
+                        }
+                    }
+                }
+            })
+            .unwrap();
+        Ok(())
+    }
+
+    /// Applies the changes of the configuration and themes files.
+    fn reload_config(&mut self, action_tx: &UnboundedSender<Action>) -> Result<(), TuiError> {
+        let mut reloaded = match self.watcher.config_changes() {
+            Ok(Some((previous, config))) => self.app.config.reload(&previous, &config),
+            Ok(None) => vec![],
+            Err(e) => {
+                action_tx.send(Action::Notification(Notification::new(
+                    Level::Error,
+                    format!("Cannot reload the configuration: {e}"),
+                )))?;
+                vec![]
+            }
+        };
+        if reloaded.contains(&"theme") {
+            self.app.config.theme().clone_into(&mut self.theme);
+        }
+        if self.watcher.themes_changed() {
+            reloaded.push("themes");
+        }
+        if reloaded.is_empty() {
+            return Ok(());
+        }
+
+        if reloaded
+            .iter()
+            .any(|r| r.contains("theme") || r.contains("color"))
+        {
+            match self.watcher.read_theme(&self.theme) {
+                Ok(theme) => self.apply_theme(theme, action_tx)?,
+                Err(e) => {
+                    action_tx.send(Action::Notification(Notification::new(
+                        Level::Error,
+                        format!("Cannot reload the theme: {e}"),
+                    )))?;
+                    return Ok(());
+                }
+            }
+        }
+        info!("Reloaded {}", reloaded.join(", "));
+        action_tx.send(Action::Notification(Notification::new(
+            Level::Info,
+            format!("Reloaded {}", reloaded.join(", ")),
+        )))?;
+        Ok(())
+    }
+
+    fn apply_theme(
+        &self,
+        theme: Theme,
+        action_tx: &UnboundedSender<Action>,
+    ) -> Result<(), TuiError> {
+        let color_support = self.app.config.color_support();
+        let highlighter_theme = Highlighter::theme(
+            theme.highlighter_theme.as_deref(),
+            self.app
+                .config
+                .workspace()
+                .config()
+                .highlighter_theme
+                .as_deref(),
+        )
+        .filter(|_| color_support.is_colored())
+        .map(|t| Highlighter::with_color_support(t, color_support));
+        action_tx.send(Action::Theme(
+            theme.with_color_support(color_support),
+            highlighter_theme,
+        ))?;
+        Ok(())
+    }
+
+    /// Switches to the theme following the current one in the themes file.
+    /// The choice is not saved in the configuration file.
+    fn next_theme(&mut self, action_tx: &UnboundedSender<Action>) -> Result<(), TuiError> {
+        let themes = match self.watcher.themes() {
+            Ok(themes) if !themes.is_empty() => themes,
+            Ok(_) => return Ok(()),
+            Err(e) => {
+                action_tx.send(Action::Notification(Notification::new(
+                    Level::Error,
+                    format!("Cannot load the themes: {e}"),
+                )))?;
+                return Ok(());
+            }
+        };
+        let next = themes
+            .get_index_of(Theme::resolve_name(&self.theme))
+            .map_or(0, |i| (i + 1) % themes.len());
+        let (name, theme) = themes.into_iter().nth(next).unwrap();
+        action_tx.send(Action::Notification(Notification::new(
+            Level::Info,
+            format!("Theme '{name}'. Run 'yozf config set /theme {name}' to keep it"),
+        )))?;
+        self.theme = name;
+        self.apply_theme(theme, action_tx)
+    }
+
+    /// Returns the workspace the user switched to, the TUI must be started again with it.
+    pub fn next_workspace(&self) -> Option<String> {
+        self.next_workspace.clone()
+    }
+
+    /// Quits the TUI to start it again with the next workspace, if it defines the current cluster.
+    fn switch_workspace(&mut self, action_tx: &UnboundedSender<Action>) -> Result<(), TuiError> {
+        let workspace = self.app.config.workspace();
+        let name = workspace.next_name();
+        if name == workspace.name() {
+            action_tx.send(Action::Notification(Notification::new(
+                Level::Info,
+                "There is no other workspace, create one with 'yozf --workspace <name>'"
+                    .to_string(),
+            )))?;
+            return Ok(());
+        }
+        let config_file =
+            Workspace::directory_of(workspace.root(), &name).join(Workspace::CONFIG_FILENAME);
+        match GlobalConfig::read(&config_file) {
+            Ok(config) if config.clusters.contains_key(&self.app.cluster) => {
+                self.next_workspace = Some(name);
+                action_tx.send(Action::Quit)?;
+            }
+            Ok(_) => action_tx.send(Action::Notification(Notification::new(
+                Level::Error,
+                format!(
+                    "Cluster '{}' is not defined in workspace '{name}'",
+                    self.app.cluster
+                ),
+            )))?,
+            Err(e) => action_tx.send(Action::Notification(Notification::new(
+                Level::Error,
+                format!("Cannot switch to workspace '{name}': {e}"),
+            )))?,
+        }
         Ok(())
     }
 
     pub(crate) fn load_topics(&mut self, action_tx: UnboundedSender<Action>) {
+        if self.replay.is_some() {
+            return;
+        }
         let app = self.app.clone();
         tokio::task::Builder::new()
             .name("topics-loader")
@@ -300,13 +1456,17 @@ impl Ui {
         let records_channel = mpsc::unbounded_channel::<KafkaRecord>();
         self.records_sender = Some(records_channel.0);
         self.load_topics(action_tx.clone());
-        let mut tui = tui::Tui::new()?;
+        let mut tui = tui::Tui::new(self.app.config.workspace().config().refresh_rates)?;
+        self.activity = tui.activity.clone();
         tui.enter()?;
+        self.listen_shutdown_signals(tui.event_tx.clone());
         self.root.register_action_handler(action_tx.clone());
         self.root.init()?;
+        self.load_notes(&action_tx)?;
         if !topics.is_empty() {
             action_tx.send(Action::SelectedTopics(topics))?;
         }
+        self.replay_session(action_tx.clone(), tui.event_tx.clone());
 
         let mut schema_registry = self.app.schema_registry();
         loop {
@@ -316,6 +1476,7 @@ impl Ui {
                     tui::Event::Tick => action_tx.send(Action::Tick)?,
                     tui::Event::Render => action_tx.send(Action::Render)?,
                     tui::Event::Resize(x, y) => action_tx.send(Action::Resize(x, y))?,
+                    tui::Event::Key(key) => self.record(SessionEvent::Key { key }),
                     _ => {}
                 }
 
@@ -329,11 +1490,44 @@ impl Ui {
                         self.app.config.push_history(prompt);
                         self.app.config.save_config()?;
                     }
-                    Action::RequestTopicDetails(ref topics) => {
-                        self.topics_details(topics.clone(), action_tx.clone());
+                    Action::Layout(layout) => {
+                        self.app.config.set_layout(layout);
+                        self.app.config.save_config()?;
+                    }
+                    Action::RequestTopicDetails(ref topics, count_method) => {
+                        self.topics_details(topics.clone(), count_method, action_tx.clone());
+                    }
+                    Action::RequestQueryPlan(ref search_query) => {
+                        self.query_plan(search_query.clone(), action_tx.clone());
+                    }
+                    Action::PressKeys(ref keys) => {
+                        for key in keys {
+                            let _ = tui.event_tx.send(tui::Event::Key(*key));
+                        }
+                    }
+                    Action::RequestTopicPreview(ref topic) => {
+                        self.topic_preview(topic.clone(), action_tx.clone());
+                    }
+                    Action::RequestFullRecord(ref record) => {
+                        self.full_record(record.clone(), Action::FullRecord, action_tx.clone());
+                    }
+                    Action::RequestAdjacentRecord(ref record, next) => {
+                        self.adjacent_record(record.clone(), next, action_tx.clone());
+                    }
+                    Action::SetNote(ref record, ref text) => {
+                        self.set_note(record, text, &action_tx)?;
                     }
                     Action::Tick => {
                         self.last_tick_key_events.drain(..);
+                        if self.config_checked_at.elapsed() >= CONFIG_WATCHER_INTERVAL {
+                            self.config_checked_at = Instant::now();
+                            self.reload_config(&action_tx)?;
+                        }
+                    }
+                    Action::Topics(ref topics) => {
+                        self.record(SessionEvent::Topics {
+                            topics: topics.clone(),
+                        });
                     }
                     Action::Refresh => {
                         self.load_topics(action_tx.clone());
@@ -342,8 +1536,18 @@ impl Ui {
                             "Refreshing topics".to_string(),
                         )))?;
                     }
+                    Action::NewView(ComponentName::Table) => {
+                        self.read_compacted_topics(action_tx.clone());
+                    }
+                    Action::NewView(ComponentName::BrokerConfigs) => {
+                        self.broker_configs(action_tx.clone());
+                    }
+                    Action::NewView(ComponentName::ConsumerGroups) => {
+                        self.consumer_groups(action_tx.clone());
+                    }
                     Action::Quit => {
                         self.worker.cancel();
+                        self.table_worker.cancel();
                         self.should_quit = true;
                     }
                     Action::Open(ref record) => {
@@ -369,13 +1573,115 @@ impl Ui {
                             let _ = self.root.draw(f, f.area(), &state);
                         })?;
                     }
+                    Action::NextTheme => {
+                        self.next_theme(&action_tx)?;
+                    }
+                    Action::NextWorkspace => {
+                        self.switch_workspace(&action_tx)?;
+                    }
+                    // The full value is read first, the record is exported once read again
+                    Action::Export(ref record) if record.truncated => {
+                        self.full_record(record.clone(), Action::Export, action_tx.clone());
+                    }
                     Action::Export(ref record) => {
                         self.export_record(record, &action_tx)?;
                     }
-                    Action::RequestSchemasOf(ref key, ref value) => {
+                    Action::CopyRecord(ref record) if record.truncated => {
+                        self.full_record(record.clone(), Action::CopyRecord, action_tx.clone());
+                    }
+                    Action::CopyRecord(ref record) => {
+                        self.copy_record(record, &action_tx)?;
+                    }
+                    Action::Alert(ref alert) => {
+                        self.alert(alert.clone(), &action_tx).await?;
+                    }
+                    Action::ExportAll => {
+                        self.export_all(&action_tx)?;
+                    }
+                    Action::Report(format, None) => {
+                        let records = self.records.lock().unwrap().iter().cloned().collect_vec();
+                        action_tx.send(Action::CopyToClipboard(records_report(
+                            format,
+                            &self.app.cluster,
+                            &self.topics,
+                            self.app.search_query.input(),
+                            &records,
+                        )))?;
+                    }
+                    Action::Report(format, Some(ref record)) => {
+                        let topic = &record.topic;
+                        let key_schema = record.key_schema.as_ref().map(|s| &s.id);
+                        let value_schema = record.value_schema.as_ref().map(|s| &s.id);
+                        action_tx.send(Action::CopyToClipboard(record_report(
+                            format,
+                            record,
+                            SchemaDetail::from(&mut schema_registry, topic, key_schema)
+                                .await
+                                .as_ref(),
+                            SchemaDetail::from(&mut schema_registry, topic, value_schema)
+                                .await
+                                .as_ref(),
+                        )))?;
+                    }
+                    Action::CopyLink => {
+                        self.copy_link(&action_tx)?;
+                    }
+                    Action::ToggleLiveExport => {
+                        self.toggle_live_export(&action_tx)?;
+                    }
+                    Action::ToggleLatencyProbe(ref confirmation) => {
+                        self.toggle_latency_probe(confirmation.clone(), &action_tx)?;
+                    }
+                    Action::Produce(ref request) => {
+                        self.produce(request.clone(), action_tx.clone())?;
+                    }
+                    Action::Trace(ref record) => {
+                        let notification = match self.app.config.trace_of(&record.topic) {
+                            Some(trace) => match trace.search(record) {
+                                Ok((topics, query)) => {
+                                    action_tx.send(Action::TraceSearch(topics, query))?;
+                                    None
+                                }
+                                Err(e) => Some(format!("Cannot trace the record: {e}")),
+                            },
+                            None => Some(format!(
+                                "No trace is configured for topic '{}'",
+                                record.topic
+                            )),
+                        };
+                        if let Some(notification) = notification {
+                            action_tx.send(Action::Notification(Notification::new(
+                                Level::Warn,
+                                notification,
+                            )))?;
+                        }
+                    }
+                    Action::TraceSearch(ref topics, _) => {
+                        self.topics = topics.clone();
+                    }
+                    Action::Republish(ref record, ref header_edits, ref confirmation) => {
+                        self.republish(
+                            record.clone(),
+                            header_edits.clone(),
+                            confirmation.clone(),
+                            action_tx.clone(),
+                        );
+                    }
+                    Action::RequestSchemaVersions(ref topic, id) => {
+                        self.schema_versions(topic.clone(), id, action_tx.clone());
+                    }
+                    Action::RequestSchemaVersion(ref topic, ref subject, version) => {
+                        self.schema_version(
+                            topic.clone(),
+                            subject.clone(),
+                            version,
+                            action_tx.clone(),
+                        );
+                    }
+                    Action::RequestSchemasOf(ref topic, ref key, ref value) => {
                         action_tx.send(Action::Schemas(
-                            SchemaDetail::from(&mut schema_registry, key.as_ref()).await,
-                            SchemaDetail::from(&mut schema_registry, value.as_ref()).await,
+                            SchemaDetail::from(&mut schema_registry, topic, key.as_ref()).await,
+                            SchemaDetail::from(&mut schema_registry, topic, value.as_ref()).await,
                         ))?;
                     }
                     Action::Render => {
@@ -397,6 +1703,11 @@ impl Ui {
                             )))?;
                         }
                         self.app.search_query = search.clone();
+                        // The exported records keep the search query they matched
+                        if self.live_export.lock().unwrap().is_some() {
+                            let exporter = self.spawn_records_exporter(&action_tx)?;
+                            *self.live_export.lock().unwrap() = Some(exporter);
+                        }
                         self.consume_topics(action_tx.clone())?;
                     }
                     _ => {}
@@ -411,7 +1722,12 @@ impl Ui {
                 break;
             }
         }
+        // The terminal is restored first, whatever the tasks still running
         tui.exit()?;
+        self.flush_exports().await;
+        if let Some(recorder) = &self.recorder {
+            recorder.close();
+        }
         Ok(())
     }
 }
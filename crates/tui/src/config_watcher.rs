@@ -0,0 +1,165 @@
+//! Watches the configuration and themes files
+//! so changes can be applied without restarting the TUI.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use app::configuration::{GlobalConfig, Workspace, read_themes};
+use indexmap::IndexMap;
+use lib::Error;
+use tracing::warn;
+
+use crate::Theme;
+
+/// Applies the `--set` and environment-variable overrides to a configuration read from the file
+pub type ConfigOverrides = Box<dyn Fn(GlobalConfig) -> Result<GlobalConfig, Error> + Send + Sync>;
+
+pub(crate) struct ConfigWatcher {
+    config_file: PathBuf,
+    themes_file: PathBuf,
+    config_modified: Option<SystemTime>,
+    themes_modified: Option<SystemTime>,
+    /// The configuration as it was the last time the file was read, overrides applied
+    config: GlobalConfig,
+    overrides: Option<ConfigOverrides>,
+}
+
+impl ConfigWatcher {
+    pub fn new(workspace: &Workspace) -> Self {
+        let config_file = workspace.config_file();
+        let themes_file = workspace.themes_file();
+        Self {
+            config_modified: Self::modified(&config_file),
+            themes_modified: Self::modified(&themes_file),
+            config: GlobalConfig::read(&config_file).unwrap_or(workspace.config().clone()),
+            config_file,
+            themes_file,
+            overrides: None,
+        }
+    }
+
+    /// Applies the overrides to the configuration each time the file is read again,
+    /// so editing the file does not undo the settings of the command line.
+    pub fn with_overrides(mut self, overrides: ConfigOverrides) -> Self {
+        match overrides(self.config.clone()) {
+            Ok(config) => self.config = config,
+            Err(e) => warn!("The overrides of the configuration are ignored: {e}"),
+        }
+        self.overrides = Some(overrides);
+        self
+    }
+
+    fn modified(file: &Path) -> Option<SystemTime> {
+        fs::metadata(file).and_then(|m| m.modified()).ok()
+    }
+
+    /// Returns the previous and the new configuration if the configuration file has been modified since the last call.
+    pub fn config_changes(&mut self) -> Result<Option<(GlobalConfig, GlobalConfig)>, Error> {
+        let modified = Self::modified(&self.config_file);
+        if modified == self.config_modified {
+            return Ok(None);
+        }
+        self.config_modified = modified;
+        let mut config = GlobalConfig::read(&self.config_file)?;
+        if let Some(overrides) = &self.overrides {
+            config = overrides(config)?;
+        }
+        let previous = std::mem::replace(&mut self.config, config.clone());
+        Ok(Some((previous, config)))
+    }
+
+    /// Returns `true` if the themes file has been modified since the last call.
+    pub fn themes_changed(&mut self) -> bool {
+        let modified = Self::modified(&self.themes_file);
+        let changed = modified != self.themes_modified;
+        self.themes_modified = modified;
+        changed
+    }
+
+    /// Reads all the themes of the themes file, in the order they are defined.
+    pub fn themes(&self) -> Result<IndexMap<String, Theme>, Error> {
+        read_themes(&self.themes_file)
+    }
+
+    /// Reads the theme with the given name from the themes file.
+    pub fn read_theme(&self, name: &str) -> Result<Theme, Error> {
+        let name = Theme::resolve_name(name);
+        self.themes()?
+            .shift_remove(name)
+            .ok_or(Error::Error(format!(
+                "Theme '{}' not found in '{}'",
+                name,
+                self.themes_file.display()
+            )))
+    }
+}
+
+#[test]
+fn test_config_changes() {
+    use std::time::Duration;
+
+    let dir = tempfile::tempdir().unwrap();
+    let config_file = dir.path().join(Workspace::CONFIG_FILENAME);
+    let mut config = GlobalConfig::new(&config_file);
+    fs::write(&config_file, serde_json::to_string(&config).unwrap()).unwrap();
+    let workspace = Workspace::new(dir.path(), config.clone(), dir.path().join("logs"));
+
+    let mut watcher = ConfigWatcher::new(&workspace);
+    assert!(watcher.config_changes().unwrap().is_none());
+
+    config.theme = "my-theme".to_string();
+    fs::write(&config_file, serde_json::to_string(&config).unwrap()).unwrap();
+    fs::File::options()
+        .append(true)
+        .open(&config_file)
+        .unwrap()
+        .set_modified(SystemTime::now() + Duration::from_secs(1))
+        .unwrap();
+    let (previous, new) = watcher.config_changes().unwrap().unwrap();
+    assert_ne!(previous.theme, "my-theme");
+    assert_eq!(new.theme, "my-theme");
+
+    fs::write(&config_file, "{").unwrap();
+    fs::File::options()
+        .append(true)
+        .open(&config_file)
+        .unwrap()
+        .set_modified(SystemTime::now() + Duration::from_secs(2))
+        .unwrap();
+    assert!(watcher.config_changes().is_err());
+}
+
+#[test]
+fn test_config_changes_keep_the_overrides() {
+    use std::time::Duration;
+
+    let dir = tempfile::tempdir().unwrap();
+    let config_file = dir.path().join(Workspace::CONFIG_FILENAME);
+    let mut config = GlobalConfig::new(&config_file);
+    fs::write(&config_file, serde_json::to_string(&config).unwrap()).unwrap();
+    let workspace = Workspace::new(dir.path(), config.clone(), dir.path().join("logs"));
+
+    let mut watcher = ConfigWatcher::new(&workspace).with_overrides(Box::new(|config| {
+        config.with_override("/theme", "overridden")
+    }));
+
+    config.theme = "my-theme".to_string();
+    config.highlighter_theme = Some("my-highlighter-theme".to_string());
+    fs::write(&config_file, serde_json::to_string(&config).unwrap()).unwrap();
+    fs::File::options()
+        .append(true)
+        .open(&config_file)
+        .unwrap()
+        .set_modified(SystemTime::now() + Duration::from_secs(1))
+        .unwrap();
+    let (previous, new) = watcher.config_changes().unwrap().unwrap();
+    assert_eq!(previous.theme, "overridden");
+    assert_eq!(new.theme, "overridden");
+    assert_eq!(
+        new.highlighter_theme.as_deref(),
+        Some("my-highlighter-theme")
+    );
+}
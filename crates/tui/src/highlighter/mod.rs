@@ -1,19 +1,29 @@
 //! A syntax highlighter using syntect,
 //! used to highlight JSON payloads in `RecordDetailsComponent`.
-use lib::DataType;
+//! Large payloads are highlighted by chunks of lines, so the first lines can be drawn before the whole payload is highlighted.
+//! The syntaxes and themes of the workspace are loaded on top of the bundled ones, see [`load_syntaxes`].
+use app::configuration::{BundledSyntaxes, ColorSupport, HighlighterBackend, HighlighterConfig};
 use ratatui::text::{Line, Span, Text};
 use resolve_path::PathResolveExt;
-use std::{path::PathBuf, sync::LazyLock};
+use std::{
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
 use syntect::{
     easy::HighlightLines,
     highlighting::{self, Theme, ThemeSet},
-    parsing::{SyntaxReference, SyntaxSet},
+    html::highlighted_html_for_string,
+    parsing::{SyntaxReference, SyntaxSet, SyntaxSetBuilder},
     util::LinesWithEndings,
 };
 use tracing::warn;
 
-static SYNTAX_SET: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
-static THEMES: LazyLock<ThemeSet> = LazyLock::new(ThemeSet::load_defaults);
+use crate::{
+    color::{degrade, index},
+    report::escape_html,
+};
+
+static SYNTAXES: OnceLock<Syntaxes> = OnceLock::new();
 pub const HIGHLIGHTER_DEFAULT_THEME: &str = "base16-ocean.dark";
 
 pub const HIGHLIGHTER_THEMES: [&str; 7] = [
@@ -26,6 +36,87 @@ pub const HIGHLIGHTER_THEMES: [&str; 7] = [
     "Solarized (light)",
 ];
 
+/// Light theme of the HTML reports, they are usually pasted into documents with a white background
+const REPORT_THEME: &str = "InspiredGitHub";
+
+/// Syntaxes and themes the highlighter is configured with.
+struct Syntaxes {
+    syntaxes: SyntaxSet,
+    themes: ThemeSet,
+    /// Syntax the values are highlighted with
+    syntax: SyntaxReference,
+    enabled: bool,
+}
+
+impl Syntaxes {
+    /// Loads the bundled syntaxes and themes, then the ones of `directory`.
+    fn new(config: &HighlighterConfig, directory: Option<&Path>) -> Self {
+        let mut builder = match config.bundled_syntaxes {
+            BundledSyntaxes::Full => SyntaxSet::load_defaults_newlines().into_builder(),
+            BundledSyntaxes::Minimal => {
+                let mut builder = SyntaxSetBuilder::new();
+                builder.add_plain_text_syntax();
+                builder
+            }
+        };
+        let mut themes = ThemeSet::load_defaults();
+        if let Some(directory) = directory.filter(|d| d.is_dir()) {
+            if let Err(e) = builder.add_from_folder(directory, true) {
+                warn!("Cannot load the syntaxes of '{}': {e}", directory.display());
+            }
+            if let Err(e) = themes.add_from_folder(directory) {
+                warn!("Cannot load the themes of '{}': {e}", directory.display());
+            }
+        }
+        let syntaxes = builder.build();
+        let syntax = syntaxes
+            .find_syntax_by_token(&config.syntax)
+            .unwrap_or_else(|| {
+                warn!(
+                    "Syntax '{}' not found, values are not highlighted",
+                    config.syntax
+                );
+                syntaxes.find_syntax_plain_text()
+            })
+            .clone();
+        Self {
+            syntaxes,
+            themes,
+            syntax,
+            enabled: config.backend == HighlighterBackend::Syntect,
+        }
+    }
+}
+
+/// Returns the syntaxes loaded by [`load_syntaxes`], the bundled ones when it has not been called.
+fn syntaxes() -> &'static Syntaxes {
+    SYNTAXES.get_or_init(|| Syntaxes::new(&HighlighterConfig::default(), None))
+}
+
+/// Loads the syntaxes and themes of the highlighter, those of `directory` on top of the bundled ones.
+/// It must be called before anything is highlighted, later calls are ignored.
+pub fn load_syntaxes(config: &HighlighterConfig, directory: &Path) {
+    if SYNTAXES
+        .set(Syntaxes::new(config, Some(directory)))
+        .is_err()
+    {
+        warn!("The syntaxes of the highlighter are already loaded");
+    }
+}
+
+/// Highlights the content as a `<pre>` block with inline styles, for the HTML reports.
+/// `extension` selects the syntax, such as `json`.
+pub(crate) fn highlight_html(content: &str, extension: &str) -> String {
+    let Syntaxes {
+        syntaxes, themes, ..
+    } = syntaxes();
+    let syntax = syntaxes
+        .find_syntax_by_extension(extension)
+        .unwrap_or_else(|| syntaxes.find_syntax_plain_text());
+    highlighted_html_for_string(content, syntaxes, syntax, &themes.themes[REPORT_THEME])
+        .unwrap_or_else(|_| format!("<pre>{}</pre>", escape_html(content)))
+}
+
 #[derive(Debug, Clone)]
 pub struct Highlighter {
     syntax: SyntaxReference,
@@ -35,12 +126,15 @@ pub struct Highlighter {
 
 impl Default for Highlighter {
     fn default() -> Self {
-        Self::new(Some(THEMES.themes[HIGHLIGHTER_DEFAULT_THEME].clone()))
+        Self::new(Some(
+            syntaxes().themes.themes[HIGHLIGHTER_DEFAULT_THEME].clone(),
+        ))
     }
 }
 
 impl<'a> Highlighter {
-    /// `name` and `fallback` could be a absolute file path to a `.tmTheme` file or a syntect theme name.
+    /// `name` and `fallback` could be a absolute file path to a `.tmTheme` file, a syntect theme name
+    /// or the name of a `.tmTheme` file of the workspace, without extension.
     pub fn theme(name: Option<&str>, fallback: Option<&str>) -> Option<highlighting::Theme> {
         match name.or(fallback) {
             Some(theme) => Self::try_to_load(name)
@@ -63,15 +157,42 @@ impl<'a> Highlighter {
 
         ThemeSet::get_theme(&name)
             .ok()
-            .or(THEMES.themes.get(&name).cloned())
+            .or(syntaxes().themes.themes.get(&name).cloned())
+    }
+
+    /// Degrades the RGB colors of a syntect theme to the colors supported by the terminal.
+    /// Degraded colors are encoded as palette numbers, see [`Self::to_ansi_color`].
+    pub fn with_color_support(mut theme: Theme, support: ColorSupport) -> Theme {
+        if support == ColorSupport::TrueColor {
+            return theme;
+        }
+        let degrade_color = |color: &mut Option<highlighting::Color>| {
+            if let Some(c) = color.as_mut().filter(|c| c.a > 1) {
+                let degraded = degrade(ratatui::style::Color::Rgb(c.r, c.g, c.b), support);
+                if let Some(i) = index(degraded) {
+                    *c = highlighting::Color {
+                        r: i,
+                        g: 0,
+                        b: 0,
+                        a: 0,
+                    };
+                }
+            }
+        };
+        degrade_color(&mut theme.settings.foreground);
+        for scope in &mut theme.scopes {
+            degrade_color(&mut scope.style.foreground);
+            degrade_color(&mut scope.style.background);
+        }
+        theme
     }
 
     pub fn new(theme: Option<highlighting::Theme>) -> Self {
         match theme {
             Some(t) => Self {
                 theme: t,
-                syntax: SYNTAX_SET.find_syntax_by_extension("json").unwrap().clone(),
-                enabled: true,
+                syntax: syntaxes().syntax.clone(),
+                enabled: syntaxes().enabled,
             },
             None => Self::disabled(),
         }
@@ -79,12 +200,13 @@ impl<'a> Highlighter {
 
     fn disabled() -> Self {
         Self {
-            theme: THEMES
+            theme: syntaxes()
+                .themes
                 .themes
                 .get(HIGHLIGHTER_DEFAULT_THEME)
                 .expect("Cannot load the default theme")
                 .clone(),
-            syntax: SYNTAX_SET.find_syntax_by_extension("json").unwrap().clone(),
+            syntax: syntaxes().syntax.clone(),
             enabled: false,
         }
     }
@@ -94,18 +216,44 @@ impl<'a> Highlighter {
             return Text::from(content.to_string());
         }
 
-        let mut h = HighlightLines::new(&self.syntax, &self.theme);
         let mut payload_lines = vec![];
-        for line in LinesWithEndings::from(content) {
-            let regions = h.highlight_line(line, &SYNTAX_SET).unwrap();
-            payload_lines.push(Self::to_line(regions));
-        }
+        self.highlight_by_chunks(content, usize::MAX, |lines| {
+            payload_lines.extend(lines);
+            true
+        });
         Text::from(payload_lines)
     }
 
-    pub fn highlight_data_type(&self, value: &DataType) -> Text<'a> {
-        let pretty = value.to_string_pretty();
-        self.highlight(&pretty)
+    /// Highlights the content `size` lines at a time, each chunk is passed to `on_chunk`.
+    /// Highlighting stops as soon as `on_chunk` returns `false`.
+    pub fn highlight_by_chunks(
+        &self,
+        content: &str,
+        size: usize,
+        mut on_chunk: impl FnMut(Vec<Line<'static>>) -> bool,
+    ) {
+        let size = size.max(1);
+        if !self.enabled {
+            for chunk in Text::from(content.to_string()).lines.chunks(size) {
+                if !on_chunk(chunk.to_vec()) {
+                    return;
+                }
+            }
+            return;
+        }
+
+        let mut h = HighlightLines::new(&self.syntax, &self.theme);
+        let mut chunk = vec![];
+        for line in LinesWithEndings::from(content) {
+            let regions = h.highlight_line(line, &syntaxes().syntaxes).unwrap();
+            chunk.push(Self::to_line(regions));
+            if chunk.len() == size && !on_chunk(std::mem::take(&mut chunk)) {
+                return;
+            }
+        }
+        if !chunk.is_empty() {
+            on_chunk(chunk);
+        }
     }
 
     fn to_line(regions: Vec<(highlighting::Style, &str)>) -> Line<'static> {
@@ -159,15 +307,19 @@ impl<'a> Highlighter {
                 0x04 => ratatui::style::Color::Blue,
                 0x05 => ratatui::style::Color::Magenta,
                 0x06 => ratatui::style::Color::Cyan,
-                0x07 => ratatui::style::Color::White,
+                0x07 => ratatui::style::Color::Gray,
+                // Bright variants use codes 90-97 (foreground) and 100-107 (background).
+                0x08 => ratatui::style::Color::DarkGray,
+                0x09 => ratatui::style::Color::LightRed,
+                0x0a => ratatui::style::Color::LightGreen,
+                0x0b => ratatui::style::Color::LightYellow,
+                0x0c => ratatui::style::Color::LightBlue,
+                0x0d => ratatui::style::Color::LightMagenta,
+                0x0e => ratatui::style::Color::LightCyan,
+                0x0f => ratatui::style::Color::White,
                 // For all other colors, use Fixed to produce escape sequences using
-                // codes 38;5 (foreground) and 48;5 (background). For example,
-                // bright red foreground is \x1b[38;5;9m. This only works on
+                // codes 38;5 (foreground) and 48;5 (background). This only works on
                 // terminals with 256-color support.
-                //
-                // TODO: When ansi_term adds support for bright variants using codes
-                // 90-97 (foreground) and 100-107 (background), we should use those
-                // for values 0x08 to 0x0f and only use Fixed for 0x10 to 0xff.
                 n => ratatui::style::Color::Indexed(n),
             })
         } else if color.a == 1 {
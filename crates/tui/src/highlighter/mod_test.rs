@@ -1,6 +1,8 @@
 use std::path::Path;
 
-use crate::highlighter::Highlighter;
+use app::configuration::{BundledSyntaxes, HighlighterConfig};
+
+use crate::highlighter::{Highlighter, Syntaxes};
 
 #[test]
 pub fn test_theme() {
@@ -32,3 +34,90 @@ pub fn test_theme_yozefu_default_theme() {
     let theme = Highlighter::theme(Some("unknown-theme"), Some("InspiredGitHub"));
     assert_eq!(theme.and_then(|t| t.name), Some("GitHub".to_string()));
 }
+
+#[test]
+pub fn test_theme_with_color_support() {
+    use app::configuration::ColorSupport;
+
+    let theme = Highlighter::theme(Some("InspiredGitHub"), None).unwrap();
+    let degraded = Highlighter::with_color_support(theme.clone(), ColorSupport::TrueColor);
+    assert_eq!(degraded, theme);
+
+    let degraded = Highlighter::with_color_support(theme, ColorSupport::Ansi256);
+    assert!(
+        degraded
+            .scopes
+            .iter()
+            .filter_map(|s| s.style.foreground)
+            .all(|c| c.a == 0)
+    );
+}
+
+#[test]
+pub fn test_highlight_by_chunks() {
+    let highlighter = Highlighter::default();
+    let content = serde_json::to_string_pretty(&serde_json::json!({
+        "title": "Swiss Army Man",
+        "year": 2016,
+        "genres": ["comedy", "drama"]
+    }))
+    .unwrap();
+
+    let mut chunks = vec![];
+    highlighter.highlight_by_chunks(&content, 3, |lines| {
+        chunks.push(lines);
+        true
+    });
+    assert_eq!(
+        chunks.iter().map(Vec::len).collect::<Vec<_>>(),
+        vec![3, 3, 2]
+    );
+    assert_eq!(chunks.concat(), highlighter.highlight(&content).lines);
+
+    let mut count = 0;
+    highlighter.highlight_by_chunks(&content, 3, |_| {
+        count += 1;
+        false
+    });
+    assert_eq!(count, 1);
+}
+
+#[test]
+pub fn test_syntaxes_of_the_workspace() {
+    let directory = tempfile::tempdir().unwrap();
+    std::fs::write(
+        directory.path().join("ndjson.sublime-syntax"),
+        r#"%YAML 1.2
+---
+name: NDJSON
+file_extensions: [ndjson]
+scope: source.ndjson
+contexts:
+  main:
+    - match: '"[^"]*"'
+      scope: string.quoted.double.ndjson
+"#,
+    )
+    .unwrap();
+    std::fs::copy(
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("theme.tmTheme"),
+        directory.path().join("burgundy.tmTheme"),
+    )
+    .unwrap();
+
+    let config = HighlighterConfig {
+        bundled_syntaxes: BundledSyntaxes::Minimal,
+        syntax: "ndjson".to_string(),
+        ..Default::default()
+    };
+    let syntaxes = Syntaxes::new(&config, Some(directory.path()));
+    assert_eq!(syntaxes.syntax.name, "NDJSON");
+    assert!(syntaxes.syntaxes.find_syntax_by_extension("json").is_none());
+    assert!(syntaxes.themes.themes.contains_key("burgundy"));
+    assert!(syntaxes.enabled);
+
+    let syntaxes = Syntaxes::new(&HighlighterConfig::default(), Some(directory.path()));
+    assert_eq!(syntaxes.syntax.name, "JSON");
+}
@@ -0,0 +1,154 @@
+//! Number of kafka records matching the search query per minute.
+//! It is drawn as a histogram above the list of records to see when the records were produced.
+//! Unlike the records buffer, every matching record is counted.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Local, SecondsFormat};
+
+const MINUTE: i64 = 60 * 1000;
+const HOUR: i64 = 60 * MINUTE;
+
+#[derive(Default)]
+pub(crate) struct Histogram {
+    /// Number of records by minute since the epoch
+    minutes: BTreeMap<i64, usize>,
+}
+
+/// A time range of the histogram and the number of records produced during that range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Bucket {
+    /// Start of the range, in milliseconds since the epoch
+    pub start: i64,
+    /// Duration of the range, in milliseconds
+    pub width: i64,
+    pub count: usize,
+}
+
+impl Histogram {
+    /// Counts a record, records without timestamp are ignored.
+    pub fn add(&mut self, timestamp: Option<i64>) {
+        if let Some(timestamp) = timestamp {
+            *self
+                .minutes
+                .entry(timestamp.div_euclid(MINUTE))
+                .or_default() += 1;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.minutes.clear();
+    }
+
+    /// Returns contiguous buckets of one minute, or one hour when it would need more than `max` buckets.
+    /// Only the `max` latest buckets are returned.
+    pub fn buckets(&self, max: usize) -> Vec<Bucket> {
+        let (Some((first, _)), Some((last, _))) = (
+            self.minutes.first_key_value(),
+            self.minutes.last_key_value(),
+        ) else {
+            return vec![];
+        };
+        let Ok(max) = i64::try_from(max) else {
+            return vec![];
+        };
+        if max == 0 {
+            return vec![];
+        }
+        let width = match last - first < max {
+            true => MINUTE,
+            false => HOUR,
+        };
+        let end = (last * MINUTE).div_euclid(width);
+        let start = (first * MINUTE).div_euclid(width).max(end - max + 1);
+        let mut buckets: Vec<Bucket> = (start..=end)
+            .map(|b| Bucket {
+                start: b * width,
+                width,
+                count: 0,
+            })
+            .collect();
+        for (minute, count) in self.minutes.range(start * width / MINUTE..) {
+            let index = (minute * MINUTE).div_euclid(width) - start;
+            buckets[index as usize].count += count;
+        }
+        buckets
+    }
+}
+
+impl Bucket {
+    /// Returns the search predicate matching the records of this bucket.
+    pub fn predicate(&self) -> String {
+        let format = |millis| {
+            DateTime::from_timestamp_millis(millis)
+                .unwrap_or_default()
+                .with_timezone(&Local)
+                .to_rfc3339_opts(SecondsFormat::Millis, false)
+        };
+        format!(
+            r#"timestamp between "{}" and "{}""#,
+            format(self.start),
+            format(self.start + self.width - 1)
+        )
+    }
+
+    /// Returns the unit of the bucket, `minute` or `hour`.
+    pub fn unit(&self) -> &'static str {
+        match self.width {
+            HOUR => "hour",
+            _ => "minute",
+        }
+    }
+
+    /// Returns the start and the end of the bucket in local time.
+    pub fn range(&self) -> (String, String) {
+        let format = |millis| {
+            DateTime::from_timestamp_millis(millis)
+                .unwrap_or_default()
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        };
+        (format(self.start), format(self.start + self.width))
+    }
+}
+
+#[test]
+fn test_buckets() {
+    let mut histogram = Histogram::default();
+    assert_eq!(histogram.buckets(10), vec![]);
+    histogram.add(Some(0));
+    histogram.add(Some(30 * 1000));
+    histogram.add(Some(3 * MINUTE));
+    histogram.add(None);
+    assert_eq!(
+        histogram
+            .buckets(10)
+            .iter()
+            .map(|b| b.count)
+            .collect::<Vec<_>>(),
+        vec![2, 0, 0, 1]
+    );
+    assert_eq!(histogram.buckets(10)[3].start, 3 * MINUTE);
+    assert_eq!(histogram.buckets(2).len(), 1);
+    assert_eq!(
+        histogram.buckets(2)[0],
+        Bucket {
+            start: 0,
+            width: HOUR,
+            count: 3
+        }
+    );
+
+    histogram.add(Some(5 * HOUR));
+    let buckets = histogram.buckets(2);
+    assert_eq!(
+        buckets
+            .iter()
+            .map(|b| (b.start, b.count))
+            .collect::<Vec<_>>(),
+        vec![(4 * HOUR, 0), (5 * HOUR, 1)]
+    );
+    assert_eq!(buckets[0].unit(), "hour");
+    assert!(buckets[1].predicate().starts_with("timestamp between \""));
+}
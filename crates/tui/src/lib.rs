@@ -1,12 +1,21 @@
 //! This library contains all the glue code with [Ratatui](https://github.com/ratatui/ratatui).
 
 mod action;
+mod clipboard;
+mod color;
 mod component;
+mod config_watcher;
 pub mod error;
 mod highlighter;
+mod histogram;
+mod match_counts;
+mod onboarding;
 mod records_buffer;
+mod report;
 mod schema_detail;
+mod session;
 pub mod theme;
+mod theme_preview;
 mod tui;
 
 pub(crate) use action::Action;
@@ -14,7 +23,10 @@ pub use action::Notification;
 
 pub use component::State;
 pub use component::Ui;
+pub use config_watcher::ConfigOverrides;
 pub use error::TuiError;
+pub use onboarding::{Onboarding, run_onboarding};
 pub use theme::Theme;
+pub use theme_preview::preview_theme;
 
-pub use highlighter::HIGHLIGHTER_THEMES;
+pub use highlighter::{HIGHLIGHTER_THEMES, load_syntaxes};
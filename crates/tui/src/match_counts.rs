@@ -0,0 +1,87 @@
+//! Number of kafka records matching the search query per topic and per partition.
+//! It is drawn above the list of records to see where the matching records are concentrated.
+//! Like the histogram, every matching record is counted, whatever the records buffer keeps.
+
+use std::collections::BTreeMap;
+
+use itertools::Itertools;
+
+#[derive(Default)]
+pub(crate) struct MatchCounts {
+    /// Number of records by topic and by partition
+    topics: BTreeMap<String, BTreeMap<i32, usize>>,
+}
+
+/// Number of matching records of a topic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TopicMatches {
+    pub topic: String,
+    pub count: usize,
+    /// Number of records by partition, the partition with the most records first
+    pub partitions: Vec<(i32, usize)>,
+}
+
+impl MatchCounts {
+    /// Counts a record of the given topic and partition.
+    pub fn add(&mut self, topic: &str, partition: i32) {
+        *self
+            .topics
+            .entry(topic.to_string())
+            .or_default()
+            .entry(partition)
+            .or_default() += 1;
+    }
+
+    pub fn clear(&mut self) {
+        self.topics.clear();
+    }
+
+    /// Returns the topics with the most matching records first.
+    pub fn topics(&self) -> Vec<TopicMatches> {
+        self.topics
+            .iter()
+            .map(|(topic, partitions)| TopicMatches {
+                topic: topic.clone(),
+                count: partitions.values().sum(),
+                partitions: partitions
+                    .iter()
+                    .map(|(partition, count)| (*partition, *count))
+                    .sorted_by_key(|(partition, count)| (usize::MAX - count, *partition))
+                    .collect(),
+            })
+            .sorted_by_key(|t| usize::MAX - t.count)
+            .collect()
+    }
+}
+
+#[test]
+fn test_match_counts() {
+    let mut counts = MatchCounts::default();
+    for (topic, partition) in [
+        ("orders", 0),
+        ("payments", 2),
+        ("payments", 0),
+        ("payments", 2),
+        ("orders", 1),
+        ("payments", 2),
+    ] {
+        counts.add(topic, partition);
+    }
+    assert_eq!(
+        counts.topics(),
+        vec![
+            TopicMatches {
+                topic: "payments".to_string(),
+                count: 4,
+                partitions: vec![(2, 3), (0, 1)],
+            },
+            TopicMatches {
+                topic: "orders".to_string(),
+                count: 2,
+                partitions: vec![(0, 1), (1, 1)],
+            },
+        ]
+    );
+    counts.clear();
+    assert!(counts.topics().is_empty());
+}
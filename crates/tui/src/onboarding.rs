@@ -0,0 +1,554 @@
+//! Guided configuration of the first cluster, started when the configuration file does not exist yet.
+//! The wizard asks for the brokers, the authentication mechanism and an optional schema registry,
+//! tests the connection and returns the cluster configuration to write in the configuration file.
+
+use std::{io::stderr, time::Duration};
+
+use app::configuration::{ClusterConfig, Configuration, SchemaRegistryConfig};
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen},
+};
+use indexmap::IndexMap;
+use ratatui::{
+    Frame, Terminal,
+    backend::CrosstermBackend,
+    layout::{Constraint, Flex, Layout, Position, Rect},
+    style::{Color, Stylize},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Padding, Paragraph, Wrap},
+};
+use rdkafka::consumer::{BaseConsumer, Consumer};
+use tui_input::{Input, backend::crossterm::EventHandler};
+use url::Url;
+
+use crate::{error::TuiError, tui::Tui};
+
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Cluster configured with the wizard.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Onboarding {
+    pub cluster: String,
+    pub config: ClusterConfig,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Brokers,
+    Authentication,
+    Username,
+    Password,
+    Connection,
+    SchemaRegistry,
+    Name,
+    Summary,
+}
+
+impl Step {
+    const ALL: [Step; 8] = [
+        Step::Brokers,
+        Step::Authentication,
+        Step::Username,
+        Step::Password,
+        Step::Connection,
+        Step::SchemaRegistry,
+        Step::Name,
+        Step::Summary,
+    ];
+
+    fn title(&self) -> &'static str {
+        match self {
+            Step::Brokers => "Brokers",
+            Step::Authentication => "Authentication",
+            Step::Username => "Username",
+            Step::Password => "Password",
+            Step::Connection => "Connection",
+            Step::SchemaRegistry => "Schema registry",
+            Step::Name => "Cluster name",
+            Step::Summary => "Summary",
+        }
+    }
+}
+
+/// Authentication mechanisms offered by the wizard, other mechanisms can be configured afterwards with `yozf configure`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Authentication {
+    Plaintext,
+    Ssl,
+    SaslPlain,
+    ScramSha256,
+    ScramSha512,
+}
+
+impl Authentication {
+    const ALL: [Authentication; 5] = [
+        Authentication::Plaintext,
+        Authentication::Ssl,
+        Authentication::SaslPlain,
+        Authentication::ScramSha256,
+        Authentication::ScramSha512,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Authentication::Plaintext => "None, plaintext",
+            Authentication::Ssl => "SSL",
+            Authentication::SaslPlain => "SASL/PLAIN over SSL",
+            Authentication::ScramSha256 => "SASL/SCRAM-SHA-256 over SSL",
+            Authentication::ScramSha512 => "SASL/SCRAM-SHA-512 over SSL",
+        }
+    }
+
+    fn properties(&self) -> Vec<(&'static str, &'static str)> {
+        match self {
+            Authentication::Plaintext => vec![("security.protocol", "plaintext")],
+            Authentication::Ssl => vec![("security.protocol", "ssl")],
+            Authentication::SaslPlain => vec![
+                ("security.protocol", "sasl_ssl"),
+                ("sasl.mechanism", "PLAIN"),
+            ],
+            Authentication::ScramSha256 => vec![
+                ("security.protocol", "sasl_ssl"),
+                ("sasl.mechanism", "SCRAM-SHA-256"),
+            ],
+            Authentication::ScramSha512 => vec![
+                ("security.protocol", "sasl_ssl"),
+                ("sasl.mechanism", "SCRAM-SHA-512"),
+            ],
+        }
+    }
+
+    fn requires_credentials(&self) -> bool {
+        !matches!(self, Authentication::Plaintext | Authentication::Ssl)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ConnectionStatus {
+    Untested,
+    Succeeded(String),
+    Failed(String),
+}
+
+/// What the wizard expects from the caller after a key stroke.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Continue,
+    TestConnection,
+    Done,
+    Cancel,
+}
+
+struct Wizard {
+    step: Step,
+    brokers: Input,
+    authentication: usize,
+    username: Input,
+    password: Input,
+    connection: ConnectionStatus,
+    schema_registry: Input,
+    name: Input,
+    error: Option<String>,
+}
+
+impl Default for Wizard {
+    fn default() -> Self {
+        Self {
+            step: Step::Brokers,
+            brokers: Input::new("localhost:9092".to_string()),
+            authentication: 0,
+            username: Input::default(),
+            password: Input::default(),
+            connection: ConnectionStatus::Untested,
+            schema_registry: Input::default(),
+            name: Input::new("localhost".to_string()),
+            error: None,
+        }
+    }
+}
+
+impl Wizard {
+    fn authentication(&self) -> Authentication {
+        Authentication::ALL[self.authentication]
+    }
+
+    /// Steps of the wizard, the credentials are skipped when the authentication does not need them.
+    fn steps(&self) -> Vec<Step> {
+        Step::ALL
+            .into_iter()
+            .filter(|s| {
+                self.authentication().requires_credentials()
+                    || !matches!(s, Step::Username | Step::Password)
+            })
+            .collect()
+    }
+
+    /// Text input of the current step, if it has one.
+    fn input(&self) -> Option<&Input> {
+        match self.step {
+            Step::Brokers => Some(&self.brokers),
+            Step::Username => Some(&self.username),
+            Step::Password => Some(&self.password),
+            Step::SchemaRegistry => Some(&self.schema_registry),
+            Step::Name => Some(&self.name),
+            Step::Authentication | Step::Connection | Step::Summary => None,
+        }
+    }
+
+    fn input_mut(&mut self) -> Option<&mut Input> {
+        match self.step {
+            Step::Brokers => Some(&mut self.brokers),
+            Step::Username => Some(&mut self.username),
+            Step::Password => Some(&mut self.password),
+            Step::SchemaRegistry => Some(&mut self.schema_registry),
+            Step::Name => Some(&mut self.name),
+            Step::Authentication | Step::Connection | Step::Summary => None,
+        }
+    }
+
+    fn move_to(&mut self, offset: isize) {
+        let steps = self.steps();
+        let index = steps.iter().position(|s| s == &self.step).unwrap_or(0);
+        if let Some(step) = index.checked_add_signed(offset).and_then(|i| steps.get(i)) {
+            self.step = *step;
+        }
+        self.error = None;
+    }
+
+    /// Returns an error message when the value of the current step is not valid.
+    fn validate(&self) -> Option<String> {
+        match self.step {
+            Step::Brokers if self.brokers.value().trim().is_empty() => {
+                Some("At least one broker is required, such as 'localhost:9092'".to_string())
+            }
+            Step::Username if self.username.value().is_empty() => {
+                Some("The username is required".to_string())
+            }
+            Step::Password if self.password.value().is_empty() => {
+                Some("The password is required".to_string())
+            }
+            Step::SchemaRegistry if !self.schema_registry.value().trim().is_empty() => {
+                Url::parse(self.schema_registry.value().trim())
+                    .err()
+                    .map(|e| format!("Invalid URL: {e}"))
+            }
+            Step::Name => {
+                let name = self.name.value();
+                (name.is_empty() || name.contains(char::is_whitespace))
+                    .then(|| "The name must not be empty nor contain spaces".to_string())
+            }
+            _ => None,
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Outcome {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Outcome::Cancel;
+        }
+        match (self.step, key.code) {
+            (Step::Brokers, KeyCode::Esc) => return Outcome::Cancel,
+            (_, KeyCode::Esc) => self.move_to(-1),
+            (Step::Authentication, KeyCode::Up) => {
+                self.authentication = self.authentication.saturating_sub(1);
+            }
+            (Step::Authentication, KeyCode::Down) => {
+                self.authentication = (self.authentication + 1).min(Authentication::ALL.len() - 1);
+            }
+            (Step::Connection, KeyCode::Tab) => self.move_to(1),
+            (Step::Connection, KeyCode::Enter) => match self.connection {
+                ConnectionStatus::Succeeded(_) => self.move_to(1),
+                _ => return Outcome::TestConnection,
+            },
+            (Step::Summary, KeyCode::Enter) => return Outcome::Done,
+            (_, KeyCode::Enter) => match self.validate() {
+                Some(error) => self.error = Some(error),
+                None => self.move_to(1),
+            },
+            _ => {
+                if let Some(input) = self.input_mut() {
+                    input.handle_event(&Event::Key(key));
+                    if matches!(self.step, Step::Brokers | Step::Username | Step::Password) {
+                        self.connection = ConnectionStatus::Untested;
+                    }
+                }
+            }
+        }
+        if self.step == Step::Authentication {
+            self.connection = ConnectionStatus::Untested;
+        }
+        Outcome::Continue
+    }
+
+    fn cluster_config(&self) -> ClusterConfig {
+        let authentication = self.authentication();
+        let mut kafka = IndexMap::new();
+        kafka.insert(
+            "bootstrap.servers".to_string(),
+            self.brokers.value().replace(' ', ""),
+        );
+        for (key, value) in authentication.properties() {
+            kafka.insert(key.to_string(), value.to_string());
+        }
+        if authentication.requires_credentials() {
+            kafka.insert(
+                "sasl.username".to_string(),
+                self.username.value().to_string(),
+            );
+            kafka.insert(
+                "sasl.password".to_string(),
+                self.password.value().to_string(),
+            );
+        }
+        ClusterConfig {
+            kafka,
+            schema_registry: Url::parse(self.schema_registry.value().trim())
+                .ok()
+                .map(SchemaRegistryConfig::new),
+            ..Default::default()
+        }
+    }
+
+    fn onboarding(&self) -> Onboarding {
+        Onboarding {
+            cluster: self.name.value().to_string(),
+            config: self.cluster_config(),
+        }
+    }
+
+    /// Fetches the metadata of the cluster with the configured properties.
+    fn test_connection(&mut self) {
+        let result = self
+            .cluster_config()
+            .create_kafka_consumer::<BaseConsumer>()
+            .and_then(|consumer| {
+                consumer
+                    .fetch_metadata(None, CONNECTION_TIMEOUT)
+                    .map_err(Into::into)
+            });
+        self.connection = match result {
+            Ok(metadata) => ConnectionStatus::Succeeded(format!(
+                "Connected to {} brokers, {} topics",
+                metadata.brokers().len(),
+                metadata.topics().len()
+            )),
+            Err(e) => ConnectionStatus::Failed(e.to_string()),
+        };
+    }
+
+    fn draw(&self, f: &mut Frame<'_>, rect: Rect) {
+        let [area] = Layout::horizontal([Constraint::Max(90)])
+            .flex(Flex::Center)
+            .areas(rect);
+        let [area] = Layout::vertical([Constraint::Max(22)])
+            .flex(Flex::Center)
+            .areas(area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .padding(Padding::new(2, 2, 1, 0))
+            .title(" Welcome to Yozefu ")
+            .title_bottom(" [ENTER]: Next   [ESC]: Back   [CTRL + C]: Cancel ");
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let [steps_area, form_area] =
+            Layout::horizontal([Constraint::Length(22), Constraint::Fill(1)]).areas(inner);
+        let current = self.steps().iter().position(|s| s == &self.step);
+        let steps = self
+            .steps()
+            .iter()
+            .enumerate()
+            .map(|(i, step)| {
+                let line = Line::from(format!("{}. {}", i + 1, step.title()));
+                match Some(i).cmp(&current) {
+                    std::cmp::Ordering::Less => line.fg(Color::Green),
+                    std::cmp::Ordering::Equal => line.bold(),
+                    std::cmp::Ordering::Greater => line.dim(),
+                }
+            })
+            .collect::<Vec<_>>();
+        f.render_widget(Paragraph::new(steps), steps_area);
+
+        let mut lines = vec![Line::from(self.step.title()).bold(), Line::from("")];
+        lines.extend(self.prompt().into_iter().map(Line::from));
+        lines.push(Line::from(""));
+        let input_line = lines.len() as u16;
+        match self.step {
+            Step::Authentication => {
+                lines.extend(Authentication::ALL.iter().enumerate().map(|(i, a)| {
+                    match i == self.authentication {
+                        true => Line::from(format!("> {}", a.label())).bold(),
+                        false => Line::from(format!("  {}", a.label())),
+                    }
+                }));
+            }
+            Step::Connection => lines.push(match &self.connection {
+                ConnectionStatus::Untested => Line::from("Not tested yet").dim(),
+                ConnectionStatus::Succeeded(message) => {
+                    Line::from(message.clone()).fg(Color::Green)
+                }
+                ConnectionStatus::Failed(message) => Line::from(message.clone()).fg(Color::Red),
+            }),
+            Step::Summary => {
+                let mut config = self.cluster_config();
+                if let Some(password) = config.kafka.get_mut("sasl.password") {
+                    *password = "********".to_string();
+                }
+                let config = serde_json::to_string_pretty(&config).unwrap_or_default();
+                lines.extend(config.lines().enumerate().map(|(i, l)| match i {
+                    0 => Line::from(format!("\"{}\": {l}", self.name.value())),
+                    _ => Line::from(l.to_string()),
+                }));
+            }
+            Step::Password => lines.push(Line::from(vec![
+                Span::from("> ").bold(),
+                Span::from("*".repeat(self.password.value().chars().count())),
+            ])),
+            _ => lines.push(Line::from(vec![
+                Span::from("> ").bold(),
+                Span::from(
+                    self.input()
+                        .map(Input::value)
+                        .unwrap_or_default()
+                        .to_string(),
+                ),
+            ])),
+        }
+        if let Some(error) = &self.error {
+            lines.push(Line::from(""));
+            lines.push(Line::from(error.clone()).fg(Color::Red));
+        }
+        f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), form_area);
+
+        if let Some(input) = self.input() {
+            f.set_cursor_position(Position {
+                x: form_area.x + 2 + input.visual_cursor() as u16,
+                y: form_area.y + input_line,
+            });
+        }
+    }
+
+    fn prompt(&self) -> Vec<&'static str> {
+        match self.step {
+            Step::Brokers => vec![
+                "No configuration file was found, let's configure your first cluster.",
+                "Bootstrap servers of the cluster, separated by commas:",
+            ],
+            Step::Authentication => vec!["How does the client authenticate to the brokers?"],
+            Step::Username => vec!["SASL username:"],
+            Step::Password => vec!["SASL password, it is stored in the configuration file:"],
+            Step::Connection => {
+                vec!["Press ENTER to test the connection to the brokers, TAB to skip this step."]
+            }
+            Step::SchemaRegistry => {
+                vec!["URL of the schema registry, leave it empty if you don't use one:"]
+            }
+            Step::Name => vec!["Name of the cluster, you select it with '--cluster <name>':"],
+            Step::Summary => vec!["Press ENTER to write the configuration file."],
+        }
+    }
+}
+
+/// Runs the wizard in the terminal.
+/// It returns `None` when the user cancels it.
+pub fn run_onboarding() -> Result<Option<Onboarding>, TuiError> {
+    Tui::init_panic_hook();
+    crossterm::terminal::enable_raw_mode()?;
+    crossterm::execute!(stderr(), EnterAlternateScreen)?;
+    let result = run_wizard(&mut Terminal::new(CrosstermBackend::new(stderr()))?);
+    crossterm::execute!(stderr(), LeaveAlternateScreen, cursor::Show)?;
+    crossterm::terminal::disable_raw_mode()?;
+    result
+}
+
+fn run_wizard(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stderr>>,
+) -> Result<Option<Onboarding>, TuiError> {
+    let mut wizard = Wizard::default();
+    loop {
+        terminal.draw(|f| wizard.draw(f, f.area()))?;
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match wizard.handle_key(key) {
+            Outcome::Continue => (),
+            Outcome::TestConnection => {
+                wizard.connection = ConnectionStatus::Untested;
+                wizard.error = Some("Connecting to the brokers...".to_string());
+                terminal.draw(|f| wizard.draw(f, f.area()))?;
+                wizard.test_connection();
+                wizard.error = None;
+            }
+            Outcome::Done => return Ok(Some(wizard.onboarding())),
+            Outcome::Cancel => return Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+fn type_keys(wizard: &mut Wizard, keys: &str) {
+    for c in keys.chars() {
+        let code = match c {
+            '\n' => KeyCode::Enter,
+            '\t' => KeyCode::Tab,
+            '↓' => KeyCode::Down,
+            c => KeyCode::Char(c),
+        };
+        wizard.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+}
+
+#[test]
+fn test_wizard() {
+    let mut wizard = Wizard {
+        brokers: Input::default(),
+        ..Default::default()
+    };
+    type_keys(&mut wizard, "\n");
+    assert_eq!(wizard.step, Step::Brokers);
+    assert!(wizard.error.is_some());
+
+    type_keys(
+        &mut wizard,
+        "kafka-1:9093,kafka-2:9093\n↓↓↓\nalice\ns3cret\n\t",
+    );
+    assert_eq!(wizard.step, Step::SchemaRegistry);
+    type_keys(&mut wizard, "registry\n");
+    assert!(wizard.error.is_some());
+    wizard.schema_registry = Input::new("https://registry.acme.io".to_string());
+    type_keys(&mut wizard, "\n");
+    wizard.name = Input::new("acme".to_string());
+    type_keys(&mut wizard, "\n");
+    assert_eq!(wizard.step, Step::Summary);
+
+    let onboarding = wizard.onboarding();
+    assert_eq!(onboarding.cluster, "acme");
+    assert_eq!(
+        onboarding.config.kafka.get("sasl.mechanism").unwrap(),
+        "SCRAM-SHA-256"
+    );
+    assert_eq!(
+        onboarding.config.kafka.get("sasl.password").unwrap(),
+        "s3cret"
+    );
+    assert_eq!(
+        onboarding.config.schema_registry.unwrap().url.as_str(),
+        "https://registry.acme.io/"
+    );
+}
+
+#[test]
+fn test_draw() {
+    use ratatui::backend::TestBackend;
+
+    let mut wizard = Wizard::default();
+    type_keys(&mut wizard, "\n↓↓\nalice\npassword\n\t\n\n");
+    let mut terminal = Terminal::new(TestBackend::new(90, 22)).unwrap();
+    terminal.draw(|f| wizard.draw(f, f.area())).unwrap();
+    insta::assert_snapshot!(terminal.backend());
+}
@@ -3,15 +3,30 @@
 //!
 //! This should be possible to increase the size but the more you display events,
 //! the more the tool gets laggy. I need to work on it.
+//!
+//! When the search query has a `sample` clause, the buffer keeps a random sample of the matching records
+//! with [reservoir sampling](https://en.wikipedia.org/wiki/Reservoir_sampling) instead of the last ones.
+//! With a `distinct by` clause, a hash index keeps track of the record stored for each key,
+//! so only the latest record per key is kept, like a compacted topic.
+//! The distinct value is read from the full value, before it is truncated for the preview.
+//!
+//! Every matching record is also counted in a [`Histogram`] and in [`MatchCounts`], whatever the buffer keeps.
+//!
+//! The version of the buffer changes each time its records change,
+//! so components drawing the records build their rows again only when needed.
+
+use std::collections::HashMap;
 
 use circular_buffer::{CircularBuffer, Iter};
 use lib::{
     KafkaRecord,
-    search::{Order, OrderBy},
+    search::{Distinct, Order, OrderBy, Sample},
 };
 use rayon::prelude::*;
 use tokio::sync::watch::{self, Receiver, Sender};
 
+use crate::{histogram::Histogram, match_counts::MatchCounts};
+
 /// Size of the ring buffer
 #[cfg(not(target_family = "windows"))]
 pub const BUFFER_SIZE: usize = 500;
@@ -26,6 +41,18 @@ pub(crate) struct RecordsBuffer {
     stats: Stats,
     pub channels: (Sender<BufferAction>, Receiver<BufferAction>),
     last_time_sorted: usize,
+    sample: Option<Sample>,
+    /// Number of records that were candidates for the sample
+    sampled: usize,
+    distinct: Option<Distinct>,
+    /// Topic, partition and offset of the record stored for each distinct value
+    index: HashMap<String, (String, i32, i64)>,
+    /// Number of matching records per minute
+    histogram: Histogram,
+    /// Number of matching records per topic and partition
+    match_counts: MatchCounts,
+    /// Incremented each time the records of the buffer change: pushed, sorted or removed
+    version: u64,
 }
 
 macro_rules! sort_records {
@@ -53,6 +80,13 @@ impl RecordsBuffer {
             stats: Stats::default(),
             channels: watch::channel(BufferAction::Stats(Stats::default())),
             last_time_sorted: 0,
+            sample: None,
+            sampled: 0,
+            distinct: None,
+            index: HashMap::new(),
+            histogram: Histogram::default(),
+            match_counts: MatchCounts::default(),
+            version: 0,
         }
     }
 
@@ -60,9 +94,24 @@ impl RecordsBuffer {
     pub fn reset(&mut self) {
         self.buffer.clear();
         self.stats = Stats::default();
+        self.sampled = 0;
+        self.index.clear();
+        self.histogram.clear();
+        self.match_counts.clear();
+        self.version += 1;
         self.dispatch_metrics();
     }
 
+    /// Keeps a random sample of the records pushed from now on, or the last ones with `None`
+    pub fn set_sample(&mut self, sample: Option<Sample>) {
+        self.sample = sample;
+    }
+
+    /// Keeps only the latest record for each distinct value from now on, or all of them with `None`
+    pub fn set_distinct(&mut self, distinct: Option<Distinct>) {
+        self.distinct = distinct;
+    }
+
     /// Returns the metrics of the number of records matched and read.
     pub fn stats(&self) -> Stats {
         Stats {
@@ -86,12 +135,88 @@ impl RecordsBuffer {
         self.buffer.iter()
     }
 
+    pub fn histogram(&self) -> &Histogram {
+        &self.histogram
+    }
+
+    pub fn match_counts(&self) -> &MatchCounts {
+        &self.match_counts
+    }
+
+    /// Returns the version of the records, see [`RecordsBuffer::version`]
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
     pub fn push(&mut self, kafka_record: KafkaRecord) -> usize {
-        self.buffer.push_back(kafka_record);
+        self.push_preview(kafka_record, 0)
+    }
+
+    /// Adds a matching record to the buffer, its value is truncated to `value_preview_size` bytes, `0` keeps it whole.
+    /// The distinct value is read before the truncation, from the full value of the record.
+    pub fn push_preview(&mut self, kafka_record: KafkaRecord, value_preview_size: usize) -> usize {
         self.stats.matched += 1;
+        self.histogram.add(kafka_record.timestamp);
+        self.match_counts
+            .add(&kafka_record.topic, kafka_record.partition);
+        self.version += 1;
+        let distinct = self.distinct.as_ref().map(|d| d.of(&kafka_record));
+        let mut preview = kafka_record;
+        preview.truncate_value(value_preview_size);
+        // A query cannot have both clauses, see `ValidSearchQuery::from_query`
+        match (distinct, self.sample) {
+            (Some(value), _) => self.push_distinct(preview, value),
+            (None, Some(sample)) => self.push_sample(preview, sample),
+            (None, None) => {
+                self.buffer.push_back(preview);
+            }
+        }
         self.stats.matched
     }
 
+    /// Replaces the record stored for the same distinct value, if any.
+    /// Records without the distinct value are all kept.
+    fn push_distinct(&mut self, kafka_record: KafkaRecord, value: Option<String>) {
+        if let Some(value) = value {
+            let id = (
+                kafka_record.topic.clone(),
+                kafka_record.partition,
+                kafka_record.offset,
+            );
+            if let Some(previous) = self.index.insert(value, id) {
+                if let Some(position) = self.buffer.iter().position(|r| {
+                    r.topic == previous.0 && r.partition == previous.1 && r.offset == previous.2
+                }) {
+                    self.buffer.remove(position);
+                }
+            }
+        }
+        let Some(evicted) = self.buffer.push_back(kafka_record) else {
+            return;
+        };
+        // The value of the evicted record may be truncated, so its entry is looked up by id
+        self.index.retain(|_, id| {
+            id.0 != evicted.topic || id.1 != evicted.partition || id.2 != evicted.offset
+        });
+    }
+
+    fn push_sample(&mut self, kafka_record: KafkaRecord, sample: Sample) {
+        let capacity = match sample {
+            Sample::Records(n) => n.min(BUFFER_SIZE),
+            Sample::Percentage(p) if rand::random_bool(p / 100.0) => BUFFER_SIZE,
+            Sample::Percentage(_) => return,
+        };
+        self.sampled += 1;
+        if self.buffer.len() < capacity {
+            self.buffer.push_back(kafka_record);
+            return;
+        }
+        let index = rand::random_range(0..self.sampled);
+        if let Some(record) = self.buffer.get_mut(index) {
+            *record = kafka_record;
+        }
+    }
+
     /// Dispatches a new events about the metrics of the buffer
     pub fn dispatch_metrics(&mut self) {
         self.channels
@@ -132,9 +257,104 @@ impl RecordsBuffer {
                 sort_records!(unsorted, topic, is_descending);
             }
         }
+        let unchanged = unsorted
+            .iter()
+            .zip(self.buffer.iter())
+            .all(|(a, b)| a.topic == b.topic && a.partition == b.partition && a.offset == b.offset);
+        if unchanged {
+            return;
+        }
         self.buffer.clear();
         self.buffer.extend(unsorted);
+        self.version += 1;
+    }
+}
+
+#[test]
+fn test_sample() {
+    let mut buffer = RecordsBuffer::new();
+    buffer.set_sample(Some(Sample::Records(10)));
+    for offset in 0..1_000 {
+        buffer.push({
+            let mut record = KafkaRecord::default();
+            record.offset = offset;
+            record
+        });
+    }
+    assert_eq!(buffer.stats().matched, 1_000);
+    assert_eq!(buffer.stats().buffer_size, 10);
+    assert!(buffer.iter().any(|r| r.offset >= 10));
+
+    buffer.reset();
+    buffer.set_sample(Some(Sample::Percentage(100.0)));
+    for offset in 0..(BUFFER_SIZE as i64 * 2) {
+        buffer.push({
+            let mut record = KafkaRecord::default();
+            record.offset = offset;
+            record
+        });
+    }
+    assert_eq!(buffer.stats().buffer_size, BUFFER_SIZE);
+}
+
+#[test]
+fn test_version() {
+    let mut buffer = RecordsBuffer::new();
+    let record = |offset| {
+        let mut record = KafkaRecord::default();
+        record.offset = offset;
+        record
+    };
+    for offset in [2, 1] {
+        buffer.new_record_read();
+        buffer.push(record(offset));
+    }
+    let version = buffer.version();
+    let order_by = OrderBy {
+        order: Order::Offset,
+        ..Default::default()
+    };
+    buffer.sort(&order_by);
+    assert!(buffer.version() > version);
+    let version = buffer.version();
+    buffer.sort(&order_by);
+    assert_eq!(buffer.version(), version);
+}
+
+#[test]
+fn test_distinct() {
+    let mut buffer = RecordsBuffer::new();
+    buffer.set_distinct(Some(Distinct::Key));
+    for offset in 0..100 {
+        buffer.push({
+            let mut record = KafkaRecord::default();
+            record.offset = offset;
+            record.key = lib::DataType::String((offset % 3).to_string());
+            record
+        });
+    }
+    assert_eq!(buffer.stats().matched, 100);
+    let mut offsets = buffer.iter().map(|r| r.offset).collect::<Vec<_>>();
+    offsets.sort_unstable();
+    assert_eq!(offsets, vec![97, 98, 99]);
+}
+
+#[test]
+fn test_distinct_on_the_full_value() {
+    let mut buffer = RecordsBuffer::new();
+    buffer.set_distinct(Some(Distinct::Value(None)));
+    for (offset, value) in ["same prefix, first", "same prefix, second"]
+        .iter()
+        .enumerate()
+    {
+        let mut record = KafkaRecord::default();
+        record.offset = offset as i64;
+        record.value = lib::DataType::String(value.to_string());
+        record.value_as_string = value.to_string();
+        buffer.push_preview(record, 4);
     }
+    assert_eq!(buffer.stats().buffer_size, 2);
+    assert!(buffer.iter().all(|r| r.truncated));
 }
 
 #[allow(clippy::large_enum_variant)]
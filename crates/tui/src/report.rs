@@ -0,0 +1,286 @@
+//! Reports of the search results or of a record, ready to be pasted into an incident report.
+//! Markdown reports rely on fenced code blocks for syntax highlighting,
+//! HTML reports are standalone snippets with inline styles.
+
+use chrono::SecondsFormat;
+use lib::{DataType, KafkaRecord, kafka::SchemaType};
+
+use crate::{highlighter::highlight_html, schema_detail::SchemaDetail};
+
+/// Maximum number of records listed in the report of the search results
+pub(crate) const MAX_REPORT_RECORDS: usize = 100;
+
+/// Number of characters of the value shown in the table of the search results
+const VALUE_PREVIEW_LENGTH: usize = 80;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+/// Renders the records matching the search query as a table, the first [`MAX_REPORT_RECORDS`] only.
+pub(crate) fn records_report(
+    format: ReportFormat,
+    cluster: &str,
+    topics: &[String],
+    query: &str,
+    records: &[KafkaRecord],
+) -> String {
+    let title = format!(
+        "{} records of {} on {}",
+        records.len(),
+        topics
+            .iter()
+            .map(|t| code(format, t))
+            .collect::<Vec<_>>()
+            .join(", "),
+        code(format, cluster)
+    );
+    let header = ["Timestamp", "Topic", "Partition", "Offset", "Key", "Value"];
+    let rows = records
+        .iter()
+        .take(MAX_REPORT_RECORDS)
+        .map(|r| {
+            [
+                timestamp(r),
+                r.topic.clone(),
+                r.partition.to_string(),
+                r.offset.to_string(),
+                r.key_as_string.clone(),
+                preview(&r.value_as_string),
+            ]
+        })
+        .collect::<Vec<_>>();
+    let note = (records.len() > MAX_REPORT_RECORDS)
+        .then(|| format!("Only the first {MAX_REPORT_RECORDS} records are listed."));
+
+    match format {
+        ReportFormat::Markdown => {
+            let mut report = vec![
+                format!("### {title}"),
+                String::new(),
+                format!("Query: {}", code(format, query)),
+                String::new(),
+                format!("| {} |", header.join(" | ")),
+                "| --- | --- | ---: | ---: | --- | --- |".to_string(),
+            ];
+            report.extend(rows.iter().map(|row| {
+                format!(
+                    "| {} |",
+                    row.iter()
+                        .map(|c| escape_markdown_cell(c))
+                        .collect::<Vec<_>>()
+                        .join(" | ")
+                )
+            }));
+            if let Some(note) = note {
+                report.extend([String::new(), format!("_{note}_")]);
+            }
+            report.join("\n")
+        }
+        ReportFormat::Html => {
+            let mut report = vec![
+                "<section>".to_string(),
+                format!("<h3>{title}</h3>"),
+                format!("<p>Query: {}</p>", code(format, query)),
+                "<table>".to_string(),
+                format!(
+                    "<thead><tr>{}</tr></thead>",
+                    header.map(|h| format!("<th>{h}</th>")).join("")
+                ),
+                "<tbody>".to_string(),
+            ];
+            report.extend(rows.iter().map(|row| {
+                format!(
+                    "<tr>{}</tr>",
+                    row.iter()
+                        .map(|c| format!("<td>{}</td>", escape_html(c)))
+                        .collect::<String>()
+                )
+            }));
+            report.extend(["</tbody>".to_string(), "</table>".to_string()]);
+            if let Some(note) = note {
+                report.push(format!("<p><em>{note}</em></p>"));
+            }
+            report.push("</section>".to_string());
+            report.join("\n")
+        }
+    }
+}
+
+/// Renders a record with its headers, its key, its value and the schemas they have been serialized with.
+pub(crate) fn record_report(
+    format: ReportFormat,
+    record: &KafkaRecord,
+    key_schema: Option<&SchemaDetail>,
+    value_schema: Option<&SchemaDetail>,
+) -> String {
+    let title = format!(
+        "{} partition {} offset {}",
+        code(format, &record.topic),
+        record.partition,
+        record.offset
+    );
+    let mut properties = vec![
+        ("Timestamp", timestamp(record)),
+        ("Key", record.key_as_string.clone()),
+    ];
+    properties.extend(
+        record
+            .headers
+            .iter()
+            .map(|(k, v)| ("Header", format!("{k}: {v}"))),
+    );
+    let mut blocks = vec![("Value".to_string(), data_type_block(&record.value))];
+    for (name, schema) in [("Key schema", key_schema), ("Value schema", value_schema)] {
+        if let Some((id, block)) = schema.and_then(schema_block) {
+            blocks.push((format!("{name} {id}"), block));
+        }
+    }
+
+    match format {
+        ReportFormat::Markdown => {
+            let mut report = vec![format!("### {title}"), String::new()];
+            report.extend(
+                properties
+                    .iter()
+                    .map(|(name, value)| format!("- {name}: {}", code(format, value))),
+            );
+            for (name, (language, content)) in blocks {
+                report.extend([
+                    String::new(),
+                    format!("#### {name}"),
+                    String::new(),
+                    format!("```{language}"),
+                    content,
+                    "```".to_string(),
+                ]);
+            }
+            report.join("\n")
+        }
+        ReportFormat::Html => {
+            let mut report = vec![
+                "<section>".to_string(),
+                format!("<h3>{title}</h3>"),
+                "<ul>".to_string(),
+            ];
+            report.extend(
+                properties
+                    .iter()
+                    .map(|(name, value)| format!("<li>{name}: {}</li>", code(format, value))),
+            );
+            report.push("</ul>".to_string());
+            for (name, (language, content)) in blocks {
+                report.push(format!("<h4>{name}</h4>"));
+                report.push(highlight_html(&content, language));
+            }
+            report.push("</section>".to_string());
+            report.join("\n")
+        }
+    }
+}
+
+/// The language of the code block and its content.
+fn data_type_block(data: &DataType) -> (&'static str, String) {
+    match data {
+        DataType::Json(_) => ("json", data.to_string_pretty()),
+        DataType::String(s) => ("txt", s.clone()),
+        _ => ("txt", data.to_string()),
+    }
+}
+
+/// The id of the schema, the language of the code block and its content.
+fn schema_block(schema: &SchemaDetail) -> Option<(u32, (&'static str, String))> {
+    let response = schema.response.as_ref()?;
+    let language = match response.schema_type {
+        Some(SchemaType::Protobuf) => "proto",
+        _ => "json",
+    };
+    Some((schema.id, (language, response.schema_to_string_pretty())))
+}
+
+fn timestamp(record: &KafkaRecord) -> String {
+    record
+        .timestamp
+        .and(record.timestamp_as_local_date_time())
+        .map(|t| t.to_rfc3339_opts(SecondsFormat::Millis, false))
+        .unwrap_or_default()
+}
+
+/// The value on a single line, truncated to [`VALUE_PREVIEW_LENGTH`] characters.
+fn preview(value: &str) -> String {
+    let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    match value.chars().count() > VALUE_PREVIEW_LENGTH {
+        true => format!(
+            "{}…",
+            value.chars().take(VALUE_PREVIEW_LENGTH).collect::<String>()
+        ),
+        false => value,
+    }
+}
+
+fn code(format: ReportFormat, text: &str) -> String {
+    match format {
+        ReportFormat::Markdown => format!("`{}`", text.replace('`', "'")),
+        ReportFormat::Html => format!("<code>{}</code>", escape_html(text)),
+    }
+}
+
+fn escape_markdown_cell(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace(['\n', '\r'], " ")
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+fn record() -> KafkaRecord {
+    {
+        let mut record = KafkaRecord::default();
+        record.topic = "orders".to_string();
+        record.partition = 2;
+        record.offset = 42;
+        record.key_as_string = "ord-42".to_string();
+        record.value = DataType::Json(serde_json::json!({"status": "FAILED"}));
+        record.value_as_string = r#"{"status":"FAILED","reason":"a|b"}"#.to_string();
+        record.headers = [("source".to_string(), "checkout".to_string())].into();
+        record
+    }
+}
+
+#[test]
+fn test_markdown_records_report() {
+    let report = records_report(
+        ReportFormat::Markdown,
+        "prod",
+        &["orders".to_string()],
+        r#"key == "ord-42""#,
+        &[record()],
+    );
+    assert_eq!(
+        report,
+        r#"### 1 records of `orders` on `prod`
+
+Query: `key == "ord-42"`
+
+| Timestamp | Topic | Partition | Offset | Key | Value |
+| --- | --- | ---: | ---: | --- | --- |
+|  | orders | 2 | 42 | ord-42 | {"status":"FAILED","reason":"a\|b"} |"#
+    );
+}
+
+#[test]
+fn test_html_record_report() {
+    let report = record_report(ReportFormat::Html, &record(), None, None);
+    assert!(report.starts_with("<section>\n<h3><code>orders</code> partition 2 offset 42</h3>"));
+    assert!(report.contains("<li>Header: <code>source: checkout</code></li>"));
+    assert!(report.contains("<h4>Value</h4>\n<pre style="));
+    assert!(report.ends_with("</section>"));
+}
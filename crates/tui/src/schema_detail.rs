@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use lib::kafka::{SchemaId, SchemaRegistryClient, SchemaResponse};
 use serde::Serialize;
 use tracing::warn;
@@ -10,12 +12,14 @@ pub struct SchemaDetail {
 }
 
 impl SchemaDetail {
+    /// Fetches the schema from the schema registry of the topic.
     pub async fn from(
         schema_registry: &mut Option<SchemaRegistryClient>,
+        topic: &str,
         id: Option<&SchemaId>,
     ) -> Option<Self> {
         let id = id.as_ref()?.0;
-        let (response, url) = match schema_registry {
+        let (response, url) = match schema_registry.as_mut().map(|s| s.for_topic(topic)) {
             Some(s) => (s.schema(id).await.ok().flatten(), s.schema_url(id)),
             None => {
                 warn!("No schema registry client configured to fetch schema {id}.");
@@ -32,3 +36,147 @@ pub struct ExportedSchemasDetails {
     pub key: Option<SchemaDetail>,
     pub value: Option<SchemaDetail>,
 }
+
+/// Number of versions displayed per page in the schemas view
+const VERSIONS_PER_PAGE: usize = 20;
+
+/// The versions of a subject, displayed page by page.
+/// Only version numbers are listed, the content of a version is fetched when it is selected.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct SchemaVersions {
+    pub subject: String,
+    /// Version numbers, the most recent first
+    versions: Vec<u32>,
+    /// Versions that have been soft-deleted, they are listed with the other versions
+    deleted: Vec<u32>,
+    selected: usize,
+    /// Contents of the versions, `None` while being fetched
+    contents: HashMap<u32, Option<SchemaDetail>>,
+}
+
+impl SchemaVersions {
+    pub fn new(subject: String, mut versions: Vec<u32>) -> Self {
+        versions.sort_unstable_by(|a, b| b.cmp(a));
+        Self {
+            subject,
+            versions,
+            ..Default::default()
+        }
+    }
+
+    /// Lists the soft-deleted versions along with the other versions.
+    pub fn with_deleted(mut self, deleted: Vec<u32>) -> Self {
+        for version in &deleted {
+            if !self.versions.contains(version) {
+                self.versions.push(*version);
+            }
+        }
+        self.versions.sort_unstable_by(|a, b| b.cmp(a));
+        self.deleted = deleted;
+        self
+    }
+
+    pub fn is_deleted(&self, version: u32) -> bool {
+        self.deleted.contains(&version)
+    }
+
+    /// All the versions of the subject have been soft-deleted.
+    pub fn is_subject_deleted(&self) -> bool {
+        !self.versions.is_empty() && self.versions.iter().all(|v| self.is_deleted(*v))
+    }
+
+    pub fn count(&self) -> usize {
+        self.versions.len()
+    }
+
+    pub fn page(&self) -> usize {
+        self.selected / VERSIONS_PER_PAGE
+    }
+
+    pub fn pages(&self) -> usize {
+        self.versions.len().div_ceil(VERSIONS_PER_PAGE).max(1)
+    }
+
+    /// Returns the versions of the current page and the index of the first one.
+    pub fn page_items(&self) -> (usize, &[u32]) {
+        let start = self.page() * VERSIONS_PER_PAGE;
+        let end = (start + VERSIONS_PER_PAGE).min(self.versions.len());
+        (start, &self.versions[start..end])
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    pub fn selected(&self) -> Option<u32> {
+        self.versions.get(self.selected).copied()
+    }
+
+    pub fn select(&mut self, index: usize) {
+        self.selected = index.min(self.versions.len().saturating_sub(1));
+    }
+
+    pub fn next_page(&mut self) {
+        self.select((self.page() + 1) * VERSIONS_PER_PAGE);
+    }
+
+    pub fn previous_page(&mut self) {
+        self.select(self.page().saturating_sub(1) * VERSIONS_PER_PAGE);
+    }
+
+    /// Returns the content of the selected version, `Some(None)` while it is being fetched.
+    pub fn content(&self) -> Option<&Option<SchemaDetail>> {
+        self.contents.get(&self.selected()?)
+    }
+
+    /// Returns the selected version if its content has not been requested yet, and marks it as being fetched.
+    pub fn version_to_fetch(&mut self) -> Option<u32> {
+        let version = self.selected()?;
+        match self.contents.contains_key(&version) {
+            true => None,
+            false => {
+                self.contents.insert(version, None);
+                Some(version)
+            }
+        }
+    }
+
+    pub fn set_content(&mut self, version: u32, detail: SchemaDetail) {
+        self.contents.insert(version, Some(detail));
+    }
+}
+
+#[test]
+fn test_schema_versions_pages() {
+    let mut versions = SchemaVersions::new("orders-value".to_string(), (1..=523).collect());
+    assert_eq!(versions.pages(), 27);
+    assert_eq!(versions.selected(), Some(523));
+    assert_eq!(versions.page_items().1.len(), 20);
+
+    versions.next_page();
+    assert_eq!(versions.page(), 1);
+    assert_eq!(versions.selected(), Some(503));
+    assert_eq!(versions.version_to_fetch(), Some(503));
+    assert_eq!(versions.version_to_fetch(), None);
+    assert_eq!(versions.content(), Some(&None));
+
+    versions.select(10_000);
+    assert_eq!(versions.page(), 26);
+    assert_eq!(versions.page_items(), (520, &[3, 2, 1][..]));
+    versions.previous_page();
+    assert_eq!(versions.selected_index(), 500);
+}
+
+#[test]
+fn test_soft_deleted_versions() {
+    let versions =
+        SchemaVersions::new("orders-value".to_string(), vec![1, 4]).with_deleted(vec![2, 3]);
+    assert_eq!(versions.page_items().1, &[4, 3, 2, 1]);
+    assert!(versions.is_deleted(3));
+    assert!(!versions.is_deleted(4));
+    assert!(!versions.is_subject_deleted());
+
+    let versions = SchemaVersions::new("legacy-value".to_string(), vec![]).with_deleted(vec![1, 2]);
+    assert_eq!(versions.selected(), Some(2));
+    assert!(versions.is_subject_deleted());
+}
@@ -0,0 +1,185 @@
+//! A session file contains the key strokes and the kafka records received by the TUI,
+//! one JSON event per line, so the session can be replayed later for bug reports or demos.
+
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use crossterm::event::KeyEvent;
+use lib::{DataType, Error, KafkaRecord};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::warn;
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum SessionEvent {
+    /// The user pressed a key
+    Key { key: KeyEvent },
+    /// The list of topics has been loaded
+    Topics { topics: Vec<String> },
+    /// A kafka record matched the search query
+    Record { record: KafkaRecord },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SessionEntry {
+    /// Milliseconds elapsed since the beginning of the session
+    elapsed: u64,
+    #[serde(flatten)]
+    event: SessionEvent,
+}
+
+/// Time between two flushes of the session file, events are written in batches in between
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Shared by the TUI and the consumer loop, events are serialized before the writer is locked.
+pub(crate) struct SessionRecorder {
+    writer: Mutex<(BufWriter<File>, Instant)>,
+    start: Instant,
+}
+
+impl SessionRecorder {
+    pub fn new(file: &Path) -> Result<Self, Error> {
+        let writer = File::create(file).map_err(|e| {
+            Error::Error(format!(
+                "Cannot create the session file '{}': {}",
+                file.display(),
+                e
+            ))
+        })?;
+        Ok(Self {
+            writer: Mutex::new((BufWriter::new(writer), Instant::now())),
+            start: Instant::now(),
+        })
+    }
+
+    pub fn record(&self, event: SessionEvent) {
+        let entry = SessionEntry {
+            elapsed: self.start.elapsed().as_millis() as u64,
+            event,
+        };
+        let line = match serde_json::to_string(&entry) {
+            Ok(line) => line,
+            Err(e) => {
+                warn!("Cannot write the event to the session file: {e}");
+                return;
+            }
+        };
+        let mut writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        let (writer, flushed_at) = &mut *writer;
+        let mut written = writeln!(writer, "{line}");
+        if written.is_ok() && flushed_at.elapsed() >= FLUSH_INTERVAL {
+            written = writer.flush();
+            *flushed_at = Instant::now();
+        }
+        if let Err(e) = written {
+            warn!("Cannot write the event to the session file: {e}");
+        }
+    }
+
+    /// Writes the session file to the disk, before quitting.
+    pub fn close(&self) {
+        let mut writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        let synced = writer
+            .0
+            .flush()
+            .and_then(|()| writer.0.get_ref().sync_all());
+        if let Err(e) = synced {
+            warn!("Cannot write the session file: {e}");
+        }
+    }
+}
+
+/// Reads a session file and calls `f` for each event, respecting the delays between events.
+/// `speed` is a multiplier: `2.0` replays the session twice as fast.
+pub(crate) async fn replay(
+    file: &Path,
+    speed: f64,
+    mut f: impl FnMut(SessionEvent),
+) -> Result<(), Error> {
+    let entries = read_session(file)?;
+    let mut previous = 0;
+    for entry in entries {
+        let delay = entry.elapsed.saturating_sub(previous);
+        previous = entry.elapsed;
+        if delay > 0 {
+            tokio::time::sleep(Duration::from_millis(delay).div_f64(speed.max(0.01))).await;
+        }
+        f(entry.event);
+    }
+    Ok(())
+}
+
+/// `DataType` is untagged, a string is deserialized as a JSON string.
+fn restore_strings(mut record: KafkaRecord) -> KafkaRecord {
+    for data in [&mut record.key, &mut record.value] {
+        if let DataType::Json(Value::String(s)) = data {
+            *data = DataType::String(std::mem::take(s));
+        }
+    }
+    record
+}
+
+fn read_session(file: &Path) -> Result<Vec<SessionEntry>, Error> {
+    fs::read_to_string(file)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(i, line)| {
+            let entry = serde_json::from_str::<SessionEntry>(line).map_err(|e| {
+                Error::Error(format!(
+                    "Invalid event at line {} of the session file '{}': {}",
+                    i + 1,
+                    file.display(),
+                    e
+                ))
+            })?;
+            Ok(match entry.event {
+                SessionEvent::Record { record } => SessionEntry {
+                    event: SessionEvent::Record {
+                        record: restore_strings(record),
+                    },
+                    ..entry
+                },
+                _ => entry,
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn test_record_and_read_session() {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("session.jsonl");
+    let events = vec![
+        SessionEvent::Topics {
+            topics: vec!["public-french-addresses".to_string()],
+        },
+        SessionEvent::Key {
+            key: KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE),
+        },
+        SessionEvent::Record {
+            record: KafkaRecord::default(),
+        },
+    ];
+
+    let recorder = SessionRecorder::new(&file).unwrap();
+    for event in &events {
+        recorder.record(event.clone());
+    }
+    drop(recorder);
+
+    let entries = read_session(&file).unwrap();
+    assert_eq!(
+        entries.into_iter().map(|e| e.event).collect::<Vec<_>>(),
+        events
+    );
+}
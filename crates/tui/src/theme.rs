@@ -1,8 +1,42 @@
 //! Theme for the TUI
 
+use std::env;
+
+use app::configuration::ColorSupport;
 use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
 
+use crate::color::{degrade, readable};
+
+/// Special theme name resolved to `light` or `dark` according to the background color of the terminal.
+pub const AUTO_THEME: &str = "auto";
+
+/// Background color of the terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Background {
+    Light,
+    Dark,
+}
+
+impl Background {
+    /// Detects the background of the terminal with the `COLORFGBG` environment variable,
+    /// set by terminals such as Konsole, rxvt or iTerm2.
+    pub fn detect() -> Option<Self> {
+        env::var("COLORFGBG")
+            .ok()
+            .and_then(|value| Self::from_colorfgbg(&value))
+    }
+
+    /// `COLORFGBG` is either `<fg>;<bg>` or `<fg>;default;<bg>` where colors are ANSI color indexes.
+    fn from_colorfgbg(value: &str) -> Option<Self> {
+        let bg = value.rsplit(';').next()?.trim().parse::<u8>().ok()?;
+        match bg {
+            7 | 9..=15 => Some(Self::Light),
+            _ => Some(Self::Dark),
+        }
+    }
+}
+
 /// A `Theme` contains all the colors
 /// to make the TUI pretty.
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -35,6 +69,93 @@ pub struct Theme {
 }
 
 impl Theme {
+    /// Returns the name of the theme to load.
+    /// [`AUTO_THEME`] is resolved to `light` or `dark` according to the background of the terminal, `dark` if it cannot be detected.
+    pub fn resolve_name(name: &str) -> &str {
+        match name {
+            AUTO_THEME => match Background::detect() {
+                Some(Background::Light) => "light",
+                _ => "dark",
+            },
+            name => name,
+        }
+    }
+
+    /// Degrades the colors of the theme to the colors supported by the terminal.
+    /// Foreground and background colors that become identical are replaced by a readable color.
+    /// Without colors, the theme becomes [`Self::monochrome`].
+    pub fn with_color_support(self, support: ColorSupport) -> Self {
+        if support == ColorSupport::Monochrome {
+            return self.monochrome();
+        }
+        let d = |color| degrade(color, support);
+        let pair = |fg, bg| (readable(d(fg), d(bg), fg, bg), d(bg));
+        let (fg, bg) = pair(self.fg, self.bg);
+        let (fg_focused_selected, bg_focused_selected) =
+            pair(self.fg_focused_selected, self.bg_focused_selected);
+        let (fg_unfocused_selected, bg_unfocused_selected) =
+            pair(self.fg_unfocused_selected, self.bg_unfocused_selected);
+        let (fg_disabled, bg_disabled) = pair(self.fg_disabled, self.bg_disabled);
+        let (fg_active, bg_active) = pair(self.fg_active, self.bg_active);
+        Self {
+            fg,
+            bg,
+            black: d(self.black),
+            red: d(self.red),
+            green: d(self.green),
+            yellow: d(self.yellow),
+            blue: d(self.blue),
+            magenta: d(self.magenta),
+            cyan: d(self.cyan),
+            white: d(self.white),
+            orange: d(self.orange),
+            focused_border: d(self.focused_border),
+            bg_focused_selected,
+            fg_focused_selected,
+            bg_unfocused_selected,
+            fg_unfocused_selected,
+            bg_disabled,
+            fg_disabled,
+            bg_active,
+            fg_active,
+            dialog_border: d(self.dialog_border),
+            autocomplete: d(self.autocomplete),
+            shortcuts: self.shortcuts.map(d),
+            ..self
+        }
+    }
+
+    /// The colors of the terminal, black and white for the selection, without syntax highlighting.
+    fn monochrome(self) -> Self {
+        Self {
+            fg: Color::Reset,
+            bg: Color::Reset,
+            black: Color::Reset,
+            red: Color::Reset,
+            green: Color::Reset,
+            yellow: Color::Reset,
+            blue: Color::Reset,
+            magenta: Color::Reset,
+            cyan: Color::Reset,
+            white: Color::Reset,
+            orange: Color::Reset,
+            focused_border: Color::Reset,
+            bg_focused_selected: Color::White,
+            fg_focused_selected: Color::Black,
+            bg_unfocused_selected: Color::DarkGray,
+            fg_unfocused_selected: Color::White,
+            bg_disabled: Color::Reset,
+            fg_disabled: Color::Reset,
+            bg_active: Color::White,
+            fg_active: Color::Black,
+            dialog_border: Color::Reset,
+            autocomplete: Color::DarkGray,
+            shortcuts: None,
+            highlighter_theme: None,
+            ..self
+        }
+    }
+
     pub fn light() -> Self {
         Self {
             name: "light".to_string(),
@@ -65,3 +186,40 @@ impl Theme {
         }
     }
 }
+
+#[test]
+fn test_background_from_colorfgbg() {
+    assert_eq!(Background::from_colorfgbg("15;0"), Some(Background::Dark));
+    assert_eq!(Background::from_colorfgbg("0;15"), Some(Background::Light));
+    assert_eq!(
+        Background::from_colorfgbg("0;default;7"),
+        Some(Background::Light)
+    );
+    assert_eq!(Background::from_colorfgbg("15;8"), Some(Background::Dark));
+    assert_eq!(Background::from_colorfgbg("default"), None);
+}
+
+#[test]
+fn test_theme_with_color_support() {
+    let theme = Theme {
+        fg: Color::Rgb(10, 10, 10),
+        bg: Color::Rgb(20, 20, 20),
+        ..Theme::light()
+    };
+    assert_eq!(
+        theme.clone().with_color_support(ColorSupport::TrueColor),
+        theme
+    );
+
+    let monochrome = theme.clone().with_color_support(ColorSupport::Monochrome);
+    assert_eq!(monochrome.name, theme.name);
+    assert_eq!(
+        (monochrome.fg, monochrome.red),
+        (Color::Reset, Color::Reset)
+    );
+    assert_eq!(monochrome.highlighter_theme, None);
+
+    let theme = theme.with_color_support(ColorSupport::Ansi16);
+    assert_eq!(theme.bg, Color::Black);
+    assert_eq!(theme.fg, Color::White);
+}
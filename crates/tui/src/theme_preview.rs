@@ -0,0 +1,137 @@
+//! Renders a sample screen with a given theme directly in the terminal,
+//! so themes can be compared without starting the TUI.
+
+use std::io::stdout;
+
+use ratatui::{
+    Frame, Terminal, TerminalOptions, Viewport,
+    backend::CrosstermBackend,
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Padding, Paragraph, Row, Table},
+};
+
+use crate::{Theme, error::TuiError};
+
+/// Number of lines of the preview
+pub const PREVIEW_HEIGHT: u16 = 11;
+
+/// Prints a preview of the theme below the cursor.
+pub fn preview_theme(theme: &Theme) -> Result<(), TuiError> {
+    let mut terminal = Terminal::with_options(
+        CrosstermBackend::new(stdout()),
+        TerminalOptions {
+            viewport: Viewport::Inline(PREVIEW_HEIGHT),
+        },
+    )?;
+    terminal.draw(|f| draw_preview(f, f.area(), theme))?;
+    terminal.show_cursor()?;
+    println!();
+    Ok(())
+}
+
+fn draw_preview(f: &mut Frame<'_>, rect: Rect, theme: &Theme) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme.focused_border))
+        .title(format!(" Theme '{}' ", theme.name))
+        .padding(Padding::horizontal(1))
+        .style(Style::default().fg(theme.fg).bg(theme.bg));
+
+    let rows = [
+        (
+            "42",
+            "public-french-addresses",
+            "Rue de la Paix",
+            Style::default()
+                .fg(theme.fg_focused_selected)
+                .bg(theme.bg_focused_selected),
+        ),
+        (
+            "43",
+            "public-french-addresses",
+            "Avenue des Champs-Élysées",
+            Style::default(),
+        ),
+        (
+            "44",
+            "public-french-addresses",
+            "Boulevard Saint-Michel",
+            Style::default()
+                .fg(theme.fg_unfocused_selected)
+                .bg(theme.bg_unfocused_selected),
+        ),
+    ];
+    let table = Table::new(
+        rows.into_iter()
+            .map(|(offset, topic, value, style)| Row::new([offset, topic, value]).style(style)),
+        [
+            Constraint::Length(6),
+            Constraint::Length(24),
+            Constraint::Fill(1),
+        ],
+    )
+    .header(Row::new(["Offset", "Topic", "Value"]).bold());
+
+    let palette = [
+        ("black", theme.black),
+        ("red", theme.red),
+        ("green", theme.green),
+        ("yellow", theme.yellow),
+        ("blue", theme.blue),
+        ("magenta", theme.magenta),
+        ("cyan", theme.cyan),
+        ("white", theme.white),
+        ("orange", theme.orange),
+    ]
+    .into_iter()
+    .flat_map(|(name, color)| [Span::from(name).fg(color), Span::raw(" ")])
+    .collect::<Vec<_>>();
+
+    let text = vec![
+        Line::from(palette),
+        Line::from(vec![
+            Span::from(" disabled ")
+                .fg(theme.fg_disabled)
+                .bg(theme.bg_disabled),
+            Span::raw(" "),
+            Span::from(" active ")
+                .fg(theme.fg_active)
+                .bg(theme.bg_active),
+            Span::raw(" "),
+            Span::from("from end - 10 ").fg(theme.fg),
+            Span::from("limit 10").fg(theme.autocomplete),
+            Span::raw(" "),
+            Span::from("dialog").fg(theme.dialog_border),
+        ]),
+        Line::from(""),
+        Line::from(
+            "[TAB]: Next panel   [CTRL + H]: Help   [ESC]: Quit"
+                .fg(theme.shortcuts.unwrap_or(theme.fg)),
+        ),
+    ];
+
+    let inner = block.inner(rect);
+    f.render_widget(block, rect);
+    let [records, _, legend] = Layout::vertical([
+        Constraint::Length(4),
+        Constraint::Length(1),
+        Constraint::Fill(1),
+    ])
+    .areas(inner);
+    f.render_widget(table, records);
+    f.render_widget(Paragraph::new(text), legend);
+}
+
+#[test]
+fn test_draw_preview() {
+    use ratatui::backend::TestBackend;
+
+    let mut terminal = Terminal::new(TestBackend::new(80, PREVIEW_HEIGHT)).unwrap();
+    terminal
+        .draw(|f| draw_preview(f, f.area(), &Theme::light()))
+        .unwrap();
+    insta::assert_snapshot!(terminal.backend());
+}
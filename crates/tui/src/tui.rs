@@ -1,8 +1,12 @@
 use std::{
     ops::{Deref, DerefMut},
     panic::{set_hook, take_hook},
+    sync::Arc,
+    time::Instant,
 };
 
+use app::configuration::RefreshRates;
+
 use crossterm::{
     cursor,
     event::{Event as CrosstermEvent, KeyEvent, KeyEventKind, MouseEvent},
@@ -12,7 +16,10 @@ use futures::{FutureExt, StreamExt};
 use lib::Error;
 use ratatui::backend::CrosstermBackend as Backend;
 use tokio::{
-    sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
+    sync::{
+        Notify,
+        mpsc::{self, UnboundedReceiver, UnboundedSender},
+    },
     task::JoinHandle,
 };
 use tokio_util::sync::CancellationToken;
@@ -20,7 +27,6 @@ use tokio_util::sync::CancellationToken;
 #[derive(Clone, Debug)]
 pub enum Event {
     Init,
-    #[allow(dead_code)]
     Quit,
     Error,
     Tick,
@@ -39,14 +45,14 @@ pub struct Tui {
     pub cancellation_token: CancellationToken,
     pub event_rx: UnboundedReceiver<Event>,
     pub event_tx: UnboundedSender<Event>,
-    pub frame_rate: f64,
-    pub tick_rate: f64,
+    pub rates: RefreshRates,
+    /// Notified when something happens besides the key strokes, typically a record read,
+    /// to draw at the full frame rate again when the TUI is idle
+    pub activity: Arc<Notify>,
 }
 
 impl Tui {
-    pub fn new() -> Result<Self, Error> {
-        let tick_rate = 4.0;
-        let frame_rate = 30.0;
+    pub fn new(rates: RefreshRates) -> Result<Self, Error> {
         let terminal = ratatui::Terminal::new(Backend::new(std::io::stderr()))?;
         let (event_tx, event_rx) = mpsc::unbounded_channel();
         let cancellation_token = CancellationToken::new();
@@ -57,14 +63,14 @@ impl Tui {
             cancellation_token,
             event_rx,
             event_tx,
-            frame_rate,
-            tick_rate,
+            rates,
+            activity: Arc::new(Notify::new()),
         })
     }
 
     pub fn start(&mut self) {
-        let tick_delay = std::time::Duration::from_secs_f64(1.0 / self.tick_rate);
-        let render_delay = std::time::Duration::from_secs_f64(1.0 / self.frame_rate);
+        let rates = self.rates;
+        let activity = self.activity.clone();
         self.cancel();
         self.cancellation_token = CancellationToken::new();
         let cancellation_token = self.cancellation_token.clone();
@@ -73,12 +79,19 @@ impl Tui {
             .name("render-loop")
             .spawn(async move {
                 let mut reader = crossterm::event::EventStream::new();
-                let mut tick_interval = tokio::time::interval(tick_delay);
-                let mut render_interval = tokio::time::interval(render_delay);
+                let mut tick_interval = tokio::time::interval(rates.tick_delay(false));
+                let mut render_interval = tokio::time::interval(rates.frame_delay(false));
+                let mut last_activity = Instant::now();
+                let mut idle = false;
                 event_tx.send(Event::Init).unwrap();
                 loop {
                     let span = tracing::span!(tracing::Level::TRACE, "render-loop");
                     let _ = span.enter();
+                    if idle != (last_activity.elapsed() >= rates.idle_after()) {
+                        idle = !idle;
+                        tick_interval = tokio::time::interval(rates.tick_delay(idle));
+                        render_interval = tokio::time::interval(rates.frame_delay(idle));
+                    }
                     let tick_delay = tick_interval.tick();
                     let render_delay = render_interval.tick();
                     let crossterm_event = reader.next().fuse();
@@ -87,7 +100,13 @@ impl Tui {
                         break;
                       }
 
+                      // Only listened when idle, records may be read hundreds of times per second
+                      () = activity.notified(), if idle => {
+                        last_activity = Instant::now();
+                      }
+
                       maybe_event = crossterm_event => {
+                        last_activity = Instant::now();
 
                         match maybe_event {
                           Some(Ok(evt)) => {